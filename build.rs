@@ -0,0 +1,4 @@
+// 마이그레이션 파일이 바뀌면 sqlx::migrate!가 다시 포함하도록 재빌드
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
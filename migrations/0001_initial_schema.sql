@@ -0,0 +1,342 @@
+-- 초기 스키마 (예전 init_database가 부팅마다 만들던 구조)
+-- 마이그레이션 도입 전부터 운영 중인 DB에도 그대로 적용되도록 모두 IF NOT EXISTS로 작성
+
+CREATE EXTENSION IF NOT EXISTS postgis;
+CREATE SCHEMA IF NOT EXISTS bigpicture;
+
+-- 원본 이미지
+CREATE TABLE IF NOT EXISTS bigpicture.original_images (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    filename VARCHAR(255) NOT NULL UNIQUE,
+    original_filename VARCHAR(255) NOT NULL,
+    file_path VARCHAR(500) NOT NULL,
+    file_size_mb DOUBLE PRECISION NOT NULL,
+    width INTEGER,
+    height INTEGER,
+    format VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- WebP 변환 이미지
+CREATE TABLE IF NOT EXISTS bigpicture.webp_images (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    original_id UUID NOT NULL REFERENCES bigpicture.original_images(id) ON DELETE CASCADE,
+    filename VARCHAR(255) NOT NULL UNIQUE,
+    file_path VARCHAR(500) NOT NULL,
+    file_size_mb DOUBLE PRECISION NOT NULL,
+    width INTEGER,
+    height INTEGER,
+    image_type VARCHAR(50) NOT NULL, -- thumbnail, map
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_original_images_filename ON bigpicture.original_images(filename);
+CREATE INDEX IF NOT EXISTS idx_original_images_created_at ON bigpicture.original_images(created_at);
+CREATE INDEX IF NOT EXISTS idx_webp_images_filename ON bigpicture.webp_images(filename);
+CREATE INDEX IF NOT EXISTS idx_webp_images_original_id ON bigpicture.webp_images(original_id);
+CREATE INDEX IF NOT EXISTS idx_webp_images_image_type ON bigpicture.webp_images(image_type);
+CREATE INDEX IF NOT EXISTS idx_webp_images_created_at ON bigpicture.webp_images(created_at);
+
+-- 회원 (회원 스키마의 유일한 정의)
+CREATE TABLE IF NOT EXISTS bigpicture.members (
+    id BIGSERIAL PRIMARY KEY,
+    email VARCHAR(255) NOT NULL UNIQUE,
+    nickname VARCHAR(100) NOT NULL,
+    profile_image_url TEXT,
+    region VARCHAR(100),
+    gender VARCHAR(20),
+    age INTEGER,
+    personality_type VARCHAR(50),
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    email_verified BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_login_at TIMESTAMP WITH TIME ZONE
+);
+ALTER TABLE bigpicture.members ADD COLUMN IF NOT EXISTS preferred_languages TEXT[];
+
+-- 마커
+CREATE TABLE IF NOT EXISTS bigpicture.markers (
+    id BIGSERIAL PRIMARY KEY,
+    member_id BIGINT REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    location GEOGRAPHY(POINT, 4326),
+    emotion_tag TEXT,
+    emotion TEXT,
+    description TEXT,
+    sharing_option VARCHAR(20) DEFAULT 'public' CHECK (sharing_option IN ('public', 'friends', 'private')),
+    likes INTEGER DEFAULT 0,
+    dislikes INTEGER DEFAULT 0,
+    views INTEGER DEFAULT 0,
+    author TEXT,
+    thumbnail_img TEXT, -- 기존 썸네일 필드 유지
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS sharing_option VARCHAR(20) DEFAULT 'public' CHECK (sharing_option IN ('public', 'friends', 'private'));
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS emotion TEXT;
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS emotion_tag_input TEXT;
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS language VARCHAR(10);
+
+-- 마커 이미지 (마커와 이미지 연결)
+CREATE TABLE IF NOT EXISTS bigpicture.marker_images (
+    id SERIAL PRIMARY KEY,
+    marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    image_type VARCHAR(50) NOT NULL, -- thumbnail, detail, gallery
+    image_url VARCHAR(500) NOT NULL,
+    image_order INTEGER DEFAULT 0, -- 이미지 순서
+    is_primary BOOLEAN DEFAULT false, -- 대표 이미지 여부
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+-- 파생 이미지의 원본 연결 (자동 생성 썸네일 -> 상세 이미지)
+ALTER TABLE bigpicture.marker_images ADD COLUMN IF NOT EXISTS source_image_id INTEGER REFERENCES bigpicture.marker_images(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS markers_location_gist ON bigpicture.markers USING GIST (location);
+CREATE INDEX IF NOT EXISTS idx_marker_images_marker_id ON bigpicture.marker_images(marker_id);
+CREATE INDEX IF NOT EXISTS idx_marker_images_image_type ON bigpicture.marker_images(image_type);
+CREATE INDEX IF NOT EXISTS idx_marker_images_is_primary ON bigpicture.marker_images(is_primary);
+CREATE INDEX IF NOT EXISTS idx_marker_images_order ON bigpicture.marker_images(marker_id, image_order);
+CREATE INDEX IF NOT EXISTS idx_marker_images_source_image_id ON bigpicture.marker_images(source_image_id);
+
+-- 업로드 시 계산한 해상도/선명도 (대표 이미지 자동 선정용)
+CREATE TABLE IF NOT EXISTS bigpicture.image_quality (
+    image_url VARCHAR(500) PRIMARY KEY,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    sharpness_score DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+ALTER TABLE bigpicture.image_quality ADD COLUMN IF NOT EXISTS original_size_bytes BIGINT;
+
+-- 업로드 원본 URL -> 서버에서 생성한 썸네일 URL
+CREATE TABLE IF NOT EXISTS bigpicture.image_variants (
+    image_url VARCHAR(500) PRIMARY KEY,
+    thumbnail_url VARCHAR(500) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- 법적 사유 게시 중단(takedown) 및 법적 보존(legal hold)
+-- legal_hold가 걸린 콘텐츠는 원본 파일을 삭제/정리 대상에서 제외
+ALTER TABLE bigpicture.members ADD COLUMN IF NOT EXISTS is_admin BOOLEAN DEFAULT false;
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS taken_down_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS legal_hold BOOLEAN DEFAULT false;
+ALTER TABLE bigpicture.marker_images ADD COLUMN IF NOT EXISTS taken_down_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE bigpicture.marker_images ADD COLUMN IF NOT EXISTS legal_hold BOOLEAN DEFAULT false;
+CREATE TABLE IF NOT EXISTS bigpicture.content_takedown_audit (
+    id BIGSERIAL PRIMARY KEY,
+    admin_member_id BIGINT NOT NULL,
+    target_type VARCHAR(20) NOT NULL, -- marker, marker_image
+    target_id BIGINT NOT NULL,
+    action VARCHAR(20) NOT NULL, -- takedown, restore
+    reason TEXT NOT NULL,
+    legal_reference VARCHAR(255),
+    legal_hold BOOLEAN NOT NULL,
+    client_ip VARCHAR(64),
+    user_agent TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_content_takedown_audit_target ON bigpicture.content_takedown_audit(target_type, target_id);
+
+-- 외부 공개 API 키 (원문은 저장하지 않고 SHA-256 해시만 보관)
+CREATE TABLE IF NOT EXISTS bigpicture.api_keys (
+    id BIGSERIAL PRIMARY KEY,
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    key_prefix VARCHAR(16) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    scope VARCHAR(50) NOT NULL, -- public_read
+    daily_quota INTEGER NOT NULL,
+    created_by BIGINT,
+    is_active BOOLEAN DEFAULT true,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    last_used_at TIMESTAMP WITH TIME ZONE
+);
+CREATE TABLE IF NOT EXISTS bigpicture.api_key_usage (
+    api_key_id BIGINT NOT NULL REFERENCES bigpicture.api_keys(id) ON DELETE CASCADE,
+    usage_date DATE NOT NULL,
+    endpoint VARCHAR(200) NOT NULL,
+    request_count INTEGER NOT NULL DEFAULT 0,
+    rejected_count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (api_key_id, usage_date, endpoint)
+);
+
+-- 행정구역 경계 (시도/시군구, 행정표준코드 기준)
+CREATE TABLE IF NOT EXISTS bigpicture.districts (
+    code VARCHAR(10) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    level VARCHAR(20) NOT NULL, -- sido, sigungu
+    parent_code VARCHAR(10),
+    geom GEOMETRY(MultiPolygon, 4326) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_districts_geom ON bigpicture.districts USING GIST (geom);
+CREATE INDEX IF NOT EXISTS idx_districts_parent_code ON bigpicture.districts(parent_code);
+
+-- 앱 내 알림함 (dedupe_key로 같은 알림 중복 생성 방지)
+CREATE TABLE IF NOT EXISTS bigpicture.notifications (
+    id BIGSERIAL PRIMARY KEY,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    notification_type VARCHAR(50) NOT NULL, -- memories
+    title VARCHAR(200) NOT NULL,
+    body TEXT,
+    marker_ids BIGINT[],
+    dedupe_key VARCHAR(100) NOT NULL,
+    read_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE (member_id, notification_type, dedupe_key)
+);
+CREATE INDEX IF NOT EXISTS idx_notifications_member_created ON bigpicture.notifications(member_id, created_at DESC);
+ALTER TABLE bigpicture.members ADD COLUMN IF NOT EXISTS memories_notification_enabled BOOLEAN DEFAULT false;
+
+-- 회원 탈퇴 (soft delete) 및 이미지 정리 작업
+ALTER TABLE bigpicture.members ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP WITH TIME ZONE;
+CREATE TABLE IF NOT EXISTS bigpicture.image_cleanup_jobs (
+    id BIGSERIAL PRIMARY KEY,
+    image_url VARCHAR(500) NOT NULL,
+    reason VARCHAR(50) NOT NULL, -- account_deletion
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    completed_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX IF NOT EXISTS idx_image_cleanup_jobs_pending ON bigpicture.image_cleanup_jobs(created_at) WHERE completed_at IS NULL;
+
+-- 관리자 마커 내보내기 작업 (백그라운드에서 파일 생성 후 S3 업로드)
+CREATE TABLE IF NOT EXISTS bigpicture.marker_exports (
+    id BIGSERIAL PRIMARY KEY,
+    requested_by BIGINT NOT NULL,
+    format VARCHAR(10) NOT NULL, -- csv, parquet
+    since TIMESTAMP WITH TIME ZONE,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, running, completed, failed
+    row_count BIGINT,
+    file_key VARCHAR(500),
+    file_size BIGINT,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX IF NOT EXISTS idx_marker_exports_pending ON bigpicture.marker_exports(created_at) WHERE status IN ('pending', 'running');
+
+-- 사업장 마커 소유 인증 신청 (관리자 검토 후 승인되면 마커에 인증 배지)
+CREATE TABLE IF NOT EXISTS bigpicture.marker_claims (
+    id BIGSERIAL PRIMARY KEY,
+    marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    business_name VARCHAR(200) NOT NULL,
+    business_registration_number VARCHAR(50) NOT NULL,
+    contact_phone VARCHAR(50),
+    document_key VARCHAR(500) NOT NULL, -- 비공개 S3 키 (관리자 API로만 열람)
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, approved, rejected
+    review_note TEXT,
+    reviewed_by BIGINT,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+-- 마커당 승인된 소유자는 한 명, 같은 회원의 대기 중 신청도 하나만
+CREATE UNIQUE INDEX IF NOT EXISTS idx_marker_claims_approved ON bigpicture.marker_claims(marker_id) WHERE status = 'approved';
+CREATE UNIQUE INDEX IF NOT EXISTS idx_marker_claims_pending ON bigpicture.marker_claims(marker_id, member_id) WHERE status = 'pending';
+
+-- 로그인 시도 감사 기록 (연속 실패 시 계정 임시 잠금 판단에도 사용)
+CREATE TABLE IF NOT EXISTS bigpicture.login_attempts (
+    id BIGSERIAL PRIMARY KEY,
+    email VARCHAR(255) NOT NULL,
+    member_id BIGINT,
+    success BOOLEAN NOT NULL,
+    failure_reason VARCHAR(50), -- unknown_email, invalid_password, no_password
+    client_ip VARCHAR(64),
+    user_agent TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_login_attempts_email_created ON bigpicture.login_attempts(LOWER(email), created_at DESC);
+
+-- 2단계 인증 비밀키 (enabled_at이 NULL이면 등록 확인 전)
+CREATE TABLE IF NOT EXISTS bigpicture.member_totp (
+    member_id BIGINT PRIMARY KEY REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    secret VARCHAR(64) NOT NULL,
+    enabled_at TIMESTAMP WITH TIME ZONE,
+    last_used_step BIGINT, -- 같은 코드 재사용 방지
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- 로그인 제공자
+CREATE TABLE IF NOT EXISTS bigpicture.auth_providers (
+    id BIGSERIAL PRIMARY KEY,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    provider_type VARCHAR(50) NOT NULL, -- google, kakao, naver, meta, email
+    provider_id VARCHAR(255) NOT NULL,
+    provider_email VARCHAR(255),
+    password_hash VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    UNIQUE(provider_type, provider_id),
+    UNIQUE(member_id, provider_type)
+);
+
+-- 마커와 회원 연결
+CREATE TABLE IF NOT EXISTS bigpicture.member_markers (
+    id BIGSERIAL PRIMARY KEY,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    interaction_type VARCHAR(50) NOT NULL, -- created, liked, disliked, viewed, bookmarked
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+
+    UNIQUE(member_id, marker_id, interaction_type)
+);
+
+-- 취미/관심사
+CREATE TABLE IF NOT EXISTS bigpicture.hobbies (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL UNIQUE,
+    category VARCHAR(50),
+    description TEXT,
+    is_active BOOLEAN DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+CREATE TABLE IF NOT EXISTS bigpicture.interests (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL UNIQUE,
+    category VARCHAR(50),
+    description TEXT,
+    is_active BOOLEAN DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+CREATE TABLE IF NOT EXISTS bigpicture.member_hobbies (
+    id SERIAL PRIMARY KEY,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    hobby_id INTEGER NOT NULL REFERENCES bigpicture.hobbies(id) ON DELETE CASCADE,
+    proficiency_level INTEGER CHECK (proficiency_level >= 1 AND proficiency_level <= 5),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(member_id, hobby_id)
+);
+CREATE TABLE IF NOT EXISTS bigpicture.member_interests (
+    id SERIAL PRIMARY KEY,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    interest_id INTEGER NOT NULL REFERENCES bigpicture.interests(id) ON DELETE CASCADE,
+    interest_level INTEGER CHECK (interest_level >= 1 AND interest_level <= 5),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(member_id, interest_id)
+);
+
+-- 추가 인덱스
+CREATE INDEX IF NOT EXISTS idx_members_email ON bigpicture.members(email);
+CREATE INDEX IF NOT EXISTS idx_members_nickname ON bigpicture.members(nickname);
+CREATE INDEX IF NOT EXISTS idx_members_created_at ON bigpicture.members(created_at);
+CREATE INDEX IF NOT EXISTS idx_auth_providers_member_id ON bigpicture.auth_providers(member_id);
+CREATE INDEX IF NOT EXISTS idx_auth_providers_provider_type_id ON bigpicture.auth_providers(provider_type, provider_id);
+CREATE INDEX IF NOT EXISTS idx_member_markers_member_id ON bigpicture.member_markers(member_id);
+CREATE INDEX IF NOT EXISTS idx_member_markers_marker_id ON bigpicture.member_markers(marker_id);
+CREATE INDEX IF NOT EXISTS idx_member_markers_interaction_type ON bigpicture.member_markers(interaction_type);
+CREATE INDEX IF NOT EXISTS idx_member_markers_member_marker ON bigpicture.member_markers(member_id, marker_id);
+CREATE INDEX IF NOT EXISTS idx_member_markers_created_at ON bigpicture.member_markers(created_at);
+CREATE INDEX IF NOT EXISTS idx_markers_member_id ON bigpicture.markers(member_id);
+CREATE INDEX IF NOT EXISTS idx_markers_language ON bigpicture.markers(language);
+-- 시간대/계절 필터용 함수 인덱스 (database.rs의 CREATED_HOUR_EXPR/CREATED_MONTH_EXPR와 같은 식이어야 사용됨)
+CREATE INDEX IF NOT EXISTS idx_markers_created_hour ON bigpicture.markers((EXTRACT(HOUR FROM (created_at AT TIME ZONE 'Asia/Seoul'))));
+CREATE INDEX IF NOT EXISTS idx_markers_created_month ON bigpicture.markers((EXTRACT(MONTH FROM (created_at AT TIME ZONE 'Asia/Seoul'))));
@@ -0,0 +1,84 @@
+-- 예전 배포에 남은 스키마 차이 보정 (새 DB에서는 바꿀 것이 없어 아무것도 하지 않음)
+DO $$
+DECLARE
+    col RECORD;
+    duplicates BIGINT;
+BEGIN
+    -- 마커 ID를 BIGINT로 통일 (경로/member_markers는 이미 i64, 예전 DB는 SERIAL)
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = 'bigpicture' AND table_name = 'markers' AND column_name = 'id' AND data_type = 'integer'
+    ) THEN
+        ALTER TABLE bigpicture.markers ALTER COLUMN id TYPE BIGINT;
+        ALTER SEQUENCE IF EXISTS bigpicture.markers_id_seq AS BIGINT;
+        ALTER TABLE bigpicture.marker_images ALTER COLUMN marker_id TYPE BIGINT;
+        ALTER TABLE bigpicture.notifications ALTER COLUMN marker_ids TYPE BIGINT[];
+        RAISE NOTICE '마커 ID BIGINT 전환 완료';
+    END IF;
+
+    -- 예전 배포는 members/auth_providers를 SERIAL + CHECK 제약으로 만든 두 번째 정의가 남아 있음
+    -- Member, AuthProvider, MemberHobby, MemberInterest 모델과 다른 부분만 보정
+    FOR col IN
+        SELECT table_name::text AS table_name, column_name::text AS column_name FROM information_schema.columns
+        WHERE table_schema = 'bigpicture' AND data_type = 'integer'
+          AND (table_name::text, column_name::text) IN (
+              ('members', 'id'), ('auth_providers', 'id'), ('auth_providers', 'member_id'),
+              ('member_hobbies', 'member_id'), ('member_interests', 'member_id')
+          )
+    LOOP
+        EXECUTE format('ALTER TABLE bigpicture.%I ALTER COLUMN %I TYPE BIGINT', col.table_name, col.column_name);
+        IF col.column_name = 'id' THEN
+            EXECUTE format('ALTER SEQUENCE IF EXISTS bigpicture.%I AS BIGINT', col.table_name || '_id_seq');
+        END IF;
+        RAISE NOTICE '%.% → BIGINT', col.table_name, col.column_name;
+    END LOOP;
+
+    -- 기본값으로 NULL을 채운 뒤 NOT NULL 지정 (모델이 Option이 아님)
+    FOR col IN
+        SELECT table_name::text AS table_name, column_name::text AS column_name FROM information_schema.columns
+        WHERE table_schema = 'bigpicture' AND is_nullable = 'YES'
+          AND (table_name::text, column_name::text) IN (
+              ('members', 'is_active'), ('members', 'email_verified'), ('members', 'created_at'), ('members', 'updated_at'),
+              ('auth_providers', 'created_at'), ('auth_providers', 'updated_at')
+          )
+    LOOP
+        EXECUTE format('UPDATE bigpicture.%I SET %I = %s WHERE %I IS NULL',
+            col.table_name, col.column_name,
+            CASE col.column_name WHEN 'is_active' THEN 'true' WHEN 'email_verified' THEN 'false' ELSE 'NOW()' END,
+            col.column_name);
+        EXECUTE format('ALTER TABLE bigpicture.%I ALTER COLUMN %I SET NOT NULL', col.table_name, col.column_name);
+        RAISE NOTICE '%.% → NOT NULL', col.table_name, col.column_name;
+    END LOOP;
+
+    -- 값 검증은 핸들러에서 하므로 예전 CHECK 제약은 제거하고 컬럼 길이를 맞춤
+    IF EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE connamespace = 'bigpicture'::regnamespace
+          AND conname IN ('members_gender_check', 'members_age_check', 'auth_providers_provider_type_check')
+    ) THEN
+        ALTER TABLE bigpicture.members DROP CONSTRAINT IF EXISTS members_gender_check;
+        ALTER TABLE bigpicture.members DROP CONSTRAINT IF EXISTS members_age_check;
+        ALTER TABLE bigpicture.auth_providers DROP CONSTRAINT IF EXISTS auth_providers_provider_type_check;
+        ALTER TABLE bigpicture.members ALTER COLUMN gender TYPE VARCHAR(20);
+        ALTER TABLE bigpicture.members ALTER COLUMN profile_image_url TYPE TEXT;
+        ALTER TABLE bigpicture.auth_providers ALTER COLUMN provider_type TYPE VARCHAR(50);
+        RAISE NOTICE '예전 CHECK 제약 제거';
+    END IF;
+
+    -- 회원당 제공자 하나 제약은 중복 데이터가 없을 때만 추가 (있으면 수동 정리 필요)
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE connamespace = 'bigpicture'::regnamespace AND conname = 'auth_providers_member_id_provider_type_key'
+    ) THEN
+        SELECT COUNT(*) INTO duplicates FROM (
+            SELECT 1 FROM bigpicture.auth_providers GROUP BY member_id, provider_type HAVING COUNT(*) > 1
+        ) d;
+        IF duplicates = 0 THEN
+            ALTER TABLE bigpicture.auth_providers ADD CONSTRAINT auth_providers_member_id_provider_type_key UNIQUE (member_id, provider_type);
+            RAISE NOTICE 'auth_providers (member_id, provider_type) UNIQUE 추가';
+        ELSE
+            RAISE WARNING 'auth_providers에 같은 회원/제공자 중복 %건이 있어 UNIQUE 제약을 추가하지 않았습니다', duplicates;
+        END IF;
+    END IF;
+END
+$$;
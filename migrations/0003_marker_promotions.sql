@@ -0,0 +1,27 @@
+-- 관리자가 등록하는 프로모션(광고) 마커: 피드/클러스터 응답에 isPromoted로 표시해 끼워 넣음
+CREATE TABLE IF NOT EXISTS bigpicture.marker_promotions (
+    id BIGSERIAL PRIMARY KEY,
+    marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    sponsor_name VARCHAR(100) NOT NULL, -- 광고주 표시명
+    district_codes TEXT[], -- 노출 대상 행정구역 코드 (NULL이면 전국)
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    impression_cap BIGINT, -- 총 노출 한도 (NULL이면 무제한)
+    impressions BIGINT NOT NULL DEFAULT 0,
+    clicks BIGINT NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_by BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (ends_at > starts_at)
+);
+CREATE INDEX IF NOT EXISTS idx_marker_promotions_active ON bigpicture.marker_promotions(starts_at, ends_at) WHERE is_active;
+
+-- 일자별 노출/클릭 집계 (리포트용)
+CREATE TABLE IF NOT EXISTS bigpicture.marker_promotion_stats (
+    promotion_id BIGINT NOT NULL REFERENCES bigpicture.marker_promotions(id) ON DELETE CASCADE,
+    stat_date DATE NOT NULL,
+    impressions BIGINT NOT NULL DEFAULT 0,
+    clicks BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (promotion_id, stat_date)
+);
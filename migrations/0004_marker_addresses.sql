@@ -0,0 +1,9 @@
+-- 마커 주소 (역지오코딩 결과: 시도/시군구/읍면동). 기존 마커는 백그라운드 작업이 천천히 채움
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS address_region VARCHAR(50); -- 시도
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS address_locality VARCHAR(50); -- 시군구
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS address_neighborhood VARCHAR(50); -- 읍면동
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS address_text VARCHAR(200); -- 전체 주소
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS address_geocoded_at TIMESTAMP WITH TIME ZONE; -- 조회 완료 시각 (주소가 없는 곳도 기록)
+
+-- 아직 주소를 조회하지 않은 마커 (백필 작업용)
+CREATE INDEX IF NOT EXISTS idx_markers_address_pending ON bigpicture.markers(id) WHERE address_geocoded_at IS NULL;
@@ -0,0 +1,26 @@
+-- 회원이 연결한 외부 SNS 계정 (크로스포스팅용, 토큰은 앱에서 AES-256-GCM으로 암호화해 저장)
+CREATE TABLE IF NOT EXISTS bigpicture.member_connected_accounts (
+    id BIGSERIAL PRIMARY KEY,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    platform VARCHAR(20) NOT NULL, -- instagram, kakaostory
+    remote_user_id VARCHAR(100) NOT NULL,
+    remote_username VARCHAR(100),
+    access_token_encrypted TEXT NOT NULL,
+    refresh_token_encrypted TEXT,
+    token_expires_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (member_id, platform)
+);
+
+-- 마커를 외부 SNS에 올린 기록 (플랫폼별 한 번)
+CREATE TABLE IF NOT EXISTS bigpicture.marker_crossposts (
+    id BIGSERIAL PRIMARY KEY,
+    marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    member_id BIGINT NOT NULL,
+    platform VARCHAR(20) NOT NULL,
+    remote_post_id VARCHAR(100) NOT NULL,
+    remote_url TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (marker_id, platform)
+);
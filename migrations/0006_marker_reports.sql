@@ -0,0 +1,16 @@
+-- 회원의 마커 신고 (관리자 검토 대기열)
+CREATE TABLE IF NOT EXISTS bigpicture.marker_reports (
+    id BIGSERIAL PRIMARY KEY,
+    marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    reporter_id BIGINT NOT NULL,
+    reason VARCHAR(20) NOT NULL, -- spam, offensive, harassment, sexual, violence, misinformation, copyright, other
+    details TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, dismissed, hidden
+    resolution_note TEXT,
+    resolved_by BIGINT,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+-- 같은 회원이 같은 마커를 처리 전에 여러 번 신고하지 못하게 함
+CREATE UNIQUE INDEX IF NOT EXISTS idx_marker_reports_pending ON bigpicture.marker_reports(marker_id, reporter_id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_marker_reports_status_created ON bigpicture.marker_reports(status, created_at);
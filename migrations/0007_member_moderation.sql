@@ -0,0 +1,17 @@
+-- 관리자 회원 정지/재활성화와 강제 로그아웃
+-- tokens_revoked_at 이전에 발급된 토큰은 인증 미들웨어에서 거절됨
+ALTER TABLE bigpicture.members ADD COLUMN IF NOT EXISTS suspended_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE bigpicture.members ADD COLUMN IF NOT EXISTS suspension_reason TEXT;
+ALTER TABLE bigpicture.members ADD COLUMN IF NOT EXISTS tokens_revoked_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE IF NOT EXISTS bigpicture.member_moderation_audit (
+    id BIGSERIAL PRIMARY KEY,
+    admin_member_id BIGINT NOT NULL,
+    member_id BIGINT NOT NULL,
+    action VARCHAR(20) NOT NULL, -- suspend, reactivate, force_logout
+    reason TEXT,
+    client_ip VARCHAR(64),
+    user_agent TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_member_moderation_audit_member ON bigpicture.member_moderation_audit(member_id, created_at);
@@ -0,0 +1,11 @@
+-- 마커 해시태그 (설명의 #태그와 생성 요청의 tags 배열, 소문자로 정규화해 저장)
+CREATE TABLE IF NOT EXISTS bigpicture.marker_tags (
+    marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    tag VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (marker_id, tag)
+);
+-- 태그로 마커 찾기 (/api/markers, /api/markers/feed의 tag 필터)
+CREATE INDEX IF NOT EXISTS idx_marker_tags_tag ON bigpicture.marker_tags(tag, marker_id);
+-- 최근 사용된 태그 집계 (/api/tags/trending)
+CREATE INDEX IF NOT EXISTS idx_marker_tags_created ON bigpicture.marker_tags(created_at);
@@ -0,0 +1,9 @@
+-- 마커 전문 검색 (/api/markers/search)
+-- 한국어 사전이 없으므로 'simple' 설정(소문자 + 공백/기호 분리)으로 색인하고, 검색어는 접두어 일치로 찾음
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS search_vector TSVECTOR
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', COALESCE(description, '')), 'A') ||
+        setweight(to_tsvector('simple', COALESCE(author, '')), 'B') ||
+        setweight(to_tsvector('simple', replace(COALESCE(emotion_tag, ''), ',', ' ')), 'C')
+    ) STORED;
+CREATE INDEX IF NOT EXISTS idx_markers_search_vector ON bigpicture.markers USING GIN (search_vector);
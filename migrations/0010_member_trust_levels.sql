@@ -0,0 +1,7 @@
+-- 회원 신뢰 등급 (new, basic, trusted)
+-- 등급은 가입 기간과 활동 이력으로 계산하고, 관리자가 지정한 값이 있으면 그 값을 사용 (NULL이면 자동)
+ALTER TABLE bigpicture.members ADD COLUMN IF NOT EXISTS trust_level_override VARCHAR(20)
+    CHECK (trust_level_override IN ('new', 'basic', 'trusted'));
+
+-- 회원별 최근 마커 수 집계 (하루 마커 생성 한도)
+CREATE INDEX IF NOT EXISTS idx_markers_member_created ON bigpicture.markers(member_id, created_at);
@@ -0,0 +1,31 @@
+-- 백그라운드 작업 데드레터 큐 (재시도 한도를 넘겼거나 다시 시도해도 소용없는 실패)
+-- 관리자가 /api/admin/dlq에서 보고 원래 작업으로 다시 넣거나(retried) 버림(discarded)
+CREATE TABLE IF NOT EXISTS bigpicture.dead_letter_jobs (
+    id BIGSERIAL PRIMARY KEY,
+    job_type VARCHAR(50) NOT NULL, -- image_cleanup(S3/로컬 파일 삭제), marker_export
+    source_id BIGINT, -- 원래 작업 테이블의 ID
+    payload JSONB NOT NULL DEFAULT '{}'::jsonb, -- 작업 내용 (원래 작업이 없어져도 다시 만들 수 있도록)
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'dead' CHECK (status IN ('dead', 'retried', 'discarded')),
+    failed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    resolved_by BIGINT,
+    resolved_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX IF NOT EXISTS idx_dead_letter_jobs_status ON bigpicture.dead_letter_jobs(status, failed_at DESC);
+-- 같은 작업은 처리 전까지 한 번만 쌓임
+CREATE UNIQUE INDEX IF NOT EXISTS idx_dead_letter_jobs_source ON bigpicture.dead_letter_jobs(job_type, source_id) WHERE status = 'dead';
+
+-- 이미 재시도 한도(5회)를 넘겨 멈춰 있던 이미지 정리 작업 옮기기
+INSERT INTO bigpicture.dead_letter_jobs (job_type, source_id, payload, attempts, last_error)
+SELECT 'image_cleanup', id, jsonb_build_object('image_url', image_url, 'reason', reason), attempts, last_error
+FROM bigpicture.image_cleanup_jobs
+WHERE completed_at IS NULL AND attempts >= 5
+ON CONFLICT (job_type, source_id) WHERE status = 'dead' DO NOTHING;
+
+-- 실패한 마커 내보내기 작업 옮기기
+INSERT INTO bigpicture.dead_letter_jobs (job_type, source_id, payload, attempts, last_error, failed_at)
+SELECT 'marker_export', id, jsonb_build_object('format', format, 'since', since, 'requested_by', requested_by), 1, error, COALESCE(completed_at, NOW())
+FROM bigpicture.marker_exports
+WHERE status = 'failed'
+ON CONFLICT (job_type, source_id) WHERE status = 'dead' DO NOTHING;
@@ -0,0 +1,32 @@
+-- 회원 활동 기록 (내 활동 타임라인, 내 마커에 대한 다른 회원의 활동 알림 피드)
+-- recipient_id는 활동 대상 마커의 작성자 (알림 피드에서 받는 사람), 대상이 없으면 NULL
+CREATE TABLE IF NOT EXISTS bigpicture.activities (
+    id BIGSERIAL PRIMARY KEY,
+    actor_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    recipient_id BIGINT REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    activity_type VARCHAR(30) NOT NULL CHECK (activity_type IN ('marker_created', 'marker_liked', 'marker_commented', 'member_followed')),
+    marker_id BIGINT REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+-- 커서(id) 페이지네이션
+CREATE INDEX IF NOT EXISTS idx_activities_actor ON bigpicture.activities(actor_id, id DESC);
+CREATE INDEX IF NOT EXISTS idx_activities_recipient ON bigpicture.activities(recipient_id, id DESC) WHERE recipient_id IS NOT NULL;
+-- 좋아요 취소 후 다시 눌러도 활동은 하나만 남음
+CREATE UNIQUE INDEX IF NOT EXISTS idx_activities_marker_liked ON bigpicture.activities(actor_id, marker_id) WHERE activity_type = 'marker_liked';
+
+-- 기존 마커와 좋아요로 타임라인 채우기 (시간순으로 넣어 id 순서와 맞춤)
+INSERT INTO bigpicture.activities (actor_id, recipient_id, activity_type, marker_id, created_at)
+SELECT actor_id, recipient_id, activity_type, marker_id, created_at
+FROM (
+    SELECT member_id AS actor_id, NULL::BIGINT AS recipient_id, 'marker_created' AS activity_type, id AS marker_id, created_at
+    FROM bigpicture.markers
+    WHERE member_id IS NOT NULL
+    UNION ALL
+    SELECT mm.member_id, m.member_id, 'marker_liked', m.id, mm.created_at
+    FROM bigpicture.member_markers mm
+    JOIN bigpicture.markers m ON m.id = mm.marker_id
+    WHERE mm.interaction_type = 'liked'
+) existing
+WHERE EXISTS (SELECT 1 FROM bigpicture.members WHERE id = existing.actor_id)
+ORDER BY created_at
+ON CONFLICT DO NOTHING;
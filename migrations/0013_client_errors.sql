@@ -0,0 +1,22 @@
+-- 모바일/웹 클라이언트 오류 보고 (크래시, API 오류)
+-- request_id는 실패한 API 응답의 X-Request-Id라 서버 로그의 같은 요청과 이어 볼 수 있음
+-- API 오류는 CLIENT_ERROR_SAMPLE_RATE 비율만 저장하므로 건수는 1/sample_rate를 곱해 추정
+CREATE TABLE IF NOT EXISTS bigpicture.client_errors (
+    id BIGSERIAL PRIMARY KEY,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('crash', 'api_error')),
+    platform VARCHAR(20) NOT NULL CHECK (platform IN ('ios', 'android', 'web')),
+    app_version VARCHAR(50),
+    device VARCHAR(100),
+    os_version VARCHAR(50),
+    route VARCHAR(300), -- 화면 경로 또는 실패한 API 경로
+    request_id VARCHAR(64),
+    http_status INTEGER,
+    message TEXT NOT NULL,
+    stack_trace TEXT,
+    member_id BIGINT REFERENCES bigpicture.members(id) ON DELETE SET NULL,
+    report_request_id VARCHAR(64), -- 보고 요청 자체의 X-Request-Id
+    sample_rate REAL NOT NULL DEFAULT 1,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_client_errors_created ON bigpicture.client_errors(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_client_errors_request ON bigpicture.client_errors(request_id) WHERE request_id IS NOT NULL;
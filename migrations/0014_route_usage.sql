@@ -0,0 +1,14 @@
+-- 라우트별 일일 사용량 (제품 지표, 레거시 엔드포인트 제거 판단용)
+-- 서버가 메모리에 모은 (UTC 날짜, 메서드, 라우트 패턴, 앱 버전)별 집계를 주기적으로 더함
+CREATE TABLE IF NOT EXISTS bigpicture.route_usage_daily (
+    usage_date DATE NOT NULL,
+    method VARCHAR(10) NOT NULL,
+    route VARCHAR(200) NOT NULL, -- 라우트 패턴 (예: /api/markers/{id}), 라우트에 걸리지 않은 요청은 (unmatched)
+    client_version VARCHAR(30) NOT NULL, -- X-App-Version 헤더, 없으면 unknown
+    request_count BIGINT NOT NULL DEFAULT 0,
+    client_error_count BIGINT NOT NULL DEFAULT 0, -- 4xx
+    server_error_count BIGINT NOT NULL DEFAULT 0, -- 5xx
+    PRIMARY KEY (usage_date, method, route, client_version)
+);
+-- 특정 라우트(레거시 /api/images 등)를 언제까지 누가 부르는지
+CREATE INDEX IF NOT EXISTS idx_route_usage_daily_route ON bigpicture.route_usage_daily(route, usage_date DESC);
@@ -0,0 +1,21 @@
+-- 푸시 알림 (FCM, iOS 기기도 FCM을 거쳐 APNs로 전달)
+-- 기기 토큰은 회원이 여러 개 가질 수 있고, 같은 기기에서 다른 회원으로 로그인하면 소유자가 바뀜
+CREATE TABLE IF NOT EXISTS bigpicture.device_tokens (
+    id BIGSERIAL PRIMARY KEY,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    token VARCHAR(512) NOT NULL UNIQUE,
+    platform VARCHAR(20) NOT NULL CHECK (platform IN ('ios', 'android', 'web')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_device_tokens_member ON bigpicture.device_tokens(member_id);
+
+-- 알림함 항목의 푸시 발송 상태 (NULL이면 알림함에만 표시)
+ALTER TABLE bigpicture.notifications ADD COLUMN IF NOT EXISTS push_status VARCHAR(20)
+    CHECK (push_status IN ('pending', 'sending', 'sent', 'failed', 'no_device', 'expired'));
+ALTER TABLE bigpicture.notifications ADD COLUMN IF NOT EXISTS pushed_at TIMESTAMP WITH TIME ZONE;
+CREATE INDEX IF NOT EXISTS idx_notifications_push_pending ON bigpicture.notifications(id) WHERE push_status IN ('pending', 'sending');
+
+-- 회원별 푸시 수신 설정 ("지난 오늘"은 기존 memories_notification_enabled)
+ALTER TABLE bigpicture.members ADD COLUMN IF NOT EXISTS push_likes_enabled BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE bigpicture.members ADD COLUMN IF NOT EXISTS push_comments_enabled BOOLEAN NOT NULL DEFAULT true;
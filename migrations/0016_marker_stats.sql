@@ -0,0 +1,3 @@
+-- 대시보드 마커 통계 (/api/markers/stats): 기간 안의 공개 마커만 훑도록 작성 시각 부분 인덱스
+CREATE INDEX IF NOT EXISTS idx_markers_public_created ON bigpicture.markers(created_at)
+    WHERE taken_down_at IS NULL AND COALESCE(sharing_option, 'public') = 'public';
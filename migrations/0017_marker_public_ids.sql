@@ -0,0 +1,29 @@
+-- 공유 링크/QR 코드/임베드에 쓰는 마커 공개 ID (순차 숫자 ID로 전체 마커를 훑어 가지 못하도록)
+-- 모음, 모음처럼 읽히는 숫자(0 1 3 4), 헷갈리는 글자(l I O)를 뺀 45자에서 10자 (약 55비트)
+-- 모음이 없어 단어가 되지 않고, 자음만으로 된 욕설/비속어 조합이 나오면 다시 뽑음
+CREATE OR REPLACE FUNCTION bigpicture.generate_public_id() RETURNS VARCHAR(10)
+LANGUAGE plpgsql VOLATILE AS $$
+DECLARE
+    alphabet CONSTANT TEXT := '256789bcdfghjkmnpqrstvwxzBCDFGHJKLMNPQRSTVWXZ';
+    bytes BYTEA;
+    candidate TEXT;
+BEGIN
+    LOOP
+        bytes := sha256(uuid_send(gen_random_uuid()));
+        candidate := '';
+        FOR i IN 0..9 LOOP
+            candidate := candidate || substr(alphabet, 1 + get_byte(bytes, i) % length(alphabet), 1);
+        END LOOP;
+        IF candidate !~* '(fck|fk|sht|kkk|wtf|cnt|dck|fgt|ngg|ngr|btch|bch|prn|cck|xxx|sx|ss)' THEN
+            RETURN candidate;
+        END IF;
+    END LOOP;
+END;
+$$;
+
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS public_id VARCHAR(10);
+UPDATE bigpicture.markers SET public_id = bigpicture.generate_public_id() WHERE public_id IS NULL;
+ALTER TABLE bigpicture.markers ALTER COLUMN public_id SET DEFAULT bigpicture.generate_public_id();
+ALTER TABLE bigpicture.markers ALTER COLUMN public_id SET NOT NULL;
+-- 충돌하면 INSERT가 실패하므로 같은 ID가 두 마커에 붙지 않음
+CREATE UNIQUE INDEX IF NOT EXISTS idx_markers_public_id ON bigpicture.markers(public_id);
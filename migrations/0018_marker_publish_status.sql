@@ -0,0 +1,13 @@
+-- 임시 저장/예약 게시 마커: status가 published인 마커만 피드/지도/검색에 나옴
+-- scheduled 마커는 publish_at이 지나면 예약 게시 작업(scheduled_markers.rs)이 published로 바꿈 (기존 마커는 모두 published)
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'published'
+    CHECK (status IN ('draft', 'published', 'scheduled'));
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS publish_at TIMESTAMPTZ;
+
+-- 예약 게시 작업이 게시할 때가 된 마커만 찾도록
+CREATE INDEX IF NOT EXISTS idx_markers_scheduled_publish_at ON bigpicture.markers(publish_at)
+    WHERE status = 'scheduled';
+
+-- 내 임시 저장/예약 마커 목록
+CREATE INDEX IF NOT EXISTS idx_markers_member_unpublished ON bigpicture.markers(member_id, updated_at DESC)
+    WHERE status <> 'published';
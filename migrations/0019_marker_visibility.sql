@@ -0,0 +1,15 @@
+-- 마커 공개 범위 (public/followers/private): 예전 friends는 followers로 바꾸고, followers 마커는 작성자를 팔로우하는 회원만 봄
+CREATE TABLE IF NOT EXISTS bigpicture.member_follows (
+    follower_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    followee_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (follower_id, followee_id),
+    CHECK (follower_id <> followee_id)
+);
+-- 작성자의 팔로워 목록
+CREATE INDEX IF NOT EXISTS idx_member_follows_followee ON bigpicture.member_follows(followee_id, created_at DESC);
+
+ALTER TABLE bigpicture.markers DROP CONSTRAINT IF EXISTS markers_sharing_option_check;
+UPDATE bigpicture.markers SET sharing_option = 'followers' WHERE sharing_option = 'friends';
+ALTER TABLE bigpicture.markers ADD CONSTRAINT markers_sharing_option_check
+    CHECK (sharing_option IN ('public', 'followers', 'private'));
@@ -0,0 +1,2 @@
+-- 급상승 마커 순위 (/api/markers/rank?mode=trending): 마커별 댓글 활동 수를 셀 때 사용
+CREATE INDEX IF NOT EXISTS idx_activities_marker_commented ON bigpicture.activities(marker_id) WHERE activity_type = 'marker_commented';
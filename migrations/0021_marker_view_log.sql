@@ -0,0 +1,10 @@
+-- 마커 조회수 중복 제거: 조회자별로 마지막으로 조회수에 더한 시각 (VIEW_DEDUPE_WINDOW_HOURS 안의 재조회는 세지 않음)
+-- viewer_key는 회원이면 m:<회원 ID>, 비로그인이면 기기 ID 또는 IP/User-Agent의 HMAC (원래 값은 저장하지 않음)
+CREATE TABLE IF NOT EXISTS bigpicture.marker_view_log (
+    marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    viewer_key VARCHAR(64) NOT NULL,
+    counted_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (marker_id, viewer_key)
+);
+-- 중복 제거 기간이 지난 기록 정리
+CREATE INDEX IF NOT EXISTS idx_marker_view_log_counted ON bigpicture.marker_view_log(counted_at);
@@ -0,0 +1,14 @@
+-- 마커 공유 링크 (POST /api/markers/{id}/share로 만든 짧은 토큰, /s/{token}으로 열림)
+-- 토큰은 공개 ID와 같은 함수로 만들어 순차 ID로 훑을 수 없고, 누가 공유한 링크로 몇 번 열렸는지 남김
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS shares INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS bigpicture.marker_shares (
+    token VARCHAR(10) PRIMARY KEY DEFAULT bigpicture.generate_public_id(),
+    marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    member_id BIGINT REFERENCES bigpicture.members(id) ON DELETE SET NULL, -- 비로그인 공유는 NULL
+    opens INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_opened_at TIMESTAMP WITH TIME ZONE
+);
+-- 회원은 마커마다 링크 하나 (다시 공유하면 같은 링크, 공유 수도 한 번만)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_marker_shares_member ON bigpicture.marker_shares(marker_id, member_id) WHERE member_id IS NOT NULL;
@@ -0,0 +1,21 @@
+-- 마커 컬렉션(앨범): 회원이 마커를 이름 붙인 묶음으로 정리 (공개 컬렉션은 프로필에서 다른 사람도 봄)
+CREATE TABLE IF NOT EXISTS bigpicture.marker_collections (
+    id BIGSERIAL PRIMARY KEY,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    description TEXT,
+    is_public BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_marker_collections_member ON bigpicture.marker_collections(member_id, updated_at DESC);
+
+-- 컬렉션에 담긴 마커 (position 오름차순으로 보여 줌, 마커가 지워지면 같이 빠짐)
+CREATE TABLE IF NOT EXISTS bigpicture.marker_collection_items (
+    collection_id BIGINT NOT NULL REFERENCES bigpicture.marker_collections(id) ON DELETE CASCADE,
+    marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (collection_id, marker_id)
+);
+CREATE INDEX IF NOT EXISTS idx_marker_collection_items_marker ON bigpicture.marker_collection_items(marker_id);
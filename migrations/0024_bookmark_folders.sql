@@ -0,0 +1,15 @@
+-- 북마크 폴더: 회원이 북마크를 이름/색을 붙인 폴더로 나눔 (position 오름차순으로 보여 줌)
+CREATE TABLE IF NOT EXISTS bigpicture.bookmark_folders (
+    id BIGSERIAL PRIMARY KEY,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    name VARCHAR(30) NOT NULL,
+    color VARCHAR(7), -- #RRGGBB
+    position INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_bookmark_folders_member ON bigpicture.bookmark_folders(member_id, position);
+
+-- 북마크(interaction_type = 'bookmarked')가 들어 있는 폴더, 폴더를 지우면 폴더 없음으로
+ALTER TABLE bigpicture.member_markers ADD COLUMN IF NOT EXISTS folder_id BIGINT REFERENCES bigpicture.bookmark_folders(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_member_markers_folder ON bigpicture.member_markers(folder_id) WHERE folder_id IS NOT NULL;
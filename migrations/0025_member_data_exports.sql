@@ -0,0 +1,18 @@
+-- 회원 개인 데이터 내보내기 (내 마커/이미지 URL/상호작용을 ZIP 또는 JSON으로)
+-- 마커가 많은 회원은 백그라운드에서 파일을 만들어 S3에 올리고, 회원은 상태 조회 후 내려받음
+CREATE TABLE IF NOT EXISTS bigpicture.member_data_exports (
+    id BIGSERIAL PRIMARY KEY,
+    member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
+    format VARCHAR(10) NOT NULL, -- zip, json
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, running, completed, failed
+    marker_count BIGINT,
+    file_key VARCHAR(500),
+    file_size BIGINT,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX IF NOT EXISTS idx_member_data_exports_pending ON bigpicture.member_data_exports(created_at) WHERE status IN ('pending', 'running');
+-- 회원당 진행 중인 작업은 하나 (다시 요청하면 진행 중인 작업을 돌려줌)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_member_data_exports_active ON bigpicture.member_data_exports(member_id) WHERE status IN ('pending', 'running');
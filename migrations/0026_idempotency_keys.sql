@@ -0,0 +1,15 @@
+-- 멱등성 키 (Idempotency-Key 헤더): 네트워크가 불안정한 앱이 같은 마커 생성/업로드를 다시 보내도 한 번만 처리
+-- 같은 키로 다시 오면 처음 응답을 그대로 돌려주고, 다른 요청에 같은 키를 쓰면 거절 (request_hash로 비교)
+CREATE TABLE IF NOT EXISTS bigpicture.idempotency_keys (
+    scope VARCHAR(100) NOT NULL, -- member:<ID> 또는 ip:<주소>
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_hash VARCHAR(40) NOT NULL, -- SHA-1 (메서드, 경로, 쿼리, 본문)
+    status VARCHAR(20) NOT NULL DEFAULT 'processing', -- processing, completed
+    response_status INTEGER,
+    response_content_type VARCHAR(200),
+    response_body BYTEA,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (scope, idempotency_key)
+);
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created ON bigpicture.idempotency_keys(created_at);
@@ -0,0 +1,3 @@
+-- 마커 낙관적 잠금: 작성자가 내용을 고칠 때마다 1씩 늘어나는 버전
+-- PATCH는 마지막으로 본 버전(If-Match 또는 version)을 보내야 하고, 그 사이 다른 기기에서 고쳤으면 409
+ALTER TABLE bigpicture.markers ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,41 @@
+-- 이미지 저장 위치 (마커 이미지를 지우면 S3 객체/로컬 파일도 정리 작업으로 삭제)
+-- "s3:{객체 키}" 또는 "local:{파일 이름}", 이미지 URL 형식(S3 경로, 전체 S3 URL, 다운로드 API URL)과 무관하게 같은 값
+CREATE OR REPLACE FUNCTION bigpicture.image_storage_key(url TEXT) RETURNS VARCHAR(500)
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT CASE
+        WHEN url IS NULL OR url = '' THEN NULL
+        WHEN strpos(url, '/api/images/download/') > 0
+            THEN 'local:' || substr(url, strpos(url, '/api/images/download/') + length('/api/images/download/'))
+        WHEN strpos(url, '.amazonaws.com/') > 0
+            THEN 's3:' || substr(url, strpos(url, '.amazonaws.com/') + length('.amazonaws.com/'))
+        WHEN url LIKE '/_%' THEN 's3:' || substr(url, 2)
+    END
+$$;
+
+ALTER TABLE bigpicture.marker_images ADD COLUMN IF NOT EXISTS storage_key VARCHAR(500);
+UPDATE bigpicture.marker_images SET storage_key = bigpicture.image_storage_key(image_url) WHERE storage_key IS NULL;
+
+-- 이미지를 추가하는 쿼리가 여러 곳이라 URL에서 자동으로 채움
+CREATE OR REPLACE FUNCTION bigpicture.set_marker_image_storage_key() RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    NEW.storage_key := bigpicture.image_storage_key(NEW.image_url);
+    RETURN NEW;
+END;
+$$;
+DROP TRIGGER IF EXISTS marker_images_storage_key ON bigpicture.marker_images;
+CREATE TRIGGER marker_images_storage_key BEFORE INSERT OR UPDATE OF image_url ON bigpicture.marker_images
+FOR EACH ROW EXECUTE FUNCTION bigpicture.set_marker_image_storage_key();
+
+-- 지운 이미지를 다른 마커 이미지/마커 썸네일이 아직 쓰는지 확인할 때
+CREATE INDEX IF NOT EXISTS idx_marker_images_storage_key ON bigpicture.marker_images(storage_key);
+CREATE INDEX IF NOT EXISTS idx_markers_thumbnail_storage_key ON bigpicture.markers(bigpicture.image_storage_key(thumbnail_img));
+
+ALTER TABLE bigpicture.image_cleanup_jobs ADD COLUMN IF NOT EXISTS storage_key VARCHAR(500);
+
+-- S3 업로드 원본 (originals/): 변환된 이미지가 정리될 때 함께 삭제
+CREATE TABLE IF NOT EXISTS bigpicture.image_originals (
+    storage_key VARCHAR(500) PRIMARY KEY, -- 변환된 이미지
+    original_key VARCHAR(500) NOT NULL, -- 원본 ("s3:originals/...")
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
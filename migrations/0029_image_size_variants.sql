@@ -0,0 +1,11 @@
+-- 반응형 이미지 (업로드마다 너비별 WebP, srcset용)
+-- 변환된 이미지의 저장 위치(storage_key)에 연결, 이미지가 정리될 때 함께 삭제
+CREATE TABLE IF NOT EXISTS bigpicture.image_size_variants (
+    storage_key VARCHAR(500) NOT NULL, -- 변환된 이미지 ("s3:markers/..." 또는 "local:markers/...")
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    variant_url VARCHAR(500) NOT NULL,
+    variant_key VARCHAR(500) NOT NULL, -- 너비별 이미지의 저장 위치
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (storage_key, width)
+);
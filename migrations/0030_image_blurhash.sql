@@ -0,0 +1,25 @@
+-- BlurHash 자리표시자: 업로드 때 변환된 이미지마다 계산해 품질 정보와 함께 저장하고, 마커 이미지 JSON에 포함
+ALTER TABLE bigpicture.image_quality ADD COLUMN IF NOT EXISTS blurhash VARCHAR(64);
+ALTER TABLE bigpicture.marker_images ADD COLUMN IF NOT EXISTS blurhash VARCHAR(64);
+
+-- 마커 이미지 URL 형식(S3 경로, 전체 URL, 다운로드 API URL)과 무관하게 저장 위치로 찾음
+CREATE INDEX IF NOT EXISTS idx_image_quality_storage_key ON bigpicture.image_quality(bigpicture.image_storage_key(image_url));
+
+-- 이미지를 추가하는 쿼리가 여러 곳이라 업로드 기록에서 자동으로 채움
+-- 서버가 만든 썸네일(source_image_id)은 원본(상세) 이미지와 같은 자리표시자 사용
+CREATE OR REPLACE FUNCTION bigpicture.set_marker_image_blurhash() RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    NEW.blurhash := COALESCE(
+        (SELECT iq.blurhash FROM bigpicture.image_quality iq
+         WHERE bigpicture.image_storage_key(iq.image_url) = bigpicture.image_storage_key(NEW.image_url)
+           AND iq.blurhash IS NOT NULL
+         LIMIT 1),
+        (SELECT mi.blurhash FROM bigpicture.marker_images mi WHERE mi.id = NEW.source_image_id)
+    );
+    RETURN NEW;
+END;
+$$;
+DROP TRIGGER IF EXISTS marker_images_blurhash ON bigpicture.marker_images;
+CREATE TRIGGER marker_images_blurhash BEFORE INSERT OR UPDATE OF image_url ON bigpicture.marker_images
+FOR EACH ROW EXECUTE FUNCTION bigpicture.set_marker_image_blurhash();
@@ -0,0 +1,64 @@
+-- 업로드 이미지 검수 결과 (MODERATION_BACKEND가 none이면 기록하지 않음)
+-- 검수기 오류/시간 초과는 pending으로 남기고 재시도 작업이 다시 검수
+CREATE TABLE IF NOT EXISTS bigpicture.image_moderation (
+    storage_key VARCHAR(500) PRIMARY KEY, -- 변환된 이미지 ("s3:{객체 키}", "local:{파일 이름}")
+    source_key VARCHAR(500), -- 서버가 만든 썸네일이면 검수한 이미지 (결과를 함께 따름)
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    moderator VARCHAR(20) NOT NULL, -- rekognition, onnx
+    labels TEXT[] NOT NULL DEFAULT '{}', -- 거절 사유 라벨
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    checked_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_image_moderation_pending ON bigpicture.image_moderation(created_at) WHERE status = 'pending' AND source_key IS NULL;
+CREATE INDEX IF NOT EXISTS idx_image_moderation_source_key ON bigpicture.image_moderation(source_key) WHERE source_key IS NOT NULL;
+
+CREATE OR REPLACE FUNCTION bigpicture.image_moderation_rejected(key VARCHAR) RETURNS BOOLEAN
+LANGUAGE sql STABLE AS $$
+    SELECT EXISTS (SELECT 1 FROM bigpicture.image_moderation WHERE storage_key = key AND status = 'rejected')
+$$;
+
+-- 거절된 이미지는 마커 이미지로 추가돼도 내려간 상태로 저장 (기존 공개 조회는 taken_down_at으로 이미 걸러냄)
+CREATE OR REPLACE FUNCTION bigpicture.hide_rejected_marker_image() RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    IF bigpicture.image_moderation_rejected(bigpicture.image_storage_key(NEW.image_url)) THEN
+        NEW.taken_down_at := COALESCE(NEW.taken_down_at, NOW());
+    END IF;
+    RETURN NEW;
+END;
+$$;
+DROP TRIGGER IF EXISTS marker_images_moderation ON bigpicture.marker_images;
+CREATE TRIGGER marker_images_moderation BEFORE INSERT OR UPDATE OF image_url ON bigpicture.marker_images
+FOR EACH ROW EXECUTE FUNCTION bigpicture.hide_rejected_marker_image();
+
+CREATE OR REPLACE FUNCTION bigpicture.hide_rejected_marker_thumbnail() RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    IF bigpicture.image_moderation_rejected(bigpicture.image_storage_key(NEW.thumbnail_img)) THEN
+        NEW.thumbnail_img := NULL;
+    END IF;
+    RETURN NEW;
+END;
+$$;
+DROP TRIGGER IF EXISTS markers_thumbnail_moderation ON bigpicture.markers;
+CREATE TRIGGER markers_thumbnail_moderation BEFORE INSERT OR UPDATE OF thumbnail_img ON bigpicture.markers
+FOR EACH ROW EXECUTE FUNCTION bigpicture.hide_rejected_marker_thumbnail();
+
+-- 재시도로 나중에 거절되면 이미 마커에 쓰인 이미지/썸네일도 내림
+-- (관리자가 taken_down_at을 되돌리면 다시 보임)
+CREATE OR REPLACE FUNCTION bigpicture.take_down_rejected_image() RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    UPDATE bigpicture.marker_images SET taken_down_at = NOW(), updated_at = NOW()
+    WHERE storage_key = NEW.storage_key AND taken_down_at IS NULL;
+    UPDATE bigpicture.markers SET thumbnail_img = NULL, updated_at = NOW()
+    WHERE bigpicture.image_storage_key(thumbnail_img) = NEW.storage_key;
+    RETURN NULL;
+END;
+$$;
+DROP TRIGGER IF EXISTS image_moderation_rejected ON bigpicture.image_moderation;
+CREATE TRIGGER image_moderation_rejected AFTER INSERT OR UPDATE OF status ON bigpicture.image_moderation
+FOR EACH ROW WHEN (NEW.status = 'rejected') EXECUTE FUNCTION bigpicture.take_down_rejected_image();
@@ -0,0 +1,17 @@
+-- 이어 받는 업로드 세션 (tus 프로토콜): 끊긴 업로드를 받은 위치(upload_offset)부터 이어서 받음
+-- 받은 조각은 서버의 UPLOAD_TEMP_DIR/sessions/{id}.part에 모으고, 다 받으면 일반 업로드와 같은 처리 결과를 result에 남김
+CREATE TABLE IF NOT EXISTS bigpicture.upload_sessions (
+    id UUID PRIMARY KEY,
+    image_type VARCHAR(20) NOT NULL CHECK (image_type IN ('thumbnail', 'map')),
+    filename VARCHAR(255) NOT NULL,
+    upload_length BIGINT NOT NULL CHECK (upload_length > 0), -- 전체 크기 (Upload-Length)
+    upload_offset BIGINT NOT NULL DEFAULT 0, -- 지금까지 받은 크기 (Upload-Offset)
+    output_format VARCHAR(10) NOT NULL DEFAULT 'webp',
+    extract_exif BOOLEAN NOT NULL DEFAULT false,
+    status VARCHAR(20) NOT NULL DEFAULT 'uploading' CHECK (status IN ('uploading', 'processing', 'completed', 'failed')),
+    result TEXT, -- 처리 결과 응답 JSON (completed/failed)
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL -- 지나면 받은 조각과 함께 정리 (Upload-Expires)
+);
+CREATE INDEX IF NOT EXISTS idx_upload_sessions_expires ON bigpicture.upload_sessions(expires_at);
@@ -0,0 +1,2 @@
+-- 서버가 만든 썸네일을 찾을 때 이미지 URL 형식(S3 경로, 전체 URL, 다운로드 API URL)과 무관하게 저장 위치로 찾음
+CREATE INDEX IF NOT EXISTS idx_image_variants_storage_key ON bigpicture.image_variants(bigpicture.image_storage_key(image_url));
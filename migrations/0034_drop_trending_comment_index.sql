@@ -0,0 +1,2 @@
+-- 급상승 점수에서 댓글 수를 빼면서 쓰지 않게 된 인덱스 (댓글 기능이 생기면 그때 다시 만듦)
+DROP INDEX IF EXISTS bigpicture.idx_activities_marker_commented;
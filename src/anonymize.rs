@@ -0,0 +1,167 @@
+// 스테이징 갱신용 데이터 익명화 (운영 덤프를 복원한 DB에서 실행)
+// 이메일/닉네임/IP/정확한 좌표 등 개인정보를 제자리에서 치환하므로 운영 DB에서는 절대 실행하지 않음
+use anyhow::{bail, Result};
+use log::info;
+use sqlx::PgPool;
+
+const DEFAULT_JITTER_METERS: f64 = 300.0;
+
+#[derive(Debug)]
+pub struct AnonymizeOptions {
+    /// 실행 대상 DB 이름 확인값 (current_database()와 같아야 실행)
+    pub confirm_database: String,
+    /// 마커 좌표를 흔들 최대 거리 (미터)
+    pub jitter_meters: f64,
+}
+
+impl AnonymizeOptions {
+    /// `anonymize --confirm <db이름> [--jitter-meters <m>]` 인자 파싱
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut confirm_database = None;
+        let mut jitter_meters = DEFAULT_JITTER_METERS;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--confirm" => confirm_database = iter.next().cloned(),
+                "--jitter-meters" => {
+                    jitter_meters = iter.next()
+                        .and_then(|v| v.parse().ok())
+                        .filter(|v: &f64| *v > 0.0)
+                        .ok_or_else(|| anyhow::anyhow!("--jitter-meters는 0보다 큰 숫자여야 합니다"))?;
+                }
+                other => bail!("알 수 없는 옵션: {}", other),
+            }
+        }
+        let confirm_database = confirm_database
+            .ok_or_else(|| anyhow::anyhow!("--confirm <데이터베이스 이름>이 필요합니다"))?;
+        Ok(AnonymizeOptions { confirm_database, jitter_meters })
+    }
+}
+
+#[derive(Debug)]
+pub struct AnonymizeReport {
+    pub members: u64,
+    pub auth_providers: u64,
+    pub markers: u64,
+    pub marker_addresses: u64, // 역지오코딩 주소를 지운 마커 (원래 좌표의 주소라 흔든 좌표와 함께 남으면 안 됨)
+    pub audit_entries: u64,
+    pub api_keys: u64,
+    pub login_attempts: u64,
+    pub totp_secrets: u64,
+    pub connected_accounts: u64,
+    pub device_tokens: u64,
+}
+
+/// 개인정보 익명화 (하나의 트랜잭션으로 실행, 실패하면 전부 롤백)
+pub async fn anonymize_database(pool: &PgPool, options: &AnonymizeOptions) -> Result<AnonymizeReport> {
+    let current_database: String = sqlx::query_scalar("SELECT current_database()")
+        .fetch_one(pool)
+        .await?;
+    if current_database != options.confirm_database {
+        bail!(
+            "확인한 DB 이름({})이 연결된 DB({})와 다릅니다. 익명화를 중단합니다",
+            options.confirm_database, current_database
+        );
+    }
+    info!("🕶️ 데이터 익명화 시작: {} (좌표 흔들기 최대 {}m)", current_database, options.jitter_meters);
+
+    let mut tx = pool.begin().await?;
+    // 회원: 이메일/닉네임은 ID 기반 가명, 프로필 이미지는 제거
+    let members = sqlx::query(
+        r#"
+        UPDATE bigpicture.members
+        SET email = 'member' || id || '@example.invalid',
+            nickname = 'user_' || id,
+            profile_image_url = NULL
+        "#
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    // 소셜 계정: 외부 식별자/이메일/비밀번호 해시 제거
+    let auth_providers = sqlx::query(
+        r#"
+        UPDATE bigpicture.auth_providers
+        SET provider_id = 'anon-' || id,
+            provider_email = CASE WHEN provider_email IS NULL THEN NULL ELSE 'member' || member_id || '@example.invalid' END,
+            password_hash = NULL
+        "#
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    // 마커: 작성자 이름 가명 처리, 좌표는 반경 내 임의 위치로 이동 (면적 기준 균등 분포)
+    // 역지오코딩 주소는 원래 좌표의 주소이므로 함께 지움 (조회 시각도 비워 흔든 좌표로 다시 채워지게 함)
+    let (markers, marker_addresses): (i64, i64) = sqlx::query_as(
+        r#"
+        WITH updated AS (
+            UPDATE bigpicture.markers m
+            SET author = CASE WHEN m.member_id IS NULL THEN NULL ELSE 'user_' || m.member_id END,
+                location = CASE WHEN m.location IS NULL THEN NULL
+                                ELSE ST_Project(m.location, $1 * sqrt(random()), radians(random() * 360))::geography END,
+                address_text = NULL,
+                address_region = NULL,
+                address_locality = NULL,
+                address_neighborhood = NULL,
+                address_geocoded_at = NULL
+            FROM bigpicture.markers old
+            WHERE old.id = m.id
+            RETURNING old.address_text IS NOT NULL OR old.address_region IS NOT NULL OR old.address_locality IS NOT NULL
+                      OR old.address_neighborhood IS NOT NULL OR old.address_geocoded_at IS NOT NULL AS had_address
+        )
+        SELECT COUNT(*), COUNT(*) FILTER (WHERE had_address) FROM updated
+        "#
+    )
+    .bind(options.jitter_meters)
+    .fetch_one(&mut *tx)
+    .await?;
+    let (markers, marker_addresses) = (markers as u64, marker_addresses as u64);
+
+    // 감사 로그: 접속 IP/User-Agent 제거
+    let audit_entries = sqlx::query(
+        "UPDATE bigpicture.content_takedown_audit SET client_ip = NULL, user_agent = NULL"
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    // 로그인 시도 기록은 이메일/IP 자체가 개인정보라 스테이징에는 남기지 않음
+    let login_attempts = sqlx::query("DELETE FROM bigpicture.login_attempts")
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // 2단계 인증 비밀키가 남아 있으면 운영 계정의 인증 코드를 만들 수 있음
+    let totp_secrets = sqlx::query("DELETE FROM bigpicture.member_totp")
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // 연결된 SNS 토큰으로 운영 회원 계정에 글을 올릴 수 있음
+    let connected_accounts = sqlx::query("DELETE FROM bigpicture.member_connected_accounts")
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // 스테이징에서 운영 회원 기기로 푸시가 가지 않도록
+    let device_tokens = sqlx::query("DELETE FROM bigpicture.device_tokens")
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // 운영 API 키로 스테이징에 접근할 수 없도록 비활성화
+    let api_keys = sqlx::query("UPDATE bigpicture.api_keys SET is_active = false")
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    tx.commit().await?;
+    let report = AnonymizeReport { members, auth_providers, markers, marker_addresses, audit_entries, api_keys, login_attempts, totp_secrets, connected_accounts, device_tokens };
+    info!(
+        "✅ 데이터 익명화 완료: 회원 {}명, 소셜 계정 {}건, 마커 {}개(주소 {}개 삭제), 감사 로그 {}건, API 키 {}개, 로그인 기록 {}건, 2단계 인증 {}건, SNS 연결 {}건, 푸시 기기 {}건 삭제",
+        report.members, report.auth_providers, report.markers, report.marker_addresses, report.audit_entries, report.api_keys, report.login_attempts, report.totp_secrets, report.connected_accounts, report.device_tokens
+    );
+    Ok(report)
+}
@@ -0,0 +1,168 @@
+// 외부 공개 API용 API 키 인증 및 일일 쿼터 제한
+use actix_web::{
+    dev::Payload,
+    http::header::{HeaderName, HeaderValue},
+    http::StatusCode,
+    web, FromRequest, HttpRequest, HttpResponse, ResponseError,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures_util::future::LocalBoxFuture;
+use log::error;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::clock::{Clock, IdGenerator};
+use crate::database::{ApiKey, Database};
+use crate::error_handler::ErrorHandler;
+
+/// 읽기 전용 공개 API 스코프 (회원 JWT와는 별개)
+pub const SCOPE_PUBLIC_READ: &str = "public_read";
+pub const API_KEY_HEADER: &str = "X-API-Key";
+/// 헤더를 붙일 수 없는 임베드(지도 타일 등)용 쿼리 파라미터
+pub const API_KEY_QUERY_PARAM: &str = "api_key";
+const API_KEY_PREFIX: &str = "bpk_";
+
+/// 새 API 키 원문 생성 (발급 응답에서 한 번만 노출)
+pub fn generate_api_key(ids: &dyn IdGenerator) -> String {
+    format!("{}{}", API_KEY_PREFIX, ids.new_id().simple())
+}
+
+/// 다음 UTC 자정까지 남은 초 (일일 쿼터 초기화 시점)
+fn seconds_until_reset(now: DateTime<Utc>) -> i64 {
+    let tomorrow = (now.date_naive() + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .map(|midnight| midnight.and_utc())
+        .unwrap_or(now);
+    (tomorrow - now).num_seconds().max(1)
+}
+
+#[derive(Debug)]
+pub enum ApiKeyError {
+    MissingKey,
+    InvalidKey,
+    WrongScope(String),
+    QuotaExceeded { daily_quota: i32, retry_after: i64 },
+    Internal(String),
+}
+
+impl fmt::Display for ApiKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiKeyError::MissingKey => write!(f, "Missing API key"),
+            ApiKeyError::InvalidKey => write!(f, "Invalid API key"),
+            ApiKeyError::WrongScope(scope) => write!(f, "API key scope not allowed: {}", scope),
+            ApiKeyError::QuotaExceeded { daily_quota, .. } => write!(f, "Daily quota exceeded ({})", daily_quota),
+            ApiKeyError::Internal(e) => write!(f, "API key check failed: {}", e),
+        }
+    }
+}
+
+impl ResponseError for ApiKeyError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiKeyError::MissingKey | ApiKeyError::InvalidKey => StatusCode::UNAUTHORIZED,
+            ApiKeyError::WrongScope(_) => StatusCode::FORBIDDEN,
+            ApiKeyError::QuotaExceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiKeyError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            ApiKeyError::MissingKey => ErrorHandler::unauthorized(
+                "API 키가 필요합니다.",
+                Some("X-API-Key 헤더나 api_key 파라미터가 없습니다")
+            ),
+            ApiKeyError::InvalidKey => ErrorHandler::unauthorized(
+                "유효하지 않은 API 키입니다.",
+                Some("등록되지 않았거나 비활성화된 키")
+            ),
+            ApiKeyError::WrongScope(scope) => ErrorHandler::forbidden(
+                "이 API 키로는 사용할 수 없는 API입니다.",
+                Some(&format!("키 스코프: {}", scope))
+            ),
+            ApiKeyError::QuotaExceeded { daily_quota, retry_after } => {
+                let mut response = ErrorHandler::too_many_requests(
+                    "일일 요청 한도를 초과했습니다.",
+                    Some(&format!("일일 한도 {}회", daily_quota)),
+                    *retry_after
+                );
+                let headers = response.headers_mut();
+                headers.insert(HeaderName::from_static("x-ratelimit-limit"), HeaderValue::from(*daily_quota));
+                headers.insert(HeaderName::from_static("x-ratelimit-remaining"), HeaderValue::from(0));
+                response
+            }
+            ApiKeyError::Internal(e) => ErrorHandler::internal_server_error(
+                "API 키 확인 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ),
+        }
+    }
+}
+
+/// public_read 스코프 API 키로 인증된 외부 클라이언트
+/// 추출 시점에 요청이 집계되고, 일일 쿼터를 넘으면 429로 거절됨
+#[derive(Debug)]
+pub struct ApiKeyClient {
+    pub key: ApiKey,
+    pub used_today: i64,
+}
+
+impl ApiKeyClient {
+    /// 응답에 붙일 X-RateLimit-* 헤더
+    pub fn rate_limit_headers(&self) -> [(&'static str, String); 2] {
+        let remaining = (self.key.daily_quota as i64 - self.used_today).max(0);
+        [
+            ("X-RateLimit-Limit", self.key.daily_quota.to_string()),
+            ("X-RateLimit-Remaining", remaining.to_string()),
+        ]
+    }
+}
+
+impl FromRequest for ApiKeyClient {
+    type Error = ApiKeyError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let raw_key = req.headers()
+            .get(API_KEY_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+            .or_else(|| {
+                web::Query::<HashMap<String, String>>::from_query(req.query_string()).ok()?
+                    .get(API_KEY_QUERY_PARAM)
+                    .map(|key| key.trim().to_string())
+                    .filter(|key| !key.is_empty())
+            });
+        let db = req.app_data::<web::Data<Database>>().cloned();
+        let now = req.app_data::<web::Data<dyn Clock>>().map(|clock| clock.now());
+        // 엔드포인트별 집계는 경로 패턴 기준 (ID 등 경로 값 제외)
+        let endpoint = req.match_pattern().unwrap_or_else(|| req.path().to_string());
+
+        Box::pin(async move {
+            let raw_key = raw_key.ok_or(ApiKeyError::MissingKey)?;
+            let db = db.ok_or_else(|| ApiKeyError::Internal("Database not registered".to_string()))?;
+            let now = now.ok_or_else(|| ApiKeyError::Internal("Clock not registered".to_string()))?;
+            let key = db.find_api_key(&raw_key).await
+                .map_err(|e| ApiKeyError::Internal(e.to_string()))?
+                .ok_or(ApiKeyError::InvalidKey)?;
+            if key.scope != SCOPE_PUBLIC_READ {
+                return Err(ApiKeyError::WrongScope(key.scope));
+            }
+
+            let (allowed, used_today) = db.record_api_key_usage(key.id, &endpoint, key.daily_quota).await
+                .map_err(|e| {
+                    error!("❌ API 키 사용량 기록 실패: {}", e);
+                    ApiKeyError::Internal(e.to_string())
+                })?;
+            if !allowed {
+                return Err(ApiKeyError::QuotaExceeded {
+                    daily_quota: key.daily_quota,
+                    retry_after: seconds_until_reset(now),
+                });
+            }
+            Ok(ApiKeyClient { key, used_today })
+        })
+    }
+}
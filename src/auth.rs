@@ -0,0 +1,214 @@
+// JWT 인증 미들웨어 및 추출기
+use actix_web::{
+    body::MessageBody,
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    http::StatusCode,
+    middleware::Next,
+    web, Error, FromRequest, HttpMessage, HttpRequest, HttpResponse, ResponseError,
+};
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::{ready, Ready};
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::database::{Database, Member};
+use crate::error_handler::ErrorHandler;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String, // subject (user id)
+    pub email: String,
+    pub exp: usize, // 만료시간 (timestamp)
+    // 아래 클레임은 예전에 발급된 토큰에는 없으므로 선택 필드
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>, // member, admin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<usize>, // 발급 시각 (강제 로그아웃 판정)
+}
+
+impl Claims {
+    /// 회원 토큰 클레임 (ttl_secs 후 만료, 발급자/대상은 설정값)
+    pub fn for_member(member: &Member, ttl_secs: i64, config: &Config, now: chrono::DateTime<chrono::Utc>) -> Self {
+        Claims {
+            sub: member.id.to_string(),
+            email: member.email.clone(),
+            exp: (now + chrono::Duration::seconds(ttl_secs)).timestamp() as usize,
+            iss: Some(config.jwt_issuer.clone()),
+            aud: Some(config.jwt_audience.clone()),
+            role: Some(member.role().to_string()),
+            nickname: Some(member.nickname.clone()),
+            iat: Some(now.timestamp() as usize),
+        }
+    }
+}
+
+/// 토큰 검증 실패 사유 (401 응답으로 변환)
+#[derive(Debug, Clone)]
+pub enum AuthError {
+    MissingToken,
+    InvalidToken(String),
+    InvalidSubject,
+    NotConfigured,
+    Suspended,
+    Revoked,
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::MissingToken => write!(f, "No Bearer token"),
+            AuthError::InvalidToken(e) => write!(f, "Invalid token: {}", e),
+            AuthError::InvalidSubject => write!(f, "Invalid user id in token"),
+            AuthError::NotConfigured => write!(f, "JWT config not registered"),
+            AuthError::Suspended => write!(f, "Member suspended"),
+            AuthError::Revoked => write!(f, "Token revoked"),
+        }
+    }
+}
+
+impl ResponseError for AuthError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AuthError::Suspended => StatusCode::FORBIDDEN,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            AuthError::MissingToken => ErrorHandler::unauthorized(
+                "No Bearer token",
+                Some("Authorization 헤더가 없거나 Bearer 형식이 아닙니다")
+            ),
+            AuthError::InvalidToken(e) => ErrorHandler::unauthorized(
+                "Invalid token",
+                Some(&format!("토큰 검증 실패: {}", e))
+            ),
+            AuthError::InvalidSubject => ErrorHandler::unauthorized(
+                "Invalid user id in token",
+                Some("토큰의 사용자 ID 파싱 실패")
+            ),
+            AuthError::NotConfigured => ErrorHandler::unauthorized(
+                "로그인이 필요합니다. JWT 토큰을 확인해주세요.",
+                Some("JWT 설정이 등록되지 않았습니다")
+            ),
+            AuthError::Suspended => ErrorHandler::forbidden(
+                "이용이 정지된 계정입니다.",
+                Some("관리자에 의해 정지되었거나 탈퇴한 회원")
+            ),
+            AuthError::Revoked => ErrorHandler::unauthorized(
+                "다시 로그인해주세요.",
+                Some("강제 로그아웃 이전에 발급된 토큰")
+            ),
+        }
+    }
+}
+
+/// 인증된 회원 (토큰 검증을 통과한 요청에서만 추출됨)
+/// 로그인이 선택인 핸들러는 `Option<AuthenticatedMember>`로 받음
+#[derive(Debug, Clone)]
+pub struct AuthenticatedMember {
+    pub member_id: i64,
+    pub claims: Claims,
+}
+
+/// 만료 판정 허용 오차 (jsonwebtoken 기본값과 동일)
+const EXP_LEEWAY_SECS: i64 = 60;
+
+/// 앱 상태의 Clock 기준 현재 시각 (등록되지 않았으면 시스템 시각)
+fn request_now(req: &HttpRequest) -> chrono::DateTime<chrono::Utc> {
+    req.app_data::<web::Data<dyn Clock>>()
+        .map(|clock| clock.now())
+        .unwrap_or_else(chrono::Utc::now)
+}
+
+/// Authorization 헤더의 Bearer 토큰 검증 (만료는 주입된 Clock 기준)
+pub fn authenticate(req: &HttpRequest, config: &Config) -> Result<AuthenticatedMember, AuthError> {
+    let token = req.headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or(AuthError::MissingToken)?;
+    let mut validation = Validation::default();
+    validation.validate_exp = false;
+    // iss/aud는 토큰에 있을 때만 검증됨 (클레임 추가 전 토큰 호환)
+    validation.set_issuer(&[&config.jwt_issuer]);
+    validation.set_audience(&[&config.jwt_audience]);
+    let claims = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
+        &validation,
+    )
+    .map_err(|e| AuthError::InvalidToken(e.to_string()))?
+    .claims;
+    if (claims.exp as i64) + EXP_LEEWAY_SECS < request_now(req).timestamp() {
+        return Err(AuthError::InvalidToken("ExpiredSignature".to_string()));
+    }
+    let member_id = claims.sub.parse().map_err(|_| AuthError::InvalidSubject)?;
+    Ok(AuthenticatedMember { member_id, claims })
+}
+
+/// 정지/탈퇴 회원과 강제 로그아웃 이전에 발급된 토큰 거절
+/// 조회 실패 시에는 통과시킴 (DB 장애로 모든 인증 요청이 막히지 않도록)
+async fn check_member_session(db: &Database, member: &AuthenticatedMember) -> Result<(), AuthError> {
+    match db.get_member_session_state(member.member_id).await {
+        Ok(Some(state)) if !state.is_active => Err(AuthError::Suspended),
+        Ok(Some(state)) => match (state.tokens_revoked_at, member.claims.iat) {
+            // 초 단위 발급 시각이므로 같은 초에 다시 발급된 토큰은 허용
+            (Some(revoked_at), Some(iat)) if (iat as i64) < revoked_at.timestamp() => Err(AuthError::Revoked),
+            (Some(_), None) => Err(AuthError::Revoked),
+            _ => Ok(()),
+        },
+        Ok(None) => Ok(()),
+        Err(e) => {
+            warn!("⚠️ 회원 세션 상태 조회 실패 (통과): {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// 요청마다 토큰을 한 번만 검증해서 결과를 request extensions에 저장
+/// 공개 API도 같은 스코프에 있으므로 여기서 거절하지 않고, 추출기에서 401 처리
+pub async fn jwt_auth(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let mut result = match req.app_data::<web::Data<Config>>() {
+        Some(config) => authenticate(req.request(), config),
+        None => Err(AuthError::NotConfigured),
+    };
+    if let (Ok(member), Some(db)) = (&result, req.app_data::<web::Data<Database>>())
+        && let Err(e) = check_member_session(db, member).await
+    {
+        result = Err(e);
+    }
+    req.extensions_mut().insert(result);
+    next.call(req).await
+}
+
+impl FromRequest for AuthenticatedMember {
+    type Error = AuthError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        // 미들웨어가 검증한 결과 사용, 미들웨어가 없는 경우에만 직접 검증
+        let cached = req.extensions().get::<Result<AuthenticatedMember, AuthError>>().cloned();
+        let result = match cached {
+            Some(result) => result,
+            None => match req.app_data::<web::Data<Config>>() {
+                Some(config) => authenticate(req, config),
+                None => Err(AuthError::NotConfigured),
+            },
+        };
+        ready(result)
+    }
+}
@@ -0,0 +1,88 @@
+// BlurHash 자리표시자 (https://blurha.sh): 이미지의 대략적인 색 분포를 20~30자 문자열로 인코딩
+// 앱이 WebP 썸네일을 받기 전에 흐린 미리보기를 바로 그릴 수 있게 변환된 이미지마다 계산해 둠
+use image::DynamicImage;
+use std::f64::consts::PI;
+
+/// 가로/세로 성분 수 (4x3이면 28자)
+pub const X_COMPONENTS: u32 = 4;
+pub const Y_COMPONENTS: u32 = 3;
+/// 계산 전에 줄이는 크기 (결과는 저주파 성분뿐이라 작아도 같음)
+const SAMPLE_SIZE: u32 = 32;
+
+const BASE83: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+/// 이미지 BlurHash (성분 수는 1~9)
+pub fn encode(image: &DynamicImage, x_components: u32, y_components: u32) -> String {
+    let sample = image.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE).to_rgb8();
+    let (width, height) = sample.dimensions();
+    let pixels: Vec<[f64; 3]> = sample.pixels()
+        .map(|pixel| [srgb_to_linear(pixel[0]), srgb_to_linear(pixel[1]), srgb_to_linear(pixel[2])])
+        .collect();
+
+    let mut factors = Vec::with_capacity((x_components * y_components) as usize);
+    for j in 0..y_components {
+        for i in 0..x_components {
+            let normalisation = if i == 0 && j == 0 { 1.0 } else { 2.0 };
+            let mut factor = [0.0; 3];
+            for y in 0..height {
+                for x in 0..width {
+                    let basis = normalisation
+                        * (PI * i as f64 * x as f64 / width as f64).cos()
+                        * (PI * j as f64 * y as f64 / height as f64).cos();
+                    for (sum, value) in factor.iter_mut().zip(pixels[(y * width + x) as usize]) {
+                        *sum += basis * value;
+                    }
+                }
+            }
+            let scale = 1.0 / (width * height) as f64;
+            factors.push(factor.map(|value| value * scale));
+        }
+    }
+
+    let mut hash = String::new();
+    push_base83(&mut hash, (x_components - 1) + (y_components - 1) * 9, 1);
+    let (dc, ac) = factors.split_first().expect("최소 1개 성분");
+    let max_value = if ac.is_empty() {
+        push_base83(&mut hash, 0, 1);
+        1.0
+    } else {
+        let actual_max = ac.iter().flatten().fold(0.0f64, |max, value| max.max(value.abs()));
+        let quantised_max = ((actual_max * 166.0 - 0.5).floor() as i64).clamp(0, 82) as u32;
+        push_base83(&mut hash, quantised_max, 1);
+        (quantised_max + 1) as f64 / 166.0
+    };
+    push_base83(&mut hash, (linear_to_srgb(dc[0]) << 16) + (linear_to_srgb(dc[1]) << 8) + linear_to_srgb(dc[2]), 4);
+    for factor in ac {
+        let quantised = factor.map(|value| {
+            let scaled = (value / max_value).abs().sqrt().copysign(value);
+            ((scaled * 9.0 + 9.5).floor() as i64).clamp(0, 18) as u32
+        });
+        push_base83(&mut hash, quantised[0] * 19 * 19 + quantised[1] * 19 + quantised[2], 2);
+    }
+    hash
+}
+
+fn push_base83(hash: &mut String, value: u32, length: u32) {
+    for position in (0..length).rev() {
+        let digit = (value / 83u32.pow(position)) % 83;
+        hash.push(BASE83[digit as usize] as char);
+    }
+}
+
+fn srgb_to_linear(value: u8) -> f64 {
+    let value = value as f64 / 255.0;
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f64) -> u32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.003_130_8 {
+        (value * 12.92 * 255.0 + 0.5) as u32
+    } else {
+        ((1.055 * value.powf(1.0 / 2.4) - 0.055) * 255.0 + 0.5) as u32
+    }
+}
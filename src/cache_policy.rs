@@ -0,0 +1,115 @@
+// 엔드포인트별 Cache-Control 정책 (익명 읽기 트래픽을 CDN이 흡수하도록)
+// 공개 통계/감정 카탈로그는 길게 캐시하고 stale-while-revalidate로 만료 직후에도 바로 응답,
+// 클러스터는 지도 이동마다 다시 부르므로 브라우저에서만 짧게 캐시, 임베드용 감정 타일은 집계 값뿐이라 길게 캐시
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::{header::{HeaderValue, CACHE_CONTROL}, Method},
+    middleware::Next,
+    web, Error,
+};
+
+use crate::config::Config;
+
+/// 공개 통계 엔드포인트 (라우트 패턴 기준)
+const STATS_ROUTES: &[&str] = &[
+    "/api/public/v1/emotions/stats",
+    "/api/districts/{code}/stats",
+    "/api/likes/stats",
+    "/api/markers/stats",
+];
+/// 거의 바뀌지 않는 카탈로그
+const CATALOG_ROUTES: &[&str] = &["/api/emotions"];
+/// 요청자마다 결과가 다를 수 있는 지도 클러스터 (my=true 등)
+const CLUSTER_ROUTES: &[&str] = &["/api/markers/cluster"];
+/// 외부 임베드용 감정 지도 타일 (마커 내용 없이 칸별 집계만)
+const TILE_ROUTES: &[&str] = &["/api/public/emotion-tiles/{z}/{x}/{y}.json"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// CDN과 브라우저 모두 캐시, 만료 후 stale_while_revalidate초 동안은 이전 응답을 주면서 갱신
+    Public { max_age: i64, stale_while_revalidate: i64 },
+    /// 브라우저만 캐시
+    Private { max_age: i64 },
+}
+
+impl CachePolicy {
+    /// Cache-Control 값 (max_age가 0 이하면 None → 헤더를 붙이지 않음)
+    pub fn header_value(&self) -> Option<String> {
+        match *self {
+            Self::Public { max_age, .. } | Self::Private { max_age } if max_age <= 0 => None,
+            Self::Public { max_age, stale_while_revalidate } if stale_while_revalidate > 0 => Some(format!(
+                "public, max-age={}, stale-while-revalidate={}", max_age, stale_while_revalidate
+            )),
+            Self::Public { max_age, .. } => Some(format!("public, max-age={}", max_age)),
+            Self::Private { max_age } => Some(format!("private, max-age={}", max_age)),
+        }
+    }
+}
+
+/// 설정값으로 만든 엔드포인트별 정책
+#[derive(Debug, Clone, Copy)]
+pub struct CachePolicies {
+    stats: CachePolicy,
+    catalog: CachePolicy,
+    cluster: CachePolicy,
+    tiles: CachePolicy,
+}
+
+impl CachePolicies {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            stats: CachePolicy::Public {
+                max_age: config.stats_cache_max_age_secs,
+                stale_while_revalidate: config.stats_cache_swr_secs,
+            },
+            catalog: CachePolicy::Public {
+                max_age: config.catalog_cache_max_age_secs,
+                stale_while_revalidate: config.catalog_cache_swr_secs,
+            },
+            cluster: CachePolicy::Private { max_age: config.cluster_cache_max_age_secs },
+            tiles: CachePolicy::Public {
+                max_age: config.tile_cache_max_age_secs,
+                stale_while_revalidate: config.tile_cache_swr_secs,
+            },
+        }
+    }
+
+    /// 라우트 패턴(예: /api/districts/{code}/stats)에 해당하는 정책
+    pub fn for_route(&self, pattern: &str) -> Option<CachePolicy> {
+        if STATS_ROUTES.contains(&pattern) {
+            Some(self.stats)
+        } else if CATALOG_ROUTES.contains(&pattern) {
+            Some(self.catalog)
+        } else if CLUSTER_ROUTES.contains(&pattern) {
+            Some(self.cluster)
+        } else if TILE_ROUTES.contains(&pattern) {
+            Some(self.tiles)
+        } else {
+            None
+        }
+    }
+}
+
+/// 앱 전체에 거는 미들웨어: 성공한 GET/HEAD 응답에만 정책을 붙이고,
+/// 핸들러가 Cache-Control을 직접 정했으면 그대로 둠 (오류 응답은 캐시하지 않음)
+pub async fn apply_cache_policy(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let cacheable = req.method() == Method::GET || req.method() == Method::HEAD;
+    let policies = req.app_data::<web::Data<Config>>().map(|config| CachePolicies::from_config(config));
+    let mut response = next.call(req).await?;
+
+    if !cacheable || !response.status().is_success() || response.headers().contains_key(CACHE_CONTROL) {
+        return Ok(response);
+    }
+    let header = response.request().match_pattern()
+        .and_then(|pattern| policies?.for_route(&pattern))
+        .and_then(|policy| policy.header_value())
+        .and_then(|value| HeaderValue::from_str(&value).ok());
+    if let Some(header) = header {
+        response.headers_mut().insert(CACHE_CONTROL, header);
+    }
+    Ok(response)
+}
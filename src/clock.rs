@@ -0,0 +1,70 @@
+// 시간/ID 생성 추상화 (앱 상태로 주입해서 테스트에서 고정값 사용)
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use uuid::Uuid;
+
+/// 현재 시각 제공자 (토큰 만료, 파일 이름 타임스탬프 등)
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// 고유 ID 제공자 (업로드 파일 이름, API 키 등)
+pub trait IdGenerator: Send + Sync {
+    fn new_id(&self) -> Uuid;
+}
+
+/// 실제 시스템 시각
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 테스트용 고정 시각 (advance로 시간 경과를 흉내냄)
+pub struct FixedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl FixedClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// 무작위 UUID v4
+pub struct RandomIdGenerator;
+
+impl IdGenerator for RandomIdGenerator {
+    fn new_id(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+/// 테스트용 순차 ID (1, 2, 3, ...을 UUID로)
+#[derive(Default)]
+pub struct SequentialIdGenerator {
+    next: AtomicU64,
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn new_id(&self) -> Uuid {
+        Uuid::from_u128(self.next.fetch_add(1, Ordering::SeqCst) as u128 + 1)
+    }
+}
@@ -0,0 +1,172 @@
+// 마커 클러스터 결과 캐시 (같은 지도 화면을 여러 사용자가 반복 조회할 때 DB 조회를 건너뜀)
+// 지도 중심을 해상도보다 3단계 잘게 나눈 H3 셀 중심으로, 영역 크기를 유효숫자 2자리로 올림해서
+// 조금씩 다른 화면도 같은 키가 되도록 맞추고, 맞춘 영역으로 조회하므로 캐시된 결과와 직접 조회 결과가 같음
+// 마커 생성/수정/게시 중단/복구 시 그 좌표를 포함한 캐시를 지움 (나머지 변경은 CLUSTER_RESULT_CACHE_SECS 후 반영)
+use chrono::{DateTime, Duration, Utc};
+use geo_types::Point;
+use h3ron::{H3Cell, Index, ToCoordinate};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::config::Config;
+use crate::database::{CreatedTimeFilter, Marker};
+
+/// 지도 중심을 맞추는 H3 셀은 클러스터 해상도보다 이만큼 잘게
+const CENTER_RESOLUTION_OFFSET: u8 = 3;
+const MAX_H3_RESOLUTION: u8 = 15;
+const PRUNE_THRESHOLD: usize = 2_000;
+
+/// 캐시 키로 맞춘 조회 영역
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClusterViewport {
+    pub center_cell: u64,
+    pub lat: f64,
+    pub lng: f64,
+    pub lat_delta: f64,
+    pub lng_delta: f64,
+}
+
+impl ClusterViewport {
+    pub fn normalize(lat: f64, lng: f64, lat_delta: f64, lng_delta: f64, resolution: u8) -> Self {
+        let center_resolution = (resolution + CENTER_RESOLUTION_OFFSET).min(MAX_H3_RESOLUTION);
+        let (center_cell, lat, lng) = match H3Cell::from_point(Point::new(lng, lat), center_resolution) {
+            Ok(cell) => match cell.to_coordinate() {
+                Ok(center) => (cell.h3index(), center.y, center.x),
+                Err(_) => (cell.h3index(), lat, lng),
+            },
+            Err(_) => (0, lat, lng),
+        };
+        Self {
+            center_cell,
+            lat,
+            lng,
+            lat_delta: round_up_significant(lat_delta),
+            lng_delta: round_up_significant(lng_delta),
+        }
+    }
+
+    /// 조회 영역 (클러스터 조회의 20% 여유 포함, 무효화 판단용)
+    fn contains(&self, lat: f64, lng: f64) -> bool {
+        (lat - self.lat).abs() <= self.lat_delta * 0.6 && (lng - self.lng).abs() <= self.lng_delta * 0.6
+    }
+}
+
+/// 유효숫자 2자리로 올림 (0.0123 → 0.013)
+fn round_up_significant(value: f64) -> f64 {
+    if !(value.is_finite() && value > 0.0) {
+        return value;
+    }
+    let scale = 10f64.powi(1 - value.log10().floor() as i32);
+    (value * scale - 1e-9).ceil() / scale
+}
+
+/// 영역 외 조회 조건 (같은 조건끼리만 결과를 공유)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClusterFilters {
+    pub emotion_tags: Vec<String>, // 정렬해서 순서만 다른 요청도 같은 키
+    pub min_likes: Option<i32>,
+    pub min_views: Option<i32>,
+    pub limit: Option<i32>,
+    pub user_id: Option<i64>, // 내 마커만 보기
+    pub viewer_id: Option<i64>, // 로그인 회원마다 보이는 followers/비공개 마커가 다르므로 따로
+    pub time_filter: CreatedTimeFilter, // 생성 시간대/계절/기간 (period는 시작 시각으로 풀어서)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    resolution: u8,
+    center_cell: u64,
+    lat_delta_bits: u64,
+    lng_delta_bits: u64,
+    filters: ClusterFilters,
+}
+
+struct CacheEntry {
+    cached_at: DateTime<Utc>,
+    viewport: ClusterViewport,
+    clusters: Arc<Vec<serde_json::Value>>,
+}
+
+/// 워커 전체가 공유하는 클러스터 결과 캐시 (TTL 0이면 사용하지 않음)
+#[derive(Clone)]
+pub struct ClusterCache {
+    entries: Arc<Mutex<HashMap<CacheKey, CacheEntry>>>,
+    ttl: Duration,
+}
+
+impl ClusterCache {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            ttl: Duration::seconds(config.cluster_result_cache_secs),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.ttl > Duration::zero()
+    }
+
+    fn key(resolution: u8, viewport: &ClusterViewport, filters: &ClusterFilters) -> CacheKey {
+        CacheKey {
+            resolution,
+            center_cell: viewport.center_cell,
+            lat_delta_bits: viewport.lat_delta.to_bits(),
+            lng_delta_bits: viewport.lng_delta.to_bits(),
+            filters: filters.clone(),
+        }
+    }
+
+    pub fn get(&self, resolution: u8, viewport: &ClusterViewport, filters: &ClusterFilters, now: DateTime<Utc>) -> Option<Arc<Vec<serde_json::Value>>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let entries = self.entries.lock().unwrap();
+        entries.get(&Self::key(resolution, viewport, filters))
+            .filter(|entry| now - entry.cached_at < self.ttl)
+            .map(|entry| entry.clusters.clone())
+    }
+
+    pub fn insert(&self, resolution: u8, viewport: &ClusterViewport, filters: &ClusterFilters, clusters: Vec<serde_json::Value>, now: DateTime<Utc>) -> Arc<Vec<serde_json::Value>> {
+        let clusters = Arc::new(clusters);
+        if !self.is_enabled() {
+            return clusters;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() > PRUNE_THRESHOLD {
+            let ttl = self.ttl;
+            entries.retain(|_, entry| now - entry.cached_at < ttl);
+        }
+        entries.insert(Self::key(resolution, viewport, filters), CacheEntry {
+            cached_at: now,
+            viewport: *viewport,
+            clusters: clusters.clone(),
+        });
+        clusters
+    }
+
+    /// 좌표가 조회 영역에 들어가는 캐시 삭제 (마커 생성/위치 변경/게시 중단/복구)
+    pub fn invalidate_point(&self, lat: f64, lng: f64) {
+        self.entries.lock().unwrap().retain(|_, entry| !entry.viewport.contains(lat, lng));
+    }
+
+    /// 마커 현재 위치가 들어가는 캐시 삭제 (위치를 모르면 전체 삭제)
+    pub fn invalidate_marker(&self, marker: &Marker) {
+        match marker.get_latitude().zip(marker.get_longitude()) {
+            Some((lat, lng)) => self.invalidate_point(lat, lng),
+            None => self.clear(),
+        }
+    }
+
+    /// 회원 탈퇴처럼 지워진 마커 위치를 모를 때
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,108 @@
+// 마커 클러스터 H3 해상도 선택
+// 클라이언트가 보낸 지도 줌으로 CLUSTER_ZOOM_RESOLUTIONS 표에서 해상도를 고르고,
+// 줌이 없을 때만 지도 영역 크기(lat/lng delta)로 추정
+// 낮은 해상도(넓은 영역)는 마커 행을 가져오지 않고 DB에서 격자로 미리 집계한 값을 H3 셀로 합침
+use log::warn;
+
+use crate::config::Config;
+
+pub const DEFAULT_ZOOM_RESOLUTIONS: &str = "0:4,14:5,15:8,16:9";
+/// 이 해상도 이상이면 클러스터로 묶지 않고 개별 마커로 응답
+pub const INDIVIDUAL_MARKER_RESOLUTION: u8 = 9;
+/// 이 해상도 이하는 DB 격자 집계로 개수만 응답 (마커 목록 없음)
+pub const AGGREGATE_MAX_RESOLUTION: u8 = 5;
+const MAX_ZOOM: i32 = 22;
+const MAX_H3_RESOLUTION: u8 = 15;
+/// H3 해상도 0~8의 평균 변 길이 (km)
+const H3_EDGE_KM: [f64; 9] = [1107.71, 418.68, 158.24, 59.81, 22.61, 8.54, 3.23, 1.22, 0.46];
+const KM_PER_DEGREE: f64 = 111.32;
+
+/// 줌 → H3 해상도 표 ("시작 줌:해상도" 목록, 시작 줌 오름차순)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoomResolutions {
+    entries: Vec<(i32, u8)>,
+}
+
+impl Default for ZoomResolutions {
+    fn default() -> Self {
+        Self::parse(DEFAULT_ZOOM_RESOLUTIONS).expect("기본 줌 해상도 표")
+    }
+}
+
+impl ZoomResolutions {
+    /// "0:4,14:5,15:8,16:9" → 줌 0~13은 4, 14는 5, 15는 8, 16 이상은 9
+    pub fn parse(table: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        for entry in table.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (zoom, resolution) = entry.split_once(':')
+                .ok_or_else(|| format!("줌 해상도 항목 형식 오류: {} (예: 14:5)", entry))?;
+            let zoom: i32 = zoom.trim().parse()
+                .ok()
+                .filter(|zoom| (0..=MAX_ZOOM).contains(zoom))
+                .ok_or_else(|| format!("줌은 0~{} 사이여야 합니다: {}", MAX_ZOOM, entry))?;
+            let resolution: u8 = resolution.trim().parse()
+                .ok()
+                .filter(|resolution| *resolution <= MAX_H3_RESOLUTION)
+                .ok_or_else(|| format!("H3 해상도는 0~{} 사이여야 합니다: {}", MAX_H3_RESOLUTION, entry))?;
+            entries.push((zoom, resolution));
+        }
+        if entries.is_empty() {
+            return Err("줌 해상도 표가 비어 있습니다".to_string());
+        }
+        entries.sort_by_key(|(zoom, _)| *zoom);
+        if entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err("같은 줌이 두 번 들어 있습니다".to_string());
+        }
+        Ok(Self { entries })
+    }
+
+    /// 설정 값이 잘못되면 경고를 남기고 기본 표 사용
+    pub fn from_config(config: &Config) -> Self {
+        Self::parse(&config.cluster_zoom_resolutions).unwrap_or_else(|e| {
+            warn!("⚠️ CLUSTER_ZOOM_RESOLUTIONS 무시 (기본값 사용): {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn is_valid_zoom(zoom: i32) -> bool {
+        (0..=MAX_ZOOM).contains(&zoom)
+    }
+
+    /// 시작 줌이 zoom 이하인 마지막 항목 (가장 작은 시작 줌보다 작으면 첫 항목)
+    pub fn for_zoom(&self, zoom: i32) -> u8 {
+        self.entries.iter()
+            .rev()
+            .find(|(start, _)| *start <= zoom)
+            .unwrap_or(&self.entries[0])
+            .1
+    }
+
+    /// 줌이 있으면 표에서, 없으면 지도 영역 크기로 해상도 선택
+    pub fn resolve(&self, zoom: Option<i32>, lat_delta: f64, lng_delta: f64) -> u8 {
+        match zoom {
+            Some(zoom) => self.for_zoom(zoom),
+            None => resolution_for_deltas(lat_delta, lng_delta),
+        }
+    }
+}
+
+/// 줌을 모르는 예전 클라이언트용: 지도에 보이는 영역 크기로 추정
+pub fn resolution_for_deltas(lat_delta: f64, lng_delta: f64) -> u8 {
+    let delta = lat_delta.max(lng_delta);
+    if delta > 2.0 {
+        3
+    } else if delta > 0.5 {
+        4
+    } else if delta > 0.1 {
+        5
+    } else if delta > 0.03 {
+        8
+    } else {
+        9
+    }
+}
+
+/// DB 집계 격자 한 칸 크기 (도). H3 셀 변의 1/4이라 격자 칸이 셀 경계에 걸쳐 옆 셀로 합쳐져도 오차가 작음
+pub fn aggregate_grid_degrees(resolution: u8) -> f64 {
+    H3_EDGE_KM[usize::from(resolution).min(H3_EDGE_KM.len() - 1)] / KM_PER_DEGREE / 4.0
+}
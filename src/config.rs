@@ -22,23 +22,148 @@ pub struct Config {
     pub map_max_width: u32,
     pub map_max_height: u32,
     pub map_quality: u8,
+    pub avif_quality: u8, // output_format=avif 업로드 품질 (1~100, WebP보다 낮아도 비슷한 화질)
+    pub png_lossless: bool, // PNG 업로드는 무손실 WebP로 변환 (스크린샷/그림, 파일은 커짐)
+    pub image_processing_concurrency: usize, // 블로킹 스레드에서 동시에 처리할 이미지 수 (기본 CPU 코어 수)
+    pub image_processing_timeout_secs: u64, // 이미지 처리(대기 포함) 요청별 제한 시간, 넘으면 503
     
     // File Upload
     pub max_file_size_mb: f64,
     pub upload_dir: String,
     pub file_server_url: String,
+    pub max_images_per_marker: i64,
+    pub max_marker_original_mb: f64,
+    pub max_inflight_uploads: usize, // 동시에 처리할 업로드 수 (넘으면 503)
+    pub upload_min_available_memory_mb: u64, // 가용 메모리가 이보다 적으면 새 업로드 거절
+    pub upload_retry_after_secs: i64,
+    pub upload_spool_threshold_mb: f64, // 업로드 본문이 이보다 크면 임시 파일로 받음
+    pub upload_temp_dir: String,
+    pub upload_session_ttl_hours: i64, // 이어 받는 업로드(tus) 세션을 이어 보낼 수 있는 기간, 지나면 받은 조각 삭제
+    pub min_free_disk_mb: u64, // 로컬 저장 후에도 남아야 하는 디스크 여유 공간
+    pub auth_rate_limit_per_ip: u32, // 윈도우당 IP별 인증 요청 수
+    pub auth_rate_limit_per_account: u32, // 윈도우당 계정(이메일)별 인증 요청 수
+    pub auth_rate_limit_window_secs: i64,
+    pub trusted_proxies: Vec<std::net::IpAddr>, // 이 주소에서 온 요청만 X-Forwarded-For/Forwarded의 클라이언트 IP를 믿음
+    pub login_lockout_max_failures: i64, // 윈도우 안에서 이만큼 연속 실패하면 계정 임시 잠금 (0이면 사용 안 함)
+    pub login_lockout_window_secs: i64,
+    pub totp_issuer: String, // 인증 앱에 표시되는 서비스 이름
     
     // S3
+    pub storage_backend: String, // 업로드 파일 저장소: "s3" 또는 "local" (UPLOAD_DIR 아래, AWS 없이 자체 호스팅)
     pub s3_bucket_name: String,
     pub s3_region: String,
     pub s3_access_key_id: String,
     pub s3_secret_access_key: String,
     // JWT
     pub jwt_secret: String,
+    pub jwt_access_ttl_secs: i64,
+    pub jwt_refresh_ttl_secs: i64,
+    pub jwt_issuer: String, // iss 클레임 (검증 시에도 사용)
+    pub jwt_audience: String, // aud 클레임
     
     // OAuth
     pub google_client_id: String,
     pub google_client_ids: Vec<String>,
+    
+    // Notifications
+    pub memories_notification_hour: u32, // "지난 오늘" 알림 생성 시각 (한국 시간, 0~23)
+    
+    // 앱 첫 화면 지도 위치
+    pub geoip_database_path: String, // GeoLite2-City .mmdb (없으면 기본 위치 사용)
+    pub default_map_latitude: f64,
+    pub default_map_longitude: f64,
+    pub default_map_zoom: u8,
+    
+    // 마커 클러스터
+    pub cluster_zoom_resolutions: String, // "시작 줌:H3 해상도" 목록 (쉼표 구분, 예: 0:4,14:5,15:8,16:9)
+    pub cluster_result_cache_secs: i64, // 같은 화면/조건 클러스터 결과 캐시 유지 시간 (0이면 캐시 안 함)
+    
+    // 공유 카드 이미지
+    pub share_card_font_path: String, // 본문 글꼴 (한글 포함, 없으면 글자 없이 렌더링)
+    pub share_card_emoji_font_path: String, // 단색 이모지 글꼴 (Noto Emoji 등)
+    pub share_card_map_tile_url: String, // {z}/{x}/{y} 지도 타일 URL (비어 있으면 간단한 지도 그림)
+    pub web_app_url: String, // 임베드 카드를 눌렀을 때 여는 웹 앱 주소 ({web_app_url}/markers/{id})
+    
+    // 실시간 시청자 집계
+    pub redis_url: String, // 비어 있으면 인스턴스 메모리에만 기록
+    pub presence_ttl_secs: i64, // 마지막 신호 후 시청 중으로 보는 시간
+    
+    // 회원 감정 프로필
+    pub emotion_profile_cache_secs: i64, // 월별 감정 집계 캐시 유지 시간
+    
+    // 응답 캐시 정책 (Cache-Control, 0이면 헤더를 붙이지 않음)
+    pub stats_cache_max_age_secs: i64, // 공개 통계 (CDN 캐시)
+    pub stats_cache_swr_secs: i64, // 만료 후 이전 응답을 주며 갱신하는 시간 (stale-while-revalidate)
+    pub catalog_cache_max_age_secs: i64, // 감정 태그 목록
+    pub catalog_cache_swr_secs: i64,
+    pub cluster_cache_max_age_secs: i64, // 마커 클러스터 (브라우저에서만 캐시)
+    pub tile_cache_max_age_secs: i64, // 공개 감정 지도 타일 (CDN 캐시)
+    pub tile_cache_swr_secs: i64,
+    
+    // 회원 신뢰 등급 (new → basic → trusted)
+    pub trust_basic_min_days: i64, // basic 승급에 필요한 가입 후 일수
+    pub trust_basic_min_markers: i64, // basic 승급에 필요한 마커 수
+    pub trust_trusted_min_days: i64,
+    pub trust_trusted_min_markers: i64,
+    pub new_member_markers_per_day: i64, // 등급별 최근 24시간 마커 생성 한도
+    pub basic_member_markers_per_day: i64,
+    pub trusted_member_markers_per_day: i64,
+    pub new_member_images_per_marker: i64, // new 등급 마커당 이미지 수 (basic 이상은 MAX_IMAGES_PER_MARKER)
+    
+    // 마커 주소 역지오코딩
+    pub kakao_rest_api_key: String, // 카카오 로컬 API 키 (비어 있으면 주소 백필 작업을 띄우지 않음)
+    pub geocode_requests_per_sec: f64, // 역지오코딩 API 호출 상한
+    pub geocode_batch_size: i64, // 백필 작업이 한 번에 가져오는 마커 수
+    
+    // 마커 작성 위치 추천
+    pub place_category_codes: String, // 근처 장소로 찾을 카카오 카테고리 그룹 코드 (쉼표 구분)
+    pub location_suggest_radius_m: f64, // 근처 마커/장소/인기 지점을 찾는 반경
+    pub location_snap_radius_m: f64, // GPS 정확도를 모를 때 좌표를 옮겨 주는 최대 거리
+    
+    // 외부 SNS 크로스포스팅
+    pub token_encryption_key: String, // 연결 계정 토큰 암호화 키 (비어 있으면 JWT_SECRET에서 유도)
+    
+    // 클라이언트 오류 보고
+    pub client_error_sample_rate: f64, // API 오류 보고를 저장할 비율 (0~1, 크래시는 항상 저장)
+    
+    // 라우트별 사용량 집계
+    pub usage_flush_interval_secs: u64, // 메모리에 모은 사용량을 DB에 반영하는 주기
+    
+    // 푸시 알림
+    pub fcm_service_account_path: String, // Firebase 서비스 계정 JSON (비어 있으면 푸시를 보내지 않음)
+    pub push_max_age_secs: i64, // 이보다 오래 대기한 푸시는 보내지 않음
+    
+    // 외부 검색 색인 (Meilisearch)
+    pub search_backend_url: String, // 비어 있으면 Postgres 전문 검색만 사용
+    pub search_api_key: String,
+    pub search_index_name: String,
+    
+    // 예약 게시 마커
+    pub scheduled_publish_interval_secs: u64, // publish_at이 지난 예약 마커를 확인하는 주기
+    
+    // 마커 조회수
+    pub view_dedupe_window_hours: i64, // 같은 조회자의 같은 마커 재조회를 세지 않는 기간
+    pub view_flush_interval_secs: u64, // 메모리에 모은 조회를 DB에 반영하는 주기
+    
+    // 멱등성 키 (마커 생성/업로드 재시도)
+    pub idempotency_key_ttl_hours: i64, // 같은 Idempotency-Key 재요청에 처음 응답을 돌려주는 기간
+    
+    // 고아 파일 정리 (DB에서 참조하지 않는 저장소 이미지, 파일이 없는 마커 이미지)
+    pub orphan_gc_interval_hours: u64, // 정리 주기, 0이면 주기 작업 없이 관리자 점검(dry-run)만
+    pub orphan_gc_min_age_hours: i64, // 이보다 최근 파일은 업로드 중일 수 있어 건드리지 않음
+    
+    // 업로드 이미지 검수
+    pub moderation_backend: String, // "none"(검수 안 함), "rekognition"(AWS Rekognition), "onnx"(로컬 ONNX 분류기 서버)
+    pub moderation_region: String, // Rekognition 리전 (기본은 S3_REGION)
+    pub moderation_min_confidence: f64, // Rekognition 검수 라벨 최소 신뢰도 (0~100), 라벨이 하나라도 나오면 거절
+    pub moderation_onnx_url: String, // ONNX 분류기 서버 주소 (JPEG를 POST하면 라벨별 점수 반환)
+    pub moderation_reject_score: f64, // ONNX 분류기 점수가 이 이상인 라벨이 있으면 거절 (0~1)
+    
+    // 워터마크 (원본 다운로드, 공유 카드)
+    pub watermark_enabled: bool, // 켜면 요청에 ?watermark=false가 없는 한 워터마크를 찍음
+    pub watermark_text: String, // 문구 워터마크 (글꼴은 SHARE_CARD_FONT_PATH)
+    pub watermark_image_path: String, // PNG 워터마크 (있으면 문구 대신 사용)
+    pub watermark_opacity: f32, // 0~1
 }
 
 impl Config {
@@ -78,8 +203,9 @@ impl Config {
                 .unwrap_or(800),
             thumbnail_quality: env::var("THUMBNAIL_QUALITY")
                 .unwrap_or_else(|_| "80".to_string())
-                .parse()
-                .unwrap_or(80),
+                .parse::<u8>()
+                .unwrap_or(80)
+                .clamp(1, 100),
             map_max_width: env::var("MAP_MAX_WIDTH")
                 .unwrap_or_else(|_| "800".to_string())
                 .parse()
@@ -90,8 +216,27 @@ impl Config {
                 .unwrap_or(600),
             map_quality: env::var("MAP_QUALITY")
                 .unwrap_or_else(|_| "85".to_string())
+                .parse::<u8>()
+                .unwrap_or(85)
+                .clamp(1, 100),
+            avif_quality: env::var("AVIF_QUALITY")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse::<u8>()
+                .unwrap_or(60)
+                .clamp(1, 100),
+            png_lossless: env::var("PNG_LOSSLESS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            image_processing_concurrency: env::var("IMAGE_PROCESSING_CONCURRENCY")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .filter(|concurrency: &usize| *concurrency > 0)
+                .unwrap_or_else(crate::image_workers::default_concurrency),
+            image_processing_timeout_secs: env::var("IMAGE_PROCESSING_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "60".to_string())
                 .parse()
-                .unwrap_or(85),
+                .unwrap_or(60),
             
             // File Upload
             max_file_size_mb: env::var("MAX_FILE_SIZE_MB")
@@ -100,14 +245,85 @@ impl Config {
                 .unwrap_or(30.0),
             upload_dir: env::var("UPLOAD_DIR").unwrap_or_else(|_| "/uploads".to_string()),
             file_server_url: env::var("FILE_SERVER_URL").unwrap_or_else(|_| "http://localhost:5500".to_string()),
+            max_images_per_marker: env::var("MAX_IMAGES_PER_MARKER")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            max_marker_original_mb: env::var("MAX_MARKER_ORIGINAL_MB")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .unwrap_or(100.0),
+            max_inflight_uploads: env::var("MAX_INFLIGHT_UPLOADS")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()
+                .unwrap_or(8),
+            upload_min_available_memory_mb: env::var("UPLOAD_MIN_AVAILABLE_MEMORY_MB")
+                .unwrap_or_else(|_| "256".to_string())
+                .parse()
+                .unwrap_or(256),
+            upload_retry_after_secs: env::var("UPLOAD_RETRY_AFTER_SECS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            upload_spool_threshold_mb: env::var("UPLOAD_SPOOL_THRESHOLD_MB")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse()
+                .unwrap_or(4.0),
+            upload_temp_dir: env::var("UPLOAD_TEMP_DIR")
+                .unwrap_or_else(|_| std::env::temp_dir().join("bigpicture-uploads").to_string_lossy().to_string()),
+            upload_session_ttl_hours: env::var("UPLOAD_SESSION_TTL_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .unwrap_or(24),
+            min_free_disk_mb: env::var("MIN_FREE_DISK_MB")
+                .unwrap_or_else(|_| "512".to_string())
+                .parse()
+                .unwrap_or(512),
+            auth_rate_limit_per_ip: env::var("AUTH_RATE_LIMIT_PER_IP")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .unwrap_or(20),
+            auth_rate_limit_per_account: env::var("AUTH_RATE_LIMIT_PER_ACCOUNT")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            auth_rate_limit_window_secs: env::var("AUTH_RATE_LIMIT_WINDOW_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            trusted_proxies: env::var("TRUSTED_PROXIES")
+                .unwrap_or_else(|_| "".to_string())
+                .split(',')
+                .filter_map(|s| s.trim().parse().ok())
+                .collect(),
+            login_lockout_max_failures: env::var("LOGIN_LOCKOUT_MAX_FAILURES")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            login_lockout_window_secs: env::var("LOGIN_LOCKOUT_WINDOW_SECS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .unwrap_or(900),
+            totp_issuer: env::var("TOTP_ISSUER").unwrap_or_else(|_| "BigPicture".to_string()),
             
             // S3
+            storage_backend: env::var("STORAGE_BACKEND").unwrap_or_else(|_| "s3".to_string()).to_lowercase(),
             s3_bucket_name: env::var("S3_BUCKET_NAME").unwrap_or_else(|_| "bigpicture-uploads".to_string()),
             s3_region: env::var("S3_REGION").unwrap_or_else(|_| "ap-northeast-2".to_string()),
             s3_access_key_id: env::var("AWS_ACCESS_KEY_ID").unwrap_or_else(|_| "".to_string()),
             s3_secret_access_key: env::var("AWS_SECRET_ACCESS_KEY").unwrap_or_else(|_| "".to_string()),
             // JWT
             jwt_secret: env::var("JWT_SECRET").unwrap_or_else(|_| "changemechangemechangeme".to_string()),
+            jwt_access_ttl_secs: env::var("JWT_ACCESS_TTL_SECS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .unwrap_or(86400), // 24시간
+            jwt_refresh_ttl_secs: env::var("JWT_REFRESH_TTL_SECS")
+                .unwrap_or_else(|_| "2592000".to_string())
+                .parse()
+                .unwrap_or(2592000), // 30일
+            jwt_issuer: env::var("JWT_ISSUER").unwrap_or_else(|_| "bigpicture-backend".to_string()),
+            jwt_audience: env::var("JWT_AUDIENCE").unwrap_or_else(|_| "bigpicture-app".to_string()),
             
             // OAuth
             google_client_id: env::var("GOOGLE_CLIENT_ID").unwrap_or_else(|_| "your-google-client-id".to_string()),
@@ -117,6 +333,224 @@ impl Config {
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty())
                 .collect(),
+            
+            // Notifications
+            memories_notification_hour: env::var("MEMORIES_NOTIFICATION_HOUR")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()
+                .ok()
+                .filter(|hour| *hour < 24)
+                .unwrap_or(8),
+            
+            // 앱 첫 화면 지도 위치 (기본: 서울시청)
+            geoip_database_path: env::var("GEOIP_DATABASE_PATH").unwrap_or_else(|_| "data/GeoLite2-City.mmdb".to_string()),
+            default_map_latitude: env::var("DEFAULT_MAP_LATITUDE")
+                .unwrap_or_else(|_| "37.5665".to_string())
+                .parse()
+                .unwrap_or(37.5665),
+            default_map_longitude: env::var("DEFAULT_MAP_LONGITUDE")
+                .unwrap_or_else(|_| "126.9780".to_string())
+                .parse()
+                .unwrap_or(126.9780),
+            default_map_zoom: env::var("DEFAULT_MAP_ZOOM")
+                .unwrap_or_else(|_| "11".to_string())
+                .parse()
+                .unwrap_or(11),
+            
+            cluster_zoom_resolutions: env::var("CLUSTER_ZOOM_RESOLUTIONS")
+                .unwrap_or_else(|_| crate::cluster_zoom::DEFAULT_ZOOM_RESOLUTIONS.to_string()),
+            cluster_result_cache_secs: env::var("CLUSTER_RESULT_CACHE_SECS")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .unwrap_or(15),
+            
+            share_card_font_path: env::var("SHARE_CARD_FONT_PATH").unwrap_or_else(|_| "fonts/NotoSansKR-Regular.ttf".to_string()),
+            share_card_emoji_font_path: env::var("SHARE_CARD_EMOJI_FONT_PATH").unwrap_or_else(|_| "fonts/NotoEmoji-Regular.ttf".to_string()),
+            share_card_map_tile_url: env::var("SHARE_CARD_MAP_TILE_URL").unwrap_or_default(),
+            web_app_url: env::var("WEB_APP_URL").unwrap_or_else(|_| "http://localhost:3000".to_string()),
+            
+            redis_url: env::var("REDIS_URL").unwrap_or_default(),
+            presence_ttl_secs: env::var("PRESENCE_TTL_SECS")
+                .unwrap_or_else(|_| "45".to_string())
+                .parse()
+                .unwrap_or(45),
+            
+            emotion_profile_cache_secs: env::var("EMOTION_PROFILE_CACHE_SECS")
+                .unwrap_or_else(|_| "600".to_string())
+                .parse()
+                .unwrap_or(600),
+            
+            stats_cache_max_age_secs: env::var("STATS_CACHE_MAX_AGE_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .unwrap_or(300),
+            stats_cache_swr_secs: env::var("STATS_CACHE_SWR_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            catalog_cache_max_age_secs: env::var("CATALOG_CACHE_MAX_AGE_SECS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .unwrap_or(86400),
+            catalog_cache_swr_secs: env::var("CATALOG_CACHE_SWR_SECS")
+                .unwrap_or_else(|_| "604800".to_string())
+                .parse()
+                .unwrap_or(604800),
+            cluster_cache_max_age_secs: env::var("CLUSTER_CACHE_MAX_AGE_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            tile_cache_max_age_secs: env::var("TILE_CACHE_MAX_AGE_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            tile_cache_swr_secs: env::var("TILE_CACHE_SWR_SECS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .unwrap_or(86400),
+            
+            trust_basic_min_days: env::var("TRUST_BASIC_MIN_DAYS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            trust_basic_min_markers: env::var("TRUST_BASIC_MIN_MARKERS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            trust_trusted_min_days: env::var("TRUST_TRUSTED_MIN_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            trust_trusted_min_markers: env::var("TRUST_TRUSTED_MIN_MARKERS")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .unwrap_or(20),
+            new_member_markers_per_day: env::var("NEW_MEMBER_MARKERS_PER_DAY")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            basic_member_markers_per_day: env::var("BASIC_MEMBER_MARKERS_PER_DAY")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            trusted_member_markers_per_day: env::var("TRUSTED_MEMBER_MARKERS_PER_DAY")
+                .unwrap_or_else(|_| "200".to_string())
+                .parse()
+                .unwrap_or(200),
+            new_member_images_per_marker: env::var("NEW_MEMBER_IMAGES_PER_MARKER")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            
+            kakao_rest_api_key: env::var("KAKAO_REST_API_KEY").unwrap_or_default(),
+            geocode_requests_per_sec: env::var("GEOCODE_REQUESTS_PER_SEC")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .ok()
+                .filter(|rate: &f64| *rate > 0.0)
+                .unwrap_or(5.0),
+            geocode_batch_size: env::var("GEOCODE_BATCH_SIZE")
+                .unwrap_or_else(|_| "200".to_string())
+                .parse()
+                .unwrap_or(200),
+            
+            place_category_codes: env::var("PLACE_CATEGORY_CODES")
+                .unwrap_or_else(|_| "AT4,CT1,CE7,FD6".to_string()),
+            location_suggest_radius_m: env::var("LOCATION_SUGGEST_RADIUS_M")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .ok()
+                .filter(|radius: &f64| *radius > 0.0)
+                .unwrap_or(300.0),
+            location_snap_radius_m: env::var("LOCATION_SNAP_RADIUS_M")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .ok()
+                .filter(|radius: &f64| *radius >= 0.0)
+                .unwrap_or(30.0),
+            
+            token_encryption_key: env::var("TOKEN_ENCRYPTION_KEY").unwrap_or_default(),
+            
+            client_error_sample_rate: env::var("CLIENT_ERROR_SAMPLE_RATE")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .ok()
+                .filter(|rate: &f64| (0.0..=1.0).contains(rate))
+                .unwrap_or(1.0),
+            
+            usage_flush_interval_secs: env::var("USAGE_FLUSH_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            
+            fcm_service_account_path: env::var("FCM_SERVICE_ACCOUNT_PATH").unwrap_or_default(),
+            push_max_age_secs: env::var("PUSH_MAX_AGE_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            
+            search_backend_url: env::var("SEARCH_BACKEND_URL").unwrap_or_default(),
+            search_api_key: env::var("SEARCH_API_KEY").unwrap_or_default(),
+            search_index_name: env::var("SEARCH_INDEX_NAME").unwrap_or_else(|_| "markers".to_string()),
+            
+            scheduled_publish_interval_secs: env::var("SCHEDULED_PUBLISH_INTERVAL_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            
+            view_dedupe_window_hours: env::var("VIEW_DEDUPE_WINDOW_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .unwrap_or(24),
+            view_flush_interval_secs: env::var("VIEW_FLUSH_INTERVAL_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            
+            idempotency_key_ttl_hours: env::var("IDEMPOTENCY_KEY_TTL_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .unwrap_or(24),
+            orphan_gc_interval_hours: env::var("ORPHAN_GC_INTERVAL_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .unwrap_or(24),
+            orphan_gc_min_age_hours: env::var("ORPHAN_GC_MIN_AGE_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse::<i64>()
+                .unwrap_or(24)
+                .max(1),
+            
+            moderation_backend: env::var("MODERATION_BACKEND").unwrap_or_else(|_| "none".to_string()).to_lowercase(),
+            moderation_region: env::var("REKOGNITION_REGION")
+                .or_else(|_| env::var("S3_REGION"))
+                .unwrap_or_else(|_| "ap-northeast-2".to_string()),
+            moderation_min_confidence: env::var("MODERATION_MIN_CONFIDENCE")
+                .unwrap_or_else(|_| "80".to_string())
+                .parse()
+                .ok()
+                .filter(|confidence: &f64| (0.0..=100.0).contains(confidence))
+                .unwrap_or(80.0),
+            moderation_onnx_url: env::var("MODERATION_ONNX_URL").unwrap_or_default(),
+            moderation_reject_score: env::var("MODERATION_REJECT_SCORE")
+                .unwrap_or_else(|_| "0.8".to_string())
+                .parse()
+                .ok()
+                .filter(|score: &f64| (0.0..=1.0).contains(score))
+                .unwrap_or(0.8),
+            
+            watermark_enabled: env::var("WATERMARK_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            watermark_text: env::var("WATERMARK_TEXT").unwrap_or_default(),
+            watermark_image_path: env::var("WATERMARK_IMAGE_PATH").unwrap_or_default(),
+            watermark_opacity: env::var("WATERMARK_OPACITY")
+                .unwrap_or_else(|_| "0.5".to_string())
+                .parse()
+                .ok()
+                .filter(|opacity: &f32| (0.0..=1.0).contains(opacity))
+                .unwrap_or(0.5),
         })
     }
     
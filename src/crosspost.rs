@@ -0,0 +1,325 @@
+// 마커를 회원이 연결한 외부 SNS(인스타그램, 카카오스토리)에 올리기
+// 연결 계정 토큰은 AES-256-GCM으로 암호화해 DB에 저장하고, 게시할 때만 복호화함
+use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures_util::future::BoxFuture;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::digest::{digest, SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::Config;
+
+pub const PLATFORM_INSTAGRAM: &str = "instagram";
+pub const PLATFORM_KAKAOSTORY: &str = "kakaostory";
+/// 인스타그램 캡션 최대 길이
+const MAX_CAPTION_CHARS: usize = 2200;
+const REQUEST_TIMEOUT_SECS: u64 = 20;
+/// 암호문 형식 버전 (키/알고리즘을 바꾸면 올림)
+const CIPHER_PREFIX: &str = "v1:";
+
+/// 연결 계정 토큰 암호화 (저장 형식: "v1:" + base64(nonce || 암호문+태그))
+#[derive(Clone)]
+pub struct TokenCipher {
+    key: Arc<LessSafeKey>,
+}
+
+impl TokenCipher {
+    /// 설정 문자열의 SHA-256을 키로 사용
+    pub fn new(secret: &str) -> Self {
+        let key_bytes = digest(&SHA256, secret.as_bytes());
+        let key = UnboundKey::new(&AES_256_GCM, key_bytes.as_ref()).expect("AES-256 키 길이");
+        Self { key: Arc::new(LessSafeKey::new(key)) }
+    }
+
+    /// TOKEN_ENCRYPTION_KEY, 없으면 JWT_SECRET에서 유도
+    pub fn from_config(config: &Config) -> Self {
+        match config.token_encryption_key.as_str() {
+            "" => Self::new(&format!("connected-accounts:{}", config.jwt_secret)),
+            key => Self::new(key),
+        }
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce_bytes).map_err(|_| anyhow!("난수 생성 실패"))?;
+        let mut sealed = plaintext.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce_bytes), Aad::empty(), &mut sealed)
+            .map_err(|_| anyhow!("토큰 암호화 실패"))?;
+        let mut out = nonce_bytes.to_vec();
+        out.extend_from_slice(&sealed);
+        Ok(format!("{}{}", CIPHER_PREFIX, BASE64.encode(out)))
+    }
+
+    pub fn decrypt(&self, encrypted: &str) -> Result<String> {
+        let encoded = encrypted.strip_prefix(CIPHER_PREFIX).ok_or_else(|| anyhow!("알 수 없는 토큰 암호문 형식"))?;
+        let mut data = BASE64.decode(encoded)?;
+        if data.len() < NONCE_LEN {
+            bail!("토큰 암호문이 너무 짧습니다");
+        }
+        let mut sealed = data.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&data).map_err(|_| anyhow!("잘못된 nonce"))?;
+        let plaintext = self.key
+            .open_in_place(nonce, Aad::empty(), &mut sealed)
+            .map_err(|_| anyhow!("토큰 복호화 실패 (키가 바뀌었거나 값이 손상됨)"))?;
+        Ok(String::from_utf8(plaintext.to_vec())?)
+    }
+}
+
+/// 토큰으로 확인한 외부 계정
+pub struct RemoteAccount {
+    pub id: String,
+    pub username: Option<String>,
+}
+
+/// 올릴 내용 (image_url은 제공자가 직접 가져갈 공개 주소, image는 업로드용 원본)
+pub struct CrossPost {
+    pub image_url: String,
+    pub image: Vec<u8>,
+    pub caption: String,
+}
+
+pub struct PublishedPost {
+    pub remote_post_id: String,
+    pub remote_url: Option<String>,
+}
+
+/// 외부 SNS 제공자
+pub trait CrossPostProvider: Send + Sync {
+    fn platform(&self) -> &'static str;
+    /// 연결 시 토큰이 유효한지 확인하고 계정 식별자 반환
+    fn verify_account<'a>(&'a self, access_token: &'a str) -> BoxFuture<'a, Result<RemoteAccount>>;
+    fn publish<'a>(&'a self, access_token: &'a str, remote_user_id: &'a str, post: &'a CrossPost) -> BoxFuture<'a, Result<PublishedPost>>;
+}
+
+/// 사용 가능한 제공자 목록 (테스트에서는 가짜 제공자로 교체)
+#[derive(Clone)]
+pub struct CrossPostProviders {
+    providers: Vec<Arc<dyn CrossPostProvider>>,
+}
+
+impl CrossPostProviders {
+    pub fn new(providers: Vec<Arc<dyn CrossPostProvider>>) -> Self {
+        Self { providers }
+    }
+
+    pub fn get(&self, platform: &str) -> Option<&Arc<dyn CrossPostProvider>> {
+        self.providers.iter().find(|provider| provider.platform() == platform)
+    }
+
+    pub fn platforms(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|provider| provider.platform()).collect()
+    }
+}
+
+impl Default for CrossPostProviders {
+    fn default() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        Self::new(vec![
+            Arc::new(InstagramProvider { client: client.clone() }),
+            Arc::new(KakaoStoryProvider { client }),
+        ])
+    }
+}
+
+/// 설명 + 감성 태그 해시태그 (직접 쓴 캡션이 있으면 그대로), 인스타그램 길이 제한에 맞춰 자름
+pub fn build_caption(custom: Option<&str>, description: Option<&str>, emotion_tag_input: Option<&str>) -> String {
+    let caption = match custom.map(str::trim).filter(|caption| !caption.is_empty()) {
+        Some(caption) => caption.to_string(),
+        None => {
+            let hashtags = emotion_tag_input.unwrap_or_default()
+                .split(',')
+                .map(|tag| tag.trim().replace(char::is_whitespace, ""))
+                .filter(|tag| !tag.is_empty())
+                .map(|tag| format!("#{}", tag.trim_start_matches('#')))
+                .collect::<Vec<_>>()
+                .join(" ");
+            [description.unwrap_or_default().trim(), hashtags.as_str()]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        }
+    };
+    caption.chars().take(MAX_CAPTION_CHARS).collect()
+}
+
+async fn response_json<T: for<'de> Deserialize<'de>>(response: reqwest::Response, platform: &str) -> Result<T> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("{} 응답 {}: {}", platform, status, body.chars().take(300).collect::<String>());
+    }
+    Ok(response.json().await?)
+}
+
+/// 인스타그램 그래프 API (비즈니스/크리에이터 계정, 이미지 URL로 컨테이너 생성 후 게시)
+pub struct InstagramProvider {
+    client: reqwest::Client,
+}
+
+const INSTAGRAM_GRAPH_URL: &str = "https://graph.instagram.com/v21.0";
+
+#[derive(Deserialize)]
+struct InstagramAccount {
+    user_id: String,
+    username: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct InstagramId {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct InstagramPermalink {
+    permalink: Option<String>,
+}
+
+impl CrossPostProvider for InstagramProvider {
+    fn platform(&self) -> &'static str {
+        PLATFORM_INSTAGRAM
+    }
+
+    fn verify_account<'a>(&'a self, access_token: &'a str) -> BoxFuture<'a, Result<RemoteAccount>> {
+        Box::pin(async move {
+            let response = self.client
+                .get(format!("{}/me", INSTAGRAM_GRAPH_URL))
+                .query(&[("fields", "user_id,username"), ("access_token", access_token)])
+                .send()
+                .await?;
+            let account: InstagramAccount = response_json(response, PLATFORM_INSTAGRAM).await?;
+            Ok(RemoteAccount { id: account.user_id, username: account.username })
+        })
+    }
+
+    fn publish<'a>(&'a self, access_token: &'a str, remote_user_id: &'a str, post: &'a CrossPost) -> BoxFuture<'a, Result<PublishedPost>> {
+        Box::pin(async move {
+            let response = self.client
+                .post(format!("{}/{}/media", INSTAGRAM_GRAPH_URL, remote_user_id))
+                .form(&[("image_url", post.image_url.as_str()), ("caption", post.caption.as_str()), ("access_token", access_token)])
+                .send()
+                .await?;
+            let container: InstagramId = response_json(response, PLATFORM_INSTAGRAM).await?;
+
+            let response = self.client
+                .post(format!("{}/{}/media_publish", INSTAGRAM_GRAPH_URL, remote_user_id))
+                .form(&[("creation_id", container.id.as_str()), ("access_token", access_token)])
+                .send()
+                .await?;
+            let media: InstagramId = response_json(response, PLATFORM_INSTAGRAM).await?;
+
+            // 게시물 주소는 부가 정보라 실패해도 게시는 성공으로 처리
+            let remote_url = match self.client
+                .get(format!("{}/{}", INSTAGRAM_GRAPH_URL, media.id))
+                .query(&[("fields", "permalink"), ("access_token", access_token)])
+                .send()
+                .await
+            {
+                Ok(response) => response_json::<InstagramPermalink>(response, PLATFORM_INSTAGRAM).await.ok().and_then(|p| p.permalink),
+                Err(_) => None,
+            };
+            Ok(PublishedPost { remote_post_id: media.id, remote_url })
+        })
+    }
+}
+
+/// 카카오스토리 API (사진을 먼저 올리고 받은 경로로 글 작성)
+pub struct KakaoStoryProvider {
+    client: reqwest::Client,
+}
+
+const KAKAO_API_URL: &str = "https://kapi.kakao.com";
+
+#[derive(Deserialize)]
+struct KakaoUser {
+    id: i64,
+    properties: Option<KakaoProperties>,
+}
+
+#[derive(Deserialize)]
+struct KakaoProperties {
+    nickname: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KakaoStoryUser {
+    #[serde(rename = "isStoryUser")]
+    is_story_user: bool,
+}
+
+#[derive(Deserialize)]
+struct KakaoStoryPost {
+    id: String,
+}
+
+/// reqwest multipart 기능 없이 사진 한 장을 담는 multipart/form-data 본문
+fn multipart_image_body(boundary: &str, image: &[u8]) -> Vec<u8> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"marker.jpg\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+        boundary
+    ).into_bytes();
+    body.extend_from_slice(image);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+impl CrossPostProvider for KakaoStoryProvider {
+    fn platform(&self) -> &'static str {
+        PLATFORM_KAKAOSTORY
+    }
+
+    fn verify_account<'a>(&'a self, access_token: &'a str) -> BoxFuture<'a, Result<RemoteAccount>> {
+        Box::pin(async move {
+            let response = self.client
+                .get(format!("{}/v1/api/story/isstoryuser", KAKAO_API_URL))
+                .bearer_auth(access_token)
+                .send()
+                .await?;
+            let story_user: KakaoStoryUser = response_json(response, PLATFORM_KAKAOSTORY).await?;
+            if !story_user.is_story_user {
+                bail!("카카오스토리 사용자가 아닙니다");
+            }
+            let response = self.client
+                .get(format!("{}/v2/user/me", KAKAO_API_URL))
+                .bearer_auth(access_token)
+                .send()
+                .await?;
+            let user: KakaoUser = response_json(response, PLATFORM_KAKAOSTORY).await?;
+            Ok(RemoteAccount { id: user.id.to_string(), username: user.properties.and_then(|p| p.nickname) })
+        })
+    }
+
+    fn publish<'a>(&'a self, access_token: &'a str, _remote_user_id: &'a str, post: &'a CrossPost) -> BoxFuture<'a, Result<PublishedPost>> {
+        Box::pin(async move {
+            let boundary = format!("bigpicture-{}", uuid::Uuid::new_v4().simple());
+            let response = self.client
+                .post(format!("{}/v1/api/story/upload/multi", KAKAO_API_URL))
+                .bearer_auth(access_token)
+                .header(reqwest::header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", boundary))
+                .body(multipart_image_body(&boundary, &post.image))
+                .send()
+                .await?;
+            let image_paths: Vec<String> = response_json(response, PLATFORM_KAKAOSTORY).await?;
+
+            let response = self.client
+                .post(format!("{}/v1/api/story/post/photo", KAKAO_API_URL))
+                .bearer_auth(access_token)
+                .form(&[
+                    ("image_url_list", serde_json::to_string(&image_paths)?.as_str()),
+                    ("content", post.caption.as_str()),
+                    ("permission", "A"),
+                ])
+                .send()
+                .await?;
+            let story: KakaoStoryPost = response_json(response, PLATFORM_KAKAOSTORY).await?;
+            Ok(PublishedPost { remote_post_id: story.id, remote_url: None })
+        })
+    }
+}
@@ -1,15 +1,62 @@
-use sqlx::{PgPool, Row};
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
 use sqlx::postgres::PgPoolOptions;
 use anyhow::Result;
 use crate::config::Config;
+use crate::route_usage::{UsageCounts, UsageKey};
+use crate::marker_views::PendingView;
+use crate::emotion_tiles::{self, TileCoord};
+use crate::cluster_cache::ClusterFilters;
+use crate::dto::MarkerImageDto;
+use crate::cluster_zoom;
 use log::{info, warn, error};
 use h3ron::H3Cell;
 use h3ron::Index;
 use geo_types::Point;
 use rayon::prelude::*;
 
+/// 클러스터 DB 집계 격자 한 칸
+#[derive(Debug, sqlx::FromRow)]
+pub struct MarkerGridCell {
+    pub latitude: f64, // 칸 안 마커 평균 좌표
+    pub longitude: f64,
+    pub marker_count: i64,
+}
+
+/// 감정 히트맵 격자점 한 칸 (좌표는 칸 안 마커가 아닌 격자점)
+#[derive(Debug, sqlx::FromRow)]
+pub struct HeatmapCell {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub marker_count: i64,
+}
+
+/// 격자 칸을 평균 좌표가 속한 H3 셀로 합쳐 클러스터 응답으로 (마커 목록 없이 개수와 가중 평균 중심)
+fn merge_grid_into_h3_clusters(cells: &[MarkerGridCell], precision: u8) -> Vec<serde_json::Value> {
+    let mut clusters: std::collections::HashMap<u64, (i64, f64, f64)> = std::collections::HashMap::new();
+    for cell in cells {
+        let Ok(h3) = H3Cell::from_point(Point::new(cell.longitude, cell.latitude), precision) else {
+            continue;
+        };
+        let entry = clusters.entry(h3.h3index()).or_default();
+        entry.0 += cell.marker_count;
+        entry.1 += cell.latitude * cell.marker_count as f64;
+        entry.2 += cell.longitude * cell.marker_count as f64;
+    }
+    let mut clusters: Vec<(u64, (i64, f64, f64))> = clusters.into_iter().collect();
+    clusters.sort_by(|a, b| b.1.0.cmp(&a.1.0).then(a.0.cmp(&b.0)));
+    clusters.into_iter()
+        .map(|(h3idx, (count, sum_lat, sum_lng))| serde_json::json!({
+            "h3_index": format!("{:x}", h3idx),
+            "lat": sum_lat / count as f64,
+            "lng": sum_lng / count as f64,
+            "count": count,
+            "aggregated": true
+        }))
+        .collect()
+}
+
 struct MarkerClusterInfo {
-    id: i32,
+    id: i64,
     member_id: i64,
     latitude: f64,
     longitude: f64,
@@ -27,6 +74,10 @@ struct MarkerClusterInfo {
     updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// 스키마 마이그레이션 (빌드 시 migrations/ 폴더를 바이너리에 포함)
+/// 이미 배포된 마이그레이션 파일은 수정하지 말고 새 버전 파일을 추가
+static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
+
 #[derive(Clone)]
 pub struct Database {
     pub pool: PgPool,
@@ -34,537 +85,38 @@ pub struct Database {
 
 impl Database {
     pub async fn new(config: &Config) -> Result<Self> {
-        let database_url = config.database_url();
-        
+        Self::connect(&config.database_url()).await
+    }
+    
+    /// URL로 연결 후 마이그레이션 적용 (테스트용 임시 DB에도 사용)
+    pub async fn connect(database_url: &str) -> Result<Self> {
         let pool = PgPoolOptions::new()
             .max_connections(5)
-            .connect(&database_url)
+            .connect(database_url)
             .await?;
         
-        // 데이터베이스 초기화
-        Self::init_database(&pool).await?;
+        // 스키마 마이그레이션
+        Self::run_migrations(&pool).await?;
         
         Ok(Self { pool })
     }
     
-    async fn init_database(pool: &PgPool) -> Result<()> {
-        println!("🔧 데이터베이스 초기화 시작...");
-        
-        // PostGIS 확장 활성화
-        println!("🗺️ PostGIS 확장 활성화 중...");
-        sqlx::query("CREATE EXTENSION IF NOT EXISTS postgis")
-            .execute(pool)
-            .await?;
-        println!("✅ PostGIS 확장 활성화 완료");
-        
-        // bigpicture 스키마 생성
-        println!("📁 bigpicture 스키마 생성 중...");
-        sqlx::query("CREATE SCHEMA IF NOT EXISTS bigpicture")
-            .execute(pool)
-            .await?;
-        println!("✅ bigpicture 스키마 생성 완료");
-        
-        // 기존 테이블 삭제 (새로운 구조로 변경)
-        println!("🗑️ 기존 테이블 삭제 중...");
-        sqlx::query("DROP TABLE IF EXISTS bigpicture.images CASCADE")
-            .execute(pool)
-            .await?;
-        println!("✅ 기존 테이블 삭제 완료");
-        
-        // 원본 이미지 테이블 생성
-        println!("📋 original_images 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.original_images (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                filename VARCHAR(255) NOT NULL UNIQUE,
-                original_filename VARCHAR(255) NOT NULL,
-                file_path VARCHAR(500) NOT NULL,
-                file_size_mb DECIMAL(10, 6) NOT NULL,
-                width INTEGER,
-                height INTEGER,
-                format VARCHAR(50) NOT NULL,
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ original_images 테이블 생성 완료");
-        
-        // WebP 변환 이미지 테이블 생성
-        println!("📋 webp_images 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.webp_images (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                original_id UUID NOT NULL REFERENCES bigpicture.original_images(id) ON DELETE CASCADE,
-                filename VARCHAR(255) NOT NULL UNIQUE,
-                file_path VARCHAR(500) NOT NULL,
-                file_size_mb DECIMAL(10, 6) NOT NULL,
-                width INTEGER,
-                height INTEGER,
-                image_type VARCHAR(50) NOT NULL, -- thumbnail, map
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ webp_images 테이블 생성 완료");
-        
-        // 인덱스 생성
-        println!("🔍 인덱스 생성 중...");
-        
-        // original_images 인덱스
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_original_images_filename ON bigpicture.original_images(filename)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_original_images_created_at ON bigpicture.original_images(created_at)")
-            .execute(pool)
-            .await?;
-        
-        // webp_images 인덱스
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_webp_images_filename ON bigpicture.webp_images(filename)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_webp_images_original_id ON bigpicture.webp_images(original_id)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_webp_images_image_type ON bigpicture.webp_images(image_type)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_webp_images_created_at ON bigpicture.webp_images(created_at)")
-            .execute(pool)
-            .await?;
-        
-        // members 테이블 생성 (먼저 생성)
-        println!("📋 members 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.members (
-                id BIGSERIAL PRIMARY KEY,
-                email VARCHAR(255) NOT NULL UNIQUE,
-                nickname VARCHAR(100) NOT NULL,
-                profile_image_url VARCHAR(500),
-                region VARCHAR(100),
-                gender VARCHAR(20),
-                age INTEGER,
-                personality_type VARCHAR(50),
-                is_active BOOLEAN DEFAULT true,
-                email_verified BOOLEAN DEFAULT false,
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                last_login_at TIMESTAMP WITH TIME ZONE
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ members 테이블 생성 완료");
-        
-        // markers 테이블 생성
-        println!("📋 markers 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.markers (
-                id SERIAL PRIMARY KEY,
-                member_id BIGINT REFERENCES bigpicture.members(id) ON DELETE CASCADE,
-                location GEOGRAPHY(POINT, 4326),
-                emotion_tag TEXT,
-                emotion TEXT,
-                description TEXT,
-                sharing_option VARCHAR(20) DEFAULT 'public' CHECK (sharing_option IN ('public', 'friends', 'private')),
-                likes INTEGER DEFAULT 0,
-                dislikes INTEGER DEFAULT 0,
-                views INTEGER DEFAULT 0,
-                author TEXT,
-                thumbnail_img TEXT, -- 기존 썸네일 필드 유지
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ markers 테이블 생성 완료");
-        
-                        // 기존 markers 테이블에 sharing_option 컬럼 추가 (마이그레이션)
-                println!("🔄 markers 테이블 마이그레이션 중...");
-                sqlx::query(
-                    r#"
-                    ALTER TABLE bigpicture.markers 
-                    ADD COLUMN IF NOT EXISTS sharing_option VARCHAR(20) DEFAULT 'public' CHECK (sharing_option IN ('public', 'friends', 'private'))
-                    "#
-                )
-                .execute(pool)
-                .await?;
-                println!("✅ markers 테이블 sharing_option 마이그레이션 완료");
-
-                // 기존 markers 테이블에 emotion 컬럼 추가 (마이그레이션)
-                sqlx::query(
-                    r#"
-                    ALTER TABLE bigpicture.markers 
-                    ADD COLUMN IF NOT EXISTS emotion TEXT
-                    "#
-                )
-                .execute(pool)
-                .await?;
-                println!("✅ markers 테이블 emotion 마이그레이션 완료");
-
-                // 기존 markers 테이블에 emotion_tag_input 컬럼 추가 (마이그레이션)
-                sqlx::query(
-                    r#"
-                    ALTER TABLE bigpicture.markers 
-                    ADD COLUMN IF NOT EXISTS emotion_tag_input TEXT
-                    "#
-                )
-                .execute(pool)
-                .await?;
-                println!("✅ markers 테이블 emotion_tag_input 마이그레이션 완료");
-        
-        // marker_images 테이블 생성 (마커와 이미지 연결)
-        println!("📋 marker_images 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.marker_images (
-                id SERIAL PRIMARY KEY,
-                marker_id INTEGER NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
-                image_type VARCHAR(50) NOT NULL, -- thumbnail, detail, gallery
-                image_url VARCHAR(500) NOT NULL,
-                image_order INTEGER DEFAULT 0, -- 이미지 순서
-                is_primary BOOLEAN DEFAULT false, -- 대표 이미지 여부
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ marker_images 테이블 생성 완료");
-        
-        // 공간 인덱스 생성 (성능 최적화)
-        sqlx::query("CREATE INDEX IF NOT EXISTS markers_location_gist ON bigpicture.markers USING GIST (location)")
-            .execute(pool)
-            .await?;
-        
-        // marker_images 인덱스
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_marker_images_marker_id ON bigpicture.marker_images(marker_id)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_marker_images_image_type ON bigpicture.marker_images(image_type)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_marker_images_is_primary ON bigpicture.marker_images(is_primary)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_marker_images_order ON bigpicture.marker_images(marker_id, image_order)")
-            .execute(pool)
-            .await?;
-        
-        // auth_providers 테이블 생성
-        println!("📋 auth_providers 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.auth_providers (
-                id BIGSERIAL PRIMARY KEY,
-                member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
-                provider_type VARCHAR(50) NOT NULL, -- google, kakao, naver, meta, email
-                provider_id VARCHAR(255) NOT NULL,
-                provider_email VARCHAR(255),
-                password_hash VARCHAR(255),
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                
-                UNIQUE(provider_type, provider_id),
-                UNIQUE(member_id, provider_type)
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ auth_providers 테이블 생성 완료");
-        
-        // member_markers 테이블 생성 (마커와 유저 연결)
-        println!("📋 member_markers 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.member_markers (
-                id BIGSERIAL PRIMARY KEY,
-                member_id BIGINT NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
-                marker_id BIGINT NOT NULL REFERENCES bigpicture.markers(id) ON DELETE CASCADE,
-                interaction_type VARCHAR(50) NOT NULL, -- created, liked, disliked, viewed, bookmarked
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                
-                UNIQUE(member_id, marker_id, interaction_type)
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ member_markers 테이블 생성 완료");
-        
-
-        
-        // 인덱스 생성
-        println!("🔍 추가 인덱스 생성 중...");
-        
-        // members 인덱스
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_members_email ON bigpicture.members(email)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_members_nickname ON bigpicture.members(nickname)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_members_created_at ON bigpicture.members(created_at)")
-            .execute(pool)
-            .await?;
-        
-        // auth_providers 인덱스
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_auth_providers_member_id ON bigpicture.auth_providers(member_id)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_auth_providers_provider_type_id ON bigpicture.auth_providers(provider_type, provider_id)")
-            .execute(pool)
-            .await?;
-        
-        // member_markers 인덱스
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_member_markers_member_id ON bigpicture.member_markers(member_id)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_member_markers_marker_id ON bigpicture.member_markers(marker_id)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_member_markers_interaction_type ON bigpicture.member_markers(interaction_type)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_member_markers_member_marker ON bigpicture.member_markers(member_id, marker_id)")
-            .execute(pool)
-            .await?;
-        
-        // markers member_id 인덱스
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_markers_member_id ON bigpicture.markers(member_id)")
-            .execute(pool)
-            .await?;
-        
-        println!("✅ 인덱스 생성 완료");
-        
-        // 테이블 존재 확인
-        println!("🔍 테이블 존재 확인 중...");
-        let original_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS (SELECT FROM information_schema.tables WHERE table_schema = 'bigpicture' AND table_name = 'original_images')"
-        )
-        .fetch_one(pool)
-        .await?;
-        
-        let webp_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS (SELECT FROM information_schema.tables WHERE table_schema = 'bigpicture' AND table_name = 'webp_images')"
-        )
-        .fetch_one(pool)
-        .await?;
-        
-        let markers_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS (SELECT FROM information_schema.tables WHERE table_schema = 'bigpicture' AND table_name = 'markers')"
-        )
-        .fetch_one(pool)
-        .await?;
-        
-        let members_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS (SELECT FROM information_schema.tables WHERE table_schema = 'bigpicture' AND table_name = 'members')"
-        )
-        .fetch_one(pool)
-        .await?;
-        
-        let auth_providers_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS (SELECT FROM information_schema.tables WHERE table_schema = 'bigpicture' AND table_name = 'auth_providers')"
-        )
-        .fetch_one(pool)
-        .await?;
-        
-        let member_markers_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS (SELECT FROM information_schema.tables WHERE table_schema = 'bigpicture' AND table_name = 'member_markers')"
-        )
-        .fetch_one(pool)
-        .await?;
-        
-        if original_exists && webp_exists && markers_exists && members_exists && auth_providers_exists && member_markers_exists {
-            println!("✅ 새로운 테이블 구조가 성공적으로 생성되었습니다!");
-            
-            // 테이블 구조 확인
-            println!("📊 original_images 테이블 구조:");
-            let original_columns = sqlx::query(
-                "SELECT column_name, data_type FROM information_schema.columns WHERE table_schema = 'bigpicture' AND table_name = 'original_images' ORDER BY ordinal_position"
-            )
-            .fetch_all(pool)
-            .await?;
-            
-            for row in original_columns {
-                let column_name: String = row.get(0);
-                let data_type: String = row.get(1);
-                println!("  - {}: {}", column_name, data_type);
-            }
-            
-            println!("📊 webp_images 테이블 구조:");
-            let webp_columns = sqlx::query(
-                "SELECT column_name, data_type FROM information_schema.columns WHERE table_schema = 'bigpicture' AND table_name = 'webp_images' ORDER BY ordinal_position"
-            )
-            .fetch_all(pool)
-            .await?;
-            
-            for row in webp_columns {
-                let column_name: String = row.get(0);
-                let data_type: String = row.get(1);
-                println!("  - {}: {}", column_name, data_type);
-            }
-            
-            println!("📊 markers 테이블 구조:");
-            let markers_columns = sqlx::query(
-                "SELECT column_name, data_type FROM information_schema.columns WHERE table_schema = 'bigpicture' AND table_name = 'markers' ORDER BY ordinal_position"
-            )
-            .fetch_all(pool)
-            .await?;
-            
-            for row in markers_columns {
-                let column_name: String = row.get(0);
-                let data_type: String = row.get(1);
-                println!("  - {}: {}", column_name, data_type);
-            }
-        } else {
-            println!("❌ 테이블 생성에 실패했습니다!");
-        }
-        
-        // 회원/멤버 관련 테이블 생성
-        println!("📋 members 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.members (
-                id SERIAL PRIMARY KEY,
-                email VARCHAR(255) UNIQUE NOT NULL,
-                nickname VARCHAR(100) NOT NULL,
-                profile_image_url TEXT,
-                region VARCHAR(100),
-                gender VARCHAR(10) CHECK (gender IN ('male', 'female', 'other', 'prefer_not_to_say')),
-                age INTEGER CHECK (age IS NULL OR (age >= 1900 AND age <= 2100)),
-                personality_type VARCHAR(50),
-                is_active BOOLEAN DEFAULT TRUE,
-                email_verified BOOLEAN DEFAULT FALSE,
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-                last_login_at TIMESTAMP WITH TIME ZONE
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ members 테이블 생성 완료");
-
-        println!("📋 auth_providers 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.auth_providers (
-                id SERIAL PRIMARY KEY,
-                member_id INTEGER NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
-                provider_type VARCHAR(20) NOT NULL CHECK (provider_type IN ('email', 'google', 'meta', 'kakao', 'naver')),
-                provider_id VARCHAR(255) NOT NULL,
-                provider_email VARCHAR(255),
-                password_hash VARCHAR(255),
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(provider_type, provider_id)
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ auth_providers 테이블 생성 완료");
-
-        println!("📋 hobbies 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.hobbies (
-                id SERIAL PRIMARY KEY,
-                name VARCHAR(100) NOT NULL UNIQUE,
-                category VARCHAR(50),
-                description TEXT,
-                is_active BOOLEAN DEFAULT TRUE,
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ hobbies 테이블 생성 완료");
-
-        println!("📋 interests 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.interests (
-                id SERIAL PRIMARY KEY,
-                name VARCHAR(100) NOT NULL UNIQUE,
-                category VARCHAR(50),
-                description TEXT,
-                is_active BOOLEAN DEFAULT TRUE,
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ interests 테이블 생성 완료");
-
-        println!("📋 member_hobbies 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.member_hobbies (
-                id SERIAL PRIMARY KEY,
-                member_id INTEGER NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
-                hobby_id INTEGER NOT NULL REFERENCES bigpicture.hobbies(id) ON DELETE CASCADE,
-                proficiency_level INTEGER CHECK (proficiency_level >= 1 AND proficiency_level <= 5),
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(member_id, hobby_id)
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ member_hobbies 테이블 생성 완료");
-
-        println!("📋 member_interests 테이블 생성 중...");
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS bigpicture.member_interests (
-                id SERIAL PRIMARY KEY,
-                member_id INTEGER NOT NULL REFERENCES bigpicture.members(id) ON DELETE CASCADE,
-                interest_id INTEGER NOT NULL REFERENCES bigpicture.interests(id) ON DELETE CASCADE,
-                interest_level INTEGER CHECK (interest_level >= 1 AND interest_level <= 5),
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(member_id, interest_id)
-            )
-            "#
-        )
-        .execute(pool)
-        .await?;
-        println!("✅ member_interests 테이블 생성 완료");
+    /// 실제 연결은 첫 쿼리 때 시도 (DB 없이 앱을 띄우는 테스트용)
+    pub fn connect_lazy(database_url: &str) -> Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(std::time::Duration::from_secs(2))
+            .connect_lazy(database_url)?;
         
-        // member_markers 테이블 인덱스 생성 (기존 테이블 활용)
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_member_markers_member_id ON bigpicture.member_markers(member_id)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_member_markers_marker_id ON bigpicture.member_markers(marker_id)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_member_markers_interaction_type ON bigpicture.member_markers(interaction_type)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_member_markers_member_marker ON bigpicture.member_markers(member_id, marker_id)")
-            .execute(pool)
-            .await?;
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_member_markers_created_at ON bigpicture.member_markers(created_at)")
-            .execute(pool)
-            .await?;
+        Ok(Self { pool })
+    }
+    
+    /// 마이그레이션 적용 (migrations/*.sql, 이미 적용된 버전은 _sqlx_migrations 기록을 보고 건너뜀)
+    async fn run_migrations(pool: &PgPool) -> Result<()> {
+        println!("🔧 데이터베이스 마이그레이션 시작...");
+        MIGRATOR.run(pool).await?;
+        let latest = MIGRATOR.iter().map(|migration| migration.version).max().unwrap_or(0);
+        println!("✅ 데이터베이스 마이그레이션 완료 (최신 버전: {})", latest);
         
         Ok(())
     }
@@ -794,10 +346,8 @@ impl Database {
     
     pub async fn get_markers(
         &self,
-        lat: f64,
-        lng: f64,
-        lat_delta: f64,
-        lng_delta: f64,
+        bounds: Option<(f64, f64, f64, f64)>, // (lat, lng, lat_delta, lng_delta)
+        district_code: Option<&str>, // 행정구역 코드 (경계 폴리곤 공간 조인, 숫자만 허용)
         emotion_tags: Option<Vec<String>>,
         min_likes: Option<i32>,
         min_views: Option<i32>,
@@ -806,77 +356,80 @@ impl Database {
         limit: Option<i32>,
         user_id: Option<i64>, // 추가: 내 마커만 조회
         current_user_id: Option<i64>, // 추가: 현재 로그인한 사용자 ID (공유 옵션 필터링용)
+        time_filter: &CreatedTimeFilter,
+        hashtag: Option<&str>, // 정규화된 해시태그 (marker_tags)
     ) -> Result<Vec<Marker>> {
         info!("🗄️ 데이터베이스 쿼리 시작:");
         
-        let lat_min = lat - lat_delta / 2.0;
-        let lat_max = lat + lat_delta / 2.0;
-        let lng_min = lng - lng_delta / 2.0;
-        let lng_max = lng + lng_delta / 2.0;
-        
-        info!("   - 검색 범위: lat({} ~ {}), lng({} ~ {})", lat_min, lat_max, lng_min, lng_max);
-        
-        // 정렬 동적 처리
-        let allowed_sort = ["created_at", "likes", "views", "dislikes"];
-        let sort_col = sort_by.filter(|s| allowed_sort.contains(&s.to_lowercase().as_str())).unwrap_or("created_at");
-        let order = sort_order.filter(|o| o.eq_ignore_ascii_case("asc") || o.eq_ignore_ascii_case("desc")).unwrap_or("desc");
-        let mut query = format!(
-            "SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, created_at, updated_at
-             FROM bigpicture.markers 
-             WHERE ST_Within(location::geometry, ST_MakeEnvelope({}, {}, {}, {}, 4326))",
-            lng_min, lat_min, lng_max, lat_max
+        // 사용자 입력은 모두 바인딩하고, 정렬은 허용 목록의 고정 문자열만 사용
+        let (sort_col, order) = marker_sort_clause(sort_by, sort_order, "created_at");
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, language, created_at, updated_at
+             FROM bigpicture.markers m
+             WHERE taken_down_at IS NULL AND status = 'published'"
         );
         
+        if let Some((lat, lng, lat_delta, lng_delta)) = bounds {
+            let lat_min = lat - lat_delta / 2.0;
+            let lat_max = lat + lat_delta / 2.0;
+            let lng_min = lng - lng_delta / 2.0;
+            let lng_max = lng + lng_delta / 2.0;
+            push_envelope_filter(&mut query, "location", (lng_min, lat_min, lng_max, lat_max));
+            info!("   - 검색 범위: lat({} ~ {}), lng({} ~ {})", lat_min, lat_max, lng_min, lng_max);
+        }
+        
+        // 행정구역 필터 (문자열 지역명 대신 경계 폴리곤으로 판정)
+        if let Some(code) = district_code.filter(|code| code.chars().all(|c| c.is_ascii_digit())) {
+            query.push(" AND EXISTS (SELECT 1 FROM bigpicture.districts d WHERE d.code = ")
+                .push_bind(code.to_string())
+                .push(" AND ST_Covers(d.geom, location::geometry))");
+            info!("   - 행정구역 필터: {}", code);
+        }
+        
         // 내 마커만 조회
         if let Some(uid) = user_id {
-            query.push_str(&format!(" AND member_id = {}", uid));
+            query.push(" AND member_id = ").push_bind(uid);
             info!("   - 내 마커만 필터: member_id = {}", uid);
         } else {
-            // 공유 옵션에 따른 필터링
-            if let Some(current_user) = current_user_id {
-                query.push_str(&format!(
-                    " AND (sharing_option = 'public' OR (sharing_option = 'friends' AND member_id = {}) OR member_id = {})",
-                    current_user, current_user
-                ));
-                info!("   - 공유 옵션 필터: 현재 사용자 {}의 권한에 따라 필터링", current_user);
-            } else {
-                // 로그인하지 않은 사용자는 public 마커만 볼 수 있음
-                query.push_str(" AND sharing_option = 'public'");
-                info!("   - 공유 옵션 필터: 비로그인 사용자는 public 마커만 조회");
-            }
+            // 공개 범위에 따른 필터링 (비로그인은 public만, 로그인 회원은 팔로우한 작성자의 followers 마커와 내 마커도)
+            query.push(" AND ").push(visibility_condition("", current_user_id));
+            info!("   - 공개 범위 필터: 조회자 {:?}", current_user_id);
         }
         
-        // 감성 태그 필터
-        if let Some(tags) = emotion_tags {
-            if !tags.is_empty() {
-                let tags_str = tags.iter().map(|tag| format!("'{}'", tag)).collect::<Vec<_>>().join(",");
-                query.push_str(&format!(" AND emotion_tag IN ({})", tags_str));
-                info!("   - 감성 태그 필터: {}", tags_str);
-            }
+        // 감성 태그, 최소 좋아요/조회수 필터
+        push_marker_filters(&mut query, emotion_tags.as_deref(), min_likes, min_views);
+        if let Some(tags) = emotion_tags.as_ref().filter(|tags| !tags.is_empty()) {
+            info!("   - 감성 태그 필터: {:?}", tags);
         }
-        
-        // 최소 좋아요 수 필터
         if let Some(likes) = min_likes {
-            query.push_str(&format!(" AND likes >= {}", likes));
             info!("   - 최소 좋아요: {}", likes);
         }
-        
-        // 최소 조회수 필터
         if let Some(views) = min_views {
-            query.push_str(&format!(" AND views >= {}", views));
             info!("   - 최소 조회수: {}", views);
         }
         
-        query.push_str(&format!(" ORDER BY {} {}", sort_col, order));
+        // 생성 시간대/계절/기간 필터
+        time_filter.push_sql_conditions(&mut query);
+        if time_filter.hour_range.is_some() || time_filter.months.is_some() {
+            info!("   - 생성 시각 필터: {:?}", time_filter);
+        }
+        
+        if let Some(tag) = hashtag {
+            query.push(" AND id IN (SELECT marker_id FROM bigpicture.marker_tags WHERE tag = ")
+                .push_bind(tag.to_string())
+                .push(")");
+            info!("   - 해시태그 필터: #{}", tag);
+        }
+        
+        query.push(format!(" ORDER BY {} {}", sort_col, order));
         
-        // LIMIT 추가 (기본값 1000개)
-        let limit_value = limit.unwrap_or(5000);
-        query.push_str(&format!(" LIMIT {}", limit_value));
+        // LIMIT 추가 (기본값 5000개)
+        query.push(" LIMIT ").push_bind(i64::from(limit.unwrap_or(5000)));
         
-        info!("   - 최종 SQL 쿼리: {}", query);
+        info!("   - 최종 SQL 쿼리: {}", query.sql());
         
         // 쿼리 실행
-        let markers = sqlx::query_as::<_, Marker>(&query)
+        let markers = query.build_query_as::<Marker>()
             .fetch_all(&self.pool)
             .await?;
         
@@ -894,96 +447,88 @@ impl Database {
         min_likes: Option<i32>,
         min_views: Option<i32>,
         user_id: Option<i64>,
+        viewer_id: Option<i64>, // 현재 로그인한 회원 (공개 범위 필터링용)
+        languages: Option<Vec<String>>, // 콘텐츠 언어 필터 (언어 미감지 마커는 항상 포함)
+        time_filter: &CreatedTimeFilter,
+        hashtag: Option<&str>, // 정규화된 해시태그 (marker_tags)
     ) -> Result<(Vec<Marker>, i64)> { // (마커 목록, 전체 개수)
         info!("🗄️ 피드 마커 조회 시작:");
         info!("   - 페이지: {}, 제한: {}", page, limit);
         
         let offset = (page - 1) * limit;
-        
-        let mut where_conditions = Vec::new();
-        let mut params: Vec<String> = Vec::new();
-        let mut param_count = 1;
-        
-        // 특정 사용자 마커만 조회
         if let Some(uid) = user_id {
-            where_conditions.push(format!("member_id = ${}", param_count));
-            params.push(uid.to_string());
-            param_count += 1;
             info!("   - 사용자 필터: member_id = {}", uid);
         }
-        
-        // 감성 태그 필터
-        if let Some(tags) = emotion_tags {
-            if !tags.is_empty() {
-                let tag_conditions: Vec<String> = tags.iter()
-                    .map(|tag| format!("emotion_tag LIKE '%{}%'", tag))
-                    .collect();
-                where_conditions.push(format!("({})", tag_conditions.join(" OR ")));
-                info!("   - 감성 태그 필터: {:?}", tags);
-            }
+        if let Some(tags) = emotion_tags.as_ref().filter(|tags| !tags.is_empty()) {
+            info!("   - 감성 태그 필터: {:?}", tags);
         }
-        
-        // 최소 좋아요 수 필터
         if let Some(min_likes) = min_likes {
-            where_conditions.push(format!("likes >= ${}", param_count));
-            params.push(min_likes.to_string());
-            param_count += 1;
             info!("   - 최소 좋아요 수: {}", min_likes);
         }
-        
-        // 최소 조회수 필터
         if let Some(min_views) = min_views {
-            where_conditions.push(format!("views >= ${}", param_count));
-            params.push(min_views.to_string());
-            param_count += 1;
             info!("   - 최소 조회수: {}", min_views);
         }
+        let languages = languages.filter(|langs| !langs.is_empty());
+        if let Some(langs) = &languages {
+            info!("   - 언어 필터: {:?}", langs);
+        }
+        if let Some(tag) = hashtag {
+            info!("   - 해시태그 필터: #{}", tag);
+        }
+        if *time_filter != CreatedTimeFilter::default() {
+            info!("   - 생성 시각 필터: {:?}", time_filter);
+        }
         
-        let where_clause = if where_conditions.is_empty() {
-            String::new()
-        } else {
-            format!("WHERE {}", where_conditions.join(" AND "))
+        // 개수/목록 쿼리에 같은 조건 (사용자 입력은 모두 바인딩)
+        let push_conditions = |query: &mut QueryBuilder<'_, Postgres>| {
+            query.push(" FROM bigpicture.markers m WHERE m.taken_down_at IS NULL AND m.status = 'published' AND ")
+                .push(visibility_condition("m.", viewer_id));
+            if let Some(uid) = user_id {
+                query.push(" AND m.member_id = ").push_bind(uid);
+            }
+            if let Some(tags) = emotion_tags.as_ref().filter(|tags| !tags.is_empty()) {
+                for (i, tag) in tags.iter().enumerate() {
+                    query.push(if i == 0 { " AND (m.emotion_tag LIKE '%' || " } else { " OR m.emotion_tag LIKE '%' || " })
+                        .push_bind(tag.clone())
+                        .push(" || '%'");
+                }
+                query.push(")");
+            }
+            if let Some(min_likes) = min_likes {
+                query.push(" AND m.likes >= ").push_bind(min_likes);
+            }
+            if let Some(min_views) = min_views {
+                query.push(" AND m.views >= ").push_bind(min_views);
+            }
+            // 콘텐츠 언어 필터 (언어 미감지 마커는 항상 포함)
+            if let Some(langs) = &languages {
+                query.push(" AND (m.language IS NULL OR m.language = ANY(").push_bind(langs.clone()).push("))");
+            }
+            if let Some(tag) = hashtag {
+                query.push(" AND m.id IN (SELECT marker_id FROM bigpicture.marker_tags WHERE tag = ")
+                    .push_bind(tag.to_string())
+                    .push(")");
+            }
+            time_filter.push_sql_conditions(query);
         };
         
         // 전체 개수 조회
-        let count_query = format!(
-            "SELECT COUNT(*) as total FROM bigpicture.markers {}",
-            where_clause
-        );
-        
-        let total_count: i64 = if params.is_empty() {
-            sqlx::query_scalar(&count_query)
-                .fetch_one(&self.pool)
-                .await?
-        } else {
-            let mut query_builder = sqlx::query_scalar(&count_query);
-            for param in &params {
-                query_builder = query_builder.bind(param);
-            }
-            query_builder.fetch_one(&self.pool).await?
-        };
+        let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*)");
+        push_conditions(&mut count_query);
+        let total_count: i64 = count_query.build_query_scalar()
+            .fetch_one(&self.pool)
+            .await?;
         
         // 마커 목록 조회
-        let markers_query = format!(
-            "SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, created_at, updated_at
-             FROM bigpicture.markers 
-             {} 
-             ORDER BY created_at DESC 
-             LIMIT {} OFFSET {}",
-            where_clause, limit, offset
+        let mut markers_query = QueryBuilder::<Postgres>::new(
+            "SELECT m.id, m.member_id, ST_AsText(m.location) as location, m.emotion_tag, m.emotion, m.description, m.sharing_option, m.likes, m.dislikes, m.views, m.shares, m.author, m.thumbnail_img, m.language, m.created_at, m.updated_at"
         );
-        
-        let markers = if params.is_empty() {
-            sqlx::query_as::<_, Marker>(&markers_query)
-                .fetch_all(&self.pool)
-                .await?
-        } else {
-            let mut query_builder = sqlx::query_as::<_, Marker>(&markers_query);
-            for param in &params {
-                query_builder = query_builder.bind(param);
-            }
-            query_builder.fetch_all(&self.pool).await?
-        };
+        push_conditions(&mut markers_query);
+        markers_query.push(" ORDER BY m.created_at DESC LIMIT ").push_bind(i64::from(limit))
+            .push(" OFFSET ").push_bind(i64::from(offset));
+        let markers = markers_query.build_query_as::<Marker>()
+            .fetch_all(&self.pool)
+            .await?;
         
         info!("✅ 피드 쿼리 완료: {}개 마커 반환 (전체: {}개)", markers.len(), total_count);
         Ok((markers, total_count))
@@ -992,7 +537,7 @@ impl Database {
     // 마커 이미지 관련 함수들
     pub async fn add_marker_image(
         &self,
-        marker_id: i32,
+        marker_id: i64,
         image_type: &str,
         image_url: &str,
         image_order: i32,
@@ -1017,12 +562,102 @@ impl Database {
         Ok(rec.get("id"))
     }
 
-    pub async fn get_marker_images(&self, marker_id: i32) -> Result<Vec<MarkerImage>> {
+    /// 마커 이미지 추가 (image_type 미지정 시 서버에서 파생)
+    /// 상세(detail) 이미지로 저장하고, 업로드 시 생성된 썸네일이 있으면 원본과 연결된 thumbnail 항목도 함께 저장
+    /// 마커 행을 잠근 뒤 쿼터를 확인하므로 동시에 추가해도 한도를 넘지 않음 (초과 시 MarkerImageQuotaExceeded)
+    pub async fn add_marker_image_with_variants(
+        &self,
+        marker_id: i64,
+        image_type: Option<&str>,
+        image_url: &str,
+        image_order: i32,
+        is_primary: bool,
+        quota: &MarkerImageQuota,
+    ) -> Result<Vec<MarkerImage>> {
+        let mut tx = self.pool.begin().await?;
+        
+        sqlx::query("SELECT id FROM bigpicture.markers WHERE id = $1 FOR UPDATE")
+            .bind(marker_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let usage = sqlx::query(MARKER_IMAGE_USAGE_SQL)
+            .bind(marker_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let new_bytes: Option<i64> = sqlx::query_scalar(IMAGE_ORIGINAL_SIZE_SQL)
+            .bind(image_url)
+            .fetch_optional(&mut *tx)
+            .await?
+            .flatten();
+        let exceeded = MarkerImageQuotaExceeded {
+            used_images: usage.get("image_count"),
+            used_bytes: usage.get("original_bytes"),
+            new_images: 1,
+            new_bytes: new_bytes.unwrap_or(0),
+        };
+        if exceeded.exceeds(quota) {
+            return Err(exceeded.into());
+        }
+        
+        let image = sqlx::query_as::<_, MarkerImage>(
+            r#"
+            INSERT INTO bigpicture.marker_images
+                (marker_id, image_type, image_url, image_order, is_primary)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
+            "#
+        )
+        .bind(marker_id)
+        .bind(image_type.unwrap_or("detail"))
+        .bind(image_url)
+        .bind(image_order)
+        .bind(is_primary)
+        .fetch_one(&mut *tx)
+        .await?;
+        
+        let mut images = vec![image];
+        if image_type.is_none() {
+            // 클라이언트가 S3 경로 또는 전체 URL 중 어느 쪽을 저장했든 저장 위치 키로 비교 (idx_image_variants_storage_key)
+            let thumbnail_url: Option<String> = sqlx::query_scalar(
+                r#"
+                SELECT thumbnail_url FROM bigpicture.image_variants
+                WHERE bigpicture.image_storage_key(image_url) = bigpicture.image_storage_key($1)
+                LIMIT 1
+                "#
+            )
+            .bind(image_url)
+            .fetch_optional(&mut *tx)
+            .await?;
+            
+            if let Some(thumbnail_url) = thumbnail_url {
+                let thumbnail = sqlx::query_as::<_, MarkerImage>(
+                    r#"
+                    INSERT INTO bigpicture.marker_images
+                        (marker_id, image_type, image_url, image_order, is_primary, source_image_id)
+                    VALUES ($1, 'thumbnail', $2, $3, false, $4)
+                    RETURNING id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
+                    "#
+                )
+                .bind(marker_id)
+                .bind(&thumbnail_url)
+                .bind(image_order)
+                .bind(images[0].id)
+                .fetch_one(&mut *tx)
+                .await?;
+                images.push(thumbnail);
+            }
+        }
+        
+        tx.commit().await?;
+        Ok(images)
+    }
+
+    pub async fn get_marker_images(&self, marker_id: i64) -> Result<Vec<MarkerImage>> {
         let rows = sqlx::query_as::<_, MarkerImage>(
             r#"
-            SELECT id, marker_id, image_type, image_url, image_order, is_primary, created_at, updated_at
+            SELECT id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
             FROM bigpicture.marker_images 
-            WHERE marker_id = $1
+            WHERE marker_id = $1 AND taken_down_at IS NULL
             ORDER BY image_order ASC, created_at ASC
             "#
         )
@@ -1033,12 +668,12 @@ impl Database {
         Ok(rows)
     }
 
-    pub async fn get_marker_images_by_type(&self, marker_id: i32, image_type: &str) -> Result<Vec<MarkerImage>> {
+    pub async fn get_marker_images_by_type(&self, marker_id: i64, image_type: &str) -> Result<Vec<MarkerImage>> {
         let rows = sqlx::query_as::<_, MarkerImage>(
             r#"
-            SELECT id, marker_id, image_type, image_url, image_order, is_primary, created_at, updated_at
+            SELECT id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
             FROM bigpicture.marker_images 
-            WHERE marker_id = $1 AND image_type = $2
+            WHERE marker_id = $1 AND image_type = $2 AND taken_down_at IS NULL
             ORDER BY image_order ASC, created_at ASC
             "#
         )
@@ -1050,12 +685,12 @@ impl Database {
         Ok(rows)
     }
 
-    pub async fn get_marker_primary_image(&self, marker_id: i32) -> Result<Option<MarkerImage>> {
+    pub async fn get_marker_primary_image(&self, marker_id: i64) -> Result<Option<MarkerImage>> {
         let row = sqlx::query_as::<_, MarkerImage>(
             r#"
-            SELECT id, marker_id, image_type, image_url, image_order, is_primary, created_at, updated_at
+            SELECT id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
             FROM bigpicture.marker_images 
-            WHERE marker_id = $1 AND is_primary = true
+            WHERE marker_id = $1 AND is_primary = true AND taken_down_at IS NULL
             LIMIT 1
             "#
         )
@@ -1082,7 +717,55 @@ impl Database {
         Ok(())
     }
 
-    pub async fn set_marker_primary_image(&self, marker_id: i32, image_id: i32) -> Result<()> {
+    /// 마커 이미지 순서 일괄 변경 (전체 이미지 ID 목록 순서대로 0부터 재부여)
+    /// 트랜잭션 내에서 이미지 목록이 요청과 일치하지 않으면(동시 수정 등) None 반환
+    pub async fn reorder_marker_images(&self, marker_id: i64, ordered_ids: &[i32]) -> Result<Option<Vec<MarkerImage>>> {
+        let mut tx = self.pool.begin().await?;
+        
+        let mut current_ids: Vec<i32> = sqlx::query_scalar(
+            "SELECT id FROM bigpicture.marker_images WHERE marker_id = $1 AND taken_down_at IS NULL FOR UPDATE"
+        )
+        .bind(marker_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        
+        let mut requested_ids = ordered_ids.to_vec();
+        current_ids.sort_unstable();
+        requested_ids.sort_unstable();
+        if current_ids != requested_ids {
+            return Ok(None);
+        }
+        
+        sqlx::query(
+            r#"
+            UPDATE bigpicture.marker_images mi
+            SET image_order = (v.ord - 1)::INTEGER, updated_at = NOW()
+            FROM unnest($2::INTEGER[]) WITH ORDINALITY AS v(id, ord)
+            WHERE mi.id = v.id AND mi.marker_id = $1
+            "#
+        )
+        .bind(marker_id)
+        .bind(ordered_ids)
+        .execute(&mut *tx)
+        .await?;
+        
+        let images = sqlx::query_as::<_, MarkerImage>(
+            r#"
+            SELECT id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
+            FROM bigpicture.marker_images 
+            WHERE marker_id = $1 AND taken_down_at IS NULL
+            ORDER BY image_order ASC, created_at ASC
+            "#
+        )
+        .bind(marker_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        
+        tx.commit().await?;
+        Ok(Some(images))
+    }
+
+    pub async fn set_marker_primary_image(&self, marker_id: i64, image_id: i32) -> Result<()> {
         // 먼저 모든 이미지의 is_primary를 false로 설정
         sqlx::query(
             r#"
@@ -1111,1165 +794,5288 @@ impl Database {
         Ok(())
     }
 
-    pub async fn delete_marker_image(&self, image_id: i32) -> Result<bool> {
-        let result = sqlx::query("DELETE FROM bigpicture.marker_images WHERE id = $1")
-            .bind(image_id)
-            .execute(&self.pool)
+    /// 업로드 이미지 품질 정보 저장 (해상도, 선명도, 원본 크기, BlurHash 자리표시자)
+    pub async fn save_image_quality(&self, image_url: &str, width: u32, height: u32, sharpness_score: f64, original_size_bytes: i64, blurhash: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bigpicture.image_quality (image_url, width, height, sharpness_score, original_size_bytes, blurhash)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (image_url)
+            DO UPDATE SET width = EXCLUDED.width, height = EXCLUDED.height,
+                          sharpness_score = EXCLUDED.sharpness_score, original_size_bytes = EXCLUDED.original_size_bytes,
+                          blurhash = EXCLUDED.blurhash
+            "#
+        )
+        .bind(image_url)
+        .bind(width as i32)
+        .bind(height as i32)
+        .bind(sharpness_score)
+        .bind(original_size_bytes)
+        .bind(blurhash)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 업로드 시 기록한 원본 크기 조회 (기록이 없는 외부 URL 등은 None)
+    pub async fn get_image_original_size(&self, image_url: &str) -> Result<Option<i64>> {
+        let size: Option<Option<i64>> = sqlx::query_scalar(IMAGE_ORIGINAL_SIZE_SQL)
+            .bind(image_url)
+            .fetch_optional(&self.pool)
             .await?;
         
-        Ok(result.rows_affected() > 0)
+        Ok(size.flatten())
     }
 
-    /// 회원 등록
-    pub async fn create_member(
-        &self,
-        email: &str,
-        nickname: &str,
-        profile_image_url: Option<&str>,
-        region: Option<&str>,
-        gender: Option<&str>,
-        birth_year: Option<i32>,
-        personality_type: Option<&str>,
-    ) -> Result<Member> {
-        let rec = sqlx::query_as::<_, Member>(
+    /// 마커 이미지 사용량 조회: (이미지 수, 원본 크기 합계 bytes)
+    /// 서버에서 자동 생성한 썸네일은 제외
+    pub async fn get_marker_image_usage(&self, marker_id: i64) -> Result<(i64, i64)> {
+        let row = sqlx::query(MARKER_IMAGE_USAGE_SQL)
+            .bind(marker_id)
+            .fetch_one(&self.pool)
+            .await?;
+        
+        Ok((row.get("image_count"), row.get("original_bytes")))
+    }
+
+    /// 업로드 원본과 서버에서 생성한 썸네일 연결 저장
+    /// 변환된 이미지와 업로드 원본 연결 (이미지가 정리될 때 원본도 삭제)
+    pub async fn save_image_original(&self, storage_key: &str, original_key: &str) -> Result<()> {
+        sqlx::query(
             r#"
-            INSERT INTO bigpicture.members
-                (email, nickname, profile_image_url, region, gender, age, personality_type)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING *
+            INSERT INTO bigpicture.image_originals (storage_key, original_key)
+            VALUES ($1, $2)
+            ON CONFLICT (storage_key)
+            DO UPDATE SET original_key = EXCLUDED.original_key
             "#
         )
-        .bind(email)
-        .bind(nickname)
-        .bind(profile_image_url)
-        .bind(region)
-        .bind(gender)
-        .bind(birth_year)
-        .bind(personality_type)
-        .fetch_one(&self.pool)
+        .bind(storage_key)
+        .bind(original_key)
+        .execute(&self.pool)
         .await?;
-        Ok(rec)
+        
+        Ok(())
     }
 
-    /// 회원 조회 by id
-    pub async fn get_member_by_id(&self, id: i64) -> Result<Option<Member>> {
-        let rec = sqlx::query_as::<_, Member>(
+    pub async fn get_image_original(&self, storage_key: &str) -> Result<Option<String>> {
+        let original_key = sqlx::query_scalar("SELECT original_key FROM bigpicture.image_originals WHERE storage_key = $1")
+            .bind(storage_key)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(original_key)
+    }
+
+    pub async fn delete_image_original(&self, storage_key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM bigpicture.image_originals WHERE storage_key = $1")
+            .bind(storage_key)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+
+    /// 변환된 이미지와 너비별(반응형) 이미지 연결
+    pub async fn save_image_size_variant(&self, storage_key: &str, width: u32, height: u32, variant_url: &str, variant_key: &str) -> Result<()> {
+        sqlx::query(
             r#"
-            SELECT * FROM bigpicture.members WHERE id = $1
+            INSERT INTO bigpicture.image_size_variants (storage_key, width, height, variant_url, variant_key)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (storage_key, width)
+            DO UPDATE SET height = EXCLUDED.height, variant_url = EXCLUDED.variant_url, variant_key = EXCLUDED.variant_key
             "#
         )
-        .bind(id)
-        .fetch_optional(&self.pool)
+        .bind(storage_key)
+        .bind(width as i32)
+        .bind(height as i32)
+        .bind(variant_url)
+        .bind(variant_key)
+        .execute(&self.pool)
         .await?;
-        Ok(rec)
+        
+        Ok(())
     }
 
-    /// 회원 조회 by id (마커 정보 포함)
-    pub async fn get_member_with_markers(&self, id: i64) -> Result<Option<(Member, Vec<MemberMarker>)>> {
-        // 회원 정보 조회
-        let member = match self.get_member_by_id(id).await? {
-            Some(member) => member,
-            None => return Ok(None),
-        };
-        
-        // 회원의 마커 상호작용 조회
-        let markers = self.get_member_marker_interactions(id).await?;
+    /// 너비별 이미지 (너비, URL, 저장 위치), 너비 오름차순
+    pub async fn get_image_size_variants(&self, storage_key: &str) -> Result<Vec<(i32, String, String)>> {
+        let rows = sqlx::query(
+            "SELECT width, variant_url, variant_key FROM bigpicture.image_size_variants WHERE storage_key = $1 ORDER BY width"
+        )
+        .bind(storage_key)
+        .fetch_all(&self.pool)
+        .await?;
         
-        Ok(Some((member, markers)))
+        Ok(rows.into_iter().map(|row| (row.get("width"), row.get("variant_url"), row.get("variant_key"))).collect())
     }
 
-    /// 회원 조회 by id (마커 상세 정보 포함)
-    pub async fn get_member_with_marker_details(&self, id: i64) -> Result<Option<(Member, Vec<(MemberMarker, Marker)>)>> {
-        // 회원 정보 조회
-        let member = match self.get_member_by_id(id).await? {
-            Some(member) => member,
-            None => return Ok(None),
-        };
+    /// DB가 참조하는 이미지 저장 위치 전부 ("s3:{키}", "local:{키}"), 고아 파일 정리가 이 목록에 없는 파일만 지움
+    /// 마커 이미지/썸네일, 프로필 이미지, 업로드 기록(품질, 원본, 너비별, 마커 썸네일, 검수), 아직 처리 중인 정리 작업
+    pub async fn get_referenced_image_keys(&self) -> Result<std::collections::HashSet<String>> {
+        let keys: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT key FROM (
+                SELECT storage_key AS key FROM bigpicture.marker_images
+                UNION SELECT bigpicture.image_storage_key(thumbnail_img) FROM bigpicture.markers
+                UNION SELECT bigpicture.image_storage_key(profile_image_url) FROM bigpicture.members
+                UNION SELECT bigpicture.image_storage_key(image_url) FROM bigpicture.image_quality
+                UNION SELECT bigpicture.image_storage_key(image_url) FROM bigpicture.image_variants
+                UNION SELECT bigpicture.image_storage_key(thumbnail_url) FROM bigpicture.image_variants
+                UNION SELECT storage_key FROM bigpicture.image_originals
+                UNION SELECT original_key FROM bigpicture.image_originals
+                UNION SELECT storage_key FROM bigpicture.image_size_variants
+                UNION SELECT variant_key FROM bigpicture.image_size_variants
+                UNION SELECT storage_key FROM bigpicture.image_moderation
+                UNION SELECT COALESCE(storage_key, bigpicture.image_storage_key(image_url)) FROM bigpicture.image_cleanup_jobs WHERE completed_at IS NULL
+            ) referenced
+            WHERE key IS NOT NULL
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
         
-        // 회원의 마커 상세 정보 조회
-        let marker_details = self.get_member_markers_with_details(id).await?;
+        Ok(keys.into_iter().collect())
+    }
+
+    /// 저장 위치가 접두사("s3:", "local:")로 시작하는 마커 이미지 (법적 보존 중인 이미지 제외), 파일이 없는 이미지 정리용
+    pub async fn get_stored_marker_images(&self, storage_prefix: &str) -> Result<Vec<StoredMarkerImage>> {
+        let images = sqlx::query_as::<_, StoredMarkerImage>(
+            r#"
+            SELECT id, marker_id, image_url, storage_key, created_at
+            FROM bigpicture.marker_images
+            WHERE starts_with(storage_key, $1) AND COALESCE(legal_hold, false) = false
+            ORDER BY id
+            "#
+        )
+        .bind(storage_prefix)
+        .fetch_all(&self.pool)
+        .await?;
         
-        Ok(Some((member, marker_details)))
+        Ok(images)
     }
 
-    /// 회원 조회 by id (마커 통계 포함)
-    pub async fn get_member_with_stats(&self, id: i64) -> Result<Option<(Member, serde_json::Value)>> {
-        // 회원 정보 조회
-        let member = match self.get_member_by_id(id).await? {
-            Some(member) => member,
-            None => return Ok(None),
-        };
+    pub async fn delete_image_size_variants(&self, storage_key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM bigpicture.image_size_variants WHERE storage_key = $1")
+            .bind(storage_key)
+            .execute(&self.pool)
+            .await?;
         
-        // 회원의 마커 통계 조회
-        let stats = self.get_member_marker_stats(id).await?;
+        Ok(())
+    }
+
+    /// 검수 시작 기록 (검수한 이미지와 그 이미지로 만든 썸네일 모두 pending)
+    pub async fn save_image_moderation_pending(&self, storage_key: &str, derived_keys: &[String], moderator: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bigpicture.image_moderation (storage_key, source_key, moderator)
+            SELECT $1, NULL, $3
+            UNION ALL
+            SELECT derived_key, $1, $3 FROM UNNEST($2::varchar[]) AS derived_key
+            ON CONFLICT (storage_key)
+            DO UPDATE SET source_key = EXCLUDED.source_key, status = 'pending', moderator = EXCLUDED.moderator, labels = '{}'
+            "#
+        )
+        .bind(storage_key)
+        .bind(derived_keys)
+        .bind(moderator)
+        .execute(&self.pool)
+        .await?;
         
-        Ok(Some((member, stats)))
+        Ok(())
     }
 
-    /// 회원 조회 by email
-    pub async fn get_member_by_email(&self, email: &str) -> Result<Option<Member>> {
-        let rec = sqlx::query_as::<_, Member>(
+    /// 검수 결과 기록 (썸네일도 같은 결과)
+    pub async fn record_image_moderation(&self, storage_key: &str, status: &str, labels: &[String]) -> Result<()> {
+        sqlx::query(
             r#"
-            SELECT * FROM bigpicture.members WHERE email = $1
+            UPDATE bigpicture.image_moderation
+            SET status = $2, labels = $3, attempts = attempts + 1, last_error = NULL, checked_at = NOW()
+            WHERE storage_key = $1 OR source_key = $1
             "#
         )
-        .bind(email)
-        .fetch_optional(&self.pool)
+        .bind(storage_key)
+        .bind(status)
+        .bind(labels)
+        .execute(&self.pool)
         .await?;
-        Ok(rec)
+        
+        Ok(())
     }
 
-    /// 전체 회원 목록 (limit 옵션)
-    pub async fn list_members(&self, limit: Option<i64>) -> Result<Vec<Member>> {
-        let recs = sqlx::query_as::<_, Member>(
+    /// 검수 실패 기록 (pending으로 남겨 재시도)
+    pub async fn record_image_moderation_failure(&self, storage_key: &str, error: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE bigpicture.image_moderation SET attempts = attempts + 1, last_error = $2 WHERE storage_key = $1"
+        )
+        .bind(storage_key)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 다시 검수할 이미지 (오래된 순, 시도 횟수가 남은 것만)
+    pub async fn get_pending_image_moderation(&self, limit: i64, max_attempts: i32) -> Result<Vec<String>> {
+        let keys = sqlx::query_scalar(
             r#"
-            SELECT * FROM bigpicture.members ORDER BY id DESC LIMIT $1
+            SELECT storage_key FROM bigpicture.image_moderation
+            WHERE status = 'pending' AND source_key IS NULL AND attempts < $2
+            ORDER BY created_at
+            LIMIT $1
             "#
         )
-        .bind(limit.unwrap_or(100))
+        .bind(limit)
+        .bind(max_attempts)
         .fetch_all(&self.pool)
         .await?;
-        Ok(recs)
+        
+        Ok(keys)
     }
 
-    /// member_markers 테이블을 사용한 좋아요/싫어요 토글
-    pub async fn toggle_like(
-        &self,
-        member_id: i64,
-        marker_id: i64,
-        like_type: &str, // "like" 또는 "dislike"
-    ) -> Result<(i32, i32)> { // (좋아요 수, 싫어요 수) 반환
-        let mut tx = self.pool.begin().await?;
+    /// 이미지 검수 상태 (검수 기록이 없으면 None)
+    pub async fn get_image_moderation_status(&self, storage_key: &str) -> Result<Option<String>> {
+        let status = sqlx::query_scalar("SELECT status FROM bigpicture.image_moderation WHERE storage_key = $1")
+            .bind(storage_key)
+            .fetch_optional(&self.pool)
+            .await?;
         
-        // interaction_type 매핑
-        let interaction_type = if like_type == "like" { "liked" } else { "disliked" };
+        Ok(status)
+    }
+
+    pub async fn delete_image_moderation(&self, storage_key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM bigpicture.image_moderation WHERE storage_key = $1")
+            .bind(storage_key)
+            .execute(&self.pool)
+            .await?;
         
-        // 기존 좋아요/싫어요 확인
-        let existing = sqlx::query_as::<_, MemberMarker>(
+        Ok(())
+    }
+
+    /// 이어 받는 업로드 세션 생성
+    pub async fn create_upload_session(&self, session: &NewUploadSession, expires_at: chrono::DateTime<chrono::Utc>) -> Result<UploadSession> {
+        let session = sqlx::query_as::<_, UploadSession>(
             r#"
-            SELECT * FROM bigpicture.member_markers 
-            WHERE member_id = $1 AND marker_id = $2 AND interaction_type = $3
+            INSERT INTO bigpicture.upload_sessions (id, image_type, filename, upload_length, output_format, extract_exif, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, image_type, filename, upload_length, upload_offset, output_format, extract_exif, status, result, created_at, expires_at
             "#
         )
-        .bind(member_id)
-        .bind(marker_id)
-        .bind(interaction_type)
-        .fetch_optional(&mut *tx)
+        .bind(session.id)
+        .bind(&session.image_type)
+        .bind(&session.filename)
+        .bind(session.upload_length)
+        .bind(&session.output_format)
+        .bind(session.extract_exif)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
         .await?;
+        
+        Ok(session)
+    }
 
-        if let Some(existing_like) = existing {
-            // 기존 좋아요/싫어요가 있으면 제거
-            sqlx::query(
-                "DELETE FROM bigpicture.member_markers WHERE id = $1"
-            )
-            .bind(existing_like.id)
-            .execute(&mut *tx)
-            .await?;
-
-            // 마커 카운트 감소
-            let update_query = match like_type {
-                "like" => "UPDATE bigpicture.markers SET likes = GREATEST(likes - 1, 0) WHERE id = $1",
-                "dislike" => "UPDATE bigpicture.markers SET dislikes = GREATEST(dislikes - 1, 0) WHERE id = $1",
-                _ => return Err(anyhow::anyhow!("Invalid like type")),
-            };
-            sqlx::query(update_query)
-                .bind(marker_id)
-                .execute(&mut *tx)
-                .await?;
-        } else {
-            // 새로운 좋아요/싫어요 추가
-            sqlx::query(
-                r#"
-                INSERT INTO bigpicture.member_markers
-                    (member_id, marker_id, interaction_type)
-                VALUES ($1, $2, $3)
-                "#
-            )
-            .bind(member_id)
-            .bind(marker_id)
-            .bind(interaction_type)
-            .execute(&mut *tx)
-            .await?;
-
-            // 마커 카운트 증가
-            let update_query = match like_type {
-                "like" => "UPDATE bigpicture.markers SET likes = likes + 1 WHERE id = $1",
-                "dislike" => "UPDATE bigpicture.markers SET dislikes = dislikes + 1 WHERE id = $1",
-                _ => return Err(anyhow::anyhow!("Invalid like type")),
-            };
-            sqlx::query(update_query)
-                .bind(marker_id)
-                .execute(&mut *tx)
-                .await?;
-        }
-
-        // 업데이트된 카운트 조회
-        let counts = sqlx::query_as::<_, (i32, i32)>(
-            "SELECT likes, dislikes FROM bigpicture.markers WHERE id = $1"
+    /// 만료되지 않은 업로드 세션
+    pub async fn get_upload_session(&self, id: uuid::Uuid, now: chrono::DateTime<chrono::Utc>) -> Result<Option<UploadSession>> {
+        let session = sqlx::query_as::<_, UploadSession>(
+            "SELECT id, image_type, filename, upload_length, upload_offset, output_format, extract_exif, status, result, created_at, expires_at FROM bigpicture.upload_sessions WHERE id = $1 AND expires_at > $2"
         )
-        .bind(marker_id)
-        .fetch_one(&mut *tx)
+        .bind(id)
+        .bind(now)
+        .fetch_optional(&self.pool)
         .await?;
-
-        tx.commit().await?;
-        Ok(counts)
+        
+        Ok(session)
     }
 
-    /// 사용자가 특정 마커에 좋아요/싫어요를 눌렀는지 확인
-    pub async fn get_user_like_status(
-        &self,
-        member_id: i64,
-        marker_id: i64,
-    ) -> Result<Option<String>> { // None, Some("like"), Some("dislike")
-        let like = sqlx::query_as::<_, MemberMarker>(
+    /// 받은 위치 갱신 (다른 요청이 먼저 옮겼으면 false), 다 받았으면 처리 중으로 표시
+    pub async fn advance_upload_session(&self, id: uuid::Uuid, from_offset: i64, to_offset: i64) -> Result<bool> {
+        let result = sqlx::query(
             r#"
-            SELECT * FROM bigpicture.member_markers 
-            WHERE member_id = $1 AND marker_id = $2 AND interaction_type IN ('liked', 'disliked')
+            UPDATE bigpicture.upload_sessions
+            SET upload_offset = $3,
+                status = CASE WHEN $3 = upload_length THEN 'processing' ELSE status END,
+                updated_at = NOW()
+            WHERE id = $1 AND upload_offset = $2 AND status = 'uploading'
             "#
         )
-        .bind(member_id)
-        .bind(marker_id)
-        .fetch_optional(&self.pool)
+        .bind(id)
+        .bind(from_offset)
+        .bind(to_offset)
+        .execute(&self.pool)
         .await?;
-
-        Ok(like.map(|l| {
-            if l.interaction_type == "liked" { "like".to_string() } else { "dislike".to_string() }
-        }))
+        
+        Ok(result.rows_affected() > 0)
     }
 
-    /// 마커의 좋아요/싫어요 목록 조회
-    pub async fn get_marker_likes(
-        &self,
-        marker_id: i64,
-        like_type: Option<&str>, // None이면 모든 타입, "like" 또는 "dislike"
-    ) -> Result<Vec<MemberMarker>> {
-        let interaction_type = like_type.map(|lt| {
-            if lt == "like" { "liked" } else { "disliked" }
-        });
+    /// 처리 결과 기록 (completed/failed, 결과 응답 JSON)
+    pub async fn finish_upload_session(&self, id: uuid::Uuid, status: &str, result: &str) -> Result<()> {
+        sqlx::query("UPDATE bigpicture.upload_sessions SET status = $2, result = $3, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(status)
+            .bind(result)
+            .execute(&self.pool)
+            .await?;
         
-        let query = if let Some(it) = interaction_type {
-            sqlx::query_as::<_, MemberMarker>(
-                r#"
-                SELECT * FROM bigpicture.member_markers 
-                WHERE marker_id = $1 AND interaction_type = $2
-                ORDER BY created_at DESC
-                "#
-            )
-            .bind(marker_id)
-            .bind(it)
-        } else {
-            sqlx::query_as::<_, MemberMarker>(
-                r#"
-                SELECT * FROM bigpicture.member_markers 
-                WHERE marker_id = $1 AND interaction_type IN ('liked', 'disliked')
-                ORDER BY created_at DESC
-                "#
-            )
-            .bind(marker_id)
-        };
-
-        let member_markers = query.fetch_all(&self.pool).await?;
-        Ok(member_markers)
+        Ok(())
     }
 
-    /// 사용자가 좋아요/싫어요한 마커 목록 조회
-    pub async fn get_user_likes(
-        &self,
-        member_id: i64,
-        like_type: Option<&str>, // None이면 모든 타입, "like" 또는 "dislike"
-        limit: Option<i32>,
-    ) -> Result<Vec<MemberMarker>> {
-        let interaction_type = like_type.map(|lt| {
-            if lt == "like" { "liked" } else { "disliked" }
-        });
+    pub async fn delete_upload_session(&self, id: uuid::Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM bigpicture.upload_sessions WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
         
-        let query = if let Some(it) = interaction_type {
-            sqlx::query_as::<_, MemberMarker>(
-                r#"
-                SELECT * FROM bigpicture.member_markers 
-                WHERE member_id = $1 AND interaction_type = $2
-                ORDER BY created_at DESC
-                LIMIT $3
-                "#
-            )
-            .bind(member_id)
-            .bind(it)
-            .bind(limit.unwrap_or(50))
-        } else {
-            sqlx::query_as::<_, MemberMarker>(
-                r#"
-                SELECT * FROM bigpicture.member_markers 
-                WHERE member_id = $1 AND interaction_type IN ('liked', 'disliked')
-                ORDER BY created_at DESC
-                LIMIT $2
-                "#
-            )
-            .bind(member_id)
-            .bind(limit.unwrap_or(50))
-        };
-
-        let member_markers = query.fetch_all(&self.pool).await?;
-        Ok(member_markers)
+        Ok(result.rows_affected() > 0)
     }
 
-    /// 좋아요/싫어요 통계 조회
-    pub async fn get_like_stats(
-        &self,
-        marker_id: Option<i64>, // None이면 전체 통계
-    ) -> Result<serde_json::Value> {
-        let stats = if let Some(mid) = marker_id {
-            // 특정 마커의 통계
-            let (total_likes, total_dislikes) = sqlx::query_as::<_, (i64, i64)>(
-                r#"
-                SELECT 
-                    COUNT(CASE WHEN interaction_type = 'liked' THEN 1 END) as total_likes,
-                    COUNT(CASE WHEN interaction_type = 'disliked' THEN 1 END) as total_dislikes
-                FROM bigpicture.member_markers 
-                WHERE marker_id = $1
-                "#
-            )
-            .bind(mid)
-            .fetch_one(&self.pool)
+    /// 만료된 업로드 세션 삭제 (받은 조각 파일을 지울 수 있게 ID 반환)
+    pub async fn delete_expired_upload_sessions(&self, now: chrono::DateTime<chrono::Utc>) -> Result<Vec<uuid::Uuid>> {
+        let ids = sqlx::query_scalar::<_, uuid::Uuid>("DELETE FROM bigpicture.upload_sessions WHERE expires_at <= $1 RETURNING id")
+            .bind(now)
+            .fetch_all(&self.pool)
             .await?;
+        
+        Ok(ids)
+    }
 
-            serde_json::json!({
-                "marker_id": mid,
-                "total_likes": total_likes,
-                "total_dislikes": total_dislikes,
-                "total_reactions": total_likes + total_dislikes
-            })
-        } else {
-            // 전체 통계
-            let (total_likes, total_dislikes, total_markers) = sqlx::query_as::<_, (i64, i64, i64)>(
-                r#"
-                SELECT 
-                    COUNT(CASE WHEN interaction_type = 'liked' THEN 1 END) as total_likes,
-                    COUNT(CASE WHEN interaction_type = 'disliked' THEN 1 END) as total_dislikes,
-                    COUNT(DISTINCT marker_id) as total_markers
-                FROM bigpicture.member_markers
-                WHERE interaction_type IN ('liked', 'disliked')
-                "#
-            )
-            .fetch_one(&self.pool)
-            .await?;
+    /// 로컬 업로드 WebP 파일의 원본 (원본 ID, 파일 경로)
+    pub async fn get_local_original_of_webp(&self, webp_filename: &str) -> Result<Option<(uuid::Uuid, String)>> {
+        let row = sqlx::query(
+            r#"
+            SELECT o.id, o.file_path
+            FROM bigpicture.webp_images w
+            JOIN bigpicture.original_images o ON o.id = w.original_id
+            WHERE w.filename = $1
+            "#
+        )
+        .bind(webp_filename)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(row.map(|row| (row.get("id"), row.get("file_path"))))
+    }
 
-            serde_json::json!({
-                "total_likes": total_likes,
-                "total_dislikes": total_dislikes,
-                "total_reactions": total_likes + total_dislikes,
-                "total_markers_with_reactions": total_markers
-            })
-        };
+    /// 원본 이미지 기록 삭제 (WebP 기록도 함께 삭제됨)
+    pub async fn delete_original_image(&self, original_id: uuid::Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM bigpicture.original_images WHERE id = $1")
+            .bind(original_id)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
 
-        Ok(stats)
+    pub async fn save_image_variant(&self, image_url: &str, thumbnail_url: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bigpicture.image_variants (image_url, thumbnail_url)
+            VALUES ($1, $2)
+            ON CONFLICT (image_url)
+            DO UPDATE SET thumbnail_url = EXCLUDED.thumbnail_url
+            "#
+        )
+        .bind(image_url)
+        .bind(thumbnail_url)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
     }
 
-    /// 소셜 로그인 회원 생성 (트랜잭션으로 처리)
-    pub async fn create_social_member(
-        &self,
-        email: &str,
-        nickname: &str,
-        provider_type: &str,
-        provider_id: &str,
-        provider_email: Option<&str>,
-        profile_image_url: Option<&str>,
-        region: Option<&str>,
-        gender: Option<&str>,
-        birth_year: Option<i32>,
-        personality_type: Option<&str>,
-    ) -> Result<(Member, AuthProvider)> {
+    /// 대표 이미지가 없으면 가장 좋은 후보(해상도 > 선명도 > 순서)를 대표로 지정
+    /// 마커 썸네일이 비어 있으면 선택된 이미지로 채움. 선택된 이미지 ID 반환
+    pub async fn ensure_marker_cover_image(&self, marker_id: i64) -> Result<Option<i32>> {
         let mut tx = self.pool.begin().await?;
         
-        // 1. 회원 생성
-        let member = sqlx::query_as::<_, Member>(
+        let existing: Option<i32> = sqlx::query_scalar(
+            "SELECT id FROM bigpicture.marker_images WHERE marker_id = $1 AND is_primary = true AND taken_down_at IS NULL LIMIT 1"
+        )
+        .bind(marker_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if existing.is_some() {
+            return Ok(existing);
+        }
+        
+        // 자동 생성된 썸네일은 후보에서 제외
+        // 클라이언트가 S3 경로 또는 전체 URL 중 어느 쪽을 저장했든 저장 위치 키로 비교 (idx_image_quality_storage_key)
+        let candidate = sqlx::query(
             r#"
-            INSERT INTO bigpicture.members
-                (email, nickname, profile_image_url, region, gender, age, personality_type, email_verified)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING *
+            SELECT mi.id, mi.image_url
+            FROM bigpicture.marker_images mi
+            LEFT JOIN bigpicture.image_quality iq
+                ON bigpicture.image_storage_key(iq.image_url) = mi.storage_key
+            WHERE mi.marker_id = $1 AND mi.source_image_id IS NULL AND mi.taken_down_at IS NULL
+            ORDER BY (iq.width::BIGINT * iq.height::BIGINT) DESC NULLS LAST,
+                     iq.sharpness_score DESC NULLS LAST,
+                     mi.image_order ASC,
+                     mi.id ASC
+            LIMIT 1
             "#
         )
-        .bind(email)
-        .bind(nickname)
-        .bind(profile_image_url)
-        .bind(region)
-        .bind(gender)
-        .bind(birth_year)
-        .bind(personality_type)
-        .bind(provider_type != "email") // 소셜 로그인은 이메일 인증 완료로 간주
-        .fetch_one(&mut *tx)
+        .bind(marker_id)
+        .fetch_optional(&mut *tx)
         .await?;
-
-        // 2. 인증 제공자 정보 생성
-        let auth_provider = sqlx::query_as::<_, AuthProvider>(
+        
+        let (image_id, image_url): (i32, String) = match candidate {
+            Some(row) => (row.get("id"), row.get("image_url")),
+            None => return Ok(None),
+        };
+        
+        sqlx::query(
             r#"
-            INSERT INTO bigpicture.auth_providers
-                (member_id, provider_type, provider_id, provider_email)
-            VALUES ($1, $2, $3, $4)
-            RETURNING *
+            UPDATE bigpicture.marker_images
+            SET is_primary = true, updated_at = NOW()
+            WHERE id = $1
             "#
         )
-        .bind(member.id)
-        .bind(provider_type)
-        .bind(provider_id)
-        .bind(provider_email)
-        .fetch_one(&mut *tx)
+        .bind(image_id)
+        .execute(&mut *tx)
         .await?;
-
+        
+        sqlx::query(
+            r#"
+            UPDATE bigpicture.markers
+            SET thumbnail_img = $1, updated_at = NOW()
+            WHERE id = $2 AND (thumbnail_img IS NULL OR thumbnail_img = '')
+            "#
+        )
+        .bind(&image_url)
+        .bind(marker_id)
+        .execute(&mut *tx)
+        .await?;
+        
         tx.commit().await?;
-        Ok((member, auth_provider))
+        info!("🖼️ 마커 {} 대표 이미지 자동 선정: 이미지 {}", marker_id, image_id);
+        Ok(Some(image_id))
     }
 
-    /// 이메일/비밀번호 회원 생성
-    pub async fn create_email_member(
-        &self,
-        email: &str,
-        nickname: &str,
-        password_hash: &str,
-        profile_image_url: Option<&str>,
-        region: Option<&str>,
-        gender: Option<&str>,
-        birth_year: Option<i32>,
-        personality_type: Option<&str>,
-    ) -> Result<(Member, AuthProvider)> {
+    /// 마커 이미지 삭제 (자동 생성 썸네일 포함)
+    /// 다른 마커 이미지나 마커 썸네일이 더 쓰지 않는 파일은 정리 작업으로 예약 (S3 객체/로컬 파일 삭제)
+    pub async fn delete_marker_image(&self, image_id: i32) -> Result<bool> {
         let mut tx = self.pool.begin().await?;
         
-        // 1. 회원 생성
-        let member = sqlx::query_as::<_, Member>(
+        // 법적 보존 중인 이미지는 삭제하지 않음
+        let deleted = sqlx::query(
             r#"
-            INSERT INTO bigpicture.members
-                (email, nickname, profile_image_url, region, gender, age, personality_type, email_verified)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING *
+            DELETE FROM bigpicture.marker_images
+            WHERE (id = $1 OR source_image_id = $1)
+              AND EXISTS (SELECT 1 FROM bigpicture.marker_images WHERE id = $1 AND COALESCE(legal_hold, false) = false)
+            RETURNING id, marker_id, image_url, storage_key
             "#
         )
-        .bind(email)
-        .bind(nickname)
-        .bind(profile_image_url)
-        .bind(region)
-        .bind(gender)
-        .bind(birth_year)
-        .bind(personality_type)
-        .bind(false) // 이메일 인증 필요
-        .fetch_one(&mut *tx)
+        .bind(image_id)
+        .fetch_all(&mut *tx)
         .await?;
-
-        // 2. 인증 제공자 정보 생성
-        let auth_provider = sqlx::query_as::<_, AuthProvider>(
+        let Some(marker_id) = deleted.iter().find(|row| row.get::<i32, _>("id") == image_id).map(|row| row.get::<i64, _>("marker_id")) else {
+            return Ok(false);
+        };
+        let image_urls: Vec<String> = deleted.iter().map(|row| row.get("image_url")).collect();
+        let storage_keys: Vec<Option<String>> = deleted.iter().map(|row| row.get("storage_key")).collect();
+        
+        // 지운 이미지가 썸네일이면 비워 두고, 남은 이미지 중에서 다시 선정되게 함
+        sqlx::query(
+            "UPDATE bigpicture.markers SET thumbnail_img = NULL, updated_at = NOW() WHERE id = $1 AND thumbnail_img = ANY($2)"
+        )
+        .bind(marker_id)
+        .bind(&image_urls)
+        .execute(&mut *tx)
+        .await?;
+        
+        sqlx::query(
             r#"
-            INSERT INTO bigpicture.auth_providers
-                (member_id, provider_type, provider_id, provider_email, password_hash)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING *
+            INSERT INTO bigpicture.image_cleanup_jobs (image_url, storage_key, reason)
+            SELECT DISTINCT ON (d.storage_key) d.image_url, d.storage_key, 'image_deletion'
+            FROM unnest($1::TEXT[], $2::TEXT[]) AS d(image_url, storage_key)
+            WHERE d.storage_key IS NOT NULL
+              AND NOT EXISTS (SELECT 1 FROM bigpicture.marker_images mi WHERE mi.storage_key = d.storage_key)
+              AND NOT EXISTS (SELECT 1 FROM bigpicture.markers m WHERE bigpicture.image_storage_key(m.thumbnail_img) = d.storage_key)
             "#
         )
-        .bind(member.id)
-        .bind("email")
-        .bind(email) // 이메일을 provider_id로 사용
-        .bind(email)
-        .bind(password_hash)
-        .fetch_one(&mut *tx)
+        .bind(&image_urls)
+        .bind(&storage_keys)
+        .execute(&mut *tx)
         .await?;
-
+        
         tx.commit().await?;
-        Ok((member, auth_provider))
+        Ok(true)
     }
 
-    /// 소셜 로그인으로 기존 회원 찾기
-    pub async fn find_member_by_social_provider(
-        &self,
-        provider_type: &str,
-        provider_id: &str,
-    ) -> Result<Option<(Member, AuthProvider)>> {
-        // 먼저 auth_provider로 member_id 찾기
-        let auth_provider = sqlx::query_as::<_, AuthProvider>(
-            r#"
-            SELECT * FROM bigpicture.auth_providers 
-            WHERE provider_type = $1 AND provider_id = $2
-            "#
+    /// 법적 보존(legal hold) 중인 마커 이미지인지 확인
+    pub async fn is_marker_image_on_legal_hold(&self, image_id: i32) -> Result<bool> {
+        let on_hold: Option<Option<bool>> = sqlx::query_scalar(
+            "SELECT legal_hold FROM bigpicture.marker_images WHERE id = $1"
         )
-        .bind(provider_type)
-        .bind(provider_id)
+        .bind(image_id)
         .fetch_optional(&self.pool)
         .await?;
         
-        if let Some(auth) = auth_provider {
-            // member_id로 회원 정보 찾기
-            let member = sqlx::query_as::<_, Member>(
-                r#"
-                SELECT * FROM bigpicture.members 
-                WHERE id = $1
-                "#
-            )
-            .bind(auth.member_id)
+        Ok(on_hold.flatten().unwrap_or(false))
+    }
+
+    /// 이미지가 속한 마커 ID 조회
+    pub async fn get_marker_id_of_image(&self, image_id: i32) -> Result<Option<i64>> {
+        let marker_id = sqlx::query_scalar("SELECT marker_id FROM bigpicture.marker_images WHERE id = $1")
+            .bind(image_id)
             .fetch_optional(&self.pool)
             .await?;
-            
-            if let Some(m) = member {
-                Ok(Some((m, auth)))
-            } else {
-                Ok(None)
-            }
-        } else {
-            Ok(None)
-        }
+        
+        Ok(marker_id)
     }
 
-    /// 이메일로 기존 회원 찾기
-    pub async fn find_member_by_email(
-        &self,
-        email: &str,
-    ) -> Result<Option<(Member, AuthProvider)>> {
-        // 먼저 이메일로 회원 찾기
-        let member = sqlx::query_as::<_, Member>(
-            r#"
-            SELECT * FROM bigpicture.members 
-            WHERE email = $1
-            "#
+    /// 팔로우 (새로 팔로우했으면 true, 이미 팔로우 중이면 false)
+    pub async fn follow_member(&self, follower_id: i64, followee_id: i64) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO bigpicture.member_follows (follower_id, followee_id) VALUES ($1, $2) ON CONFLICT DO NOTHING"
         )
-        .bind(email)
-        .fetch_optional(&self.pool)
+        .bind(follower_id)
+        .bind(followee_id)
+        .execute(&self.pool)
         .await?;
         
-        if let Some(m) = member {
-            // member_id로 auth_provider 찾기
-            let auth_provider = sqlx::query_as::<_, AuthProvider>(
-                r#"
-                SELECT * FROM bigpicture.auth_providers 
-                WHERE member_id = $1
-                LIMIT 1
-                "#
-            )
-            .bind(m.id)
-            .fetch_optional(&self.pool)
-            .await?;
-            
-            if let Some(auth) = auth_provider {
-                Ok(Some((m, auth)))
-            } else {
-                Ok(None)
-            }
-        } else {
-            Ok(None)
-        }
+        Ok(result.rows_affected() > 0)
     }
 
-    /// 회원의 마지막 로그인 시간 업데이트
-    pub async fn update_last_login(&self, member_id: i64) -> Result<()> {
-        sqlx::query(
-            r#"
-            UPDATE bigpicture.members 
-            SET last_login_at = NOW(), updated_at = NOW()
-            WHERE id = $1
-            "#
+    /// 팔로우 취소 (팔로우 중이 아니었으면 false)
+    pub async fn unfollow_member(&self, follower_id: i64, followee_id: i64) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM bigpicture.member_follows WHERE follower_id = $1 AND followee_id = $2"
         )
-        .bind(member_id)
+        .bind(follower_id)
+        .bind(followee_id)
         .execute(&self.pool)
         .await?;
         
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
-    /// 회원에게 추가 소셜 로그인 연결
-    pub async fn link_social_provider(
-        &self,
-        member_id: i64,
-        provider_type: &str,
-        provider_id: &str,
-        provider_email: Option<&str>,
-    ) -> Result<AuthProvider> {
-        let auth_provider = sqlx::query_as::<_, AuthProvider>(
-            r#"
-            INSERT INTO bigpicture.auth_providers
-                (member_id, provider_type, provider_id, provider_email)
-            VALUES ($1, $2, $3, $4)
-            RETURNING *
-            "#
+    pub async fn is_following(&self, follower_id: i64, followee_id: i64) -> Result<bool> {
+        let following: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM bigpicture.member_follows WHERE follower_id = $1 AND followee_id = $2)"
         )
-        .bind(member_id)
-        .bind(provider_type)
-        .bind(provider_id)
-        .bind(provider_email)
+        .bind(follower_id)
+        .bind(followee_id)
         .fetch_one(&self.pool)
         .await?;
-
-        Ok(auth_provider)
+        
+        Ok(following)
     }
 
-    // 관심사 연결
-    pub async fn add_member_interests(&self, member_id: i64, interests: &[String]) -> Result<()> {
-        for interest_name in interests {
-            // 관심사 id 찾기 또는 생성
-            let interest = sqlx::query_as::<_, Interest>(
-                r#"
-                INSERT INTO bigpicture.interests (name, is_active)
-                VALUES ($1, true)
-                ON CONFLICT (name) DO UPDATE SET is_active = true
+    /// 컬렉션 만들기
+    pub async fn create_marker_collection(&self, member_id: i64, name: &str, description: Option<&str>, is_public: bool) -> Result<MarkerCollection> {
+        let collection = sqlx::query_as::<_, MarkerCollection>(&format!(
+            r#"
+            WITH c AS (
+                INSERT INTO bigpicture.marker_collections (member_id, name, description, is_public)
+                VALUES ($1, $2, $3, $4)
                 RETURNING *
-                "#
             )
-            .bind(interest_name)
+            SELECT {} FROM c
+            "#,
+            MARKER_COLLECTION_COLUMNS
+        ))
+        .bind(member_id)
+        .bind(name)
+        .bind(description)
+        .bind(is_public)
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok(collection)
+    }
+
+    pub async fn get_marker_collection(&self, collection_id: i64) -> Result<Option<MarkerCollection>> {
+        let collection = sqlx::query_as::<_, MarkerCollection>(&format!(
+            "SELECT {} FROM bigpicture.marker_collections c WHERE c.id = $1",
+            MARKER_COLLECTION_COLUMNS
+        ))
+        .bind(collection_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(collection)
+    }
+
+    /// 회원의 컬렉션 목록 (include_private가 아니면 공개 컬렉션만, 최근 수정순)
+    pub async fn get_member_collections(&self, member_id: i64, include_private: bool) -> Result<Vec<MarkerCollection>> {
+        let collections = sqlx::query_as::<_, MarkerCollection>(&format!(
+            r#"
+            SELECT {} FROM bigpicture.marker_collections c
+            WHERE c.member_id = $1 AND ($2 OR c.is_public)
+            ORDER BY c.updated_at DESC, c.id DESC
+            "#,
+            MARKER_COLLECTION_COLUMNS
+        ))
+        .bind(member_id)
+        .bind(include_private)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(collections)
+    }
+
+    pub async fn count_member_collections(&self, member_id: i64) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.marker_collections WHERE member_id = $1")
+            .bind(member_id)
             .fetch_one(&self.pool)
             .await?;
-            // 연결
-            sqlx::query(
-                r#"
-                INSERT INTO bigpicture.member_interests (member_id, interest_id)
-                VALUES ($1, $2)
-                ON CONFLICT DO NOTHING
-                "#
+        
+        Ok(count)
+    }
+
+    /// 컬렉션 이름/설명/공개 여부 수정 (None은 그대로, 설명을 빈 문자열로 주면 지움)
+    pub async fn update_marker_collection(
+        &self,
+        collection_id: i64,
+        name: Option<&str>,
+        description: Option<&str>,
+        is_public: Option<bool>,
+    ) -> Result<Option<MarkerCollection>> {
+        let collection = sqlx::query_as::<_, MarkerCollection>(&format!(
+            r#"
+            WITH c AS (
+                UPDATE bigpicture.marker_collections
+                SET name = COALESCE($2, name),
+                    description = CASE WHEN $3::TEXT IS NULL THEN description ELSE NULLIF($3, '') END,
+                    is_public = COALESCE($4, is_public),
+                    updated_at = NOW()
+                WHERE id = $1
+                RETURNING *
             )
-            .bind(member_id)
-            .bind(interest.id)
+            SELECT {} FROM c
+            "#,
+            MARKER_COLLECTION_COLUMNS
+        ))
+        .bind(collection_id)
+        .bind(name)
+        .bind(description)
+        .bind(is_public)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(collection)
+    }
+
+    pub async fn delete_marker_collection(&self, collection_id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM bigpicture.marker_collections WHERE id = $1")
+            .bind(collection_id)
             .execute(&self.pool)
             .await?;
-        }
-        Ok(())
-    }
-    // 취미 연결
-    pub async fn add_member_hobbies(&self, member_id: i64, hobbies: &[String]) -> Result<()> {
-        for hobby_name in hobbies {
-            // 취미 id 찾기 또는 생성
-            let hobby = sqlx::query_as::<_, Hobby>(
-                r#"
-                INSERT INTO bigpicture.hobbies (name, is_active)
-                VALUES ($1, true)
-                ON CONFLICT (name) DO UPDATE SET is_active = true
-                RETURNING *
-                "#
-            )
-            .bind(hobby_name)
-            .fetch_one(&self.pool)
-            .await?;
-            // 연결
-            sqlx::query(
-                r#"
-                INSERT INTO bigpicture.member_hobbies (member_id, hobby_id)
-                VALUES ($1, $2)
-                ON CONFLICT DO NOTHING
-                "#
-            )
-            .bind(member_id)
-            .bind(hobby.id)
-            .execute(&self.pool)
-            .await?;
-        }
-        Ok(())
-    }
-
-    /// 마커 생성
-    pub async fn create_marker(
-        &self,
-        member_id: i64,
-        latitude: f64,
-        longitude: f64,
-        emotion_tag: &str,
-        emotion_tag_input: Option<&str>, // 사용자가 입력한 감성태그들
-        emotion: Option<&str>, // 기존 emotion 필드 (호환성 유지)
-        description: &str,
-        author: &str,
-        thumbnail_img: Option<&str>,
-        sharing_option: Option<&str>, // 추가: 공유 옵션
-    ) -> Result<Marker> {
-        let marker = sqlx::query_as::<_, Marker>(
-            r#"
-            INSERT INTO bigpicture.markers
-                (member_id, location, emotion_tag, emotion_tag_input, emotion, description, author, thumbnail_img, sharing_option)
-            VALUES ($1, ST_SetSRID(ST_MakePoint($2, $3), 4326)::geography, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, created_at, updated_at
-            "#
-        )
-        .bind(member_id)
-        .bind(longitude) // PostGIS는 (longitude, latitude) 순서
-        .bind(latitude)
-        .bind(emotion_tag)
-        .bind(emotion_tag_input)
-        .bind(emotion)
-        .bind(description)
-        .bind(author)
-        .bind(thumbnail_img)
-        .bind(sharing_option.unwrap_or("public"))
-        .fetch_one(&self.pool)
-        .await?;
-
-        Ok(marker)
+        
+        Ok(result.rows_affected() > 0)
     }
 
-    /// 마커 좋아요/싫어요 처리
-    pub async fn toggle_marker_reaction(
-        &self,
-        member_id: i64,
-        marker_id: i64,
-        reaction_type: &str, // "like" 또는 "dislike"
-    ) -> Result<(i32, i32)> { // (좋아요 수, 싫어요 수) 반환
-        info!("🔍 SQL 로깅 시작: toggle_marker_reaction - member_id: {}, marker_id: {}, reaction_type: {}", member_id, marker_id, reaction_type);
+    /// 컬렉션 끝에 마커 추가 (새로 담았으면 true, 이미 있으면 false)
+    pub async fn add_marker_to_collection(&self, collection_id: i64, marker_id: i64) -> Result<bool> {
         let mut tx = self.pool.begin().await?;
-        
-        // 기존 반응 확인
-        let existing = sqlx::query_as::<_, MemberMarker>(
+        let result = sqlx::query(
             r#"
-            SELECT * FROM bigpicture.member_markers 
-            WHERE member_id = $1 AND marker_id = $2 AND interaction_type IN ('liked', 'disliked')
+            INSERT INTO bigpicture.marker_collection_items (collection_id, marker_id, position)
+            SELECT $1, $2, COALESCE(MAX(position), 0) + 1
+            FROM bigpicture.marker_collection_items WHERE collection_id = $1
+            ON CONFLICT DO NOTHING
             "#
         )
-        .bind(member_id)
+        .bind(collection_id)
         .bind(marker_id)
-        .fetch_optional(&mut *tx)
+        .execute(&mut *tx)
         .await?;
-
-        if let Some(existing_reaction) = existing {
-            if existing_reaction.interaction_type == reaction_type {
-                // 같은 반응이면 제거
-                sqlx::query(
-                    "DELETE FROM bigpicture.member_markers WHERE id = $1"
-                )
-                .bind(existing_reaction.id)
-                .execute(&mut *tx)
-                .await?;
-
-                // 마커 카운트 감소
-                let update_query = match reaction_type {
-                    "liked" => "UPDATE bigpicture.markers SET likes = GREATEST(likes - 1, 0) WHERE id = $1",
-                    "disliked" => "UPDATE bigpicture.markers SET dislikes = GREATEST(dislikes - 1, 0) WHERE id = $1",
-                    _ => return Err(anyhow::anyhow!("Invalid reaction type")),
-                };
-                sqlx::query(update_query)
-                    .bind(marker_id)
-                    .execute(&mut *tx)
-                    .await?;
-            } else {
-                // 다른 반응이면 변경
-                sqlx::query(
-                    "UPDATE bigpicture.member_markers SET interaction_type = $1, updated_at = NOW() WHERE id = $2"
-                )
-                .bind(reaction_type)
-                .bind(existing_reaction.id)
+        let added = result.rows_affected() > 0;
+        if added {
+            sqlx::query("UPDATE bigpicture.marker_collections SET updated_at = NOW() WHERE id = $1")
+                .bind(collection_id)
                 .execute(&mut *tx)
                 .await?;
+        }
+        tx.commit().await?;
+        
+        Ok(added)
+    }
 
-                // 마커 카운트 업데이트
-                if reaction_type == "liked" {
-                    sqlx::query(
-                        "UPDATE bigpicture.markers SET likes = likes + 1, dislikes = GREATEST(dislikes - 1, 0) WHERE id = $1"
-                    )
-                    .bind(marker_id)
-                    .execute(&mut *tx)
-                    .await?;
-                } else {
-                    sqlx::query(
-                        "UPDATE bigpicture.markers SET dislikes = dislikes + 1, likes = GREATEST(likes - 1, 0) WHERE id = $1"
-                    )
-                    .bind(marker_id)
-                    .execute(&mut *tx)
-                    .await?;
-                }
-            }
-        } else {
-            // 새로운 반응 추가
-            sqlx::query(
-                r#"
-                INSERT INTO bigpicture.member_markers
-                    (member_id, marker_id, interaction_type)
-                VALUES ($1, $2, $3)
-                "#
-            )
-            .bind(member_id)
+    /// 컬렉션에서 마커 빼기 (담겨 있지 않았으면 false)
+    pub async fn remove_marker_from_collection(&self, collection_id: i64, marker_id: i64) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query("DELETE FROM bigpicture.marker_collection_items WHERE collection_id = $1 AND marker_id = $2")
+            .bind(collection_id)
             .bind(marker_id)
-            .bind(reaction_type)
             .execute(&mut *tx)
             .await?;
-
-            // 마커 카운트 증가
-            let update_query = match reaction_type {
-                "liked" => "UPDATE bigpicture.markers SET likes = likes + 1 WHERE id = $1",
-                "disliked" => "UPDATE bigpicture.markers SET dislikes = dislikes + 1 WHERE id = $1",
-                _ => return Err(anyhow::anyhow!("Invalid reaction type")),
-            };
-            sqlx::query(update_query)
-                .bind(marker_id)
+        let removed = result.rows_affected() > 0;
+        if removed {
+            sqlx::query("UPDATE bigpicture.marker_collections SET updated_at = NOW() WHERE id = $1")
+                .bind(collection_id)
                 .execute(&mut *tx)
                 .await?;
         }
-
-        // 업데이트된 카운트 조회
-        let counts = sqlx::query_as::<_, (i32, i32)>(
-            "SELECT likes, dislikes FROM bigpicture.markers WHERE id = $1"
-        )
-        .bind(marker_id)
-        .fetch_one(&mut *tx)
-        .await?;
-
         tx.commit().await?;
-        info!("✅ SQL 로깅 완료: toggle_marker_reaction - 최종 결과: likes={}, dislikes={}", counts.0, counts.1);
-        Ok(counts)
+        
+        Ok(removed)
     }
 
-    /// 마커 조회 기록 추가
-    pub async fn add_marker_view(&self, member_id: i64, marker_id: i64) -> Result<()> {
+    /// 컬렉션 마커 순서 바꾸기 (marker_ids가 지금 담긴 마커와 정확히 같지 않으면 바꾸지 않고 false)
+    pub async fn reorder_collection_markers(&self, collection_id: i64, marker_ids: &[i64]) -> Result<bool> {
         let mut tx = self.pool.begin().await?;
-        
-        // 기존 조회 기록 확인
-        let existing = sqlx::query_as::<_, MemberMarker>(
-            r#"
-            SELECT * FROM bigpicture.member_markers 
-            WHERE member_id = $1 AND marker_id = $2 AND interaction_type = 'viewed'
-            "#
+        let mut current: Vec<i64> = sqlx::query_scalar(
+            "SELECT marker_id FROM bigpicture.marker_collection_items WHERE collection_id = $1 FOR UPDATE"
         )
-        .bind(member_id)
-        .bind(marker_id)
-        .fetch_optional(&mut *tx)
+        .bind(collection_id)
+        .fetch_all(&mut *tx)
         .await?;
-
-        if existing.is_none() {
-            // 새로운 조회 기록 추가
-            sqlx::query(
-                r#"
-                INSERT INTO bigpicture.member_markers
-                    (member_id, marker_id, interaction_type)
-                VALUES ($1, $2, 'viewed')
-                "#
-            )
-            .bind(member_id)
-            .bind(marker_id)
-            .execute(&mut *tx)
-            .await?;
-
-            // 마커 조회수 증가
-            sqlx::query(
-                "UPDATE bigpicture.markers SET views = views + 1 WHERE id = $1"
-            )
-            .bind(marker_id)
-            .execute(&mut *tx)
-            .await?;
+        let mut requested = marker_ids.to_vec();
+        current.sort_unstable();
+        requested.sort_unstable();
+        if current != requested {
+            return Ok(false);
         }
-
-        tx.commit().await?;
-        Ok(())
-    }
-
-    /// 마커 북마크 토글
-    pub async fn toggle_marker_bookmark(&self, member_id: i64, marker_id: i64) -> Result<bool> {
-        let mut tx = self.pool.begin().await?;
         
-        // 기존 북마크 확인
-        let existing = sqlx::query_as::<_, MemberMarker>(
+        sqlx::query(
             r#"
-            SELECT * FROM bigpicture.member_markers 
-            WHERE member_id = $1 AND marker_id = $2 AND interaction_type = 'bookmarked'
+            UPDATE bigpicture.marker_collection_items i
+            SET position = o.position
+            FROM unnest($2::BIGINT[]) WITH ORDINALITY AS o(marker_id, position)
+            WHERE i.collection_id = $1 AND i.marker_id = o.marker_id
             "#
         )
-        .bind(member_id)
-        .bind(marker_id)
-        .fetch_optional(&mut *tx)
+        .bind(collection_id)
+        .bind(marker_ids)
+        .execute(&mut *tx)
         .await?;
-
-        let is_bookmarked = if let Some(existing_bookmark) = existing {
-            // 북마크 제거
-            sqlx::query(
-                "DELETE FROM bigpicture.member_markers WHERE id = $1"
-            )
-            .bind(existing_bookmark.id)
-            .execute(&mut *tx)
-            .await?;
-            false
-        } else {
-            // 북마크 추가
-            sqlx::query(
-                r#"
-                INSERT INTO bigpicture.member_markers
-                    (member_id, marker_id, interaction_type)
-                VALUES ($1, $2, 'bookmarked')
-                "#
-            )
-            .bind(member_id)
-            .bind(marker_id)
+        sqlx::query("UPDATE bigpicture.marker_collections SET updated_at = NOW() WHERE id = $1")
+            .bind(collection_id)
             .execute(&mut *tx)
             .await?;
-            true
-        };
-
         tx.commit().await?;
-        Ok(is_bookmarked)
+        
+        Ok(true)
     }
 
-    /// 유저가 생성한 마커 목록 조회
-    pub async fn get_member_created_markers(&self, member_id: i64, limit: Option<i32>) -> Result<Vec<Marker>> {
-        let markers = sqlx::query_as::<_, Marker>(
+    /// 컬렉션에 담긴 마커 (순서대로, 요청한 회원이 볼 수 있는 게시된 마커만)
+    pub async fn get_collection_markers(&self, collection_id: i64, viewer_id: Option<i64>) -> Result<Vec<Marker>> {
+        let markers = sqlx::query_as::<_, Marker>(&format!(
             r#"
-            SELECT id, ST_AsText(location) as location, emotion_tag, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, member_id, created_at, updated_at 
-            FROM bigpicture.markers 
-            WHERE member_id = $1 
-            ORDER BY created_at DESC 
-            LIMIT $2
-            "#
-        )
-        .bind(member_id)
-        .bind(limit.unwrap_or(50))
+            SELECT m.id, m.member_id, ST_AsText(m.location) as location, m.emotion_tag, m.emotion_tag_input, m.emotion, m.description, m.sharing_option,
+                   m.likes, m.dislikes, m.views, m.author, m.thumbnail_img, m.language, m.created_at, m.updated_at
+            FROM bigpicture.marker_collection_items i
+            JOIN bigpicture.markers m ON m.id = i.marker_id
+            WHERE i.collection_id = $1 AND m.taken_down_at IS NULL AND m.status = 'published' AND {}
+            ORDER BY i.position, i.added_at
+            "#,
+            visibility_condition("m.", viewer_id)
+        ))
+        .bind(collection_id)
         .fetch_all(&self.pool)
         .await?;
+        
         Ok(markers)
     }
 
-    /// 유저가 좋아요한 마커 목록 조회
-    pub async fn get_member_liked_markers(&self, member_id: i64, limit: Option<i32>) -> Result<Vec<Marker>> {
-        let markers = sqlx::query_as::<_, Marker>(
-            r#"
-            SELECT m.id, ST_AsText(m.location) as location, m.emotion_tag, m.emotion, m.description, m.sharing_option, m.likes, m.dislikes, m.views, m.author, m.thumbnail_img, m.member_id, m.created_at, m.updated_at 
-            FROM bigpicture.markers m
-            INNER JOIN bigpicture.member_markers mm ON m.id = mm.marker_id
-            WHERE mm.member_id = $1 AND mm.interaction_type = 'liked'
-            ORDER BY mm.created_at DESC 
-            LIMIT $2
-            "#
+    /// 관리자 회원인지 확인
+    pub async fn is_admin_member(&self, member_id: i64) -> Result<bool> {
+        let is_admin: Option<Option<bool>> = sqlx::query_scalar(
+            "SELECT is_admin FROM bigpicture.members WHERE id = $1 AND is_active = true"
         )
         .bind(member_id)
-        .bind(limit.unwrap_or(50))
-        .fetch_all(&self.pool)
+        .fetch_optional(&self.pool)
         .await?;
-        Ok(markers)
+        
+        Ok(is_admin.flatten().unwrap_or(false))
     }
 
-    /// 유저가 북마크한 마커 목록 조회
-    pub async fn get_member_bookmarked_markers(&self, member_id: i64, limit: Option<i32>) -> Result<Vec<Marker>> {
-        let markers = sqlx::query_as::<_, Marker>(
+    /// 인증 미들웨어용 회원 상태 (정지/탈퇴 여부, 강제 로그아웃 시각)
+    pub async fn get_member_session_state(&self, member_id: i64) -> Result<Option<MemberSessionState>> {
+        let state = sqlx::query_as::<_, MemberSessionState>(
+            "SELECT is_active, tokens_revoked_at FROM bigpicture.members WHERE id = $1"
+        )
+        .bind(member_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(state)
+    }
+
+    /// 관리자 회원 정지/재활성화/강제 로그아웃 적용 및 감사 로그 기록 (한 트랜잭션)
+    /// 정지와 강제 로그아웃은 지금까지 발급된 토큰을 모두 무효화함
+    /// 탈퇴했거나 없는 회원이면 None
+    pub async fn apply_member_moderation(&self, action: &MemberModerationAction, now: chrono::DateTime<chrono::Utc>) -> Result<Option<Member>> {
+        let mut tx = self.pool.begin().await?;
+        
+        let member = match action.action.as_str() {
+            "suspend" => sqlx::query_as::<_, Member>(
+                r#"
+                UPDATE bigpicture.members
+                SET is_active = false, suspended_at = $2, suspension_reason = $3, tokens_revoked_at = $2, updated_at = NOW()
+                WHERE id = $1 AND deleted_at IS NULL
+                RETURNING *
+                "#
+            )
+            .bind(action.member_id)
+            .bind(now)
+            .bind(&action.reason),
+            "reactivate" => sqlx::query_as::<_, Member>(
+                r#"
+                UPDATE bigpicture.members
+                SET is_active = true, suspended_at = NULL, suspension_reason = NULL, updated_at = NOW()
+                WHERE id = $1 AND deleted_at IS NULL
+                RETURNING *
+                "#
+            )
+            .bind(action.member_id),
+            "force_logout" => sqlx::query_as::<_, Member>(
+                "UPDATE bigpicture.members SET tokens_revoked_at = $2 WHERE id = $1 AND deleted_at IS NULL RETURNING *"
+            )
+            .bind(action.member_id)
+            .bind(now),
+            // trust_new, trust_basic, trust_trusted: 등급 지정 / trust_auto: 자동 계산으로 되돌림
+            "trust_new" | "trust_basic" | "trust_trusted" | "trust_auto" => sqlx::query_as::<_, Member>(
+                "UPDATE bigpicture.members SET trust_level_override = $2, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING *"
+            )
+            .bind(action.member_id)
+            .bind(action.action.strip_prefix("trust_").filter(|level| *level != "auto")),
+            other => return Err(anyhow::anyhow!("알 수 없는 회원 조치: {}", other)),
+        }
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(member) = member else {
+            return Ok(None);
+        };
+        
+        sqlx::query(
             r#"
-            SELECT m.id, ST_AsText(m.location) as location, m.emotion_tag, m.emotion, m.description, m.sharing_option, m.likes, m.dislikes, m.views, m.author, m.thumbnail_img, m.member_id, m.created_at, m.updated_at 
-            FROM bigpicture.markers m
-            INNER JOIN bigpicture.member_markers mm ON m.id = mm.marker_id
-            WHERE mm.member_id = $1 AND mm.interaction_type = 'bookmarked'
-            ORDER BY mm.created_at DESC 
-            LIMIT $2
+            INSERT INTO bigpicture.member_moderation_audit
+                (admin_member_id, member_id, action, reason, client_ip, user_agent, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             "#
         )
-        .bind(member_id)
-        .bind(limit.unwrap_or(50))
-        .fetch_all(&self.pool)
+        .bind(action.admin_member_id)
+        .bind(action.member_id)
+        .bind(&action.action)
+        .bind(&action.reason)
+        .bind(&action.client_ip)
+        .bind(&action.user_agent)
+        .bind(now)
+        .execute(&mut *tx)
         .await?;
-        Ok(markers)
+        
+        tx.commit().await?;
+        Ok(Some(member))
     }
 
-    /// 마커의 상세 정보 조회
-    pub async fn get_marker_detail(&self, marker_id: i64) -> Result<Option<Marker>> {
-        let marker = sqlx::query_as::<_, Marker>(
-            "SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, created_at, updated_at FROM bigpicture.markers WHERE id = $1"
+    /// 신뢰 등급 계산에 필요한 회원 이력 (since 이후 생성한 마커 수 포함, 탈퇴 회원은 None)
+    pub async fn get_member_trust_stats(&self, member_id: i64, since: chrono::DateTime<chrono::Utc>) -> Result<Option<MemberTrustStats>> {
+        let stats = sqlx::query_as::<_, MemberTrustStats>(
+            r#"
+            SELECT m.created_at,
+                   m.trust_level_override,
+                   COUNT(mk.id) FILTER (WHERE mk.taken_down_at IS NULL) AS marker_count,
+                   COUNT(mk.id) FILTER (WHERE mk.taken_down_at IS NOT NULL) AS taken_down_count,
+                   COUNT(mk.id) FILTER (WHERE mk.created_at >= $2) AS markers_last_day
+            FROM bigpicture.members m
+            LEFT JOIN bigpicture.markers mk ON mk.member_id = m.id
+            WHERE m.id = $1 AND m.deleted_at IS NULL
+            GROUP BY m.id
+            "#
         )
-        .bind(marker_id)
+        .bind(member_id)
+        .bind(since)
         .fetch_optional(&self.pool)
         .await?;
+        
+        Ok(stats)
+    }
 
-        Ok(marker)
+    /// 관리자 회원 목록 (이메일 부분 일치, 로그인 수단, 가입일 범위, 활성 여부 필터)
+    pub async fn list_members_for_admin(&self, filter: &MemberListFilter) -> Result<Vec<AdminMemberRow>> {
+        let rows = sqlx::query_as::<_, AdminMemberRow>(
+            r#"
+            SELECT m.*,
+                   COALESCE(
+                       (SELECT array_agg(ap.provider_type ORDER BY ap.provider_type)
+                        FROM bigpicture.auth_providers ap WHERE ap.member_id = m.id),
+                       ARRAY[]::VARCHAR[]
+                   ) AS provider_types
+            FROM bigpicture.members m
+            WHERE ($1::TEXT IS NULL OR m.email ILIKE '%' || $1 || '%')
+              AND ($2::TEXT IS NULL OR EXISTS (
+                      SELECT 1 FROM bigpicture.auth_providers ap
+                      WHERE ap.member_id = m.id AND ap.provider_type = $2))
+              AND ($3::TIMESTAMPTZ IS NULL OR m.created_at >= $3)
+              AND ($4::TIMESTAMPTZ IS NULL OR m.created_at < $4)
+              AND ($5::BOOLEAN IS NULL OR m.is_active = $5)
+            ORDER BY m.created_at DESC, m.id DESC
+            LIMIT $6 OFFSET $7
+            "#
+        )
+        .bind(&filter.email)
+        .bind(&filter.provider_type)
+        .bind(filter.created_from)
+        .bind(filter.created_to)
+        .bind(filter.is_active)
+        .bind(filter.limit)
+        .bind(filter.offset)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows)
     }
 
-    /// 3번 사용자와 마커 연결 (복합키 사용)
-    pub async fn connect_member_to_marker(&self, member_id: i64, marker_id: i64, interaction_type: &str) -> Result<()> {
+    /// 관리자 게시 중단/복구 적용 및 감사 로그 기록 (한 트랜잭션)
+    /// 게시 중단 시 대상(마커는 소속 이미지 포함)에 법적 보존을 걸고, 복구 시에는 요청한 경우에만 해제
+    /// 대상이 없으면 false
+    pub async fn apply_content_takedown(&self, action: &ContentTakedownAction) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let takedown = action.action == "takedown";
+        
+        let affected = match action.target_type.as_str() {
+            "marker" => {
+                let result = sqlx::query(
+                    r#"
+                    UPDATE bigpicture.markers
+                    SET taken_down_at = CASE WHEN $2 THEN COALESCE(taken_down_at, NOW()) ELSE NULL END,
+                        legal_hold = CASE WHEN $2 THEN true WHEN $3 THEN false ELSE legal_hold END,
+                        updated_at = NOW()
+                    WHERE id = $1
+                    "#
+                )
+                .bind(action.target_id)
+                .bind(takedown)
+                .bind(action.release_legal_hold)
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query(
+                    r#"
+                    UPDATE bigpicture.marker_images
+                    SET legal_hold = CASE WHEN $2 THEN true WHEN $3 THEN false ELSE legal_hold END
+                    WHERE marker_id = $1
+                    "#
+                )
+                .bind(action.target_id)
+                .bind(takedown)
+                .bind(action.release_legal_hold)
+                .execute(&mut *tx)
+                .await?;
+                result.rows_affected()
+            }
+            "marker_image" => {
+                sqlx::query(
+                    r#"
+                    UPDATE bigpicture.marker_images
+                    SET taken_down_at = CASE WHEN $2 THEN COALESCE(taken_down_at, NOW()) ELSE NULL END,
+                        legal_hold = CASE WHEN $2 THEN true WHEN $3 THEN false ELSE legal_hold END,
+                        is_primary = CASE WHEN $2 THEN false ELSE is_primary END,
+                        updated_at = NOW()
+                    WHERE id = $1
+                    "#
+                )
+                .bind(action.target_id as i32)
+                .bind(takedown)
+                .bind(action.release_legal_hold)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected()
+            }
+            other => return Err(anyhow::anyhow!("지원하지 않는 대상 타입: {}", other)),
+        };
+        if affected == 0 {
+            return Ok(false);
+        }
+        
         sqlx::query(
             r#"
-            INSERT INTO bigpicture.member_markers (member_id, marker_id, interaction_type)
-            VALUES ($1, $2, $3)
-            ON CONFLICT (member_id, marker_id, interaction_type) 
-            DO UPDATE SET updated_at = NOW()
+            INSERT INTO bigpicture.content_takedown_audit
+                (admin_member_id, target_type, target_id, action, reason, legal_reference, legal_hold, client_ip, user_agent)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             "#
         )
-        .bind(member_id)
-        .bind(marker_id)
-        .bind(interaction_type)
-        .execute(&self.pool)
+        .bind(action.admin_member_id)
+        .bind(&action.target_type)
+        .bind(action.target_id)
+        .bind(&action.action)
+        .bind(&action.reason)
+        .bind(&action.legal_reference)
+        .bind(takedown || !action.release_legal_hold)
+        .bind(&action.client_ip)
+        .bind(&action.user_agent)
+        .execute(&mut *tx)
         .await?;
         
-        Ok(())
+        tx.commit().await?;
+        info!("⚖️ 콘텐츠 {} 적용: {} {} (관리자 {})", action.action, action.target_type, action.target_id, action.admin_member_id);
+        Ok(true)
     }
 
-    /// 3번 사용자의 모든 마커 상호작용 조회
-    pub async fn get_member_marker_interactions(&self, member_id: i64) -> Result<Vec<MemberMarker>> {
-        let recs = sqlx::query_as::<_, MemberMarker>(
+    /// 게시 중단 감사 로그 조회 (최신순)
+    pub async fn get_takedown_audit(&self, target_type: Option<&str>, target_id: Option<i64>, limit: i64) -> Result<Vec<ContentTakedownAudit>> {
+        let rows = sqlx::query_as::<_, ContentTakedownAudit>(
             r#"
-            SELECT id, member_id, marker_id, interaction_type, created_at, updated_at
-            FROM bigpicture.member_markers 
-            WHERE member_id = $1
-            ORDER BY created_at DESC
+            SELECT id, admin_member_id, target_type, target_id, action, reason, legal_reference, legal_hold, client_ip, user_agent, created_at
+            FROM bigpicture.content_takedown_audit
+            WHERE ($1::VARCHAR IS NULL OR target_type = $1)
+              AND ($2::BIGINT IS NULL OR target_id = $2)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3
             "#
         )
-        .bind(member_id)
+        .bind(target_type)
+        .bind(target_id)
+        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
         
-        Ok(recs)
+        Ok(rows)
     }
 
-    /// 3번 사용자의 특정 상호작용 타입 마커 조회
-    pub async fn get_member_markers_by_interaction(&self, member_id: i64, interaction_type: &str) -> Result<Vec<MemberMarker>> {
-        let recs = sqlx::query_as::<_, MemberMarker>(
+    /// API 키 발급 (원문 키는 해시로만 저장)
+    pub async fn create_api_key(
+        &self,
+        raw_key: &str,
+        name: &str,
+        scope: &str,
+        daily_quota: i32,
+        created_by: i64,
+    ) -> Result<ApiKey> {
+        let key = sqlx::query_as::<_, ApiKey>(
             r#"
-            SELECT id, member_id, marker_id, interaction_type, created_at, updated_at
-            FROM bigpicture.member_markers 
-            WHERE member_id = $1 AND interaction_type = $2
-            ORDER BY created_at DESC
+            INSERT INTO bigpicture.api_keys (key_hash, key_prefix, name, scope, daily_quota, created_by)
+            VALUES (encode(sha256(convert_to($1, 'UTF8')), 'hex'), left($1, 12), $2, $3, $4, $5)
+            RETURNING id, key_prefix, name, scope, daily_quota, created_by, is_active, created_at, last_used_at
             "#
         )
-        .bind(member_id)
-        .bind(interaction_type)
-        .fetch_all(&self.pool)
+        .bind(raw_key)
+        .bind(name)
+        .bind(scope)
+        .bind(daily_quota)
+        .bind(created_by)
+        .fetch_one(&self.pool)
         .await?;
         
-        Ok(recs)
+        Ok(key)
     }
 
-    /// 3번 사용자와 마커 상세 정보 함께 조회 (JOIN)
-    pub async fn get_member_markers_with_details(&self, member_id: i64) -> Result<Vec<(MemberMarker, Marker)>> {
-        let recs = sqlx::query(
+    /// 원문 키로 활성 API 키 조회
+    pub async fn find_api_key(&self, raw_key: &str) -> Result<Option<ApiKey>> {
+        let key = sqlx::query_as::<_, ApiKey>(
             r#"
-            SELECT 
-                mm.id as mm_id, mm.member_id, mm.marker_id, mm.interaction_type, 
-                mm.created_at as mm_created_at, mm.updated_at as mm_updated_at,
-                m.id as m_id, m.member_id, ST_AsText(m.location) as location, m.emotion_tag, m.emotion,
-                m.description, m.sharing_option, m.likes, m.dislikes, m.views, m.author, m.thumbnail_img,
-                m.created_at as m_created_at, m.updated_at as m_updated_at
-            FROM bigpicture.member_markers mm
-            JOIN bigpicture.markers m ON mm.marker_id = m.id
-            WHERE mm.member_id = $1
-            ORDER BY mm.created_at DESC
+            SELECT id, key_prefix, name, scope, daily_quota, created_by, is_active, created_at, last_used_at
+            FROM bigpicture.api_keys
+            WHERE key_hash = encode(sha256(convert_to($1, 'UTF8')), 'hex') AND is_active = true
             "#
         )
-        .bind(member_id)
-        .fetch_all(&self.pool)
+        .bind(raw_key)
+        .fetch_optional(&self.pool)
         .await?;
         
-        let mut result = Vec::new();
-        for row in recs {
-            let member_marker = MemberMarker {
-                id: row.get("mm_id"),
-                member_id: row.get("member_id"),
-                marker_id: row.get("marker_id"),
-                interaction_type: row.get("interaction_type"),
-                created_at: row.get("mm_created_at"),
-                updated_at: row.get("mm_updated_at"),
-            };
-            
-            let marker = Marker {
-                id: row.get("m_id"),
-                member_id: row.get("member_id"),
-                location: row.get("location"),
-                emotion_tag: row.get("emotion_tag"),
-                emotion_tag_input: row.get("emotion_tag_input"),
-                emotion: row.get("emotion"),
-                description: row.get("description"),
-                sharing_option: row.get("sharing_option"),
-                likes: row.get("likes"),
-                dislikes: row.get("dislikes"),
-                views: row.get("views"),
-                author: row.get("author"),
-                thumbnail_img: row.get("thumbnail_img"),
-                created_at: row.get("m_created_at"),
-                updated_at: row.get("m_updated_at"),
-            };
-            
-            result.push((member_marker, marker));
-        }
-        
-        Ok(result)
+        Ok(key)
     }
 
-    /// 3번 사용자의 마커 상호작용 통계 조회
-    pub async fn get_member_marker_stats(&self, member_id: i64) -> Result<serde_json::Value> {
-        let stats = sqlx::query(
+    /// API 키 요청 기록 후 오늘(UTC) 허용된 요청 수 반환
+    /// 일일 쿼터를 넘은 요청은 rejected_count로만 집계
+    pub async fn record_api_key_usage(&self, api_key_id: i64, endpoint: &str, daily_quota: i32) -> Result<(bool, i64)> {
+        let mut tx = self.pool.begin().await?;
+        
+        // 같은 키의 동시 요청이 쿼터를 넘지 않도록 키 단위로 잠금
+        sqlx::query("SELECT id FROM bigpicture.api_keys WHERE id = $1 FOR UPDATE")
+            .bind(api_key_id)
+            .execute(&mut *tx)
+            .await?;
+        let used_today: i64 = sqlx::query_scalar(
             r#"
-            SELECT 
-                interaction_type,
-                COUNT(*) as count,
-                MIN(created_at) as first_interaction,
-                MAX(created_at) as last_interaction
-            FROM bigpicture.member_markers 
-            WHERE member_id = $1
-            GROUP BY interaction_type
-            ORDER BY count DESC
+            SELECT COALESCE(SUM(request_count), 0)::BIGINT FROM bigpicture.api_key_usage
+            WHERE api_key_id = $1 AND usage_date = (NOW() AT TIME ZONE 'UTC')::DATE
             "#
         )
-        .bind(member_id)
-        .fetch_all(&self.pool)
+        .bind(api_key_id)
+        .fetch_one(&mut *tx)
         .await?;
         
-        let mut result = serde_json::Map::new();
-        for row in stats {
-            let interaction_type: String = row.get("interaction_type");
-            let count: i64 = row.get("count");
-            let first_interaction: Option<chrono::DateTime<chrono::Utc>> = row.get("first_interaction");
-            let last_interaction: Option<chrono::DateTime<chrono::Utc>> = row.get("last_interaction");
-            
-            let mut interaction_data = serde_json::Map::new();
-            interaction_data.insert("count".to_string(), serde_json::Value::Number(count.into()));
-            if let Some(first) = first_interaction {
-                interaction_data.insert("first_interaction".to_string(), serde_json::Value::String(first.to_rfc3339()));
-            }
-            if let Some(last) = last_interaction {
-                interaction_data.insert("last_interaction".to_string(), serde_json::Value::String(last.to_rfc3339()));
-            }
-            
-            result.insert(interaction_type, serde_json::Value::Object(interaction_data));
+        let allowed = used_today < daily_quota as i64;
+        sqlx::query(
+            r#"
+            INSERT INTO bigpicture.api_key_usage (api_key_id, usage_date, endpoint, request_count, rejected_count)
+            VALUES ($1, (NOW() AT TIME ZONE 'UTC')::DATE, $2, CASE WHEN $3 THEN 1 ELSE 0 END, CASE WHEN $3 THEN 0 ELSE 1 END)
+            ON CONFLICT (api_key_id, usage_date, endpoint)
+            DO UPDATE SET request_count = api_key_usage.request_count + EXCLUDED.request_count,
+                          rejected_count = api_key_usage.rejected_count + EXCLUDED.rejected_count
+            "#
+        )
+        .bind(api_key_id)
+        .bind(endpoint)
+        .bind(allowed)
+        .execute(&mut *tx)
+        .await?;
+        if allowed {
+            sqlx::query("UPDATE bigpicture.api_keys SET last_used_at = NOW() WHERE id = $1")
+                .bind(api_key_id)
+                .execute(&mut *tx)
+                .await?;
         }
         
-        Ok(serde_json::Value::Object(result))
+        tx.commit().await?;
+        Ok((allowed, if allowed { used_today + 1 } else { used_today }))
     }
 
-    pub async fn get_markers_cluster(
-        &self,
-        lat: f64,
-        lng: f64,
-        lat_delta: f64,
-        lng_delta: f64,
-        emotion_tags: Option<Vec<String>>,
-        min_likes: Option<i32>,
-        min_views: Option<i32>,
-        sort_by: Option<&str>,
-        sort_order: Option<&str>,
-        limit: Option<i32>,
-        user_id: Option<i64>,
-        zoom: Option<i32>, // zoom 추가
-    ) -> Result<Vec<serde_json::Value>> {
-        // 현재 화면보다 약간 더 넓은 영역을 조회해서 지도 이동 시 미리 로딩
-        let buffer_factor = 1.2; // 20% 더 넓은 영역 조회
-        let lat_min = lat - (lat_delta / 2.0) * buffer_factor;
-        let lat_max = lat + (lat_delta / 2.0) * buffer_factor;
-        let lng_min = lng - (lng_delta / 2.0) * buffer_factor;
-        let lng_max = lng + (lng_delta / 2.0) * buffer_factor;
-
-        let mut query = format!(
-            "SELECT m.id, m.member_id, ST_Y(m.location::geometry) as latitude, ST_X(m.location::geometry) as longitude, 
-                    m.emotion_tag, m.emotion_tag_input, m.emotion, m.description, m.sharing_option, m.likes, m.dislikes, m.views, m.author, m.thumbnail_img, 
-                    m.created_at, m.updated_at
-             FROM bigpicture.markers m
-             WHERE ST_Within(m.location::geometry, ST_MakeEnvelope({}, {}, {}, {}, 4326))",
-            lng_min, lat_min, lng_max, lat_max
-        );
-        if let Some(uid) = user_id {
-            query.push_str(&format!(" AND member_id = {}", uid));
-        }
-        if let Some(tags) = &emotion_tags {
-            if !tags.is_empty() {
-                let tags_str = tags.iter().map(|tag| format!("'{}'", tag)).collect::<Vec<_>>().join(",");
-                query.push_str(&format!(" AND emotion_tag IN ({})", tags_str));
-            }
-        }
-        if let Some(likes) = min_likes {
-            query.push_str(&format!(" AND likes >= {}", likes));
-        }
-        if let Some(views) = min_views {
-            query.push_str(&format!(" AND views >= {}", views));
-        }
-        query.push_str(" ORDER BY created_at DESC");
-        let limit_value = limit.unwrap_or(1000);
-        query.push_str(&format!(" LIMIT {}", limit_value));
-
-        let rows = sqlx::query(
-            &query
+    /// API 키별 일자/엔드포인트 사용량 (최근 days일)
+    pub async fn get_api_key_usage(&self, api_key_id: i64, days: i32) -> Result<Vec<ApiKeyUsage>> {
+        let rows = sqlx::query_as::<_, ApiKeyUsage>(
+            r#"
+            SELECT usage_date, endpoint, request_count, rejected_count
+            FROM bigpicture.api_key_usage
+            WHERE api_key_id = $1 AND usage_date > (NOW() AT TIME ZONE 'UTC')::DATE - $2
+            ORDER BY usage_date DESC, endpoint ASC
+            "#
         )
+        .bind(api_key_id)
+        .bind(days)
         .fetch_all(&self.pool)
         .await?;
+        
+        Ok(rows)
+    }
 
-        // PgRow -> MarkerClusterInfo 변환
-        let mut marker_infos = Vec::new();
-        for row in rows {
-            marker_infos.push(MarkerClusterInfo {
-                id: row.try_get("id").unwrap_or(0),
+    /// 메모리에 모은 라우트 사용량을 일일 집계에 더함
+    pub async fn add_route_usage(&self, rows: &[(UsageKey, UsageCounts)]) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bigpicture.route_usage_daily
+                (usage_date, method, route, client_version, request_count, client_error_count, server_error_count)
+            SELECT * FROM unnest($1::DATE[], $2::TEXT[], $3::TEXT[], $4::TEXT[], $5::BIGINT[], $6::BIGINT[], $7::BIGINT[])
+            ON CONFLICT (usage_date, method, route, client_version)
+            DO UPDATE SET request_count = route_usage_daily.request_count + EXCLUDED.request_count,
+                          client_error_count = route_usage_daily.client_error_count + EXCLUDED.client_error_count,
+                          server_error_count = route_usage_daily.server_error_count + EXCLUDED.server_error_count
+            "#
+        )
+        .bind(rows.iter().map(|(key, _)| key.usage_date).collect::<Vec<_>>())
+        .bind(rows.iter().map(|(key, _)| key.method.clone()).collect::<Vec<_>>())
+        .bind(rows.iter().map(|(key, _)| key.route.clone()).collect::<Vec<_>>())
+        .bind(rows.iter().map(|(key, _)| key.client_version.clone()).collect::<Vec<_>>())
+        .bind(rows.iter().map(|(_, counts)| counts.requests).collect::<Vec<_>>())
+        .bind(rows.iter().map(|(_, counts)| counts.client_errors).collect::<Vec<_>>())
+        .bind(rows.iter().map(|(_, counts)| counts.server_errors).collect::<Vec<_>>())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 최근 days일(UTC) 라우트/앱 버전별 사용량 합계 (route_prefix로 시작하는 라우트만, 요청 많은 순)
+    pub async fn get_route_usage(&self, days: i32, route_prefix: Option<&str>, client_version: Option<&str>) -> Result<Vec<RouteUsageRow>> {
+        let rows = sqlx::query_as::<_, RouteUsageRow>(
+            r#"
+            SELECT method, route, client_version,
+                   SUM(request_count)::BIGINT AS request_count,
+                   SUM(client_error_count)::BIGINT AS client_error_count,
+                   SUM(server_error_count)::BIGINT AS server_error_count,
+                   MIN(usage_date) AS first_used_on,
+                   MAX(usage_date) AS last_used_on
+            FROM bigpicture.route_usage_daily
+            WHERE usage_date > (NOW() AT TIME ZONE 'UTC')::DATE - $1
+              AND ($2::TEXT IS NULL OR starts_with(route, $2))
+              AND ($3::TEXT IS NULL OR client_version = $3)
+            GROUP BY method, route, client_version
+            ORDER BY request_count DESC, route, method, client_version
+            "#
+        )
+        .bind(days)
+        .bind(route_prefix)
+        .bind(client_version)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows)
+    }
+
+    pub async fn get_api_key(&self, api_key_id: i64) -> Result<Option<ApiKey>> {
+        let key = sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, key_prefix, name, scope, daily_quota, created_by, is_active, created_at, last_used_at
+            FROM bigpicture.api_keys WHERE id = $1
+            "#
+        )
+        .bind(api_key_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(key)
+    }
+
+    /// 공개 API: 영역 내 공개 마커 검색 (게시 중단/비공개 제외)
+    pub async fn search_public_markers(
+        &self,
+        lat_min: f64,
+        lat_max: f64,
+        lng_min: f64,
+        lng_max: f64,
+        emotion_tags: Option<Vec<String>>,
+        limit: i64,
+    ) -> Result<Vec<Marker>> {
+        let markers = sqlx::query_as::<_, Marker>(
+            r#"
+            SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, language, created_at, updated_at
+            FROM bigpicture.markers
+            WHERE ST_Within(location::geometry, ST_MakeEnvelope($1, $2, $3, $4, 4326))
+              AND COALESCE(sharing_option, 'public') = 'public'
+              AND taken_down_at IS NULL AND status = 'published'
+              AND ($5::TEXT[] IS NULL OR string_to_array(emotion_tag, ',') && $5::TEXT[])
+            ORDER BY likes DESC, created_at DESC
+            LIMIT $6
+            "#
+        )
+        .bind(lng_min)
+        .bind(lat_min)
+        .bind(lng_max)
+        .bind(lat_max)
+        .bind(emotion_tags)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(markers)
+    }
+
+    /// 마커 전문 검색 (공개 마커만, 검색 순위 → 최신 순)
+    /// tsquery는 marker_search_tsquery로 만든 값, envelope은 (lng_min, lat_min, lng_max, lat_max)
+    pub async fn search_markers(
+        &self,
+        tsquery: &str,
+        envelope: Option<(f64, f64, f64, f64)>,
+        page: i32,
+        limit: i32,
+    ) -> Result<(Vec<MarkerSearchHit>, i64)> { // (검색 결과, 전체 개수)
+        let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM bigpicture.markers WHERE ");
+        push_marker_search_filters(&mut count_query, tsquery, envelope);
+        let total_count: i64 = count_query.build_query_scalar().fetch_one(&self.pool).await?;
+        
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, language, created_at, updated_at, ts_rank(search_vector, to_tsquery('simple', "
+        );
+        query.push_bind(tsquery)
+            .push(")) AS rank FROM bigpicture.markers WHERE ");
+        push_marker_search_filters(&mut query, tsquery, envelope);
+        query.push(" ORDER BY rank DESC, created_at DESC LIMIT ")
+            .push_bind(limit as i64)
+            .push(" OFFSET ")
+            .push_bind(((page - 1) * limit) as i64);
+        let hits = query.build_query_as::<MarkerSearchHit>().fetch_all(&self.pool).await?;
+        
+        Ok((hits, total_count))
+    }
+
+    /// 외부 검색 색인에 넣을 공개 마커 (after_id 다음부터 id 순, marker_id가 있으면 그 마커만)
+    pub async fn get_search_documents(&self, after_id: i64, marker_id: Option<i64>, limit: i64) -> Result<Vec<MarkerSearchDocument>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT m.id, m.description, m.emotion_tag, m.emotion_tag_input, m.author, mem.nickname, m.language,
+                    ST_Y(m.location::geometry) AS latitude, ST_X(m.location::geometry) AS longitude, m.created_at,
+                    COALESCE(ARRAY_AGG(mt.tag ORDER BY mt.tag) FILTER (WHERE mt.tag IS NOT NULL), '{}') AS tags
+             FROM bigpicture.markers m
+             LEFT JOIN bigpicture.members mem ON mem.id = m.member_id
+             LEFT JOIN bigpicture.marker_tags mt ON mt.marker_id = m.id
+             WHERE COALESCE(m.sharing_option, 'public') = 'public' AND m.taken_down_at IS NULL AND m.status = 'published' AND m.id > "
+        );
+        query.push_bind(after_id);
+        if let Some(marker_id) = marker_id {
+            query.push(" AND m.id = ").push_bind(marker_id);
+        }
+        query.push(" GROUP BY m.id, mem.nickname ORDER BY m.id LIMIT ").push_bind(limit);
+        
+        let documents = query.build_query_as::<MarkerSearchDocument>()
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(documents)
+    }
+
+    /// 외부 검색 결과 id를 마커로 (순서 유지, 그사이 비공개/게시 중단된 마커는 뺌)
+    pub async fn get_public_markers_by_ids(&self, ids: &[i64]) -> Result<Vec<Marker>> {
+        let markers = sqlx::query_as::<_, Marker>(
+            r#"
+            SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, language, created_at, updated_at
+            FROM bigpicture.markers
+            WHERE id = ANY($1) AND COALESCE(sharing_option, 'public') = 'public' AND taken_down_at IS NULL AND status = 'published'
+            ORDER BY array_position($1, id)
+            "#
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(markers)
+    }
+
+    /// 회원 마커의 월별(한국 시간) 감정 태그 수 [start, end)
+    /// include_private가 false면 공개 마커만 (다른 사람이 보는 프로필)
+    pub async fn get_member_emotion_counts(
+        &self,
+        member_id: i64,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        include_private: bool,
+    ) -> Result<Vec<EmotionMonthCount>> {
+        let counts = sqlx::query_as::<_, EmotionMonthCount>(
+            r#"
+            SELECT to_char(m.created_at AT TIME ZONE 'Asia/Seoul', 'YYYY-MM') AS month,
+                   TRIM(tag) AS emotion_tag,
+                   COUNT(*) AS marker_count
+            FROM bigpicture.markers m, unnest(string_to_array(m.emotion_tag, ',')) AS tag
+            WHERE m.member_id = $1
+              AND m.created_at >= $2
+              AND m.created_at < $3
+              AND m.taken_down_at IS NULL AND m.status = 'published'
+              AND ($4 OR COALESCE(m.sharing_option, 'public') = 'public')
+              AND TRIM(tag) <> ''
+            GROUP BY 1, 2
+            ORDER BY 1, 3 DESC
+            "#
+        )
+        .bind(member_id)
+        .bind(start)
+        .bind(end)
+        .bind(include_private)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(counts)
+    }
+
+    /// 공개 API: 감성 태그별 공개 마커 통계
+    pub async fn get_public_emotion_stats(&self) -> Result<Vec<(String, i64, i64)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT TRIM(tag) AS tag, COUNT(*) AS marker_count, COALESCE(SUM(m.likes), 0)::BIGINT AS total_likes
+            FROM bigpicture.markers m, unnest(string_to_array(m.emotion_tag, ',')) AS tag
+            WHERE COALESCE(m.sharing_option, 'public') = 'public'
+              AND m.taken_down_at IS NULL AND m.status = 'published'
+              AND TRIM(tag) <> ''
+            GROUP BY TRIM(tag)
+            ORDER BY marker_count DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.iter()
+            .map(|row| (row.get("tag"), row.get("marker_count"), row.get("total_likes")))
+            .collect())
+    }
+
+    /// 감정 지도 타일 1장: 칸별 공개 마커 수와 칸 안의 감정 태그별 마커 수
+    /// 마커가 min_markers개 미만인 칸은 결과에 넣지 않음
+    pub async fn get_emotion_tile(&self, tile: &TileCoord, min_markers: i64) -> Result<Vec<EmotionTileRow>> {
+        let (lng_min, lat_min, lng_max, lat_max) = tile.envelope();
+        let (west, north) = tile.origin_m();
+        let cell_m = tile.size_m() / f64::from(emotion_tiles::GRID_SIZE);
+        let last_cell = emotion_tiles::GRID_SIZE as i32 - 1;
+        let rows = sqlx::query_as::<_, EmotionTileRow>(
+            r#"
+            WITH points AS (
+                SELECT LEAST(GREATEST(FLOOR((ST_X(p.point) - $5) / $7), 0), $8)::INT AS cell_x,
+                       LEAST(GREATEST(FLOOR(($6 - ST_Y(p.point)) / $7), 0), $8)::INT AS cell_y,
+                       p.emotion_tag
+                FROM (
+                    SELECT ST_Transform(m.location::geometry, 3857) AS point, m.emotion_tag
+                    FROM bigpicture.markers m
+                    WHERE m.taken_down_at IS NULL AND m.status = 'published'
+                      AND COALESCE(m.sharing_option, 'public') = 'public'
+                      AND m.location && ST_MakeEnvelope($1, $2, $3, $4, 4326)::geography
+                      AND ST_Intersects(m.location::geometry, ST_MakeEnvelope($1, $2, $3, $4, 4326))
+                ) p
+            ),
+            cells AS (
+                SELECT cell_x, cell_y, COUNT(*) AS marker_count
+                FROM points
+                GROUP BY cell_x, cell_y
+                HAVING COUNT(*) >= $9
+            )
+            SELECT c.cell_x, c.cell_y, c.marker_count, TRIM(tag) AS emotion_tag, COUNT(*) AS tag_count
+            FROM points p
+            JOIN cells c USING (cell_x, cell_y),
+                 unnest(string_to_array(p.emotion_tag, ',')) AS tag
+            WHERE TRIM(tag) <> ''
+            GROUP BY c.cell_x, c.cell_y, c.marker_count, TRIM(tag)
+            ORDER BY c.cell_y, c.cell_x
+            "#
+        )
+        .bind(lng_min)
+        .bind(lat_min)
+        .bind(lng_max)
+        .bind(lat_max)
+        .bind(west)
+        .bind(north)
+        .bind(cell_m)
+        .bind(last_cell)
+        .bind(min_markers)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows)
+    }
+
+    /// 행정구역 경계 일괄 등록 (같은 코드는 덮어씀)
+    pub async fn import_districts(&self, districts: &[DistrictImport]) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        
+        for district in districts {
+            sqlx::query(
+                r#"
+                INSERT INTO bigpicture.districts (code, name, level, parent_code, geom)
+                VALUES ($1, $2, $3, $4, ST_Multi(ST_CollectionExtract(ST_MakeValid(ST_SetSRID(ST_GeomFromGeoJSON($5), 4326)), 3)))
+                ON CONFLICT (code) DO UPDATE SET
+                    name = EXCLUDED.name,
+                    level = EXCLUDED.level,
+                    parent_code = EXCLUDED.parent_code,
+                    geom = EXCLUDED.geom,
+                    updated_at = NOW()
+                "#
+            )
+            .bind(&district.code)
+            .bind(&district.name)
+            .bind(&district.level)
+            .bind(&district.parent_code)
+            .bind(&district.geometry)
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await?;
+        Ok(districts.len())
+    }
+
+    pub async fn get_district(&self, code: &str) -> Result<Option<District>> {
+        let district = sqlx::query_as::<_, District>(
+            "SELECT code, name, level, parent_code, updated_at FROM bigpicture.districts WHERE code = $1"
+        )
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(district)
+    }
+
+    /// 행정구역 내 공개 마커 통계 (경계 폴리곤과 공간 조인)
+    pub async fn get_district_stats(&self, code: &str) -> Result<DistrictStats> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(m.id) AS marker_count,
+                   COALESCE(SUM(m.likes), 0)::BIGINT AS total_likes,
+                   COALESCE(SUM(m.views), 0)::BIGINT AS total_views,
+                   MAX(m.created_at) AS last_marker_at
+            FROM bigpicture.districts d
+            JOIN bigpicture.markers m ON ST_Covers(d.geom, m.location::geometry)
+            WHERE d.code = $1
+              AND COALESCE(m.sharing_option, 'public') = 'public'
+              AND m.taken_down_at IS NULL AND m.status = 'published'
+            "#
+        )
+        .bind(code)
+        .fetch_one(&self.pool)
+        .await?;
+        
+        let emotion_rows = sqlx::query(
+            r#"
+            SELECT TRIM(tag) AS tag, COUNT(*) AS marker_count
+            FROM bigpicture.districts d
+            JOIN bigpicture.markers m ON ST_Covers(d.geom, m.location::geometry)
+            CROSS JOIN unnest(string_to_array(m.emotion_tag, ',')) AS tag
+            WHERE d.code = $1
+              AND COALESCE(m.sharing_option, 'public') = 'public'
+              AND m.taken_down_at IS NULL AND m.status = 'published'
+              AND TRIM(tag) <> ''
+            GROUP BY TRIM(tag)
+            ORDER BY marker_count DESC
+            LIMIT 10
+            "#
+        )
+        .bind(code)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(DistrictStats {
+            marker_count: row.get("marker_count"),
+            total_likes: row.get("total_likes"),
+            total_views: row.get("total_views"),
+            last_marker_at: row.get("last_marker_at"),
+            top_emotions: emotion_rows.iter()
+                .map(|r| (r.get("tag"), r.get("marker_count")))
+                .collect(),
+        })
+    }
+
+    /// 대시보드용 공개 마커 통계 [from, to): 전체 수, 감정 태그별 수, 기간(한국 날짜)별 수, 마커가 많은 지역
+    /// 기간별 수는 마커가 없는 기간도 0으로 채움
+    pub async fn get_marker_stats(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+        interval: StatsInterval,
+        region_limit: i64,
+    ) -> Result<MarkerStats> {
+        let total_markers: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM bigpicture.markers m
+            WHERE m.created_at >= $1 AND m.created_at < $2
+              AND COALESCE(m.sharing_option, 'public') = 'public'
+              AND m.taken_down_at IS NULL AND m.status = 'published'
+            "#
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.pool)
+        .await?;
+        
+        let emotion_rows = sqlx::query(
+            r#"
+            SELECT TRIM(tag) AS tag, COUNT(*) AS marker_count
+            FROM bigpicture.markers m, unnest(string_to_array(m.emotion_tag, ',')) AS tag
+            WHERE m.created_at >= $1 AND m.created_at < $2
+              AND COALESCE(m.sharing_option, 'public') = 'public'
+              AND m.taken_down_at IS NULL AND m.status = 'published'
+              AND TRIM(tag) <> ''
+            GROUP BY TRIM(tag)
+            ORDER BY marker_count DESC, tag
+            "#
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let unit = interval.as_str();
+        let timeline = sqlx::query_as::<_, MarkerStatsPeriod>(&format!(
+            r#"
+            WITH counts AS (
+                SELECT date_trunc('{unit}', m.created_at AT TIME ZONE 'Asia/Seoul') AS period, COUNT(*) AS marker_count
+                FROM bigpicture.markers m
+                WHERE m.created_at >= $1 AND m.created_at < $2
+                  AND COALESCE(m.sharing_option, 'public') = 'public'
+                  AND m.taken_down_at IS NULL AND m.status = 'published'
+                GROUP BY 1
+            )
+            SELECT series.period::DATE AS period, COALESCE(counts.marker_count, 0) AS marker_count
+            FROM generate_series(
+                date_trunc('{unit}', $1 AT TIME ZONE 'Asia/Seoul'),
+                ($2 AT TIME ZONE 'Asia/Seoul') - INTERVAL '1 microsecond',
+                INTERVAL '1 {unit}'
+            ) AS series(period)
+            LEFT JOIN counts ON counts.period = series.period
+            ORDER BY series.period
+            "#
+        ))
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let top_regions = sqlx::query_as::<_, MarkerStatsRegion>(
+            r#"
+            SELECT m.address_region AS region, m.address_locality AS locality, COUNT(*) AS marker_count
+            FROM bigpicture.markers m
+            WHERE m.created_at >= $1 AND m.created_at < $2
+              AND COALESCE(m.sharing_option, 'public') = 'public'
+              AND m.taken_down_at IS NULL AND m.status = 'published'
+              AND m.address_region IS NOT NULL
+            GROUP BY m.address_region, m.address_locality
+            ORDER BY marker_count DESC, region, locality
+            LIMIT $3
+            "#
+        )
+        .bind(from)
+        .bind(to)
+        .bind(region_limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(MarkerStats {
+            total_markers,
+            by_emotion: emotion_rows.iter()
+                .map(|r| (r.get("tag"), r.get("marker_count")))
+                .collect(),
+            timeline,
+            top_regions,
+        })
+    }
+
+    /// 지난 해들의 오늘(한국 날짜 기준) 작성한 내 마커
+    pub async fn get_member_memories(&self, member_id: i64) -> Result<Vec<Marker>> {
+        let markers = sqlx::query_as::<_, Marker>(
+            r#"
+            SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, language, created_at, updated_at
+            FROM bigpicture.markers
+            WHERE member_id = $1
+              AND taken_down_at IS NULL AND status = 'published'
+              AND to_char(created_at AT TIME ZONE 'Asia/Seoul', 'MM-DD') = to_char(NOW() AT TIME ZONE 'Asia/Seoul', 'MM-DD')
+              AND EXTRACT(YEAR FROM created_at AT TIME ZONE 'Asia/Seoul') < EXTRACT(YEAR FROM NOW() AT TIME ZONE 'Asia/Seoul')
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(markers)
+    }
+
+    pub async fn is_memories_notification_enabled(&self, member_id: i64) -> Result<bool> {
+        let enabled: Option<bool> = sqlx::query_scalar(
+            "SELECT COALESCE(memories_notification_enabled, false) FROM bigpicture.members WHERE id = $1"
+        )
+        .bind(member_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(enabled.unwrap_or(false))
+    }
+
+    /// "지난 오늘" 알림 수신 설정 (회원이 없으면 false)
+    pub async fn set_memories_notification(&self, member_id: i64, enabled: bool) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE bigpicture.members SET memories_notification_enabled = $1, updated_at = NOW() WHERE id = $2"
+        )
+        .bind(enabled)
+        .bind(member_id)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 수신 동의한 회원들에게 오늘의 "지난 오늘" 알림 생성 (하루 한 번만, 생성 건수 반환)
+    pub async fn create_memory_notifications(&self) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO bigpicture.notifications (member_id, notification_type, title, body, marker_ids, dedupe_key, push_status)
+            SELECT m.member_id,
+                   'memories',
+                   '지난 오늘의 추억',
+                   format('예전 오늘 남긴 마커 %s개를 다시 만나보세요.', COUNT(*)),
+                   array_agg(m.id ORDER BY m.created_at DESC),
+                   to_char(NOW() AT TIME ZONE 'Asia/Seoul', 'YYYY-MM-DD'),
+                   'pending'
+            FROM bigpicture.markers m
+            JOIN bigpicture.members mb ON mb.id = m.member_id
+            WHERE mb.memories_notification_enabled = true
+              AND mb.is_active = true
+              AND m.taken_down_at IS NULL AND m.status = 'published'
+              AND to_char(m.created_at AT TIME ZONE 'Asia/Seoul', 'MM-DD') = to_char(NOW() AT TIME ZONE 'Asia/Seoul', 'MM-DD')
+              AND EXTRACT(YEAR FROM m.created_at AT TIME ZONE 'Asia/Seoul') < EXTRACT(YEAR FROM NOW() AT TIME ZONE 'Asia/Seoul')
+            GROUP BY m.member_id
+            ON CONFLICT (member_id, notification_type, dedupe_key) DO NOTHING
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected())
+    }
+
+    pub async fn get_member_notifications(&self, member_id: i64, limit: i64) -> Result<Vec<Notification>> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT id, member_id, notification_type, title, body, marker_ids, read_at, created_at
+            FROM bigpicture.notifications
+            WHERE member_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#
+        )
+        .bind(member_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(notifications)
+    }
+
+    /// 푸시 기기 토큰 등록 (이미 있는 토큰이면 소유자/플랫폼을 갱신)
+    pub async fn register_device_token(&self, member_id: i64, token: &str, platform: &str) -> Result<DeviceToken> {
+        let device = sqlx::query_as::<_, DeviceToken>(
+            r#"
+            INSERT INTO bigpicture.device_tokens (member_id, token, platform)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (token)
+            DO UPDATE SET member_id = EXCLUDED.member_id, platform = EXCLUDED.platform, last_seen_at = NOW()
+            RETURNING platform, created_at, last_seen_at
+            "#
+        )
+        .bind(member_id)
+        .bind(token)
+        .bind(platform)
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok(device)
+    }
+
+    /// 로그아웃 등으로 내 기기 토큰 해제 (없으면 false)
+    pub async fn unregister_device_token(&self, member_id: i64, token: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM bigpicture.device_tokens WHERE member_id = $1 AND token = $2")
+            .bind(member_id)
+            .bind(token)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// FCM이 더 이상 쓸 수 없다고 알려준 토큰 삭제
+    pub async fn delete_device_token(&self, token: &str) -> Result<()> {
+        sqlx::query("DELETE FROM bigpicture.device_tokens WHERE token = $1")
+            .bind(token)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+
+    pub async fn get_member_device_tokens(&self, member_id: i64) -> Result<Vec<String>> {
+        let tokens = sqlx::query_scalar(
+            "SELECT token FROM bigpicture.device_tokens WHERE member_id = $1 ORDER BY last_seen_at DESC"
+        )
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(tokens)
+    }
+
+    pub async fn get_notification_preferences(&self, member_id: i64) -> Result<Option<NotificationPreferences>> {
+        let preferences = sqlx::query_as::<_, NotificationPreferences>(
+            r#"
+            SELECT push_likes_enabled AS likes, push_comments_enabled AS comments,
+                   COALESCE(memories_notification_enabled, false) AS memories
+            FROM bigpicture.members WHERE id = $1
+            "#
+        )
+        .bind(member_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(preferences)
+    }
+
+    /// 알림 수신 설정 변경 (None인 항목은 그대로, 회원이 없으면 None)
+    pub async fn update_notification_preferences(&self, member_id: i64, likes: Option<bool>, comments: Option<bool>, memories: Option<bool>) -> Result<Option<NotificationPreferences>> {
+        let preferences = sqlx::query_as::<_, NotificationPreferences>(
+            r#"
+            UPDATE bigpicture.members
+            SET push_likes_enabled = COALESCE($2, push_likes_enabled),
+                push_comments_enabled = COALESCE($3, push_comments_enabled),
+                memories_notification_enabled = COALESCE($4, memories_notification_enabled),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING push_likes_enabled AS likes, push_comments_enabled AS comments,
+                      COALESCE(memories_notification_enabled, false) AS memories
+            "#
+        )
+        .bind(member_id)
+        .bind(likes)
+        .bind(comments)
+        .bind(memories)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(preferences)
+    }
+
+    /// 발송할 푸시를 가져가며 sending으로 표시 (여러 인스턴스가 같은 알림을 보내지 않도록 SKIP LOCKED)
+    pub async fn claim_pending_pushes(&self, limit: i64) -> Result<Vec<PendingPush>> {
+        let pushes = sqlx::query_as::<_, PendingPush>(
+            r#"
+            UPDATE bigpicture.notifications SET push_status = 'sending'
+            WHERE id IN (
+                SELECT id FROM bigpicture.notifications
+                WHERE push_status = 'pending'
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, member_id, notification_type, title, body, marker_ids
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(pushes)
+    }
+
+    /// 발송 결과 기록 (sent, failed, no_device)
+    pub async fn finish_push(&self, notification_id: i64, status: &str) -> Result<()> {
+        sqlx::query("UPDATE bigpicture.notifications SET push_status = $2, pushed_at = NOW() WHERE id = $1")
+            .bind(notification_id)
+            .bind(status)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+
+    /// max_age_secs보다 오래 대기 중인 푸시(발송 중 서버가 꺼진 것 포함)는 보내지 않음
+    pub async fn expire_pending_pushes(&self, max_age_secs: i64) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bigpicture.notifications SET push_status = 'expired'
+            WHERE push_status IN ('pending', 'sending')
+              AND created_at < NOW() - make_interval(secs => $1::DOUBLE PRECISION)
+            "#
+        )
+        .bind(max_age_secs)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected())
+    }
+
+    /// 활동 기록 (recipient_id는 대상 마커 작성자, 본인 마커면 알림 피드에 나오지 않음)
+    pub async fn record_activity(&self, actor_id: i64, recipient_id: Option<i64>, activity_type: &str, marker_id: Option<i64>) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO bigpicture.activities (actor_id, recipient_id, activity_type, marker_id) VALUES ($1, $2, $3, $4)"
+        )
+        .bind(actor_id)
+        .bind(recipient_id)
+        .bind(activity_type)
+        .bind(marker_id)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 내 활동 타임라인 (최신순, before_id보다 오래된 것만)
+    pub async fn get_member_activities(&self, member_id: i64, before_id: Option<i64>, limit: i64) -> Result<Vec<Activity>> {
+        let activities = sqlx::query_as::<_, Activity>(&format!(
+            "{} WHERE a.actor_id = $1 AND ($2::BIGINT IS NULL OR a.id < $2) ORDER BY a.id DESC LIMIT $3",
+            ACTIVITY_SELECT
+        ))
+        .bind(member_id)
+        .bind(before_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(activities)
+    }
+
+    /// 내 마커에 다른 회원이 남긴 활동 (본인 활동, 정지/탈퇴한 회원의 활동 제외)
+    pub async fn get_member_activity_notifications(&self, member_id: i64, before_id: Option<i64>, limit: i64) -> Result<Vec<Activity>> {
+        let activities = sqlx::query_as::<_, Activity>(&format!(
+            r#"
+            {} WHERE a.recipient_id = $1 AND a.actor_id <> $1
+              AND actor.deleted_at IS NULL AND actor.suspended_at IS NULL
+              AND ($2::BIGINT IS NULL OR a.id < $2)
+            ORDER BY a.id DESC LIMIT $3
+            "#,
+            ACTIVITY_SELECT
+        ))
+        .bind(member_id)
+        .bind(before_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(activities)
+    }
+
+    /// 회원 탈퇴: 개인정보 제거 후 soft delete
+    /// 공개 범위가 있는 마커는 작성자만 "탈퇴회원"으로 바꿔 남기고, 본인만 보던 비공개 마커는 삭제
+    /// (법적 보존 중인 마커/이미지는 삭제하지 않음). 삭제된 이미지 파일은 정리 작업으로 예약
+    pub async fn delete_member_account(&self, member_id: i64) -> Result<Option<AccountDeletionSummary>> {
+        let mut tx = self.pool.begin().await?;
+        
+        let profile_image: Option<Option<String>> = sqlx::query_scalar(
+            "SELECT profile_image_url FROM bigpicture.members WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"
+        )
+        .bind(member_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(profile_image) = profile_image else {
+            return Ok(None);
+        };
+        
+        // 삭제할 비공개 마커 (법적 보존 마커/이미지가 있으면 제외)
+        let private_marker_ids: Vec<i64> = sqlx::query_scalar(
+            r#"
+            SELECT m.id FROM bigpicture.markers m
+            WHERE m.member_id = $1
+              AND m.sharing_option = 'private'
+              AND COALESCE(m.legal_hold, false) = false
+              AND NOT EXISTS (
+                  SELECT 1 FROM bigpicture.marker_images mi
+                  WHERE mi.marker_id = m.id AND COALESCE(mi.legal_hold, false) = true
+              )
+            "#
+        )
+        .bind(member_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        
+        let mut image_urls: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT image_url FROM bigpicture.marker_images WHERE marker_id = ANY($1)
+            UNION
+            SELECT thumbnail_img FROM bigpicture.markers WHERE id = ANY($1) AND thumbnail_img IS NOT NULL AND thumbnail_img <> ''
+            "#
+        )
+        .bind(&private_marker_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+        image_urls.extend(profile_image.filter(|url| !url.is_empty()));
+        
+        let deleted_markers = sqlx::query("DELETE FROM bigpicture.markers WHERE id = ANY($1)")
+            .bind(&private_marker_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        
+        let anonymized_markers = sqlx::query(
+            "UPDATE bigpicture.markers SET author = '탈퇴회원', updated_at = NOW() WHERE member_id = $1"
+        )
+        .bind(member_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        
+        sqlx::query("DELETE FROM bigpicture.auth_providers WHERE member_id = $1")
+            .bind(member_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM bigpicture.notifications WHERE member_id = $1")
+            .bind(member_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM bigpicture.device_tokens WHERE member_id = $1")
+            .bind(member_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM bigpicture.login_attempts WHERE member_id = $1")
+            .bind(member_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM bigpicture.member_totp WHERE member_id = $1")
+            .bind(member_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM bigpicture.member_connected_accounts WHERE member_id = $1")
+            .bind(member_id)
+            .execute(&mut *tx)
+            .await?;
+        
+        // 같은 이메일로 다시 가입할 수 있도록 이메일도 가명으로 교체
+        sqlx::query(
+            r#"
+            UPDATE bigpicture.members
+            SET email = 'deleted_' || id || '@deleted.invalid',
+                nickname = '탈퇴회원',
+                profile_image_url = NULL,
+                region = NULL,
+                gender = NULL,
+                age = NULL,
+                personality_type = NULL,
+                preferred_languages = NULL,
+                memories_notification_enabled = false,
+                is_active = false,
+                deleted_at = NOW(),
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(member_id)
+        .execute(&mut *tx)
+        .await?;
+        
+        sqlx::query(
+            r#"
+            INSERT INTO bigpicture.image_cleanup_jobs (image_url, storage_key, reason)
+            SELECT url, bigpicture.image_storage_key(url), 'account_deletion' FROM unnest($1::TEXT[]) AS url
+            "#
+        )
+        .bind(&image_urls)
+        .execute(&mut *tx)
+        .await?;
+        
+        tx.commit().await?;
+        Ok(Some(AccountDeletionSummary {
+            anonymized_markers,
+            deleted_markers,
+            queued_images: image_urls.len(),
+        }))
+    }
+
+    /// 처리할 이미지 정리 작업 (재시도 횟수 제한)
+    pub async fn get_pending_image_cleanup_jobs(&self, limit: i64, max_attempts: i32) -> Result<Vec<ImageCleanupJob>> {
+        let jobs = sqlx::query_as::<_, ImageCleanupJob>(
+            r#"
+            SELECT id, image_url, storage_key, attempts FROM bigpicture.image_cleanup_jobs
+            WHERE completed_at IS NULL AND attempts < $2
+            ORDER BY created_at ASC
+            LIMIT $1
+            "#
+        )
+        .bind(limit)
+        .bind(max_attempts)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(jobs)
+    }
+
+    pub async fn finish_image_cleanup_job(&self, job_id: i64, error: Option<&str>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE bigpicture.image_cleanup_jobs
+            SET attempts = attempts + 1,
+                last_error = $2,
+                completed_at = CASE WHEN $2 IS NULL THEN NOW() ELSE NULL END
+            WHERE id = $1
+            "#
+        )
+        .bind(job_id)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 영구 실패한 작업을 데드레터 큐에 기록 (같은 작업이 이미 처리 대기 중이면 내용만 갱신)
+    pub async fn add_dead_letter_job(&self, job: &NewDeadLetterJob) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bigpicture.dead_letter_jobs (job_type, source_id, payload, attempts, last_error)
+            VALUES ($1, $2, $3::jsonb, $4, $5)
+            ON CONFLICT (job_type, source_id) WHERE status = 'dead'
+            DO UPDATE SET payload = EXCLUDED.payload, attempts = EXCLUDED.attempts, last_error = EXCLUDED.last_error, failed_at = NOW()
+            "#
+        )
+        .bind(&job.job_type)
+        .bind(job.source_id)
+        .bind(job.payload.to_string())
+        .bind(job.attempts)
+        .bind(&job.last_error)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 데드레터 목록 (최근 실패순, status/job_type이 None이면 전체)
+    pub async fn list_dead_letter_jobs(&self, status: Option<&str>, job_type: Option<&str>, limit: i64, offset: i64) -> Result<Vec<DeadLetterJob>> {
+        let jobs = sqlx::query_as::<_, DeadLetterJob>(&format!(
+            r#"
+            SELECT {} FROM bigpicture.dead_letter_jobs
+            WHERE ($1::TEXT IS NULL OR status = $1)
+              AND ($2::TEXT IS NULL OR job_type = $2)
+            ORDER BY failed_at DESC, id DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            DEAD_LETTER_COLUMNS
+        ))
+        .bind(status)
+        .bind(job_type)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(jobs)
+    }
+
+    /// 작업 종류/상태별 데드레터 수 (대시보드 요약)
+    pub async fn count_dead_letter_jobs(&self) -> Result<Vec<(String, String, i64)>> {
+        let rows = sqlx::query(
+            "SELECT job_type, status, COUNT(*) AS job_count FROM bigpicture.dead_letter_jobs GROUP BY job_type, status ORDER BY job_type, status"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.iter()
+            .map(|row| (row.get("job_type"), row.get("status"), row.get("job_count")))
+            .collect())
+    }
+
+    /// 데드레터 처리: retry면 원래 작업을 다시 대기 상태로 넣고, discard면 버림
+    /// 처리 대기(dead) 상태가 아니거나 없으면 None
+    pub async fn resolve_dead_letter_job(&self, id: i64, retry: bool, admin_member_id: i64) -> Result<Option<DeadLetterJob>> {
+        let mut tx = self.pool.begin().await?;
+        
+        let job = sqlx::query_as::<_, DeadLetterJob>(&format!(
+            "SELECT {} FROM bigpicture.dead_letter_jobs WHERE id = $1 AND status = 'dead' FOR UPDATE",
+            DEAD_LETTER_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(job) = job else {
+            return Ok(None);
+        };
+        
+        if retry {
+            match job.job_type.as_str() {
+                DLQ_IMAGE_CLEANUP => {
+                    // 원래 작업이 남아 있으면 시도 횟수만 초기화, 없으면 payload로 새로 등록
+                    let reset = sqlx::query(
+                        "UPDATE bigpicture.image_cleanup_jobs SET attempts = 0, last_error = NULL WHERE id = $1 AND completed_at IS NULL"
+                    )
+                    .bind(job.source_id)
+                    .execute(&mut *tx)
+                    .await?;
+                    if reset.rows_affected() == 0 {
+                        sqlx::query(
+                            r#"
+                            INSERT INTO bigpicture.image_cleanup_jobs (image_url, storage_key, reason)
+                            SELECT payload->>'image_url', payload->>'storage_key', COALESCE(payload->>'reason', 'dead_letter_retry')
+                            FROM bigpicture.dead_letter_jobs WHERE id = $1 AND payload ? 'image_url'
+                            "#
+                        )
+                        .bind(job.id)
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                }
+                DLQ_MARKER_EXPORT => {
+                    sqlx::query(
+                        "UPDATE bigpicture.marker_exports SET status = 'pending', error = NULL, started_at = NULL, completed_at = NULL WHERE id = $1"
+                    )
+                    .bind(job.source_id)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                other => return Err(anyhow::anyhow!("재시도할 수 없는 작업 종류: {}", other)),
+            }
+        }
+        
+        let job = sqlx::query_as::<_, DeadLetterJob>(&format!(
+            "UPDATE bigpicture.dead_letter_jobs SET status = $2, resolved_by = $3, resolved_at = NOW() WHERE id = $1 RETURNING {}",
+            DEAD_LETTER_COLUMNS
+        ))
+        .bind(id)
+        .bind(if retry { "retried" } else { "discarded" })
+        .bind(admin_member_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        
+        tx.commit().await?;
+        Ok(Some(job))
+    }
+
+    /// 클라이언트 오류 보고 저장
+    pub async fn create_client_error(&self, report: &NewClientError) -> Result<ClientError> {
+        let client_error = sqlx::query_as::<_, ClientError>(&format!(
+            r#"
+            INSERT INTO bigpicture.client_errors
+                (kind, platform, app_version, device, os_version, route, request_id, http_status, message, stack_trace, member_id, report_request_id, sample_rate)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING {}
+            "#,
+            CLIENT_ERROR_COLUMNS
+        ))
+        .bind(&report.kind)
+        .bind(&report.platform)
+        .bind(&report.app_version)
+        .bind(&report.device)
+        .bind(&report.os_version)
+        .bind(&report.route)
+        .bind(&report.request_id)
+        .bind(report.http_status)
+        .bind(&report.message)
+        .bind(&report.stack_trace)
+        .bind(report.member_id)
+        .bind(&report.report_request_id)
+        .bind(report.sample_rate)
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok(client_error)
+    }
+
+    /// 클라이언트 오류 목록 (최신순, None인 조건은 전체, request_id는 실패한 요청 또는 보고 요청의 ID)
+    pub async fn list_client_errors(&self, filter: &ClientErrorFilter, limit: i64, offset: i64) -> Result<Vec<ClientError>> {
+        let client_errors = sqlx::query_as::<_, ClientError>(&format!(
+            r#"
+            SELECT {} FROM bigpicture.client_errors
+            WHERE ($1::TEXT IS NULL OR kind = $1)
+              AND ($2::TEXT IS NULL OR platform = $2)
+              AND ($3::TEXT IS NULL OR app_version = $3)
+              AND ($4::TEXT IS NULL OR request_id = $4 OR report_request_id = $4)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $5 OFFSET $6
+            "#,
+            CLIENT_ERROR_COLUMNS
+        ))
+        .bind(&filter.kind)
+        .bind(&filter.platform)
+        .bind(&filter.app_version)
+        .bind(&filter.request_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(client_errors)
+    }
+
+    /// 최근 hours시간 종류/플랫폼/앱 버전별 보고 수와 샘플링을 감안한 추정 건수
+    pub async fn summarize_client_errors(&self, hours: i64) -> Result<Vec<ClientErrorSummary>> {
+        let summary = sqlx::query_as::<_, ClientErrorSummary>(
+            r#"
+            SELECT kind, platform, app_version,
+                   COUNT(*) AS reported,
+                   ROUND(SUM(1.0 / GREATEST(sample_rate, 0.0001)))::BIGINT AS estimated
+            FROM bigpicture.client_errors
+            WHERE created_at > NOW() - make_interval(hours => $1::INT)
+            GROUP BY kind, platform, app_version
+            ORDER BY estimated DESC, kind, platform, app_version
+            "#
+        )
+        .bind(hours)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(summary)
+    }
+
+    /// 마커 내보내기 작업 등록
+    pub async fn create_marker_export(&self, requested_by: i64, format: &str, since: Option<chrono::DateTime<chrono::Utc>>) -> Result<MarkerExport> {
+        let job = sqlx::query_as::<_, MarkerExport>(
+            r#"
+            INSERT INTO bigpicture.marker_exports (requested_by, format, since)
+            VALUES ($1, $2, $3)
+            RETURNING id, requested_by, format, since, status, row_count, file_key, file_size, error, created_at, completed_at
+            "#
+        )
+        .bind(requested_by)
+        .bind(format)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok(job)
+    }
+
+    pub async fn get_marker_export(&self, job_id: i64) -> Result<Option<MarkerExport>> {
+        let job = sqlx::query_as::<_, MarkerExport>(
+            "SELECT id, requested_by, format, since, status, row_count, file_key, file_size, error, created_at, completed_at FROM bigpicture.marker_exports WHERE id = $1"
+        )
+        .bind(job_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(job)
+    }
+
+    /// 다음 내보내기 작업을 가져와 실행 중으로 표시
+    /// 서버가 재시작되어 오래 실행 중으로 남은 작업도 다시 가져옴
+    pub async fn claim_marker_export(&self, stale_after_secs: i64) -> Result<Option<MarkerExport>> {
+        let job = sqlx::query_as::<_, MarkerExport>(
+            r#"
+            UPDATE bigpicture.marker_exports
+            SET status = 'running', started_at = NOW()
+            WHERE id = (
+                SELECT id FROM bigpicture.marker_exports
+                WHERE status = 'pending'
+                   OR (status = 'running' AND started_at < NOW() - make_interval(secs => $1))
+                ORDER BY created_at ASC
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, requested_by, format, since, status, row_count, file_key, file_size, error, created_at, completed_at
+            "#
+        )
+        .bind(stale_after_secs as f64)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(job)
+    }
+
+    pub async fn complete_marker_export(&self, job_id: i64, file_key: &str, row_count: i64, file_size: i64) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE bigpicture.marker_exports
+            SET status = 'completed', file_key = $2, row_count = $3, file_size = $4, error = NULL, completed_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(job_id)
+        .bind(file_key)
+        .bind(row_count)
+        .bind(file_size)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    pub async fn fail_marker_export(&self, job_id: i64, error: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE bigpicture.marker_exports SET status = 'failed', error = $2, completed_at = NOW() WHERE id = $1"
+        )
+        .bind(job_id)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 개인 데이터 내보내기에 넣을 내 마커 전체 (임시 저장/예약/비공개 포함, 오래된 순)
+    pub async fn get_member_export_markers(&self, member_id: i64) -> Result<Vec<Marker>> {
+        let markers = sqlx::query_as::<_, Marker>(
+            r#"
+            SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, shares, author, thumbnail_img, language, taken_down_at, public_id, status, publish_at, created_at, updated_at
+            FROM bigpicture.markers
+            WHERE member_id = $1
+            ORDER BY created_at ASC, id ASC
+            "#
+        )
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(markers)
+    }
+
+    pub async fn count_member_export_markers(&self, member_id: i64) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.markers WHERE member_id = $1")
+            .bind(member_id)
+            .fetch_one(&self.pool)
+            .await?;
+        
+        Ok(count)
+    }
+
+    /// 여러 마커의 이미지 (게시 중단된 이미지 제외, 마커/순서별)
+    pub async fn get_images_for_markers(&self, marker_ids: &[i64]) -> Result<Vec<MarkerImage>> {
+        if marker_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let rows = sqlx::query_as::<_, MarkerImage>(
+            r#"
+            SELECT id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
+            FROM bigpicture.marker_images
+            WHERE marker_id = ANY($1) AND taken_down_at IS NULL
+            ORDER BY marker_id ASC, image_order ASC, created_at ASC
+            "#
+        )
+        .bind(marker_ids)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows)
+    }
+
+    /// 개인 데이터 내보내기 작업 등록 (이미 대기/실행 중인 작업이 있으면 그 작업, 새로 만들었으면 true)
+    pub async fn create_member_data_export(&self, member_id: i64, format: &str) -> Result<(MemberDataExport, bool)> {
+        let created = sqlx::query_as::<_, MemberDataExport>(&format!(
+            r#"
+            INSERT INTO bigpicture.member_data_exports (member_id, format)
+            VALUES ($1, $2)
+            ON CONFLICT (member_id) WHERE status IN ('pending', 'running') DO NOTHING
+            RETURNING {}
+            "#,
+            MEMBER_DATA_EXPORT_COLUMNS
+        ))
+        .bind(member_id)
+        .bind(format)
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some(job) = created {
+            return Ok((job, true));
+        }
+        
+        let active = sqlx::query_as::<_, MemberDataExport>(&format!(
+            "SELECT {} FROM bigpicture.member_data_exports WHERE member_id = $1 AND status IN ('pending', 'running')",
+            MEMBER_DATA_EXPORT_COLUMNS
+        ))
+        .bind(member_id)
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok((active, false))
+    }
+
+    pub async fn get_member_data_export(&self, job_id: i64) -> Result<Option<MemberDataExport>> {
+        let job = sqlx::query_as::<_, MemberDataExport>(&format!(
+            "SELECT {} FROM bigpicture.member_data_exports WHERE id = $1",
+            MEMBER_DATA_EXPORT_COLUMNS
+        ))
+        .bind(job_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(job)
+    }
+
+    /// 다음 개인 데이터 내보내기 작업을 가져와 실행 중으로 표시 (claim_marker_export와 같은 방식)
+    pub async fn claim_member_data_export(&self, stale_after_secs: i64) -> Result<Option<MemberDataExport>> {
+        let job = sqlx::query_as::<_, MemberDataExport>(&format!(
+            r#"
+            UPDATE bigpicture.member_data_exports
+            SET status = 'running', started_at = NOW()
+            WHERE id = (
+                SELECT id FROM bigpicture.member_data_exports
+                WHERE status = 'pending'
+                   OR (status = 'running' AND started_at < NOW() - make_interval(secs => $1))
+                ORDER BY created_at ASC
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            MEMBER_DATA_EXPORT_COLUMNS
+        ))
+        .bind(stale_after_secs as f64)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(job)
+    }
+
+    pub async fn complete_member_data_export(&self, job_id: i64, file_key: &str, marker_count: i64, file_size: i64) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE bigpicture.member_data_exports
+            SET status = 'completed', file_key = $2, marker_count = $3, file_size = $4, error = NULL, completed_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(job_id)
+        .bind(file_key)
+        .bind(marker_count)
+        .bind(file_size)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    pub async fn fail_member_data_export(&self, job_id: i64, error: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE bigpicture.member_data_exports SET status = 'failed', error = $2, completed_at = NOW() WHERE id = $1"
+        )
+        .bind(job_id)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 멱등성 키 선점 (만료되었거나 처리 중에 멈춘 키는 지우고 새로 선점)
+    pub async fn claim_idempotency_key(
+        &self,
+        scope: &str,
+        key: &str,
+        request_hash: &str,
+        now: chrono::DateTime<chrono::Utc>,
+        ttl: chrono::Duration,
+        lock_timeout: chrono::Duration,
+    ) -> Result<IdempotencyClaim> {
+        sqlx::query(
+            r#"
+            DELETE FROM bigpicture.idempotency_keys
+            WHERE scope = $1 AND idempotency_key = $2
+              AND (created_at < $3 OR (status = 'processing' AND created_at < $4))
+            "#
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(now - ttl)
+        .bind(now - lock_timeout)
+        .execute(&self.pool)
+        .await?;
+        
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO bigpicture.idempotency_keys (scope, idempotency_key, request_hash, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (scope, idempotency_key) DO NOTHING
+            "#
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(request_hash)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        if inserted.rows_affected() == 1 {
+            return Ok(IdempotencyClaim::Acquired);
+        }
+        
+        let existing = sqlx::query_as::<_, (String, String, Option<i32>, Option<String>, Option<Vec<u8>>)>(
+            r#"
+            SELECT request_hash, status, response_status, response_content_type, response_body
+            FROM bigpicture.idempotency_keys
+            WHERE scope = $1 AND idempotency_key = $2
+            "#
+        )
+        .bind(scope)
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(match existing {
+            // 그 사이 지워졌으면 다음 재시도에서 선점
+            None => IdempotencyClaim::InProgress,
+            Some((hash, _, _, _, _)) if hash != request_hash => IdempotencyClaim::Mismatch,
+            Some((_, status, Some(response_status), content_type, Some(body))) if status == "completed" => {
+                IdempotencyClaim::Completed(IdempotentResponse { status: response_status, content_type, body })
+            }
+            Some(_) => IdempotencyClaim::InProgress,
+        })
+    }
+
+    /// 처리한 응답을 멱등성 키에 저장 (같은 키 재요청에 그대로 돌려줌)
+    pub async fn complete_idempotency_key(&self, scope: &str, key: &str, response: &IdempotentResponse, now: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE bigpicture.idempotency_keys
+            SET status = 'completed', response_status = $3, response_content_type = $4, response_body = $5, completed_at = $6
+            WHERE scope = $1 AND idempotency_key = $2
+            "#
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(response.status)
+        .bind(&response.content_type)
+        .bind(&response.body)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 처리 중인 멱등성 키 해제 (실패한 요청은 같은 키로 다시 시도할 수 있게)
+    pub async fn release_idempotency_key(&self, scope: &str, key: &str) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM bigpicture.idempotency_keys WHERE scope = $1 AND idempotency_key = $2 AND status = 'processing'"
+        )
+        .bind(scope)
+        .bind(key)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 보관 기간이 지난 멱등성 키 정리 (지운 수)
+    pub async fn delete_expired_idempotency_keys(&self, before: chrono::DateTime<chrono::Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM bigpicture.idempotency_keys WHERE created_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected())
+    }
+
+    /// 마커 소유 인증 신청 등록 (같은 회원의 대기 중 신청이 이미 있으면 None)
+    pub async fn create_marker_claim(
+        &self,
+        marker_id: i64,
+        member_id: i64,
+        business_name: &str,
+        business_registration_number: &str,
+        contact_phone: Option<&str>,
+        document_key: &str,
+    ) -> Result<Option<MarkerClaim>> {
+        let claim = sqlx::query_as::<_, MarkerClaim>(&format!(
+            r#"
+            INSERT INTO bigpicture.marker_claims (marker_id, member_id, business_name, business_registration_number, contact_phone, document_key)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT DO NOTHING
+            RETURNING {}
+            "#,
+            MARKER_CLAIM_COLUMNS
+        ))
+        .bind(marker_id)
+        .bind(member_id)
+        .bind(business_name)
+        .bind(business_registration_number)
+        .bind(contact_phone)
+        .bind(document_key)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(claim)
+    }
+
+    pub async fn get_marker_claim(&self, claim_id: i64) -> Result<Option<MarkerClaim>> {
+        let claim = sqlx::query_as::<_, MarkerClaim>(&format!(
+            "SELECT {} FROM bigpicture.marker_claims WHERE id = $1",
+            MARKER_CLAIM_COLUMNS
+        ))
+        .bind(claim_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(claim)
+    }
+
+    /// 관리자 검토 목록 (상태 필터, 오래된 신청부터)
+    pub async fn list_marker_claims(&self, status: Option<&str>, limit: i64) -> Result<Vec<MarkerClaim>> {
+        let claims = sqlx::query_as::<_, MarkerClaim>(&format!(
+            r#"
+            SELECT {} FROM bigpicture.marker_claims
+            WHERE ($1::text IS NULL OR status = $1)
+            ORDER BY created_at ASC, id ASC
+            LIMIT $2
+            "#,
+            MARKER_CLAIM_COLUMNS
+        ))
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(claims)
+    }
+
+    /// 회원 본인의 신청 내역
+    pub async fn get_member_marker_claims(&self, member_id: i64) -> Result<Vec<MarkerClaim>> {
+        let claims = sqlx::query_as::<_, MarkerClaim>(&format!(
+            "SELECT {} FROM bigpicture.marker_claims WHERE member_id = $1 ORDER BY created_at DESC, id DESC",
+            MARKER_CLAIM_COLUMNS
+        ))
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(claims)
+    }
+
+    /// 마커의 인증된 소유자 (승인된 신청)
+    pub async fn get_marker_verified_owner(&self, marker_id: i64) -> Result<Option<MarkerClaim>> {
+        let claim = sqlx::query_as::<_, MarkerClaim>(&format!(
+            "SELECT {} FROM bigpicture.marker_claims WHERE marker_id = $1 AND status = 'approved'",
+            MARKER_CLAIM_COLUMNS
+        ))
+        .bind(marker_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(claim)
+    }
+
+    /// 회원이 인증받은 사업장 마커들
+    pub async fn get_member_verified_claims(&self, member_id: i64) -> Result<Vec<MarkerClaim>> {
+        let claims = sqlx::query_as::<_, MarkerClaim>(&format!(
+            "SELECT {} FROM bigpicture.marker_claims WHERE member_id = $1 AND status = 'approved' ORDER BY reviewed_at ASC",
+            MARKER_CLAIM_COLUMNS
+        ))
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(claims)
+    }
+
+    /// 대기 중 신청 검토 (이미 검토된 신청이면 None)
+    /// 승인하면 같은 마커의 다른 대기 중 신청은 함께 반려
+    pub async fn review_marker_claim(&self, claim_id: i64, reviewer_id: i64, approve: bool, note: Option<&str>) -> Result<Option<MarkerClaim>> {
+        let mut tx = self.pool.begin().await?;
+        
+        let claim = sqlx::query_as::<_, MarkerClaim>(&format!(
+            r#"
+            UPDATE bigpicture.marker_claims
+            SET status = $2, review_note = $3, reviewed_by = $4, reviewed_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING {}
+            "#,
+            MARKER_CLAIM_COLUMNS
+        ))
+        .bind(claim_id)
+        .bind(if approve { "approved" } else { "rejected" })
+        .bind(note)
+        .bind(reviewer_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        
+        if let Some(claim) = claim.as_ref().filter(|_| approve) {
+            sqlx::query(
+                r#"
+                UPDATE bigpicture.marker_claims
+                SET status = 'rejected', review_note = '다른 신청이 승인됨', reviewed_by = $3, reviewed_at = NOW(), updated_at = NOW()
+                WHERE marker_id = $1 AND id <> $2 AND status = 'pending'
+                "#
+            )
+            .bind(claim.marker_id)
+            .bind(claim.id)
+            .bind(reviewer_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await?;
+        Ok(claim)
+    }
+
+    /// 마커 신고 (같은 회원의 처리 대기 중인 신고가 있으면 None)
+    pub async fn create_marker_report(&self, marker_id: i64, reporter_id: i64, reason: &str, details: Option<&str>) -> Result<Option<MarkerReport>> {
+        let report = sqlx::query_as::<_, MarkerReport>(&format!(
+            r#"
+            INSERT INTO bigpicture.marker_reports (marker_id, reporter_id, reason, details)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            RETURNING {}
+            "#,
+            MARKER_REPORT_COLUMNS
+        ))
+        .bind(marker_id)
+        .bind(reporter_id)
+        .bind(reason)
+        .bind(details)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(report)
+    }
+
+    /// 신고 목록 (status 없으면 전체, 최근 신고순)
+    pub async fn list_marker_reports(&self, status: Option<&str>, limit: i64) -> Result<Vec<MarkerReport>> {
+        let reports = sqlx::query_as::<_, MarkerReport>(&format!(
+            r#"
+            SELECT {} FROM bigpicture.marker_reports
+            WHERE ($1::VARCHAR IS NULL OR status = $1)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+            MARKER_REPORT_COLUMNS
+        ))
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(reports)
+    }
+
+    /// 신고 처리: 같은 마커의 대기 중인 신고를 모두 같은 결과로 닫음
+    /// hide면 마커를 게시 중단하고(법적 보존 없이) 감사 로그를 남김. 대기 중인 신고가 아니면 None
+    pub async fn resolve_marker_reports(&self, report_id: i64, resolution: &MarkerReportResolution) -> Result<Option<Vec<MarkerReport>>> {
+        let mut tx = self.pool.begin().await?;
+        
+        let marker_id: Option<i64> = sqlx::query_scalar(
+            "SELECT marker_id FROM bigpicture.marker_reports WHERE id = $1 AND status = 'pending' FOR UPDATE"
+        )
+        .bind(report_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(marker_id) = marker_id else {
+            return Ok(None);
+        };
+        
+        let resolved = sqlx::query_as::<_, MarkerReport>(&format!(
+            r#"
+            UPDATE bigpicture.marker_reports
+            SET status = $2, resolution_note = $3, resolved_by = $4, resolved_at = NOW()
+            WHERE marker_id = $1 AND status = 'pending'
+            RETURNING {}
+            "#,
+            MARKER_REPORT_COLUMNS
+        ))
+        .bind(marker_id)
+        .bind(if resolution.hide { "hidden" } else { "dismissed" })
+        .bind(&resolution.note)
+        .bind(resolution.admin_member_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        
+        if resolution.hide {
+            sqlx::query(
+                "UPDATE bigpicture.markers SET taken_down_at = COALESCE(taken_down_at, NOW()), updated_at = NOW() WHERE id = $1"
+            )
+            .bind(marker_id)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO bigpicture.content_takedown_audit
+                    (admin_member_id, target_type, target_id, action, reason, legal_hold, client_ip, user_agent)
+                VALUES ($1, 'marker', $2, 'hide', $3, false, $4, $5)
+                "#
+            )
+            .bind(resolution.admin_member_id)
+            .bind(marker_id)
+            .bind(resolution.note.as_deref().unwrap_or("신고 처리"))
+            .bind(&resolution.client_ip)
+            .bind(&resolution.user_agent)
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await?;
+        info!("🚩 마커 {} 신고 {}건 처리: {} (관리자 {})", marker_id, resolved.len(), if resolution.hide { "숨김" } else { "기각" }, resolution.admin_member_id);
+        Ok(Some(resolved))
+    }
+
+    /// 프로모션 마커 등록
+    pub async fn create_marker_promotion(&self, promotion: &NewMarkerPromotion, created_by: i64) -> Result<MarkerPromotion> {
+        let created = sqlx::query_as::<_, MarkerPromotion>(&format!(
+            r#"
+            INSERT INTO bigpicture.marker_promotions (marker_id, sponsor_name, district_codes, starts_at, ends_at, impression_cap, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            MARKER_PROMOTION_COLUMNS
+        ))
+        .bind(promotion.marker_id)
+        .bind(&promotion.sponsor_name)
+        .bind(&promotion.district_codes)
+        .bind(promotion.starts_at)
+        .bind(promotion.ends_at)
+        .bind(promotion.impression_cap)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok(created)
+    }
+
+    pub async fn get_marker_promotion(&self, promotion_id: i64) -> Result<Option<MarkerPromotion>> {
+        let promotion = sqlx::query_as::<_, MarkerPromotion>(&format!(
+            "SELECT {} FROM bigpicture.marker_promotions WHERE id = $1",
+            MARKER_PROMOTION_COLUMNS
+        ))
+        .bind(promotion_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(promotion)
+    }
+
+    pub async fn list_marker_promotions(&self, limit: i64) -> Result<Vec<MarkerPromotion>> {
+        let promotions = sqlx::query_as::<_, MarkerPromotion>(&format!(
+            "SELECT {} FROM bigpicture.marker_promotions ORDER BY created_at DESC, id DESC LIMIT $1",
+            MARKER_PROMOTION_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(promotions)
+    }
+
+    /// 프로모션 일부 수정 (None인 값은 그대로)
+    pub async fn update_marker_promotion(
+        &self,
+        promotion_id: i64,
+        is_active: Option<bool>,
+        ends_at: Option<chrono::DateTime<chrono::Utc>>,
+        impression_cap: Option<i64>,
+    ) -> Result<Option<MarkerPromotion>> {
+        let promotion = sqlx::query_as::<_, MarkerPromotion>(&format!(
+            r#"
+            UPDATE bigpicture.marker_promotions
+            SET is_active = COALESCE($2, is_active),
+                ends_at = COALESCE($3, ends_at),
+                impression_cap = COALESCE($4, impression_cap),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            MARKER_PROMOTION_COLUMNS
+        ))
+        .bind(promotion_id)
+        .bind(is_active)
+        .bind(ends_at)
+        .bind(impression_cap)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(promotion)
+    }
+
+    /// 지금 노출할 프로모션 마커 (기간 중, 노출 한도 미달, 공개/게시 중 마커, 노출이 적은 순)
+    /// point가 있으면 대상 행정구역에 포함될 때만, 없으면 전국 대상 프로모션만
+    /// envelope이 있으면 마커가 그 영역 안에 있어야 함 (지도 클러스터)
+    pub async fn get_promoted_markers(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        point: Option<(f64, f64)>,
+        envelope: Option<(f64, f64, f64, f64)>,
+        limit: i64,
+    ) -> Result<Vec<(MarkerPromotion, Marker)>> {
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM bigpicture.marker_promotions p JOIN bigpicture.markers m ON m.id = p.marker_id WHERE p.is_active",
+            MARKER_PROMOTION_COLUMNS.split(", ").map(|column| format!("p.{}", column)).collect::<Vec<_>>().join(", ")
+        ));
+        query.push(" AND p.starts_at <= ").push_bind(now)
+            .push(" AND p.ends_at > ").push_bind(now)
+            .push(" AND (p.impression_cap IS NULL OR p.impressions < p.impression_cap)")
+            .push(" AND m.taken_down_at IS NULL AND m.status = 'published' AND COALESCE(m.sharing_option, 'public') = 'public'");
+        match point {
+            Some((lat, lng)) => {
+                query.push(" AND (p.district_codes IS NULL OR EXISTS (SELECT 1 FROM bigpicture.districts d WHERE d.code = ANY(p.district_codes) AND ST_Covers(d.geom, ST_SetSRID(ST_MakePoint(")
+                    .push_bind(lng)
+                    .push(", ")
+                    .push_bind(lat)
+                    .push("), 4326))))");
+            }
+            None => {
+                query.push(" AND p.district_codes IS NULL");
+            }
+        }
+        if let Some(envelope) = envelope {
+            push_envelope_filter(&mut query, "m.location", envelope);
+        }
+        query.push(" ORDER BY p.impressions ASC, p.id ASC LIMIT ").push_bind(limit);
+        
+        let promotions = query.build_query_as::<MarkerPromotion>()
+            .fetch_all(&self.pool)
+            .await?;
+        let mut promoted = Vec::with_capacity(promotions.len());
+        for promotion in promotions {
+            if let Some(marker) = self.get_marker_detail(promotion.marker_id).await? {
+                promoted.push((promotion, marker));
+            }
+        }
+        
+        Ok(promoted)
+    }
+
+    /// 노출/클릭 기록 (기간이 지났거나 중단/한도 초과된 프로모션의 노출은 기록하지 않고 false)
+    pub async fn record_promotion_event(&self, promotion_id: i64, event: PromotionEvent, now: chrono::DateTime<chrono::Utc>) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        
+        let counted = match event {
+            PromotionEvent::Impression => sqlx::query(
+                r#"
+                UPDATE bigpicture.marker_promotions SET impressions = impressions + 1
+                WHERE id = $1 AND is_active AND starts_at <= $2 AND ends_at > $2
+                  AND (impression_cap IS NULL OR impressions < impression_cap)
+                "#
+            ),
+            PromotionEvent::Click => sqlx::query(
+                "UPDATE bigpicture.marker_promotions SET clicks = clicks + 1 WHERE id = $1 AND starts_at <= $2"
+            ),
+        }
+        .bind(promotion_id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() > 0;
+        
+        if counted {
+            let (impressions, clicks) = match event {
+                PromotionEvent::Impression => (1i64, 0i64),
+                PromotionEvent::Click => (0, 1),
+            };
+            sqlx::query(
+                r#"
+                INSERT INTO bigpicture.marker_promotion_stats (promotion_id, stat_date, impressions, clicks)
+                VALUES ($1, ($2 AT TIME ZONE 'UTC')::DATE, $3, $4)
+                ON CONFLICT (promotion_id, stat_date)
+                DO UPDATE SET impressions = marker_promotion_stats.impressions + EXCLUDED.impressions,
+                              clicks = marker_promotion_stats.clicks + EXCLUDED.clicks
+                "#
+            )
+            .bind(promotion_id)
+            .bind(now)
+            .bind(impressions)
+            .bind(clicks)
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await?;
+        Ok(counted)
+    }
+
+    /// 프로모션 일자별 노출/클릭 (최근 days일, UTC 기준)
+    pub async fn get_promotion_stats(&self, promotion_id: i64, days: i32) -> Result<Vec<PromotionDailyStats>> {
+        let rows = sqlx::query_as::<_, PromotionDailyStats>(
+            r#"
+            SELECT stat_date, impressions, clicks
+            FROM bigpicture.marker_promotion_stats
+            WHERE promotion_id = $1 AND stat_date > (NOW() AT TIME ZONE 'UTC')::DATE - $2
+            ORDER BY stat_date DESC
+            "#
+        )
+        .bind(promotion_id)
+        .bind(days)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows)
+    }
+
+    /// 외부 SNS 계정 연결 (같은 플랫폼을 다시 연결하면 계정/토큰 교체)
+    pub async fn upsert_connected_account(&self, member_id: i64, account: &NewConnectedAccount) -> Result<ConnectedAccount> {
+        let connected = sqlx::query_as::<_, ConnectedAccount>(&format!(
+            r#"
+            INSERT INTO bigpicture.member_connected_accounts
+                (member_id, platform, remote_user_id, remote_username, access_token_encrypted, refresh_token_encrypted, token_expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (member_id, platform) DO UPDATE
+            SET remote_user_id = EXCLUDED.remote_user_id,
+                remote_username = EXCLUDED.remote_username,
+                access_token_encrypted = EXCLUDED.access_token_encrypted,
+                refresh_token_encrypted = EXCLUDED.refresh_token_encrypted,
+                token_expires_at = EXCLUDED.token_expires_at,
+                updated_at = NOW()
+            RETURNING {}
+            "#,
+            CONNECTED_ACCOUNT_COLUMNS
+        ))
+        .bind(member_id)
+        .bind(&account.platform)
+        .bind(&account.remote_user_id)
+        .bind(&account.remote_username)
+        .bind(&account.access_token_encrypted)
+        .bind(&account.refresh_token_encrypted)
+        .bind(account.token_expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok(connected)
+    }
+
+    pub async fn get_connected_account(&self, member_id: i64, platform: &str) -> Result<Option<ConnectedAccount>> {
+        let account = sqlx::query_as::<_, ConnectedAccount>(&format!(
+            "SELECT {} FROM bigpicture.member_connected_accounts WHERE member_id = $1 AND platform = $2",
+            CONNECTED_ACCOUNT_COLUMNS
+        ))
+        .bind(member_id)
+        .bind(platform)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(account)
+    }
+
+    pub async fn list_connected_accounts(&self, member_id: i64) -> Result<Vec<ConnectedAccount>> {
+        let accounts = sqlx::query_as::<_, ConnectedAccount>(&format!(
+            "SELECT {} FROM bigpicture.member_connected_accounts WHERE member_id = $1 ORDER BY platform",
+            CONNECTED_ACCOUNT_COLUMNS
+        ))
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(accounts)
+    }
+
+    /// 연결 해제 (연결된 계정이 없었으면 false)
+    pub async fn delete_connected_account(&self, member_id: i64, platform: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM bigpicture.member_connected_accounts WHERE member_id = $1 AND platform = $2")
+            .bind(member_id)
+            .bind(platform)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_marker_crosspost(&self, marker_id: i64, platform: &str) -> Result<Option<MarkerCrosspost>> {
+        let crosspost = sqlx::query_as::<_, MarkerCrosspost>(&format!(
+            "SELECT {} FROM bigpicture.marker_crossposts WHERE marker_id = $1 AND platform = $2",
+            MARKER_CROSSPOST_COLUMNS
+        ))
+        .bind(marker_id)
+        .bind(platform)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(crosspost)
+    }
+
+    /// 크로스포스팅 기록 (이미 같은 플랫폼에 올린 기록이 있으면 None)
+    pub async fn create_marker_crosspost(
+        &self,
+        marker_id: i64,
+        member_id: i64,
+        platform: &str,
+        remote_post_id: &str,
+        remote_url: Option<&str>,
+    ) -> Result<Option<MarkerCrosspost>> {
+        let crosspost = sqlx::query_as::<_, MarkerCrosspost>(&format!(
+            r#"
+            INSERT INTO bigpicture.marker_crossposts (marker_id, member_id, platform, remote_post_id, remote_url)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (marker_id, platform) DO NOTHING
+            RETURNING {}
+            "#,
+            MARKER_CROSSPOST_COLUMNS
+        ))
+        .bind(marker_id)
+        .bind(member_id)
+        .bind(platform)
+        .bind(remote_post_id)
+        .bind(remote_url)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(crosspost)
+    }
+
+    /// 아직 주소를 조회하지 않은 마커 좌표 (id 순, (id, 위도, 경도))
+    pub async fn get_markers_pending_geocode(&self, limit: i64) -> Result<Vec<(i64, f64, f64)>> {
+        let rows = sqlx::query_as::<_, (i64, f64, f64)>(
+            r#"
+            SELECT id, ST_Y(location::geometry), ST_X(location::geometry)
+            FROM bigpicture.markers
+            WHERE address_geocoded_at IS NULL AND location IS NOT NULL
+            ORDER BY id
+            LIMIT $1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows)
+    }
+
+    /// 역지오코딩 결과 저장 (주소가 없는 곳(바다, 해외 등)도 조회 완료로 기록)
+    pub async fn set_marker_addresses(&self, marker_ids: &[i64], address: Option<&MarkerAddress>, now: chrono::DateTime<chrono::Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bigpicture.markers
+            SET address_region = $2, address_locality = $3, address_neighborhood = $4, address_text = $5,
+                address_geocoded_at = $6
+            WHERE id = ANY($1)
+            "#
+        )
+        .bind(marker_ids)
+        .bind(address.map(|a| a.region.as_str()))
+        .bind(address.map(|a| a.locality.as_str()))
+        .bind(address.map(|a| a.neighborhood.as_str()))
+        .bind(address.map(|a| a.full_address.as_str()))
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected())
+    }
+
+    /// 주소 백필 진행 상황
+    pub async fn get_geocode_backfill_progress(&self) -> Result<GeocodeBackfillProgress> {
+        let progress = sqlx::query_as::<_, GeocodeBackfillProgress>(
+            r#"
+            SELECT COUNT(*) AS total_markers,
+                   COUNT(*) FILTER (WHERE address_geocoded_at IS NOT NULL) AS geocoded,
+                   COUNT(*) FILTER (WHERE address_text IS NOT NULL) AS with_address,
+                   COUNT(*) FILTER (WHERE address_geocoded_at IS NULL) AS pending
+            FROM bigpicture.markers
+            WHERE location IS NOT NULL
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok(progress)
+    }
+
+    /// 내보낼 공개 마커와 상호작용 집계 (작성자/설명/이미지 주소 등 개인정보 제외, id 순 스트리밍)
+    pub fn stream_marker_export_rows(&self, since: Option<chrono::DateTime<chrono::Utc>>) -> futures::stream::BoxStream<'_, std::result::Result<MarkerExportRow, sqlx::Error>> {
+        sqlx::query_as::<_, MarkerExportRow>(
+            r#"
+            SELECT m.id,
+                   ST_Y(m.location::geometry) AS latitude,
+                   ST_X(m.location::geometry) AS longitude,
+                   m.emotion_tag, m.emotion_tag_input, m.language,
+                   m.likes, m.dislikes, m.views,
+                   i.liked_members, i.disliked_members, i.viewed_members, i.bookmarked_members,
+                   (SELECT COUNT(*) FROM bigpicture.marker_images mi WHERE mi.marker_id = m.id AND mi.taken_down_at IS NULL) AS image_count,
+                   m.created_at, m.updated_at
+            FROM bigpicture.markers m
+            CROSS JOIN LATERAL (
+                SELECT COUNT(*) FILTER (WHERE mm.interaction_type = 'liked') AS liked_members,
+                       COUNT(*) FILTER (WHERE mm.interaction_type = 'disliked') AS disliked_members,
+                       COUNT(*) FILTER (WHERE mm.interaction_type = 'viewed') AS viewed_members,
+                       COUNT(*) FILTER (WHERE mm.interaction_type = 'bookmarked') AS bookmarked_members
+                FROM bigpicture.member_markers mm
+                WHERE mm.marker_id = m.id
+            ) i
+            WHERE m.taken_down_at IS NULL AND m.status = 'published'
+              AND m.sharing_option = 'public'
+              AND ($1::TIMESTAMPTZ IS NULL OR m.updated_at >= $1)
+            ORDER BY m.id
+            "#
+        )
+        .bind(since)
+        .fetch(&self.pool)
+    }
+
+    /// 관리자 마커 내보내기 (CSV/GeoJSON) 행을 DB 커서로 흘려 받음 (게시 중단 마커 포함, id 순)
+    pub fn stream_admin_marker_export_rows(&self, filter: &AdminMarkerExportFilter) -> futures::stream::BoxStream<'_, std::result::Result<AdminMarkerExportRow, sqlx::Error>> {
+        sqlx::query_as::<_, AdminMarkerExportRow>(
+            r#"
+            SELECT m.id, m.public_id, m.member_id,
+                   ST_Y(m.location::geometry) AS latitude,
+                   ST_X(m.location::geometry) AS longitude,
+                   m.emotion_tag, m.emotion_tag_input, m.description, m.sharing_option, m.status, m.language,
+                   m.likes, m.dislikes, m.views,
+                   m.address_region, m.address_locality, m.address_neighborhood,
+                   m.taken_down_at, m.created_at, m.updated_at
+            FROM bigpicture.markers m
+            WHERE ($1::TIMESTAMPTZ IS NULL OR m.created_at >= $1)
+              AND ($2::TIMESTAMPTZ IS NULL OR m.created_at < $2)
+              AND ($3::TEXT IS NULL OR m.address_region = $3)
+              AND ($4::TEXT IS NULL OR EXISTS (
+                    SELECT 1 FROM bigpicture.districts d WHERE d.code = $4 AND ST_Covers(d.geom, m.location::geometry)
+                  ))
+            ORDER BY m.id
+            "#
+        )
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.region.clone())
+        .bind(filter.district_code.clone())
+        .fetch(&self.pool)
+    }
+
+    /// 회원 등록
+    pub async fn create_member(
+        &self,
+        email: &str,
+        nickname: &str,
+        profile_image_url: Option<&str>,
+        region: Option<&str>,
+        gender: Option<&str>,
+        birth_year: Option<i32>,
+        personality_type: Option<&str>,
+    ) -> Result<Member> {
+        let rec = sqlx::query_as::<_, Member>(
+            r#"
+            INSERT INTO bigpicture.members
+                (email, nickname, profile_image_url, region, gender, age, personality_type)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#
+        )
+        .bind(email)
+        .bind(nickname)
+        .bind(profile_image_url)
+        .bind(region)
+        .bind(gender)
+        .bind(birth_year)
+        .bind(personality_type)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(registration_error)?;
+        Ok(rec)
+    }
+
+    /// 회원 조회 by id
+    pub async fn get_member_by_id(&self, id: i64) -> Result<Option<Member>> {
+        let rec = sqlx::query_as::<_, Member>(
+            r#"
+            SELECT * FROM bigpicture.members WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(rec)
+    }
+
+    /// 회원 조회 by id (마커 정보 포함)
+    pub async fn get_member_with_markers(&self, id: i64) -> Result<Option<(Member, Vec<MemberMarker>)>> {
+        // 회원 정보 조회
+        let member = match self.get_member_by_id(id).await? {
+            Some(member) => member,
+            None => return Ok(None),
+        };
+        
+        // 회원의 마커 상호작용 조회
+        let markers = self.get_member_marker_interactions(id).await?;
+        
+        Ok(Some((member, markers)))
+    }
+
+    /// 회원 조회 by id (마커 상세 정보 포함)
+    pub async fn get_member_with_marker_details(&self, id: i64) -> Result<Option<(Member, Vec<(MemberMarker, Marker)>)>> {
+        // 회원 정보 조회
+        let member = match self.get_member_by_id(id).await? {
+            Some(member) => member,
+            None => return Ok(None),
+        };
+        
+        // 회원의 마커 상세 정보 조회
+        let marker_details = self.get_member_markers_with_details(id).await?;
+        
+        Ok(Some((member, marker_details)))
+    }
+
+    /// 회원 조회 by id (마커 통계 포함)
+    pub async fn get_member_with_stats(&self, id: i64) -> Result<Option<(Member, serde_json::Value)>> {
+        // 회원 정보 조회
+        let member = match self.get_member_by_id(id).await? {
+            Some(member) => member,
+            None => return Ok(None),
+        };
+        
+        // 회원의 마커 통계 조회
+        let stats = self.get_member_marker_stats(id).await?;
+        
+        Ok(Some((member, stats)))
+    }
+
+    /// 회원 조회 by email
+    pub async fn get_member_by_email(&self, email: &str) -> Result<Option<Member>> {
+        let rec = sqlx::query_as::<_, Member>(
+            r#"
+            SELECT * FROM bigpicture.members WHERE email = $1
+            "#
+        )
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(rec)
+    }
+
+    /// 전체 회원 목록 (limit 옵션)
+    pub async fn list_members(&self, limit: Option<i64>) -> Result<Vec<Member>> {
+        let recs = sqlx::query_as::<_, Member>(
+            r#"
+            SELECT * FROM bigpicture.members ORDER BY id DESC LIMIT $1
+            "#
+        )
+        .bind(limit.unwrap_or(100))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(recs)
+    }
+
+    /// member_markers 테이블을 사용한 좋아요/싫어요 토글
+    pub async fn toggle_like(
+        &self,
+        member_id: i64,
+        marker_id: i64,
+        like_type: &str, // "like" 또는 "dislike"
+    ) -> Result<(i32, i32)> { // (좋아요 수, 싫어요 수) 반환
+        let mut tx = self.pool.begin().await?;
+        
+        // interaction_type 매핑
+        let interaction_type = if like_type == "like" { "liked" } else { "disliked" };
+        
+        // 기존 좋아요/싫어요 확인
+        let existing = sqlx::query_as::<_, MemberMarker>(
+            r#"
+            SELECT * FROM bigpicture.member_markers 
+            WHERE member_id = $1 AND marker_id = $2 AND interaction_type = $3
+            "#
+        )
+        .bind(member_id)
+        .bind(marker_id)
+        .bind(interaction_type)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(existing_like) = existing {
+            // 기존 좋아요/싫어요가 있으면 제거
+            sqlx::query(
+                "DELETE FROM bigpicture.member_markers WHERE id = $1"
+            )
+            .bind(existing_like.id)
+            .execute(&mut *tx)
+            .await?;
+
+            // 마커 카운트 감소
+            let update_query = match like_type {
+                "like" => "UPDATE bigpicture.markers SET likes = GREATEST(likes - 1, 0) WHERE id = $1",
+                "dislike" => "UPDATE bigpicture.markers SET dislikes = GREATEST(dislikes - 1, 0) WHERE id = $1",
+                _ => return Err(anyhow::anyhow!("Invalid like type")),
+            };
+            sqlx::query(update_query)
+                .bind(marker_id)
+                .execute(&mut *tx)
+                .await?;
+        } else {
+            // 새로운 좋아요/싫어요 추가
+            sqlx::query(
+                r#"
+                INSERT INTO bigpicture.member_markers
+                    (member_id, marker_id, interaction_type)
+                VALUES ($1, $2, $3)
+                "#
+            )
+            .bind(member_id)
+            .bind(marker_id)
+            .bind(interaction_type)
+            .execute(&mut *tx)
+            .await?;
+
+            // 마커 카운트 증가
+            let update_query = match like_type {
+                "like" => "UPDATE bigpicture.markers SET likes = likes + 1 WHERE id = $1",
+                "dislike" => "UPDATE bigpicture.markers SET dislikes = dislikes + 1 WHERE id = $1",
+                _ => return Err(anyhow::anyhow!("Invalid like type")),
+            };
+            sqlx::query(update_query)
+                .bind(marker_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        if sync_like_activity(&mut tx, member_id, marker_id).await? {
+            notify_marker_liked(&mut tx, member_id, marker_id).await?;
+        }
+
+        // 업데이트된 카운트 조회
+        let counts = sqlx::query_as::<_, (i32, i32)>(
+            "SELECT likes, dislikes FROM bigpicture.markers WHERE id = $1"
+        )
+        .bind(marker_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(counts)
+    }
+
+    /// 사용자가 특정 마커에 좋아요/싫어요를 눌렀는지 확인
+    pub async fn get_user_like_status(
+        &self,
+        member_id: i64,
+        marker_id: i64,
+    ) -> Result<Option<String>> { // None, Some("like"), Some("dislike")
+        let like = sqlx::query_as::<_, MemberMarker>(
+            r#"
+            SELECT * FROM bigpicture.member_markers 
+            WHERE member_id = $1 AND marker_id = $2 AND interaction_type IN ('liked', 'disliked')
+            "#
+        )
+        .bind(member_id)
+        .bind(marker_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(like.map(|l| {
+            if l.interaction_type == "liked" { "like".to_string() } else { "dislike".to_string() }
+        }))
+    }
+
+    /// 회원이 여러 마커에 남긴 좋아요/싫어요/북마크 상태 (마커 ID별, 상호작용이 없는 마커는 빠짐)
+    pub async fn get_member_marker_states(
+        &self,
+        member_id: i64,
+        marker_ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, MemberMarkerState>> {
+        if marker_ids.is_empty() {
+            return Ok(std::collections::HashMap::new());
+        }
+        let states = sqlx::query_as::<_, MemberMarkerState>(
+            r#"
+            SELECT marker_id,
+                   BOOL_OR(interaction_type = 'liked') AS is_liked,
+                   BOOL_OR(interaction_type = 'disliked') AS is_disliked,
+                   BOOL_OR(interaction_type = 'bookmarked') AS is_bookmarked
+            FROM bigpicture.member_markers
+            WHERE member_id = $1 AND marker_id = ANY($2)
+              AND interaction_type IN ('liked', 'disliked', 'bookmarked')
+            GROUP BY marker_id
+            "#
+        )
+        .bind(member_id)
+        .bind(marker_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(states.into_iter().map(|state| (state.marker_id, state)).collect())
+    }
+
+    /// 마커의 좋아요/싫어요 목록 조회
+    pub async fn get_marker_likes(
+        &self,
+        marker_id: i64,
+        like_type: Option<&str>, // None이면 모든 타입, "like" 또는 "dislike"
+    ) -> Result<Vec<MemberMarker>> {
+        let interaction_type = like_type.map(|lt| {
+            if lt == "like" { "liked" } else { "disliked" }
+        });
+        
+        let query = if let Some(it) = interaction_type {
+            sqlx::query_as::<_, MemberMarker>(
+                r#"
+                SELECT * FROM bigpicture.member_markers 
+                WHERE marker_id = $1 AND interaction_type = $2
+                ORDER BY created_at DESC
+                "#
+            )
+            .bind(marker_id)
+            .bind(it)
+        } else {
+            sqlx::query_as::<_, MemberMarker>(
+                r#"
+                SELECT * FROM bigpicture.member_markers 
+                WHERE marker_id = $1 AND interaction_type IN ('liked', 'disliked')
+                ORDER BY created_at DESC
+                "#
+            )
+            .bind(marker_id)
+        };
+
+        let member_markers = query.fetch_all(&self.pool).await?;
+        Ok(member_markers)
+    }
+
+    /// 사용자가 좋아요/싫어요한 마커 목록 조회
+    pub async fn get_user_likes(
+        &self,
+        member_id: i64,
+        like_type: Option<&str>, // None이면 모든 타입, "like" 또는 "dislike"
+        limit: Option<i32>,
+    ) -> Result<Vec<MemberMarker>> {
+        let interaction_type = like_type.map(|lt| {
+            if lt == "like" { "liked" } else { "disliked" }
+        });
+        
+        let query = if let Some(it) = interaction_type {
+            sqlx::query_as::<_, MemberMarker>(
+                r#"
+                SELECT * FROM bigpicture.member_markers 
+                WHERE member_id = $1 AND interaction_type = $2
+                ORDER BY created_at DESC
+                LIMIT $3
+                "#
+            )
+            .bind(member_id)
+            .bind(it)
+            .bind(limit.unwrap_or(50))
+        } else {
+            sqlx::query_as::<_, MemberMarker>(
+                r#"
+                SELECT * FROM bigpicture.member_markers 
+                WHERE member_id = $1 AND interaction_type IN ('liked', 'disliked')
+                ORDER BY created_at DESC
+                LIMIT $2
+                "#
+            )
+            .bind(member_id)
+            .bind(limit.unwrap_or(50))
+        };
+
+        let member_markers = query.fetch_all(&self.pool).await?;
+        Ok(member_markers)
+    }
+
+    /// 좋아요/싫어요 통계 조회
+    pub async fn get_like_stats(
+        &self,
+        marker_id: Option<i64>, // None이면 전체 통계
+    ) -> Result<serde_json::Value> {
+        let stats = if let Some(mid) = marker_id {
+            // 특정 마커의 통계
+            let (total_likes, total_dislikes) = sqlx::query_as::<_, (i64, i64)>(
+                r#"
+                SELECT 
+                    COUNT(CASE WHEN interaction_type = 'liked' THEN 1 END) as total_likes,
+                    COUNT(CASE WHEN interaction_type = 'disliked' THEN 1 END) as total_dislikes
+                FROM bigpicture.member_markers 
+                WHERE marker_id = $1
+                "#
+            )
+            .bind(mid)
+            .fetch_one(&self.pool)
+            .await?;
+
+            serde_json::json!({
+                "marker_id": mid,
+                "total_likes": total_likes,
+                "total_dislikes": total_dislikes,
+                "total_reactions": total_likes + total_dislikes
+            })
+        } else {
+            // 전체 통계
+            let (total_likes, total_dislikes, total_markers) = sqlx::query_as::<_, (i64, i64, i64)>(
+                r#"
+                SELECT 
+                    COUNT(CASE WHEN interaction_type = 'liked' THEN 1 END) as total_likes,
+                    COUNT(CASE WHEN interaction_type = 'disliked' THEN 1 END) as total_dislikes,
+                    COUNT(DISTINCT marker_id) as total_markers
+                FROM bigpicture.member_markers
+                WHERE interaction_type IN ('liked', 'disliked')
+                "#
+            )
+            .fetch_one(&self.pool)
+            .await?;
+
+            serde_json::json!({
+                "total_likes": total_likes,
+                "total_dislikes": total_dislikes,
+                "total_reactions": total_likes + total_dislikes,
+                "total_markers_with_reactions": total_markers
+            })
+        };
+
+        Ok(stats)
+    }
+
+    /// 소셜 로그인 회원 생성 (트랜잭션으로 처리)
+    pub async fn create_social_member(
+        &self,
+        email: &str,
+        nickname: &str,
+        provider_type: &str,
+        provider_id: &str,
+        provider_email: Option<&str>,
+        profile_image_url: Option<&str>,
+        region: Option<&str>,
+        gender: Option<&str>,
+        birth_year: Option<i32>,
+        personality_type: Option<&str>,
+    ) -> Result<(Member, AuthProvider)> {
+        let mut tx = self.pool.begin().await?;
+        
+        // 1. 회원 생성
+        let member = sqlx::query_as::<_, Member>(
+            r#"
+            INSERT INTO bigpicture.members
+                (email, nickname, profile_image_url, region, gender, age, personality_type, email_verified)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#
+        )
+        .bind(email)
+        .bind(nickname)
+        .bind(profile_image_url)
+        .bind(region)
+        .bind(gender)
+        .bind(birth_year)
+        .bind(personality_type)
+        .bind(provider_type != "email") // 소셜 로그인은 이메일 인증 완료로 간주
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(registration_error)?;
+
+        // 2. 인증 제공자 정보 생성
+        let auth_provider = sqlx::query_as::<_, AuthProvider>(
+            r#"
+            INSERT INTO bigpicture.auth_providers
+                (member_id, provider_type, provider_id, provider_email)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#
+        )
+        .bind(member.id)
+        .bind(provider_type)
+        .bind(provider_id)
+        .bind(provider_email)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(registration_error)?;
+
+        tx.commit().await?;
+        Ok((member, auth_provider))
+    }
+
+    /// 이메일/비밀번호 회원 생성
+    pub async fn create_email_member(
+        &self,
+        email: &str,
+        nickname: &str,
+        password_hash: &str,
+        profile_image_url: Option<&str>,
+        region: Option<&str>,
+        gender: Option<&str>,
+        birth_year: Option<i32>,
+        personality_type: Option<&str>,
+    ) -> Result<(Member, AuthProvider)> {
+        let mut tx = self.pool.begin().await?;
+        
+        // 1. 회원 생성
+        let member = sqlx::query_as::<_, Member>(
+            r#"
+            INSERT INTO bigpicture.members
+                (email, nickname, profile_image_url, region, gender, age, personality_type, email_verified)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#
+        )
+        .bind(email)
+        .bind(nickname)
+        .bind(profile_image_url)
+        .bind(region)
+        .bind(gender)
+        .bind(birth_year)
+        .bind(personality_type)
+        .bind(false) // 이메일 인증 필요
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(registration_error)?;
+
+        // 2. 인증 제공자 정보 생성
+        let auth_provider = sqlx::query_as::<_, AuthProvider>(
+            r#"
+            INSERT INTO bigpicture.auth_providers
+                (member_id, provider_type, provider_id, provider_email, password_hash)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#
+        )
+        .bind(member.id)
+        .bind("email")
+        .bind(email) // 이메일을 provider_id로 사용
+        .bind(email)
+        .bind(password_hash)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(registration_error)?;
+
+        tx.commit().await?;
+        Ok((member, auth_provider))
+    }
+
+    /// 소셜 로그인으로 기존 회원 찾기
+    pub async fn find_member_by_social_provider(
+        &self,
+        provider_type: &str,
+        provider_id: &str,
+    ) -> Result<Option<(Member, AuthProvider)>> {
+        // 먼저 auth_provider로 member_id 찾기
+        let auth_provider = sqlx::query_as::<_, AuthProvider>(
+            r#"
+            SELECT * FROM bigpicture.auth_providers 
+            WHERE provider_type = $1 AND provider_id = $2
+            "#
+        )
+        .bind(provider_type)
+        .bind(provider_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        if let Some(auth) = auth_provider {
+            // member_id로 회원 정보 찾기
+            let member = sqlx::query_as::<_, Member>(
+                r#"
+                SELECT * FROM bigpicture.members 
+                WHERE id = $1
+                "#
+            )
+            .bind(auth.member_id)
+            .fetch_optional(&self.pool)
+            .await?;
+            
+            if let Some(m) = member {
+                Ok(Some((m, auth)))
+            } else {
+                Ok(None)
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 로그인 시도 기록 (성공/실패 모두)
+    pub async fn record_login_attempt(&self, attempt: &LoginAttempt, at: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bigpicture.login_attempts
+                (email, member_id, success, failure_reason, client_ip, user_agent, created_at)
+            VALUES (LOWER(TRIM($1)), $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(&attempt.email)
+        .bind(attempt.member_id)
+        .bind(attempt.success)
+        .bind(&attempt.failure_reason)
+        .bind(&attempt.client_ip)
+        .bind(&attempt.user_agent)
+        .bind(at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 계정 잠금 해제 시각 (window 안에서 마지막 성공 이후 실패가 max_failures번 이상이면 Some)
+    /// 잠금은 그 중 가장 오래된 실패로부터 window가 지나면 풀림
+    pub async fn get_login_locked_until(
+        &self,
+        email: &str,
+        max_failures: i64,
+        window: chrono::Duration,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        let failures: Vec<chrono::DateTime<chrono::Utc>> = sqlx::query_scalar(
+            r#"
+            SELECT created_at FROM bigpicture.login_attempts
+            WHERE LOWER(email) = LOWER(TRIM($1))
+              AND success = false
+              AND created_at > $2
+              AND created_at > COALESCE((
+                  SELECT MAX(created_at) FROM bigpicture.login_attempts
+                  WHERE LOWER(email) = LOWER(TRIM($1)) AND success = true
+              ), '-infinity'::timestamptz)
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#
+        )
+        .bind(email)
+        .bind(now - window)
+        .bind(max_failures)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if max_failures <= 0 || (failures.len() as i64) < max_failures {
+            return Ok(None);
+        }
+        Ok(failures.last().map(|oldest| *oldest + window))
+    }
+
+    /// 회원의 2단계 인증 정보 (설정한 적 없으면 None)
+    pub async fn get_member_totp(&self, member_id: i64) -> Result<Option<MemberTotp>> {
+        let totp = sqlx::query_as::<_, MemberTotp>(
+            "SELECT member_id, secret, enabled_at, last_used_step FROM bigpicture.member_totp WHERE member_id = $1"
+        )
+        .bind(member_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(totp)
+    }
+
+    /// 등록 확인 전 비밀키 저장 (다시 설정하면 교체, 이미 활성화된 경우 false)
+    pub async fn save_pending_totp_secret(&self, member_id: i64, secret: &str, now: chrono::DateTime<chrono::Utc>) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO bigpicture.member_totp (member_id, secret, created_at, updated_at)
+            VALUES ($1, $2, $3, $3)
+            ON CONFLICT (member_id) DO UPDATE
+            SET secret = EXCLUDED.secret, last_used_step = NULL, updated_at = EXCLUDED.updated_at
+            WHERE bigpicture.member_totp.enabled_at IS NULL
+            "#
+        )
+        .bind(member_id)
+        .bind(secret)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 코드 사용 기록 (이전에 쓴 단계 이하면 재사용으로 보고 false), 첫 사용 시 2단계 인증 활성화
+    pub async fn consume_totp_step(&self, member_id: i64, step: i64, now: chrono::DateTime<chrono::Utc>) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bigpicture.member_totp
+            SET last_used_step = $2, enabled_at = COALESCE(enabled_at, $3), updated_at = $3
+            WHERE member_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            "#
+        )
+        .bind(member_id)
+        .bind(step)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 이메일로 기존 회원 찾기
+    pub async fn find_member_by_email(
+        &self,
+        email: &str,
+    ) -> Result<Option<(Member, AuthProvider)>> {
+        // 먼저 이메일로 회원 찾기
+        let member = sqlx::query_as::<_, Member>(
+            r#"
+            SELECT * FROM bigpicture.members 
+            WHERE email = $1
+            "#
+        )
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        if let Some(m) = member {
+            // member_id로 auth_provider 찾기
+            let auth_provider = sqlx::query_as::<_, AuthProvider>(
+                r#"
+                SELECT * FROM bigpicture.auth_providers 
+                WHERE member_id = $1
+                LIMIT 1
+                "#
+            )
+            .bind(m.id)
+            .fetch_optional(&self.pool)
+            .await?;
+            
+            if let Some(auth) = auth_provider {
+                Ok(Some((m, auth)))
+            } else {
+                Ok(None)
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 회원의 마지막 로그인 시간 업데이트
+    pub async fn update_last_login(&self, member_id: i64) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE bigpicture.members 
+            SET last_login_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(member_id)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 회원의 선호 콘텐츠 언어 업데이트
+    pub async fn update_member_preferred_languages(&self, member_id: i64, languages: &[String]) -> Result<Option<Member>> {
+        let member = sqlx::query_as::<_, Member>(
+            r#"
+            UPDATE bigpicture.members
+            SET preferred_languages = $1, updated_at = NOW()
+            WHERE id = $2
+            RETURNING *
+            "#
+        )
+        .bind(languages)
+        .bind(member_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(member)
+    }
+
+    /// 다른 회원이 이미 쓰고 있는 닉네임인지 (대소문자/앞뒤 공백 무시, 탈퇴 회원 제외)
+    pub async fn is_nickname_taken(&self, nickname: &str, excluding_member_id: i64) -> Result<bool> {
+        let taken: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM bigpicture.members
+                WHERE LOWER(TRIM(nickname)) = LOWER(TRIM($1))
+                  AND id <> $2
+                  AND deleted_at IS NULL
+            )
+            "#
+        )
+        .bind(nickname)
+        .bind(excluding_member_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(taken)
+    }
+
+    /// 프로필 수정 (None인 항목은 유지, 관심사/취미는 보낸 목록으로 교체)
+    pub async fn update_member_profile(&self, member_id: i64, update: &MemberProfileUpdate) -> Result<Option<Member>> {
+        let mut tx = self.pool.begin().await?;
+
+        let member = sqlx::query_as::<_, Member>(
+            r#"
+            UPDATE bigpicture.members
+            SET nickname = COALESCE($2, nickname),
+                region = COALESCE($3, region),
+                gender = COALESCE($4, gender),
+                age = COALESCE($5, age),
+                personality_type = COALESCE($6, personality_type),
+                profile_image_url = COALESCE($7, profile_image_url),
+                updated_at = NOW()
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING *
+            "#
+        )
+        .bind(member_id)
+        .bind(&update.nickname)
+        .bind(&update.region)
+        .bind(&update.gender)
+        .bind(update.birth_year)
+        .bind(&update.personality_type)
+        .bind(&update.profile_image_url)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(member) = member else {
+            return Ok(None);
+        };
+
+        if let Some(interests) = &update.interests {
+            sqlx::query("DELETE FROM bigpicture.member_interests WHERE member_id = $1")
+                .bind(member_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                r#"
+                WITH upserted AS (
+                    INSERT INTO bigpicture.interests (name, is_active)
+                    SELECT UNNEST($2::text[]), true
+                    ON CONFLICT (name) DO UPDATE SET is_active = true
+                    RETURNING id
+                )
+                INSERT INTO bigpicture.member_interests (member_id, interest_id)
+                SELECT $1, id FROM upserted
+                ON CONFLICT DO NOTHING
+                "#
+            )
+            .bind(member_id)
+            .bind(interests)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if let Some(hobbies) = &update.hobbies {
+            sqlx::query("DELETE FROM bigpicture.member_hobbies WHERE member_id = $1")
+                .bind(member_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                r#"
+                WITH upserted AS (
+                    INSERT INTO bigpicture.hobbies (name, is_active)
+                    SELECT UNNEST($2::text[]), true
+                    ON CONFLICT (name) DO UPDATE SET is_active = true
+                    RETURNING id
+                )
+                INSERT INTO bigpicture.member_hobbies (member_id, hobby_id)
+                SELECT $1, id FROM upserted
+                ON CONFLICT DO NOTHING
+                "#
+            )
+            .bind(member_id)
+            .bind(hobbies)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Some(member))
+    }
+
+    /// 회원에게 추가 소셜 로그인 연결
+    pub async fn link_social_provider(
+        &self,
+        member_id: i64,
+        provider_type: &str,
+        provider_id: &str,
+        provider_email: Option<&str>,
+    ) -> Result<AuthProvider> {
+        let auth_provider = sqlx::query_as::<_, AuthProvider>(
+            r#"
+            INSERT INTO bigpicture.auth_providers
+                (member_id, provider_type, provider_id, provider_email)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#
+        )
+        .bind(member_id)
+        .bind(provider_type)
+        .bind(provider_id)
+        .bind(provider_email)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(registration_error)?;
+
+        Ok(auth_provider)
+    }
+
+    // 관심사 연결
+    pub async fn add_member_interests(&self, member_id: i64, interests: &[String]) -> Result<()> {
+        for interest_name in interests {
+            // 관심사 id 찾기 또는 생성
+            let interest = sqlx::query_as::<_, Interest>(
+                r#"
+                INSERT INTO bigpicture.interests (name, is_active)
+                VALUES ($1, true)
+                ON CONFLICT (name) DO UPDATE SET is_active = true
+                RETURNING *
+                "#
+            )
+            .bind(interest_name)
+            .fetch_one(&self.pool)
+            .await?;
+            // 연결
+            sqlx::query(
+                r#"
+                INSERT INTO bigpicture.member_interests (member_id, interest_id)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+                "#
+            )
+            .bind(member_id)
+            .bind(interest.id)
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+    // 취미 연결
+    pub async fn add_member_hobbies(&self, member_id: i64, hobbies: &[String]) -> Result<()> {
+        for hobby_name in hobbies {
+            // 취미 id 찾기 또는 생성
+            let hobby = sqlx::query_as::<_, Hobby>(
+                r#"
+                INSERT INTO bigpicture.hobbies (name, is_active)
+                VALUES ($1, true)
+                ON CONFLICT (name) DO UPDATE SET is_active = true
+                RETURNING *
+                "#
+            )
+            .bind(hobby_name)
+            .fetch_one(&self.pool)
+            .await?;
+            // 연결
+            sqlx::query(
+                r#"
+                INSERT INTO bigpicture.member_hobbies (member_id, hobby_id)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+                "#
+            )
+            .bind(member_id)
+            .bind(hobby.id)
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// 마커 생성 (마커, 이미지, 카운터 초기값, 작성자 created 상호작용을 한 트랜잭션으로 저장)
+    /// 이미지는 한 번에 넣고, 타입을 지정하지 않은 이미지는 업로드 때 만든 썸네일도 함께 연결
+    pub async fn create_marker(
+        &self,
+        member_id: i64,
+        latitude: f64,
+        longitude: f64,
+        emotion_tag: &str,
+        emotion_tag_input: Option<&str>, // 사용자가 입력한 감성태그들
+        emotion: Option<&str>, // 기존 emotion 필드 (호환성 유지)
+        description: &str,
+        author: &str,
+        thumbnail_img: Option<&str>,
+        sharing_option: Option<&str>, // 추가: 공유 옵션
+        language: Option<&str>, // 설명에서 감지된 콘텐츠 언어
+        status: MarkerStatus,
+        publish_at: Option<chrono::DateTime<chrono::Utc>>, // 예약 게시 시각 (scheduled일 때만)
+        images: &[NewMarkerImage],
+    ) -> Result<(Marker, Vec<MarkerImage>)> {
+        let mut tx = self.pool.begin().await?;
+        
+        let marker = sqlx::query_as::<_, Marker>(
+            r#"
+            INSERT INTO bigpicture.markers
+                (member_id, location, emotion_tag, emotion_tag_input, emotion, description, author, thumbnail_img, sharing_option, language, status, publish_at, likes, dislikes, views)
+            VALUES ($1, ST_SetSRID(ST_MakePoint($2, $3), 4326)::geography, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, 0, 0, 0)
+            RETURNING id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, language, public_id, status, publish_at, created_at, updated_at
+            "#
+        )
+        .bind(member_id)
+        .bind(longitude) // PostGIS는 (longitude, latitude) 순서
+        .bind(latitude)
+        .bind(emotion_tag)
+        .bind(emotion_tag_input)
+        .bind(emotion)
+        .bind(description)
+        .bind(author)
+        .bind(thumbnail_img)
+        .bind(sharing_option.unwrap_or("public"))
+        .bind(language)
+        .bind(status.as_str())
+        .bind(publish_at)
+        .fetch_one(&mut *tx)
+        .await?;
+        
+        let mut added_images = Vec::new();
+        if !images.is_empty() {
+            // 요청 순서대로 id가 매겨지므로 id 순으로 정렬하면 images와 같은 순서
+            let mut inserted = sqlx::query_as::<_, MarkerImage>(
+                r#"
+                INSERT INTO bigpicture.marker_images (marker_id, image_type, image_url, image_order, is_primary)
+                SELECT $1, image_type, image_url, image_order, is_primary
+                FROM unnest($2::VARCHAR[], $3::VARCHAR[], $4::INTEGER[], $5::BOOLEAN[]) WITH ORDINALITY
+                    AS i(image_type, image_url, image_order, is_primary, ord)
+                ORDER BY ord
+                RETURNING id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
+                "#
+            )
+            .bind(marker.id)
+            .bind(images.iter().map(|image| image.image_type.clone().unwrap_or_else(|| "detail".to_string())).collect::<Vec<_>>())
+            .bind(images.iter().map(|image| image.image_url.clone()).collect::<Vec<_>>())
+            .bind(images.iter().map(|image| image.image_order).collect::<Vec<_>>())
+            .bind(images.iter().map(|image| image.is_primary).collect::<Vec<_>>())
+            .fetch_all(&mut *tx)
+            .await?;
+            inserted.sort_by_key(|image| image.id);
+            
+            let derived_ids: Vec<i32> = inserted.iter()
+                .zip(images)
+                .filter(|(_, image)| image.image_type.is_none())
+                .map(|(inserted, _)| inserted.id)
+                .collect();
+            let mut thumbnails = if derived_ids.is_empty() {
+                Vec::new()
+            } else {
+                // 클라이언트가 S3 경로 또는 전체 URL 중 어느 쪽을 저장했든 저장 위치 키로 비교 (idx_image_variants_storage_key)
+                sqlx::query_as::<_, MarkerImage>(
+                    r#"
+                    INSERT INTO bigpicture.marker_images
+                        (marker_id, image_type, image_url, image_order, is_primary, source_image_id)
+                    SELECT mi.marker_id, 'thumbnail', v.thumbnail_url, mi.image_order, false, mi.id
+                    FROM bigpicture.marker_images mi
+                    CROSS JOIN LATERAL (
+                        SELECT thumbnail_url FROM bigpicture.image_variants
+                        WHERE bigpicture.image_storage_key(image_url) = mi.storage_key
+                        LIMIT 1
+                    ) v
+                    WHERE mi.id = ANY($1)
+                    ORDER BY mi.id
+                    RETURNING id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
+                    "#
+                )
+                .bind(&derived_ids)
+                .fetch_all(&mut *tx)
+                .await?
+            };
+            
+            // 원본 바로 뒤에 그 썸네일
+            for image in inserted {
+                let id = image.id;
+                added_images.push(image);
+                if let Some(index) = thumbnails.iter().position(|thumbnail| thumbnail.source_image_id == Some(id)) {
+                    added_images.push(thumbnails.remove(index));
+                }
+            }
+        }
+        
+        sqlx::query(
+            "INSERT INTO bigpicture.member_markers (member_id, marker_id, interaction_type) VALUES ($1, $2, 'created')"
+        )
+        .bind(member_id)
+        .bind(marker.id)
+        .execute(&mut *tx)
+        .await?;
+        
+        tx.commit().await?;
+        Ok((marker, added_images))
+    }
+
+    /// 마커 해시태그 저장 (기존 태그는 모두 교체)
+    pub async fn set_marker_tags(&self, marker_id: i64, tags: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM bigpicture.marker_tags WHERE marker_id = $1")
+            .bind(marker_id)
+            .execute(&mut *tx)
+            .await?;
+        if !tags.is_empty() {
+            sqlx::query(
+                "INSERT INTO bigpicture.marker_tags (marker_id, tag) SELECT $1, unnest($2::VARCHAR[]) ON CONFLICT DO NOTHING"
+            )
+            .bind(marker_id)
+            .bind(tags)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 마커 해시태그 (저장 순서와 무관하게 이름순)
+    pub async fn get_marker_tags(&self, marker_id: i64) -> Result<Vec<String>> {
+        let tags = sqlx::query_scalar("SELECT tag FROM bigpicture.marker_tags WHERE marker_id = $1 ORDER BY tag")
+            .bind(marker_id)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(tags)
+    }
+
+    /// 인기 해시태그 (since 이후 공개 마커에 붙은 횟수순, 직전 같은 길이 구간 횟수도 함께)
+    pub async fn get_trending_tags(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        previous_since: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<TrendingTag>> {
+        let tags = sqlx::query_as::<_, TrendingTag>(
+            r#"
+            SELECT mt.tag,
+                   COUNT(*) FILTER (WHERE mt.created_at >= $1) AS marker_count,
+                   COUNT(*) FILTER (WHERE mt.created_at < $1) AS previous_count,
+                   MAX(mt.created_at) AS last_used_at
+            FROM bigpicture.marker_tags mt
+            JOIN bigpicture.markers m ON m.id = mt.marker_id
+            WHERE mt.created_at >= $2
+              AND m.taken_down_at IS NULL AND m.status = 'published'
+              AND m.sharing_option = 'public'
+            GROUP BY mt.tag
+            HAVING COUNT(*) FILTER (WHERE mt.created_at >= $1) > 0
+            ORDER BY marker_count DESC, last_used_at DESC
+            LIMIT $3
+            "#
+        )
+        .bind(since)
+        .bind(previous_since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(tags)
+    }
+
+    /// 마커 수정 (작성자 본인 마커만, 전달된 필드만 변경)
+    /// 설명이 바뀌면 콘텐츠 언어도 함께 갱신. 대상이 없거나 작성자가 아니거나 버전이 다르면 None
+    /// status를 바꾸면 publish_at도 함께 바꾸고, 처음 게시되는 마커는 게시 시각을 작성 시각으로 (피드에서 새 마커로 보이도록)
+    pub async fn update_marker(&self, marker_id: i64, member_id: i64, input: &UpdateMarkerInput) -> Result<Option<Marker>> {
+        let marker = sqlx::query_as::<_, Marker>(
+            r#"
+            UPDATE bigpicture.markers
+            SET description = COALESCE($3, description),
+                language = CASE WHEN $3 IS NULL THEN language ELSE $8 END,
+                emotion_tag = COALESCE($4, emotion_tag),
+                location = CASE WHEN $5::DOUBLE PRECISION IS NULL THEN location
+                                ELSE ST_SetSRID(ST_MakePoint($5, $6), 4326)::geography END,
+                thumbnail_img = COALESCE($7, thumbnail_img),
+                status = COALESCE($9::VARCHAR, status),
+                publish_at = CASE WHEN $9::VARCHAR IS NULL THEN publish_at ELSE $10::TIMESTAMPTZ END,
+                created_at = CASE WHEN $9::VARCHAR = 'published' AND status <> 'published' THEN NOW() ELSE created_at END,
+                sharing_option = COALESCE($11::VARCHAR, sharing_option),
+                version = version + 1,
+                updated_at = NOW()
+            WHERE id = $1 AND member_id = $2 AND ($12::INT IS NULL OR version = $12)
+            RETURNING id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, language, public_id, status, publish_at, version, created_at, updated_at
+            "#
+        )
+        .bind(marker_id)
+        .bind(member_id)
+        .bind(&input.description)
+        .bind(&input.emotion_tag)
+        .bind(input.location.map(|(_, lng)| lng)) // PostGIS는 (longitude, latitude) 순서
+        .bind(input.location.map(|(lat, _)| lat))
+        .bind(&input.thumbnail_img)
+        .bind(&input.language)
+        .bind(input.status.map(|status| status.as_str()))
+        .bind(input.publish_at)
+        .bind(input.visibility.map(|visibility| visibility.as_str()))
+        .bind(input.expected_version)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(marker)
+    }
+
+    /// 예약 시각이 지난 scheduled 마커를 published로 바꾸고 바꾼 마커를 반환
+    /// 작성 시각은 예약 시각으로 맞춤. 한 UPDATE로 바꾸므로 여러 인스턴스가 동시에 돌려도 마커마다 한 번만 반환됨
+    pub async fn publish_due_markers(&self, now: chrono::DateTime<chrono::Utc>) -> Result<Vec<Marker>> {
+        let markers = sqlx::query_as::<_, Marker>(
+            r#"
+            UPDATE bigpicture.markers
+            SET status = 'published',
+                created_at = publish_at,
+                updated_at = NOW()
+            WHERE status = 'scheduled' AND publish_at <= $1
+            RETURNING id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, language, taken_down_at, public_id, status, publish_at, created_at, updated_at
+            "#
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(markers)
+    }
+
+    /// 내 임시 저장/예약 마커 (최근 수정 순)
+    pub async fn get_member_unpublished_markers(&self, member_id: i64) -> Result<Vec<Marker>> {
+        let markers = sqlx::query_as::<_, Marker>(
+            r#"
+            SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, language, taken_down_at, public_id, status, publish_at, created_at, updated_at
+            FROM bigpicture.markers
+            WHERE member_id = $1 AND status <> 'published'
+            ORDER BY updated_at DESC
+            "#
+        )
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(markers)
+    }
+
+    /// 마커 좋아요/싫어요 처리
+    pub async fn toggle_marker_reaction(
+        &self,
+        member_id: i64,
+        marker_id: i64,
+        reaction_type: &str, // "like" 또는 "dislike"
+    ) -> Result<(i32, i32)> { // (좋아요 수, 싫어요 수) 반환
+        info!("🔍 SQL 로깅 시작: toggle_marker_reaction - member_id: {}, marker_id: {}, reaction_type: {}", member_id, marker_id, reaction_type);
+        let mut tx = self.pool.begin().await?;
+        
+        // 기존 반응 확인
+        let existing = sqlx::query_as::<_, MemberMarker>(
+            r#"
+            SELECT * FROM bigpicture.member_markers 
+            WHERE member_id = $1 AND marker_id = $2 AND interaction_type IN ('liked', 'disliked')
+            "#
+        )
+        .bind(member_id)
+        .bind(marker_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(existing_reaction) = existing {
+            if existing_reaction.interaction_type == reaction_type {
+                // 같은 반응이면 제거
+                sqlx::query(
+                    "DELETE FROM bigpicture.member_markers WHERE id = $1"
+                )
+                .bind(existing_reaction.id)
+                .execute(&mut *tx)
+                .await?;
+
+                // 마커 카운트 감소
+                let update_query = match reaction_type {
+                    "liked" => "UPDATE bigpicture.markers SET likes = GREATEST(likes - 1, 0) WHERE id = $1",
+                    "disliked" => "UPDATE bigpicture.markers SET dislikes = GREATEST(dislikes - 1, 0) WHERE id = $1",
+                    _ => return Err(anyhow::anyhow!("Invalid reaction type")),
+                };
+                sqlx::query(update_query)
+                    .bind(marker_id)
+                    .execute(&mut *tx)
+                    .await?;
+            } else {
+                // 다른 반응이면 변경
+                sqlx::query(
+                    "UPDATE bigpicture.member_markers SET interaction_type = $1, updated_at = NOW() WHERE id = $2"
+                )
+                .bind(reaction_type)
+                .bind(existing_reaction.id)
+                .execute(&mut *tx)
+                .await?;
+
+                // 마커 카운트 업데이트
+                if reaction_type == "liked" {
+                    sqlx::query(
+                        "UPDATE bigpicture.markers SET likes = likes + 1, dislikes = GREATEST(dislikes - 1, 0) WHERE id = $1"
+                    )
+                    .bind(marker_id)
+                    .execute(&mut *tx)
+                    .await?;
+                } else {
+                    sqlx::query(
+                        "UPDATE bigpicture.markers SET dislikes = dislikes + 1, likes = GREATEST(likes - 1, 0) WHERE id = $1"
+                    )
+                    .bind(marker_id)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+        } else {
+            // 새로운 반응 추가
+            sqlx::query(
+                r#"
+                INSERT INTO bigpicture.member_markers
+                    (member_id, marker_id, interaction_type)
+                VALUES ($1, $2, $3)
+                "#
+            )
+            .bind(member_id)
+            .bind(marker_id)
+            .bind(reaction_type)
+            .execute(&mut *tx)
+            .await?;
+
+            // 마커 카운트 증가
+            let update_query = match reaction_type {
+                "liked" => "UPDATE bigpicture.markers SET likes = likes + 1 WHERE id = $1",
+                "disliked" => "UPDATE bigpicture.markers SET dislikes = dislikes + 1 WHERE id = $1",
+                _ => return Err(anyhow::anyhow!("Invalid reaction type")),
+            };
+            sqlx::query(update_query)
+                .bind(marker_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        if sync_like_activity(&mut tx, member_id, marker_id).await? {
+            notify_marker_liked(&mut tx, member_id, marker_id).await?;
+        }
+
+        // 업데이트된 카운트 조회
+        let counts = sqlx::query_as::<_, (i32, i32)>(
+            "SELECT likes, dislikes FROM bigpicture.markers WHERE id = $1"
+        )
+        .bind(marker_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        info!("✅ SQL 로깅 완료: toggle_marker_reaction - 최종 결과: likes={}, dislikes={}", counts.0, counts.1);
+        Ok(counts)
+    }
+
+    /// 메모리에 모은 조회 반영 (marker_view_log 기준 window 안에 이미 센 조회자는 건너뜀), 조회수에 더한 수 반환
+    /// 회원 조회는 member_markers에 viewed 상호작용으로도 남기고, window가 지난 조회 기록은 정리
+    pub async fn add_marker_views(&self, views: &[PendingView], window: chrono::Duration, now: chrono::DateTime<chrono::Utc>) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let window_secs = window.num_seconds() as f64;
+
+        // 지워진 마커 조회는 버림
+        let counted: Vec<i64> = sqlx::query_scalar(
+            r#"
+            WITH incoming AS (
+                SELECT v.marker_id, v.viewer_key, v.viewed_at
+                FROM unnest($1::BIGINT[], $2::TEXT[], $3::TIMESTAMPTZ[]) AS v(marker_id, viewer_key, viewed_at)
+                WHERE EXISTS (SELECT 1 FROM bigpicture.markers m WHERE m.id = v.marker_id)
+            ),
+            counted AS (
+                INSERT INTO bigpicture.marker_view_log (marker_id, viewer_key, counted_at)
+                SELECT marker_id, viewer_key, viewed_at FROM incoming
+                ON CONFLICT (marker_id, viewer_key) DO UPDATE SET counted_at = EXCLUDED.counted_at
+                WHERE marker_view_log.counted_at <= EXCLUDED.counted_at - make_interval(secs => $4)
+                RETURNING marker_id
+            ),
+            per_marker AS (
+                SELECT marker_id, COUNT(*) AS view_count FROM counted GROUP BY marker_id
+            )
+            UPDATE bigpicture.markers m SET views = m.views + p.view_count
+            FROM per_marker p
+            WHERE m.id = p.marker_id
+            RETURNING p.view_count
+            "#
+        )
+        .bind(views.iter().map(|view| view.marker_id).collect::<Vec<_>>())
+        .bind(views.iter().map(|view| view.viewer_key.clone()).collect::<Vec<_>>())
+        .bind(views.iter().map(|view| view.viewed_at).collect::<Vec<_>>())
+        .bind(window_secs)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let member_views: Vec<(i64, i64)> = views.iter()
+            .filter_map(|view| view.member_id.map(|member_id| (member_id, view.marker_id)))
+            .collect();
+        if !member_views.is_empty() {
+            sqlx::query(
+                r#"
+                INSERT INTO bigpicture.member_markers (member_id, marker_id, interaction_type)
+                SELECT v.member_id, v.marker_id, 'viewed'
+                FROM unnest($1::BIGINT[], $2::BIGINT[]) AS v(member_id, marker_id)
+                WHERE EXISTS (SELECT 1 FROM bigpicture.markers m WHERE m.id = v.marker_id)
+                  AND EXISTS (SELECT 1 FROM bigpicture.members mb WHERE mb.id = v.member_id)
+                ON CONFLICT (member_id, marker_id, interaction_type) DO NOTHING
+                "#
+            )
+            .bind(member_views.iter().map(|(member_id, _)| *member_id).collect::<Vec<_>>())
+            .bind(member_views.iter().map(|(_, marker_id)| *marker_id).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query("DELETE FROM bigpicture.marker_view_log WHERE counted_at < $1 - make_interval(secs => $2)")
+            .bind(now)
+            .bind(window_secs)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(counted.iter().map(|count| *count as u64).sum())
+    }
+
+    /// 마커 북마크 토글
+    pub async fn toggle_marker_bookmark(&self, member_id: i64, marker_id: i64) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        
+        // 기존 북마크 확인
+        let existing = sqlx::query_as::<_, MemberMarker>(
+            r#"
+            SELECT * FROM bigpicture.member_markers 
+            WHERE member_id = $1 AND marker_id = $2 AND interaction_type = 'bookmarked'
+            "#
+        )
+        .bind(member_id)
+        .bind(marker_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let is_bookmarked = if let Some(existing_bookmark) = existing {
+            // 북마크 제거
+            sqlx::query(
+                "DELETE FROM bigpicture.member_markers WHERE id = $1"
+            )
+            .bind(existing_bookmark.id)
+            .execute(&mut *tx)
+            .await?;
+            false
+        } else {
+            // 북마크 추가
+            sqlx::query(
+                r#"
+                INSERT INTO bigpicture.member_markers
+                    (member_id, marker_id, interaction_type)
+                VALUES ($1, $2, 'bookmarked')
+                "#
+            )
+            .bind(member_id)
+            .bind(marker_id)
+            .execute(&mut *tx)
+            .await?;
+            true
+        };
+
+        tx.commit().await?;
+        Ok(is_bookmarked)
+    }
+
+    /// 유저가 생성한 마커 목록 조회
+    pub async fn get_member_created_markers(&self, member_id: i64, limit: Option<i32>) -> Result<Vec<Marker>> {
+        let markers = sqlx::query_as::<_, Marker>(
+            r#"
+            SELECT id, ST_AsText(location) as location, emotion_tag, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, member_id, created_at, updated_at 
+            FROM bigpicture.markers 
+            WHERE member_id = $1 AND taken_down_at IS NULL AND status = 'published'
+            ORDER BY created_at DESC 
+            LIMIT $2
+            "#
+        )
+        .bind(member_id)
+        .bind(limit.unwrap_or(50))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(markers)
+    }
+
+    /// 유저가 좋아요한 마커 목록 조회
+    pub async fn get_member_liked_markers(&self, member_id: i64, limit: Option<i32>) -> Result<Vec<Marker>> {
+        let markers = sqlx::query_as::<_, Marker>(
+            r#"
+            SELECT m.id, ST_AsText(m.location) as location, m.emotion_tag, m.emotion, m.description, m.sharing_option, m.likes, m.dislikes, m.views, m.author, m.thumbnail_img, m.member_id, m.created_at, m.updated_at 
+            FROM bigpicture.markers m
+            INNER JOIN bigpicture.member_markers mm ON m.id = mm.marker_id
+            WHERE mm.member_id = $1 AND mm.interaction_type = 'liked' AND m.taken_down_at IS NULL
+            ORDER BY mm.created_at DESC 
+            LIMIT $2
+            "#
+        )
+        .bind(member_id)
+        .bind(limit.unwrap_or(50))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(markers)
+    }
+
+    /// 유저가 북마크한 마커 목록 조회
+    pub async fn get_member_bookmarked_markers(&self, member_id: i64, limit: Option<i32>, folder_id: Option<i64>) -> Result<Vec<BookmarkedMarker>> {
+        let markers = sqlx::query_as::<_, BookmarkedMarker>(
+            r#"
+            SELECT m.id, ST_AsText(m.location) as location, m.emotion_tag, m.emotion, m.description, m.sharing_option, m.likes, m.dislikes, m.views, m.author, m.thumbnail_img, m.member_id, m.created_at, m.updated_at,
+                   f.id AS folder_id, f.name AS folder_name, f.color AS folder_color
+            FROM bigpicture.markers m
+            INNER JOIN bigpicture.member_markers mm ON m.id = mm.marker_id
+            LEFT JOIN bigpicture.bookmark_folders f ON f.id = mm.folder_id
+            WHERE mm.member_id = $1 AND mm.interaction_type = 'bookmarked' AND m.taken_down_at IS NULL
+              AND ($3::BIGINT IS NULL OR mm.folder_id = $3)
+            ORDER BY mm.created_at DESC 
+            LIMIT $2
+            "#
+        )
+        .bind(member_id)
+        .bind(limit.unwrap_or(50))
+        .bind(folder_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(markers)
+    }
+
+    /// 회원의 북마크 폴더 (정한 순서대로, 폴더마다 북마크 수)
+    pub async fn list_bookmark_folders(&self, member_id: i64) -> Result<Vec<BookmarkFolder>> {
+        let folders = sqlx::query_as::<_, BookmarkFolder>(&format!(
+            "SELECT {} FROM bigpicture.bookmark_folders f WHERE f.member_id = $1 ORDER BY f.position, f.id",
+            BOOKMARK_FOLDER_COLUMNS
+        ))
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(folders)
+    }
+
+    pub async fn get_bookmark_folder(&self, folder_id: i64) -> Result<Option<BookmarkFolder>> {
+        let folder = sqlx::query_as::<_, BookmarkFolder>(&format!(
+            "SELECT {} FROM bigpicture.bookmark_folders f WHERE f.id = $1",
+            BOOKMARK_FOLDER_COLUMNS
+        ))
+        .bind(folder_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(folder)
+    }
+
+    /// 북마크 폴더 만들기 (맨 뒤에 추가)
+    pub async fn create_bookmark_folder(&self, member_id: i64, name: &str, color: Option<&str>) -> Result<BookmarkFolder> {
+        let folder = sqlx::query_as::<_, BookmarkFolder>(&format!(
+            r#"
+            WITH f AS (
+                INSERT INTO bigpicture.bookmark_folders (member_id, name, color, position)
+                SELECT $1, $2, $3, COALESCE(MAX(position), 0) + 1
+                FROM bigpicture.bookmark_folders WHERE member_id = $1
+                RETURNING *
+            )
+            SELECT {} FROM f
+            "#,
+            BOOKMARK_FOLDER_COLUMNS
+        ))
+        .bind(member_id)
+        .bind(name)
+        .bind(color)
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok(folder)
+    }
+
+    /// 북마크 폴더 이름/색 수정 (None은 그대로, 색을 빈 문자열로 주면 지움)
+    pub async fn update_bookmark_folder(&self, folder_id: i64, name: Option<&str>, color: Option<&str>) -> Result<Option<BookmarkFolder>> {
+        let folder = sqlx::query_as::<_, BookmarkFolder>(&format!(
+            r#"
+            WITH f AS (
+                UPDATE bigpicture.bookmark_folders
+                SET name = COALESCE($2, name),
+                    color = CASE WHEN $3::TEXT IS NULL THEN color ELSE NULLIF($3, '') END,
+                    updated_at = NOW()
+                WHERE id = $1
+                RETURNING *
+            )
+            SELECT {} FROM f
+            "#,
+            BOOKMARK_FOLDER_COLUMNS
+        ))
+        .bind(folder_id)
+        .bind(name)
+        .bind(color)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(folder)
+    }
+
+    /// 북마크 폴더 삭제 (들어 있던 북마크는 폴더 없음으로)
+    pub async fn delete_bookmark_folder(&self, folder_id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM bigpicture.bookmark_folders WHERE id = $1")
+            .bind(folder_id)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 북마크 폴더 순서 바꾸기 (folder_ids가 회원의 폴더와 정확히 같지 않으면 바꾸지 않고 false)
+    pub async fn reorder_bookmark_folders(&self, member_id: i64, folder_ids: &[i64]) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let mut current: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM bigpicture.bookmark_folders WHERE member_id = $1 FOR UPDATE"
+        )
+        .bind(member_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let mut requested = folder_ids.to_vec();
+        current.sort_unstable();
+        requested.sort_unstable();
+        if current != requested {
+            return Ok(false);
+        }
+        
+        sqlx::query(
+            r#"
+            UPDATE bigpicture.bookmark_folders f
+            SET position = o.position, updated_at = NOW()
+            FROM unnest($2::BIGINT[]) WITH ORDINALITY AS o(id, position)
+            WHERE f.member_id = $1 AND f.id = o.id
+            "#
+        )
+        .bind(member_id)
+        .bind(folder_ids)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        
+        Ok(true)
+    }
+
+    /// 북마크를 폴더로 옮기기 (folder_id None이면 폴더에서 뺌), 옮긴 북마크 수 반환 (북마크하지 않은 마커는 건너뜀)
+    pub async fn move_bookmarks_to_folder(&self, member_id: i64, marker_ids: &[i64], folder_id: Option<i64>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bigpicture.member_markers
+            SET folder_id = $3, updated_at = NOW()
+            WHERE member_id = $1 AND marker_id = ANY($2) AND interaction_type = 'bookmarked'
+            "#
+        )
+        .bind(member_id)
+        .bind(marker_ids)
+        .bind(folder_id)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected())
+    }
+
+    /// 마커의 상세 정보 조회
+    pub async fn get_marker_detail(&self, marker_id: i64) -> Result<Option<Marker>> {
+        let marker = sqlx::query_as::<_, Marker>(
+            "SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, shares, author, thumbnail_img, language, taken_down_at, public_id, status, publish_at, version, created_at, updated_at FROM bigpicture.markers WHERE id = $1"
+        )
+        .bind(marker_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(marker)
+    }
+
+    /// 공개 ID로 마커 조회 (공유 링크/임베드, 공개 여부는 호출하는 쪽에서 확인)
+    pub async fn get_marker_by_public_id(&self, public_id: &str) -> Result<Option<Marker>> {
+        let marker = sqlx::query_as::<_, Marker>(
+            "SELECT id, member_id, ST_AsText(location) as location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, shares, author, thumbnail_img, language, taken_down_at, public_id, status, publish_at, created_at, updated_at FROM bigpicture.markers WHERE public_id = $1"
+        )
+        .bind(public_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(marker)
+    }
+
+    /// 마커 공유 링크 만들기, (링크, 마커 공유 수) 반환
+    /// 회원은 마커마다 링크 하나만 만들고 다시 공유하면 같은 링크를 돌려주며 공유 수는 처음에만 올림
+    pub async fn create_marker_share(&self, marker_id: i64, member_id: Option<i64>) -> Result<(MarkerShare, i32)> {
+        let mut tx = self.pool.begin().await?;
+
+        let created = sqlx::query_as::<_, MarkerShare>(
+            r#"
+            INSERT INTO bigpicture.marker_shares (marker_id, member_id)
+            VALUES ($1, $2)
+            ON CONFLICT (marker_id, member_id) WHERE member_id IS NOT NULL DO NOTHING
+            RETURNING token, marker_id, member_id, opens, created_at, last_opened_at
+            "#
+        )
+        .bind(marker_id)
+        .bind(member_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let (share, shares) = match created {
+            Some(share) => {
+                let shares: i32 = sqlx::query_scalar(
+                    "UPDATE bigpicture.markers SET shares = shares + 1 WHERE id = $1 RETURNING shares"
+                )
+                .bind(marker_id)
+                .fetch_one(&mut *tx)
+                .await?;
+                (share, shares)
+            }
+            None => {
+                let share = sqlx::query_as::<_, MarkerShare>(
+                    r#"
+                    SELECT token, marker_id, member_id, opens, created_at, last_opened_at
+                    FROM bigpicture.marker_shares
+                    WHERE marker_id = $1 AND member_id = $2
+                    "#
+                )
+                .bind(marker_id)
+                .bind(member_id)
+                .fetch_one(&mut *tx)
+                .await?;
+                let shares: i32 = sqlx::query_scalar("SELECT shares FROM bigpicture.markers WHERE id = $1")
+                    .bind(marker_id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                (share, shares)
+            }
+        };
+
+        tx.commit().await?;
+        Ok((share, shares))
+    }
+
+    /// 공유 링크 열기 (열린 횟수/마지막 시각 기록, 없는 토큰이면 None)
+    pub async fn open_marker_share(&self, token: &str, now: chrono::DateTime<chrono::Utc>) -> Result<Option<MarkerShare>> {
+        let share = sqlx::query_as::<_, MarkerShare>(
+            r#"
+            UPDATE bigpicture.marker_shares
+            SET opens = opens + 1, last_opened_at = $2
+            WHERE token = $1
+            RETURNING token, marker_id, member_id, opens, created_at, last_opened_at
+            "#
+        )
+        .bind(token)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(share)
+    }
+
+    /// 3번 사용자와 마커 연결 (복합키 사용)
+    pub async fn connect_member_to_marker(&self, member_id: i64, marker_id: i64, interaction_type: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bigpicture.member_markers (member_id, marker_id, interaction_type)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (member_id, marker_id, interaction_type) 
+            DO UPDATE SET updated_at = NOW()
+            "#
+        )
+        .bind(member_id)
+        .bind(marker_id)
+        .bind(interaction_type)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+
+    /// 3번 사용자의 모든 마커 상호작용 조회
+    pub async fn get_member_marker_interactions(&self, member_id: i64) -> Result<Vec<MemberMarker>> {
+        let recs = sqlx::query_as::<_, MemberMarker>(
+            r#"
+            SELECT id, member_id, marker_id, interaction_type, created_at, updated_at
+            FROM bigpicture.member_markers 
+            WHERE member_id = $1
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(recs)
+    }
+
+    /// 3번 사용자의 특정 상호작용 타입 마커 조회
+    pub async fn get_member_markers_by_interaction(&self, member_id: i64, interaction_type: &str) -> Result<Vec<MemberMarker>> {
+        let recs = sqlx::query_as::<_, MemberMarker>(
+            r#"
+            SELECT id, member_id, marker_id, interaction_type, created_at, updated_at
+            FROM bigpicture.member_markers 
+            WHERE member_id = $1 AND interaction_type = $2
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(member_id)
+        .bind(interaction_type)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(recs)
+    }
+
+    /// 3번 사용자와 마커 상세 정보 함께 조회 (JOIN)
+    pub async fn get_member_markers_with_details(&self, member_id: i64) -> Result<Vec<(MemberMarker, Marker)>> {
+        let recs = sqlx::query(
+            r#"
+            SELECT 
+                mm.id as mm_id, mm.member_id, mm.marker_id, mm.interaction_type, 
+                mm.created_at as mm_created_at, mm.updated_at as mm_updated_at,
+                m.id as m_id, m.member_id, ST_AsText(m.location) as location, m.emotion_tag, m.emotion,
+                m.description, m.sharing_option, m.likes, m.dislikes, m.views, m.author, m.thumbnail_img, m.language,
+                m.created_at as m_created_at, m.updated_at as m_updated_at
+            FROM bigpicture.member_markers mm
+            JOIN bigpicture.markers m ON mm.marker_id = m.id
+            WHERE mm.member_id = $1 AND m.taken_down_at IS NULL
+            ORDER BY mm.created_at DESC
+            "#
+        )
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let mut result = Vec::new();
+        for row in recs {
+            let member_marker = MemberMarker {
+                id: row.get("mm_id"),
+                member_id: row.get("member_id"),
+                marker_id: row.get("marker_id"),
+                interaction_type: row.get("interaction_type"),
+                created_at: row.get("mm_created_at"),
+                updated_at: row.get("mm_updated_at"),
+            };
+            
+            let marker = Marker {
+                id: row.get("m_id"),
+                member_id: row.get("member_id"),
+                location: row.get("location"),
+                emotion_tag: row.get("emotion_tag"),
+                emotion_tag_input: row.get("emotion_tag_input"),
+                emotion: row.get("emotion"),
+                description: row.get("description"),
+                sharing_option: row.get("sharing_option"),
+                likes: row.get("likes"),
+                dislikes: row.get("dislikes"),
+                views: row.get("views"),
+                author: row.get("author"),
+                thumbnail_img: row.get("thumbnail_img"),
+                language: row.get("language"),
+                taken_down_at: None, // 게시 중단된 마커는 조회 조건에서 제외됨
+                public_id: None,
+                status: None,
+                publish_at: None,
+                shares: None,
+                version: None,
+                created_at: row.get("m_created_at"),
+                updated_at: row.get("m_updated_at"),
+            };
+            
+            result.push((member_marker, marker));
+        }
+        
+        Ok(result)
+    }
+
+    /// 3번 사용자의 마커 상호작용 통계 조회
+    pub async fn get_member_marker_stats(&self, member_id: i64) -> Result<serde_json::Value> {
+        let stats = sqlx::query(
+            r#"
+            SELECT 
+                interaction_type,
+                COUNT(*) as count,
+                MIN(created_at) as first_interaction,
+                MAX(created_at) as last_interaction
+            FROM bigpicture.member_markers 
+            WHERE member_id = $1
+            GROUP BY interaction_type
+            ORDER BY count DESC
+            "#
+        )
+        .bind(member_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let mut result = serde_json::Map::new();
+        for row in stats {
+            let interaction_type: String = row.get("interaction_type");
+            let count: i64 = row.get("count");
+            let first_interaction: Option<chrono::DateTime<chrono::Utc>> = row.get("first_interaction");
+            let last_interaction: Option<chrono::DateTime<chrono::Utc>> = row.get("last_interaction");
+            
+            let mut interaction_data = serde_json::Map::new();
+            interaction_data.insert("count".to_string(), serde_json::Value::Number(count.into()));
+            if let Some(first) = first_interaction {
+                interaction_data.insert("first_interaction".to_string(), serde_json::Value::String(first.to_rfc3339()));
+            }
+            if let Some(last) = last_interaction {
+                interaction_data.insert("last_interaction".to_string(), serde_json::Value::String(last.to_rfc3339()));
+            }
+            
+            result.insert(interaction_type, serde_json::Value::Object(interaction_data));
+        }
+        
+        Ok(serde_json::Value::Object(result))
+    }
+
+    /// 좌표 반경 안의 마커 (GIST 인덱스 KNN으로 가까운 순, "내 주변" 화면용)
+    /// 로그인했으면 내 마커와 팔로우한 작성자의 followers 마커도 포함
+    pub async fn get_nearby_markers(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        radius_m: f64,
+        emotion_tags: Option<&[String]>,
+        current_user_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<NearbyMarkerHit>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "WITH origin AS (SELECT ST_SetSRID(ST_MakePoint("
+        );
+        query.push_bind(longitude)
+            .push(", ")
+            .push_bind(latitude)
+            .push(
+                "), 4326)::geography AS point)
+                 SELECT m.id, m.member_id, ST_AsText(m.location) as location, m.emotion_tag, m.emotion_tag_input, m.emotion, m.description, m.sharing_option,
+                        m.likes, m.dislikes, m.views, m.author, m.thumbnail_img, m.language, m.created_at, m.updated_at,
+                        ST_Distance(m.location, origin.point) AS distance_m
+                 FROM bigpicture.markers m, origin
+                 WHERE m.taken_down_at IS NULL AND m.status = 'published'
+                   AND ST_DWithin(m.location, origin.point, "
+            )
+            .push_bind(radius_m)
+            .push(")");
+        query.push(" AND ").push(visibility_condition("m.", current_user_id));
+        push_marker_filters(&mut query, emotion_tags, None, None);
+        // <-> 정렬은 location GIST 인덱스로 가까운 것부터 읽음 (반경 전체를 거리 계산 후 정렬하지 않음)
+        query.push(" ORDER BY m.location <-> origin.point, m.id LIMIT ").push_bind(limit);
+        
+        let hits = query.build_query_as::<NearbyMarkerHit>()
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(hits)
+    }
+
+    /// 좌표 반경 안의 공개 마커 (가까운 순, 마커 작성 중 위치 추천용)
+    pub async fn get_nearby_public_markers(&self, latitude: f64, longitude: f64, radius_m: f64, limit: i64) -> Result<Vec<NearbyMarker>> {
+        let markers = sqlx::query_as::<_, NearbyMarker>(
+            r#"
+            SELECT m.id, m.description, m.emotion_tag, m.thumbnail_img, m.likes,
+                   ST_Y(m.location::geometry) AS latitude,
+                   ST_X(m.location::geometry) AS longitude,
+                   ST_Distance(m.location, ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography) AS distance_m
+            FROM bigpicture.markers m
+            WHERE m.taken_down_at IS NULL AND m.status = 'published'
+              AND COALESCE(m.sharing_option, 'public') = 'public'
+              AND ST_DWithin(m.location, ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography, $3)
+            ORDER BY distance_m, m.id
+            LIMIT $4
+            "#
+        )
+        .bind(latitude)
+        .bind(longitude)
+        .bind(radius_m)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(markers)
+    }
+
+    /// 반경 안에서 공개 마커가 min_markers개 이상 모인 지점 (약 50m 격자로 묶은 중심, 마커 많은 순)
+    pub async fn get_popular_spots(&self, latitude: f64, longitude: f64, radius_m: f64, min_markers: i64, limit: i64) -> Result<Vec<PopularSpot>> {
+        let spots = sqlx::query_as::<_, PopularSpot>(
+            r#"
+            WITH nearby AS (
+                SELECT m.location::geometry AS geom, m.likes, m.address_neighborhood, m.emotion_tag
+                FROM bigpicture.markers m
+                WHERE m.taken_down_at IS NULL AND m.status = 'published'
+                  AND COALESCE(m.sharing_option, 'public') = 'public'
+                  AND ST_DWithin(m.location, ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography, $3)
+            ), spots AS (
+                SELECT ST_Centroid(ST_Collect(geom)) AS center,
+                       COUNT(*) AS marker_count,
+                       COALESCE(SUM(likes), 0)::BIGINT AS total_likes,
+                       mode() WITHIN GROUP (ORDER BY address_neighborhood) AS neighborhood,
+                       mode() WITHIN GROUP (ORDER BY emotion_tag) AS top_emotion_tag
+                FROM nearby
+                GROUP BY ST_SnapToGrid(geom, 0.0005)
+                HAVING COUNT(*) >= $4
+            )
+            SELECT ST_Y(center) AS latitude, ST_X(center) AS longitude, marker_count, total_likes, neighborhood, top_emotion_tag,
+                   ST_Distance(center::geography, ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography) AS distance_m
+            FROM spots
+            ORDER BY marker_count DESC, distance_m
+            LIMIT $5
+            "#
+        )
+        .bind(latitude)
+        .bind(longitude)
+        .bind(radius_m)
+        .bind(min_markers)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(spots)
+    }
+
+    /// 영역 안 마커를 grid_degrees 격자로 묶은 칸별 개수와 평균 좌표 (클러스터와 같은 필터)
+    pub async fn get_marker_grid_counts(&self, envelope: (f64, f64, f64, f64), grid_degrees: f64, filters: &ClusterFilters) -> Result<Vec<MarkerGridCell>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT AVG(ST_Y(m.location::geometry)) AS latitude, AVG(ST_X(m.location::geometry)) AS longitude, COUNT(*) AS marker_count
+             FROM bigpicture.markers m
+             WHERE m.taken_down_at IS NULL AND m.status = 'published'"
+        );
+        push_envelope_filter(&mut query, "m.location", envelope);
+        if let Some(uid) = filters.user_id {
+            query.push(" AND member_id = ").push_bind(uid);
+        }
+        query.push(" AND ").push(visibility_condition("m.", filters.viewer_id));
+        push_marker_filters(&mut query, Some(&filters.emotion_tags), filters.min_likes, filters.min_views);
+        filters.time_filter.push_sql_conditions(&mut query);
+        query.push(" GROUP BY ST_SnapToGrid(m.location::geometry, ").push_bind(grid_degrees).push(")");
+        
+        let cells = query.build_query_as::<MarkerGridCell>()
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(cells)
+    }
+
+    /// 감정 히트맵: 공개 마커를 ST_SnapToGrid 격자점별로 셈 (감정은 쉼표로 여러 개 저장되므로 하나라도 겹치면 포함)
+    pub async fn get_emotion_heatmap(
+        &self,
+        envelope: (f64, f64, f64, f64),
+        grid_degrees: f64,
+        emotion_tags: Option<&[String]>,
+        from: Option<chrono::DateTime<chrono::Utc>>,
+        to: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Vec<HeatmapCell>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT ST_Y(cell) AS latitude, ST_X(cell) AS longitude, COUNT(*) AS marker_count
+             FROM (
+                 SELECT ST_SnapToGrid(m.location::geometry, "
+        );
+        query.push_bind(grid_degrees).push(
+            ") AS cell
+                 FROM bigpicture.markers m
+                 WHERE m.taken_down_at IS NULL AND m.status = 'published' AND m.sharing_option = 'public'"
+        );
+        push_envelope_filter(&mut query, "m.location", envelope);
+        if let Some(tags) = emotion_tags.filter(|tags| !tags.is_empty()) {
+            query.push(" AND string_to_array(m.emotion_tag, ',') && ").push_bind(tags.to_vec()).push("::TEXT[]");
+        }
+        if let Some(from) = from {
+            query.push(" AND m.created_at >= ").push_bind(from);
+        }
+        if let Some(to) = to {
+            query.push(" AND m.created_at < ").push_bind(to);
+        }
+        query.push(") grid GROUP BY cell ORDER BY marker_count DESC, latitude, longitude");
+        
+        let cells = query.build_query_as::<HeatmapCell>()
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(cells)
+    }
+
+    pub async fn get_markers_cluster(
+        &self,
+        lat: f64,
+        lng: f64,
+        lat_delta: f64,
+        lng_delta: f64,
+        filters: &ClusterFilters, // 감성 태그/최소 좋아요·조회수/내 마커/로그인 회원/시간대 조건
+        sort_by: Option<&str>,
+        sort_order: Option<&str>,
+        precision: u8, // H3 해상도 (cluster_zoom::ZoomResolutions::resolve)
+    ) -> Result<Vec<serde_json::Value>> {
+        // 현재 화면보다 약간 더 넓은 영역을 조회해서 지도 이동 시 미리 로딩
+        let buffer_factor = 1.2; // 20% 더 넓은 영역 조회
+        let lat_min = lat - (lat_delta / 2.0) * buffer_factor;
+        let lat_max = lat + (lat_delta / 2.0) * buffer_factor;
+        let lng_min = lng - (lng_delta / 2.0) * buffer_factor;
+        let lng_max = lng + (lng_delta / 2.0) * buffer_factor;
+        let envelope = (lng_min, lat_min, lng_max, lat_max);
+
+        // 넓은 영역은 마커를 LIMIT만큼 잘라 오지 않고 전체 개수를 DB에서 집계
+        if precision <= cluster_zoom::AGGREGATE_MAX_RESOLUTION {
+            let cells = self.get_marker_grid_counts(envelope, cluster_zoom::aggregate_grid_degrees(precision), filters).await?;
+            return Ok(merge_grid_into_h3_clusters(&cells, precision));
+        }
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT m.id, m.member_id, ST_Y(m.location::geometry) as latitude, ST_X(m.location::geometry) as longitude, 
+                    m.emotion_tag, m.emotion_tag_input, m.emotion, m.description, m.sharing_option, m.likes, m.dislikes, m.views, m.author, m.thumbnail_img, 
+                    m.created_at, m.updated_at
+             FROM bigpicture.markers m
+             WHERE m.taken_down_at IS NULL AND m.status = 'published'"
+        );
+        push_envelope_filter(&mut query, "m.location", envelope);
+        if let Some(uid) = filters.user_id {
+            query.push(" AND member_id = ").push_bind(uid);
+        }
+        query.push(" AND ").push(visibility_condition("m.", filters.viewer_id));
+        push_marker_filters(&mut query, Some(&filters.emotion_tags), filters.min_likes, filters.min_views);
+        filters.time_filter.push_sql_conditions(&mut query);
+        query.push(" ORDER BY created_at DESC");
+        query.push(" LIMIT ").push_bind(i64::from(filters.limit.unwrap_or(1000)));
+
+        let rows = query.build()
+            .fetch_all(&self.pool)
+            .await?;
+
+        // PgRow -> MarkerClusterInfo 변환
+        let mut marker_infos = Vec::new();
+        for row in rows {
+            marker_infos.push(MarkerClusterInfo {
+                id: row.try_get("id").unwrap_or(0),
                 member_id: row.try_get("member_id").unwrap_or(0),
                 latitude: row.try_get("latitude").unwrap_or(0.0),
                 longitude: row.try_get("longitude").unwrap_or(0.0),
@@ -2288,87 +6094,150 @@ impl Database {
             });
         }
 
-        // 줌 레벨에 따른 클러스터링 조정
-        let precision = if let Some(z) = zoom {
-            if z <= 13 {
-                4 // 줌 13 이하에서는 큰 클러스터
-            } else if z == 14 {
-                5
-            } else if z == 15 {
-                8
-            } else {
-                9
-            }
-        } else {
-            if lat_delta > 2.0 || lng_delta > 2.0 {
-                3
-            } else if lat_delta > 0.5 || lng_delta > 0.5 {
-                4
-            } else if lat_delta > 0.1 || lng_delta > 0.1 {
-                5
-            } else if lat_delta > 0.03 || lng_delta > 0.03 {
-                8
-            } else {
-                9
-            }
-        };
-        // precision이 9 이상이거나 lat_delta/lng_delta가 아주 작으면 클러스터링 없이 개별 마커로 분리
-        if precision >= 9 || (lat_delta < 0.01 && lng_delta < 0.01) {
-            let all_marker_ids: Vec<i32> = marker_infos.iter().map(|m| m.id).collect();
-            use futures::stream::{FuturesUnordered, StreamExt};
-            let image_futures: FuturesUnordered<_> = all_marker_ids.iter()
-                .map(|&marker_id| {
-                    let db = &self.pool;
-                    async move {
-                        let rows = sqlx::query(
-                            r#"
-                            SELECT id, marker_id, image_type, image_url, image_order, is_primary, created_at, updated_at
-                            FROM bigpicture.marker_images 
-                            WHERE marker_id = $1
-                            ORDER BY image_order ASC
-                            "#
-                        )
-                        .bind(marker_id)
-                        .fetch_all(db)
-                        .await
-                        .unwrap_or_default();
-                        let images: Vec<MarkerImage> = rows.iter().map(|row| MarkerImage {
-                            id: row.try_get("id").unwrap_or(0),
-                            marker_id: row.try_get("marker_id").unwrap_or(0),
-                            image_type: row.try_get("image_type").unwrap_or_default(),
-                            image_url: row.try_get("image_url").unwrap_or_default(),
-                            image_order: row.try_get("image_order").unwrap_or(0),
-                            is_primary: row.try_get("is_primary").unwrap_or(false),
-                            created_at: row.try_get("created_at").unwrap_or_else(|_| chrono::Utc::now()),
-                            updated_at: row.try_get("updated_at").unwrap_or_else(|_| chrono::Utc::now()),
-                        }).collect();
-                        (marker_id, images)
-                    }
-                })
-                .collect();
-            let marker_images_map: std::collections::HashMap<i32, Vec<MarkerImage>> = 
-                image_futures.collect::<Vec<_>>().await.into_iter().collect();
-            let mut result = Vec::new();
-            for m in marker_infos {
-                let empty_vec = Vec::new();
-                let images = marker_images_map.get(&m.id).unwrap_or(&empty_vec);
-                let images_json: Vec<serde_json::Value> = images.iter().map(|img| serde_json::json!({
-                    "id": img.id,
-                    "markerId": img.marker_id,
-                    "imageType": img.image_type,
-                    "imageUrl": img.image_url,
-                    "imageOrder": img.image_order,
-                    "isPrimary": img.is_primary,
-                    "createdAt": img.created_at,
-                    "updatedAt": img.updated_at
-                })).collect();
-                result.push(serde_json::json!({
-                    "h3_index": null,
-                    "lat": m.latitude,
-                    "lng": m.longitude,
-                    "count": 1,
-                    "marker_ids": [m.id],
-                    "markers": [serde_json::json!({
+        // precision이 개별 마커 해상도 이상이거나 lat_delta/lng_delta가 아주 작으면 클러스터링 없이 개별 마커로 분리
+        if precision >= crate::cluster_zoom::INDIVIDUAL_MARKER_RESOLUTION || (lat_delta < 0.01 && lng_delta < 0.01) {
+            let all_marker_ids: Vec<i64> = marker_infos.iter().map(|m| m.id).collect();
+            use futures::stream::{FuturesUnordered, StreamExt};
+            let image_futures: FuturesUnordered<_> = all_marker_ids.iter()
+                .map(|&marker_id| {
+                    let db = &self.pool;
+                    async move {
+                        let rows = sqlx::query(
+                            r#"
+                            SELECT id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
+                            FROM bigpicture.marker_images 
+                            WHERE marker_id = $1 AND taken_down_at IS NULL
+                            ORDER BY image_order ASC
+                            "#
+                        )
+                        .bind(marker_id)
+                        .fetch_all(db)
+                        .await
+                        .unwrap_or_default();
+                        let images: Vec<MarkerImage> = rows.iter().map(|row| MarkerImage {
+                            id: row.try_get("id").unwrap_or(0),
+                            marker_id: row.try_get("marker_id").unwrap_or(0),
+                            image_type: row.try_get("image_type").unwrap_or_default(),
+                            image_url: row.try_get("image_url").unwrap_or_default(),
+                            image_order: row.try_get("image_order").unwrap_or(0),
+                            is_primary: row.try_get("is_primary").unwrap_or(false),
+                            source_image_id: row.try_get("source_image_id").unwrap_or(None),
+                            blurhash: row.try_get("blurhash").unwrap_or(None),
+                            created_at: row.try_get("created_at").unwrap_or_else(|_| chrono::Utc::now()),
+                            updated_at: row.try_get("updated_at").unwrap_or_else(|_| chrono::Utc::now()),
+                        }).collect();
+                        (marker_id, images)
+                    }
+                })
+                .collect();
+            let marker_images_map: std::collections::HashMap<i64, Vec<MarkerImage>> = 
+                image_futures.collect::<Vec<_>>().await.into_iter().collect();
+            let mut result = Vec::new();
+            for m in marker_infos {
+                let empty_vec = Vec::new();
+                let images = marker_images_map.get(&m.id).unwrap_or(&empty_vec);
+                let images_json: Vec<MarkerImageDto> = images.iter().map(MarkerImageDto::from).collect();
+                result.push(serde_json::json!({
+                    "h3_index": null,
+                    "lat": m.latitude,
+                    "lng": m.longitude,
+                    "count": 1,
+                    "marker_ids": [m.id],
+                    "markers": [serde_json::json!({
+                        "id": m.id,
+                        "memberId": m.member_id,
+                        "latitude": m.latitude,
+                        "longitude": m.longitude,
+                        "emotionTag": m.emotion_tag,
+                        "emotionTagInput": m.emotion_tag_input,
+                        "emotion": m.emotion,
+                        "description": m.description,
+                        "sharingOption": m.sharing_option,
+                        "likes": m.likes,
+                        "dislikes": m.dislikes,
+                        "views": m.views,
+                        "author": m.author,
+                        "thumbnailImg": m.thumbnail_img,
+                        "createdAt": m.created_at.to_rfc3339(),
+                        "updatedAt": m.updated_at.to_rfc3339(),
+                        "images": images_json
+                    })]
+                }));
+            }
+            return Ok(result);
+        }
+        use std::collections::HashMap;
+        let mut clusters: HashMap<u64, Vec<MarkerClusterInfo>> = HashMap::new();
+        for marker in marker_infos {
+            let h3 = H3Cell::from_point(Point::new(marker.longitude, marker.latitude), precision).unwrap();
+            let h3idx = h3.h3index();
+            clusters.entry(h3idx).or_default().push(marker);
+        }
+
+        // 모든 마커 ID 수집
+        let all_marker_ids: Vec<i64> = clusters.values()
+            .flat_map(|marker_list| marker_list.iter().map(|m| m.id))
+            .collect();
+
+        // 비동기 병렬로 모든 마커의 이미지 조회
+        use futures::stream::{FuturesUnordered, StreamExt};
+        let image_futures: FuturesUnordered<_> = all_marker_ids.iter()
+            .map(|&marker_id| {
+                let db = &self.pool;
+                async move {
+                    let rows = sqlx::query(
+                        r#"
+                        SELECT id, marker_id, image_type, image_url, image_order, is_primary, source_image_id, blurhash, created_at, updated_at
+                        FROM bigpicture.marker_images 
+                        WHERE marker_id = $1 AND taken_down_at IS NULL
+                        ORDER BY image_order ASC
+                        "#
+                    )
+                    .bind(marker_id)
+                    .fetch_all(db)
+                    .await
+                    .unwrap_or_default();
+
+                    let images: Vec<MarkerImage> = rows.iter().map(|row| MarkerImage {
+                        id: row.try_get("id").unwrap_or(0),
+                        marker_id: row.try_get("marker_id").unwrap_or(0),
+                        image_type: row.try_get("image_type").unwrap_or_default(),
+                        image_url: row.try_get("image_url").unwrap_or_default(),
+                        image_order: row.try_get("image_order").unwrap_or(0),
+                        is_primary: row.try_get("is_primary").unwrap_or(false),
+                        source_image_id: row.try_get("source_image_id").unwrap_or(None),
+                        blurhash: row.try_get("blurhash").unwrap_or(None),
+                        created_at: row.try_get("created_at").unwrap_or_else(|_| chrono::Utc::now()),
+                        updated_at: row.try_get("updated_at").unwrap_or_else(|_| chrono::Utc::now()),
+                    }).collect();
+
+                    (marker_id, images)
+                }
+            })
+            .collect();
+
+        let marker_images_map: std::collections::HashMap<i64, Vec<MarkerImage>> = 
+            image_futures.collect::<Vec<_>>().await.into_iter().collect();
+
+        // 병렬 처리를 위한 클러스터 데이터 준비
+        let cluster_data: Vec<_> = clusters.into_iter().collect();
+        
+        // 병렬로 클러스터 처리
+        let result: Vec<serde_json::Value> = tokio::task::spawn_blocking(move || {
+            cluster_data.into_par_iter().map(|(h3idx, marker_list)| {
+                let count = marker_list.len();
+                let (sum_lat, sum_lng) = marker_list.iter().fold((0.0, 0.0), |acc, m| (acc.0 + m.latitude, acc.1 + m.longitude));
+                let center_lat = sum_lat / count as f64;
+                let center_lng = sum_lng / count as f64;
+                let marker_ids: Vec<i64> = marker_list.iter().map(|m| m.id).collect();
+
+                // 병렬로 마커 JSON 변환 (이미지 포함)
+                let markers: Vec<serde_json::Value> = marker_list.par_iter().map(|m| {
+                    let empty_vec = Vec::new();
+                    let images = marker_images_map.get(&m.id).unwrap_or(&empty_vec);
+                    let images_json: Vec<MarkerImageDto> = images.iter().map(MarkerImageDto::from).collect();
+
+                    serde_json::json!({
                         "id": m.id,
                         "memberId": m.member_id,
                         "latitude": m.latitude,
@@ -2386,269 +6255,1531 @@ impl Database {
                         "createdAt": m.created_at.to_rfc3339(),
                         "updatedAt": m.updated_at.to_rfc3339(),
                         "images": images_json
-                    })]
-                }));
-            }
-            return Ok(result);
+                    })
+                }).collect();
+
+                serde_json::json!({
+                    "h3_index": format!("{:x}", h3idx),
+                    "lat": center_lat,
+                    "lng": center_lng,
+                    "count": count,
+                    "marker_ids": marker_ids,
+                    "markers": markers
+                })
+            }).collect()
+        }).await?;
+        Ok(result)
+    }
+
+    pub async fn get_markers_rank(
+        &self,
+        _lat: f64,
+        _lng: f64,
+        _lat_delta: f64,
+        _lng_delta: f64,
+        emotion_tags: Option<Vec<String>>,
+        min_likes: Option<i32>,
+        min_views: Option<i32>,
+        sort_by: Option<&str>,
+        sort_order: Option<&str>,
+        limit: Option<i32>,
+        user_id: Option<i64>,
+        viewer_id: Option<i64>,
+    ) -> Result<Vec<Marker>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, member_id, location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, language, created_at, updated_at
+             FROM bigpicture.markers WHERE taken_down_at IS NULL AND status = 'published'"
+        );
+        query.push(" AND ").push(visibility_condition("", viewer_id));
+        push_marker_filters(&mut query, emotion_tags.as_deref(), min_likes, min_views);
+        if let Some(uid) = user_id {
+            query.push(" AND member_id = ").push_bind(uid);
+        }
+        let (sort_col, order) = marker_sort_clause(sort_by, sort_order, "likes");
+        query.push(format!(" ORDER BY {} {}", sort_col, order));
+        query.push(" LIMIT ").push_bind(i64::from(limit.unwrap_or(20)));
+
+        let rows = query.build()
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut markers = Vec::new();
+        for row in rows {
+            markers.push(Marker {
+                id: row.try_get("id").unwrap_or(0),
+                member_id: row.try_get("member_id").ok(),
+                location: row.try_get("location").ok(),
+                emotion_tag: row.try_get("emotion_tag").ok(),
+                emotion_tag_input: row.try_get("emotion_tag_input").ok(),
+                emotion: row.try_get("emotion").ok(),
+                description: row.try_get("description").ok(),
+                sharing_option: row.try_get("sharing_option").ok(),
+                likes: row.try_get("likes").unwrap_or(0),
+                dislikes: row.try_get("dislikes").unwrap_or(0),
+                views: row.try_get("views").unwrap_or(0),
+                author: row.try_get("author").ok(),
+                thumbnail_img: row.try_get("thumbnail_img").ok(),
+                language: row.try_get("language").ok(),
+                taken_down_at: None, // 게시 중단된 마커는 조회 조건에서 제외됨
+                public_id: None,
+                status: None,
+                publish_at: None,
+                shares: None,
+                version: None,
+                created_at: row.try_get("created_at").unwrap_or_else(|_| chrono::Utc::now()),
+                updated_at: row.try_get("updated_at").unwrap_or_else(|_| chrono::Utc::now()),
+            });
+        }
+        Ok(markers)
+    }
+
+    /// 급상승 마커: 최근 TRENDING_WINDOW_DAYS일 마커를 반응 점수가 시간이 지날수록 줄어드는 순으로
+    /// 점수 = (좋아요·조회수 가중합) / (경과 시간(h) + 2)^TRENDING_GRAVITY (기본 가중치 3 : 0.1)
+    pub async fn get_trending_markers(&self, now: chrono::DateTime<chrono::Utc>, filter: &TrendingMarkerFilter) -> Result<Vec<TrendingMarker>> {
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT m.id, m.member_id, ST_AsText(m.location) AS location, m.emotion_tag, m.emotion_tag_input, m.emotion, m.description, m.sharing_option,
+                    m.likes, m.dislikes, m.views, m.author, m.thumbnail_img, m.language, m.public_id, m.created_at, m.updated_at,
+                    (m.likes * {like} + m.views * {view})::FLOAT8
+                        / POWER(GREATEST(EXTRACT(EPOCH FROM (",
+            like = TRENDING_LIKE_WEIGHT,
+            view = TRENDING_VIEW_WEIGHT,
+        ));
+        query.push_bind(now);
+        query.push(format!(
+            " - m.created_at))::FLOAT8 / 3600.0, 0) + 2, {gravity}) AS trending_score
+             FROM bigpicture.markers m
+             WHERE m.taken_down_at IS NULL AND m.status = 'published'",
+            gravity = TRENDING_GRAVITY,
+        ));
+        query.push(" AND m.created_at >= ").push_bind(now - chrono::Duration::days(TRENDING_WINDOW_DAYS));
+        query.push(" AND m.created_at <= ").push_bind(now);
+        query.push(" AND ").push(visibility_condition("m.", filter.viewer_id));
+        push_marker_filters(&mut query, filter.emotion_tags.as_deref(), filter.min_likes, filter.min_views);
+        if let Some(uid) = filter.user_id {
+            query.push(" AND m.member_id = ").push_bind(uid);
+        }
+        query.push(" ORDER BY trending_score DESC, m.created_at DESC, m.id DESC");
+        query.push(" LIMIT ").push_bind(i64::from(filter.limit.unwrap_or(20)));
+
+        let markers = query.build_query_as::<TrendingMarker>()
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(markers)
+    }
+
+    /// 회원 맞춤 추천 마커 (최근 RECOMMENDATION_WINDOW_DAYS일, 내 마커와 이미 좋아요한 마커 제외) + 전체 개수
+    /// 관심사/취미 이름이 해시태그·감성 태그 입력·설명에 나오는 수, 내가 좋아요한 마커의 감정과 겹치는 정도,
+    /// 회원 지역과 마커 주소(시도/시군구) 일치에 인기도를 더하고 오래될수록 줄여서 점수순
+    /// languages가 있으면 피드와 같이 해당 언어 마커만 (언어 미감지 마커는 항상 포함)
+    pub async fn get_recommended_markers(
+        &self,
+        member_id: i64,
+        now: chrono::DateTime<chrono::Utc>,
+        languages: Option<&[String]>,
+        page: i32,
+        limit: i32,
+    ) -> Result<(Vec<RecommendedMarker>, i64)> {
+        let candidates = format!(
+            r#"
+            WITH keywords AS (
+                SELECT DISTINCT LOWER(TRIM(name)) AS keyword FROM (
+                    SELECT i.name FROM bigpicture.member_interests mi JOIN bigpicture.interests i ON i.id = mi.interest_id WHERE mi.member_id = $1
+                    UNION ALL
+                    SELECT h.name FROM bigpicture.member_hobbies mh JOIN bigpicture.hobbies h ON h.id = mh.hobby_id WHERE mh.member_id = $1
+                ) names
+                WHERE TRIM(name) <> ''
+            ),
+            liked_emotions AS (
+                SELECT TRIM(tag) AS emotion_tag, COUNT(*) AS weight
+                FROM bigpicture.member_markers mm
+                JOIN bigpicture.markers lm ON lm.id = mm.marker_id,
+                     unnest(string_to_array(lm.emotion_tag, ',')) AS tag
+                WHERE mm.member_id = $1 AND mm.interaction_type = 'liked' AND TRIM(tag) <> ''
+                GROUP BY 1
+            ),
+            me AS (
+                SELECT NULLIF(TRIM(region), '') AS region FROM bigpicture.members WHERE id = $1
+            ),
+            candidates AS (
+                SELECT m.*,
+                       (SELECT COUNT(*) FROM keywords k
+                        WHERE EXISTS (SELECT 1 FROM bigpicture.marker_tags t WHERE t.marker_id = m.id AND t.tag = k.keyword)
+                           OR LOWER(COALESCE(m.emotion_tag_input, '') || ' ' || COALESCE(m.description, '')) LIKE '%' || k.keyword || '%') AS keyword_matches,
+                       (SELECT COALESCE(SUM(le.weight), 0)::BIGINT FROM liked_emotions le
+                        WHERE le.emotion_tag IN (SELECT TRIM(tag) FROM unnest(string_to_array(m.emotion_tag, ',')) AS tag)) AS emotion_affinity,
+                       COALESCE((SELECT me.region LIKE '%' || m.address_locality || '%' OR me.region LIKE '%' || m.address_region || '%' FROM me), false) AS region_match
+                FROM bigpicture.markers m
+                WHERE m.taken_down_at IS NULL AND m.status = 'published'
+                  AND m.created_at >= $2 - INTERVAL '{window} days' AND m.created_at <= $2
+                  AND m.member_id IS DISTINCT FROM $1
+                  AND ($3::TEXT[] IS NULL OR m.language IS NULL OR m.language = ANY($3))
+                  AND {visibility}
+                  AND NOT EXISTS (
+                      SELECT 1 FROM bigpicture.member_markers mine
+                      WHERE mine.member_id = $1 AND mine.marker_id = m.id AND mine.interaction_type = 'liked'
+                  )
+            )
+            "#,
+            window = RECOMMENDATION_WINDOW_DAYS,
+            visibility = visibility_condition("m.", Some(member_id)),
+        );
+
+        let total_count: i64 = sqlx::query_scalar(&format!("{} SELECT COUNT(*) FROM candidates", candidates))
+            .bind(member_id)
+            .bind(now)
+            .bind(languages)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let markers = sqlx::query_as::<_, RecommendedMarker>(&format!(
+            r#"{candidates}
+            SELECT id, member_id, ST_AsText(location) AS location, emotion_tag, emotion_tag_input, emotion, description, sharing_option,
+                   likes, dislikes, views, author, thumbnail_img, language, public_id, created_at, updated_at,
+                   keyword_matches, emotion_affinity, region_match, recommendation_score
+            FROM (
+                SELECT c.*,
+                       (c.keyword_matches * {keyword} + LEAST(c.emotion_affinity, 5) * {emotion} + CASE WHEN c.region_match THEN {region} ELSE 0 END
+                        + LN(1 + c.likes + c.views / 10.0))::FLOAT8
+                           / SQRT(GREATEST(EXTRACT(EPOCH FROM ($2 - c.created_at))::FLOAT8 / 86400.0, 0) + 1) AS recommendation_score
+                FROM candidates c
+            ) scored
+            ORDER BY recommendation_score DESC, created_at DESC, id DESC
+            LIMIT $4 OFFSET $5
+            "#,
+            candidates = candidates,
+            keyword = RECOMMENDATION_KEYWORD_WEIGHT,
+            emotion = RECOMMENDATION_EMOTION_WEIGHT,
+            region = RECOMMENDATION_REGION_WEIGHT,
+        ))
+        .bind(member_id)
+        .bind(now)
+        .bind(languages)
+        .bind(i64::from(limit))
+        .bind(i64::from((page - 1) * limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((markers, total_count))
+    }
+}
+
+/// 추천 점수 가중치 (get_recommended_markers): 관심사/취미 일치 1개, 좋아요한 감정 1회(최대 5회), 지역 일치
+pub const RECOMMENDATION_KEYWORD_WEIGHT: f64 = 3.0;
+pub const RECOMMENDATION_EMOTION_WEIGHT: f64 = 1.0;
+pub const RECOMMENDATION_REGION_WEIGHT: f64 = 2.0;
+/// 이보다 오래된 마커는 추천하지 않음
+pub const RECOMMENDATION_WINDOW_DAYS: i64 = 90;
+
+/// 추천 마커와 점수 근거
+#[derive(sqlx::FromRow, Debug)]
+pub struct RecommendedMarker {
+    #[sqlx(flatten)]
+    pub marker: Marker,
+    pub keyword_matches: i64, // 관심사/취미 일치 수
+    pub emotion_affinity: i64, // 내가 좋아요한 마커 중 같은 감정이 있던 횟수
+    pub region_match: bool,
+    pub recommendation_score: f64,
+}
+
+/// 급상승 점수 가중치와 감쇠 (get_trending_markers)
+pub const TRENDING_LIKE_WEIGHT: f64 = 3.0;
+pub const TRENDING_VIEW_WEIGHT: f64 = 0.1;
+pub const TRENDING_GRAVITY: f64 = 1.5;
+/// 이보다 오래된 마커는 급상승 순위에 넣지 않음
+pub const TRENDING_WINDOW_DAYS: i64 = 30;
+
+/// 급상승 순위 조건 (None이면 조건 없음)
+#[derive(Debug, Default)]
+pub struct TrendingMarkerFilter {
+    pub emotion_tags: Option<Vec<String>>,
+    pub min_likes: Option<i32>,
+    pub min_views: Option<i32>,
+    pub limit: Option<i32>, // 기본 20
+    pub user_id: Option<i64>, // 내 마커만 보기
+    pub viewer_id: Option<i64>, // 공개 범위 필터링용
+}
+
+/// 급상승 순위 마커와 점수
+#[derive(sqlx::FromRow, Debug)]
+pub struct TrendingMarker {
+    #[sqlx(flatten)]
+    pub marker: Marker,
+    pub trending_score: f64,
+}
+
+#[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize)]
+#[serde_with::serde_as]
+pub struct OriginalImage {
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub id: uuid::Uuid,
+    pub filename: String,
+    pub original_filename: String,
+    pub file_path: String,
+    pub file_size_mb: f64,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub format: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize)]
+#[serde_with::serde_as]
+pub struct WebpImage {
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub id: uuid::Uuid,
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub original_id: uuid::Uuid,
+    pub filename: String,
+    pub file_path: String,
+    pub file_size_mb: f64,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub image_type: String, // thumbnail, map
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+// 기존 ImageInfo는 호환성을 위해 유지
+#[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize)]
+#[serde_with::serde_as]
+pub struct ImageInfo {
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub id: uuid::Uuid,
+    pub filename: String,
+    pub original_filename: String,
+    pub file_path: String,
+    pub file_size_mb: f64,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub format: String,
+    pub image_type: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(sqlx::FromRow, Debug, serde::Serialize)]
+pub struct Marker {
+    pub id: i64,
+    pub member_id: Option<i64>, // 마커를 생성한 사용자 ID
+    pub location: Option<String>, // PostGIS geography 타입 (WKT 형식)
+    pub emotion_tag: Option<String>, // 선택된 감정들을 문자열로 전송 (예: "happy,sad,angry")
+    pub emotion_tag_input: Option<String>, // 사용자가 입력한 감성태그들 (예: "커피,맛집,데이트")
+    pub emotion: Option<String>, // 기존 emotion 필드 (호환성 유지)
+    pub description: Option<String>,
+    pub sharing_option: Option<String>, // public, friends, private
+    pub likes: i32,
+    pub dislikes: i32,
+    pub views: i32,
+    pub author: Option<String>,
+    pub thumbnail_img: Option<String>, // 기존 썸네일 필드 유지
+    #[sqlx(default)]
+    pub language: Option<String>, // 설명에서 감지된 콘텐츠 언어 (ko, ja, zh, en)
+    #[sqlx(default)]
+    pub taken_down_at: Option<chrono::DateTime<chrono::Utc>>, // 관리자 게시 중단 시각
+    #[sqlx(default)]
+    pub public_id: Option<String>, // 공유 링크/임베드용 공개 ID (public_id.rs)
+    #[sqlx(default)]
+    pub status: Option<String>, // draft, published, scheduled (MarkerStatus)
+    #[sqlx(default)]
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>, // 예약 게시 시각 (scheduled일 때만)
+    #[sqlx(default)]
+    pub shares: Option<i32>, // 공유 링크를 만든 수 (상세/피드/공유 링크 조회에서만)
+    #[sqlx(default)]
+    pub version: Option<i32>, // 낙관적 잠금 버전 (상세/수정 응답에서만)
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 마커 정렬 컬럼/방향 (허용 목록의 고정 문자열만 SQL에 들어감)
+fn marker_sort_clause(sort_by: Option<&str>, sort_order: Option<&str>, default_column: &'static str) -> (&'static str, &'static str) {
+    let column = match sort_by.map(str::to_lowercase).as_deref() {
+        Some("created_at") => "created_at",
+        Some("likes") => "likes",
+        Some("views") => "views",
+        Some("dislikes") => "dislikes",
+        _ => default_column,
+    };
+    let order = match sort_order {
+        Some(o) if o.eq_ignore_ascii_case("asc") => "ASC",
+        _ => "DESC",
+    };
+    (column, order)
+}
+
+/// 지도 영역 필터 (좌표는 바인딩)
+fn push_envelope_filter(query: &mut QueryBuilder<'_, Postgres>, column: &str, (lng_min, lat_min, lng_max, lat_max): (f64, f64, f64, f64)) {
+    query.push(format!(" AND ST_Within({}::geometry, ST_MakeEnvelope(", column))
+        .push_bind(lng_min)
+        .push(", ")
+        .push_bind(lat_min)
+        .push(", ")
+        .push_bind(lng_max)
+        .push(", ")
+        .push_bind(lat_max)
+        .push(", 4326))");
+}
+
+/// 좋아요 활동을 현재 좋아요 상태에 맞춤 (취소하면 지우고, 다시 눌러도 하나만 남김), 현재 좋아요 여부 반환
+async fn sync_like_activity(conn: &mut sqlx::PgConnection, member_id: i64, marker_id: i64) -> Result<bool> {
+    let liked: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM bigpicture.member_markers WHERE member_id = $1 AND marker_id = $2 AND interaction_type = 'liked')"
+    )
+    .bind(member_id)
+    .bind(marker_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    
+    let query = if liked {
+        r#"
+        INSERT INTO bigpicture.activities (actor_id, recipient_id, activity_type, marker_id)
+        SELECT $1, member_id, 'marker_liked', id FROM bigpicture.markers WHERE id = $2
+        ON CONFLICT (actor_id, marker_id) WHERE activity_type = 'marker_liked' DO NOTHING
+        "#
+    } else {
+        "DELETE FROM bigpicture.activities WHERE actor_id = $1 AND marker_id = $2 AND activity_type = 'marker_liked'"
+    };
+    sqlx::query(query)
+        .bind(member_id)
+        .bind(marker_id)
+        .execute(&mut *conn)
+        .await?;
+    
+    Ok(liked)
+}
+
+/// 마커 작성자 알림함에 좋아요 알림 추가 (본인 마커 제외, 같은 회원의 좋아요는 한 번만)
+/// 작성자가 좋아요 푸시를 켜 두었으면 푸시 대기 상태로 넣음
+async fn notify_marker_liked(conn: &mut sqlx::PgConnection, member_id: i64, marker_id: i64) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO bigpicture.notifications (member_id, notification_type, title, body, marker_ids, dedupe_key, push_status)
+        SELECT author.id,
+               'marker_liked',
+               '내 마커에 좋아요가 달렸어요',
+               format('%s님이 내 마커를 좋아합니다.', actor.nickname),
+               ARRAY[m.id],
+               format('%s:%s', m.id, actor.id),
+               CASE WHEN author.push_likes_enabled THEN 'pending' END
+        FROM bigpicture.markers m
+        JOIN bigpicture.members author ON author.id = m.member_id
+        JOIN bigpicture.members actor ON actor.id = $1
+        WHERE m.id = $2 AND author.id <> actor.id AND author.is_active = true
+        ON CONFLICT (member_id, notification_type, dedupe_key) DO NOTHING
+        "#
+    )
+    .bind(member_id)
+    .bind(marker_id)
+    .execute(&mut *conn)
+    .await?;
+    
+    Ok(())
+}
+
+/// 전문 검색 조건 (search_vector GIN 인덱스 사용, 공개/게시 중인 마커만)
+fn push_marker_search_filters(query: &mut QueryBuilder<'_, Postgres>, tsquery: &str, envelope: Option<(f64, f64, f64, f64)>) {
+    query.push("search_vector @@ to_tsquery('simple', ")
+        .push_bind(tsquery.to_string())
+        .push(") AND COALESCE(sharing_option, 'public') = 'public' AND taken_down_at IS NULL AND status = 'published'");
+    if let Some(envelope) = envelope {
+        push_envelope_filter(query, "location", envelope);
+    }
+}
+
+/// 검색어 → 접두어 일치 tsquery (예: "한강 야경" → "한강:* & 야경:*")
+/// 글자/숫자 외의 문자는 단어 구분으로 보고 버리므로 tsquery 문법 오류가 나지 않음. 단어가 없으면 None
+pub fn marker_search_tsquery(q: &str) -> Option<String> {
+    let terms: Vec<String> = q.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(|term| format!("{}:*", term.to_lowercase()))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" & "))
+}
+
+/// 검색어 단어 수 상한 (긴 검색어로 인한 느린 쿼리 방지)
+const MAX_SEARCH_TERMS: usize = 8;
+
+/// 감성 태그/최소 좋아요/최소 조회수 필터 (값은 모두 바인딩)
+fn push_marker_filters(query: &mut QueryBuilder<'_, Postgres>, emotion_tags: Option<&[String]>, min_likes: Option<i32>, min_views: Option<i32>) {
+    if let Some(tags) = emotion_tags.filter(|tags| !tags.is_empty()) {
+        query.push(" AND emotion_tag = ANY(").push_bind(tags.to_vec()).push(")");
+    }
+    if let Some(likes) = min_likes {
+        query.push(" AND likes >= ").push_bind(likes);
+    }
+    if let Some(views) = min_views {
+        query.push(" AND views >= ").push_bind(views);
+    }
+}
+
+/// 공개 범위 조건: 비로그인은 public만, 로그인 회원은 내 마커와 내가 팔로우하는 작성자의 followers 마커도
+/// (column_prefix는 "" 또는 "m.", 조회자 ID는 정수라 그대로 넣음)
+pub fn visibility_condition(column_prefix: &str, viewer_id: Option<i64>) -> String {
+    match viewer_id {
+        None => format!("COALESCE({}sharing_option, 'public') = 'public'", column_prefix),
+        Some(viewer_id) => format!(
+            "(COALESCE({p}sharing_option, 'public') = 'public' OR {p}member_id = {v} OR ({p}sharing_option = 'followers' AND EXISTS (SELECT 1 FROM bigpicture.member_follows f WHERE f.followee_id = {p}member_id AND f.follower_id = {v})))",
+            p = column_prefix,
+            v = viewer_id
+        ),
+    }
+}
+
+/// 회원 가입/소셜 로그인 연결 중 UNIQUE 제약 위반 (동시 가입 등으로 사전 확인을 지나친 경우)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationConflict {
+    EmailTaken, // members.email
+    ProviderAlreadyLinked, // auth_providers (제공자 계정이 다른 회원에 연결됨 / 회원에 같은 제공자가 이미 있음)
+}
+
+impl RegistrationConflict {
+    /// 응답의 errorCode
+    pub fn code(&self) -> &'static str {
+        match self {
+            RegistrationConflict::EmailTaken => "EMAIL_TAKEN",
+            RegistrationConflict::ProviderAlreadyLinked => "PROVIDER_ALREADY_LINKED",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            RegistrationConflict::EmailTaken => "이미 가입된 이메일입니다.",
+            RegistrationConflict::ProviderAlreadyLinked => "이미 다른 계정에 연결되었거나 같은 종류의 로그인이 연결된 계정입니다.",
         }
-        use std::collections::HashMap;
-        let mut clusters: HashMap<u64, Vec<MarkerClusterInfo>> = HashMap::new();
-        for marker in marker_infos {
-            let h3 = H3Cell::from_point(Point::new(marker.longitude, marker.latitude), precision).unwrap();
-            let h3idx = h3.h3index();
-            clusters.entry(h3idx).or_default().push(marker);
+    }
+}
+
+impl std::fmt::Display for RegistrationConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl std::error::Error for RegistrationConflict {}
+
+/// 업로드 시 기록한 원본 크기 (이미지 URL 형식과 무관하게 저장 위치 키로 비교)
+const IMAGE_ORIGINAL_SIZE_SQL: &str = r#"
+    SELECT original_size_bytes FROM bigpicture.image_quality
+    WHERE bigpicture.image_storage_key(image_url) = bigpicture.image_storage_key($1)
+    LIMIT 1
+"#;
+
+/// 마커 이미지 사용량 (이미지 수, 원본 크기 합계). 서버에서 자동 생성한 썸네일은 제외
+const MARKER_IMAGE_USAGE_SQL: &str = r#"
+    SELECT COUNT(*) AS image_count,
+           COALESCE(SUM((
+               SELECT iq.original_size_bytes FROM bigpicture.image_quality iq
+               WHERE bigpicture.image_storage_key(iq.image_url) = mi.storage_key
+               LIMIT 1
+           )), 0)::BIGINT AS original_bytes
+    FROM bigpicture.marker_images mi
+    WHERE mi.marker_id = $1 AND mi.source_image_id IS NULL
+"#;
+
+/// 마커 이미지 쿼터 (마커당 이미지 수는 작성자 신뢰 등급 기준)
+#[derive(Debug, Clone, Copy)]
+pub struct MarkerImageQuota {
+    pub max_images: i64,
+    pub max_original_bytes: i64,
+}
+
+/// 마커 이미지 쿼터 초과 (추가 전 사용량과 추가하려던 양)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkerImageQuotaExceeded {
+    pub used_images: i64,
+    pub used_bytes: i64,
+    pub new_images: i64,
+    pub new_bytes: i64,
+}
+
+impl MarkerImageQuotaExceeded {
+    pub fn exceeds(&self, quota: &MarkerImageQuota) -> bool {
+        self.used_images + self.new_images > quota.max_images
+            || self.used_bytes + self.new_bytes > quota.max_original_bytes
+    }
+}
+
+impl std::fmt::Display for MarkerImageQuotaExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "마커 이미지 쿼터 초과 (현재 {}개/{} bytes, 추가 {}개/{} bytes)", self.used_images, self.used_bytes, self.new_images, self.new_bytes)
+    }
+}
+
+impl std::error::Error for MarkerImageQuotaExceeded {}
+
+/// 회원/인증 제공자 INSERT 오류 중 UNIQUE 위반은 RegistrationConflict로 바꿈 (나머지는 그대로)
+fn registration_error(e: sqlx::Error) -> anyhow::Error {
+    if let sqlx::Error::Database(db_error) = &e
+        && db_error.is_unique_violation()
+    {
+        match db_error.table() {
+            Some("members") if db_error.constraint().is_some_and(|name| name.contains("email")) => {
+                return RegistrationConflict::EmailTaken.into();
+            }
+            Some("auth_providers") => return RegistrationConflict::ProviderAlreadyLinked.into(),
+            _ => {}
         }
+    }
+    e.into()
+}
 
-        // 모든 마커 ID 수집
-        let all_marker_ids: Vec<i32> = clusters.values()
-            .flat_map(|marker_list| marker_list.iter().map(|m| m.id))
-            .collect();
+/// 생성 시각(한국 시간)의 시/월 추출식 (migrations의 함수 인덱스와 같은 식이어야 인덱스를 탐)
+const CREATED_HOUR_EXPR: &str = "EXTRACT(HOUR FROM (m.created_at AT TIME ZONE 'Asia/Seoul'))";
+const CREATED_MONTH_EXPR: &str = "EXTRACT(MONTH FROM (m.created_at AT TIME ZONE 'Asia/Seoul'))";
 
-        // 비동기 병렬로 모든 마커의 이미지 조회
-        use futures::stream::{FuturesUnordered, StreamExt};
-        let image_futures: FuturesUnordered<_> = all_marker_ids.iter()
-            .map(|&marker_id| {
-                let db = &self.pool;
-                async move {
-                    let rows = sqlx::query(
-                        r#"
-                        SELECT id, marker_id, image_type, image_url, image_order, is_primary, created_at, updated_at
-                        FROM bigpicture.marker_images 
-                        WHERE marker_id = $1
-                        ORDER BY image_order ASC
-                        "#
-                    )
-                    .bind(marker_id)
-                    .fetch_all(db)
-                    .await
-                    .unwrap_or_default();
+/// 한국 시간 (기간 필터의 오늘/이번 주/이번 달 기준)
+const KST_OFFSET_SECS: i32 = 9 * 3600;
 
-                    let images: Vec<MarkerImage> = rows.iter().map(|row| MarkerImage {
-                        id: row.try_get("id").unwrap_or(0),
-                        marker_id: row.try_get("marker_id").unwrap_or(0),
-                        image_type: row.try_get("image_type").unwrap_or_default(),
-                        image_url: row.try_get("image_url").unwrap_or_default(),
-                        image_order: row.try_get("image_order").unwrap_or(0),
-                        is_primary: row.try_get("is_primary").unwrap_or(false),
-                        created_at: row.try_get("created_at").unwrap_or_else(|_| chrono::Utc::now()),
-                        updated_at: row.try_get("updated_at").unwrap_or_else(|_| chrono::Utc::now()),
-                    }).collect();
+/// 마커 생성 시각 필터 (시간대, 계절, 기간)
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct CreatedTimeFilter {
+    pub hour_range: Option<(u32, u32)>, // 시작~끝 시(포함), 22-5처럼 자정을 넘길 수 있음
+    pub months: Option<Vec<u32>>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>, // 포함
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>, // 미포함
+}
 
-                    (marker_id, images)
+impl CreatedTimeFilter {
+    /// created_hour_range("22-5"), season("winter", "summer,autumn", "겨울") 파싱
+    pub fn parse(hour_range: Option<&str>, season: Option<&str>) -> std::result::Result<Self, String> {
+        let hour_range = match hour_range.map(str::trim).filter(|r| !r.is_empty()) {
+            Some(range) => {
+                let (start, end) = range.split_once('-').ok_or_else(|| format!("시간 범위 형식 오류: {}", range))?;
+                let start: u32 = start.trim().parse().map_err(|_| format!("시작 시각 오류: {}", start))?;
+                let end: u32 = end.trim().parse().map_err(|_| format!("끝 시각 오류: {}", end))?;
+                if start > 23 || end > 23 {
+                    return Err(format!("시각은 0~23이어야 합니다: {}", range));
                 }
-            })
-            .collect();
+                Some((start, end))
+            }
+            None => None,
+        };
+        
+        let months = match season.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(seasons) => {
+                let mut months = Vec::new();
+                for season in seasons.split(',').map(|s| s.trim().to_lowercase()) {
+                    let season_months: [u32; 3] = match season.as_str() {
+                        "spring" | "봄" => [3, 4, 5],
+                        "summer" | "여름" => [6, 7, 8],
+                        "autumn" | "fall" | "가을" => [9, 10, 11],
+                        "winter" | "겨울" => [12, 1, 2],
+                        _ => return Err(format!("알 수 없는 계절: {}", season)),
+                    };
+                    months.extend(season_months);
+                }
+                Some(months)
+            }
+            None => None,
+        };
+        
+        Ok(CreatedTimeFilter { hour_range, months, created_after: None, created_before: None })
+    }
+
+    /// created_after/created_before(RFC 3339 또는 YYYY-MM-DD)와 period 적용
+    /// period는 한국 시간 기준 today(오늘 0시부터), week(이번 주 월요일부터), month(이번 달 1일부터)이고,
+    /// created_after와 함께 주면 더 늦은 쪽부터
+    pub fn with_created_range(
+        mut self,
+        created_after: Option<&str>,
+        created_before: Option<&str>,
+        period: Option<&str>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> std::result::Result<Self, String> {
+        let parse = |name: &str, raw: Option<&str>| match raw.map(str::trim).filter(|raw| !raw.is_empty()) {
+            Some(raw) => parse_datetime_param(raw)
+                .map(Some)
+                .ok_or_else(|| format!("{} 형식 오류 (RFC 3339 또는 YYYY-MM-DD): {}", name, raw)),
+            None => Ok(None),
+        };
+        self.created_after = parse("created_after", created_after)?;
+        self.created_before = parse("created_before", created_before)?;
+        if let Some(period) = period.map(str::trim).filter(|period| !period.is_empty()) {
+            let start = period_start(period, now).ok_or_else(|| format!("알 수 없는 기간: {} (today, week, month)", period))?;
+            self.created_after = Some(self.created_after.map_or(start, |after| after.max(start)));
+        }
+        if let (Some(after), Some(before)) = (self.created_after, self.created_before)
+            && after >= before
+        {
+            return Err("created_after는 created_before보다 앞서야 합니다".to_string());
+        }
+        Ok(self)
+    }
+
+    /// WHERE 조건 추가 (" AND ..."로 이어 붙이고 값은 모두 바인딩, 마커 테이블 별칭은 m)
+    fn push_sql_conditions(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        if let Some((start, end)) = self.hour_range {
+            let (start, end) = (start as i32, end as i32);
+            if start <= end {
+                query.push(format!(" AND {} BETWEEN ", CREATED_HOUR_EXPR)).push_bind(start)
+                    .push(" AND ").push_bind(end);
+            } else {
+                query.push(format!(" AND ({} >= ", CREATED_HOUR_EXPR)).push_bind(start)
+                    .push(format!(" OR {} <= ", CREATED_HOUR_EXPR)).push_bind(end)
+                    .push(")");
+            }
+        }
+        if let Some(months) = self.months.as_ref().filter(|months| !months.is_empty()) {
+            let months: Vec<i32> = months.iter().map(|&month| month as i32).collect();
+            query.push(format!(" AND {} = ANY(", CREATED_MONTH_EXPR)).push_bind(months).push(")");
+        }
+        if let Some(after) = self.created_after {
+            query.push(" AND m.created_at >= ").push_bind(after);
+        }
+        if let Some(before) = self.created_before {
+            query.push(" AND m.created_at < ").push_bind(before);
+        }
+    }
+}
+
+/// 한국 시간 기준 오늘/이번 주/이번 달 시작 시각
+fn period_start(period: &str, now: chrono::DateTime<chrono::Utc>) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::{Datelike, TimeZone};
+    let kst = chrono::FixedOffset::east_opt(KST_OFFSET_SECS).expect("valid KST offset");
+    let today = now.with_timezone(&kst).date_naive();
+    let start = match period {
+        "today" => today,
+        "week" => today - chrono::Duration::days(i64::from(today.weekday().num_days_from_monday())),
+        "month" => today.with_day(1)?,
+        _ => return None,
+    };
+    kst.from_local_datetime(&start.and_hms_opt(0, 0, 0)?).single().map(|start| start.with_timezone(&chrono::Utc))
+}
+
+/// 시각 쿼리 파라미터: RFC 3339 또는 YYYY-MM-DD(UTC 자정)
+pub fn parse_datetime_param(raw: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(since) = chrono::DateTime::parse_from_rfc3339(raw) {
+        return Some(since.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc())
+}
+
+/// 로그인 시도 감사 기록 입력
+#[derive(Debug)]
+pub struct LoginAttempt {
+    pub email: String,
+    pub member_id: Option<i64>,
+    pub success: bool,
+    pub failure_reason: Option<String>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct MemberTotp {
+    pub member_id: i64,
+    pub secret: String, // Base32
+    pub enabled_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_step: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct AccountDeletionSummary {
+    pub anonymized_markers: u64,
+    pub deleted_markers: u64,
+    pub queued_images: usize,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ImageCleanupJob {
+    pub id: i64,
+    pub image_url: String,
+    pub storage_key: Option<String>,
+    pub attempts: i32,
+}
+
+/// 저장소에 파일이 있어야 하는 마커 이미지 (고아 파일 정리에서 파일이 없으면 삭제)
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredMarkerImage {
+    pub id: i32,
+    pub marker_id: i64,
+    pub image_url: String,
+    pub storage_key: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 이어 받는 업로드 세션 (tus)
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UploadSession {
+    pub id: uuid::Uuid,
+    pub image_type: String,
+    pub filename: String,
+    pub upload_length: i64,
+    pub upload_offset: i64,
+    pub output_format: String,
+    pub extract_exif: bool,
+    pub status: String, // uploading, processing, completed, failed
+    pub result: Option<String>, // 처리 결과 응답 JSON
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 새 업로드 세션 (Upload-Length와 Upload-Metadata)
+#[derive(Debug, Clone)]
+pub struct NewUploadSession {
+    pub id: uuid::Uuid,
+    pub image_type: String,
+    pub filename: String,
+    pub upload_length: i64,
+    pub output_format: String,
+    pub extract_exif: bool,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MarkerExport {
+    pub id: i64,
+    pub requested_by: i64,
+    pub format: String,
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    pub status: String,
+    pub row_count: Option<i64>,
+    pub file_key: Option<String>,
+    pub file_size: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// 멱등성 키 선점 결과
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyClaim {
+    Acquired,                       // 처음 온 요청 → 처리
+    InProgress,                     // 같은 키 요청이 아직 처리 중
+    Mismatch,                       // 같은 키를 다른 요청에 사용
+    Completed(IdempotentResponse),  // 이미 처리됨 → 저장된 응답 재사용
+}
+
+/// 멱등성 키에 저장한 응답
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdempotentResponse {
+    pub status: i32,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+const MEMBER_DATA_EXPORT_COLUMNS: &str = "id, member_id, format, status, marker_count, file_key, file_size, error, created_at, completed_at";
+
+/// 회원 개인 데이터 내보내기 작업
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MemberDataExport {
+    pub id: i64,
+    pub member_id: i64,
+    pub format: String, // zip, json
+    pub status: String,
+    pub marker_count: Option<i64>,
+    pub file_key: Option<String>,
+    pub file_size: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+const MARKER_CLAIM_COLUMNS: &str = "id, marker_id, member_id, business_name, business_registration_number, contact_phone, document_key, status, review_note, reviewed_by, reviewed_at, created_at, updated_at";
+
+/// 사업장 마커 소유 인증 신청
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MarkerClaim {
+    pub id: i64,
+    pub marker_id: i64,
+    pub member_id: i64,
+    pub business_name: String,
+    pub business_registration_number: String,
+    pub contact_phone: Option<String>,
+    pub document_key: String,
+    pub status: String,
+    pub review_note: Option<String>,
+    pub reviewed_by: Option<i64>,
+    pub reviewed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+const MARKER_PROMOTION_COLUMNS: &str = "id, marker_id, sponsor_name, district_codes, starts_at, ends_at, impression_cap, impressions, clicks, is_active, created_by, created_at, updated_at";
+
+/// 프로모션(광고) 마커
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MarkerPromotion {
+    pub id: i64,
+    pub marker_id: i64,
+    pub sponsor_name: String,
+    pub district_codes: Option<Vec<String>>, // None이면 전국
+    pub starts_at: chrono::DateTime<chrono::Utc>,
+    pub ends_at: chrono::DateTime<chrono::Utc>,
+    pub impression_cap: Option<i64>,
+    pub impressions: i64,
+    pub clicks: i64,
+    pub is_active: bool,
+    pub created_by: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 마커 생성 시 함께 저장할 이미지
+pub struct NewMarkerImage {
+    pub image_type: Option<String>, // 없으면 detail + 업로드 때 만든 썸네일 연결
+    pub image_url: String,
+    pub image_order: i32,
+    pub is_primary: bool,
+}
+
+/// 프로모션 등록 입력
+pub struct NewMarkerPromotion {
+    pub marker_id: i64,
+    pub sponsor_name: String,
+    pub district_codes: Option<Vec<String>>,
+    pub starts_at: chrono::DateTime<chrono::Utc>,
+    pub ends_at: chrono::DateTime<chrono::Utc>,
+    pub impression_cap: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromotionEvent {
+    Impression,
+    Click,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct PromotionDailyStats {
+    pub stat_date: chrono::NaiveDate,
+    pub impressions: i64,
+    pub clicks: i64,
+}
+
+/// bookmark_folders를 f로 두고 들어 있는 북마크 수와 함께 조회
+const BOOKMARK_FOLDER_COLUMNS: &str = "f.id, f.member_id, f.name, f.color, f.position, (SELECT COUNT(*) FROM bigpicture.member_markers mm WHERE mm.folder_id = f.id AND mm.interaction_type = 'bookmarked') AS bookmark_count, f.created_at, f.updated_at";
+
+/// 북마크 폴더
+#[derive(Debug, sqlx::FromRow)]
+pub struct BookmarkFolder {
+    pub id: i64,
+    pub member_id: i64,
+    pub name: String,
+    pub color: Option<String>, // #RRGGBB
+    pub position: i32,
+    pub bookmark_count: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 북마크한 마커 + 들어 있는 폴더 (폴더 없으면 None)
+#[derive(Debug, sqlx::FromRow)]
+pub struct BookmarkedMarker {
+    #[sqlx(flatten)]
+    pub marker: Marker,
+    pub folder_id: Option<i64>,
+    pub folder_name: Option<String>,
+    pub folder_color: Option<String>,
+}
+
+/// marker_collections를 c로 두고 담긴 마커 수와 함께 조회
+const MARKER_COLLECTION_COLUMNS: &str = "c.id, c.member_id, c.name, c.description, c.is_public, (SELECT COUNT(*) FROM bigpicture.marker_collection_items i WHERE i.collection_id = c.id) AS marker_count, c.created_at, c.updated_at";
+
+/// 마커 컬렉션 (앨범)
+#[derive(Debug, sqlx::FromRow)]
+pub struct MarkerCollection {
+    pub id: i64,
+    pub member_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_public: bool,
+    pub marker_count: i64, // 볼 수 없는 마커도 포함한 담긴 수
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+const MARKER_REPORT_COLUMNS: &str = "id, marker_id, reporter_id, reason, details, status, resolution_note, resolved_by, resolved_at, created_at";
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct MarkerReport {
+    pub id: i64,
+    pub marker_id: i64,
+    pub reporter_id: i64,
+    pub reason: String,
+    pub details: Option<String>,
+    pub status: String, // pending, dismissed, hidden
+    pub resolution_note: Option<String>,
+    pub resolved_by: Option<i64>,
+    pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 신고 처리 결과 (hide면 마커 게시 중단, 감사 로그용 접속 정보 포함)
+pub struct MarkerReportResolution {
+    pub admin_member_id: i64,
+    pub hide: bool,
+    pub note: Option<String>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+const CONNECTED_ACCOUNT_COLUMNS: &str = "id, member_id, platform, remote_user_id, remote_username, access_token_encrypted, refresh_token_encrypted, token_expires_at, created_at, updated_at";
+
+/// 회원이 연결한 외부 SNS 계정 (토큰은 암호문, 응답에 넣지 않음)
+#[derive(Debug, sqlx::FromRow)]
+pub struct ConnectedAccount {
+    pub id: i64,
+    pub member_id: i64,
+    pub platform: String,
+    pub remote_user_id: String,
+    pub remote_username: Option<String>,
+    pub access_token_encrypted: String,
+    pub refresh_token_encrypted: Option<String>,
+    pub token_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub struct NewConnectedAccount {
+    pub platform: String,
+    pub remote_user_id: String,
+    pub remote_username: Option<String>,
+    pub access_token_encrypted: String,
+    pub refresh_token_encrypted: Option<String>,
+    pub token_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+const MARKER_CROSSPOST_COLUMNS: &str = "id, marker_id, member_id, platform, remote_post_id, remote_url, created_at";
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct MarkerCrosspost {
+    pub id: i64,
+    pub marker_id: i64,
+    pub member_id: i64,
+    pub platform: String,
+    pub remote_post_id: String,
+    pub remote_url: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 역지오코딩으로 얻은 마커 주소
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkerAddress {
+    pub region: String, // 시도
+    pub locality: String, // 시군구
+    pub neighborhood: String, // 읍면동
+    pub full_address: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct GeocodeBackfillProgress {
+    pub total_markers: i64,
+    pub geocoded: i64,
+    pub with_address: i64,
+    pub pending: i64,
+}
+
+/// 분석용 내보내기 한 행 (공개 마커 + 상호작용 회원 수)
+#[derive(Debug, sqlx::FromRow)]
+pub struct MarkerExportRow {
+    pub id: i64,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub emotion_tag: Option<String>,
+    pub emotion_tag_input: Option<String>,
+    pub language: Option<String>,
+    pub likes: i32,
+    pub dislikes: i32,
+    pub views: i32,
+    pub liked_members: i64,
+    pub disliked_members: i64,
+    pub viewed_members: i64,
+    pub bookmarked_members: i64,
+    pub image_count: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 관리자 마커 내보내기 조건 (None인 조건은 전체, 기간은 작성 시각 기준 [from, to))
+#[derive(Debug, Clone, Default)]
+pub struct AdminMarkerExportFilter {
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub region: Option<String>, // 시도 (address_region)
+    pub district_code: Option<String>, // 행정구역 코드 (경계 폴리곤 공간 조인)
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct AdminMarkerExportRow {
+    pub id: i64,
+    pub public_id: Option<String>,
+    pub member_id: Option<i64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub emotion_tag: Option<String>,
+    pub emotion_tag_input: Option<String>,
+    pub description: Option<String>,
+    pub sharing_option: Option<String>,
+    pub status: Option<String>,
+    pub language: Option<String>,
+    pub likes: i32,
+    pub dislikes: i32,
+    pub views: i32,
+    pub address_region: Option<String>,
+    pub address_locality: Option<String>,
+    pub address_neighborhood: Option<String>,
+    pub taken_down_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 활동 타임라인 항목 (작성자 닉네임과 대상 마커 요약 포함)
+#[derive(Debug, sqlx::FromRow)]
+pub struct Activity {
+    pub id: i64,
+    pub actor_id: i64,
+    pub actor_nickname: Option<String>,
+    pub recipient_id: Option<i64>,
+    pub activity_type: String,
+    pub marker_id: Option<i64>,
+    pub marker_description: Option<String>,
+    pub marker_emotion_tag: Option<String>,
+    pub marker_thumbnail_img: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 등록된 푸시 기기 (토큰 자체는 응답에 싣지 않음)
+#[derive(Debug, sqlx::FromRow)]
+pub struct DeviceToken {
+    pub platform: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_seen_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 알림 종류별 푸시 수신 설정
+#[derive(Debug, sqlx::FromRow)]
+pub struct NotificationPreferences {
+    pub likes: bool,
+    pub comments: bool, // 댓글 기능이 생기면 사용
+    pub memories: bool, // "지난 오늘" (알림함 생성 자체를 켜고 끔)
+}
+
+/// 발송 대기에서 가져간 푸시 알림
+#[derive(Debug, sqlx::FromRow)]
+pub struct PendingPush {
+    pub id: i64,
+    pub member_id: i64,
+    pub notification_type: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub marker_ids: Option<Vec<i64>>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct Notification {
+    pub id: i64,
+    pub member_id: i64,
+    pub notification_type: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub marker_ids: Option<Vec<i64>>,
+    pub read_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 회원 프로필 수정 입력 (검증/정리된 값, None은 변경 없음)
+#[derive(Debug, Default)]
+pub struct MemberProfileUpdate {
+    pub nickname: Option<String>,
+    pub region: Option<String>,
+    pub gender: Option<String>,
+    pub birth_year: Option<i32>,
+    pub personality_type: Option<String>,
+    pub profile_image_url: Option<String>,
+    pub interests: Option<Vec<String>>,
+    pub hobbies: Option<Vec<String>>,
+}
+
+/// 마커 수정 입력 (검증된 값, None은 변경 없음)
+#[derive(Debug, Default)]
+pub struct UpdateMarkerInput {
+    pub description: Option<String>,
+    pub emotion_tag: Option<String>,
+    pub location: Option<(f64, f64)>, // (latitude, longitude)
+    pub thumbnail_img: Option<String>,
+    pub language: Option<String>, // 설명을 바꿀 때만 반영
+    pub status: Option<MarkerStatus>,
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub visibility: Option<MarkerVisibility>,
+    pub expected_version: Option<i32>, // None이면 버전 확인 없이 수정 (If-Match: *)
+}
 
-        let marker_images_map: std::collections::HashMap<i32, Vec<MarkerImage>> = 
-            image_futures.collect::<Vec<_>>().await.into_iter().collect();
+/// 행정구역 경계 등록 입력 (geometry는 GeoJSON 문자열)
+#[derive(Debug)]
+pub struct DistrictImport {
+    pub code: String,
+    pub name: String,
+    pub level: String,
+    pub parent_code: Option<String>,
+    pub geometry: String,
+}
 
-        // 병렬 처리를 위한 클러스터 데이터 준비
-        let cluster_data: Vec<_> = clusters.into_iter().collect();
-        
-        // 병렬로 클러스터 처리
-        let result: Vec<serde_json::Value> = tokio::task::spawn_blocking(move || {
-            cluster_data.into_par_iter().map(|(h3idx, marker_list)| {
-                let count = marker_list.len();
-                let (sum_lat, sum_lng) = marker_list.iter().fold((0.0, 0.0), |acc, m| (acc.0 + m.latitude, acc.1 + m.longitude));
-                let center_lat = sum_lat / count as f64;
-                let center_lng = sum_lng / count as f64;
-                let marker_ids: Vec<i32> = marker_list.iter().map(|m| m.id).collect();
+#[derive(Debug, sqlx::FromRow)]
+pub struct District {
+    pub code: String,
+    pub name: String,
+    pub level: String,
+    pub parent_code: Option<String>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
 
-                // 병렬로 마커 JSON 변환 (이미지 포함)
-                let markers: Vec<serde_json::Value> = marker_list.par_iter().map(|m| {
-                    let empty_vec = Vec::new();
-                    let images = marker_images_map.get(&m.id).unwrap_or(&empty_vec);
-                    let images_json: Vec<serde_json::Value> = images.iter().map(|img| serde_json::json!({
-                        "id": img.id,
-                        "markerId": img.marker_id,
-                        "imageType": img.image_type,
-                        "imageUrl": img.image_url,
-                        "imageOrder": img.image_order,
-                        "isPrimary": img.is_primary,
-                        "createdAt": img.created_at,
-                        "updatedAt": img.updated_at
-                    })).collect();
+#[derive(Debug)]
+pub struct DistrictStats {
+    pub marker_count: i64,
+    pub total_likes: i64,
+    pub total_views: i64,
+    pub last_marker_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub top_emotions: Vec<(String, i64)>,
+}
 
-                    serde_json::json!({
-                        "id": m.id,
-                        "memberId": m.member_id,
-                        "latitude": m.latitude,
-                        "longitude": m.longitude,
-                        "emotionTag": m.emotion_tag,
-                        "emotionTagInput": m.emotion_tag_input,
-                        "emotion": m.emotion,
-                        "description": m.description,
-                        "sharingOption": m.sharing_option,
-                        "likes": m.likes,
-                        "dislikes": m.dislikes,
-                        "views": m.views,
-                        "author": m.author,
-                        "thumbnailImg": m.thumbnail_img,
-                        "createdAt": m.created_at.to_rfc3339(),
-                        "updatedAt": m.updated_at.to_rfc3339(),
-                        "images": images_json
-                    })
-                }).collect();
+/// 마커 통계 기간 단위 (한국 시간 기준, 주는 월요일 시작)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsInterval {
+    Day,
+    Week,
+}
 
-                serde_json::json!({
-                    "h3_index": format!("{:x}", h3idx),
-                    "lat": center_lat,
-                    "lng": center_lng,
-                    "count": count,
-                    "marker_ids": marker_ids,
-                    "markers": markers
-                })
-            }).collect()
-        }).await?;
-        Ok(result)
+impl StatsInterval {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            _ => None,
+        }
     }
 
-    pub async fn get_markers_rank(
-        &self,
-        _lat: f64,
-        _lng: f64,
-        _lat_delta: f64,
-        _lng_delta: f64,
-        emotion_tags: Option<Vec<String>>,
-        min_likes: Option<i32>,
-        min_views: Option<i32>,
-        sort_by: Option<&str>,
-        sort_order: Option<&str>,
-        limit: Option<i32>,
-        user_id: Option<i64>,
-    ) -> Result<Vec<Marker>> {
-        let mut query = String::from(
-            "SELECT id, member_id, location, emotion_tag, emotion_tag_input, emotion, description, sharing_option, likes, dislikes, views, author, thumbnail_img, created_at, updated_at
-             FROM bigpicture.markers WHERE 1=1"
-        );
-        if let Some(tags) = &emotion_tags {
-            if !tags.is_empty() {
-                let tags_str = tags.iter().map(|tag| format!("'{}'", tag)).collect::<Vec<_>>().join(",");
-                query.push_str(&format!(" AND emotion_tag IN ({})", tags_str));
-            }
-        }
-        if let Some(likes) = min_likes {
-            query.push_str(&format!(" AND likes >= {}", likes));
-        }
-        if let Some(views) = min_views {
-            query.push_str(&format!(" AND views >= {}", views));
-        }
-        if let Some(uid) = user_id {
-            query.push_str(&format!(" AND member_id = {}", uid));
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
         }
-        let allowed_sort = ["created_at", "likes", "views", "dislikes"];
-        let sort_col = sort_by.filter(|s| allowed_sort.contains(&s.to_lowercase().as_str())).unwrap_or("likes");
-        let order = sort_order.filter(|o| o.eq_ignore_ascii_case("asc") || o.eq_ignore_ascii_case("desc")).unwrap_or("desc");
-        query.push_str(&format!(" ORDER BY {} {}", sort_col, order));
-        let limit_value = limit.unwrap_or(20);
-        query.push_str(&format!(" LIMIT {}", limit_value));
-
-        let rows = sqlx::query(&query)
-            .fetch_all(&self.pool)
-            .await?;
+    }
 
-        let mut markers = Vec::new();
-        for row in rows {
-            markers.push(Marker {
-                id: row.try_get("id").unwrap_or(0),
-                member_id: row.try_get("member_id").ok(),
-                location: row.try_get("location").ok(),
-                emotion_tag: row.try_get("emotion_tag").ok(),
-                emotion_tag_input: row.try_get("emotion_tag_input").ok(),
-                emotion: row.try_get("emotion").ok(),
-                description: row.try_get("description").ok(),
-                sharing_option: row.try_get("sharing_option").ok(),
-                likes: row.try_get("likes").unwrap_or(0),
-                dislikes: row.try_get("dislikes").unwrap_or(0),
-                views: row.try_get("views").unwrap_or(0),
-                author: row.try_get("author").ok(),
-                thumbnail_img: row.try_get("thumbnail_img").ok(),
-                created_at: row.try_get("created_at").unwrap_or_else(|_| chrono::Utc::now()),
-                updated_at: row.try_get("updated_at").unwrap_or_else(|_| chrono::Utc::now()),
-            });
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            Self::Day => chrono::Duration::days(1),
+            Self::Week => chrono::Duration::weeks(1),
         }
-        Ok(markers)
     }
 }
 
-#[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize)]
-#[serde_with::serde_as]
-pub struct OriginalImage {
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    pub id: uuid::Uuid,
-    pub filename: String,
-    pub original_filename: String,
-    pub file_path: String,
-    pub file_size_mb: f64,
-    pub width: Option<i32>,
-    pub height: Option<i32>,
-    pub format: String,
-    pub created_at: chrono::DateTime<chrono::Utc>,
-    pub updated_at: chrono::DateTime<chrono::Utc>,
+#[derive(Debug)]
+pub struct MarkerStats {
+    pub total_markers: i64,
+    pub by_emotion: Vec<(String, i64)>,
+    pub timeline: Vec<MarkerStatsPeriod>,
+    pub top_regions: Vec<MarkerStatsRegion>,
 }
 
-#[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize)]
-#[serde_with::serde_as]
-pub struct WebpImage {
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    pub id: uuid::Uuid,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    pub original_id: uuid::Uuid,
-    pub filename: String,
-    pub file_path: String,
-    pub file_size_mb: f64,
-    pub width: Option<i32>,
-    pub height: Option<i32>,
-    pub image_type: String, // thumbnail, map
-    pub created_at: chrono::DateTime<chrono::Utc>,
-    pub updated_at: chrono::DateTime<chrono::Utc>,
+#[derive(Debug, sqlx::FromRow)]
+pub struct MarkerStatsPeriod {
+    pub period: chrono::NaiveDate, // 기간 시작일 (한국 날짜)
+    pub marker_count: i64,
 }
 
-// 기존 ImageInfo는 호환성을 위해 유지
-#[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize)]
-#[serde_with::serde_as]
-pub struct ImageInfo {
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    pub id: uuid::Uuid,
-    pub filename: String,
-    pub original_filename: String,
-    pub file_path: String,
-    pub file_size_mb: f64,
-    pub width: Option<i32>,
-    pub height: Option<i32>,
-    pub format: String,
-    pub image_type: String,
+#[derive(Debug, sqlx::FromRow)]
+pub struct MarkerStatsRegion {
+    pub region: String, // 시도
+    pub locality: Option<String>, // 시군구
+    pub marker_count: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ApiKey {
+    pub id: i64,
+    pub key_prefix: String, // 식별용 앞부분 (원문 키는 발급 시 한 번만 노출)
+    pub name: String,
+    pub scope: String,
+    pub daily_quota: i32,
+    pub created_by: Option<i64>,
+    pub is_active: Option<bool>,
     pub created_at: chrono::DateTime<chrono::Utc>,
-    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(sqlx::FromRow, Debug, serde::Serialize)]
-pub struct Marker {
-    pub id: i32,
-    pub member_id: Option<i64>, // 마커를 생성한 사용자 ID
-    pub location: Option<String>, // PostGIS geography 타입 (WKT 형식)
-    pub emotion_tag: Option<String>, // 선택된 감정들을 문자열로 전송 (예: "happy,sad,angry")
-    pub emotion_tag_input: Option<String>, // 사용자가 입력한 감성태그들 (예: "커피,맛집,데이트")
-    pub emotion: Option<String>, // 기존 emotion 필드 (호환성 유지)
+#[derive(Debug, sqlx::FromRow)]
+pub struct ApiKeyUsage {
+    pub usage_date: chrono::NaiveDate,
+    pub endpoint: String,
+    pub request_count: i32,
+    pub rejected_count: i32,
+}
+
+/// 위치 추천: 근처 공개 마커
+#[derive(Debug, sqlx::FromRow)]
+pub struct NearbyMarker {
+    pub id: i64,
     pub description: Option<String>,
-    pub sharing_option: Option<String>, // public, friends, private
+    pub emotion_tag: Option<String>,
+    pub thumbnail_img: Option<String>,
     pub likes: i32,
-    pub dislikes: i32,
-    pub views: i32,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub distance_m: f64,
+}
+
+/// 위치 추천: 마커가 많이 모인 지점
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PopularSpot {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub marker_count: i64,
+    pub total_likes: i64,
+    pub neighborhood: Option<String>, // 가장 많은 마커의 읍면동
+    pub top_emotion_tag: Option<String>,
+    pub distance_m: f64,
+}
+
+/// 기간 내 라우트/앱 버전별 사용량 합계
+#[derive(Debug, sqlx::FromRow)]
+pub struct RouteUsageRow {
+    pub method: String,
+    pub route: String,
+    pub client_version: String,
+    pub request_count: i64,
+    pub client_error_count: i64,
+    pub server_error_count: i64,
+    pub first_used_on: chrono::NaiveDate,
+    pub last_used_on: chrono::NaiveDate,
+}
+
+/// 관리자 게시 중단/복구 요청 (감사 로그 항목과 동일한 정보)
+pub struct ContentTakedownAction {
+    pub admin_member_id: i64,
+    pub target_type: String, // marker, marker_image
+    pub target_id: i64,
+    pub action: String, // takedown, restore
+    pub reason: String,
+    pub legal_reference: Option<String>,
+    pub release_legal_hold: bool, // 복구 시 법적 보존 해제 여부
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// 활동 종류: marker_created, marker_liked(좋아요 토글에서 기록), member_followed(처음 팔로우할 때),
+/// marker_commented(댓글 기능이 생기면 기록)
+pub const ACTIVITY_MARKER_CREATED: &str = "marker_created";
+pub const ACTIVITY_MEMBER_FOLLOWED: &str = "member_followed";
+
+const ACTIVITY_SELECT: &str = r#"
+    SELECT a.id, a.actor_id, actor.nickname AS actor_nickname, a.recipient_id, a.activity_type, a.marker_id,
+           m.description AS marker_description, m.emotion_tag AS marker_emotion_tag, m.thumbnail_img AS marker_thumbnail_img, a.created_at
+    FROM bigpicture.activities a
+    JOIN bigpicture.members actor ON actor.id = a.actor_id
+    LEFT JOIN bigpicture.markers m ON m.id = a.marker_id
+"#;
+
+/// 데드레터 작업 종류 (재시도 시 원래 작업 테이블로 되돌림)
+pub const DLQ_IMAGE_CLEANUP: &str = "image_cleanup";
+pub const DLQ_MARKER_EXPORT: &str = "marker_export";
+
+const DEAD_LETTER_COLUMNS: &str = "id, job_type, source_id, payload::TEXT AS payload, attempts, last_error, status, failed_at, resolved_by, resolved_at";
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct DeadLetterJob {
+    pub id: i64,
+    pub job_type: String,
+    pub source_id: Option<i64>,
+    pub payload: String, // JSON 문자열
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub status: String, // dead, retried, discarded
+    pub failed_at: chrono::DateTime<chrono::Utc>,
+    pub resolved_by: Option<i64>,
+    pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// 데드레터 큐에 넣을 실패 작업
+pub struct NewDeadLetterJob {
+    pub job_type: String,
+    pub source_id: Option<i64>,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+}
+
+const CLIENT_ERROR_COLUMNS: &str = "id, kind, platform, app_version, device, os_version, route, request_id, http_status, message, stack_trace, member_id, report_request_id, sample_rate, created_at";
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ClientError {
+    pub id: i64,
+    pub kind: String, // crash, api_error
+    pub platform: String, // ios, android, web
+    pub app_version: Option<String>,
+    pub device: Option<String>,
+    pub os_version: Option<String>,
+    pub route: Option<String>,
+    pub request_id: Option<String>, // 실패한 API 응답의 X-Request-Id
+    pub http_status: Option<i32>,
+    pub message: String,
+    pub stack_trace: Option<String>,
+    pub member_id: Option<i64>,
+    pub report_request_id: Option<String>,
+    pub sample_rate: f32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 저장할 클라이언트 오류 보고 (길이 제한/검증은 핸들러에서)
+pub struct NewClientError {
+    pub kind: String,
+    pub platform: String,
+    pub app_version: Option<String>,
+    pub device: Option<String>,
+    pub os_version: Option<String>,
+    pub route: Option<String>,
+    pub request_id: Option<String>,
+    pub http_status: Option<i32>,
+    pub message: String,
+    pub stack_trace: Option<String>,
+    pub member_id: Option<i64>,
+    pub report_request_id: Option<String>,
+    pub sample_rate: f32,
+}
+
+/// 관리자 클라이언트 오류 목록 조건 (None이면 조건 없음)
+pub struct ClientErrorFilter {
+    pub kind: Option<String>,
+    pub platform: Option<String>,
+    pub app_version: Option<String>,
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ClientErrorSummary {
+    pub kind: String,
+    pub platform: String,
+    pub app_version: Option<String>,
+    pub reported: i64,
+    pub estimated: i64, // 샘플링 전 추정 건수
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct MemberTrustStats {
+    pub created_at: chrono::DateTime<chrono::Utc>, // 가입 시각
+    pub trust_level_override: Option<String>,
+    pub marker_count: i64, // 게시 중인 마커 수
+    pub taken_down_count: i64, // 게시 중단된 마커 수
+    pub markers_last_day: i64, // 최근 24시간 생성한 마커 수 (게시 중단 포함)
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct EmotionMonthCount {
+    pub month: String, // YYYY-MM (한국 시간)
+    pub emotion_tag: String,
+    pub marker_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct MarkerSearchHit {
+    #[sqlx(flatten)]
+    pub marker: Marker,
+    pub rank: f32, // ts_rank (설명 > 작성자 > 감성 태그 가중치)
+}
+
+/// 외부 검색 색인 문서 원본 (공개 마커 + 해시태그 + 현재 닉네임)
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MarkerSearchDocument {
+    pub id: i64,
+    pub description: Option<String>,
+    pub emotion_tag: Option<String>,
+    pub emotion_tag_input: Option<String>,
     pub author: Option<String>,
-    pub thumbnail_img: Option<String>, // 기존 썸네일 필드 유지
+    pub nickname: Option<String>,
+    pub language: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub tags: Vec<String>,
+}
+
+/// 감정 지도 타일의 칸 × 감정 태그 집계
+#[derive(Debug, sqlx::FromRow)]
+pub struct EmotionTileRow {
+    pub cell_x: i32,
+    pub cell_y: i32,
+    pub marker_count: i64, // 칸 전체 마커 수
+    pub emotion_tag: String,
+    pub tag_count: i64, // 칸 안에서 이 감정 태그가 붙은 마커 수
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct NearbyMarkerHit {
+    #[sqlx(flatten)]
+    pub marker: Marker,
+    pub distance_m: f64, // 기준 좌표에서의 거리 (미터)
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct TrendingTag {
+    pub tag: String,
+    pub marker_count: i64,
+    pub previous_count: i64, // 직전 같은 길이 구간
+    pub last_used_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct MemberSessionState {
+    pub is_active: bool,
+    pub tokens_revoked_at: Option<chrono::DateTime<chrono::Utc>>, // 이 시각 이전에 발급된 토큰은 거절
+}
+
+/// 관리자 회원 조치 요청 (감사 로그 항목과 동일한 정보)
+pub struct MemberModerationAction {
+    pub admin_member_id: i64,
+    pub member_id: i64,
+    pub action: String, // suspend, reactivate, force_logout, trust_new/basic/trusted/auto
+    pub reason: Option<String>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// 관리자 회원 목록 필터 (None이면 조건 없음)
+pub struct MemberListFilter {
+    pub email: Option<String>,
+    pub provider_type: Option<String>,
+    pub created_from: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_to: Option<chrono::DateTime<chrono::Utc>>, // 미포함
+    pub is_active: Option<bool>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct AdminMemberRow {
+    #[sqlx(flatten)]
+    pub member: Member,
+    pub provider_types: Vec<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ContentTakedownAudit {
+    pub id: i64,
+    pub admin_member_id: i64,
+    pub target_type: String,
+    pub target_id: i64,
+    pub action: String,
+    pub reason: String,
+    pub legal_reference: Option<String>,
+    pub legal_hold: bool,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
-    pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(sqlx::FromRow)]
 pub struct MarkerImage {
     pub id: i32,
-    pub marker_id: i32,
+    pub marker_id: i64,
     pub image_type: String, // thumbnail, detail, gallery
     pub image_url: String,
     pub image_order: i32,
     pub is_primary: bool,
+    #[sqlx(default)]
+    pub source_image_id: Option<i32>, // 자동 생성된 썸네일인 경우 원본(상세) 이미지 ID
+    #[sqlx(default)]
+    pub blurhash: Option<String>, // 업로드 때 계산한 자리표시자 (서버에서 변환하지 않은 외부 이미지는 없음)
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -2688,6 +7819,71 @@ impl Marker {
             }
         })
     }
+
+    /// 피드/지도/검색에 나오는 게시 상태인지 (상태 컬럼을 읽지 않은 조회는 게시된 마커만 가져옴)
+    pub fn is_published(&self) -> bool {
+        self.status.as_deref().is_none_or(|status| status == MarkerStatus::Published.as_str())
+    }
+}
+
+/// 마커 게시 상태 (published만 다른 사용자에게 보임)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerStatus {
+    Draft,
+    Published,
+    Scheduled, // publish_at이 지나면 예약 게시 작업이 published로 바꿈
+}
+
+impl MarkerStatus {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "draft" => Some(Self::Draft),
+            "published" => Some(Self::Published),
+            "scheduled" => Some(Self::Scheduled),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Published => "published",
+            Self::Scheduled => "scheduled",
+        }
+    }
+}
+
+/// 마커 공개 범위 (sharing_option 컬럼에 저장)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerVisibility {
+    Public,
+    Followers, // 작성자를 팔로우하는 회원과 작성자만
+    Private, // 작성자만
+}
+
+impl MarkerVisibility {
+    /// 예전 앱이 보내는 friends는 followers로
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "public" => Some(Self::Public),
+            "followers" | "friends" => Some(Self::Followers),
+            "private" => Some(Self::Private),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Followers => "followers",
+            Self::Private => "private",
+        }
+    }
+
+    /// 저장된 공개 범위 (값이 없으면 public)
+    pub fn of(marker: &Marker) -> Self {
+        marker.sharing_option.as_deref().and_then(Self::parse).unwrap_or(Self::Public)
+    }
 }
 
 #[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize, Debug)]
@@ -2698,7 +7894,27 @@ pub struct MemberMarker {
     pub interaction_type: String, // created, liked, disliked, viewed, bookmarked
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
-} 
+}
+
+/// 요청한 회원 기준 마커 반응/북마크 상태
+#[derive(sqlx::FromRow, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemberMarkerState {
+    pub marker_id: i64,
+    pub is_liked: bool,
+    pub is_disliked: bool,
+    pub is_bookmarked: bool,
+}
+
+/// 마커 공유 링크 (/s/{token})
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct MarkerShare {
+    pub token: String,
+    pub marker_id: i64,
+    pub member_id: Option<i64>, // 비로그인 공유는 None
+    pub opens: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_opened_at: Option<chrono::DateTime<chrono::Utc>>,
+}
 
 #[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize, Debug)]
 pub struct Member {
@@ -2715,6 +7931,23 @@ pub struct Member {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[sqlx(default)]
+    pub preferred_languages: Option<Vec<String>>, // 선호 콘텐츠 언어 (피드/추천 기본 필터)
+    #[sqlx(default)]
+    pub is_admin: Option<bool>,
+    #[sqlx(default)]
+    pub suspended_at: Option<chrono::DateTime<chrono::Utc>>, // 관리자 정지 시각 (재활성화하면 NULL)
+    #[sqlx(default)]
+    pub suspension_reason: Option<String>,
+    #[sqlx(default)]
+    pub trust_level_override: Option<String>, // 관리자 지정 신뢰 등급 (NULL이면 자동 계산)
+}
+
+impl Member {
+    /// 토큰에 담는 권한 (member, admin)
+    pub fn role(&self) -> &'static str {
+        if self.is_admin.unwrap_or(false) { "admin" } else { "member" }
+    }
 }
 
 #[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize, Debug)]
@@ -2752,7 +7985,7 @@ pub struct Interest {
 #[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize, Debug)]
 pub struct MemberHobby {
     pub id: i32,
-    pub member_id: i32,
+    pub member_id: i64,
     pub hobby_id: i32,
     pub proficiency_level: Option<i32>,
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -2761,7 +7994,7 @@ pub struct MemberHobby {
 #[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize, Debug)]
 pub struct MemberInterest {
     pub id: i32,
-    pub member_id: i32,
+    pub member_id: i64,
     pub interest_id: i32,
     pub interest_level: Option<i32>,
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -0,0 +1,923 @@
+// API 응답 DTO (DB 구조체를 그대로 노출하지 않고 camelCase로 직렬화)
+// 필드를 추가할 때 여기만 고치면 모든 핸들러 응답에 반영됨
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::database::{Activity, AdminMemberRow, ApiKey, ApiKeyUsage, AuthProvider, BookmarkFolder, ClientError, ConnectedAccount, ContentTakedownAction, ContentTakedownAudit, DeadLetterJob, Marker, MarkerClaim, MarkerCollection, MarkerExport, MarkerImage, MarkerPromotion, MarkerReport, MarkerVisibility, Member, MemberDataExport, Notification, NotificationPreferences, UploadSession};
+use crate::google_auth::GoogleIdTokenPayload;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberDto {
+    pub id: i64,
+    pub email: String,
+    pub nickname: String,
+    pub profile_image_url: Option<String>,
+    pub region: Option<String>,
+    pub gender: Option<String>,
+    pub age: Option<i32>,
+    pub personality_type: Option<String>,
+    pub is_active: bool,
+    pub email_verified: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub preferred_languages: Option<Vec<String>>,
+}
+
+impl From<&Member> for MemberDto {
+    fn from(member: &Member) -> Self {
+        Self {
+            id: member.id,
+            email: member.email.clone(),
+            nickname: member.nickname.clone(),
+            profile_image_url: member.profile_image_url.clone(),
+            region: member.region.clone(),
+            gender: member.gender.clone(),
+            age: member.age,
+            personality_type: member.personality_type.clone(),
+            is_active: member.is_active,
+            email_verified: member.email_verified,
+            created_at: member.created_at,
+            updated_at: member.updated_at,
+            last_login_at: member.last_login_at,
+            preferred_languages: member.preferred_languages.clone(),
+        }
+    }
+}
+
+/// 관리자 회원 목록 항목 (정지 정보와 연결된 로그인 수단 포함)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminMemberDto {
+    #[serde(flatten)]
+    pub member: MemberDto,
+    pub is_admin: bool,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub suspension_reason: Option<String>,
+    pub trust_level_override: Option<String>, // 관리자 지정 신뢰 등급 (없으면 자동)
+    pub provider_types: Vec<String>,
+}
+
+impl From<&AdminMemberRow> for AdminMemberDto {
+    fn from(row: &AdminMemberRow) -> Self {
+        Self {
+            member: MemberDto::from(&row.member),
+            is_admin: row.member.is_admin.unwrap_or(false),
+            suspended_at: row.member.suspended_at,
+            suspension_reason: row.member.suspension_reason.clone(),
+            trust_level_override: row.member.trust_level_override.clone(),
+            provider_types: row.provider_types.clone(),
+        }
+    }
+}
+
+/// 로그인 수단 (비밀번호 해시는 응답에 포함하지 않음)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthProviderDto {
+    pub id: i64,
+    pub member_id: i64,
+    pub provider_type: String,
+    pub provider_id: String,
+    pub provider_email: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&AuthProvider> for AuthProviderDto {
+    fn from(auth_provider: &AuthProvider) -> Self {
+        Self {
+            id: auth_provider.id,
+            member_id: auth_provider.member_id,
+            provider_type: auth_provider.provider_type.clone(),
+            provider_id: auth_provider.provider_id.clone(),
+            provider_email: auth_provider.provider_email.clone(),
+            created_at: auth_provider.created_at,
+            updated_at: auth_provider.updated_at,
+        }
+    }
+}
+
+/// 구글 ID 토큰에서 꺼낸 프로필
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleProfileDto {
+    pub email: String,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+}
+
+impl From<&GoogleIdTokenPayload> for GoogleProfileDto {
+    fn from(payload: &GoogleIdTokenPayload) -> Self {
+        Self {
+            email: payload.email.clone(),
+            name: payload.name.clone(),
+            picture: payload.picture.clone(),
+            given_name: payload.given_name.clone(),
+            family_name: payload.family_name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerImageDto {
+    pub id: i32,
+    pub marker_id: i64,
+    pub image_type: String,
+    pub image_url: String,
+    pub image_order: i32,
+    pub is_primary: bool,
+    pub source_image_id: Option<i32>,
+    pub blurhash: Option<String>, // 썸네일이 로드되기 전 그릴 자리표시자
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&MarkerImage> for MarkerImageDto {
+    fn from(image: &MarkerImage) -> Self {
+        Self {
+            id: image.id,
+            marker_id: image.marker_id,
+            image_type: image.image_type.clone(),
+            image_url: image.image_url.clone(),
+            image_order: image.image_order,
+            is_primary: image.is_primary,
+            source_image_id: image.source_image_id,
+            blurhash: image.blurhash.clone(),
+            created_at: image.created_at,
+            updated_at: image.updated_at,
+        }
+    }
+}
+
+/// 마커 (WKT location 대신 latitude/longitude, 좌표를 읽지 못하면 0)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerDto {
+    pub id: i64,
+    pub member_id: Option<i64>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub emotion_tag: Option<String>,
+    pub emotion_tag_input: Option<String>,
+    pub emotion: Option<String>,
+    pub description: Option<String>,
+    pub sharing_option: Option<String>,
+    pub visibility: &'static str, // public, followers, private (sharing_option이 없으면 public)
+    pub likes: i32,
+    pub dislikes: i32,
+    pub views: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shares: Option<i32>, // 상세/피드/공유 링크 응답에서만
+    pub author: Option<String>,
+    pub thumbnail_img: Option<String>,
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_id: Option<String>, // 상세/생성 응답에서만 (공유 링크/QR/임베드용)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>, // 상세/생성/수정 응답에서만 (draft, published, scheduled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<i32>, // 상세/수정 응답에서만 (수정 시 If-Match 또는 version으로 전달)
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub images: Option<Vec<MarkerImageDto>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub years_ago: Option<i32>, // "지난 오늘" 응답에서만 사용
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>, // 마커 생성 응답에서만 사용 (상세는 data.tags)
+}
+
+impl From<&Marker> for MarkerDto {
+    fn from(marker: &Marker) -> Self {
+        let (latitude, longitude) = match (marker.get_latitude(), marker.get_longitude()) {
+            (Some(lat), Some(lng)) => (lat, lng),
+            _ => (0.0, 0.0),
+        };
+        Self {
+            id: marker.id,
+            member_id: marker.member_id,
+            latitude,
+            longitude,
+            emotion_tag: marker.emotion_tag.clone(),
+            emotion_tag_input: marker.emotion_tag_input.clone(),
+            emotion: marker.emotion.clone(),
+            description: marker.description.clone(),
+            sharing_option: marker.sharing_option.clone(),
+            visibility: MarkerVisibility::of(marker).as_str(),
+            likes: marker.likes,
+            dislikes: marker.dislikes,
+            views: marker.views,
+            shares: marker.shares,
+            author: marker.author.clone(),
+            thumbnail_img: marker.thumbnail_img.clone(),
+            language: marker.language.clone(),
+            public_id: marker.public_id.clone(),
+            status: marker.status.clone(),
+            publish_at: marker.publish_at,
+            version: marker.version,
+            created_at: marker.created_at,
+            updated_at: marker.updated_at,
+            images: None,
+            years_ago: None,
+            tags: None,
+        }
+    }
+}
+
+impl MarkerDto {
+    pub fn with_images(mut self, images: Vec<MarkerImageDto>) -> Self {
+        self.images = Some(images);
+        self
+    }
+
+    pub fn with_years_ago(mut self, years_ago: i32) -> Self {
+        self.years_ago = Some(years_ago);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+}
+
+/// 공개 API용 마커 (작성자 식별 정보와 공유 설정 제외)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicMarkerDto {
+    pub id: i64,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub emotion_tag: Option<String>,
+    pub emotion_tag_input: Option<String>,
+    pub emotion: Option<String>,
+    pub description: Option<String>,
+    pub likes: i32,
+    pub dislikes: i32,
+    pub views: i32,
+    pub thumbnail_img: Option<String>,
+    pub language: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Marker> for PublicMarkerDto {
+    fn from(marker: &Marker) -> Self {
+        let MarkerDto {
+            id, latitude, longitude, emotion_tag, emotion_tag_input, emotion, description,
+            likes, dislikes, views, thumbnail_img, language, created_at, updated_at, ..
+        } = MarkerDto::from(marker);
+        Self {
+            id, latitude, longitude, emotion_tag, emotion_tag_input, emotion, description,
+            likes, dislikes, views, thumbnail_img, language, created_at, updated_at,
+        }
+    }
+}
+
+/// 마커 인증 신청 (서류 키와 사업자 정보는 신청자 본인/관리자 응답에만 사용)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerClaimDto {
+    pub id: i64,
+    pub marker_id: i64,
+    pub member_id: i64,
+    pub business_name: String,
+    pub business_registration_number: String,
+    pub contact_phone: Option<String>,
+    pub status: String,
+    pub review_note: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&MarkerClaim> for MarkerClaimDto {
+    fn from(claim: &MarkerClaim) -> Self {
+        Self {
+            id: claim.id,
+            marker_id: claim.marker_id,
+            member_id: claim.member_id,
+            business_name: claim.business_name.clone(),
+            business_registration_number: claim.business_registration_number.clone(),
+            contact_phone: claim.contact_phone.clone(),
+            status: claim.status.clone(),
+            review_note: claim.review_note.clone(),
+            reviewed_at: claim.reviewed_at,
+            created_at: claim.created_at,
+        }
+    }
+}
+
+/// 관리자 인증 신청 목록 항목 (서류 다운로드 경로 포함)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminMarkerClaimDto {
+    #[serde(flatten)]
+    pub claim: MarkerClaimDto,
+    pub document_url: String,
+}
+
+impl From<&MarkerClaim> for AdminMarkerClaimDto {
+    fn from(claim: &MarkerClaim) -> Self {
+        Self {
+            claim: MarkerClaimDto::from(claim),
+            document_url: format!("/api/admin/marker-claims/{}/document", claim.id),
+        }
+    }
+}
+
+/// 관리자 마커 내보내기 작업 (상태 조회/다운로드 경로 포함, 완료 전에는 downloadUrl 없음)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerExportDto {
+    pub id: i64,
+    pub format: String,
+    pub since: Option<DateTime<Utc>>,
+    pub status: String,
+    pub row_count: Option<i64>,
+    pub file_size: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub status_url: String,
+    pub download_url: Option<String>,
+}
+
+impl From<&MarkerExport> for MarkerExportDto {
+    fn from(job: &MarkerExport) -> Self {
+        Self {
+            id: job.id,
+            format: job.format.clone(),
+            since: job.since,
+            status: job.status.clone(),
+            row_count: job.row_count,
+            file_size: job.file_size,
+            error: job.error.clone(),
+            created_at: job.created_at,
+            completed_at: job.completed_at,
+            status_url: format!("/api/admin/export/jobs/{}", job.id),
+            download_url: (job.status == "completed").then(|| format!("/api/admin/export/jobs/{}/download", job.id)),
+        }
+    }
+}
+
+/// 스폰서 마커 노출 설정 (클릭률은 노출이 없으면 0)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerPromotionDto {
+    pub id: i64,
+    pub marker_id: i64,
+    pub sponsor_name: String,
+    pub district_codes: Option<Vec<String>>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub impression_cap: Option<i64>,
+    pub impressions: i64,
+    pub clicks: i64,
+    pub click_through_rate: f64,
+    pub is_active: bool,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&MarkerPromotion> for MarkerPromotionDto {
+    fn from(promotion: &MarkerPromotion) -> Self {
+        Self {
+            id: promotion.id,
+            marker_id: promotion.marker_id,
+            sponsor_name: promotion.sponsor_name.clone(),
+            district_codes: promotion.district_codes.clone(),
+            starts_at: promotion.starts_at,
+            ends_at: promotion.ends_at,
+            impression_cap: promotion.impression_cap,
+            impressions: promotion.impressions,
+            clicks: promotion.clicks,
+            click_through_rate: if promotion.impressions > 0 { promotion.clicks as f64 / promotion.impressions as f64 } else { 0.0 },
+            is_active: promotion.is_active,
+            created_by: promotion.created_by,
+            created_at: promotion.created_at,
+            updated_at: promotion.updated_at,
+        }
+    }
+}
+
+/// 연결된 SNS 계정 (암호화된 토큰은 응답에 포함하지 않음)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectedAccountDto {
+    pub platform: String,
+    pub remote_user_id: String,
+    pub remote_username: Option<String>,
+    pub token_expires_at: Option<DateTime<Utc>>,
+    pub connected_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&ConnectedAccount> for ConnectedAccountDto {
+    fn from(account: &ConnectedAccount) -> Self {
+        Self {
+            platform: account.platform.clone(),
+            remote_user_id: account.remote_user_id.clone(),
+            remote_username: account.remote_username.clone(),
+            token_expires_at: account.token_expires_at,
+            connected_at: account.created_at,
+            updated_at: account.updated_at,
+        }
+    }
+}
+
+/// 마커 신고 (관리자 신고 목록)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerReportDto {
+    pub id: i64,
+    pub marker_id: i64,
+    pub reporter_id: i64,
+    pub reason: String,
+    pub details: Option<String>,
+    pub status: String,
+    pub resolution_note: Option<String>,
+    pub resolved_by: Option<i64>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&MarkerReport> for MarkerReportDto {
+    fn from(report: &MarkerReport) -> Self {
+        Self {
+            id: report.id,
+            marker_id: report.marker_id,
+            reporter_id: report.reporter_id,
+            reason: report.reason.clone(),
+            details: report.details.clone(),
+            status: report.status.clone(),
+            resolution_note: report.resolution_note.clone(),
+            resolved_by: report.resolved_by,
+            resolved_at: report.resolved_at,
+            created_at: report.created_at,
+        }
+    }
+}
+
+/// 데드레터 작업 (payload는 저장된 JSON 문자열을 객체로, 깨져 있으면 null)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterJobDto {
+    pub id: i64,
+    pub job_type: String,
+    pub source_id: Option<i64>,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub status: String,
+    pub failed_at: DateTime<Utc>,
+    pub resolved_by: Option<i64>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl From<&DeadLetterJob> for DeadLetterJobDto {
+    fn from(job: &DeadLetterJob) -> Self {
+        Self {
+            id: job.id,
+            job_type: job.job_type.clone(),
+            source_id: job.source_id,
+            payload: serde_json::from_str(&job.payload).unwrap_or(serde_json::Value::Null),
+            attempts: job.attempts,
+            last_error: job.last_error.clone(),
+            status: job.status.clone(),
+            failed_at: job.failed_at,
+            resolved_by: job.resolved_by,
+            resolved_at: job.resolved_at,
+        }
+    }
+}
+
+/// 클라이언트 오류 보고 (관리자 조회)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientErrorDto {
+    pub id: i64,
+    pub kind: String,
+    pub platform: String,
+    pub app_version: Option<String>,
+    pub device: Option<String>,
+    pub os_version: Option<String>,
+    pub route: Option<String>,
+    pub request_id: Option<String>,
+    pub http_status: Option<i32>,
+    pub message: String,
+    pub stack_trace: Option<String>,
+    pub member_id: Option<i64>,
+    pub report_request_id: Option<String>,
+    pub sample_rate: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&ClientError> for ClientErrorDto {
+    fn from(report: &ClientError) -> Self {
+        Self {
+            id: report.id,
+            kind: report.kind.clone(),
+            platform: report.platform.clone(),
+            app_version: report.app_version.clone(),
+            device: report.device.clone(),
+            os_version: report.os_version.clone(),
+            route: report.route.clone(),
+            request_id: report.request_id.clone(),
+            http_status: report.http_status,
+            message: report.message.clone(),
+            stack_trace: report.stack_trace.clone(),
+            member_id: report.member_id,
+            report_request_id: report.report_request_id.clone(),
+            sample_rate: report.sample_rate,
+            created_at: report.created_at,
+        }
+    }
+}
+
+/// 알림 종류별 수신 설정
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferencesDto {
+    pub likes: bool,
+    pub comments: bool,
+    pub memories: bool,
+}
+
+impl From<&NotificationPreferences> for NotificationPreferencesDto {
+    fn from(preferences: &NotificationPreferences) -> Self {
+        Self {
+            likes: preferences.likes,
+            comments: preferences.comments,
+            memories: preferences.memories,
+        }
+    }
+}
+
+/// 마커 컬렉션 (markerCount는 볼 수 없는 마커도 포함한 담긴 수)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerCollectionDto {
+    pub id: i64,
+    pub member_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_public: bool,
+    pub marker_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&MarkerCollection> for MarkerCollectionDto {
+    fn from(collection: &MarkerCollection) -> Self {
+        Self {
+            id: collection.id,
+            member_id: collection.member_id,
+            name: collection.name.clone(),
+            description: collection.description.clone(),
+            is_public: collection.is_public,
+            marker_count: collection.marker_count,
+            created_at: collection.created_at,
+            updated_at: collection.updated_at,
+        }
+    }
+}
+
+/// 컬렉션 상세 (보는 사람이 볼 수 있는 마커 포함)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerCollectionDetailDto {
+    #[serde(flatten)]
+    pub collection: MarkerCollectionDto,
+    pub markers: Vec<MarkerDto>,
+}
+
+/// 북마크 폴더 (색은 #RRGGBB)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkFolderDto {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub position: i32,
+    pub bookmark_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&BookmarkFolder> for BookmarkFolderDto {
+    fn from(folder: &BookmarkFolder) -> Self {
+        Self {
+            id: folder.id,
+            name: folder.name.clone(),
+            color: folder.color.clone(),
+            position: folder.position,
+            bookmark_count: folder.bookmark_count,
+            created_at: folder.created_at,
+            updated_at: folder.updated_at,
+        }
+    }
+}
+
+/// 개인 데이터 내보내기 작업 (상태 조회/다운로드 경로 포함, 완료 전에는 downloadUrl 없음)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberDataExportDto {
+    pub id: i64,
+    pub format: String,
+    pub status: String,
+    pub marker_count: Option<i64>,
+    pub file_size: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub status_url: String,
+    pub download_url: Option<String>,
+}
+
+impl From<&MemberDataExport> for MemberDataExportDto {
+    fn from(job: &MemberDataExport) -> Self {
+        Self {
+            id: job.id,
+            format: job.format.clone(),
+            status: job.status.clone(),
+            marker_count: job.marker_count,
+            file_size: job.file_size,
+            error: job.error.clone(),
+            created_at: job.created_at,
+            completed_at: job.completed_at,
+            status_url: format!("/api/members/me/exports/{}", job.id),
+            download_url: (job.status == "completed").then(|| format!("/api/members/me/exports/{}/download", job.id)),
+        }
+    }
+}
+
+/// 이어 받는 업로드 세션 (progress는 받은 비율 %, result는 다 받은 뒤 처리한 업로드 응답)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadSessionDto {
+    pub id: uuid::Uuid,
+    pub upload_url: String,
+    pub image_type: String,
+    pub filename: String,
+    pub upload_length: i64,
+    pub upload_offset: i64,
+    pub progress: f64,
+    pub status: String,
+    pub result: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<&UploadSession> for UploadSessionDto {
+    fn from(session: &UploadSession) -> Self {
+        let progress = session.upload_offset as f64 / session.upload_length as f64 * 100.0;
+        Self {
+            id: session.id,
+            upload_url: format!("/api/uploads/{}", session.id),
+            image_type: session.image_type.clone(),
+            filename: session.filename.clone(),
+            upload_length: session.upload_length,
+            upload_offset: session.upload_offset,
+            progress: (progress * 10.0).round() / 10.0,
+            status: session.status.clone(),
+            result: session.result.as_deref().and_then(|result| serde_json::from_str(result).ok()),
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+        }
+    }
+}
+
+/// 알림함 항목
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationDto {
+    pub id: i64,
+    pub member_id: i64,
+    #[serde(rename = "type")]
+    pub notification_type: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub marker_ids: Option<Vec<i64>>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&Notification> for NotificationDto {
+    fn from(notification: &Notification) -> Self {
+        Self {
+            id: notification.id,
+            member_id: notification.member_id,
+            notification_type: notification.notification_type.clone(),
+            title: notification.title.clone(),
+            body: notification.body.clone(),
+            marker_ids: notification.marker_ids.clone(),
+            read_at: notification.read_at,
+            created_at: notification.created_at,
+        }
+    }
+}
+
+/// 활동 타임라인 항목 (마커가 없는 활동은 marker가 null)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityDto {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub activity_type: String,
+    pub actor: ActivityActorDto,
+    pub recipient_id: Option<i64>,
+    pub marker: Option<ActivityMarkerDto>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityActorDto {
+    pub id: i64,
+    pub nickname: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityMarkerDto {
+    pub id: i64,
+    pub description: Option<String>,
+    pub emotion_tag: Option<String>,
+    pub thumbnail_img: Option<String>,
+}
+
+impl From<&Activity> for ActivityDto {
+    fn from(activity: &Activity) -> Self {
+        Self {
+            id: activity.id,
+            activity_type: activity.activity_type.clone(),
+            actor: ActivityActorDto {
+                id: activity.actor_id,
+                nickname: activity.actor_nickname.clone(),
+            },
+            recipient_id: activity.recipient_id,
+            marker: activity.marker_id.map(|marker_id| ActivityMarkerDto {
+                id: marker_id,
+                description: activity.marker_description.clone(),
+                emotion_tag: activity.marker_emotion_tag.clone(),
+                thumbnail_img: activity.marker_thumbnail_img.clone(),
+            }),
+            created_at: activity.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerCrosspostDto {
+    pub marker_id: i64,
+    pub platform: String,
+    pub remote_post_id: String,
+    pub remote_url: Option<String>,
+    pub created_at: Option<DateTime<Utc>>, // 같은 게시물이 먼저 기록된 경우 None
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentTakedownDto {
+    pub target_type: String,
+    pub target_id: i64,
+    pub action: String,
+    pub legal_hold: bool,
+}
+
+impl From<&ContentTakedownAction> for ContentTakedownDto {
+    fn from(takedown: &ContentTakedownAction) -> Self {
+        Self {
+            target_type: takedown.target_type.clone(),
+            target_id: takedown.target_id,
+            action: takedown.action.clone(),
+            // 게시 중단은 항상 보존, 복구는 보존 해제를 요청한 경우에만 풀림
+            legal_hold: takedown.action == "takedown" || !takedown.release_legal_hold,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentTakedownAuditDto {
+    pub id: i64,
+    pub admin_member_id: i64,
+    pub target_type: String,
+    pub target_id: i64,
+    pub action: String,
+    pub reason: String,
+    pub legal_reference: Option<String>,
+    pub legal_hold: bool,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&ContentTakedownAudit> for ContentTakedownAuditDto {
+    fn from(entry: &ContentTakedownAudit) -> Self {
+        Self {
+            id: entry.id,
+            admin_member_id: entry.admin_member_id,
+            target_type: entry.target_type.clone(),
+            target_id: entry.target_id,
+            action: entry.action.clone(),
+            reason: entry.reason.clone(),
+            legal_reference: entry.legal_reference.clone(),
+            legal_hold: entry.legal_hold,
+            client_ip: entry.client_ip.clone(),
+            user_agent: entry.user_agent.clone(),
+            created_at: entry.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyDto {
+    pub id: i64,
+    pub key_prefix: String,
+    pub name: String,
+    pub scope: String,
+    pub daily_quota: i32,
+    pub created_by: Option<i64>,
+    pub is_active: Option<bool>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<&ApiKey> for ApiKeyDto {
+    fn from(key: &ApiKey) -> Self {
+        Self {
+            id: key.id,
+            key_prefix: key.key_prefix.clone(),
+            name: key.name.clone(),
+            scope: key.scope.clone(),
+            daily_quota: key.daily_quota,
+            created_by: key.created_by,
+            is_active: key.is_active,
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+        }
+    }
+}
+
+/// 발급 응답 전용 (원문 키는 이때만 내려감)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedApiKeyDto {
+    #[serde(flatten)]
+    pub key: ApiKeyDto,
+    pub api_key: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyUsageDto {
+    pub date: NaiveDate,
+    pub endpoint: String,
+    pub request_count: i32,
+    pub rejected_count: i32,
+}
+
+impl From<&ApiKeyUsage> for ApiKeyUsageDto {
+    fn from(usage: &ApiKeyUsage) -> Self {
+        Self {
+            date: usage.usage_date,
+            endpoint: usage.endpoint.clone(),
+            request_count: usage.request_count,
+            rejected_count: usage.rejected_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerShareDto {
+    pub token: String,
+    pub share_url: String,
+    pub shares: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedMarkerDto {
+    pub marker: MarkerDto,
+    pub images: Vec<MarkerImageDto>,
+    pub share_url: String,
+    pub card_url: String,
+    pub embed_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>, // 공유 토큰 링크로 열었을 때만
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opens: Option<i32>,
+}
@@ -0,0 +1,229 @@
+// 블로그 등 외부 페이지에 넣는 마커 카드 (iframe용 HTML, JSON, oEmbed)
+// 응답은 마커 내용으로 만든 ETag와 함께 캐시되도록 작게 유지
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::database::Marker;
+use crate::emotions::EmotionTag;
+use crate::public_id;
+
+/// 카드 모양이 바뀌면 올려서 캐시를 갱신
+const EMBED_LAYOUT_VERSION: u32 = 1;
+pub const EMBED_WIDTH: u32 = 400;
+pub const EMBED_HEIGHT: u32 = 300;
+pub const EMBED_MIN_WIDTH: u32 = 240;
+pub const MAP_THUMBNAIL_SIZE: u32 = 256;
+const MAX_TITLE_CHARS: usize = 60;
+const MAX_DESCRIPTION_CHARS: usize = 140;
+
+/// 카드에 들어가는 마커 정보 (주소는 모두 절대 URL)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedMarker {
+    pub public_id: String, // 숫자 ID는 외부에 내보내지 않음
+    pub title: String,
+    pub description: Option<String>,
+    pub emoji: Option<&'static str>,
+    pub emotion: Option<&'static str>,
+    pub image_url: Option<String>,
+    pub map_url: String,
+    pub marker_url: String, // 클릭하면 열리는 웹 페이지
+    pub embed_url: String,
+    pub likes: i32,
+    pub views: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl EmbedMarker {
+    pub fn new(marker: &Marker, emotion: Option<&'static EmotionTag>, image_url: Option<String>, api_base_url: &str, web_app_url: &str) -> Self {
+        let description = marker.description.as_deref()
+            .map(str::trim)
+            .filter(|description| !description.is_empty());
+        let title = match description.and_then(|description| description.lines().next()) {
+            Some(line) => truncate(line, MAX_TITLE_CHARS),
+            None => match emotion {
+                Some(emotion) => format!("{} {}", emotion.emoji, emotion.name),
+                None => "BigPicture 마커".to_string(),
+            },
+        };
+        let api_base_url = api_base_url.trim_end_matches('/');
+        let public_id = marker.public_id.clone().unwrap_or_default();
+        Self {
+            title,
+            description: description.map(|description| truncate(description, MAX_DESCRIPTION_CHARS)),
+            emoji: emotion.map(|emotion| emotion.emoji),
+            emotion: emotion.map(|emotion| emotion.name),
+            image_url,
+            map_url: format!("{}/embed/markers/{}/map.png", api_base_url, public_id),
+            marker_url: public_id::share_url(web_app_url, &public_id),
+            embed_url: format!("{}/embed/markers/{}", api_base_url, public_id),
+            likes: marker.likes,
+            views: marker.views,
+            created_at: marker.created_at,
+            public_id,
+        }
+    }
+}
+
+/// 마커 내용이 바뀌면 달라지는 ETag (큰따옴표 포함)
+pub fn etag(marker: &Marker, image_url: Option<&str>, variant: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    EMBED_LAYOUT_VERSION.hash(&mut hasher);
+    variant.hash(&mut hasher);
+    marker.updated_at.timestamp_millis().hash(&mut hasher);
+    marker.likes.hash(&mut hasher);
+    marker.views.hash(&mut hasher);
+    image_url.hash(&mut hasher);
+    format!("\"{}-{:016x}\"", marker.public_id.as_deref().unwrap_or_default(), hasher.finish())
+}
+
+/// 위치 지도 썸네일 S3 캐시 키 (좌표와 타일 서버가 같으면 같은 이미지)
+pub fn map_cache_key(public_id: &str, latitude: f64, longitude: f64, tile_url: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    EMBED_LAYOUT_VERSION.hash(&mut hasher);
+    latitude.to_bits().hash(&mut hasher);
+    longitude.to_bits().hash(&mut hasher);
+    tile_url.hash(&mut hasher);
+    format!("cards/map_{}_{:016x}.png", public_id, hasher.finish())
+}
+
+/// iframe에 그대로 넣는 카드 HTML (외부 스크립트 없이 인라인 스타일만 사용)
+pub fn render_html(card: &EmbedMarker) -> String {
+    let image = match &card.image_url {
+        Some(url) => format!(r#"<img class="photo" src="{}" alt="">"#, escape_html(url)),
+        None => format!(r#"<div class="photo emoji">{}</div>"#, escape_html(card.emoji.unwrap_or("📍"))),
+    };
+    let description = card.description.as_deref()
+        .map(|description| format!(r#"<p class="desc">{}</p>"#, escape_html(description)))
+        .unwrap_or_default();
+    let emotion = match (card.emoji, card.emotion) {
+        (Some(emoji), Some(name)) => format!(r#"<span class="emotion">{} {}</span>"#, escape_html(emoji), escape_html(name)),
+        _ => String::new(),
+    };
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ko">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+body{{margin:0;font-family:-apple-system,"Apple SD Gothic Neo","Noto Sans KR",sans-serif;background:#fff;color:#222}}
+a.card{{display:flex;flex-direction:column;height:100vh;max-height:{height}px;border:1px solid #e5e5e5;border-radius:12px;overflow:hidden;color:inherit;text-decoration:none;box-sizing:border-box}}
+.media{{position:relative;flex:1;min-height:0;background:#f4f4f1}}
+.photo{{width:100%;height:100%;object-fit:cover;display:block}}
+.emoji{{display:flex;align-items:center;justify-content:center;font-size:64px}}
+.map{{position:absolute;right:8px;bottom:8px;width:72px;height:72px;border:2px solid #fff;border-radius:8px}}
+.body{{padding:10px 12px}}
+.title{{margin:0;font-size:15px;font-weight:600;white-space:nowrap;overflow:hidden;text-overflow:ellipsis}}
+.desc{{margin:4px 0 0;font-size:13px;color:#555;display:-webkit-box;-webkit-line-clamp:2;-webkit-box-orient:vertical;overflow:hidden}}
+.meta{{margin-top:6px;font-size:12px;color:#888;display:flex;gap:8px}}
+.brand{{margin-left:auto}}
+</style>
+</head>
+<body>
+<a class="card" href="{marker_url}" target="_blank" rel="noopener">
+<div class="media">{image}<img class="map" src="{map_url}" alt=""></div>
+<div class="body">
+<p class="title">{title}</p>
+{description}
+<div class="meta">{emotion}<span>♥ {likes}</span><span class="brand">BigPicture</span></div>
+</div>
+</a>
+</body>
+</html>
+"#,
+        title = escape_html(&card.title),
+        height = EMBED_HEIGHT,
+        marker_url = escape_html(&card.marker_url),
+        image = image,
+        map_url = escape_html(&card.map_url),
+        description = description,
+        emotion = emotion,
+        likes = card.likes,
+    )
+}
+
+/// oEmbed 1.0 rich 응답 (maxwidth/maxheight 안에서 기본 크기 비율 유지)
+pub fn oembed(card: &EmbedMarker, provider_url: &str, max_width: Option<u32>, max_height: Option<u32>) -> serde_json::Value {
+    let mut width = EMBED_WIDTH;
+    if let Some(max_width) = max_width {
+        width = width.min(max_width.max(EMBED_MIN_WIDTH));
+    }
+    let mut height = width * EMBED_HEIGHT / EMBED_WIDTH;
+    if let Some(max_height) = max_height.filter(|max_height| *max_height < height) {
+        height = max_height;
+    }
+    let html = format!(
+        r#"<iframe src="{}" width="{}" height="{}" frameborder="0" scrolling="no" style="border:0;max-width:100%" loading="lazy" title="{}"></iframe>"#,
+        escape_html(&card.embed_url), width, height, escape_html(&card.title)
+    );
+    let mut response = serde_json::json!({
+        "version": "1.0",
+        "type": "rich",
+        "title": card.title,
+        "provider_name": "BigPicture",
+        "provider_url": provider_url,
+        "cache_age": 3600,
+        "html": html,
+        "width": width,
+        "height": height
+    });
+    // 사진이 없으면 위치 지도를 썸네일로 사용
+    let (thumbnail_url, thumbnail_size) = match &card.image_url {
+        Some(url) => (url.clone(), None),
+        None => (card.map_url.clone(), Some(MAP_THUMBNAIL_SIZE)),
+    };
+    response["thumbnail_url"] = serde_json::json!(thumbnail_url);
+    if let Some(size) = thumbnail_size {
+        response["thumbnail_width"] = serde_json::json!(size);
+        response["thumbnail_height"] = serde_json::json!(size);
+    }
+    response
+}
+
+/// oEmbed `url`에서 마커 공개 ID 추출 (`.../embed/markers/{public_id}` 또는 공유 링크 `.../m/{public_id}`)
+pub fn public_id_from_url(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next()?.trim_end_matches('/');
+    let mut segments = path.rsplit('/');
+    let id = segments.next().filter(|id| public_id::is_valid(id))?;
+    matches!(segments.next()?, "markers" | "m").then(|| id.to_string())
+}
+
+/// 임베드 주소에 대한 oEmbed 조회 주소 (발견용 Link 헤더)
+pub fn oembed_url(api_base_url: &str, embed_url: &str) -> String {
+    let mut encoded = String::with_capacity(embed_url.len() * 3);
+    for byte in embed_url.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    format!("{}/oembed?url={}", api_base_url.trim_end_matches('/'), encoded)
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
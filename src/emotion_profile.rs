@@ -0,0 +1,201 @@
+// 회원 감정 프로필 ("올해의 감정" 화면): 월별 감정 분포 집계와 인스턴스 메모리 캐시
+// 집계는 DB에서 GROUP BY 한 번으로 가져오고, 빈 달 채우기/비율 계산은 여기서 처리
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use crate::config::Config;
+use crate::database::EmotionMonthCount;
+use crate::emotions::get_emotion_by_id;
+
+const KST_OFFSET_SECS: i32 = 9 * 3600;
+/// 이 개수를 넘으면 만료된 캐시를 정리
+const PRUNE_THRESHOLD: usize = 10_000;
+/// 한 번에 볼 수 있는 최대 개월 수
+pub const MAX_PROFILE_MONTHS: u32 = 60;
+
+/// 집계 구간 (한국 시간 기준 from이 속한 달부터 months개월)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProfileRange {
+    pub from: NaiveDate, // 첫 달 1일
+    pub months: u32,
+}
+
+impl ProfileRange {
+    /// 특정 연도 1~12월
+    pub fn for_year(year: i32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, 1, 1).map(|from| Self { from, months: 12 })
+    }
+
+    /// 이번 달을 포함한 최근 months개월
+    pub fn recent(now: DateTime<Utc>, months: u32) -> Self {
+        let months = months.clamp(1, MAX_PROFILE_MONTHS);
+        let today = now.with_timezone(&kst()).date_naive();
+        let this_month = today.with_day(1).unwrap_or(today);
+        Self { from: add_months(this_month, -(months as i32 - 1)), months }
+    }
+
+    /// 조회 구간 [start, end) (UTC)
+    pub fn bounds(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        (month_start_utc(self.from), month_start_utc(add_months(self.from, self.months as i32)))
+    }
+
+    /// "YYYY-MM" 월 목록 (DB 집계의 month와 같은 형식)
+    pub fn month_labels(&self) -> Vec<String> {
+        (0..self.months as i32)
+            .map(|offset| add_months(self.from, offset).format("%Y-%m").to_string())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmotionShare {
+    pub emotion_tag: String,
+    pub emoji: Option<&'static str>,
+    pub name: Option<&'static str>,
+    pub count: i64,
+    pub ratio: f64, // 해당 구간 감정 태그 합계 대비 비율 (0~1)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmotionMonth {
+    pub month: String,
+    pub total: i64,
+    pub top_emotion: Option<String>,
+    pub emotions: Vec<EmotionShare>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmotionProfile {
+    pub member_id: i64,
+    pub from: String,
+    pub to: String,
+    pub total: i64, // 감정 태그 수 (마커 하나에 여러 감정이면 각각 집계)
+    pub dominant_emotion: Option<EmotionShare>,
+    pub emotions: Vec<EmotionShare>,
+    pub months: Vec<EmotionMonth>,
+}
+
+/// DB 월별 집계 → 프로필 (구간 안의 빈 달도 total 0으로 포함)
+pub fn build_profile(member_id: i64, range: &ProfileRange, counts: &[EmotionMonthCount]) -> EmotionProfile {
+    let labels = range.month_labels();
+    let mut by_month: BTreeMap<&str, Vec<(&str, i64)>> = labels.iter().map(|label| (label.as_str(), Vec::new())).collect();
+    let mut overall: HashMap<&str, i64> = HashMap::new();
+    for row in counts {
+        if let Some(month) = by_month.get_mut(row.month.as_str()) {
+            month.push((row.emotion_tag.as_str(), row.marker_count));
+            *overall.entry(row.emotion_tag.as_str()).or_default() += row.marker_count;
+        }
+    }
+
+    let months: Vec<EmotionMonth> = by_month.into_iter()
+        .map(|(month, counts)| {
+            let emotions = shares(counts);
+            EmotionMonth {
+                month: month.to_string(),
+                total: emotions.iter().map(|share| share.count).sum(),
+                top_emotion: emotions.first().map(|share| share.emotion_tag.clone()),
+                emotions,
+            }
+        })
+        .collect();
+    let emotions = shares(overall.into_iter().collect());
+    EmotionProfile {
+        member_id,
+        from: labels.first().cloned().unwrap_or_default(),
+        to: labels.last().cloned().unwrap_or_default(),
+        total: emotions.iter().map(|share| share.count).sum(),
+        dominant_emotion: emotions.first().cloned(),
+        emotions,
+        months,
+    }
+}
+
+/// 많은 순(같으면 태그 이름 순)으로 정렬한 감정별 비율
+fn shares(mut counts: Vec<(&str, i64)>) -> Vec<EmotionShare> {
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    let total: i64 = counts.iter().map(|(_, count)| count).sum();
+    counts.into_iter()
+        .map(|(tag, count)| {
+            let emotion = get_emotion_by_id(tag);
+            EmotionShare {
+                emotion_tag: tag.to_string(),
+                emoji: emotion.map(|emotion| emotion.emoji),
+                name: emotion.map(|emotion| emotion.name),
+                count,
+                ratio: if total > 0 { count as f64 / total as f64 } else { 0.0 },
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CacheKey {
+    member_id: i64,
+    range: ProfileRange,
+    include_private: bool, // 본인 조회는 비공개 마커까지 포함하므로 따로 캐시
+}
+
+/// (집계 시각, 프로필)
+type CacheEntry = (DateTime<Utc>, Arc<EmotionProfile>);
+
+/// 워커 전체가 공유하는 감정 프로필 캐시 (EMOTION_PROFILE_CACHE_SECS 동안 재사용)
+#[derive(Clone)]
+pub struct EmotionProfileCache {
+    entries: Arc<Mutex<HashMap<CacheKey, CacheEntry>>>,
+    ttl: Duration,
+}
+
+impl EmotionProfileCache {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            ttl: Duration::seconds(config.emotion_profile_cache_secs),
+        }
+    }
+
+    pub fn get(&self, member_id: i64, range: &ProfileRange, include_private: bool, now: DateTime<Utc>) -> Option<Arc<EmotionProfile>> {
+        let key = CacheKey { member_id, range: *range, include_private };
+        let entries = self.entries.lock().unwrap();
+        entries.get(&key)
+            .filter(|(cached_at, _)| now - *cached_at < self.ttl)
+            .map(|(_, profile)| profile.clone())
+    }
+
+    pub fn insert(&self, range: &ProfileRange, include_private: bool, profile: EmotionProfile, now: DateTime<Utc>) -> Arc<EmotionProfile> {
+        let key = CacheKey { member_id: profile.member_id, range: *range, include_private };
+        let profile = Arc::new(profile);
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() > PRUNE_THRESHOLD {
+            let ttl = self.ttl;
+            entries.retain(|_, (cached_at, _)| now - *cached_at < ttl);
+        }
+        entries.insert(key, (now, profile.clone()));
+        profile
+    }
+
+    /// 회원의 캐시 전부 삭제 (마커 생성/수정 후 바로 반영되도록)
+    pub fn invalidate_member(&self, member_id: i64) {
+        self.entries.lock().unwrap().retain(|key, _| key.member_id != member_id);
+    }
+}
+
+fn kst() -> FixedOffset {
+    FixedOffset::east_opt(KST_OFFSET_SECS).expect("valid KST offset")
+}
+
+fn add_months(month: NaiveDate, offset: i32) -> NaiveDate {
+    let index = month.year() * 12 + month.month0() as i32 + offset;
+    NaiveDate::from_ymd_opt(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1).unwrap_or(month)
+}
+
+fn month_start_utc(month: NaiveDate) -> DateTime<Utc> {
+    month.and_hms_opt(0, 0, 0)
+        .and_then(|start| start.and_local_timezone(kst()).single())
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_default()
+}
@@ -0,0 +1,108 @@
+// 외부 임베드용 감정 지도 타일 (/api/public/emotion-tiles/{z}/{x}/{y}.json)
+// 웹 메르카토르 타일을 GRID_SIZE x GRID_SIZE 칸으로 나눠 칸별 공개 마커 수와 감정 비율만 담음
+// (설명/작성자/이미지 없음), 마커가 MIN_CELL_MARKERS개 미만인 칸은 개인 위치가 드러나지 않도록 뺌
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+
+use crate::database::EmotionTileRow;
+
+/// EPSG:3857 원점에서 끝까지의 거리 (미터)
+pub const MERCATOR_EXTENT_M: f64 = 20_037_508.342_789_244;
+/// 너무 확대하면 칸이 건물 하나 크기가 되므로 여기까지만 제공
+pub const MAX_TILE_ZOOM: u32 = 14;
+/// 타일 한 변의 칸 수 (256px 타일 기준 한 칸 32px)
+pub const GRID_SIZE: u32 = 8;
+pub const MIN_CELL_MARKERS: i64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileCoord {
+    pub z: u32,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl TileCoord {
+    pub fn new(z: u32, x: u32, y: u32) -> Result<Self, String> {
+        if z > MAX_TILE_ZOOM {
+            return Err(format!("줌은 0~{} 사이여야 합니다: {}", MAX_TILE_ZOOM, z));
+        }
+        let tiles = 1u32 << z;
+        if x >= tiles || y >= tiles {
+            return Err(format!("줌 {}의 타일 좌표는 0~{} 사이여야 합니다: {}/{}", z, tiles - 1, x, y));
+        }
+        Ok(Self { z, x, y })
+    }
+
+    /// 타일 한 변 길이 (EPSG:3857 미터)
+    pub fn size_m(&self) -> f64 {
+        2.0 * MERCATOR_EXTENT_M / f64::from(1u32 << self.z)
+    }
+
+    /// 타일 서쪽/북쪽 끝 (EPSG:3857 미터)
+    pub fn origin_m(&self) -> (f64, f64) {
+        let size = self.size_m();
+        (-MERCATOR_EXTENT_M + f64::from(self.x) * size, MERCATOR_EXTENT_M - f64::from(self.y) * size)
+    }
+
+    /// 타일 경계 (lng_min, lat_min, lng_max, lat_max)
+    pub fn envelope(&self) -> (f64, f64, f64, f64) {
+        let (west, north) = self.origin_m();
+        let size = self.size_m();
+        let (lng_min, lat_max) = mercator_to_lng_lat(west, north);
+        let (lng_max, lat_min) = mercator_to_lng_lat(west + size, north - size);
+        (lng_min, lat_min, lng_max, lat_max)
+    }
+
+    /// 칸 가운데 좌표 (lat, lng)
+    pub fn cell_center(&self, cell_x: u32, cell_y: u32) -> (f64, f64) {
+        let (west, north) = self.origin_m();
+        let cell = self.size_m() / f64::from(GRID_SIZE);
+        let (lng, lat) = mercator_to_lng_lat(
+            west + (f64::from(cell_x) + 0.5) * cell,
+            north - (f64::from(cell_y) + 0.5) * cell,
+        );
+        (lat, lng)
+    }
+}
+
+pub fn mercator_to_lng_lat(x: f64, y: f64) -> (f64, f64) {
+    let lng = x / MERCATOR_EXTENT_M * 180.0;
+    let lat = (2.0 * (y / MERCATOR_EXTENT_M * PI).exp().atan() - PI / 2.0).to_degrees();
+    (lng, lat)
+}
+
+/// DB 집계 행(칸 × 감정)을 칸별 응답으로 묶음 (칸 순서: 위→아래, 왼쪽→오른쪽)
+pub fn tile_json(tile: &TileCoord, rows: &[EmotionTileRow]) -> serde_json::Value {
+    let mut cells: BTreeMap<(i32, i32), (i64, Vec<&EmotionTileRow>)> = BTreeMap::new();
+    for row in rows {
+        let cell = cells.entry((row.cell_y, row.cell_x)).or_insert((row.marker_count, Vec::new()));
+        cell.1.push(row);
+    }
+    let cells: Vec<serde_json::Value> = cells.into_iter()
+        .map(|((cell_y, cell_x), (marker_count, mut emotions))| {
+            emotions.sort_by(|a, b| b.tag_count.cmp(&a.tag_count).then_with(|| a.emotion_tag.cmp(&b.emotion_tag)));
+            let (lat, lng) = tile.cell_center(cell_x as u32, cell_y as u32);
+            serde_json::json!({
+                "cellX": cell_x,
+                "cellY": cell_y,
+                "latitude": lat,
+                "longitude": lng,
+                "markerCount": marker_count,
+                "dominantEmotion": emotions.first().map(|row| row.emotion_tag.as_str()),
+                "emotions": emotions.iter().map(|row| serde_json::json!({
+                    "emotionTag": row.emotion_tag,
+                    "count": row.tag_count,
+                    "ratio": row.tag_count as f64 / marker_count.max(1) as f64
+                })).collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "z": tile.z,
+        "x": tile.x,
+        "y": tile.y,
+        "gridSize": GRID_SIZE,
+        "minCellMarkers": MIN_CELL_MARKERS,
+        "cells": cells
+    })
+}
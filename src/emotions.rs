@@ -147,4 +147,12 @@ pub fn get_all_emotions() -> &'static [EmotionTag] {
 
 pub fn is_valid_emotion_id(id: &str) -> bool {
     EMOTION_TAGS.iter().any(|emotion| emotion.id == id)
-} 
\ No newline at end of file
+}
+
+/// 쉼표로 구분한 감정 태그 중 EMOTION_TAGS에 없는 것 (빈 항목은 무시)
+pub fn unknown_emotion_ids(tags: &str) -> Vec<&str> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && !is_valid_emotion_id(id))
+        .collect()
+}
@@ -88,10 +88,157 @@ impl ErrorHandler {
         Self::log_and_respond(StatusCode::NOT_FOUND, message, None, None)
     }
 
+    pub fn conflict(message: &str, details: Option<&str>) -> HttpResponse {
+        Self::log_and_respond(StatusCode::CONFLICT, message, details, None)
+    }
+
+    /// 클라이언트가 분기할 수 있는 오류 코드(errorCode)를 붙인 409 (예: EMAIL_TAKEN)
+    pub fn conflict_with_code(message: &str, error_code: &str) -> HttpResponse {
+        let status = StatusCode::CONFLICT;
+        warn!("⚔️ {} Conflict - {} ({})", status.as_u16(), message, error_code);
+        
+        HttpResponse::build(status).json(json!({
+            "success": false,
+            "error": {
+                "code": status.as_u16(),
+                "message": message,
+                "status": "Conflict",
+                "errorCode": error_code
+            }
+        }))
+    }
+
+    /// 동시 수정 충돌 (409, 지금 저장된 내용과 ETag를 함께 내려줘 클라이언트가 다시 합치게 함)
+    pub fn version_conflict(message: &str, etag: &str, current: serde_json::Value) -> HttpResponse {
+        let status = StatusCode::CONFLICT;
+        warn!("⚔️ {} Conflict - {} (현재 {})", status.as_u16(), message, etag);
+        
+        HttpResponse::build(status)
+            .insert_header(("ETag", etag))
+            .json(json!({
+                "success": false,
+                "error": {
+                    "code": status.as_u16(),
+                    "message": message,
+                    "status": "Conflict",
+                    "errorCode": "VERSION_CONFLICT",
+                    "current": current
+                }
+            }))
+    }
+
+    /// 조건부 요청 필요 (428, If-Match 없이 수정하려는 경우)
+    pub fn precondition_required(message: &str, details: Option<&str>) -> HttpResponse {
+        Self::log_and_respond(StatusCode::PRECONDITION_REQUIRED, message, details, None)
+    }
+
     pub fn unprocessable_entity(message: &str, details: Option<&str>) -> HttpResponse {
         Self::log_and_respond(StatusCode::UNPROCESSABLE_ENTITY, message, details, None)
     }
 
+    /// 쿼터 초과 (현재 한도/사용량을 함께 내려줌)
+    pub fn quota_exceeded(message: &str, details: Option<&str>, quota: serde_json::Value) -> HttpResponse {
+        let status = StatusCode::UNPROCESSABLE_ENTITY;
+        warn!("📦 {} Quota Exceeded - {}", status.as_u16(), message);
+        if let Some(details) = details {
+            warn!("   📋 상세 에러: {}", details);
+        }
+        
+        HttpResponse::build(status).json(json!({
+            "success": false,
+            "error": {
+                "code": status.as_u16(),
+                "message": message,
+                "status": "Quota Exceeded",
+                "quota": quota
+            }
+        }))
+    }
+
+    /// 요청 한도 초과 (429, 다시 시도할 수 있을 때까지 남은 초를 Retry-After로 전달)
+    pub fn too_many_requests(message: &str, details: Option<&str>, retry_after_secs: i64) -> HttpResponse {
+        let status = StatusCode::TOO_MANY_REQUESTS;
+        warn!("🚦 {} Too Many Requests - {}", status.as_u16(), message);
+        if let Some(details) = details {
+            warn!("   📋 상세 에러: {}", details);
+        }
+        
+        HttpResponse::build(status)
+            .insert_header(("Retry-After", retry_after_secs.to_string()))
+            .json(json!({
+                "success": false,
+                "error": {
+                    "code": status.as_u16(),
+                    "message": message,
+                    "status": "Too Many Requests",
+                    "retryAfter": retry_after_secs
+                }
+            }))
+    }
+
+    /// 로그인 연속 실패로 계정 임시 잠금 (423, 잠금 해제까지 남은 초를 Retry-After로 전달)
+    pub fn account_locked(message: &str, locked_until: chrono::DateTime<chrono::Utc>, retry_after_secs: i64) -> HttpResponse {
+        let status = StatusCode::LOCKED;
+        warn!("🔒 {} Locked - {} (해제 {})", status.as_u16(), message, locked_until);
+        
+        HttpResponse::build(status)
+            .insert_header(("Retry-After", retry_after_secs.to_string()))
+            .json(json!({
+                "success": false,
+                "error": {
+                    "code": status.as_u16(),
+                    "message": message,
+                    "status": "Account Locked",
+                    "lockedUntil": locked_until,
+                    "retryAfter": retry_after_secs
+                }
+            }))
+    }
+
+    /// 서버 저장 공간 부족 (507)
+    pub fn insufficient_storage(message: &str, details: Option<&str>) -> HttpResponse {
+        let status = StatusCode::INSUFFICIENT_STORAGE;
+        error!("💽 {} Insufficient Storage - {}", status.as_u16(), message);
+        if let Some(details) = details {
+            error!("   📋 상세 에러: {}", details);
+        }
+        
+        HttpResponse::build(status).json(json!({
+            "success": false,
+            "error": {
+                "code": status.as_u16(),
+                "message": message,
+                "status": "Insufficient Storage"
+            }
+        }))
+    }
+
+    /// 일시적 과부하 (503, Retry-After 이후 재시도)
+    pub fn service_unavailable(message: &str, details: Option<&str>, retry_after_secs: i64) -> HttpResponse {
+        let status = StatusCode::SERVICE_UNAVAILABLE;
+        warn!("🚧 {} Service Unavailable - {}", status.as_u16(), message);
+        if let Some(details) = details {
+            warn!("   📋 상세 에러: {}", details);
+        }
+        
+        HttpResponse::build(status)
+            .insert_header(("Retry-After", retry_after_secs.to_string()))
+            .json(json!({
+                "success": false,
+                "error": {
+                    "code": status.as_u16(),
+                    "message": message,
+                    "status": "Service Unavailable",
+                    "retryAfter": retry_after_secs
+                }
+            }))
+    }
+
+    /// 외부 서비스(SNS 등) 호출 실패
+    pub fn bad_gateway(message: &str, details: Option<&str>) -> HttpResponse {
+        Self::log_and_respond(StatusCode::BAD_GATEWAY, message, details, None)
+    }
+
     pub fn internal_server_error(message: &str, details: Option<&str>) -> HttpResponse {
         Self::log_and_respond(StatusCode::INTERNAL_SERVER_ERROR, message, details, None)
     }
@@ -0,0 +1,335 @@
+// 로컬 MaxMind DB(GeoLite2-City .mmdb)로 IP → 대략적인 위치 조회
+// 외부 서비스 호출 없이 앱 첫 화면의 지도 위치를 정하는 용도라, 필요한 만큼만 MMDB 형식을 직접 읽음
+// (검색 트리 + 데이터 섹션, https://maxmind.github.io/MaxMind-DB/)
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+const METADATA_MARKER: &[u8] = b"\xAB\xCD\xEFMaxMind.com";
+const DATA_SECTION_SEPARATOR: usize = 16;
+
+/// 데이터 섹션 값 (조회에 쓰지 않는 바이트 배열/불리언은 내용을 버림)
+#[derive(Debug, Clone)]
+enum Value {
+    String(String),
+    Double(f64),
+    Uint(u64),
+    Int(i32),
+    Bool,
+    Bytes,
+    Map(BTreeMap<String, Value>),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Map(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    fn path(&self, keys: &[&str]) -> Option<&Value> {
+        keys.iter().try_fold(self, |value, key| value.get(key))
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Double(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Uint(v) => Some(*v),
+            Value::Int(v) if *v >= 0 => Some(*v as u64),
+            _ => None,
+        }
+    }
+
+    /// names 맵에서 한국어 → 영어 순으로 이름 선택
+    fn localized_name(&self) -> Option<String> {
+        let names = self.get("names")?;
+        ["ko", "en"].iter()
+            .find_map(|lang| names.get(lang).and_then(Value::as_str))
+            .map(str::to_string)
+    }
+}
+
+/// 섹션 시작 위치 기준으로 포인터를 해석하는 디코더
+struct Decoder<'a> {
+    buf: &'a [u8],
+    base: usize,
+}
+
+impl Decoder<'_> {
+    fn byte(&self, offset: usize) -> Result<u8, String> {
+        self.buf.get(self.base + offset).copied().ok_or_else(|| format!("offset {} 범위 초과", offset))
+    }
+
+    fn bytes(&self, offset: usize, len: usize) -> Result<&[u8], String> {
+        self.buf.get(self.base + offset..self.base + offset + len).ok_or_else(|| format!("offset {} 범위 초과", offset))
+    }
+
+    fn uint(&self, offset: usize, len: usize) -> Result<u64, String> {
+        Ok(self.bytes(offset, len)?.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+    }
+
+    /// (값, 다음 offset)
+    fn decode(&self, offset: usize) -> Result<(Value, usize), String> {
+        let ctrl = self.byte(offset)?;
+        let mut offset = offset + 1;
+        let mut kind = ctrl >> 5;
+        if kind == 1 {
+            let high = (ctrl & 0x07) as u64;
+            let (pointer, len) = match (ctrl >> 3) & 0x03 {
+                0 => ((high << 8) | self.uint(offset, 1)?, 1),
+                1 => (((high << 16) | self.uint(offset, 2)?) + 2048, 2),
+                2 => (((high << 24) | self.uint(offset, 3)?) + 526_336, 3),
+                _ => (self.uint(offset, 4)?, 4),
+            };
+            let (value, _) = self.decode(pointer as usize)?;
+            return Ok((value, offset + len));
+        }
+        if kind == 0 {
+            kind = 7 + self.byte(offset)?;
+            offset += 1;
+        }
+        let mut size = (ctrl & 0x1f) as usize;
+        match size {
+            29 => { size = 29 + self.uint(offset, 1)? as usize; offset += 1; }
+            30 => { size = 285 + self.uint(offset, 2)? as usize; offset += 2; }
+            31 => { size = 65_821 + self.uint(offset, 3)? as usize; offset += 3; }
+            _ => {}
+        }
+
+        match kind {
+            2 => {
+                let text = std::str::from_utf8(self.bytes(offset, size)?).map_err(|e| e.to_string())?;
+                Ok((Value::String(text.to_string()), offset + size))
+            }
+            3 => {
+                let raw: [u8; 8] = self.bytes(offset, 8)?.try_into().map_err(|_| "double 길이 오류".to_string())?;
+                Ok((Value::Double(f64::from_be_bytes(raw)), offset + 8))
+            }
+            4 => Ok((Value::Bytes, offset + size)),
+            5 | 6 | 9 | 10 => {
+                // uint128은 하위 8바이트만 사용 (위치 조회에는 쓰이지 않음)
+                let skip = size.saturating_sub(8);
+                Ok((Value::Uint(self.uint(offset + skip, size - skip)?), offset + size))
+            }
+            7 => {
+                let mut map = BTreeMap::new();
+                for _ in 0..size {
+                    let (key, next) = self.decode(offset)?;
+                    let (value, next) = self.decode(next)?;
+                    if let Value::String(key) = key {
+                        map.insert(key, value);
+                    }
+                    offset = next;
+                }
+                Ok((Value::Map(map), offset))
+            }
+            8 => {
+                let raw = self.uint(offset, size)? as u32;
+                let value = if size > 0 && size < 4 { (raw << (32 - size * 8)) as i32 >> (32 - size * 8) } else { raw as i32 };
+                Ok((Value::Int(value), offset + size))
+            }
+            11 => {
+                let mut items = Vec::with_capacity(size);
+                for _ in 0..size {
+                    let (value, next) = self.decode(offset)?;
+                    items.push(value);
+                    offset = next;
+                }
+                Ok((Value::Array(items), offset))
+            }
+            14 => Ok((Value::Bool, offset)),
+            15 => {
+                let raw: [u8; 4] = self.bytes(offset, 4)?.try_into().map_err(|_| "float 길이 오류".to_string())?;
+                Ok((Value::Double(f32::from_be_bytes(raw) as f64), offset + 4))
+            }
+            other => Err(format!("지원하지 않는 데이터 타입 {}", other)),
+        }
+    }
+}
+
+struct MmdbReader {
+    buf: Vec<u8>,
+    node_count: usize,
+    record_size: usize,
+    ip_version: u64,
+    data_start: usize,
+    ipv4_start: usize,
+}
+
+impl MmdbReader {
+    fn from_bytes(buf: Vec<u8>) -> Result<Self, String> {
+        let marker = buf.windows(METADATA_MARKER.len())
+            .rposition(|window| window == METADATA_MARKER)
+            .ok_or("메타데이터 마커 없음")?;
+        let metadata_start = marker + METADATA_MARKER.len();
+        let (metadata, _) = Decoder { buf: &buf, base: metadata_start }.decode(0)?;
+        let field = |key: &str| metadata.get(key).and_then(Value::as_u64).ok_or(format!("메타데이터 {} 없음", key));
+        let node_count = field("node_count")? as usize;
+        let record_size = field("record_size")? as usize;
+        let ip_version = field("ip_version")?;
+        if ![24, 28, 32].contains(&record_size) {
+            return Err(format!("지원하지 않는 record_size {}", record_size));
+        }
+        let data_start = node_count * record_size * 2 / 8 + DATA_SECTION_SEPARATOR;
+        if data_start > marker {
+            return Err("검색 트리 크기가 파일보다 큼".to_string());
+        }
+
+        let mut reader = Self { buf, node_count, record_size, ip_version, data_start, ipv4_start: 0 };
+        // IPv6 DB에서 IPv4 주소는 ::/96 아래에 있음
+        if ip_version == 6 {
+            let mut node = 0;
+            for _ in 0..96 {
+                if node >= node_count {
+                    break;
+                }
+                node = reader.read_record(node, 0)?;
+            }
+            reader.ipv4_start = node;
+        }
+        Ok(reader)
+    }
+
+    fn read_record(&self, node: usize, bit: usize) -> Result<usize, String> {
+        let bytes = |offset: usize, len: usize| {
+            self.buf.get(offset..offset + len)
+                .map(|b| b.iter().fold(0usize, |acc, x| (acc << 8) | *x as usize))
+                .ok_or_else(|| format!("노드 {} 범위 초과", node))
+        };
+        match self.record_size {
+            24 => bytes(node * 6 + bit * 3, 3),
+            28 => {
+                let base = node * 7;
+                let middle = bytes(base + 3, 1)?;
+                if bit == 0 {
+                    Ok(((middle & 0xf0) << 20) | bytes(base, 3)?)
+                } else {
+                    Ok(((middle & 0x0f) << 24) | bytes(base + 4, 3)?)
+                }
+            }
+            _ => bytes(node * 8 + bit * 4, 4),
+        }
+    }
+
+    fn lookup(&self, ip: IpAddr) -> Result<Option<Value>, String> {
+        let (bits, mut node) = match ip {
+            IpAddr::V4(v4) if self.ip_version == 6 => (v4.octets().to_vec(), self.ipv4_start),
+            IpAddr::V4(v4) => (v4.octets().to_vec(), 0),
+            IpAddr::V6(_) if self.ip_version == 4 => return Ok(None),
+            IpAddr::V6(v6) => (v6.octets().to_vec(), 0),
+        };
+        for i in 0..bits.len() * 8 {
+            if node >= self.node_count {
+                break;
+            }
+            let bit = ((bits[i / 8] >> (7 - i % 8)) & 1) as usize;
+            node = self.read_record(node, bit)?;
+        }
+        if node <= self.node_count {
+            return Ok(None);
+        }
+        let offset = node - self.node_count - DATA_SECTION_SEPARATOR;
+        let (value, _) = Decoder { buf: &self.buf, base: self.data_start }.decode(offset)?;
+        Ok(Some(value))
+    }
+}
+
+/// IP로 찾은 위치 (이름은 한국어가 있으면 한국어)
+#[derive(Debug, Clone)]
+pub struct GeoLocation {
+    pub country_code: Option<String>,
+    pub country: Option<String>,
+    pub subdivision: Option<String>,
+    pub city: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+impl GeoLocation {
+    /// 찾은 범위에 맞는 지도 확대 수준 (도시 > 시도 > 국가)
+    pub fn suggested_zoom(&self) -> u8 {
+        if self.city.is_some() {
+            12
+        } else if self.subdivision.is_some() {
+            9
+        } else {
+            6
+        }
+    }
+}
+
+/// 앱 전체가 공유하는 GeoIP DB (파일이 없으면 조회 결과는 항상 None)
+#[derive(Clone, Default)]
+pub struct GeoIp {
+    reader: Option<Arc<MmdbReader>>,
+}
+
+impl GeoIp {
+    pub fn open(path: &str) -> Self {
+        let reader = match std::fs::read(path) {
+            Ok(buf) => match MmdbReader::from_bytes(buf) {
+                Ok(reader) => {
+                    info!("🌏 GeoIP DB 로드: {} (노드 {}개)", path, reader.node_count);
+                    Some(Arc::new(reader))
+                }
+                Err(e) => {
+                    warn!("⚠️ GeoIP DB 형식 오류 ({}): {}", path, e);
+                    None
+                }
+            },
+            Err(e) => {
+                warn!("⚠️ GeoIP DB 없음 ({}): {} - 기본 지도 위치를 사용합니다", path, e);
+                None
+            }
+        };
+        Self { reader }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.reader.is_some()
+    }
+
+    pub fn lookup(&self, ip: IpAddr) -> Option<GeoLocation> {
+        let record = match self.reader.as_ref()?.lookup(ip) {
+            Ok(record) => record?,
+            Err(e) => {
+                warn!("⚠️ GeoIP 조회 실패 ({}): {}", ip, e);
+                return None;
+            }
+        };
+        let location = GeoLocation {
+            country_code: record.path(&["country", "iso_code"]).and_then(Value::as_str).map(str::to_string),
+            country: record.get("country").and_then(Value::localized_name),
+            subdivision: match record.get("subdivisions") {
+                Some(Value::Array(items)) => items.first().and_then(Value::localized_name),
+                _ => None,
+            },
+            city: record.get("city").and_then(Value::localized_name),
+            latitude: record.path(&["location", "latitude"]).and_then(Value::as_f64),
+            longitude: record.path(&["location", "longitude"]).and_then(Value::as_f64),
+        };
+        Some(location)
+    }
+}
+
+/// connection_info의 주소("1.2.3.4", "1.2.3.4:5678", "[::1]:80")에서 IP만 추출
+pub fn parse_client_ip(raw: &str) -> Option<IpAddr> {
+    raw.parse::<IpAddr>().ok()
+        .or_else(|| raw.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
@@ -0,0 +1,66 @@
+// 마커 조회 결과 GeoJSON 출력 (format=geojson)
+// 웹 지도(Mapbox/Leaflet) 레이어에 바로 넣을 수 있도록 FeatureCollection으로 바꾸고,
+// 좌표는 GeoJSON 순서([경도, 위도])로 geometry에, 나머지 필드는 그대로 properties에 넣음
+use serde_json::{json, Map, Value};
+
+pub const GEOJSON_CONTENT_TYPE: &str = "application/geo+json";
+
+/// 응답 형식 (기본 json)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Json,
+    GeoJson,
+}
+
+impl ResponseFormat {
+    pub fn parse(format: Option<&str>) -> Result<Self, String> {
+        match format.map(str::trim).filter(|format| !format.is_empty()) {
+            None => Ok(Self::Json),
+            Some(format) if format.eq_ignore_ascii_case("json") => Ok(Self::Json),
+            Some(format) if format.eq_ignore_ascii_case("geojson") => Ok(Self::GeoJson),
+            Some(format) => Err(format!("format은 json 또는 geojson이어야 합니다: {}", format)),
+        }
+    }
+}
+
+/// 객체 하나를 Point Feature로 (좌표가 없으면 geometry null, id 필드는 Feature id로도 씀)
+fn point_feature(value: Value, lat_key: &str, lng_key: &str) -> Value {
+    let mut properties = match value {
+        Value::Object(object) => object,
+        _ => Map::new(),
+    };
+    let lat = properties.remove(lat_key).and_then(|lat| lat.as_f64());
+    let lng = properties.remove(lng_key).and_then(|lng| lng.as_f64());
+    let geometry = match (lat, lng) {
+        (Some(lat), Some(lng)) => json!({ "type": "Point", "coordinates": [lng, lat] }),
+        _ => Value::Null,
+    };
+    let mut feature = json!({
+        "type": "Feature",
+        "geometry": geometry,
+        "properties": properties
+    });
+    if let Some(id) = feature["properties"].get("id").filter(|id| id.is_i64() || id.is_string()).cloned() {
+        feature["id"] = id;
+    }
+    feature
+}
+
+/// 마커 DTO (latitude/longitude) → Feature
+pub fn marker_feature(marker: Value) -> Value {
+    point_feature(marker, "latitude", "longitude")
+}
+
+/// 클러스터 (lat/lng 중심) → Feature
+pub fn cluster_feature(cluster: Value) -> Value {
+    point_feature(cluster, "lat", "lng")
+}
+
+/// Feature 목록 + 응답 부가 정보(metadata)를 FeatureCollection으로
+pub fn feature_collection(features: Vec<Value>, metadata: Value) -> Value {
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+        "metadata": metadata
+    })
+}
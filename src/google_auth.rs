@@ -0,0 +1,171 @@
+// 구글 ID 토큰 검증 (JWKS 서명 + iss/aud 확인)
+use anyhow::{anyhow, bail, Result};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::config::Config;
+
+const GOOGLE_CERTS_URL: &str = "https://www.googleapis.com/oauth2/v3/certs";
+const GOOGLE_ISSUERS: [&str; 2] = ["accounts.google.com", "https://accounts.google.com"];
+const DEFAULT_KEYS_TTL_SECS: u64 = 3600;
+/// 모르는 kid 때문에 공개키를 다시 받아오는 최소 간격 (위조 토큰으로 외부 요청이 폭주하지 않도록)
+const MIN_KEYS_REFETCH_SECS: u64 = 60;
+const PLACEHOLDER_CLIENT_ID: &str = "your-google-client-id";
+
+// 구글 ID 토큰 페이로드 구조체
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoogleIdTokenPayload {
+    pub iss: String,           // issuer (Google)
+    pub sub: String,           // subject (Google user ID)
+    pub aud: String,           // audience (client ID)
+    pub exp: i64,              // expiration time
+    pub iat: i64,              // issued at
+    pub email: String,         // user email
+    pub email_verified: bool,  // email verification status
+    pub name: Option<String>,  // user name
+    pub picture: Option<String>, // profile picture URL
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub locale: Option<String>,
+}
+
+// 구글 공개키 구조체
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GooglePublicKey {
+    pub kid: String,
+    pub e: String,
+    pub n: String,
+    pub alg: String,
+    pub kty: String,
+    #[serde(rename = "use")]
+    pub use_field: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoogleKeysResponse {
+    pub keys: Vec<GooglePublicKey>,
+}
+
+/// 캐시된 구글 공개키 (Cache-Control max-age 동안 유지)
+struct CachedKeys {
+    keys: HashMap<String, GooglePublicKey>,
+    fetched_at: Instant,
+    expires_at: Instant,
+}
+
+impl CachedKeys {
+    /// 캐시로 답할 수 있으면 Some (키가 없어도 최근에 받아왔으면 다시 받지 않고 에러)
+    fn lookup(&self, kid: &str, now: Instant) -> Option<Result<GooglePublicKey>> {
+        if self.expires_at <= now {
+            return None;
+        }
+        match self.keys.get(kid) {
+            Some(key) => Some(Ok(key.clone())),
+            None if now.duration_since(self.fetched_at) < Duration::from_secs(MIN_KEYS_REFETCH_SECS) => {
+                Some(Err(anyhow!("알 수 없는 키 ID: {}", kid)))
+            }
+            None => None,
+        }
+    }
+}
+
+fn keys_cache() -> &'static RwLock<Option<CachedKeys>> {
+    static CACHE: OnceLock<RwLock<Option<CachedKeys>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// Cache-Control 헤더의 max-age 값 (없으면 기본값)
+fn max_age(headers: &reqwest::header::HeaderMap) -> Duration {
+    let secs = headers.get(reqwest::header::CACHE_CONTROL)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| {
+            v.split(',')
+                .filter_map(|directive| directive.trim().strip_prefix("max-age="))
+                .find_map(|age| age.parse::<u64>().ok())
+        })
+        .unwrap_or(DEFAULT_KEYS_TTL_SECS);
+    Duration::from_secs(secs)
+}
+
+async fn fetch_google_keys() -> Result<CachedKeys> {
+    let response = reqwest::Client::new()
+        .get(GOOGLE_CERTS_URL)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await?
+        .error_for_status()?;
+    let ttl = max_age(response.headers());
+    let body: GoogleKeysResponse = response.json().await?;
+    info!("🔑 구글 공개키 갱신: {}개 (캐시 {}초)", body.keys.len(), ttl.as_secs());
+    Ok(CachedKeys {
+        keys: body.keys.into_iter().map(|key| (key.kid.clone(), key)).collect(),
+        fetched_at: Instant::now(),
+        expires_at: Instant::now() + ttl,
+    })
+}
+
+/// kid에 해당하는 공개키 조회. 캐시가 만료됐거나 키가 없으면(키 교체) 한 번 새로 받아옴
+/// 키가 없어도 MIN_KEYS_REFETCH_SECS 안에 받아온 캐시면 다시 받지 않음
+async fn find_google_key(kid: &str) -> Result<GooglePublicKey> {
+    if let Some(found) = keys_cache().read().await.as_ref().and_then(|cached| cached.lookup(kid, Instant::now())) {
+        return found;
+    }
+
+    let mut cache = keys_cache().write().await;
+    // 다른 요청이 먼저 갱신했을 수 있으므로 다시 확인
+    if let Some(found) = cache.as_ref().and_then(|cached| cached.lookup(kid, Instant::now())) {
+        return found;
+    }
+    let fresh = fetch_google_keys().await?;
+    let key = fresh.keys.get(kid).cloned();
+    *cache = Some(fresh);
+    key.ok_or_else(|| anyhow!("알 수 없는 키 ID: {}", kid))
+}
+
+/// 토큰 aud로 허용할 클라이언트 ID 목록 (GOOGLE_CLIENT_IDS + GOOGLE_CLIENT_ID)
+fn allowed_client_ids(config: &Config) -> Vec<String> {
+    let mut ids = config.google_client_ids.clone();
+    if !config.google_client_id.is_empty()
+        && config.google_client_id != PLACEHOLDER_CLIENT_ID
+        && !ids.contains(&config.google_client_id)
+    {
+        ids.push(config.google_client_id.clone());
+    }
+    ids
+}
+
+/// 구글 ID 토큰 검증: RS256 서명, 만료, iss, aud(설정된 클라이언트 ID) 확인
+pub async fn verify_google_id_token(id_token: &str, config: &Config) -> Result<GoogleIdTokenPayload> {
+    let client_ids = allowed_client_ids(config);
+    if client_ids.is_empty() {
+        warn!("⚠️ GOOGLE_CLIENT_IDS가 설정되지 않아 구글 ID 토큰을 검증할 수 없습니다");
+        bail!("Google client ID not configured");
+    }
+
+    let header = decode_header(id_token)?;
+    if header.alg != Algorithm::RS256 {
+        bail!("Unsupported algorithm: {:?}", header.alg);
+    }
+    let kid = header.kid.ok_or_else(|| anyhow!("Missing key id"))?;
+    let key = find_google_key(&kid).await?;
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_audience(&client_ids);
+    validation.set_issuer(&GOOGLE_ISSUERS);
+    let payload = decode::<GoogleIdTokenPayload>(
+        id_token,
+        &DecodingKey::from_rsa_components(&key.n, &key.e)?,
+        &validation,
+    )?
+    .claims;
+
+    if !payload.email_verified {
+        bail!("Email not verified");
+    }
+    Ok(payload)
+}
@@ -0,0 +1,72 @@
+// 마커 해시태그 추출/정규화
+// 설명 속 #태그와 생성 요청의 tags 배열을 같은 규칙(소문자, 글자/숫자/밑줄만)으로 저장해 검색과 집계가 일치하도록 함
+
+/// 마커 하나에 저장하는 최대 태그 수
+pub const MAX_TAGS_PER_MARKER: usize = 10;
+/// 태그 최대 길이 (글자 수, DB 컬럼은 VARCHAR(50))
+pub const MAX_TAG_CHARS: usize = 30;
+
+fn is_tag_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// 태그 정규화 (앞의 #은 떼고 소문자로, 허용되지 않는 글자가 있거나 너무 길면 None)
+pub fn normalize_tag(raw: &str) -> Option<String> {
+    let tag = raw.trim().trim_start_matches('#');
+    if tag.is_empty() || tag.chars().count() > MAX_TAG_CHARS || !tag.chars().all(is_tag_char) {
+        return None;
+    }
+    // 숫자만 있는 태그(#1, #2024)는 목록 번호와 구분이 안 되므로 제외
+    if tag.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    Some(tag.to_lowercase())
+}
+
+/// 본문에서 #태그 추출 (단어 중간의 #, 예: C#이나 URL 조각은 제외)
+pub fn extract_hashtags(text: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut previous: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        let starts_tag = ch == '#' && !previous.is_some_and(|prev| is_tag_char(prev) || prev == '#' || prev == '/');
+        previous = Some(ch);
+        if !starts_tag {
+            continue;
+        }
+        let mut end = start + ch.len_utf8();
+        while let Some(&(index, next)) = chars.peek() {
+            if !is_tag_char(next) {
+                break;
+            }
+            end = index + next.len_utf8();
+            previous = Some(next);
+            chars.next();
+        }
+        if let Some(tag) = normalize_tag(&text[start..end])
+            && !tags.contains(&tag)
+        {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// 저장할 마커 태그 (요청 tags 먼저, 그다음 설명의 #태그, 중복 제거 후 최대 MAX_TAGS_PER_MARKER개)
+/// 요청 tags에 잘못된 태그가 있으면 그 태그를 Err로 반환
+pub fn collect_marker_tags(description: &str, explicit: Option<&[String]>) -> Result<Vec<String>, String> {
+    let mut tags: Vec<String> = Vec::new();
+    for raw in explicit.unwrap_or_default() {
+        let tag = normalize_tag(raw).ok_or_else(|| raw.clone())?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    for tag in extract_hashtags(description) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags.truncate(MAX_TAGS_PER_MARKER);
+    Ok(tags)
+}
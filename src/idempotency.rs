@@ -0,0 +1,209 @@
+// 멱등성 키 (Idempotency-Key 헤더): 마커 생성/업로드 POST를 앱이 재시도해도 한 번만 처리
+// 처음 요청의 2xx 응답을 저장해 두고, 같은 키로 다시 오면 핸들러를 거치지 않고 그 응답을 돌려줌
+// 키는 회원별(비로그인은 IP별)로 구분하고, 같은 키를 다른 요청(메서드/경로/쿼리/본문 해시가 다름)에 쓰면 422
+use actix_web::{
+    body::{self, BodySize, BoxBody, MessageBody},
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    http::{header, StatusCode},
+    middleware::Next,
+    web, Error, HttpMessage, HttpResponse,
+};
+use chrono::Duration;
+use futures_util::stream::StreamExt;
+use log::{info, warn};
+use sha1::{Digest, Sha1};
+use std::sync::Arc;
+
+use crate::auth::{AuthError, AuthenticatedMember};
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::database::{Database, IdempotencyClaim, IdempotentResponse};
+use crate::error_handler::ErrorHandler;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// 저장된 응답을 돌려줄 때 붙이는 헤더
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+/// 해시를 위해 메모리로 읽는 본문 최대 크기 (멀티파트 업로드는 읽지 않음)
+const MAX_HASHED_BODY_BYTES: usize = 1024 * 1024;
+/// 이보다 큰 응답은 저장하지 않음 (재요청은 다시 처리됨)
+const MAX_STORED_RESPONSE_BYTES: u64 = 256 * 1024;
+/// 처리 중 표시가 이보다 오래되면 서버가 중간에 멈춘 것으로 보고 다시 선점
+const PROCESSING_LOCK_SECS: i64 = 120;
+const CLEANUP_INTERVAL_SECS: u64 = 3600;
+
+/// 헤더에 쓸 수 있는 키 (공백 없는 ASCII, 255자 이하)
+pub fn is_valid_idempotency_key(value: &str) -> bool {
+    !value.is_empty() && value.len() <= MAX_IDEMPOTENCY_KEY_LEN && value.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// 요청 해시 (SHA-1 hex): 메서드, 경로, 쿼리, 미디어 타입, 본문
+/// 멀티파트는 재시도마다 경계 문자열이 달라질 수 있어 본문을 넣지 않음 (body None)
+pub fn request_hash(method: &str, path: &str, query: &str, media_type: &str, body: Option<&[u8]>) -> String {
+    let mut hasher = Sha1::new();
+    for part in [method, path, query, media_type] {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+    if let Some(body) = body {
+        hasher.update(body);
+    }
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// 본문을 모두 읽어 다시 넣음 (핸들러가 그대로 읽을 수 있게), 너무 크면 None
+async fn buffer_body(req: &mut ServiceRequest) -> Result<Option<web::Bytes>, Error> {
+    let mut payload = std::mem::replace(req.parts_mut().1, Payload::None);
+    let mut body = web::BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk?;
+        if body.len() + chunk.len() > MAX_HASHED_BODY_BYTES {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let body = body.freeze();
+    req.set_payload(Payload::from(body.clone()));
+    Ok(Some(body))
+}
+
+fn replay(stored: IdempotentResponse) -> HttpResponse {
+    let status = StatusCode::from_u16(stored.status as u16).unwrap_or(StatusCode::OK);
+    let mut response = HttpResponse::build(status);
+    if let Some(content_type) = stored.content_type {
+        response.content_type(content_type);
+    }
+    response.insert_header((IDEMPOTENT_REPLAYED_HEADER, "true")).body(stored.body)
+}
+
+/// 마커 생성/업로드 리소스에 거는 미들웨어 (Idempotency-Key가 있는 POST만 처리)
+/// 키 저장소(DB) 오류 시에는 요청을 막지 않고 멱등성 없이 처리
+pub async fn idempotent_requests(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let key = req.headers().get(IDEMPOTENCY_KEY_HEADER).map(|value| value.to_str().unwrap_or_default().trim().to_string());
+    let db = req.app_data::<web::Data<Database>>().cloned();
+    let clock = req.app_data::<web::Data<dyn Clock>>().cloned();
+    let config = req.app_data::<web::Data<Config>>().cloned();
+    let (Some(key), Some(db), Some(clock), Some(config)) = (key, db, clock, config) else {
+        return Ok(next.call(req).await?.map_into_boxed_body());
+    };
+    if req.method() != actix_web::http::Method::POST {
+        return Ok(next.call(req).await?.map_into_boxed_body());
+    }
+    if !is_valid_idempotency_key(&key) {
+        return Ok(req.into_response(ErrorHandler::bad_request(
+            "Idempotency-Key 형식이 올바르지 않습니다",
+            Some(&format!("공백 없는 ASCII {}자 이하", MAX_IDEMPOTENCY_KEY_LEN)),
+            None
+        )).map_into_boxed_body());
+    }
+
+    let member_id = match req.extensions().get::<Result<AuthenticatedMember, AuthError>>() {
+        Some(Ok(member)) => Some(member.member_id),
+        _ => None,
+    };
+    let scope = match member_id {
+        Some(member_id) => format!("member:{}", member_id),
+        None => format!("ip:{}", req.connection_info().realip_remote_addr().unwrap_or("unknown")),
+    };
+    let media_type = req.mime_type().ok().flatten().map(|mime| mime.essence_str().to_string()).unwrap_or_default();
+    let body = if media_type.starts_with("multipart/") {
+        None
+    } else {
+        match buffer_body(&mut req).await? {
+            Some(body) => Some(body),
+            None => {
+                return Ok(req.into_response(ErrorHandler::bad_request(
+                    "Idempotency-Key를 쓰는 요청 본문이 너무 큽니다",
+                    Some(&format!("최대 {}KB", MAX_HASHED_BODY_BYTES / 1024)),
+                    None
+                )).map_into_boxed_body());
+            }
+        }
+    };
+    let hash = request_hash(req.method().as_str(), req.path(), req.query_string(), &media_type, body.as_deref());
+
+    let now = clock.now();
+    let ttl = Duration::hours(config.idempotency_key_ttl_hours.max(1));
+    let claim = db.claim_idempotency_key(&scope, &key, &hash, now, ttl, Duration::seconds(PROCESSING_LOCK_SECS)).await;
+    match claim {
+        Ok(IdempotencyClaim::Acquired) => {}
+        Ok(IdempotencyClaim::Completed(stored)) => {
+            info!("🔁 멱등성 키 재요청: {} {} ({}) → 저장된 응답 {}", req.method(), req.path(), scope, stored.status);
+            return Ok(req.into_response(replay(stored)).map_into_boxed_body());
+        }
+        Ok(IdempotencyClaim::InProgress) => {
+            return Ok(req.into_response(ErrorHandler::conflict(
+                "같은 Idempotency-Key 요청을 처리하고 있습니다. 잠시 후 다시 시도해주세요.",
+                Some(&key)
+            )).map_into_boxed_body());
+        }
+        Ok(IdempotencyClaim::Mismatch) => {
+            return Ok(req.into_response(ErrorHandler::unprocessable_entity(
+                "Idempotency-Key가 다른 요청에 이미 사용되었습니다",
+                Some("요청마다 새 키를 만들고, 재시도할 때만 같은 키를 보내세요")
+            )).map_into_boxed_body());
+        }
+        Err(e) => {
+            warn!("⚠️ 멱등성 키 확인 실패 (멱등성 없이 처리): {}", e);
+            return Ok(next.call(req).await?.map_into_boxed_body());
+        }
+    }
+
+    let response = match next.call(req).await {
+        Ok(response) => response,
+        Err(e) => {
+            release(&db, &scope, &key).await;
+            return Err(e);
+        }
+    };
+    // 실패 응답(토큰 만료, 일시 오류 등)은 저장하지 않고 같은 키로 다시 시도할 수 있게 함
+    let storable = matches!(response.response().body().size(), BodySize::Sized(size) if size <= MAX_STORED_RESPONSE_BYTES);
+    if !response.status().is_success() || !storable {
+        release(&db, &scope, &key).await;
+        return Ok(response.map_into_boxed_body());
+    }
+
+    let (http_req, http_res) = response.into_parts();
+    let (http_res, response_body) = http_res.into_parts();
+    let bytes = match body::to_bytes(response_body).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            release(&db, &scope, &key).await;
+            let error: Box<dyn std::error::Error> = e.into();
+            return Err(actix_web::error::ErrorInternalServerError(error.to_string()));
+        }
+    };
+    let stored = IdempotentResponse {
+        status: http_res.status().as_u16() as i32,
+        content_type: http_res.headers().get(header::CONTENT_TYPE).and_then(|value| value.to_str().ok()).map(str::to_string),
+        body: bytes.to_vec(),
+    };
+    if let Err(e) = db.complete_idempotency_key(&scope, &key, &stored, clock.now()).await {
+        warn!("⚠️ 멱등성 키 응답 저장 실패: {}", e);
+        release(&db, &scope, &key).await;
+    }
+    Ok(ServiceResponse::new(http_req, http_res.set_body(bytes)).map_into_boxed_body())
+}
+
+async fn release(db: &Database, scope: &str, key: &str) {
+    if let Err(e) = db.release_idempotency_key(scope, key).await {
+        warn!("⚠️ 멱등성 키 해제 실패: {}", e);
+    }
+}
+
+/// 보관 기간이 지난 멱등성 키를 주기적으로 정리 (서버 시작 시 spawn)
+pub async fn run_idempotency_cleanup_worker(db: Database, clock: Arc<dyn Clock>, ttl_hours: i64) {
+    info!("🔁 멱등성 키 정리 작업 시작 ({}시간 보관)", ttl_hours.max(1));
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(CLEANUP_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        match db.delete_expired_idempotency_keys(clock.now() - Duration::hours(ttl_hours.max(1))).await {
+            Ok(0) => {}
+            Ok(deleted) => info!("🧹 만료된 멱등성 키 {}개 정리", deleted),
+            Err(e) => warn!("⚠️ 멱등성 키 정리 실패: {}", e),
+        }
+    }
+}
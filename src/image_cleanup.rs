@@ -0,0 +1,170 @@
+// 이미지 파일 정리 작업 처리 (회원 탈퇴, 마커 이미지 삭제 등으로 더 이상 참조되지 않는 S3/로컬 파일 삭제)
+// 변환된 이미지와 함께 업로드 원본(S3 originals/, 로컬 *_original/)과 너비별 이미지도 삭제
+use log::{error, info, warn};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::database::{Database, ImageCleanupJob, NewDeadLetterJob, DLQ_IMAGE_CLEANUP};
+use crate::s3_service::S3Service;
+use crate::storage::{LocalStorage, Storage};
+
+const CLEANUP_INTERVAL_SECS: u64 = 300;
+const CLEANUP_BATCH_SIZE: i64 = 50;
+const MAX_CLEANUP_ATTEMPTS: i32 = 5;
+
+/// 로컬 업로드 파일이 있을 수 있는 디렉토리 종류
+const LOCAL_IMAGE_TYPES: [&str; 3] = ["thumbnail", "map", "generated_thumbnail"];
+
+pub(crate) enum StoredImage {
+    S3(String),
+    Local(String),
+}
+
+impl StoredImage {
+    /// DB에 기록하는 저장 위치 ("s3:{객체 키}", "local:{파일 이름}", 마이그레이션의 image_storage_key와 같은 형식)
+    pub(crate) fn storage_key(&self) -> String {
+        match self {
+            StoredImage::S3(key) => format!("s3:{}", key),
+            StoredImage::Local(filename) => format!("local:{}", filename),
+        }
+    }
+
+    pub(crate) fn parse(storage_key: &str) -> Option<Self> {
+        match storage_key.split_once(':')? {
+            ("s3", key) if !key.is_empty() => Some(StoredImage::S3(key.to_string())),
+            ("local", filename) if !filename.is_empty() => Some(StoredImage::Local(filename.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// 이미지 URL의 저장 위치 키 (알 수 없는 URL이면 None)
+pub fn storage_key(image_url: &str) -> Option<String> {
+    locate(image_url).map(|stored| stored.storage_key())
+}
+
+/// 저장된 이미지 URL이 가리키는 위치
+/// S3 업로드는 "/markers/..." 경로 또는 전체 S3 URL, 로컬 업로드는 다운로드 API URL로 저장됨
+pub(crate) fn locate(image_url: &str) -> Option<StoredImage> {
+    if let Some((_, filename)) = image_url.split_once("/api/images/download/") {
+        return Some(StoredImage::Local(filename.to_string()));
+    }
+    if let Some((_, key)) = image_url.split_once(".amazonaws.com/") {
+        return Some(StoredImage::S3(key.to_string()));
+    }
+    image_url.strip_prefix('/')
+        .filter(|key| !key.is_empty())
+        .map(|key| StoredImage::S3(key.to_string()))
+}
+
+fn remove_local_file(filename: &str, config: &Config) -> anyhow::Result<()> {
+    if filename.contains('/') || filename.contains("..") {
+        anyhow::bail!("잘못된 파일 이름: {}", filename);
+    }
+    for image_type in LOCAL_IMAGE_TYPES {
+        for dir in [config.get_upload_path(image_type), config.get_original_upload_path(image_type)] {
+            let path = format!("{}/{}", dir, filename);
+            if Path::new(&path).exists() {
+                std::fs::remove_file(&path)?;
+                info!("🗑️ 로컬 파일 삭제: {}", path);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 로컬 업로드 원본 (WebP 기록에 연결된 *_original/ 파일), 파일과 기록을 함께 삭제
+async fn remove_local_original(db: &Database, filename: &str) -> anyhow::Result<()> {
+    let Some((original_id, file_path)) = db.get_local_original_of_webp(filename).await? else {
+        return Ok(());
+    };
+    if file_path.contains("..") {
+        anyhow::bail!("잘못된 원본 경로: {}", file_path);
+    }
+    if Path::new(&file_path).exists() {
+        std::fs::remove_file(&file_path)?;
+        info!("🗑️ 로컬 원본 삭제: {}", file_path);
+    }
+    db.delete_original_image(original_id).await
+}
+
+async fn delete_image(db: &Database, job: &ImageCleanupJob, config: &Config, s3_service: &S3Service) -> anyhow::Result<()> {
+    let stored = match job.storage_key.as_deref() {
+        Some(storage_key) => StoredImage::parse(storage_key),
+        None => locate(&job.image_url),
+    };
+    let Some(stored) = stored else {
+        warn!("⚠️ 삭제할 수 없는 이미지 URL, 건너뜀: {}", job.image_url);
+        return Ok(());
+    };
+    
+    // 너비별(반응형) 이미지도 변환된 이미지보다 먼저 삭제
+    for (_, _, variant_key) in db.get_image_size_variants(&stored.storage_key()).await? {
+        match StoredImage::parse(&variant_key) {
+            Some(StoredImage::S3(key)) => s3_service.delete_file(&key).await?,
+            Some(StoredImage::Local(key)) => LocalStorage::from_config(config).delete(&key).await?,
+            None => {}
+        }
+    }
+    db.delete_image_size_variants(&stored.storage_key()).await?;
+    db.delete_image_moderation(&stored.storage_key()).await?;
+    // 원본을 먼저 지워야 중간에 실패해도 재시도 때 원본 기록을 다시 찾을 수 있음
+    if let Some(original_key) = db.get_image_original(&stored.storage_key()).await? {
+        match StoredImage::parse(&original_key) {
+            Some(StoredImage::S3(original)) => s3_service.delete_file(&original).await?,
+            Some(StoredImage::Local(original)) => LocalStorage::from_config(config).delete(&original).await?,
+            None => {}
+        }
+        db.delete_image_original(&stored.storage_key()).await?;
+    }
+    match &stored {
+        StoredImage::S3(key) => s3_service.delete_file(key).await,
+        // 저장소(STORAGE_BACKEND=local)에 키 경로로 저장된 파일
+        StoredImage::Local(key) if key.contains('/') => LocalStorage::from_config(config).delete(key).await,
+        StoredImage::Local(filename) => {
+            remove_local_original(db, filename).await?;
+            remove_local_file(filename, config)
+        }
+    }
+}
+
+/// 대기 중인 정리 작업 한 묶음 처리 (처리한 작업 수 반환)
+pub async fn process_pending_jobs(db: &Database, config: &Config, s3_service: &S3Service) -> anyhow::Result<usize> {
+    let jobs = db.get_pending_image_cleanup_jobs(CLEANUP_BATCH_SIZE, MAX_CLEANUP_ATTEMPTS).await?;
+    for job in &jobs {
+        let error_message = delete_image(db, job, config, s3_service).await
+            .err()
+            .map(|e| e.to_string());
+        if let Some(e) = &error_message {
+            warn!("⚠️ 이미지 정리 실패 ({}회째): {} - {}", job.attempts + 1, job.image_url, e);
+        }
+        db.finish_image_cleanup_job(job.id, error_message.as_deref()).await?;
+        // 마지막 시도까지 실패하면 더 이상 가져가지 않으므로 데드레터 큐로 넘김
+        if error_message.is_some() && job.attempts + 1 >= MAX_CLEANUP_ATTEMPTS {
+            error!("🪦 이미지 정리 재시도 한도 초과, 데드레터 큐로 이동: {}", job.image_url);
+            db.add_dead_letter_job(&NewDeadLetterJob {
+                job_type: DLQ_IMAGE_CLEANUP.to_string(),
+                source_id: Some(job.id),
+                payload: serde_json::json!({ "image_url": job.image_url, "storage_key": job.storage_key }),
+                attempts: job.attempts + 1,
+                last_error: error_message,
+            }).await?;
+        }
+    }
+    Ok(jobs.len())
+}
+
+/// 주기적으로 정리 작업 처리
+pub async fn run_image_cleanup_worker(db: Database, config: Config, s3_service: S3Service) {
+    info!("🧹 이미지 정리 작업 처리기 시작 ({}초 간격)", CLEANUP_INTERVAL_SECS);
+    let mut interval = tokio::time::interval(Duration::from_secs(CLEANUP_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        match process_pending_jobs(&db, &config, &s3_service).await {
+            Ok(0) => {}
+            Ok(count) => info!("🧹 이미지 정리 작업 {}건 처리", count),
+            Err(e) => error!("❌ 이미지 정리 작업 처리 실패: {}", e),
+        }
+    }
+}
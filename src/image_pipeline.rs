@@ -0,0 +1,493 @@
+// 이미지 처리 파이프라인: 디코딩 → 방향 보정 → 리사이즈 → 필터 → 인코딩
+// 워터마크, blurhash, 유해 이미지 검사, 스마트 크롭 같은 선택 기능은 ImagePlugin으로 원하는 단계에 등록
+// (ImageProcessor를 고치지 않고 붙였다 뗄 수 있음), 단계/플러그인별 소요 시간을 모아 /api/metrics로 보여줌
+use anyhow::{bail, Result};
+use chrono::NaiveDateTime;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::blurhash;
+use crate::image_processor::{ImageProcessor, ImageQuality};
+use crate::image_workers::ImageWorkers;
+use crate::moderation::Moderation;
+
+/// 파이프라인 단계 (선언 순서대로 실행)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PipelineStage {
+    Decode,
+    Orient,
+    Resize,
+    Filter,
+    Encode,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 5] = [
+        PipelineStage::Decode,
+        PipelineStage::Orient,
+        PipelineStage::Resize,
+        PipelineStage::Filter,
+        PipelineStage::Encode,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PipelineStage::Decode => "decode",
+            PipelineStage::Orient => "orient",
+            PipelineStage::Resize => "resize",
+            PipelineStage::Filter => "filter",
+            PipelineStage::Encode => "encode",
+        }
+    }
+}
+
+/// 단계 사이에 넘겨지는 처리 중인 이미지
+pub struct ImageFrame {
+    pub image: DynamicImage,
+    /// EXIF 방향 값 (1~8, 없으면 1). 방향 보정 단계가 끝나면 1
+    pub orientation: u16,
+    /// 원본 기준 품질 정보 (리사이즈 단계에서 리사이즈 전에 측정)
+    pub quality: Option<ImageQuality>,
+    /// 플러그인이 남기는 결과 (예: blurhash, nsfw 점수)
+    pub annotations: BTreeMap<String, String>,
+    /// 인코딩 단계가 끝난 뒤의 결과 바이트 (인코딩 단계 플러그인이 확인/교체 가능)
+    pub encoded: Vec<u8>,
+    /// 원본 파일 형식 (PNG 무손실 인코딩 판단용, 알 수 없으면 None)
+    pub source_format: Option<ImageFormat>,
+}
+
+/// 단계에 끼워 넣는 선택 기능. 같은 단계의 플러그인은 기본 처리 뒤에 등록 순서대로 실행
+pub trait ImagePlugin: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn stage(&self) -> PipelineStage;
+    fn apply(&self, frame: &mut ImageFrame) -> Result<()>;
+    /// true면 실패 시 이미지 처리 전체를 실패시킴 (기본은 경고만 남기고 계속)
+    fn required(&self) -> bool {
+        false
+    }
+}
+
+/// 단계 또는 플러그인 1회 실행 시간
+#[derive(Debug, Clone)]
+pub struct StageTiming {
+    pub stage: PipelineStage,
+    pub plugin: Option<&'static str>, // None이면 기본 처리
+    pub elapsed: Duration,
+}
+
+impl StageTiming {
+    /// 지표 키 (예: resize, filter:watermark)
+    pub fn key(&self) -> String {
+        match self.plugin {
+            Some(plugin) => format!("{}:{}", self.stage.as_str(), plugin),
+            None => self.stage.as_str().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct StageStats {
+    count: u64,
+    failures: u64,
+    total: Duration,
+    max: Duration,
+}
+
+/// 등록된 플러그인과 누적 단계 지표 (워커 전체가 공유)
+/// 검수는 외부 서비스를 기다려야 해서 동기 플러그인 대신 인코딩 후 업로드 단계에서 따로 실행
+#[derive(Clone, Default)]
+pub struct ImageHooks {
+    plugins: Vec<Arc<dyn ImagePlugin>>,
+    stats: Arc<Mutex<HashMap<String, StageStats>>>,
+    moderation: Moderation,
+    workers: ImageWorkers,
+}
+
+impl ImageHooks {
+    /// 플러그인 등록 (서버 시작 시, 테스트에서는 AppState를 만든 뒤)
+    pub fn with_plugin(mut self, plugin: Arc<dyn ImagePlugin>) -> Self {
+        self.plugins.push(plugin);
+        self
+    }
+
+    /// 업로드 이미지 검수기 연결 (MODERATION_BACKEND)
+    pub fn with_moderation(mut self, moderation: Moderation) -> Self {
+        self.moderation = moderation;
+        self
+    }
+
+    pub fn moderation(&self) -> &Moderation {
+        &self.moderation
+    }
+
+    /// 이미지 처리 블로킹 스레드 자리 (IMAGE_PROCESSING_CONCURRENCY, IMAGE_PROCESSING_TIMEOUT_SECS)
+    pub fn with_workers(mut self, workers: ImageWorkers) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    pub fn workers(&self) -> &ImageWorkers {
+        &self.workers
+    }
+
+    pub fn plugin_names(&self) -> Vec<&'static str> {
+        self.plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+
+    fn plugins_for(&self, stage: PipelineStage) -> impl Iterator<Item = &Arc<dyn ImagePlugin>> {
+        self.plugins.iter().filter(move |plugin| plugin.stage() == stage)
+    }
+
+    fn record(&self, timing: &StageTiming, failed: bool) {
+        let mut stats = self.stats.lock().unwrap();
+        let entry = stats.entry(timing.key()).or_default();
+        entry.count += 1;
+        entry.failures += failed as u64;
+        entry.total += timing.elapsed;
+        entry.max = entry.max.max(timing.elapsed);
+    }
+
+    /// 메트릭 응답용 단계/플러그인별 누적 시간
+    pub fn metrics_json(&self) -> serde_json::Value {
+        let stats = self.stats.lock().unwrap();
+        let mut keys: Vec<&String> = stats.keys().collect();
+        keys.sort();
+        let stages: Vec<serde_json::Value> = keys.into_iter()
+            .map(|key| {
+                let entry = stats[key];
+                serde_json::json!({
+                    "stage": key,
+                    "count": entry.count,
+                    "failures": entry.failures,
+                    "avgMs": entry.total.as_secs_f64() * 1000.0 / entry.count.max(1) as f64,
+                    "maxMs": entry.max.as_secs_f64() * 1000.0
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "plugins": self.plugin_names(),
+            "stages": stages
+        })
+    }
+}
+
+/// 파이프라인 실행 결과
+pub struct PipelineOutput {
+    pub data: Vec<u8>,
+    pub quality: ImageQuality,
+    pub dimensions: (u32, u32), // 인코딩한 이미지 크기 (quality는 리사이즈 전 크기)
+    pub blurhash: String, // 인코딩한 이미지의 자리표시자
+    pub annotations: BTreeMap<String, String>,
+    pub timings: Vec<StageTiming>,
+}
+
+/// EXIF를 찾으려고 파일 앞부분에서 읽는 최대 크기 (APP1 세그먼트는 64KB 이하)
+const EXIF_SCAN_BYTES: u64 = 128 * 1024;
+
+/// 처리할 원본 이미지: 메모리의 바이트 또는 업로드 임시 파일
+/// 파일은 디코더가 직접 읽으므로 원본 전체를 메모리에 올리지 않음
+#[derive(Debug, Clone, Copy)]
+pub enum ImageSource<'a> {
+    Memory(&'a [u8]),
+    File(&'a Path),
+}
+
+impl ImageSource<'_> {
+    /// 디코딩한 이미지와 EXIF 방향 값, 원본 형식
+    fn decode(&self) -> Result<(DynamicImage, u16, Option<ImageFormat>)> {
+        match self {
+            ImageSource::Memory(data) => Ok((image::load_from_memory(data)?, read_exif_orientation(data).unwrap_or(1), image::guess_format(data).ok())),
+            ImageSource::File(path) => {
+                let reader = image::io::Reader::open(path)?.with_guessed_format()?;
+                let format = reader.format();
+                Ok((reader.decode()?, read_exif_orientation(&read_head(path)?).unwrap_or(1), format))
+            }
+        }
+    }
+
+    /// EXIF 방향대로 세운 이미지 (검수처럼 변환 파이프라인 밖에서 원본을 볼 때)
+    pub fn decode_upright(&self) -> Result<DynamicImage> {
+        let (image, orientation, _) = self.decode()?;
+        Ok(apply_orientation(image, orientation))
+    }
+
+    /// 촬영 위치/시간 (JPEG EXIF, 파일은 앞부분만 읽음)
+    pub fn read_exif(&self) -> Result<PhotoExif> {
+        match self {
+            ImageSource::Memory(data) => Ok(read_photo_exif(data)),
+            ImageSource::File(path) => Ok(read_photo_exif(&read_head(path)?)),
+        }
+    }
+}
+
+/// 블로킹 스레드로 넘길 수 있는 원본 (ImageSource의 소유 버전)
+#[derive(Debug, Clone)]
+pub enum SharedImageSource {
+    Memory(Arc<[u8]>),
+    File(PathBuf),
+}
+
+impl SharedImageSource {
+    pub fn as_source(&self) -> ImageSource<'_> {
+        match self {
+            SharedImageSource::Memory(data) => ImageSource::Memory(data),
+            SharedImageSource::File(path) => ImageSource::File(path),
+        }
+    }
+}
+
+impl From<ImageSource<'_>> for SharedImageSource {
+    fn from(source: ImageSource<'_>) -> Self {
+        match source {
+            ImageSource::Memory(data) => SharedImageSource::Memory(Arc::from(data)),
+            ImageSource::File(path) => SharedImageSource::File(path.to_path_buf()),
+        }
+    }
+}
+
+fn read_head(path: &Path) -> Result<Vec<u8>> {
+    let mut head = Vec::new();
+    std::fs::File::open(path)?.take(EXIF_SCAN_BYTES).read_to_end(&mut head)?;
+    Ok(head)
+}
+
+/// 프로세서 설정(최대 크기)과 등록된 플러그인으로 이미지 1장 처리
+pub fn run_pipeline(processor: &ImageProcessor, hooks: &ImageHooks, source: ImageSource<'_>) -> Result<PipelineOutput> {
+    let mut timings = Vec::new();
+    let started = Instant::now();
+    let (image, orientation, source_format) = source.decode()?;
+    let mut frame = ImageFrame {
+        image,
+        orientation,
+        quality: None,
+        annotations: BTreeMap::new(),
+        encoded: Vec::new(),
+        source_format,
+    };
+    finish_stage(hooks, &mut timings, PipelineStage::Decode, started);
+
+    for stage in PipelineStage::ALL {
+        if stage != PipelineStage::Decode {
+            let started = Instant::now();
+            run_builtin_stage(processor, stage, &mut frame)?;
+            finish_stage(hooks, &mut timings, stage, started);
+        }
+        for plugin in hooks.plugins_for(stage) {
+            let started = Instant::now();
+            let result = plugin.apply(&mut frame);
+            let failed = result.is_err();
+            timings.push(StageTiming { stage, plugin: Some(plugin.name()), elapsed: started.elapsed() });
+            hooks.record(timings.last().unwrap(), failed);
+            if let Err(e) = result {
+                if plugin.required() {
+                    bail!("이미지 플러그인 {} 실패: {}", plugin.name(), e);
+                }
+                warn!("⚠️ 이미지 플러그인 {} 실패 (건너뜀): {}", plugin.name(), e);
+            }
+        }
+    }
+
+    info!("⏱️ 이미지 파이프라인: {}", timings.iter()
+        .map(|timing| format!("{} {:.0}ms", timing.key(), timing.elapsed.as_secs_f64() * 1000.0))
+        .collect::<Vec<_>>()
+        .join(", "));
+    let quality = frame.quality.unwrap_or_else(|| {
+        let (width, height) = frame.image.dimensions();
+        ImageQuality { width, height, sharpness: 0.0 }
+    });
+    Ok(PipelineOutput {
+        data: frame.encoded,
+        quality,
+        dimensions: frame.image.dimensions(),
+        blurhash: blurhash::encode(&frame.image, blurhash::X_COMPONENTS, blurhash::Y_COMPONENTS),
+        annotations: frame.annotations,
+        timings,
+    })
+}
+
+fn finish_stage(hooks: &ImageHooks, timings: &mut Vec<StageTiming>, stage: PipelineStage, started: Instant) {
+    let timing = StageTiming { stage, plugin: None, elapsed: started.elapsed() };
+    hooks.record(&timing, false);
+    timings.push(timing);
+}
+
+fn run_builtin_stage(processor: &ImageProcessor, stage: PipelineStage, frame: &mut ImageFrame) -> Result<()> {
+    match stage {
+        PipelineStage::Decode | PipelineStage::Filter => {}
+        PipelineStage::Orient => {
+            let image = std::mem::replace(&mut frame.image, DynamicImage::new_rgba8(0, 0));
+            frame.image = apply_orientation(image, frame.orientation);
+            frame.orientation = 1;
+        }
+        PipelineStage::Resize => {
+            let (width, height) = frame.image.dimensions();
+            frame.quality = Some(ImageQuality {
+                width,
+                height,
+                sharpness: processor.compute_sharpness(&frame.image),
+            });
+            let image = std::mem::replace(&mut frame.image, DynamicImage::new_rgba8(0, 0));
+            frame.image = processor.resize_image(image);
+        }
+        PipelineStage::Encode => {
+            frame.encoded = processor.encode(&frame.image, frame.source_format)?;
+        }
+    }
+    Ok(())
+}
+
+/// EXIF 방향 값대로 회전/반전 (휴대폰 세로 사진이 눕혀 저장되는 문제)
+pub fn apply_orientation(image: DynamicImage, orientation: u16) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+/// JPEG의 EXIF(APP1)에서 방향 태그(0x0112) 읽기, JPEG가 아니거나 없으면 None
+pub fn read_exif_orientation(data: &[u8]) -> Option<u16> {
+    let tiff = Tiff::parse(find_exif_tiff(data)?)?;
+    tiff.entry(tiff.first_ifd()?, 0x0112)
+        .and_then(|entry| tiff.u16_at(entry + 8))
+        .filter(|orientation| (1..=8).contains(orientation))
+}
+
+/// 사진 EXIF의 촬영 위치(GPS)와 촬영 시간 (마커 위치/시간 추천용)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhotoExif {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub taken_at: Option<NaiveDateTime>, // DateTimeOriginal (시간대 없는 촬영 기기 현지 시간)
+}
+
+/// JPEG의 EXIF에서 GPS 좌표와 DateTimeOriginal 읽기, 없는 값은 None
+pub fn read_photo_exif(data: &[u8]) -> PhotoExif {
+    let Some(tiff) = find_exif_tiff(data).and_then(Tiff::parse) else {
+        return PhotoExif::default();
+    };
+    let Some(ifd0) = tiff.first_ifd() else {
+        return PhotoExif::default();
+    };
+    let (latitude, longitude) = tiff.pointer(ifd0, 0x8825)
+        .and_then(|gps| Some((tiff.gps_coordinate(gps, 0x0001, 0x0002, 90.0)?, tiff.gps_coordinate(gps, 0x0003, 0x0004, 180.0)?)))
+        .unzip();
+    let taken_at = tiff.pointer(ifd0, 0x8769)
+        .and_then(|exif| tiff.ascii(exif, 0x9003))
+        .and_then(|value| NaiveDateTime::parse_from_str(value, "%Y:%m:%d %H:%M:%S").ok());
+    PhotoExif { latitude, longitude, taken_at }
+}
+
+/// JPEG의 EXIF(APP1) 세그먼트 안 TIFF 데이터
+fn find_exif_tiff(data: &[u8]) -> Option<&[u8]> {
+    if data.len() < 4 || data[0..2] != [0xFF, 0xD8] {
+        return None;
+    }
+    let mut offset = 2;
+    while offset + 4 <= data.len() && data[offset] == 0xFF {
+        let marker = data[offset + 1];
+        // 이미지 데이터 시작(SOS) 뒤에는 EXIF가 없음
+        if marker == 0xDA {
+            return None;
+        }
+        let length = u16::from_be_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let segment = data.get(offset + 4..offset + 2 + length)?;
+        if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+            return Some(&segment[6..]);
+        }
+        offset += 2 + length;
+    }
+    None
+}
+
+/// EXIF TIFF 구조 (IFD 항목 12바이트: 태그, 형식, 개수, 값 또는 값 위치)
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(0..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        Some(Self { data, little_endian })
+    }
+
+    fn u16_at(&self, at: usize) -> Option<u16> {
+        let bytes = [*self.data.get(at)?, *self.data.get(at + 1)?];
+        Some(if self.little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    }
+
+    fn u32_at(&self, at: usize) -> Option<u32> {
+        let bytes = [*self.data.get(at)?, *self.data.get(at + 1)?, *self.data.get(at + 2)?, *self.data.get(at + 3)?];
+        Some(if self.little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    fn first_ifd(&self) -> Option<usize> {
+        Some(self.u32_at(4)? as usize)
+    }
+
+    /// IFD에서 태그 항목 위치
+    fn entry(&self, ifd: usize, tag: u16) -> Option<usize> {
+        let entries = self.u16_at(ifd)? as usize;
+        (0..entries)
+            .map(|index| ifd + 2 + index * 12)
+            .find(|entry| self.u16_at(*entry) == Some(tag))
+    }
+
+    /// 하위 IFD(Exif 0x8769, GPS 0x8825) 위치
+    fn pointer(&self, ifd: usize, tag: u16) -> Option<usize> {
+        Some(self.u32_at(self.entry(ifd, tag)? + 8)? as usize)
+    }
+
+    /// 값이 4바이트보다 크면 항목에는 값 위치가 들어 있음
+    fn value_at(&self, entry: usize, size: usize) -> Option<usize> {
+        if size <= 4 {
+            Some(entry + 8)
+        } else {
+            Some(self.u32_at(entry + 8)? as usize)
+        }
+    }
+
+    fn ascii(&self, ifd: usize, tag: u16) -> Option<&'a str> {
+        let entry = self.entry(ifd, tag)?;
+        let count = self.u32_at(entry + 4)? as usize;
+        let at = self.value_at(entry, count)?;
+        let value = std::str::from_utf8(self.data.get(at..at.checked_add(count)?)?).ok()?;
+        Some(value.trim_end_matches('\0').trim())
+    }
+
+    fn rational(&self, at: usize) -> Option<f64> {
+        let (numerator, denominator) = (self.u32_at(at)?, self.u32_at(at + 4)?);
+        (denominator != 0).then(|| numerator as f64 / denominator as f64)
+    }
+
+    /// 도/분/초(RATIONAL 3개)와 방향(N/S, E/W)을 부호 있는 소수 좌표로, 범위를 벗어나면 None
+    fn gps_coordinate(&self, gps: usize, ref_tag: u16, value_tag: u16, limit: f64) -> Option<f64> {
+        let entry = self.entry(gps, value_tag)?;
+        if self.u32_at(entry + 4)? < 3 {
+            return None;
+        }
+        let at = self.value_at(entry, 24)?;
+        let degrees = self.rational(at)? + self.rational(at + 8)? / 60.0 + self.rational(at + 16)? / 3600.0;
+        let coordinate = match self.ascii(gps, ref_tag)? {
+            "S" | "W" => -degrees,
+            _ => degrees,
+        };
+        (coordinate.abs() <= limit).then_some(coordinate)
+    }
+}
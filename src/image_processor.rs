@@ -1,15 +1,76 @@
-use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+use image::{DynamicImage, GenericImageView, ImageFormat, Rgba, RgbaImage};
 use image::imageops::{resize, FilterType};
 use imageproc::drawing::draw_filled_circle;
+use std::io::Cursor;
 use std::path::Path;
 use anyhow::Result;
 use webp::{Encoder, WebPMemory};
 use log::info;
+use ravif::{Img, RGBA8};
+use serde::{Deserialize, Serialize};
 
+use crate::image_pipeline::{run_pipeline, ImageHooks, ImageSource, PipelineOutput};
+
+#[derive(Clone)]
 pub struct ImageProcessor {
     pub max_width: u32,
     pub max_height: u32,
     pub quality: u8,
+    hooks: ImageHooks,
+    output_format: OutputFormat,
+    avif_quality: u8,
+    lossless_png: bool,
+}
+
+/// 변환 결과 형식 (업로드 ?output_format=webp|avif)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Webp,
+    Avif, // 사진은 같은 화질에 WebP보다 작지만 인코딩이 느림
+}
+
+impl OutputFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Webp => "webp",
+            OutputFormat::Avif => "avif",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Webp => "image/webp",
+            OutputFormat::Avif => "image/avif",
+        }
+    }
+}
+
+/// AVIF 인코딩 속도 (1 느림/작음 ~ 10 빠름/큼), 업로드 응답 시간을 위해 빠른 쪽
+const AVIF_SPEED: u8 = 8;
+
+/// 대표 이미지 자동 선정에 쓰이는 이미지 품질 정보
+#[derive(Debug, Clone, Copy)]
+pub struct ImageQuality {
+    pub width: u32,
+    pub height: u32,
+    pub sharpness: f64, // 라플라시안 분산 (클수록 선명)
+}
+
+/// 원형 썸네일 흰색 테두리 두께, 그림자 오프셋(오른쪽 아래), 그림자 불투명도
+const CIRCLE_BORDER_WIDTH: u32 = 4;
+const CIRCLE_SHADOW_OFFSET: u32 = 3;
+const CIRCLE_SHADOW_OPACITY: f32 = 80.0 / 255.0;
+
+/// 반응형 이미지 너비 (srcset용, 원본보다 큰 너비는 만들지 않음)
+pub const RESPONSIVE_WIDTHS: &[u32] = &[256, 800, 1600];
+
+/// 너비별로 다시 인코딩한 WebP 이미지
+pub struct ResponsiveVariant {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
 }
 
 impl ImageProcessor {
@@ -18,76 +79,207 @@ impl ImageProcessor {
             max_width,
             max_height,
             quality,
+            hooks: ImageHooks::default(),
+            output_format: OutputFormat::Webp,
+            avif_quality: quality,
+            lossless_png: false,
         }
     }
 
+    /// 결과 형식 지정 (AVIF는 avif_quality로 인코딩, WebP는 기존과 같음)
+    pub fn with_output_format(mut self, output_format: OutputFormat, avif_quality: u8) -> Self {
+        self.output_format = output_format;
+        self.avif_quality = avif_quality.clamp(1, 100);
+        self
+    }
+
+    /// 인코딩 품질 변경 (1~100, 형식별 설정값으로 만든 프로세서의 품질을 다른 프로세서에 맞출 때)
+    pub fn with_quality(mut self, quality: u8) -> Self {
+        self.quality = quality.clamp(1, 100);
+        self
+    }
+
+    /// PNG 원본을 무손실 WebP로 인코딩 (스크린샷, 그림처럼 손실 압축에서 번지는 이미지, AVIF는 그대로 손실 압축)
+    pub fn with_lossless_png(mut self, lossless_png: bool) -> Self {
+        self.lossless_png = lossless_png;
+        self
+    }
+
+    pub fn lossless_png(&self) -> bool {
+        self.lossless_png
+    }
+
+    pub fn output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+
+    /// 파이프라인 플러그인 연결 (워터마크, blurhash 등, 등록된 것이 없으면 기본 처리만)
+    pub fn with_hooks(mut self, hooks: &ImageHooks) -> Self {
+        self.hooks = hooks.clone();
+        self
+    }
+
+    pub fn hooks(&self) -> &ImageHooks {
+        &self.hooks
+    }
+
+    /// 블로킹 스레드에서 이 프로세서로 처리 (동시 처리 수와 제한 시간은 hooks의 ImageWorkers)
+    pub async fn run_blocking<T, F>(&self, job: F) -> Result<T>
+    where
+        F: FnOnce(&ImageProcessor) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let processor = self.clone();
+        self.hooks.workers().run(move || job(&processor)).await
+    }
+
     pub fn process_image(&self, image_data: &[u8]) -> Result<Vec<u8>> {
-        // 파일 크기 확인
-        let file_size_mb = self.get_file_size_mb(image_data);
-        info!("🖼️ 이미지 처리 시작: {:.2}MB", file_size_mb);
-        
+        Ok(self.process_image_with_quality(image_data)?.0)
+    }
 
-        
-        // 이미지 디코딩
-        let img = image::load_from_memory(image_data)?;
-        let (width, height) = img.dimensions();
-        
-        // 이미 적절한 크기인 경우 WebP 변환만 수행
-        if width <= self.max_width && height <= self.max_height {
-            info!("📏 이미지 크기 적절함 - WebP 변환만 수행");
-            let rgba = img.to_rgba8();
-            let encoder = Encoder::from_rgba(&rgba, rgba.width(), rgba.height());
-            let webp_data: WebPMemory = encoder.encode(80.0);
-            return Ok(webp_data.to_vec());
+    /// 이미지 처리와 함께 품질 정보(해상도, 선명도)를 계산 (디코딩 1회)
+    pub fn process_image_with_quality(&self, image_data: &[u8]) -> Result<(Vec<u8>, ImageQuality)> {
+        let output = self.run_pipeline(image_data)?;
+        Ok((output.data, output.quality))
+    }
+
+    /// 업로드 임시 파일을 바로 디코딩해 처리 (원본 바이트를 메모리에 모으지 않음)
+    pub fn process_source(&self, source: ImageSource<'_>) -> Result<Vec<u8>> {
+        Ok(self.process_source_with_quality(source)?.0)
+    }
+
+    pub fn process_source_with_quality(&self, source: ImageSource<'_>) -> Result<(Vec<u8>, ImageQuality)> {
+        let output = self.run_source_pipeline(source)?;
+        Ok((output.data, output.quality))
+    }
+
+    /// 너비별 WebP 생성 (같은 파이프라인/플러그인 사용, 높이는 비율대로)
+    /// 원본 너비보다 큰 너비는 확대하지 않고 건너뜀
+    pub fn process_responsive_variants(&self, source: ImageSource<'_>, widths: &[u32]) -> Result<Vec<ResponsiveVariant>> {
+        let source_width = match source {
+            ImageSource::Memory(data) => image::io::Reader::new(Cursor::new(data)).with_guessed_format()?.into_dimensions()?.0,
+            ImageSource::File(path) => self.get_file_image_info(path)?.0,
+        };
+        let mut variants = Vec::new();
+        for &width in widths.iter().filter(|&&width| width <= source_width) {
+            let processor = ImageProcessor {
+                max_width: width,
+                max_height: u32::MAX,
+                quality: self.quality,
+                hooks: self.hooks.clone(),
+                output_format: self.output_format,
+                avif_quality: self.avif_quality,
+                lossless_png: self.lossless_png,
+            };
+            let output = run_pipeline(&processor, &self.hooks, source)?;
+            let (variant_width, height) = output.dimensions;
+            info!("📏 반응형 이미지 {}w: {}x{}, {:.2}MB", width, variant_width, height, output.data.len() as f64 / (1024.0 * 1024.0));
+            variants.push(ResponsiveVariant { width, height, data: output.data });
         }
+        Ok(variants)
+    }
+
+    /// 디코딩 → 방향 보정 → 리사이즈 → 필터 → 인코딩 (플러그인 결과와 단계별 시간 포함)
+    pub fn run_pipeline(&self, image_data: &[u8]) -> Result<PipelineOutput> {
+        self.run_source_pipeline(ImageSource::Memory(image_data))
+    }
+
+    pub fn run_source_pipeline(&self, source: ImageSource<'_>) -> Result<PipelineOutput> {
+        let file_size_mb = match source {
+            ImageSource::Memory(data) => self.get_file_size_mb(data),
+            ImageSource::File(path) => std::fs::metadata(path)?.len() as f64 / (1024.0 * 1024.0),
+        };
+        info!("🖼️ 이미지 처리 시작: {:.2}MB", file_size_mb);
         
-        // 리사이즈가 필요한 경우
-        info!("📐 이미지 리사이즈 필요: {}x{} -> {}x{}", width, height, self.max_width, self.max_height);
-        let resized = self.resize_image(img);
+        let output = run_pipeline(self, &self.hooks, source)?;
+        info!("🔎 이미지 품질: {}x{}, 선명도 {:.2}", output.quality.width, output.quality.height, output.quality.sharpness);
         
-        // WebP로 인코딩
-        let rgba = resized.to_rgba8();
+        let processed_size_mb = output.data.len() as f64 / (1024.0 * 1024.0);
+        info!("✅ 이미지 처리 완료: {:.2}MB -> {:.2}MB", file_size_mb, processed_size_mb);
+        Ok(output)
+    }
+
+    /// 파이프라인 인코딩 단계: 지정한 형식(WebP 기본, AVIF)으로 변환
+    pub(crate) fn encode(&self, img: &DynamicImage, source_format: Option<ImageFormat>) -> Result<Vec<u8>> {
+        match self.output_format {
+            OutputFormat::Webp => Ok(self.encode_webp(img, self.lossless_png && source_format == Some(ImageFormat::Png))),
+            OutputFormat::Avif => self.encode_avif(img),
+        }
+    }
+
+    fn encode_avif(&self, img: &DynamicImage) -> Result<Vec<u8>> {
+        let rgba = img.to_rgba8();
+        let pixels: Vec<RGBA8> = rgba.pixels().map(|pixel| RGBA8::new(pixel[0], pixel[1], pixel[2], pixel[3])).collect();
+        let encoded = ravif::Encoder::new()
+            .with_quality(self.avif_quality as f32)
+            .with_speed(AVIF_SPEED)
+            .encode_rgba(Img::new(pixels.as_slice(), rgba.width() as usize, rgba.height() as usize))?;
+        Ok(encoded.avif_file)
+    }
+
+    fn encode_webp(&self, img: &DynamicImage, lossless: bool) -> Vec<u8> {
+        let rgba = img.to_rgba8();
         let encoder = Encoder::from_rgba(&rgba, rgba.width(), rgba.height());
-        let webp_data: WebPMemory = encoder.encode(80.0);
+        let webp_data: WebPMemory = if lossless {
+            encoder.encode_lossless()
+        } else {
+            encoder.encode(self.quality as f32)
+        };
+        webp_data.to_vec()
+    }
+
+    /// 라플라시안 분산으로 선명도 계산 (흐린 이미지일수록 값이 작음)
+    /// 큰 이미지는 512px 이하로 축소 후 계산해서 해상도와 무관하게 비교 가능하도록 함
+    pub fn compute_sharpness(&self, img: &DynamicImage) -> f64 {
+        let gray = img.thumbnail(512, 512).to_luma8();
+        let (w, h) = gray.dimensions();
+        if w < 3 || h < 3 {
+            return 0.0;
+        }
         
-        let processed_size_mb = webp_data.len() as f64 / (1024.0 * 1024.0);
-        info!("✅ 이미지 처리 완료: {:.2}MB -> {:.2}MB", file_size_mb, processed_size_mb);
+        let mut sum = 0.0f64;
+        let mut sum_sq = 0.0f64;
+        let mut count = 0.0f64;
+        for y in 1..h - 1 {
+            for x in 1..w - 1 {
+                let center = gray.get_pixel(x, y)[0] as f64;
+                let laplacian = 4.0 * center
+                    - gray.get_pixel(x - 1, y)[0] as f64
+                    - gray.get_pixel(x + 1, y)[0] as f64
+                    - gray.get_pixel(x, y - 1)[0] as f64
+                    - gray.get_pixel(x, y + 1)[0] as f64;
+                sum += laplacian;
+                sum_sq += laplacian * laplacian;
+                count += 1.0;
+            }
+        }
         
-        Ok(webp_data.to_vec())
+        let mean = sum / count;
+        sum_sq / count - mean * mean
     }
 
     pub fn process_circular_thumbnail(&self, image_data: &[u8]) -> Result<Vec<u8>> {
         let file_size_mb = self.get_file_size_mb(image_data);
         info!("🔄 원형 썸네일 처리 시작: {:.2}MB", file_size_mb);
         
-        // 이미지 디코딩
-        let img = image::load_from_memory(image_data)?;
-        let (width, height) = img.dimensions();
+        // 이미지 디코딩 후 정사각형으로 크롭
+        let square = self.crop_to_square(image::load_from_memory(image_data)?);
         
-        // 원형 썸네일은 최대 500x500으로 제한 (S3 업로드 안정성)
-        let max_circular_size = 500u32;
-        let processed_img = if width > max_circular_size || height > max_circular_size || file_size_mb > 5.0 {
-            info!("📐 원형 썸네일 크기 제한 - 리사이즈: {}x{} -> {}x{}", width, height, max_circular_size, max_circular_size);
-            
-            // 비율을 유지하면서 최대 크기로 리사이즈
-            let ratio = (max_circular_size as f32 / width as f32).min(max_circular_size as f32 / height as f32);
-            let new_width = (width as f32 * ratio) as u32;
-            let new_height = (height as f32 * ratio) as u32;
-            
-            img.resize(new_width, new_height, image::imageops::FilterType::Nearest)
+        // 지름은 최대 max_width/max_height (작은 이미지는 키우지 않음)
+        // 축소는 영역 평균(thumbnail)이라 큰 사진도 빠르고 계단 현상이 없음
+        let diameter = square.width().min(self.max_width).min(self.max_height).max(1);
+        let square = if square.width() > diameter {
+            info!("📐 원형 썸네일 리사이즈: {}x{} -> {}x{}", square.width(), square.height(), diameter, diameter);
+            square.thumbnail_exact(diameter, diameter)
         } else {
-            img
+            square
         };
         
-        // 정사각형으로 크롭
-        let cropped = self.crop_to_square(processed_img);
-        
         // 원형으로 마스킹하고 흰색 테두리 추가
-        let circular = self.make_circular_with_border(cropped);
+        let circular = make_circular_with_border(&square.to_rgba8());
         
         // WebP로 인코딩
-        let rgba = circular.to_rgba8();
-        let encoder = Encoder::from_rgba(&rgba, rgba.width(), rgba.height());
+        let encoder = Encoder::from_rgba(&circular, circular.width(), circular.height());
         let webp_data: WebPMemory = encoder.encode(self.quality as f32);
         
         let processed_size_mb = webp_data.len() as f64 / (1024.0 * 1024.0);
@@ -96,7 +288,8 @@ impl ImageProcessor {
         Ok(webp_data.to_vec())
     }
 
-    fn resize_image(&self, img: DynamicImage) -> DynamicImage {
+    /// 파이프라인 리사이즈 단계: 최대 크기 안으로 비율 유지 축소
+    pub(crate) fn resize_image(&self, img: DynamicImage) -> DynamicImage {
         let (width, height) = img.dimensions();
         
         // 이미지가 최대 크기보다 작으면 리사이즈하지 않음
@@ -134,68 +327,6 @@ impl ImageProcessor {
         img.crop_imm(x, y, size, size)
     }
 
-    fn make_circular_with_border(&self, img: DynamicImage) -> DynamicImage {
-        let size = img.width().min(img.height());
-        let border_width = 4u32; // 흰색 테두리 두께
-        let shadow_offset = 3u32; // 그림자 오프셋
-        let total_size = size + (border_width * 2) + shadow_offset;
-        
-        // 투명 배경의 새 이미지 생성
-        let mut output = RgbaImage::new(total_size, total_size);
-        
-        // 투명으로 채우기
-        for pixel in output.pixels_mut() {
-            *pixel = Rgba([0, 0, 0, 0]);
-        }
-        
-        // 그림자 그리기 (약간 아래쪽으로 오프셋)
-        let shadow_center = (total_size / 2) + shadow_offset;
-        let shadow_radius = (size / 2) + border_width;
-        
-        for y in 0..total_size {
-            for x in 0..total_size {
-                let dx = if x > shadow_center { x - shadow_center } else { shadow_center - x };
-                let dy = if y > shadow_center { y - shadow_center } else { shadow_center - y };
-                let distance_squared = dx * dx + dy * dy;
-                
-                if distance_squared <= shadow_radius * shadow_radius {
-                    // 그림자 영역 (반투명 검은색)
-                    output.put_pixel(x, y, Rgba([0, 0, 0, 80]));
-                }
-            }
-        }
-        
-        // 원형 이미지와 테두리 그리기
-        let center = total_size / 2;
-        let radius = size / 2;
-        
-        for y in 0..total_size {
-            for x in 0..total_size {
-                let dx = if x > center { x - center } else { center - x };
-                let dy = if y > center { y - center } else { center - y };
-                let distance_squared = dx * dx + dy * dy;
-                
-                if distance_squared <= (radius + border_width) * (radius + border_width) {
-                    if distance_squared <= radius * radius {
-                        // 원형 이미지 영역
-                        let src_x = (x as i32 - center as i32) + (size / 2) as i32;
-                        let src_y = (y as i32 - center as i32) + (size / 2) as i32;
-                        
-                        if src_x >= 0 && src_x < size as i32 && src_y >= 0 && src_y < size as i32 {
-                            let pixel = img.get_pixel(src_x as u32, src_y as u32);
-                            output.put_pixel(x, y, pixel);
-                        }
-                    } else {
-                        // 흰색 테두리 영역
-                        output.put_pixel(x, y, Rgba([255, 255, 255, 255]));
-                    }
-                }
-            }
-        }
-        
-        DynamicImage::ImageRgba8(output)
-    }
-
     pub fn get_image_info(&self, image_data: &[u8]) -> Result<(u32, u32, String)> {
         let img = image::load_from_memory(image_data)?;
         let (width, height) = img.as_rgba8().map_or((0, 0), |rgba| rgba.dimensions());
@@ -216,6 +347,14 @@ impl ImageProcessor {
         Ok((width, height, format.to_string()))
     }
 
+    /// 파일 헤더만 읽어 해상도/형식 확인 (전체를 디코딩하지 않음)
+    pub fn get_file_image_info(&self, path: &Path) -> Result<(u32, u32, String)> {
+        let reader = image::io::Reader::open(path)?.with_guessed_format()?;
+        let format = reader.format().map_or("Unknown".to_string(), |format| format!("{:?}", format).to_uppercase());
+        let (width, height) = reader.into_dimensions()?;
+        Ok((width, height, format))
+    }
+
     pub fn is_valid_image_format(&self, filename: &str) -> bool {
         let ext = Path::new(filename)
             .extension()
@@ -226,6 +365,22 @@ impl ImageProcessor {
         matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp")
     }
 
+    /// 파일 앞부분(매직 바이트)으로 실제 형식 확인: 지원하는 이미지가 아니거나 확장자와 다르면 사유 반환
+    /// (확장자만 바꾼 실행 파일 등이 디코더까지 가지 않도록 처리 전에 확인)
+    pub fn check_image_content(&self, filename: &str, head: &[u8]) -> std::result::Result<ImageFormat, String> {
+        let format = match image::guess_format(head) {
+            Ok(format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::Gif | ImageFormat::Bmp | ImageFormat::WebP)) => format,
+            Ok(format) => return Err(format!("지원하지 않는 이미지 형식입니다 ({:?})", format)),
+            Err(_) => return Err("이미지 파일이 아닙니다".to_string()),
+        };
+        if let Some(ext) = Path::new(filename).extension().and_then(|ext| ext.to_str())
+            && ImageFormat::from_extension(ext) != Some(format)
+        {
+            return Err(format!("확장자(.{})와 파일 내용({:?})이 다릅니다", ext, format));
+        }
+        Ok(format)
+    }
+
     pub fn get_file_size_mb(&self, data: &[u8]) -> f64 {
         data.len() as f64 / (1024.0 * 1024.0)
     }
@@ -238,4 +393,80 @@ pub fn create_thumbnail_processor() -> ImageProcessor {
 
 pub fn create_map_processor() -> ImageProcessor {
     ImageProcessor::new(800, 600, 85)
-} 
\ No newline at end of file
+} 
+
+/// 정사각형 이미지를 원형으로 잘라 흰색 테두리와 그림자를 붙임
+/// 원 안쪽에 완전히 들어가는 구간은 행 단위로 그대로 복사하고, 가장자리 픽셀만 원이 덮는 비율로 섞음 (안티에일리어싱)
+fn make_circular_with_border(square: &RgbaImage) -> RgbaImage {
+    let size = square.width();
+    let total_size = size + CIRCLE_BORDER_WIDTH * 2 + CIRCLE_SHADOW_OFFSET;
+    let mut output = RgbaImage::new(total_size, total_size);
+    
+    let radius = size as f32 / 2.0;
+    let outer_radius = radius + CIRCLE_BORDER_WIDTH as f32;
+    let center = outer_radius;
+    let shadow_center = center + CIRCLE_SHADOW_OFFSET as f32;
+    let offset = CIRCLE_BORDER_WIDTH as usize;
+    let source = square.as_raw();
+    let source_row = size as usize * 4;
+    
+    for (y, row) in output.chunks_exact_mut(total_size as usize * 4).enumerate() {
+        let dy = y as f32 + 0.5 - center;
+        let shadow_dy = y as f32 + 0.5 - shadow_center;
+        let source_y = y.saturating_sub(offset).min(size as usize - 1);
+        let source_pixels = &source[source_y * source_row..(source_y + 1) * source_row];
+        
+        // 픽셀 중심이 반지름 - 0.5 안에 있으면 원본 그대로
+        let inside = (radius - 0.5).max(0.0).powi(2) - dy * dy;
+        let (inside_start, inside_end) = if inside > 0.0 {
+            let half = inside.sqrt();
+            let start = ((center - half - 0.5).ceil() as usize).max(offset);
+            let end = (((center + half - 0.5).floor() as usize) + 1).min(offset + size as usize);
+            (start, end.max(start))
+        } else {
+            (0, 0)
+        };
+        if inside_end > inside_start {
+            row[inside_start * 4..inside_end * 4].copy_from_slice(&source_pixels[(inside_start - offset) * 4..(inside_end - offset) * 4]);
+        }
+        
+        for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+            if (inside_start..inside_end).contains(&x) {
+                continue;
+            }
+            let dx = x as f32 + 0.5 - center;
+            let distance = (dx * dx + dy * dy).sqrt();
+            let shadow_dx = x as f32 + 0.5 - shadow_center;
+            let shadow = circle_coverage((shadow_dx * shadow_dx + shadow_dy * shadow_dy).sqrt(), outer_radius);
+            let outer = circle_coverage(distance, outer_radius);
+            if outer == 0.0 && shadow == 0.0 {
+                continue;
+            }
+            let inner = circle_coverage(distance, radius);
+            let image_pixel = match x.checked_sub(offset) {
+                Some(source_x) if inner > 0.0 => &source_pixels[source_x.min(size as usize - 1) * 4..][..4],
+                _ => &[0, 0, 0, 0][..],
+            };
+            
+            // 이미지(inner)와 테두리(outer - inner)는 겹치지 않으므로 더하고, 그림자는 원이 덮지 않은 부분에만 보임
+            let image_alpha = inner * f32::from(image_pixel[3]) / 255.0;
+            let border_alpha = outer - inner;
+            let alpha = image_alpha + border_alpha + CIRCLE_SHADOW_OPACITY * shadow * (1.0 - outer);
+            if alpha <= 0.0 {
+                continue;
+            }
+            for channel in 0..3 {
+                let color = f32::from(image_pixel[channel]) * image_alpha + 255.0 * border_alpha;
+                pixel[channel] = (color / alpha).round().min(255.0) as u8;
+            }
+            pixel[3] = (alpha * 255.0).round().min(255.0) as u8;
+        }
+    }
+    
+    output
+}
+
+/// 픽셀 중심이 원 중심에서 distance만큼 떨어져 있을 때 원이 픽셀을 덮는 비율 (경계 1px에서 0~1)
+fn circle_coverage(distance: f32, radius: f32) -> f32 {
+    (radius + 0.5 - distance).clamp(0.0, 1.0)
+}
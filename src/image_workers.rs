@@ -0,0 +1,83 @@
+// CPU를 많이 쓰는 이미지 처리(디코딩, 리사이즈, 인코딩)를 async 실행기 밖의 블로킹 스레드에서 실행
+// 동시에 처리하는 이미지 수를 IMAGE_PROCESSING_CONCURRENCY로 제한하고, 자리를 기다리는 시간까지 포함해
+// IMAGE_PROCESSING_TIMEOUT_SECS 안에 끝나지 않으면 요청은 포기 (이미 시작한 처리는 끝까지 자리를 차지함)
+use anyhow::Result;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+use crate::config::Config;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// 처리 시간 초과 (업로드는 503으로 응답)
+#[derive(Debug)]
+pub struct ImageProcessingTimeout(pub Duration);
+
+impl fmt::Display for ImageProcessingTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "이미지 처리 시간 초과 ({:.1}초)", self.0.as_secs_f64())
+    }
+}
+
+impl std::error::Error for ImageProcessingTimeout {}
+
+/// 이미지 처리용 블로킹 작업 자리 (ImageHooks에 담겨 모든 요청이 공유)
+#[derive(Clone)]
+pub struct ImageWorkers {
+    permits: Arc<Semaphore>,
+    concurrency: usize,
+    timeout: Duration,
+}
+
+impl Default for ImageWorkers {
+    fn default() -> Self {
+        Self::new(default_concurrency(), Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+    }
+}
+
+impl ImageWorkers {
+    pub fn new(concurrency: usize, timeout: Duration) -> Self {
+        let concurrency = concurrency.max(1);
+        Self {
+            permits: Arc::new(Semaphore::new(concurrency)),
+            concurrency,
+            timeout,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.image_processing_concurrency, Duration::from_secs(config.image_processing_timeout_secs))
+    }
+
+    /// 지금 처리 중인 이미지 수
+    pub fn in_flight(&self) -> usize {
+        self.concurrency - self.permits.available_permits()
+    }
+
+    /// 자리가 나면 블로킹 스레드에서 실행 (시간 초과는 ImageProcessingTimeout 오류)
+    pub async fn run<T, F>(&self, job: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let permits = self.permits.clone();
+        let work = async move {
+            let permit = permits.acquire_owned().await?;
+            tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                job()
+            }).await?
+        };
+        match tokio::time::timeout(self.timeout, work).await {
+            Ok(result) => result,
+            Err(_) => Err(ImageProcessingTimeout(self.timeout).into()),
+        }
+    }
+}
+
+/// 기본 동시 처리 수 (CPU 코어 수)
+pub fn default_concurrency() -> usize {
+    std::thread::available_parallelism().map_or(4, |cores| cores.get())
+}
@@ -0,0 +1,83 @@
+// 콘텐츠 언어 감지 및 선호 언어 처리
+
+pub const SUPPORTED_LANGUAGES: [&str; 4] = ["ko", "ja", "zh", "en"];
+
+pub fn is_supported_language(code: &str) -> bool {
+    SUPPORTED_LANGUAGES.contains(&code)
+}
+
+/// 텍스트의 문자 체계(스크립트)를 기준으로 언어 코드 추정
+/// 판별할 수 있는 글자가 없으면 None (이모지/숫자만 있는 경우 등)
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let mut hangul = 0usize;
+    let mut kana = 0usize;
+    let mut han = 0usize;
+    let mut latin = 0usize;
+
+    for ch in text.chars() {
+        match ch as u32 {
+            0xAC00..=0xD7A3 | 0x1100..=0x11FF | 0x3130..=0x318F => hangul += 1,
+            0x3040..=0x30FF => kana += 1,
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => han += 1,
+            _ if ch.is_ascii_alphabetic() => latin += 1,
+            _ => {}
+        }
+    }
+
+    // 한글/가나가 조금이라도 섞여 있으면 한자보다 우선 (한국어/일본어 문장에도 한자가 섞이기 때문)
+    if hangul > 0 && hangul >= kana {
+        Some("ko")
+    } else if kana > 0 {
+        Some("ja")
+    } else if han > 0 {
+        Some("zh")
+    } else if latin > 0 {
+        Some("en")
+    } else {
+        None
+    }
+}
+
+/// Accept-Language 헤더("ko-KR,ko;q=0.9,en;q=0.8")에서 지원 언어 중 가장 선호하는 것
+pub fn parse_accept_language(header: &str) -> Option<&'static str> {
+    let mut candidates: Vec<(f32, &str)> = header
+        .split(',')
+        .filter_map(|part| {
+            let mut pieces = part.split(';');
+            let tag = pieces.next()?.trim();
+            let quality = pieces
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse().ok())
+                .unwrap_or(1.0);
+            Some((quality, tag))
+        })
+        .filter(|(quality, _)| *quality > 0.0)
+        .collect();
+    // 같은 q값이면 헤더 순서 유지
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    candidates.into_iter().find_map(|(_, tag)| {
+        let primary = tag.split('-').next()?.to_lowercase();
+        SUPPORTED_LANGUAGES.iter().find(|code| **code == primary).copied()
+    })
+}
+
+/// 국가 코드(ISO 3166-1)의 기본 언어 (지원하지 않는 국가는 영어)
+pub fn language_for_country(country_code: &str) -> &'static str {
+    match country_code.to_uppercase().as_str() {
+        "KR" => "ko",
+        "JP" => "ja",
+        "CN" | "TW" | "HK" | "MO" => "zh",
+        _ => "en",
+    }
+}
+
+/// "ko,en" 형식의 언어 목록 파싱 (지원하지 않는 코드는 제외, 중복 제거)
+pub fn parse_language_list(raw: &str) -> Vec<String> {
+    let mut languages: Vec<String> = Vec::new();
+    for code in raw.split(',').map(|c| c.trim().to_lowercase()) {
+        if is_supported_language(&code) && !languages.contains(&code) {
+            languages.push(code);
+        }
+    }
+    languages
+}
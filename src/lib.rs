@@ -0,0 +1,211 @@
+// BigPicture 백엔드 라이브러리 (서버 바이너리와 통합 테스트가 같은 앱 구성을 사용)
+use actix_cors::Cors;
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceFactory, ServiceRequest, ServiceResponse},
+    middleware::from_fn,
+    web, App, Error,
+};
+
+pub mod image_processor;
+pub mod routes;
+pub mod database;
+pub mod config;
+pub mod s3_service;
+pub mod s3_routes;
+pub mod error_handler;
+pub mod emotions;
+pub mod language;
+pub mod auth;
+pub mod link_preview;
+pub mod google_auth;
+pub mod api_keys;
+pub mod memories;
+pub mod anonymize;
+pub mod clock;
+pub mod image_cleanup;
+pub mod upload_guard;
+pub mod upload_spool;
+pub mod schema_check;
+pub mod rate_limit;
+pub mod dto;
+pub mod totp;
+pub mod geoip;
+pub mod share_card;
+pub mod parquet;
+pub mod marker_export;
+pub mod presence;
+pub mod promotions;
+pub mod reverse_geocode;
+pub mod crosspost;
+pub mod embed;
+pub mod hashtags;
+pub mod emotion_profile;
+pub mod trust;
+pub mod cache_policy;
+pub mod request_id;
+pub mod route_usage;
+pub mod push;
+pub mod image_pipeline;
+pub mod image_workers;
+pub mod resumable_upload;
+pub mod blurhash;
+pub mod moderation;
+pub mod watermark;
+pub mod marker_events;
+pub mod location_suggest;
+pub mod cluster_zoom;
+pub mod emotion_tiles;
+pub mod cluster_cache;
+pub mod search_index;
+pub mod public_id;
+pub mod scheduled_markers;
+pub mod geojson;
+pub mod marker_views;
+pub mod zip;
+pub mod member_export;
+pub mod idempotency;
+pub mod storage;
+pub mod orphan_gc;
+
+use std::sync::Arc;
+
+use clock::{Clock, IdGenerator, RandomIdGenerator, SystemClock};
+use config::Config;
+use database::Database;
+use s3_service::S3Service;
+use storage::{ObjectKeys, Storage};
+use upload_guard::UploadLimiter;
+use rate_limit::AuthRateLimiter;
+use geoip::GeoIp;
+use share_card::CardFonts;
+use presence::MarkerPresence;
+use crosspost::{CrossPostProviders, TokenCipher};
+use emotion_profile::EmotionProfileCache;
+use route_usage::RouteUsage;
+use image_pipeline::ImageHooks;
+use image_workers::ImageWorkers;
+use resumable_upload::UploadSessions;
+use moderation::Moderation;
+use watermark::Watermark;
+use marker_events::MarkerEvents;
+use location_suggest::Places;
+use cluster_cache::ClusterCache;
+use search_index::SearchIndex;
+use marker_views::MarkerViews;
+
+/// 앱이 공유하는 상태 (운영에서는 실제 DB/S3, 테스트에서는 임시 DB나 가짜 저장소를 넣음)
+#[derive(Clone)]
+pub struct AppState {
+    pub database: Database,
+    pub config: Config,
+    pub s3_service: S3Service,
+    pub storage: Arc<dyn Storage>, // 업로드/내보내기 파일 저장소 (STORAGE_BACKEND)
+    pub clock: Arc<dyn Clock>,
+    pub ids: Arc<dyn IdGenerator>,
+    pub upload_limiter: UploadLimiter,
+    pub auth_rate_limiter: AuthRateLimiter,
+    pub geoip: GeoIp,
+    pub card_fonts: CardFonts,
+    pub presence: MarkerPresence,
+    pub crosspost_providers: CrossPostProviders,
+    pub token_cipher: TokenCipher,
+    pub emotion_profiles: EmotionProfileCache,
+    pub route_usage: RouteUsage,
+    pub image_hooks: ImageHooks,
+    pub marker_events: MarkerEvents,
+    pub places: Places,
+    pub cluster_cache: ClusterCache,
+    pub search_index: SearchIndex,
+    pub marker_views: MarkerViews,
+    pub watermark: Watermark,
+    pub upload_sessions: UploadSessions,
+}
+
+impl AppState {
+    /// 시스템 시각과 무작위 UUID를 쓰는 기본 상태
+    pub fn new(database: Database, config: Config, s3_service: S3Service) -> Self {
+        Self {
+            upload_limiter: UploadLimiter::new(&config),
+            auth_rate_limiter: AuthRateLimiter::new(&config),
+            geoip: GeoIp::open(&config.geoip_database_path),
+            card_fonts: CardFonts::load(&config.share_card_font_path, &config.share_card_emoji_font_path),
+            presence: MarkerPresence::new(&config),
+            crosspost_providers: CrossPostProviders::default(),
+            token_cipher: TokenCipher::from_config(&config),
+            emotion_profiles: EmotionProfileCache::new(&config),
+            route_usage: RouteUsage::default(),
+            image_hooks: ImageHooks::default().with_moderation(Moderation::from_config(&config, &s3_service))
+                .with_workers(ImageWorkers::from_config(&config)),
+            marker_events: MarkerEvents::default(),
+            places: Places::from_config(&config),
+            cluster_cache: ClusterCache::new(&config),
+            search_index: SearchIndex::from_config(&config),
+            marker_views: MarkerViews::new(&config),
+            watermark: Watermark::from_config(&config),
+            upload_sessions: UploadSessions::new(&config),
+            storage: storage::from_config(&config, &s3_service),
+            database,
+            config,
+            s3_service,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+        }
+    }
+}
+
+/// 미들웨어/라우트가 모두 구성된 actix App 생성
+pub fn build_app(
+    state: AppState,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = Error,
+        InitError = (),
+    >,
+> {
+    // CORS 설정 - 모든 origin 허용 (localhost, IP 주소, 도메인 모두)
+    let cors = Cors::default()
+        .allow_any_origin()
+        .allow_any_method()
+        .allow_any_header()
+        .expose_headers([request_id::REQUEST_ID_HEADER, idempotency::IDEMPOTENT_REPLAYED_HEADER])
+        .expose_headers(resumable_upload::TUS_EXPOSED_HEADERS)
+        .supports_credentials()
+        .max_age(3600);
+
+    App::new()
+        .wrap(from_fn(route_usage::record_route_usage))
+        .wrap(from_fn(cache_policy::apply_cache_policy))
+        .wrap(from_fn(auth::jwt_auth))
+        .wrap(cors)
+        .wrap(from_fn(request_id::assign_request_id))
+        .app_data(web::Data::new(state.database.pool.clone()))
+        .app_data(web::Data::new(state.database))
+        .app_data(web::Data::new(state.config))
+        .app_data(web::Data::new(state.s3_service.with_generators(state.clock.clone(), state.ids.clone())))
+        .app_data(web::Data::new(ObjectKeys::new(state.clock.clone(), state.ids.clone())))
+        .app_data(web::Data::from(state.storage))
+        .app_data(web::Data::from(state.clock))
+        .app_data(web::Data::from(state.ids))
+        .app_data(web::Data::new(state.upload_limiter))
+        .app_data(web::Data::new(state.auth_rate_limiter))
+        .app_data(web::Data::new(state.geoip))
+        .app_data(web::Data::new(state.card_fonts))
+        .app_data(web::Data::new(state.presence))
+        .app_data(web::Data::new(state.crosspost_providers))
+        .app_data(web::Data::new(state.token_cipher))
+        .app_data(web::Data::new(state.emotion_profiles))
+        .app_data(web::Data::new(state.route_usage))
+        .app_data(web::Data::new(state.image_hooks))
+        .app_data(web::Data::new(state.marker_events))
+        .app_data(web::Data::new(state.places))
+        .app_data(web::Data::new(state.cluster_cache))
+        .app_data(web::Data::new(state.search_index))
+        .app_data(web::Data::new(state.marker_views))
+        .app_data(web::Data::new(state.watermark))
+        .app_data(web::Data::new(state.upload_sessions))
+        .configure(routes::setup_routes)
+}
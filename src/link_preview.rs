@@ -0,0 +1,240 @@
+// 외부 웹 페이지 메타데이터(OG 태그) 추출 및 SSRF 방지 요청
+use anyhow::{anyhow, bail, Result};
+use log::info;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+const MAX_REDIRECTS: usize = 3;
+const MAX_PAGE_BYTES: usize = 2 * 1024 * 1024;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// 페이지에서 추출한 마커 초안용 메타데이터
+#[derive(Debug, Default)]
+pub struct PageMetadata {
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// 사설망/루프백/링크로컬 등 내부 주소 여부
+fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let octets = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_unspecified()
+                || v4.is_multicast()
+                || octets[0] == 0
+                || (octets[0] == 100 && (64..128).contains(&octets[1])) // CGNAT
+                || (octets[0] == 198 && (octets[1] == 18 || octets[1] == 19)))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ip(&IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00 // unique local
+                || (first & 0xffc0) == 0xfe80) // link local
+        }
+    }
+}
+
+/// http(s) URL만 허용하고, 호스트가 공인 IP로만 해석되는지 확인
+/// 검증한 주소로 연결을 고정해서 DNS 재바인딩을 막음
+async fn resolve_public_url(raw: &str) -> Result<(reqwest::Url, SocketAddr)> {
+    let url = reqwest::Url::parse(raw).map_err(|e| anyhow!("잘못된 URL: {}", e))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        bail!("http/https URL만 허용됩니다");
+    }
+    let host = url.host_str().ok_or_else(|| anyhow!("호스트가 없는 URL입니다"))?.to_string();
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.trim_matches(|c| c == '[' || c == ']'), port))
+        .await
+        .map_err(|e| anyhow!("호스트 조회 실패: {}", e))?
+        .collect();
+    if addrs.is_empty() {
+        bail!("호스트 주소를 찾을 수 없습니다");
+    }
+    if let Some(blocked) = addrs.iter().find(|addr| !is_public_ip(&addr.ip())) {
+        bail!("내부 네트워크 주소로의 요청은 허용되지 않습니다: {}", blocked.ip());
+    }
+    Ok((url, addrs[0]))
+}
+
+/// 리다이렉트마다 주소를 다시 검증하며 GET 요청, 본문은 max_bytes까지만 읽음
+async fn safe_get(raw_url: &str, max_bytes: usize) -> Result<(reqwest::Url, Option<String>, Vec<u8>)> {
+    let mut current = raw_url.to_string();
+    for _ in 0..=MAX_REDIRECTS {
+        let (url, addr) = resolve_public_url(&current).await?;
+        let host = url.host_str().unwrap_or_default().to_string();
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .resolve(&host, addr)
+            .user_agent("BigPictureBot/1.0")
+            .build()?;
+
+        let mut response = client.get(url.clone()).send().await?;
+        if response.status().is_redirection() {
+            let location = response.headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| anyhow!("리다이렉트 위치가 없습니다"))?;
+            current = url.join(location)?.to_string();
+            info!("↪️ 리다이렉트: {}", current);
+            continue;
+        }
+        if !response.status().is_success() {
+            bail!("요청 실패: HTTP {}", response.status());
+        }
+        if response.content_length().is_some_and(|len| len as usize > max_bytes) {
+            bail!("응답 크기가 제한({} bytes)을 초과합니다", max_bytes);
+        }
+
+        let content_type = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > max_bytes {
+                bail!("응답 크기가 제한({} bytes)을 초과합니다", max_bytes);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        return Ok((url, content_type, body));
+    }
+    bail!("리다이렉트가 너무 많습니다")
+}
+
+/// 외부 페이지를 가져와 OG/지오 메타데이터 추출
+pub async fn fetch_page_metadata(raw_url: &str) -> Result<PageMetadata> {
+    let (url, content_type, body) = safe_get(raw_url, MAX_PAGE_BYTES).await?;
+    if content_type.as_deref().is_some_and(|ct| !ct.contains("html")) {
+        bail!("HTML 페이지가 아닙니다: {}", content_type.unwrap_or_default());
+    }
+    let html = String::from_utf8_lossy(&body);
+    let metas = parse_meta_tags(&html);
+    let find = |keys: &[&str]| -> Option<String> {
+        keys.iter().find_map(|key| {
+            metas.iter()
+                .find(|(k, v)| k.eq_ignore_ascii_case(key) && !v.trim().is_empty())
+                .map(|(_, v)| v.trim().to_string())
+        })
+    };
+
+    let mut metadata = PageMetadata {
+        url: url.to_string(),
+        title: find(&["og:title", "twitter:title"]).or_else(|| parse_title(&html)),
+        description: find(&["og:description", "twitter:description", "description"]),
+        image_url: find(&["og:image", "og:image:url", "og:image:secure_url", "twitter:image"])
+            .and_then(|img| url.join(&img).ok())
+            .map(|img| img.to_string()),
+        ..Default::default()
+    };
+
+    // 좌표: place:location / og:latitude / geo.position("lat;lng") / ICBM("lat, lng")
+    let lat = find(&["place:location:latitude", "og:latitude", "og:location:latitude"]);
+    let lng = find(&["place:location:longitude", "og:longitude", "og:location:longitude"]);
+    let pair = match (lat, lng) {
+        (Some(lat), Some(lng)) => Some((lat, lng)),
+        _ => find(&["geo.position", "ICBM"]).and_then(|pos| {
+            let mut parts = pos.split([';', ',']).map(|p| p.trim().to_string());
+            Some((parts.next()?, parts.next()?))
+        }),
+    };
+    if let Some((lat, lng)) = pair
+        && let (Ok(lat), Ok(lng)) = (lat.parse::<f64>(), lng.parse::<f64>())
+        && (-90.0..=90.0).contains(&lat)
+        && (-180.0..=180.0).contains(&lng)
+    {
+        metadata.latitude = Some(lat);
+        metadata.longitude = Some(lng);
+    }
+
+    info!("🔗 페이지 메타데이터 추출: {} (제목: {:?}, 이미지: {:?}, 좌표: {:?}/{:?})",
+          metadata.url, metadata.title, metadata.image_url, metadata.latitude, metadata.longitude);
+    Ok(metadata)
+}
+
+/// 외부 이미지 다운로드 (페이지와 같은 SSRF 검증 적용)
+pub async fn fetch_image(raw_url: &str, max_bytes: usize) -> Result<Vec<u8>> {
+    let (_, content_type, body) = safe_get(raw_url, max_bytes).await?;
+    if content_type.as_deref().is_some_and(|ct| !ct.starts_with("image/")) {
+        bail!("이미지가 아닙니다: {}", content_type.unwrap_or_default());
+    }
+    Ok(body)
+}
+
+/// <meta property|name="..." content="..."> 목록 추출
+fn parse_meta_tags(html: &str) -> Vec<(String, String)> {
+    let lower = html.to_ascii_lowercase();
+    let mut metas = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = lower[pos..].find("<meta") {
+        let start = pos + start;
+        let end = match lower[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let tag = &html[start + 5..end];
+        let key = attribute(tag, "property").or_else(|| attribute(tag, "name"));
+        if let (Some(key), Some(content)) = (key, attribute(tag, "content")) {
+            metas.push((key, decode_entities(&content)));
+        }
+        pos = end;
+    }
+    metas
+}
+
+fn parse_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title")?;
+    let start = start + lower[start..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = decode_entities(html[start..end].trim());
+    if title.is_empty() { None } else { Some(title) }
+}
+
+/// 태그 문자열에서 속성 값 추출 (따옴표 있는/없는 값 모두 처리)
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let mut search = 0;
+    while let Some(found) = lower[search..].find(name) {
+        let idx = search + found;
+        search = idx + name.len();
+        // 다른 속성 이름의 일부(data-name 등)인 경우 건너뜀
+        if idx > 0 && !lower.as_bytes()[idx - 1].is_ascii_whitespace() {
+            continue;
+        }
+        let rest = tag[search..].trim_start();
+        let Some(rest) = rest.strip_prefix('=') else { continue };
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => rest[1..].split(quote).next().unwrap_or_default(),
+            _ => rest.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or_default(),
+        };
+        return Some(value.to_string());
+    }
+    None
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,225 @@
+// 마커 작성 중 위치 추천: "혹시 여기 계신가요?" (근처 장소, 마커가 많이 모인 지점, 보정 좌표)
+// GPS 오차로 같은 장소의 마커가 흩어지지 않도록 가까운 장소/인기 지점 좌표로 맞춰 줌
+use anyhow::{anyhow, Result};
+use futures_util::future::{join_all, BoxFuture};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::database::PopularSpot;
+
+const REQUEST_TIMEOUT_SECS: u64 = 3;
+/// 카테고리별로 가져오는 장소 수 (카카오 최대 15)
+const PLACES_PER_CATEGORY: usize = 5;
+pub const MAX_PLACES: usize = 10;
+/// GPS 정확도(accuracy)를 보정 반경으로 쓸 때의 범위
+const MIN_SNAP_RADIUS_M: f64 = 10.0;
+const MAX_SNAP_RADIUS_M: f64 = 100.0;
+
+/// 근처 장소 (POI)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Place {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub address: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub distance_m: f64,
+}
+
+/// 좌표 주변 장소 검색 제공자 (테스트에서는 가짜 제공자로 교체)
+pub trait PlaceSearch: Send + Sync {
+    fn nearby<'a>(&'a self, latitude: f64, longitude: f64, radius_m: f64) -> BoxFuture<'a, Result<Vec<Place>>>;
+}
+
+/// 카카오 로컬 API 카테고리 검색 (관광명소, 문화시설, 카페, 음식점 등 카테고리마다 1회 호출)
+pub struct KakaoPlaceSearch {
+    client: reqwest::Client,
+    api_key: String,
+    categories: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct KakaoPlaceResponse {
+    documents: Vec<KakaoPlace>,
+}
+
+#[derive(Deserialize)]
+struct KakaoPlace {
+    id: String,
+    place_name: String,
+    category_group_name: String,
+    address_name: String,
+    road_address_name: String,
+    x: String,
+    y: String,
+}
+
+impl KakaoPlaceSearch {
+    pub fn new(api_key: &str, categories: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+            api_key: api_key.to_string(),
+            categories: categories.split(',')
+                .map(str::trim)
+                .filter(|code| !code.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    async fn search_category(&self, category: &str, latitude: f64, longitude: f64, radius_m: f64) -> Result<Vec<Place>> {
+        let response = self.client
+            .get("https://dapi.kakao.com/v2/local/search/category.json")
+            .query(&[
+                ("category_group_code", category.to_string()),
+                ("x", longitude.to_string()),
+                ("y", latitude.to_string()),
+                ("radius", (radius_m.round() as i64).clamp(1, 20_000).to_string()),
+                ("sort", "distance".to_string()),
+                ("size", PLACES_PER_CATEGORY.to_string()),
+            ])
+            .header(reqwest::header::AUTHORIZATION, format!("KakaoAK {}", self.api_key))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("카카오 장소 검색 응답 {} ({})", response.status(), category));
+        }
+        let body: KakaoPlaceResponse = response.json().await?;
+        Ok(body.documents.into_iter()
+            .filter_map(|place| {
+                let (lat, lng) = (place.y.parse().ok()?, place.x.parse().ok()?);
+                let address = if place.road_address_name.is_empty() { place.address_name } else { place.road_address_name };
+                Some(Place {
+                    id: place.id,
+                    name: place.place_name,
+                    category: place.category_group_name,
+                    address: Some(address).filter(|address| !address.is_empty()),
+                    latitude: lat,
+                    longitude: lng,
+                    distance_m: distance_m(latitude, longitude, lat, lng),
+                })
+            })
+            .collect())
+    }
+}
+
+impl PlaceSearch for KakaoPlaceSearch {
+    fn nearby<'a>(&'a self, latitude: f64, longitude: f64, radius_m: f64) -> BoxFuture<'a, Result<Vec<Place>>> {
+        Box::pin(async move {
+            let results = join_all(self.categories.iter()
+                .map(|category| self.search_category(category, latitude, longitude, radius_m))
+            ).await;
+            let mut places = Vec::new();
+            for result in results {
+                match result {
+                    Ok(found) => places.extend(found),
+                    Err(e) => warn!("⚠️ 근처 장소 검색 일부 실패: {}", e),
+                }
+            }
+            Ok(places)
+        })
+    }
+}
+
+/// 사용 가능한 장소 검색 (KAKAO_REST_API_KEY가 없으면 장소 없이 마커 기반 추천만)
+#[derive(Clone, Default)]
+pub struct Places {
+    search: Option<Arc<dyn PlaceSearch>>,
+}
+
+impl Places {
+    pub fn new(search: Arc<dyn PlaceSearch>) -> Self {
+        Self { search: Some(search) }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        if config.kakao_rest_api_key.is_empty() {
+            return Self::default();
+        }
+        Self::new(Arc::new(KakaoPlaceSearch::new(&config.kakao_rest_api_key, &config.place_category_codes)))
+    }
+
+    /// 가까운 순 장소 (같은 장소 중복 제거, 실패하면 빈 목록)
+    pub async fn nearby(&self, latitude: f64, longitude: f64, radius_m: f64) -> Vec<Place> {
+        let Some(search) = &self.search else {
+            return Vec::new();
+        };
+        let mut places = match search.nearby(latitude, longitude, radius_m).await {
+            Ok(places) => places,
+            Err(e) => {
+                warn!("⚠️ 근처 장소 검색 실패: {}", e);
+                return Vec::new();
+            }
+        };
+        places.retain(|place| place.distance_m <= radius_m);
+        places.sort_by(|a, b| a.distance_m.total_cmp(&b.distance_m));
+        let mut seen = std::collections::HashSet::new();
+        places.retain(|place| seen.insert(place.id.clone()));
+        places.truncate(MAX_PLACES);
+        places
+    }
+}
+
+/// 보정 좌표와 그 근거
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnappedLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub source: &'static str, // place, popular_spot, none(입력 좌표 그대로)
+    pub name: Option<String>,
+    pub distance_m: f64, // 입력 좌표에서 옮긴 거리
+}
+
+/// GPS 정확도(미터)가 있으면 그만큼, 없으면 기본 반경 안에서만 좌표를 옮김
+pub fn snap_radius_m(accuracy_m: Option<f64>, default_radius_m: f64) -> f64 {
+    accuracy_m
+        .filter(|accuracy| accuracy.is_finite() && *accuracy > 0.0)
+        .map(|accuracy| accuracy.clamp(MIN_SNAP_RADIUS_M, MAX_SNAP_RADIUS_M))
+        .unwrap_or(default_radius_m)
+}
+
+/// 반경 안의 가장 가까운 장소 → 없으면 반경 안에서 마커가 가장 많은 지점 → 없으면 입력 좌표
+pub fn snap_location(latitude: f64, longitude: f64, places: &[Place], spots: &[PopularSpot], radius_m: f64) -> SnappedLocation {
+    if let Some(place) = places.iter()
+        .filter(|place| place.distance_m <= radius_m)
+        .min_by(|a, b| a.distance_m.total_cmp(&b.distance_m))
+    {
+        return SnappedLocation {
+            latitude: place.latitude,
+            longitude: place.longitude,
+            source: "place",
+            name: Some(place.name.clone()),
+            distance_m: place.distance_m,
+        };
+    }
+    if let Some(spot) = spots.iter()
+        .filter(|spot| spot.distance_m <= radius_m)
+        .max_by_key(|spot| spot.marker_count)
+    {
+        return SnappedLocation {
+            latitude: spot.latitude,
+            longitude: spot.longitude,
+            source: "popular_spot",
+            name: spot.neighborhood.clone(),
+            distance_m: spot.distance_m,
+        };
+    }
+    SnappedLocation { latitude, longitude, source: "none", name: None, distance_m: 0.0 }
+}
+
+/// 두 좌표 사이 거리 (미터, 하버사인)
+pub fn distance_m(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    const EARTH_RADIUS_M: f64 = 6_371_000.0;
+    let (d_lat, d_lng) = ((lat2 - lat1).to_radians(), (lng2 - lng1).to_radians());
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
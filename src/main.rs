@@ -1,21 +1,11 @@
-use actix_web::{App, HttpServer, web};
-use actix_cors::Cors;
+use actix_web::HttpServer;
 use log::info;
 use http;
 
-mod image_processor;
-mod routes;
-mod database;
-mod config;
-mod s3_service;
-mod s3_routes;
-mod error_handler;
-mod emotions;
-
-use routes::setup_routes;
-use database::Database;
-use config::Config;
-use s3_service::S3Service;
+use bigpictureback::{anonymize, build_app, idempotency, image_cleanup, marker_export, marker_views, member_export, memories, moderation, orphan_gc, push, resumable_upload, reverse_geocode, route_usage, scheduled_markers, schema_check, search_index, upload_spool, AppState};
+use bigpictureback::database::Database;
+use bigpictureback::config::Config;
+use bigpictureback::s3_service::S3Service;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -52,6 +42,28 @@ async fn main() -> std::io::Result<()> {
         }
     };
     
+    // 관리 작업: 스테이징 데이터 익명화 (`bigpictureback anonymize --confirm <db이름>`)
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("anonymize") {
+        let result = match anonymize::AnonymizeOptions::from_args(&args[2..]) {
+            Ok(options) => anonymize::anonymize_database(&database.pool, &options).await,
+            Err(e) => Err(e),
+        };
+        return match result {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                eprintln!("❌ 익명화 실패: {}", e);
+                Err(std::io::Error::other("Anonymization failed"))
+            }
+        };
+    }
+    
+    // 스키마 검증 (불일치는 로그와 /api/health/ready로 보고하고 서버는 계속 띄움)
+    match schema_check::verify_schema(&database.pool).await {
+        Ok(report) => report.log(),
+        Err(e) => eprintln!("⚠️ 스키마 검증 실패: {}", e),
+    }
+    
     // S3 서비스 초기화
     let s3_service = match S3Service::new(
         config.s3_bucket_name.clone(), 
@@ -69,24 +81,138 @@ async fn main() -> std::io::Result<()> {
         }
     };
     
+    // 이전 실행에서 남은 업로드 임시 파일 정리
+    upload_spool::clean_stale_temp_files(&config);
+    
+    // 매일 아침 "지난 오늘" 알림 생성
+    tokio::spawn(memories::run_memory_notification_scheduler(
+        database.clone(),
+        config.memories_notification_hour,
+    ));
+    
+    // 탈퇴 회원 이미지 등 정리 작업 처리
+    tokio::spawn(image_cleanup::run_image_cleanup_worker(
+        database.clone(),
+        config.clone(),
+        s3_service.clone(),
+    ));
+    
+    // 기존 마커 주소 역지오코딩 (API 키가 있을 때만)
+    if config.kakao_rest_api_key.is_empty() {
+        info!("ℹ️ KAKAO_REST_API_KEY가 없어 마커 주소 백필 작업을 건너뜁니다");
+    } else {
+        tokio::spawn(reverse_geocode::run_geocode_backfill_worker(
+            database.clone(),
+            reverse_geocode::KakaoGeocoder::new(&config.kakao_rest_api_key),
+            config.clone(),
+        ));
+    }
+    
+    // 좋아요/"지난 오늘" 푸시 알림 발송 (FCM 서비스 계정이 있을 때만)
+    if config.fcm_service_account_path.is_empty() {
+        info!("ℹ️ FCM_SERVICE_ACCOUNT_PATH가 없어 푸시 알림을 보내지 않습니다 (알림함에만 저장)");
+    } else {
+        match push::FcmSender::from_service_account_file(&config.fcm_service_account_path) {
+            Ok(sender) => {
+                tokio::spawn(push::run_push_worker(
+                    database.clone(),
+                    std::sync::Arc::new(sender),
+                    config.push_max_age_secs,
+                ));
+            }
+            Err(e) => eprintln!("⚠️ FCM 설정 실패, 푸시 알림을 보내지 않습니다: {}", e),
+        }
+    }
+    
     let _server_address = config.server_address();
-    HttpServer::new(move || {
-        // CORS 설정 - 모든 origin 허용 (localhost, IP 주소, 도메인 모두)
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allow_any_method()
-            .allow_any_header()
-            .supports_credentials()
-            .max_age(3600);
-        
-        App::new()
-            .wrap(cors)
-            .app_data(web::Data::new(database.pool.clone()))
-            .app_data(web::Data::new(database.clone()))
-            .app_data(web::Data::new(config.clone()))
-            .app_data(web::Data::new(s3_service.clone()))
-            .configure(setup_routes)
-    })
+    let state = AppState::new(database, config, s3_service);
+    
+    // 관리자 마커 내보내기 파일 생성
+    tokio::spawn(marker_export::run_marker_export_worker(
+        state.database.clone(),
+        state.storage.clone(),
+    ));
+    
+    // 마커가 많은 회원의 개인 데이터 내보내기 파일 생성
+    tokio::spawn(member_export::run_member_export_worker(
+        state.database.clone(),
+        state.storage.clone(),
+    ));
+    
+    // 라우트별 사용량을 주기적으로 DB에 반영
+    tokio::spawn(route_usage::run_usage_flush_worker(
+        state.database.clone(),
+        state.route_usage.clone(),
+        state.config.usage_flush_interval_secs,
+    ));
+    
+    // publish_at이 지난 예약 마커 게시
+    tokio::spawn(scheduled_markers::run_scheduled_marker_publisher(
+        state.database.clone(),
+        state.clock.clone(),
+        state.marker_events.clone(),
+        state.cluster_cache.clone(),
+        state.emotion_profiles.clone(),
+        state.config.scheduled_publish_interval_secs,
+    ));
+    
+    // 메모리에 모은 마커 조회를 주기적으로 조회수에 반영
+    tokio::spawn(marker_views::run_view_flush_worker(
+        state.database.clone(),
+        state.marker_views.clone(),
+        state.clock.clone(),
+        state.config.view_flush_interval_secs,
+    ));
+    
+    // 보관 기간이 지난 멱등성 키 정리
+    tokio::spawn(idempotency::run_idempotency_cleanup_worker(
+        state.database.clone(),
+        state.clock.clone(),
+        state.config.idempotency_key_ttl_hours,
+    ));
+    
+    // 만료된 이어 받는 업로드 세션과 받은 조각 정리
+    tokio::spawn(resumable_upload::run_upload_session_cleanup_worker(
+        state.database.clone(),
+        state.upload_sessions.clone(),
+        state.clock.clone(),
+    ));
+    
+    // DB가 참조하지 않는 저장소 이미지와 파일이 없는 마커 이미지 정리 (ORPHAN_GC_INTERVAL_HOURS=0이면 관리자 점검만)
+    if state.config.orphan_gc_interval_hours == 0 {
+        info!("ℹ️ ORPHAN_GC_INTERVAL_HOURS=0이라 고아 파일 정리 작업을 건너뜁니다");
+    } else {
+        tokio::spawn(orphan_gc::run_orphan_gc_worker(
+            state.database.clone(),
+            state.storage.clone(),
+            state.clock.clone(),
+            state.config.orphan_gc_interval_hours,
+            state.config.orphan_gc_min_age_hours,
+        ));
+    }
+    
+    // 마커 변경을 외부 검색 색인에 반영 (SEARCH_BACKEND_URL이 있을 때만)
+    match state.search_index.backend() {
+        Some(backend) => {
+            tokio::spawn(search_index::run_search_indexer(
+                state.database.clone(),
+                backend.clone(),
+                state.marker_events.subscribe(),
+            ));
+        }
+        None => info!("ℹ️ SEARCH_BACKEND_URL이 없어 Postgres 전문 검색만 사용합니다"),
+    }
+    
+    // 검수기 오류로 pending에 남은 업로드 이미지 재검수 (MODERATION_BACKEND가 있을 때만)
+    if let Some(moderator) = state.image_hooks.moderation().moderator() {
+        tokio::spawn(moderation::run_moderation_retry_worker(
+            state.database.clone(),
+            state.storage.clone(),
+            moderator.clone(),
+        ));
+    }
+    
+    HttpServer::new(move || build_app(state.clone()))
     .bind("0.0.0.0:5500")?  // 모든 IP에서 접근 가능하도록 0.0.0.0으로 바인딩
     .run()
     .await
@@ -0,0 +1,252 @@
+// 마커 실시간 변경 알림 (WebSocket /api/ws)
+// 클라이언트가 지도 영역(중심 + lat/lng delta, GET /api/markers와 같은 방식)을 구독하면
+// 그 영역 안의 공개 마커 생성/수정/삭제를 바로 보내서 폴링 없이 지도를 갱신
+// 이벤트는 인스턴스 메모리에서만 전달됨 (다른 인스턴스에 붙은 클라이언트는 다시 조회해야 함)
+use actix_ws::{Message, MessageStream, Session};
+use log::{info, warn};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::database::Marker;
+use crate::dto::MarkerDto;
+
+/// 느린 클라이언트를 위해 쌓아 두는 이벤트 수 (넘치면 resync를 보내 다시 조회하게 함)
+const EVENT_BUFFER: usize = 256;
+const HEARTBEAT_INTERVAL_SECS: u64 = 30;
+/// 이 시간 동안 아무 메시지(pong 포함)도 없으면 연결 종료
+const CLIENT_TIMEOUT_SECS: u64 = 90;
+pub const MAX_CLIENT_FRAME_BYTES: usize = 4 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl MarkerEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarkerEventKind::Created => "marker.created",
+            MarkerEventKind::Updated => "marker.updated",
+            MarkerEventKind::Deleted => "marker.deleted",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MarkerEvent {
+    pub kind: MarkerEventKind,
+    pub marker_id: i64,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// 위치가 바뀐 수정이면 이전 위치 (이전 영역을 보던 클라이언트도 지도에서 옮기도록)
+    pub previous: Option<(f64, f64)>,
+    pub data: Option<serde_json::Value>, // 생성/수정 시 MarkerDto
+}
+
+impl MarkerEvent {
+    /// 공개 마커만 이벤트로 만듦 (비공개/친구 공개 마커, 게시 중단/임시 저장/예약 마커의 내용은 보내지 않음)
+    pub fn from_marker(kind: MarkerEventKind, marker: &Marker, previous: Option<(f64, f64)>) -> Option<Self> {
+        if marker.sharing_option.as_deref().unwrap_or("public") != "public" {
+            return None;
+        }
+        if kind != MarkerEventKind::Deleted && marker.taken_down_at.is_some() {
+            return None;
+        }
+        if !marker.is_published() {
+            return None;
+        }
+        let (latitude, longitude) = (marker.get_latitude()?, marker.get_longitude()?);
+        Some(Self {
+            kind,
+            marker_id: marker.id,
+            latitude,
+            longitude,
+            previous: previous.filter(|position| *position != (latitude, longitude)),
+            data: (kind != MarkerEventKind::Deleted).then(|| serde_json::json!(MarkerDto::from(marker))),
+        })
+    }
+
+    pub fn is_visible_in(&self, bounds: &Bounds) -> bool {
+        bounds.contains(self.latitude, self.longitude)
+            || self.previous.is_some_and(|(lat, lng)| bounds.contains(lat, lng))
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": self.kind.as_str(),
+            "markerId": self.marker_id,
+            "latitude": self.latitude,
+            "longitude": self.longitude,
+            "previous": self.previous.map(|(lat, lng)| serde_json::json!({ "latitude": lat, "longitude": lng })),
+            "data": self.data
+        })
+    }
+}
+
+/// 구독 영역 (중심 + 전체 폭, 경도는 날짜변경선을 넘어가도 됨)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub lat: f64,
+    pub lng: f64,
+    pub lat_delta: f64,
+    pub lng_delta: f64,
+}
+
+impl Bounds {
+    pub fn new(lat: f64, lng: f64, lat_delta: f64, lng_delta: f64) -> Result<Self, String> {
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+            return Err(format!("중심 좌표 범위 오류: lat {}, lng {}", lat, lng));
+        }
+        if !(lat_delta > 0.0 && lat_delta <= 180.0 && lng_delta > 0.0 && lng_delta <= 360.0) {
+            return Err(format!("영역 크기 오류: lat_delta {}, lng_delta {}", lat_delta, lng_delta));
+        }
+        Ok(Self { lat, lng, lat_delta, lng_delta })
+    }
+
+    pub fn contains(&self, lat: f64, lng: f64) -> bool {
+        let west = self.lng - self.lng_delta / 2.0;
+        (lat - self.lat).abs() <= self.lat_delta / 2.0
+            && (lng - west).rem_euclid(360.0) <= self.lng_delta
+    }
+
+    fn to_json(self) -> serde_json::Value {
+        serde_json::json!({
+            "lat": self.lat,
+            "lng": self.lng,
+            "latDelta": self.lat_delta,
+            "lngDelta": self.lng_delta
+        })
+    }
+}
+
+/// 마커 변경 이벤트 허브 (워커 전체가 공유, 연결마다 구독)
+#[derive(Clone)]
+pub struct MarkerEvents {
+    sender: broadcast::Sender<Arc<MarkerEvent>>,
+}
+
+impl Default for MarkerEvents {
+    fn default() -> Self {
+        Self { sender: broadcast::channel(EVENT_BUFFER).0 }
+    }
+}
+
+impl MarkerEvents {
+    /// 이벤트 발행 (공개 마커가 아니면 무시)
+    pub fn publish(&self, kind: MarkerEventKind, marker: &Marker, previous: Option<(f64, f64)>) {
+        if let Some(event) = MarkerEvent::from_marker(kind, marker, previous) {
+            // 구독자가 없으면 Err지만 보낼 곳이 없을 뿐이므로 무시
+            let _ = self.sender.send(Arc::new(event));
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<MarkerEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// 현재 연결된 구독자 수 (메트릭용)
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+/// 클라이언트 → 서버 메시지
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Subscribe { lat: f64, lng: f64, lat_delta: f64, lng_delta: f64 },
+    Unsubscribe,
+}
+
+/// 연결 1개 처리: 구독 영역 변경을 받고, 영역 안의 이벤트를 보내고, 주기적으로 ping
+pub async fn run_marker_socket(
+    mut session: Session,
+    mut stream: MessageStream,
+    mut events: broadcast::Receiver<Arc<MarkerEvent>>,
+    mut bounds: Option<Bounds>,
+) {
+    let mut heartbeat = tokio::time::interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
+    let mut last_seen = Instant::now();
+    if let Some(bounds) = bounds {
+        let _ = session.text(subscribed_json(bounds).to_string()).await;
+    }
+
+    loop {
+        tokio::select! {
+            message = stream.recv() => {
+                last_seen = Instant::now();
+                let reply = match message {
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(ClientMessage::Subscribe { lat, lng, lat_delta, lng_delta }) => {
+                            match Bounds::new(lat, lng, lat_delta, lng_delta) {
+                                Ok(new_bounds) => {
+                                    bounds = Some(new_bounds);
+                                    Some(subscribed_json(new_bounds))
+                                }
+                                Err(e) => Some(error_json(&e)),
+                            }
+                        }
+                        Ok(ClientMessage::Unsubscribe) => {
+                            bounds = None;
+                            Some(serde_json::json!({ "type": "unsubscribed" }))
+                        }
+                        Err(e) => Some(error_json(&format!("알 수 없는 메시지: {}", e))),
+                    },
+                    Some(Ok(Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            break;
+                        }
+                        None
+                    }
+                    Some(Ok(Message::Pong(_))) => None,
+                    Some(Ok(Message::Binary(_))) => Some(error_json("텍스트(JSON) 메시지만 지원합니다")),
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => None,
+                    Some(Err(e)) => {
+                        warn!("⚠️ 마커 실시간 연결 프로토콜 오류: {}", e);
+                        break;
+                    }
+                };
+                if let Some(reply) = reply
+                    && session.text(reply.to_string()).await.is_err()
+                {
+                    break;
+                }
+            }
+            event = events.recv() => {
+                let payload = match event {
+                    Ok(event) if bounds.is_some_and(|bounds| event.is_visible_in(&bounds)) => event.to_json(),
+                    Ok(_) => continue,
+                    // 너무 느려서 놓친 이벤트가 있으면 영역을 다시 조회하도록 알림
+                    Err(RecvError::Lagged(missed)) => serde_json::json!({ "type": "resync", "missed": missed }),
+                    Err(RecvError::Closed) => break,
+                };
+                if session.text(payload.to_string()).await.is_err() {
+                    break;
+                }
+            }
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > Duration::from_secs(CLIENT_TIMEOUT_SECS) {
+                    info!("🔌 마커 실시간 연결 응답 없음, 종료");
+                    break;
+                }
+                if session.ping(b"").await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    let _ = session.close(None).await;
+}
+
+fn subscribed_json(bounds: Bounds) -> serde_json::Value {
+    serde_json::json!({ "type": "subscribed", "bounds": bounds.to_json() })
+}
+
+fn error_json(message: &str) -> serde_json::Value {
+    serde_json::json!({ "type": "error", "message": message })
+}
@@ -0,0 +1,314 @@
+// 관리자용 마커 데이터 내보내기 (분석팀 전달용 CSV/Parquet, 작성자/설명/이미지 등 개인정보 제외)
+// 요청은 marker_exports 테이블에 쌓이고, 백그라운드 작업이 파일을 만들어 저장소(S3 또는 로컬)에 올림
+// 관리자 검토용 CSV/GeoJSON 내보내기는 파일을 만들지 않고 DB 커서에서 읽는 대로 응답으로 흘려보냄
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures_util::stream::StreamExt;
+use log::{error, info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::database::{AdminMarkerExportFilter, AdminMarkerExportRow, Database, MarkerExport, MarkerExportRow, NewDeadLetterJob, DLQ_MARKER_EXPORT};
+use crate::geojson;
+use crate::parquet::{Column, ColumnType, ParquetWriter, Value};
+use crate::storage::Storage;
+
+pub const EXPORT_FORMATS: [&str; 2] = ["csv", "parquet"];
+pub const STREAM_EXPORT_FORMATS: [&str; 2] = ["csv", "geojson"];
+/// 스트리밍 응답 조각 크기 (이만큼 모이면 보냄)
+const STREAM_CHUNK_BYTES: usize = 64 * 1024;
+/// 클라이언트가 느리면 DB에서 더 읽지 않고 기다리도록 미리 만들어 둘 조각 수
+const STREAM_BUFFERED_CHUNKS: usize = 4;
+const EXPORT_POLL_INTERVAL_SECS: u64 = 10;
+/// 이보다 오래 실행 중인 작업은 서버 재시작으로 중단된 것으로 보고 다시 처리
+const STALE_EXPORT_SECS: i64 = 3600;
+
+/// 내보내기 열 (MarkerExportRow 순서와 같음)
+pub fn export_columns() -> Vec<Column> {
+    let column = |name, column_type, optional| Column { name, column_type, optional };
+    vec![
+        column("marker_id", ColumnType::Int64, false),
+        column("latitude", ColumnType::Double, true),
+        column("longitude", ColumnType::Double, true),
+        column("emotion_tag", ColumnType::Utf8, true),
+        column("emotion_tag_input", ColumnType::Utf8, true),
+        column("language", ColumnType::Utf8, true),
+        column("likes", ColumnType::Int32, false),
+        column("dislikes", ColumnType::Int32, false),
+        column("views", ColumnType::Int32, false),
+        column("liked_members", ColumnType::Int64, false),
+        column("disliked_members", ColumnType::Int64, false),
+        column("viewed_members", ColumnType::Int64, false),
+        column("bookmarked_members", ColumnType::Int64, false),
+        column("image_count", ColumnType::Int64, false),
+        column("created_at", ColumnType::TimestampMicros, false),
+        column("updated_at", ColumnType::TimestampMicros, false),
+    ]
+}
+
+fn row_values(row: MarkerExportRow) -> Vec<Value> {
+    let text = |value: Option<String>| value.map(Value::Utf8).unwrap_or(Value::Null);
+    let number = |value: Option<f64>| value.map(Value::Double).unwrap_or(Value::Null);
+    vec![
+        Value::Int64(row.id),
+        number(row.latitude),
+        number(row.longitude),
+        text(row.emotion_tag),
+        text(row.emotion_tag_input),
+        text(row.language),
+        Value::Int32(row.likes),
+        Value::Int32(row.dislikes),
+        Value::Int32(row.views),
+        Value::Int64(row.liked_members),
+        Value::Int64(row.disliked_members),
+        Value::Int64(row.viewed_members),
+        Value::Int64(row.bookmarked_members),
+        Value::Int64(row.image_count),
+        Value::Timestamp(row.created_at),
+        Value::Timestamp(row.updated_at),
+    ]
+}
+
+pub fn content_type(format: &str) -> &'static str {
+    match format {
+        "parquet" => "application/vnd.apache.parquet",
+        "geojson" => geojson::GEOJSON_CONTENT_TYPE,
+        _ => "text/csv; charset=utf-8",
+    }
+}
+
+pub fn file_name(job: &MarkerExport) -> String {
+    format!("markers_export_{}.{}", job.id, job.format)
+}
+
+/// CSV 필드 (쉼표/따옴표/줄바꿈이 있으면 따옴표로 감쌈)
+fn csv_field(value: &Value) -> String {
+    let raw = match value {
+        Value::Null => return String::new(),
+        Value::Int32(v) => v.to_string(),
+        Value::Int64(v) => v.to_string(),
+        Value::Double(v) => v.to_string(),
+        Value::Utf8(s) => s.clone(),
+        Value::Timestamp(t) => t.to_rfc3339_opts(SecondsFormat::Secs, true),
+    };
+    if raw.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", raw.replace('"', "\"\""))
+    } else {
+        raw
+    }
+}
+
+fn write_csv_line(out: &mut Vec<u8>, fields: impl Iterator<Item = String>) {
+    out.extend_from_slice(fields.collect::<Vec<_>>().join(",").as_bytes());
+    out.extend_from_slice(b"\r\n");
+}
+
+enum ExportWriter {
+    Csv(Vec<u8>),
+    Parquet(ParquetWriter<Vec<u8>>),
+}
+
+/// DB에서 행을 흘려 받으며 파일 내용 생성 (내용, 행 수)
+pub async fn export_markers(db: &Database, format: &str, since: Option<DateTime<Utc>>) -> Result<(Vec<u8>, i64)> {
+    let columns = export_columns();
+    let mut writer = match format {
+        "parquet" => ExportWriter::Parquet(ParquetWriter::new(Vec::new(), columns)?),
+        "csv" => {
+            let mut out = Vec::new();
+            write_csv_line(&mut out, columns.iter().map(|column| column.name.to_string()));
+            ExportWriter::Csv(out)
+        }
+        other => anyhow::bail!("지원하지 않는 내보내기 형식: {}", other),
+    };
+
+    let mut rows = db.stream_marker_export_rows(since);
+    let mut row_count = 0i64;
+    while let Some(row) = rows.next().await {
+        let values = row_values(row?);
+        match &mut writer {
+            ExportWriter::Csv(out) => write_csv_line(out, values.iter().map(csv_field)),
+            ExportWriter::Parquet(parquet) => parquet.write_row(values)?,
+        }
+        row_count += 1;
+    }
+
+    let data = match writer {
+        ExportWriter::Csv(out) => out,
+        ExportWriter::Parquet(parquet) => parquet.finish()?,
+    };
+    Ok((data, row_count))
+}
+
+/// 관리자 스트리밍 내보내기 열 (admin_row_values 순서와 같음)
+const ADMIN_EXPORT_COLUMNS: [&str; 20] = [
+    "marker_id", "public_id", "member_id", "latitude", "longitude", "emotion_tag", "emotion_tag_input", "description",
+    "visibility", "status", "language", "likes", "dislikes", "views", "address_region", "address_locality",
+    "address_neighborhood", "taken_down_at", "created_at", "updated_at",
+];
+
+fn admin_row_values(row: AdminMarkerExportRow) -> Vec<Value> {
+    let text = |value: Option<String>| value.map(Value::Utf8).unwrap_or(Value::Null);
+    let number = |value: Option<f64>| value.map(Value::Double).unwrap_or(Value::Null);
+    vec![
+        Value::Int64(row.id),
+        text(row.public_id),
+        row.member_id.map(Value::Int64).unwrap_or(Value::Null),
+        number(row.latitude),
+        number(row.longitude),
+        text(row.emotion_tag),
+        text(row.emotion_tag_input),
+        text(row.description),
+        Value::Utf8(row.sharing_option.unwrap_or_else(|| "public".to_string())),
+        text(row.status),
+        text(row.language),
+        Value::Int32(row.likes),
+        Value::Int32(row.dislikes),
+        Value::Int32(row.views),
+        text(row.address_region),
+        text(row.address_locality),
+        text(row.address_neighborhood),
+        row.taken_down_at.map(Value::Timestamp).unwrap_or(Value::Null),
+        Value::Timestamp(row.created_at),
+        Value::Timestamp(row.updated_at),
+    ]
+}
+
+/// 행 하나를 GeoJSON Feature로 (properties 키는 API 응답처럼 camelCase)
+fn admin_row_feature(values: Vec<Value>) -> serde_json::Value {
+    let properties: serde_json::Map<String, serde_json::Value> = ADMIN_EXPORT_COLUMNS
+        .iter()
+        .zip(values)
+        .map(|(name, value)| {
+            let key = match *name {
+                "marker_id" => "id".to_string(),
+                name => camel_case(name),
+            };
+            let value = match value {
+                Value::Null => serde_json::Value::Null,
+                Value::Int32(v) => v.into(),
+                Value::Int64(v) => v.into(),
+                Value::Double(v) => v.into(),
+                Value::Utf8(s) => s.into(),
+                Value::Timestamp(t) => t.to_rfc3339_opts(SecondsFormat::Secs, true).into(),
+            };
+            (key, value)
+        })
+        .collect();
+    geojson::marker_feature(serde_json::Value::Object(properties))
+}
+
+fn camel_case(name: &str) -> String {
+    let mut parts = name.split('_');
+    let mut out = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            out.extend(first.to_uppercase());
+            out.push_str(chars.as_str());
+        }
+    }
+    out
+}
+
+/// 조건에 맞는 마커를 DB 커서에서 읽는 대로 CSV/GeoJSON 조각으로 보냄
+/// 받는 쪽이 끊기면 읽기를 멈추고, DB 오류는 마지막 조각으로 전달 (응답이 중간에 끊김)
+pub fn stream_admin_export(db: Database, filter: AdminMarkerExportFilter, format: &str) -> tokio::sync::mpsc::Receiver<Result<Vec<u8>>> {
+    let (sender, receiver) = tokio::sync::mpsc::channel(STREAM_BUFFERED_CHUNKS);
+    let as_geojson = format == "geojson";
+    tokio::spawn(async move {
+        let mut chunk = Vec::with_capacity(STREAM_CHUNK_BYTES);
+        if as_geojson {
+            chunk.extend_from_slice(br#"{"type":"FeatureCollection","features":["#);
+        } else {
+            write_csv_line(&mut chunk, ADMIN_EXPORT_COLUMNS.iter().map(|name| name.to_string()));
+        }
+        
+        let mut rows = db.stream_admin_marker_export_rows(&filter);
+        let mut row_count = 0u64;
+        while let Some(row) = rows.next().await {
+            let values = match row {
+                Ok(row) => admin_row_values(row),
+                Err(e) => {
+                    warn!("⚠️ 관리자 마커 내보내기 중단: {}행 이후 DB 오류 - {}", row_count, e);
+                    let _ = sender.send(Err(e.into())).await;
+                    return;
+                }
+            };
+            if as_geojson {
+                if row_count > 0 {
+                    chunk.push(b',');
+                }
+                chunk.extend_from_slice(admin_row_feature(values).to_string().as_bytes());
+            } else {
+                write_csv_line(&mut chunk, values.iter().map(csv_field));
+            }
+            row_count += 1;
+            if chunk.len() >= STREAM_CHUNK_BYTES && sender.send(Ok(std::mem::take(&mut chunk))).await.is_err() {
+                info!("ℹ️ 관리자 마커 내보내기: 클라이언트 연결이 끊겨 {}행에서 중단", row_count);
+                return;
+            }
+        }
+        
+        if as_geojson {
+            chunk.extend_from_slice(br#"]}"#);
+        }
+        if sender.send(Ok(chunk)).await.is_ok() {
+            info!("✅ 관리자 마커 내보내기 전송 완료: {}행", row_count);
+        }
+    });
+    receiver
+}
+
+/// 대기 중인 작업 하나 처리 (처리할 작업이 있었으면 true)
+pub async fn process_next_export(db: &Database, storage: &dyn Storage) -> Result<bool> {
+    let Some(job) = db.claim_marker_export(STALE_EXPORT_SECS).await? else {
+        return Ok(false);
+    };
+    info!("📦 마커 내보내기 시작: 작업 {} ({}, since {:?})", job.id, job.format, job.since);
+
+    let result = async {
+        let (data, row_count) = export_markers(db, &job.format, job.since).await?;
+        let key = format!("exports/{}", file_name(&job));
+        let file_size = data.len() as i64;
+        storage.put(&key, data, content_type(&job.format)).await?;
+        Ok::<_, anyhow::Error>((key, row_count, file_size))
+    }.await;
+
+    match result {
+        Ok((key, row_count, file_size)) => {
+            db.complete_marker_export(job.id, &key, row_count, file_size).await?;
+            info!("✅ 마커 내보내기 완료: 작업 {} ({}행, {}바이트)", job.id, row_count, file_size);
+        }
+        Err(e) => {
+            warn!("⚠️ 마커 내보내기 실패: 작업 {} - {}", job.id, e);
+            db.fail_marker_export(job.id, &e.to_string()).await?;
+            db.add_dead_letter_job(&NewDeadLetterJob {
+                job_type: DLQ_MARKER_EXPORT.to_string(),
+                source_id: Some(job.id),
+                payload: serde_json::json!({ "format": job.format, "since": job.since, "requested_by": job.requested_by }),
+                attempts: 1,
+                last_error: Some(e.to_string()),
+            }).await?;
+        }
+    }
+    Ok(true)
+}
+
+/// 주기적으로 내보내기 작업 처리
+pub async fn run_marker_export_worker(db: Database, storage: Arc<dyn Storage>) {
+    info!("📦 마커 내보내기 작업 처리기 시작 ({}초 간격)", EXPORT_POLL_INTERVAL_SECS);
+    let mut interval = tokio::time::interval(Duration::from_secs(EXPORT_POLL_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        loop {
+            match process_next_export(&db, storage.as_ref()).await {
+                Ok(true) => continue,
+                Ok(false) => break,
+                Err(e) => {
+                    error!("❌ 마커 내보내기 작업 처리 실패: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+// 마커 조회수 집계
+// 로그인 회원은 회원 ID, 비로그인은 기기 ID(X-Device-Id) 또는 IP/User-Agent의 HMAC으로 조회자를 구분하고
+// 같은 조회자의 같은 마커 조회는 VIEW_DEDUPE_WINDOW_HOURS(기본 24시간)에 한 번만 셈
+// 요청마다 DB에 쓰지 않고 인스턴스 메모리에 모았다가 주기적으로 한 번에 반영하며,
+// 인스턴스 간/재시작 전후 중복은 marker_view_log의 마지막 집계 시각으로 거름
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use log::{info, warn};
+use sha1::Sha1;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::database::Database;
+
+/// 비로그인 앱이 보내는 기기 ID 헤더 (없으면 IP/User-Agent로 구분)
+pub const DEVICE_ID_HEADER: &str = "x-device-id";
+/// 반영 전 조회가 이만큼 쌓이면 새 조회자는 버림 (DB 장애 중 메모리가 늘지 않도록)
+const MAX_PENDING_VIEWS: usize = 100_000;
+const MAX_DEVICE_ID_LEN: usize = 100;
+
+/// 아직 DB에 반영하지 않은 조회 1건
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingView {
+    pub marker_id: i64,
+    pub viewer_key: String,
+    pub member_id: Option<i64>, // 회원 조회는 member_markers에 viewed 상호작용으로도 남김
+    pub viewed_at: DateTime<Utc>,
+}
+
+/// 조회자 구분 키 (회원 m:<ID>, 기기 d:<HMAC>, 그 외 a:<HMAC>)
+pub fn viewer_key(member_id: Option<i64>, device_id: Option<&str>, client_ip: &str, user_agent: &str, secret: &str) -> String {
+    if let Some(member_id) = member_id {
+        return format!("m:{}", member_id);
+    }
+    let device_id = device_id.map(str::trim).filter(|id| !id.is_empty() && id.len() <= MAX_DEVICE_ID_LEN);
+    let (prefix, source) = match device_id {
+        Some(device_id) => ("d", device_id.to_string()),
+        None => ("a", format!("{}\n{}", client_ip, user_agent)),
+    };
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(source.as_bytes());
+    let digest: String = mac.finalize().into_bytes().iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{}:{}", prefix, digest)
+}
+
+/// 워커 전체가 공유하는 반영 전 조회 (마커/조회자별 첫 조회만)
+#[derive(Clone)]
+pub struct MarkerViews {
+    pending: Arc<Mutex<HashMap<(i64, String), PendingView>>>,
+    window: Duration,
+}
+
+impl MarkerViews {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            window: Duration::hours(config.view_dedupe_window_hours.max(1)),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// 조회 1회 기록 (반영 전에 같은 조회자가 다시 본 것은 무시)
+    pub fn record(&self, marker_id: i64, viewer_key: String, member_id: Option<i64>, now: DateTime<Utc>) {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.len() >= MAX_PENDING_VIEWS {
+            return;
+        }
+        pending.entry((marker_id, viewer_key.clone())).or_insert(PendingView {
+            marker_id,
+            viewer_key,
+            member_id,
+            viewed_at: now,
+        });
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    /// 쌓인 조회를 꺼내고 비움
+    pub fn take(&self) -> Vec<PendingView> {
+        self.pending.lock().unwrap().drain().map(|(_, view)| view).collect()
+    }
+
+    /// DB 반영에 실패한 조회를 되돌려 다음 반영 때 다시 시도 (그 사이 새로 들어온 같은 조회보다 앞선 것을 남김)
+    fn restore(&self, views: Vec<PendingView>) {
+        let mut pending = self.pending.lock().unwrap();
+        for view in views {
+            pending.insert((view.marker_id, view.viewer_key.clone()), view);
+        }
+    }
+
+    /// 쌓인 조회를 반영하고 실제로 조회수에 더한 수 반환 (중복 제거 기간이 지난 기록도 정리)
+    pub async fn flush(&self, db: &Database, now: DateTime<Utc>) -> anyhow::Result<u64> {
+        let views = self.take();
+        if views.is_empty() {
+            return Ok(0);
+        }
+        match db.add_marker_views(&views, self.window, now).await {
+            Ok(counted) => Ok(counted),
+            Err(e) => {
+                self.restore(views);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// 주기적으로 쌓인 조회를 DB에 반영 (서버 시작 시 spawn)
+pub async fn run_view_flush_worker(db: Database, views: MarkerViews, clock: Arc<dyn Clock>, interval_secs: u64) {
+    info!("👁️ 마커 조회수 반영 작업 시작 ({}초마다, 같은 조회자는 {}시간에 한 번)", interval_secs, views.window().num_hours());
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs.max(1)));
+    loop {
+        interval.tick().await;
+        if let Err(e) = views.flush(&db, clock.now()).await {
+            warn!("⚠️ 마커 조회수 반영 실패 (다음에 다시 시도): {}", e);
+        }
+    }
+}
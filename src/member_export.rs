@@ -0,0 +1,163 @@
+// 회원 개인 데이터 내보내기 (내 마커 + 이미지 URL + 상호작용)
+// zip: member.json / markers.json / markers.geojson / images.json / interactions.json 을 묶은 ZIP
+// json: 같은 내용을 JSON 문서 하나로
+// 마커가 적으면 요청에 바로 응답하고, 많으면 member_data_exports 작업으로 백그라운드에서 만들어 저장소(S3 또는 로컬)에 올림
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::database::{Database, MemberDataExport};
+use crate::dto::{MarkerDto, MarkerImageDto, MemberDto};
+use crate::geojson;
+use crate::storage::Storage;
+use crate::zip::ZipWriter;
+
+pub const MEMBER_EXPORT_FORMATS: [&str; 2] = ["zip", "json"];
+/// 마커가 이보다 많으면 요청에서 바로 만들지 않고 백그라운드 작업으로 넘김
+pub const INLINE_EXPORT_MAX_MARKERS: i64 = 200;
+const EXPORT_POLL_INTERVAL_SECS: u64 = 10;
+/// 이보다 오래 실행 중인 작업은 서버 재시작으로 중단된 것으로 보고 다시 처리
+const STALE_EXPORT_SECS: i64 = 3600;
+
+pub fn content_type(format: &str) -> &'static str {
+    match format {
+        "zip" => "application/zip",
+        _ => "application/json; charset=utf-8",
+    }
+}
+
+pub fn file_name(member_id: i64, format: &str, exported_at: DateTime<Utc>) -> String {
+    format!("bigpicture_export_{}_{}.{}", member_id, exported_at.format("%Y%m%d"), format)
+}
+
+/// 내보내기 문서 (각 항목이 ZIP의 파일 하나)
+struct MemberExportContent {
+    member: Value,
+    markers: Value,
+    geojson: Value,
+    images: Value,
+    interactions: Value,
+    marker_count: i64,
+}
+
+async fn collect(db: &Database, member_id: i64, exported_at: DateTime<Utc>) -> Result<MemberExportContent> {
+    let member = db.get_member_by_id(member_id).await?.ok_or_else(|| anyhow!("회원을 찾을 수 없습니다: {}", member_id))?;
+    let markers = db.get_member_export_markers(member_id).await?;
+    let marker_ids: Vec<i64> = markers.iter().map(|marker| marker.id).collect();
+    let images = db.get_images_for_markers(&marker_ids).await?;
+    let interactions = db.get_member_marker_interactions(member_id).await?;
+
+    let mut images_by_marker: HashMap<i64, Vec<MarkerImageDto>> = HashMap::new();
+    for image in &images {
+        images_by_marker.entry(image.marker_id).or_default().push(MarkerImageDto::from(image));
+    }
+    let markers: Vec<Value> = markers
+        .iter()
+        .map(|marker| json!(MarkerDto::from(marker).with_images(images_by_marker.remove(&marker.id).unwrap_or_default())))
+        .collect();
+    let marker_count = markers.len() as i64;
+    let features = markers.iter().cloned().map(geojson::marker_feature).collect();
+
+    Ok(MemberExportContent {
+        member: json!(MemberDto::from(&member)),
+        geojson: geojson::feature_collection(features, json!({ "memberId": member_id, "exportedAt": exported_at, "count": marker_count })),
+        markers: json!(markers),
+        images: json!(images.iter().map(MarkerImageDto::from).collect::<Vec<_>>()),
+        interactions: json!(interactions.iter().map(|interaction| json!({
+            "markerId": interaction.marker_id,
+            "interactionType": interaction.interaction_type,
+            "createdAt": interaction.created_at,
+            "updatedAt": interaction.updated_at
+        })).collect::<Vec<_>>()),
+        marker_count,
+    })
+}
+
+/// 회원 데이터를 모아 파일 내용 생성 (내용, 마커 수)
+pub async fn export_member_data(db: &Database, member_id: i64, format: &str, exported_at: DateTime<Utc>) -> Result<(Vec<u8>, i64)> {
+    if !MEMBER_EXPORT_FORMATS.contains(&format) {
+        bail!("지원하지 않는 내보내기 형식: {}", format);
+    }
+    let content = collect(db, member_id, exported_at).await?;
+    let data = match format {
+        "zip" => {
+            let mut zip = ZipWriter::new();
+            let files = [
+                ("member.json", &content.member),
+                ("markers.json", &content.markers),
+                ("markers.geojson", &content.geojson),
+                ("images.json", &content.images),
+                ("interactions.json", &content.interactions),
+            ];
+            for (name, value) in files {
+                zip.add_file(name, &serde_json::to_vec_pretty(value)?, exported_at)?;
+            }
+            zip.finish()?
+        }
+        _ => serde_json::to_vec(&json!({
+            "exportedAt": exported_at,
+            "member": content.member,
+            "markers": content.markers,
+            "geojson": content.geojson,
+            "images": content.images,
+            "interactions": content.interactions
+        }))?,
+    };
+    Ok((data, content.marker_count))
+}
+
+/// 대기 중인 작업 하나 처리 (처리할 작업이 있었으면 true)
+pub async fn process_next_member_export(db: &Database, storage: &dyn Storage) -> Result<bool> {
+    let Some(job) = db.claim_member_data_export(STALE_EXPORT_SECS).await? else {
+        return Ok(false);
+    };
+    info!("📦 개인 데이터 내보내기 시작: 작업 {} (회원 {}, {})", job.id, job.member_id, job.format);
+
+    let result = async {
+        let (data, marker_count) = export_member_data(db, job.member_id, &job.format, job.created_at).await?;
+        let key = storage_key(&job);
+        let file_size = data.len() as i64;
+        storage.put(&key, data, content_type(&job.format)).await?;
+        Ok::<_, anyhow::Error>((key, marker_count, file_size))
+    }.await;
+
+    match result {
+        Ok((key, marker_count, file_size)) => {
+            db.complete_member_data_export(job.id, &key, marker_count, file_size).await?;
+            info!("✅ 개인 데이터 내보내기 완료: 작업 {} (마커 {}개, {}바이트)", job.id, marker_count, file_size);
+        }
+        Err(e) => {
+            // 회원이 다시 요청하면 새 작업으로 재시도되므로 dead letter로 보내지 않음
+            warn!("⚠️ 개인 데이터 내보내기 실패: 작업 {} - {}", job.id, e);
+            db.fail_member_data_export(job.id, &e.to_string()).await?;
+        }
+    }
+    Ok(true)
+}
+
+fn storage_key(job: &MemberDataExport) -> String {
+    format!("member-exports/{}/{}.{}", job.member_id, job.id, job.format)
+}
+
+/// 주기적으로 개인 데이터 내보내기 작업 처리
+pub async fn run_member_export_worker(db: Database, storage: Arc<dyn Storage>) {
+    info!("📦 개인 데이터 내보내기 작업 처리기 시작 ({}초 간격)", EXPORT_POLL_INTERVAL_SECS);
+    let mut interval = tokio::time::interval(Duration::from_secs(EXPORT_POLL_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        loop {
+            match process_next_member_export(&db, storage.as_ref()).await {
+                Ok(true) => continue,
+                Ok(false) => break,
+                Err(e) => {
+                    error!("❌ 개인 데이터 내보내기 작업 처리 실패: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
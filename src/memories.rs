@@ -0,0 +1,36 @@
+// "지난 오늘" 알림 스케줄러 (매일 아침 한국 시간 기준으로 알림 생성)
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use log::{error, info};
+
+use crate::database::Database;
+
+const KST_OFFSET_SECS: i32 = 9 * 3600;
+
+/// 다음 알림 생성 시각 (한국 시간 hour시 정각)
+fn next_run_at(now: DateTime<Utc>, hour: u32) -> DateTime<Utc> {
+    let kst = FixedOffset::east_opt(KST_OFFSET_SECS).expect("valid KST offset");
+    let local_now = now.with_timezone(&kst);
+    let today_run = local_now.date_naive()
+        .and_hms_opt(hour, 0, 0)
+        .and_then(|run| run.and_local_timezone(kst).single())
+        .map(|run| run.with_timezone(&Utc))
+        .unwrap_or(now);
+    if today_run > now { today_run } else { today_run + Duration::days(1) }
+}
+
+/// 서버가 떠 있는 동안 매일 한 번 알림 생성
+/// 여러 인스턴스가 실행돼도 dedupe_key로 회원당 하루 한 건만 생성됨
+pub async fn run_memory_notification_scheduler(db: Database, hour: u32) {
+    info!("⏰ 지난 오늘 알림 스케줄러 시작 (매일 {}시 KST)", hour);
+    loop {
+        let now = Utc::now();
+        let next = next_run_at(now, hour);
+        let wait = (next - now).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+
+        match db.create_memory_notifications().await {
+            Ok(created) => info!("🔔 지난 오늘 알림 {}건 생성", created),
+            Err(e) => error!("❌ 지난 오늘 알림 생성 실패: {}", e),
+        }
+    }
+}
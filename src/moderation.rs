@@ -0,0 +1,366 @@
+// 업로드 이미지 검수 (AWS Rekognition 또는 로컬 ONNX 분류기 서버)
+// 업로드 때 검수해 image_moderation에 pending/approved/rejected로 기록하고, 거절된 이미지는 DB 트리거가 마커 이미지/썸네일에서 내림
+// 검수기 오류나 시간 초과는 업로드를 막지 않고 pending으로 남겨 재시도 작업이 다시 검수
+use anyhow::{anyhow, bail, Result};
+use aws_credential_types::provider::{ProvideCredentials, SharedCredentialsProvider};
+use aws_sigv4::http_request::{sign, SignableBody, SignableRequest, SigningSettings};
+use aws_sigv4::sign::v4;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures_util::future::BoxFuture;
+use image::codecs::jpeg::JpegEncoder;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::config::Config;
+use crate::database::Database;
+use crate::image_cleanup::StoredImage;
+use crate::image_pipeline::{ImageSource, SharedImageSource};
+use crate::image_workers::ImageWorkers;
+use crate::s3_service::S3Service;
+use crate::storage::Storage;
+
+pub const MODERATION_BACKENDS: [&str; 3] = ["none", "rekognition", "onnx"];
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+/// 업로드 응답을 이보다 오래 붙잡지 않음 (넘으면 pending으로 두고 재시도)
+const MODERATION_TIMEOUT_SECS: u64 = 15;
+/// 검수기로 보내는 이미지의 긴 변 (Rekognition은 5MB 이하 JPEG/PNG만 받음)
+const MODERATION_MAX_SIDE: u32 = 1024;
+const MODERATION_JPEG_QUALITY: u8 = 85;
+/// 재시도 작업이 한 번에 가져가는 이미지 수
+const MODERATION_BATCH_SIZE: i64 = 20;
+const MODERATION_RETRY_INTERVAL_SECS: u64 = 60;
+/// 이만큼 실패하면 더 재시도하지 않음 (pending으로 남아 공개는 유지)
+const MAX_MODERATION_ATTEMPTS: i32 = 5;
+const REKOGNITION_TARGET: &str = "RekognitionService.DetectModerationLabels";
+const REKOGNITION_CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+/// ONNX 분류기 클래스 중 거절 사유가 아닌 것
+const ONNX_SAFE_LABELS: [&str; 3] = ["safe", "neutral", "drawings"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl ModerationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModerationStatus::Pending => "pending",
+            ModerationStatus::Approved => "approved",
+            ModerationStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// 검수 결과 (거절 사유 라벨이 하나라도 있으면 거절)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModerationVerdict {
+    pub status: ModerationStatus,
+    pub labels: Vec<String>,
+}
+
+impl ModerationVerdict {
+    pub fn from_labels(mut labels: Vec<String>) -> Self {
+        labels.sort();
+        labels.dedup();
+        let status = if labels.is_empty() { ModerationStatus::Approved } else { ModerationStatus::Rejected };
+        Self { status, labels }
+    }
+}
+
+/// 이미지 검수기 (테스트에서는 가짜 검수기로 교체)
+pub trait ImageModerator: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn moderate<'a>(&'a self, jpeg: &'a [u8]) -> BoxFuture<'a, Result<ModerationVerdict>>;
+}
+
+/// AWS Rekognition DetectModerationLabels (S3와 같은 자격 증명으로 SigV4 서명)
+pub struct RekognitionModerator {
+    client: reqwest::Client,
+    region: String,
+    credentials: SharedCredentialsProvider,
+    min_confidence: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DetectModerationLabelsResponse {
+    #[serde(default)]
+    moderation_labels: Vec<RekognitionLabel>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RekognitionLabel {
+    name: String,
+}
+
+impl RekognitionModerator {
+    pub fn new(region: &str, credentials: SharedCredentialsProvider, min_confidence: f64) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+            region: region.to_string(),
+            credentials,
+            min_confidence,
+        }
+    }
+
+    async fn detect(&self, jpeg: &[u8]) -> Result<ModerationVerdict> {
+        let url = format!("https://rekognition.{}.amazonaws.com/", self.region);
+        let body = serde_json::to_vec(&serde_json::json!({
+            "Image": { "Bytes": BASE64.encode(jpeg) },
+            "MinConfidence": self.min_confidence,
+        }))?;
+
+        let identity = self.credentials.provide_credentials().await?.into();
+        let params = v4::SigningParams::builder()
+            .identity(&identity)
+            .region(&self.region)
+            .name("rekognition")
+            .time(SystemTime::now())
+            .settings(SigningSettings::default())
+            .build()?
+            .into();
+        let headers = [("content-type", REKOGNITION_CONTENT_TYPE), ("x-amz-target", REKOGNITION_TARGET)];
+        let signable = SignableRequest::new("POST", url.as_str(), headers.into_iter(), SignableBody::Bytes(&body))?;
+        let (instructions, _) = sign(signable, &params)?.into_parts();
+
+        let mut request = self.client.post(&url);
+        for (name, value) in headers.into_iter().chain(instructions.headers()) {
+            request = request.header(name, value);
+        }
+        let response = request.body(body).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            bail!("Rekognition 응답 오류 {}: {}", status, detail);
+        }
+        let labels: DetectModerationLabelsResponse = response.json().await?;
+        Ok(ModerationVerdict::from_labels(labels.moderation_labels.into_iter().map(|label| label.name).collect()))
+    }
+}
+
+impl ImageModerator for RekognitionModerator {
+    fn name(&self) -> &'static str {
+        "rekognition"
+    }
+
+    fn moderate<'a>(&'a self, jpeg: &'a [u8]) -> BoxFuture<'a, Result<ModerationVerdict>> {
+        Box::pin(self.detect(jpeg))
+    }
+}
+
+/// 로컬 ONNX 분류기 서버 (JPEG 본문을 POST하면 {"labels": [{"name": "porn", "score": 0.93}, ...]} 반환)
+pub struct OnnxModerator {
+    client: reqwest::Client,
+    url: String,
+    reject_score: f64,
+}
+
+#[derive(Deserialize)]
+struct OnnxClassification {
+    #[serde(default)]
+    labels: Vec<OnnxLabel>,
+}
+
+#[derive(Deserialize)]
+struct OnnxLabel {
+    name: String,
+    score: f64,
+}
+
+impl OnnxModerator {
+    pub fn new(url: &str, reject_score: f64) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+            url: url.to_string(),
+            reject_score,
+        }
+    }
+
+    async fn classify(&self, jpeg: &[u8]) -> Result<ModerationVerdict> {
+        let response = self.client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "image/jpeg")
+            .body(jpeg.to_vec())
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            bail!("ONNX 분류기 응답 오류 {}", response.status());
+        }
+        let classification: OnnxClassification = response.json().await?;
+        Ok(ModerationVerdict::from_labels(
+            classification.labels.into_iter()
+                .filter(|label| !ONNX_SAFE_LABELS.contains(&label.name.to_lowercase().as_str()))
+                .filter(|label| label.score >= self.reject_score)
+                .map(|label| label.name)
+                .collect(),
+        ))
+    }
+}
+
+impl ImageModerator for OnnxModerator {
+    fn name(&self) -> &'static str {
+        "onnx"
+    }
+
+    fn moderate<'a>(&'a self, jpeg: &'a [u8]) -> BoxFuture<'a, Result<ModerationVerdict>> {
+        Box::pin(self.classify(jpeg))
+    }
+}
+
+/// 설정된 검수기 (MODERATION_BACKEND=none이면 검수하지 않고 기록도 남기지 않음)
+#[derive(Clone, Default)]
+pub struct Moderation {
+    moderator: Option<Arc<dyn ImageModerator>>,
+}
+
+impl Moderation {
+    pub fn new(moderator: Arc<dyn ImageModerator>) -> Self {
+        Self { moderator: Some(moderator) }
+    }
+
+    pub fn from_config(config: &Config, s3_service: &S3Service) -> Self {
+        match config.moderation_backend.as_str() {
+            "rekognition" => match s3_service.credentials() {
+                Some(credentials) => Self::new(Arc::new(RekognitionModerator::new(
+                    &config.moderation_region,
+                    credentials,
+                    config.moderation_min_confidence,
+                ))),
+                None => {
+                    warn!("⚠️ AWS 자격 증명이 없어 이미지 검수를 사용하지 않습니다");
+                    Self::default()
+                }
+            },
+            "onnx" if config.moderation_onnx_url.is_empty() => {
+                warn!("⚠️ MODERATION_ONNX_URL이 없어 이미지 검수를 사용하지 않습니다");
+                Self::default()
+            }
+            "onnx" => Self::new(Arc::new(OnnxModerator::new(&config.moderation_onnx_url, config.moderation_reject_score))),
+            "none" => Self::default(),
+            other => {
+                warn!("⚠️ 알 수 없는 MODERATION_BACKEND({}), 이미지 검수를 사용하지 않습니다 ({} 중 하나)", other, MODERATION_BACKENDS.join(", "));
+                Self::default()
+            }
+        }
+    }
+
+    pub fn moderator(&self) -> Option<&Arc<dyn ImageModerator>> {
+        self.moderator.as_ref()
+    }
+
+    /// 업로드한 이미지 검수 (검수기가 없으면 None)
+    /// derived_keys는 같은 업로드로 만든 썸네일 등으로, 검수한 이미지와 같은 결과를 따름
+    /// 검수용 JPEG 변환은 workers의 블로킹 스레드에서 실행
+    pub async fn review(
+        &self,
+        db: &Database,
+        workers: &ImageWorkers,
+        storage_key: &str,
+        derived_keys: &[String],
+        source: SharedImageSource,
+    ) -> Option<ModerationStatus> {
+        let moderator = self.moderator.as_ref()?;
+        if let Err(e) = db.save_image_moderation_pending(storage_key, derived_keys, moderator.name()).await {
+            warn!("⚠️ 이미지 검수 기록 실패: {}", e);
+            return Some(ModerationStatus::Pending);
+        }
+        let jpeg = match workers.run(move || moderation_jpeg(source.as_source())).await {
+            Ok(jpeg) => jpeg,
+            Err(e) => {
+                warn!("⚠️ 검수용 이미지 변환 실패: {}", e);
+                if let Err(e) = db.record_image_moderation_failure(storage_key, &e.to_string()).await {
+                    warn!("⚠️ 이미지 검수 기록 실패: {}", e);
+                }
+                return Some(ModerationStatus::Pending);
+            }
+        };
+        Some(moderate_and_record(db, moderator.as_ref(), storage_key, &jpeg).await)
+    }
+}
+
+/// 검수기로 보낼 JPEG (EXIF 방향대로 세우고 긴 변을 줄임)
+pub fn moderation_jpeg(source: ImageSource<'_>) -> Result<Vec<u8>> {
+    let image = source.decode_upright()?.thumbnail(MODERATION_MAX_SIDE, MODERATION_MAX_SIDE).to_rgb8();
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, MODERATION_JPEG_QUALITY).encode_image(&image)?;
+    Ok(jpeg)
+}
+
+/// 검수하고 결과를 기록 (실패/시간 초과는 pending)
+async fn moderate_and_record(db: &Database, moderator: &dyn ImageModerator, storage_key: &str, jpeg: &[u8]) -> ModerationStatus {
+    let result = match tokio::time::timeout(Duration::from_secs(MODERATION_TIMEOUT_SECS), moderator.moderate(jpeg)).await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow!("검수 시간 초과 ({}초)", MODERATION_TIMEOUT_SECS)),
+    };
+    let recorded = match &result {
+        Ok(verdict) => db.record_image_moderation(storage_key, verdict.status.as_str(), &verdict.labels).await,
+        Err(e) => db.record_image_moderation_failure(storage_key, &e.to_string()).await,
+    };
+    if let Err(e) = recorded {
+        warn!("⚠️ 이미지 검수 기록 실패: {}", e);
+    }
+    match result {
+        Ok(verdict) => {
+            if verdict.status == ModerationStatus::Rejected {
+                info!("🚫 이미지 검수 거절: {} ({})", storage_key, verdict.labels.join(", "));
+            }
+            verdict.status
+        }
+        Err(e) => {
+            warn!("⚠️ 이미지 검수 실패 ({}), 나중에 다시 검수: {} - {}", moderator.name(), storage_key, e);
+            ModerationStatus::Pending
+        }
+    }
+}
+
+/// pending으로 남은 이미지 한 묶음 다시 검수 (처리한 이미지 수 반환)
+pub async fn process_pending_moderation(db: &Database, storage: &dyn Storage, moderator: &dyn ImageModerator) -> Result<usize> {
+    let pending = db.get_pending_image_moderation(MODERATION_BATCH_SIZE, MAX_MODERATION_ATTEMPTS).await?;
+    for storage_key in &pending {
+        let image = match StoredImage::parse(storage_key) {
+            Some(StoredImage::S3(key)) | Some(StoredImage::Local(key)) => storage.get(&key).await,
+            None => Err(anyhow!("알 수 없는 저장 위치")),
+        };
+        let jpeg = match image {
+            Ok(data) => tokio::task::spawn_blocking(move || moderation_jpeg(ImageSource::Memory(&data)))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|jpeg| jpeg),
+            Err(e) => Err(e),
+        };
+        match jpeg {
+            Ok(jpeg) => {
+                moderate_and_record(db, moderator, storage_key, &jpeg).await;
+            }
+            Err(e) => {
+                warn!("⚠️ 검수할 이미지를 읽을 수 없음: {} - {}", storage_key, e);
+                db.record_image_moderation_failure(storage_key, &e.to_string()).await?;
+            }
+        }
+    }
+    Ok(pending.len())
+}
+
+/// 주기적으로 pending 이미지 재검수
+pub async fn run_moderation_retry_worker(db: Database, storage: Arc<dyn Storage>, moderator: Arc<dyn ImageModerator>) {
+    info!("🛡️ 이미지 검수 재시도 작업 시작 ({}, {}초 간격)", moderator.name(), MODERATION_RETRY_INTERVAL_SECS);
+    let mut interval = tokio::time::interval(Duration::from_secs(MODERATION_RETRY_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        if let Err(e) = process_pending_moderation(&db, storage.as_ref(), moderator.as_ref()).await {
+            error!("❌ 이미지 검수 재시도 실패: {}", e);
+        }
+    }
+}
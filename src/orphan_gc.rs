@@ -0,0 +1,158 @@
+// 고아 파일 정리: DB가 참조하지 않는 저장소 이미지(변환 이미지, 원본, 너비별 이미지)와 파일이 없는 마커 이미지를 주기적으로 정리
+// 업로드 후 DB 기록 실패, 처리 중 실패한 이어 받는 업로드 원본, 저장소에서 직접 지운 파일 등으로 생김
+// 지금 설정된 저장소(STORAGE_BACKEND)의 이미지 폴더만 보고, 업로드 중일 수 있는 최근 파일은 건드리지 않음
+use chrono::{DateTime, Duration, Utc};
+use log::{error, info, warn};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::clock::Clock;
+use crate::database::{Database, StoredMarkerImage};
+use crate::storage::{Storage, StoredObject, PUBLIC_PREFIXES};
+
+/// 한 번에 지우는 최대 개수 (설정 실수로 전부 고아로 보이더라도 피해를 제한)
+pub const MAX_REMOVALS_PER_RUN: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedFile {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingImage {
+    pub id: i32,
+    pub marker_id: i64,
+    pub image_url: String,
+}
+
+/// 정리 대상 (dry-run이면 지울 대상만, 아니면 지운 결과까지)
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanReport {
+    pub dry_run: bool,
+    pub storage: String,
+    pub scanned_files: usize,
+    pub orphaned_file_count: usize,
+    pub orphaned_bytes: u64,
+    pub orphaned_files: Vec<OrphanedFile>, // 최대 MAX_REMOVALS_PER_RUN개 (오래된 순)
+    pub missing_image_count: usize,
+    pub missing_images: Vec<MissingImage>, // 최대 MAX_REMOVALS_PER_RUN개
+    pub deleted_files: usize,
+    pub removed_images: usize,
+    pub errors: Vec<String>,
+}
+
+/// DB 참조 키 형식 ("s3:{키}", "local:{키}")
+fn storage_key(storage: &dyn Storage, key: &str) -> String {
+    format!("{}:{}", storage.name(), key)
+}
+
+/// 지울 대상 찾기 (아무것도 지우지 않음)
+/// min_age보다 최근에 저장된 파일과 추가된 마커 이미지는 업로드/처리 중일 수 있어 제외
+pub async fn scan(db: &Database, storage: &dyn Storage, now: DateTime<Utc>, min_age: Duration) -> anyhow::Result<OrphanReport> {
+    let mut objects: Vec<StoredObject> = Vec::new();
+    for prefix in PUBLIC_PREFIXES {
+        objects.extend(storage.list(prefix).await?);
+    }
+    let referenced = db.get_referenced_image_keys().await?;
+    let cutoff = now - min_age;
+
+    let mut orphaned: Vec<&StoredObject> = objects.iter()
+        .filter(|object| object.modified.is_some_and(|modified| modified < cutoff))
+        .filter(|object| !referenced.contains(&storage_key(storage, &object.key)))
+        .collect();
+    orphaned.sort_by_key(|object| object.modified);
+
+    // 목록이 비었으면 저장소 설정이 잘못됐을 수 있으므로 파일이 없는 이미지는 찾지 않음
+    let mut missing: Vec<StoredMarkerImage> = Vec::new();
+    if !objects.is_empty() {
+        let stored: HashSet<String> = objects.iter().map(|object| storage_key(storage, &object.key)).collect();
+        let storage_prefix = storage_key(storage, "");
+        missing = db.get_stored_marker_images(&storage_prefix).await?
+            .into_iter()
+            .filter(|image| image.created_at < cutoff)
+            .filter(|image| {
+                let key = &image.storage_key[storage_prefix.len()..];
+                PUBLIC_PREFIXES.iter().any(|prefix| key.starts_with(prefix)) && !stored.contains(&image.storage_key)
+            })
+            .collect();
+    }
+
+    Ok(OrphanReport {
+        dry_run: true,
+        storage: storage.name().to_string(),
+        scanned_files: objects.len(),
+        orphaned_file_count: orphaned.len(),
+        orphaned_bytes: orphaned.iter().map(|object| object.size).sum(),
+        orphaned_files: orphaned.iter().take(MAX_REMOVALS_PER_RUN).map(|object| OrphanedFile {
+            key: object.key.clone(),
+            size: object.size,
+            last_modified: object.modified,
+        }).collect(),
+        missing_image_count: missing.len(),
+        missing_images: missing.into_iter().take(MAX_REMOVALS_PER_RUN).map(|image| MissingImage {
+            id: image.id,
+            marker_id: image.marker_id,
+            image_url: image.image_url,
+        }).collect(),
+        ..Default::default()
+    })
+}
+
+/// 찾은 대상 정리: 고아 파일은 저장소에서 삭제, 파일이 없는 마커 이미지는 마커 이미지 삭제와 같은 방식으로 삭제
+/// (대표 이미지/썸네일이면 비워 두고 남은 이미지 중에서 다시 선정)
+pub async fn collect_garbage(db: &Database, storage: &dyn Storage, now: DateTime<Utc>, min_age: Duration) -> anyhow::Result<OrphanReport> {
+    let mut report = scan(db, storage, now, min_age).await?;
+    report.dry_run = false;
+    for file in &report.orphaned_files {
+        match storage.delete(&file.key).await {
+            Ok(()) => report.deleted_files += 1,
+            Err(e) => report.errors.push(format!("{}: {}", file.key, e)),
+        }
+    }
+    for image in &report.missing_images {
+        match db.delete_marker_image(image.id).await {
+            Ok(true) => {
+                warn!("🖼️ 파일이 없는 마커 이미지 삭제: 마커 {} 이미지 {} ({})", image.marker_id, image.id, image.image_url);
+                report.removed_images += 1;
+            }
+            Ok(false) => {}
+            Err(e) => report.errors.push(format!("마커 이미지 {}: {}", image.id, e)),
+        }
+    }
+    Ok(report)
+}
+
+/// 주기적으로 고아 파일 정리 (ORPHAN_GC_INTERVAL_HOURS가 0이면 시작하지 않음)
+pub async fn run_orphan_gc_worker(db: Database, storage: Arc<dyn Storage>, clock: Arc<dyn Clock>, interval_hours: u64, min_age_hours: i64) {
+    info!("🧹 고아 파일 정리 작업 시작 ({}시간 간격, {}시간 지난 파일만)", interval_hours, min_age_hours);
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_hours * 3600));
+    // 시작 직후가 아니라 첫 주기가 지난 뒤부터 (배포 직후 업로드와 겹치지 않게)
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        match collect_garbage(&db, storage.as_ref(), clock.now(), Duration::hours(min_age_hours)).await {
+            Ok(report) => {
+                if report.deleted_files > 0 || report.removed_images > 0 {
+                    info!(
+                        "🧹 고아 파일 {}개 삭제 (전체 {}개, {:.2}MB), 파일이 없는 마커 이미지 {}개 정리 (파일 {}개 확인)",
+                        report.deleted_files,
+                        report.orphaned_file_count,
+                        report.orphaned_bytes as f64 / (1024.0 * 1024.0),
+                        report.removed_images,
+                        report.scanned_files
+                    );
+                }
+                for e in &report.errors {
+                    warn!("⚠️ 고아 파일 정리 실패: {}", e);
+                }
+            }
+            Err(e) => error!("❌ 고아 파일 정리 실패: {}", e),
+        }
+    }
+}
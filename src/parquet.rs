@@ -0,0 +1,380 @@
+// 분석용 내보내기에 필요한 만큼만 구현한 Parquet 작성기
+// 평면 스키마, PLAIN 인코딩, 무압축, 열마다 데이터 페이지 하나 (https://parquet.apache.org/docs/file-format/)
+// 메타데이터는 Thrift compact protocol로 직접 기록
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use std::io::Write;
+
+const MAGIC: &[u8; 4] = b"PAR1";
+/// 행 그룹 하나에 모으는 행 수 (이만큼 메모리에 버퍼링)
+const ROW_GROUP_SIZE: usize = 10_000;
+
+// parquet.thrift 열거값
+const TYPE_INT32: i32 = 1;
+const TYPE_INT64: i32 = 2;
+const TYPE_DOUBLE: i32 = 5;
+const TYPE_BYTE_ARRAY: i32 = 6;
+const REPETITION_REQUIRED: i32 = 0;
+const REPETITION_OPTIONAL: i32 = 1;
+const CONVERTED_UTF8: i32 = 0;
+const CONVERTED_TIMESTAMP_MICROS: i32 = 10;
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const CODEC_UNCOMPRESSED: i32 = 0;
+const PAGE_TYPE_DATA: i32 = 0;
+
+/// 열 타입
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnType {
+    Int32,
+    Int64,
+    Double,
+    Utf8,
+    TimestampMicros,
+}
+
+impl ColumnType {
+    fn physical(self) -> i32 {
+        match self {
+            ColumnType::Int32 => TYPE_INT32,
+            ColumnType::Int64 | ColumnType::TimestampMicros => TYPE_INT64,
+            ColumnType::Double => TYPE_DOUBLE,
+            ColumnType::Utf8 => TYPE_BYTE_ARRAY,
+        }
+    }
+
+    fn converted(self) -> Option<i32> {
+        match self {
+            ColumnType::Utf8 => Some(CONVERTED_UTF8),
+            ColumnType::TimestampMicros => Some(CONVERTED_TIMESTAMP_MICROS),
+            _ => None,
+        }
+    }
+}
+
+/// 열 정의 (optional이면 NULL 허용)
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: &'static str,
+    pub column_type: ColumnType,
+    pub optional: bool,
+}
+
+/// 한 칸의 값
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Int32(i32),
+    Int64(i64),
+    Double(f64),
+    Utf8(String),
+    Timestamp(DateTime<Utc>),
+}
+
+struct ColumnChunkMeta {
+    num_values: i64,
+    data_page_offset: i64,
+    total_size: i64,
+}
+
+struct RowGroupMeta {
+    num_rows: i64,
+    total_byte_size: i64,
+    columns: Vec<ColumnChunkMeta>,
+}
+
+/// 행 단위로 받아 행 그룹마다 파일에 씀
+pub struct ParquetWriter<W: Write> {
+    out: W,
+    offset: i64,
+    columns: Vec<Column>,
+    buffered: Vec<Vec<Value>>,
+    buffered_rows: usize,
+    row_groups: Vec<RowGroupMeta>,
+}
+
+impl<W: Write> ParquetWriter<W> {
+    pub fn new(mut out: W, columns: Vec<Column>) -> Result<Self> {
+        out.write_all(MAGIC)?;
+        let buffered = columns.iter().map(|_| Vec::new()).collect();
+        Ok(Self {
+            out,
+            offset: MAGIC.len() as i64,
+            columns,
+            buffered,
+            buffered_rows: 0,
+            row_groups: Vec::new(),
+        })
+    }
+
+    pub fn write_row(&mut self, row: Vec<Value>) -> Result<()> {
+        if row.len() != self.columns.len() {
+            bail!("열 개수 불일치: {} (스키마 {})", row.len(), self.columns.len());
+        }
+        for ((column, value), values) in self.columns.iter().zip(row).zip(self.buffered.iter_mut()) {
+            if !value_matches(column, &value) {
+                bail!("열 {}에 맞지 않는 값: {:?}", column.name, value);
+            }
+            values.push(value);
+        }
+        self.buffered_rows += 1;
+        if self.buffered_rows >= ROW_GROUP_SIZE {
+            self.flush_row_group()?;
+        }
+        Ok(())
+    }
+
+    /// 남은 행을 쓰고 푸터(FileMetaData)로 마무리
+    pub fn finish(mut self) -> Result<W> {
+        self.flush_row_group()?;
+        let metadata = self.file_metadata();
+        self.out.write_all(&metadata)?;
+        self.out.write_all(&(metadata.len() as u32).to_le_bytes())?;
+        self.out.write_all(MAGIC)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn flush_row_group(&mut self) -> Result<()> {
+        if self.buffered_rows == 0 {
+            return Ok(());
+        }
+        let mut chunks = Vec::with_capacity(self.columns.len());
+        for (column, values) in self.columns.iter().zip(self.buffered.iter_mut()) {
+            let page = encode_data_page(column, values);
+            values.clear();
+            chunks.push(ColumnChunkMeta {
+                num_values: self.buffered_rows as i64,
+                data_page_offset: self.offset,
+                total_size: page.len() as i64,
+            });
+            self.out.write_all(&page)?;
+            self.offset += page.len() as i64;
+        }
+        self.row_groups.push(RowGroupMeta {
+            num_rows: self.buffered_rows as i64,
+            total_byte_size: chunks.iter().map(|chunk| chunk.total_size).sum(),
+            columns: chunks,
+        });
+        self.buffered_rows = 0;
+        Ok(())
+    }
+
+    fn file_metadata(&self) -> Vec<u8> {
+        let mut w = CompactWriter::default();
+        w.i32_field(1, 1); // version
+        w.list_field(2, CT_STRUCT, self.columns.len() + 1);
+        w.struct_begin();
+        w.string_field(4, "schema");
+        w.i32_field(5, self.columns.len() as i32);
+        w.struct_end();
+        for column in &self.columns {
+            w.struct_begin();
+            w.i32_field(1, column.column_type.physical());
+            w.i32_field(3, if column.optional { REPETITION_OPTIONAL } else { REPETITION_REQUIRED });
+            w.string_field(4, column.name);
+            if let Some(converted) = column.column_type.converted() {
+                w.i32_field(6, converted);
+            }
+            w.struct_end();
+        }
+        w.i64_field(3, self.row_groups.iter().map(|group| group.num_rows).sum());
+        w.list_field(4, CT_STRUCT, self.row_groups.len());
+        for group in &self.row_groups {
+            w.struct_begin();
+            w.list_field(1, CT_STRUCT, group.columns.len());
+            for (column, chunk) in self.columns.iter().zip(&group.columns) {
+                w.struct_begin();
+                w.i64_field(2, chunk.data_page_offset); // file_offset
+                w.struct_field_begin(3); // ColumnMetaData
+                w.i32_field(1, column.column_type.physical());
+                w.list_field(2, CT_I32, 2);
+                w.i32_element(ENCODING_PLAIN);
+                w.i32_element(ENCODING_RLE);
+                w.list_field(3, CT_BINARY, 1);
+                w.string_element(column.name);
+                w.i32_field(4, CODEC_UNCOMPRESSED);
+                w.i64_field(5, chunk.num_values);
+                w.i64_field(6, chunk.total_size);
+                w.i64_field(7, chunk.total_size);
+                w.i64_field(9, chunk.data_page_offset);
+                w.struct_end();
+                w.struct_end();
+            }
+            w.i64_field(2, group.total_byte_size);
+            w.i64_field(3, group.num_rows);
+            w.struct_end();
+        }
+        w.string_field(6, "bigpictureback");
+        w.stop();
+        w.buf
+    }
+}
+
+fn value_matches(column: &Column, value: &Value) -> bool {
+    match (column.column_type, value) {
+        (_, Value::Null) => column.optional,
+        (ColumnType::Int32, Value::Int32(_))
+        | (ColumnType::Int64, Value::Int64(_))
+        | (ColumnType::Double, Value::Double(_))
+        | (ColumnType::Utf8, Value::Utf8(_))
+        | (ColumnType::TimestampMicros, Value::Timestamp(_)) => true,
+        _ => false,
+    }
+}
+
+/// 페이지 헤더 + (정의 레벨) + PLAIN 값
+fn encode_data_page(column: &Column, values: &[Value]) -> Vec<u8> {
+    let mut body = Vec::new();
+    if column.optional {
+        let levels = encode_definition_levels(values);
+        body.extend_from_slice(&(levels.len() as u32).to_le_bytes());
+        body.extend_from_slice(&levels);
+    }
+    for value in values {
+        match value {
+            Value::Null => {}
+            Value::Int32(v) => body.extend_from_slice(&v.to_le_bytes()),
+            Value::Int64(v) => body.extend_from_slice(&v.to_le_bytes()),
+            Value::Double(v) => body.extend_from_slice(&v.to_le_bytes()),
+            Value::Utf8(s) => {
+                body.extend_from_slice(&(s.len() as u32).to_le_bytes());
+                body.extend_from_slice(s.as_bytes());
+            }
+            Value::Timestamp(t) => body.extend_from_slice(&t.timestamp_micros().to_le_bytes()),
+        }
+    }
+
+    let mut header = CompactWriter::default();
+    header.i32_field(1, PAGE_TYPE_DATA);
+    header.i32_field(2, body.len() as i32);
+    header.i32_field(3, body.len() as i32);
+    header.struct_field_begin(5); // DataPageHeader
+    header.i32_field(1, values.len() as i32);
+    header.i32_field(2, ENCODING_PLAIN);
+    header.i32_field(3, ENCODING_RLE);
+    header.i32_field(4, ENCODING_RLE);
+    header.struct_end();
+    header.stop();
+
+    let mut page = header.buf;
+    page.extend_from_slice(&body);
+    page
+}
+
+/// 비트 폭 1의 RLE/bit-packed hybrid (전부 bit-packed 실행 하나로 기록)
+fn encode_definition_levels(values: &[Value]) -> Vec<u8> {
+    let groups = values.len().div_ceil(8);
+    let mut out = Vec::with_capacity(groups + 5);
+    write_uleb128(&mut out, ((groups as u64) << 1) | 1);
+    for chunk in values.chunks(8) {
+        let byte = chunk.iter().enumerate()
+            .filter(|(_, value)| **value != Value::Null)
+            .fold(0u8, |byte, (i, _)| byte | (1 << i));
+        out.push(byte);
+    }
+    out
+}
+
+fn write_uleb128(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+// Thrift compact protocol 타입 번호
+const CT_I32: u8 = 5;
+const CT_I64: u8 = 6;
+const CT_BINARY: u8 = 8;
+const CT_LIST: u8 = 9;
+const CT_STRUCT: u8 = 12;
+
+/// Thrift compact protocol 작성기 (필드 id는 직전 필드와의 차이로 기록)
+#[derive(Default)]
+struct CompactWriter {
+    buf: Vec<u8>,
+    last_field: i16,
+    stack: Vec<i16>,
+}
+
+impl CompactWriter {
+    fn field_header(&mut self, id: i16, field_type: u8) {
+        let delta = id - self.last_field;
+        if (1..=15).contains(&delta) {
+            self.buf.push(((delta as u8) << 4) | field_type);
+        } else {
+            self.buf.push(field_type);
+            self.varint(zigzag(i64::from(id)));
+        }
+        self.last_field = id;
+    }
+
+    fn varint(&mut self, value: u64) {
+        write_uleb128(&mut self.buf, value);
+    }
+
+    fn i32_field(&mut self, id: i16, value: i32) {
+        self.field_header(id, CT_I32);
+        self.i32_element(value);
+    }
+
+    fn i64_field(&mut self, id: i16, value: i64) {
+        self.field_header(id, CT_I64);
+        self.varint(zigzag(value));
+    }
+
+    fn string_field(&mut self, id: i16, value: &str) {
+        self.field_header(id, CT_BINARY);
+        self.string_element(value);
+    }
+
+    fn list_field(&mut self, id: i16, element_type: u8, len: usize) {
+        self.field_header(id, CT_LIST);
+        if len < 15 {
+            self.buf.push(((len as u8) << 4) | element_type);
+        } else {
+            self.buf.push(0xf0 | element_type);
+            self.varint(len as u64);
+        }
+    }
+
+    fn i32_element(&mut self, value: i32) {
+        self.varint(zigzag(i64::from(value)));
+    }
+
+    fn string_element(&mut self, value: &str) {
+        self.varint(value.len() as u64);
+        self.buf.extend_from_slice(value.as_bytes());
+    }
+
+    /// 구조체 필드 시작 (struct_end로 닫음)
+    fn struct_field_begin(&mut self, id: i16) {
+        self.field_header(id, CT_STRUCT);
+        self.struct_begin();
+    }
+
+    /// 리스트 원소 구조체 시작
+    fn struct_begin(&mut self) {
+        self.stack.push(self.last_field);
+        self.last_field = 0;
+    }
+
+    fn struct_end(&mut self) {
+        self.stop();
+        self.last_field = self.stack.pop().unwrap_or(0);
+    }
+
+    fn stop(&mut self) {
+        self.buf.push(0);
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
@@ -0,0 +1,262 @@
+// "지금 이 마커를 보는 사람" 실시간 집계 (대략적인 동시 접속자 수)
+// REDIS_URL이 있으면 인스턴스 간 공유되는 Redis 정렬 집합(마커별 키, TTL)을 쓰고,
+// 없거나 Redis 오류 시에는 인스턴스 메모리에만 기록
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufStream};
+use tokio::net::TcpStream;
+
+use crate::config::Config;
+
+const REDIS_TIMEOUT_MS: u64 = 500;
+const KEY_PREFIX: &str = "bigpicture:presence:marker:";
+/// 메모리 저장소에서 만료 항목을 정리하기 시작하는 마커 수
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// 마커 ID → (시청자 키 → 만료 시각)
+type LocalViewers = HashMap<i64, HashMap<String, DateTime<Utc>>>;
+
+/// 마커별 시청자 기록
+#[derive(Clone)]
+pub struct MarkerPresence {
+    redis: Option<Arc<RedisClient>>,
+    local: Arc<Mutex<LocalViewers>>,
+    ttl: Duration,
+}
+
+impl MarkerPresence {
+    pub fn new(config: &Config) -> Self {
+        let redis = match config.redis_url.trim() {
+            "" => None,
+            url => match RedisClient::from_url(url) {
+                Ok(client) => {
+                    info!("✅ 실시간 시청자 집계: Redis 사용 ({})", client.addr);
+                    Some(Arc::new(client))
+                }
+                Err(e) => {
+                    warn!("⚠️ REDIS_URL 해석 실패, 메모리 집계 사용: {}", e);
+                    None
+                }
+            },
+        };
+        Self {
+            redis,
+            local: Arc::new(Mutex::new(HashMap::new())),
+            ttl: Duration::seconds(config.presence_ttl_secs),
+        }
+    }
+
+    /// 시청 중으로 기록(갱신)하고 현재 시청자 수 반환
+    pub async fn touch(&self, marker_id: i64, viewer: &str, now: DateTime<Utc>) -> u64 {
+        if let Some(redis) = &self.redis {
+            let key = format!("{}{}", KEY_PREFIX, marker_id);
+            let expires_at = (now + self.ttl).timestamp_millis().to_string();
+            let now_ms = now.timestamp_millis().to_string();
+            let ttl_ms = self.ttl.num_milliseconds().to_string();
+            let commands = [
+                vec!["ZADD", &key, &expires_at, viewer],
+                vec!["ZREMRANGEBYSCORE", &key, "-inf", &now_ms],
+                vec!["ZCARD", &key],
+                vec!["PEXPIRE", &key, &ttl_ms],
+            ];
+            match redis.pipeline(&commands).await {
+                Ok(replies) => {
+                    if let Some(Reply::Integer(count)) = replies.get(2) {
+                        return (*count).max(0) as u64;
+                    }
+                    warn!("⚠️ Redis 시청자 수 응답 형식 오류: {:?}", replies.get(2));
+                }
+                Err(e) => warn!("⚠️ Redis 시청자 기록 실패, 메모리 집계 사용: {}", e),
+            }
+        }
+        self.touch_local(marker_id, viewer, now)
+    }
+
+    /// 현재 시청자 수 (기록하지 않음)
+    pub async fn count(&self, marker_id: i64, now: DateTime<Utc>) -> u64 {
+        if let Some(redis) = &self.redis {
+            let key = format!("{}{}", KEY_PREFIX, marker_id);
+            let now_ms = now.timestamp_millis().to_string();
+            let commands = [vec!["ZCOUNT", &key, &now_ms, "+inf"]];
+            match redis.pipeline(&commands).await {
+                Ok(replies) => {
+                    if let Some(Reply::Integer(count)) = replies.first() {
+                        return (*count).max(0) as u64;
+                    }
+                }
+                Err(e) => warn!("⚠️ Redis 시청자 수 조회 실패, 메모리 집계 사용: {}", e),
+            }
+        }
+        let local = self.local.lock().unwrap();
+        local.get(&marker_id)
+            .map(|viewers| viewers.values().filter(|expires_at| **expires_at > now).count() as u64)
+            .unwrap_or(0)
+    }
+
+    fn touch_local(&self, marker_id: i64, viewer: &str, now: DateTime<Utc>) -> u64 {
+        let mut local = self.local.lock().unwrap();
+        if local.len() > PRUNE_THRESHOLD {
+            local.retain(|_, viewers| {
+                viewers.retain(|_, expires_at| *expires_at > now);
+                !viewers.is_empty()
+            });
+        }
+        let viewers = local.entry(marker_id).or_default();
+        viewers.retain(|_, expires_at| *expires_at > now);
+        viewers.insert(viewer.to_string(), now + self.ttl);
+        viewers.len() as u64
+    }
+}
+
+/// RESP 응답
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reply {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<Reply>>),
+}
+
+/// 필요한 명령만 보내는 최소 Redis 클라이언트 (연결 하나를 재사용, 오류 시 다음 요청에서 재연결)
+pub struct RedisClient {
+    addr: String,
+    password: Option<String>,
+    username: Option<String>,
+    db: Option<String>,
+    conn: tokio::sync::Mutex<Option<BufStream<TcpStream>>>,
+}
+
+impl RedisClient {
+    /// redis://[user:password@]host[:port][/db]
+    pub fn from_url(url: &str) -> anyhow::Result<Self> {
+        let parsed = reqwest::Url::parse(url)?;
+        if parsed.scheme() != "redis" {
+            anyhow::bail!("지원하지 않는 Redis 주소 형식: {}", parsed.scheme());
+        }
+        let host = parsed.host_str().ok_or_else(|| anyhow::anyhow!("Redis 호스트 없음"))?;
+        let db = parsed.path().trim_start_matches('/');
+        Ok(Self {
+            addr: format!("{}:{}", host, parsed.port().unwrap_or(6379)),
+            password: parsed.password().map(str::to_string),
+            username: Some(parsed.username()).filter(|user| !user.is_empty()).map(str::to_string),
+            db: Some(db).filter(|db| !db.is_empty()).map(str::to_string),
+            conn: tokio::sync::Mutex::new(None),
+        })
+    }
+
+    /// 명령 여러 개를 한 번에 보내고 응답을 순서대로 받음
+    pub async fn pipeline(&self, commands: &[Vec<&str>]) -> anyhow::Result<Vec<Reply>> {
+        let mut guard = self.conn.lock().await;
+        let result = tokio::time::timeout(
+            std::time::Duration::from_millis(REDIS_TIMEOUT_MS),
+            self.run(&mut guard, commands),
+        ).await;
+        match result {
+            Ok(Ok(replies)) => Ok(replies),
+            Ok(Err(e)) => {
+                *guard = None;
+                Err(e)
+            }
+            Err(_) => {
+                *guard = None;
+                anyhow::bail!("Redis 응답 시간 초과 ({}ms)", REDIS_TIMEOUT_MS)
+            }
+        }
+    }
+
+    async fn run(&self, conn: &mut Option<BufStream<TcpStream>>, commands: &[Vec<&str>]) -> anyhow::Result<Vec<Reply>> {
+        if conn.is_none() {
+            *conn = Some(self.connect().await?);
+        }
+        let stream = conn.as_mut().expect("connected");
+        send_commands(stream, commands).await?;
+        let mut replies = Vec::with_capacity(commands.len());
+        for _ in commands {
+            replies.push(read_reply(stream).await?);
+        }
+        Ok(replies)
+    }
+
+    async fn connect(&self) -> anyhow::Result<BufStream<TcpStream>> {
+        let mut stream = BufStream::new(TcpStream::connect(&self.addr).await?);
+        let mut setup: Vec<Vec<&str>> = Vec::new();
+        if let Some(password) = &self.password {
+            match &self.username {
+                Some(username) => setup.push(vec!["AUTH", username, password]),
+                None => setup.push(vec!["AUTH", password]),
+            }
+        }
+        if let Some(db) = &self.db {
+            setup.push(vec!["SELECT", db]);
+        }
+        if !setup.is_empty() {
+            send_commands(&mut stream, &setup).await?;
+            for _ in &setup {
+                if let Reply::Error(e) = read_reply(&mut stream).await? {
+                    anyhow::bail!("Redis 연결 설정 실패: {}", e);
+                }
+            }
+        }
+        Ok(stream)
+    }
+}
+
+async fn send_commands(stream: &mut BufStream<TcpStream>, commands: &[Vec<&str>]) -> anyhow::Result<()> {
+    let mut buf = Vec::new();
+    for command in commands {
+        buf.extend_from_slice(format!("*{}\r\n", command.len()).as_bytes());
+        for arg in command {
+            buf.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+            buf.extend_from_slice(arg.as_bytes());
+            buf.extend_from_slice(b"\r\n");
+        }
+    }
+    stream.write_all(&buf).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn read_line(stream: &mut BufStream<TcpStream>) -> anyhow::Result<String> {
+    let mut line = String::new();
+    if stream.read_line(&mut line).await? == 0 {
+        anyhow::bail!("Redis 연결이 닫힘");
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+async fn read_reply(stream: &mut BufStream<TcpStream>) -> anyhow::Result<Reply> {
+    let line = read_line(stream).await?;
+    let (kind, rest) = line.split_at(line.chars().next().map(char::len_utf8).unwrap_or(0));
+    Ok(match kind {
+        "+" => Reply::Simple(rest.to_string()),
+        "-" => Reply::Error(rest.to_string()),
+        ":" => Reply::Integer(rest.parse()?),
+        "$" => {
+            let len: i64 = rest.parse()?;
+            if len < 0 {
+                Reply::Bulk(None)
+            } else {
+                let mut data = vec![0; len as usize + 2];
+                stream.read_exact(&mut data).await?;
+                data.truncate(len as usize);
+                Reply::Bulk(Some(data))
+            }
+        }
+        "*" => {
+            let len: i64 = rest.parse()?;
+            if len < 0 {
+                Reply::Array(None)
+            } else {
+                let mut items = Vec::with_capacity(len as usize);
+                for _ in 0..len {
+                    items.push(Box::pin(read_reply(stream)).await?);
+                }
+                Reply::Array(Some(items))
+            }
+        }
+        _ => anyhow::bail!("알 수 없는 Redis 응답: {}", line),
+    })
+}
@@ -0,0 +1,75 @@
+// 프로모션(광고) 마커를 피드/클러스터 응답에 끼워 넣기
+// 끼워 넣은 마커는 isPromoted: true와 광고주 정보를 달고, 일반 마커는 isPromoted: false
+use serde_json::{json, Value};
+
+use crate::database::MarkerPromotion;
+
+/// 피드 한 페이지에 넣는 프로모션 수와 위치 (0부터, 결과가 더 짧으면 맨 뒤)
+pub const FEED_PROMOTIONS_PER_PAGE: i64 = 1;
+pub const FEED_PROMOTION_SLOT: usize = 2;
+/// 지도 영역 안에서 따로 표시하는 프로모션 수
+pub const CLUSTER_PROMOTIONS: i64 = 2;
+
+/// 마커 JSON에 프로모션 표시 추가 (노출/클릭은 클라이언트가 impressionUrl/clickUrl로 보고)
+pub fn promoted_marker_json(promotion: &MarkerPromotion, mut marker: Value) -> Value {
+    if let Some(obj) = marker.as_object_mut() {
+        obj.insert("isPromoted".to_string(), json!(true));
+        obj.insert("promotion".to_string(), json!({
+            "id": promotion.id,
+            "sponsorName": promotion.sponsor_name,
+            "impressionUrl": format!("/api/promotions/{}/impression", promotion.id),
+            "clickUrl": format!("/api/promotions/{}/click", promotion.id)
+        }));
+    }
+    marker
+}
+
+fn marker_id(marker: &Value) -> Option<i64> {
+    marker.get("id").and_then(Value::as_i64)
+}
+
+fn mark_organic(marker: &mut Value) {
+    if let Some(obj) = marker.as_object_mut() {
+        obj.entry("isPromoted").or_insert(json!(false));
+    }
+}
+
+/// 피드 목록에 프로모션 삽입 (같은 마커가 일반 결과에도 있으면 일반 쪽을 뺌)
+pub fn inject_into_feed(markers: &mut Vec<Value>, promoted: Vec<Value>) {
+    let promoted_ids: Vec<i64> = promoted.iter().filter_map(marker_id).collect();
+    markers.retain(|marker| !marker_id(marker).is_some_and(|id| promoted_ids.contains(&id)));
+    markers.iter_mut().for_each(mark_organic);
+    for (offset, marker) in promoted.into_iter().enumerate() {
+        let slot = (FEED_PROMOTION_SLOT + offset).min(markers.len());
+        markers.insert(slot, marker);
+    }
+}
+
+/// 클러스터 목록 앞에 프로모션 마커를 단독 클러스터로 추가
+/// 일반 클러스터에 같은 마커가 있으면 빼고 개수/ID 목록을 맞춤 (비게 되면 클러스터 제거)
+pub fn inject_into_clusters(clusters: &mut Vec<Value>, promoted: Vec<Value>) {
+    let promoted_ids: Vec<i64> = promoted.iter().filter_map(marker_id).collect();
+    for cluster in clusters.iter_mut() {
+        if let Some(markers) = cluster.get_mut("markers").and_then(Value::as_array_mut) {
+            markers.retain(|marker| !marker_id(marker).is_some_and(|id| promoted_ids.contains(&id)));
+            markers.iter_mut().for_each(mark_organic);
+            let count = markers.len();
+            cluster["count"] = json!(count);
+        }
+        if let Some(ids) = cluster.get_mut("marker_ids").and_then(Value::as_array_mut) {
+            ids.retain(|id| !id.as_i64().is_some_and(|id| promoted_ids.contains(&id)));
+        }
+    }
+    clusters.retain(|cluster| cluster.get("count").and_then(Value::as_u64) != Some(0));
+
+    let promoted_clusters = promoted.into_iter().map(|marker| json!({
+        "h3_index": null,
+        "lat": marker.get("latitude"),
+        "lng": marker.get("longitude"),
+        "count": 1,
+        "marker_ids": [marker_id(&marker)],
+        "isPromoted": true,
+        "markers": [marker]
+    }));
+    clusters.splice(0..0, promoted_clusters);
+}
@@ -0,0 +1,21 @@
+// 마커 공개 ID (공유 링크, QR 코드, 임베드, oEmbed에서 숫자 ID 대신 사용)
+// 값은 DB 기본값(bigpicture.generate_public_id, migrations/0017)이 만들고, 여기서는 주소로 받은 값의 형식만 확인해서
+// 형식이 틀린 요청은 DB를 조회하지 않고 404로 돌려보냄
+
+pub const PUBLIC_ID_LENGTH: usize = 10;
+/// 모음, 모음처럼 읽히는 숫자(0 1 3 4), 헷갈리는 글자(l I O)를 뺀 45자 (DB 함수와 같아야 함)
+pub const PUBLIC_ID_ALPHABET: &str = "256789bcdfghjkmnpqrstvwxzBCDFGHJKLMNPQRSTVWXZ";
+
+pub fn is_valid(value: &str) -> bool {
+    value.len() == PUBLIC_ID_LENGTH && value.chars().all(|ch| PUBLIC_ID_ALPHABET.contains(ch))
+}
+
+/// 공유 링크 (QR 코드에도 이 주소를 담음)
+pub fn share_url(web_app_url: &str, public_id: &str) -> String {
+    format!("{}/m/{}", web_app_url.trim_end_matches('/'), public_id)
+}
+
+/// 공유 토큰 링크 (POST /api/markers/{id}/share, 토큰도 같은 DB 함수로 만들어 형식이 같음)
+pub fn share_token_url(api_base_url: &str, token: &str) -> String {
+    format!("{}/s/{}", api_base_url.trim_end_matches('/'), token)
+}
@@ -0,0 +1,218 @@
+// 푸시 알림 발송 (FCM HTTP v1, iOS 기기도 FCM이 APNs로 전달)
+// 알림함(notifications)에 push_status = 'pending'으로 쌓인 알림을 워커가 가져가 회원의 모든 기기로 보냄
+use anyhow::{anyhow, bail, Result};
+use chrono::Duration as ChronoDuration;
+use futures_util::future::BoxFuture;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::database::{Database, PendingPush};
+
+pub const PUSH_PLATFORMS: [&str; 3] = ["ios", "android", "web"];
+const FCM_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+const FCM_SEND_URL: &str = "https://fcm.googleapis.com/v1/projects";
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+/// 액세스 토큰 만료 이만큼 전에 새로 받음
+const TOKEN_REFRESH_MARGIN_SECS: u64 = 60;
+/// 워커가 한 번에 가져가는 알림 수
+const PUSH_BATCH_SIZE: i64 = 100;
+const PUSH_INTERVAL_SECS: u64 = 5;
+
+/// 기기로 보낼 내용 (data는 앱이 알림을 눌렀을 때 이동할 화면 정보)
+pub struct PushMessage {
+    pub title: String,
+    pub body: Option<String>,
+    pub data: Vec<(String, String)>,
+}
+
+impl PushMessage {
+    pub fn from_notification(push: &PendingPush) -> Self {
+        let mut data = vec![
+            ("notificationId".to_string(), push.id.to_string()),
+            ("type".to_string(), push.notification_type.clone()),
+        ];
+        if let Some(marker_id) = push.marker_ids.as_ref().and_then(|ids| ids.first()) {
+            data.push(("markerId".to_string(), marker_id.to_string()));
+        }
+        Self { title: push.title.clone(), body: push.body.clone(), data }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushResult {
+    Delivered,
+    /// 앱 삭제 등으로 더 이상 쓸 수 없는 토큰 (저장된 토큰을 지움)
+    Unregistered,
+}
+
+/// 푸시 발송 제공자 (테스트에서는 가짜 발송기로 교체)
+pub trait PushSender: Send + Sync {
+    fn send<'a>(&'a self, token: &'a str, message: &'a PushMessage) -> BoxFuture<'a, Result<PushResult>>;
+}
+
+/// Firebase 서비스 계정 JSON에서 쓰는 필드
+#[derive(Deserialize)]
+struct ServiceAccount {
+    project_id: String,
+    client_email: String,
+    private_key: String,
+    #[serde(default = "default_token_uri")]
+    token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    "https://oauth2.googleapis.com/token".to_string()
+}
+
+#[derive(Serialize)]
+struct AssertionClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// FCM HTTP v1 발송기 (서비스 계정으로 OAuth 액세스 토큰을 받아 1시간 가까이 재사용)
+pub struct FcmSender {
+    client: reqwest::Client,
+    project_id: String,
+    client_email: String,
+    token_uri: String,
+    key: EncodingKey,
+    access_token: tokio::sync::Mutex<Option<(String, Instant)>>,
+}
+
+impl FcmSender {
+    pub fn from_service_account_file(path: &str) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("FCM 서비스 계정 파일을 읽을 수 없습니다 ({}): {}", path, e))?;
+        let account: ServiceAccount = serde_json::from_str(&json)?;
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+            key: EncodingKey::from_rsa_pem(account.private_key.as_bytes())?,
+            project_id: account.project_id,
+            client_email: account.client_email,
+            token_uri: account.token_uri,
+            access_token: tokio::sync::Mutex::new(None),
+        })
+    }
+
+    async fn access_token(&self) -> Result<String> {
+        let mut cached = self.access_token.lock().await;
+        let refresh_after = Instant::now() + Duration::from_secs(TOKEN_REFRESH_MARGIN_SECS);
+        if let Some((token, _)) = cached.as_ref().filter(|(_, expires_at)| refresh_after < *expires_at) {
+            return Ok(token.clone());
+        }
+        let now = chrono::Utc::now();
+        let claims = AssertionClaims {
+            iss: &self.client_email,
+            scope: FCM_SCOPE,
+            aud: &self.token_uri,
+            iat: now.timestamp(),
+            exp: (now + ChronoDuration::hours(1)).timestamp(),
+        };
+        let assertion = encode(&Header::new(Algorithm::RS256), &claims, &self.key)?;
+        let response = self.client
+            .post(&self.token_uri)
+            .form(&[("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"), ("assertion", assertion.as_str())])
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("FCM 액세스 토큰 발급 실패 {}: {}", status, body.chars().take(300).collect::<String>());
+        }
+        let token: AccessToken = response.json().await?;
+        *cached = Some((token.access_token.clone(), Instant::now() + Duration::from_secs(token.expires_in)));
+        Ok(token.access_token)
+    }
+}
+
+impl PushSender for FcmSender {
+    fn send<'a>(&'a self, token: &'a str, message: &'a PushMessage) -> BoxFuture<'a, Result<PushResult>> {
+        Box::pin(async move {
+            let data: serde_json::Map<String, serde_json::Value> = message.data.iter()
+                .map(|(key, value)| (key.clone(), serde_json::Value::String(value.clone())))
+                .collect();
+            let body = serde_json::json!({
+                "message": {
+                    "token": token,
+                    "notification": { "title": message.title, "body": message.body },
+                    "data": data,
+                    "apns": { "payload": { "aps": { "sound": "default" } } }
+                }
+            });
+            let response = self.client
+                .post(format!("{}/{}/messages:send", FCM_SEND_URL, self.project_id))
+                .bearer_auth(self.access_token().await?)
+                .json(&body)
+                .send()
+                .await?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(PushResult::Delivered);
+            }
+            let body = response.text().await.unwrap_or_default();
+            // 삭제된 앱의 토큰은 404 UNREGISTERED
+            if status == reqwest::StatusCode::NOT_FOUND || body.contains("UNREGISTERED") {
+                return Ok(PushResult::Unregistered);
+            }
+            bail!("FCM 응답 {}: {}", status, body.chars().take(300).collect::<String>())
+        })
+    }
+}
+
+/// 대기 중인 푸시를 한 번 처리하고 처리한 알림 수 반환
+/// max_age보다 오래된 대기 알림은 늦게 도착하면 혼란스러우므로 보내지 않음 (expired)
+pub async fn process_pending_pushes(db: &Database, sender: &dyn PushSender, max_age_secs: i64) -> Result<usize> {
+    let expired = db.expire_pending_pushes(max_age_secs).await?;
+    if expired > 0 {
+        info!("🔕 오래된 푸시 {}건 만료", expired);
+    }
+    let pushes = db.claim_pending_pushes(PUSH_BATCH_SIZE).await?;
+    for push in &pushes {
+        let tokens = db.get_member_device_tokens(push.member_id).await?;
+        if tokens.is_empty() {
+            db.finish_push(push.id, "no_device").await?;
+            continue;
+        }
+        let message = PushMessage::from_notification(push);
+        let mut delivered = 0;
+        for token in &tokens {
+            match sender.send(token, &message).await {
+                Ok(PushResult::Delivered) => delivered += 1,
+                Ok(PushResult::Unregistered) => {
+                    db.delete_device_token(token).await?;
+                }
+                Err(e) => warn!("⚠️ 푸시 {} 발송 실패 (회원 {}): {}", push.id, push.member_id, e),
+            }
+        }
+        db.finish_push(push.id, if delivered > 0 { "sent" } else { "failed" }).await?;
+    }
+    Ok(pushes.len())
+}
+
+/// 서버가 떠 있는 동안 대기 중인 푸시를 계속 발송 (FCM 설정이 있을 때만 spawn)
+pub async fn run_push_worker(db: Database, sender: Arc<dyn PushSender>, max_age_secs: i64) {
+    info!("📲 푸시 알림 발송 시작 ({}초마다 확인)", PUSH_INTERVAL_SECS);
+    let mut interval = tokio::time::interval(Duration::from_secs(PUSH_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        if let Err(e) = process_pending_pushes(&db, sender.as_ref(), max_age_secs).await {
+            error!("❌ 푸시 알림 처리 실패: {}", e);
+        }
+    }
+}
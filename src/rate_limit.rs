@@ -0,0 +1,121 @@
+// 인증 엔드포인트 요청 제한 (IP별 + 계정별 고정 윈도우, 무차별 대입 방지)
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web, Error, HttpResponse,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::error_handler::ErrorHandler;
+
+/// 이 개수를 넘으면 만료된 윈도우를 정리
+const PRUNE_THRESHOLD: usize = 10_000;
+
+struct Window {
+    started_at: DateTime<Utc>,
+    count: u32,
+}
+
+/// 워커 전체가 공유하는 인증 요청 카운터 (인스턴스 단위 메모리 저장)
+#[derive(Clone)]
+pub struct AuthRateLimiter {
+    windows: Arc<Mutex<HashMap<String, Window>>>,
+    rejected: Arc<AtomicU64>,
+    per_ip: u32,
+    per_account: u32,
+    window: Duration,
+    trusted_proxies: Arc<Vec<IpAddr>>,
+}
+
+impl AuthRateLimiter {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            windows: Arc::new(Mutex::new(HashMap::new())),
+            rejected: Arc::new(AtomicU64::new(0)),
+            per_ip: config.auth_rate_limit_per_ip,
+            per_account: config.auth_rate_limit_per_account,
+            window: Duration::seconds(config.auth_rate_limit_window_secs),
+            trusted_proxies: Arc::new(config.trusted_proxies.clone()),
+        }
+    }
+
+    /// IP 제한 키로 쓸 클라이언트 주소
+    /// 전달 헤더(X-Forwarded-For/Forwarded)는 클라이언트가 바꿀 수 있으므로 TRUSTED_PROXIES에서 온 요청만 믿고, 나머지는 접속 주소 사용
+    pub fn client_ip(&self, req: &ServiceRequest) -> String {
+        let Some(peer) = req.peer_addr().map(|addr| addr.ip()) else {
+            return "unknown".to_string();
+        };
+        if self.trusted_proxies.contains(&peer)
+            && let Some(forwarded) = req.connection_info().realip_remote_addr()
+        {
+            return forwarded.to_string();
+        }
+        peer.to_string()
+    }
+
+    /// 요청 1회 집계, 한도를 넘으면 다시 시도할 수 있을 때까지 남은 초 반환
+    fn hit(&self, key: String, limit: u32, now: DateTime<Utc>) -> Result<(), i64> {
+        let mut windows = self.windows.lock().unwrap();
+        if windows.len() > PRUNE_THRESHOLD {
+            let window = self.window;
+            windows.retain(|_, w| now - w.started_at < window);
+        }
+        let entry = windows.entry(key).or_insert(Window { started_at: now, count: 0 });
+        if now - entry.started_at >= self.window {
+            *entry = Window { started_at: now, count: 0 };
+        }
+        entry.count += 1;
+        if entry.count > limit {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err((entry.started_at + self.window - now).num_seconds().max(1));
+        }
+        Ok(())
+    }
+
+    pub fn check_ip(&self, ip: &str, now: DateTime<Utc>) -> Result<(), i64> {
+        self.hit(format!("ip:{}", ip), self.per_ip, now)
+    }
+
+    /// 계정(이메일 등) 기준 제한 - IP를 바꿔 가며 한 계정을 노리는 경우 대비
+    pub fn check_account(&self, account: &str, now: DateTime<Utc>) -> Result<(), i64> {
+        self.hit(format!("account:{}", account.trim().to_lowercase()), self.per_account, now)
+    }
+
+    pub fn rejected_count(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    pub fn too_many_requests(retry_after_secs: i64) -> HttpResponse {
+        ErrorHandler::too_many_requests(
+            "요청이 너무 많습니다. 잠시 후 다시 시도해주세요.",
+            Some(&format!("{}초 후 재시도 가능", retry_after_secs)),
+            retry_after_secs
+        )
+    }
+}
+
+/// 인증 리소스에 거는 IP별 제한 미들웨어 (계정별 제한은 핸들러에서 본문을 읽은 뒤 확인)
+pub async fn limit_auth_requests(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let limiter = req.app_data::<web::Data<AuthRateLimiter>>().cloned();
+    let clock = req.app_data::<web::Data<dyn Clock>>().cloned();
+    let (Some(limiter), Some(clock)) = (limiter, clock) else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+
+    let ip = limiter.client_ip(&req);
+    if let Err(retry_after) = limiter.check_ip(&ip, clock.now()) {
+        log::warn!("🚦 인증 요청 제한: {} {} (IP {})", req.method(), req.path(), ip);
+        return Ok(req.into_response(AuthRateLimiter::too_many_requests(retry_after)).map_into_right_body());
+    }
+    Ok(next.call(req).await?.map_into_left_body())
+}
@@ -0,0 +1,60 @@
+// 요청 ID (X-Request-Id): 모든 응답에 붙여 클라이언트 오류 보고와 서버 로그를 이어 봄
+// 게이트웨이나 클라이언트가 보낸 값이 올바르면 그대로 쓰고, 없으면 새로 만듦
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue},
+    middleware::Next,
+    web, Error, HttpMessage, HttpRequest,
+};
+use log::warn;
+
+use crate::clock::IdGenerator;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+/// 요청 extensions에 저장되는 요청 ID
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// 헤더/로그에 그대로 써도 되는 ID (영숫자, -, _, 64자 이하)
+pub fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// 현재 요청의 ID (미들웨어를 거치지 않았으면 None)
+pub fn request_id(req: &HttpRequest) -> Option<String> {
+    req.extensions().get::<RequestId>().map(|id| id.0.clone())
+}
+
+/// 앱 전체에 거는 미들웨어: 요청 ID를 정해 응답 헤더에 붙이고, 서버 오류는 ID와 함께 기록
+pub async fn assign_request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let incoming = req.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| is_valid_request_id(value))
+        .map(str::to_string);
+    let id = incoming.unwrap_or_else(|| {
+        let uuid = req.app_data::<web::Data<dyn IdGenerator>>()
+            .map(|ids| ids.new_id())
+            .unwrap_or_else(uuid::Uuid::new_v4);
+        uuid.simple().to_string()
+    });
+    req.extensions_mut().insert(RequestId(id.clone()));
+    let mut response = next.call(req).await?;
+
+    if response.status().is_server_error() {
+        warn!("🧾 [{}] {} {} → {}", id, response.request().method(), response.request().path(), response.status());
+    }
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+    Ok(response)
+}
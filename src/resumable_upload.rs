@@ -0,0 +1,255 @@
+// 이어 받는 업로드 (tus 1.0.0: core, creation, termination, expiration)
+// 모바일 네트워크에서 끊긴 큰 업로드를 처음부터 다시 보내지 않고, HEAD로 받은 위치(Upload-Offset)를 확인해 그 뒤부터 PATCH로 이어 보냄
+// 받은 조각은 UPLOAD_TEMP_DIR/sessions/{id}.part에 이어 쓰고 (서버가 여러 대면 같은 디스크를 쓰거나 세션을 한 서버로 보내야 함),
+// 다 받으면 일반 업로드와 같은 처리(원본 저장, 변환, 썸네일, 검수)를 하고 결과 응답을 세션에 남김
+use actix_web::{body, http::StatusCode, web, HttpResponse};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Duration;
+use futures_util::stream::StreamExt;
+use log::{info, warn};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::database::{Database, UploadSession};
+use crate::image_pipeline::ImageHooks;
+use crate::image_processor::OutputFormat;
+use crate::s3_routes::{self, ReceivedOriginal, UploadTarget};
+use crate::storage::{self, ObjectKeys, Storage};
+use crate::upload_spool::SpoolError;
+
+pub const TUS_RESUMABLE: &str = "1.0.0";
+pub const TUS_EXTENSIONS: &str = "creation,termination,expiration";
+/// PATCH 본문의 Content-Type
+pub const OFFSET_CONTENT_TYPE: &str = "application/offset+octet-stream";
+/// 브라우저 클라이언트가 읽어야 하는 tus 응답 헤더
+pub const TUS_EXPOSED_HEADERS: [&str; 7] = ["Location", "Tus-Resumable", "Tus-Version", "Tus-Extension", "Tus-Max-Size", "Upload-Offset", "Upload-Length"];
+/// 이어 받는 업로드로 받을 수 있는 이미지 종류 (원형 썸네일은 작아서 일반 업로드만)
+pub const RESUMABLE_IMAGE_TYPES: &[&str] = &["thumbnail", "map"];
+
+/// 내용 확인(매직 바이트)에 필요한 앞부분 크기
+const SNIFF_BYTES: usize = 16;
+/// 다 받은 원본을 저장소로 보낼 때 한 번에 읽는 크기
+const COPY_CHUNK_BYTES: usize = 1024 * 1024;
+const CLEANUP_INTERVAL_SECS: u64 = 3600;
+
+/// Upload-Metadata 헤더 ("filename cGhvdG8uanBn,type dGh1bWJuYWls", 값은 base64, 값 없는 키도 허용)
+pub fn parse_metadata(header: &str) -> Result<HashMap<String, String>, String> {
+    let mut metadata = HashMap::new();
+    for pair in header.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once(' ').unwrap_or((pair, ""));
+        let value = BASE64.decode(value.trim())
+            .map_err(|e| format!("{}: base64가 아닙니다 ({})", key, e))
+            .and_then(|bytes| String::from_utf8(bytes).map_err(|_| format!("{}: UTF-8이 아닙니다", key)))?;
+        if metadata.insert(key.to_string(), value).is_some() {
+            return Err(format!("{}: 같은 키가 두 번 있습니다", key));
+        }
+    }
+    Ok(metadata)
+}
+
+/// 세션별 PATCH 잠금과 받은 조각 파일 위치 (워커 전체가 공유)
+#[derive(Clone)]
+pub struct UploadSessions {
+    dir: PathBuf,
+    ttl_hours: i64,
+    writing: Arc<Mutex<HashSet<Uuid>>>,
+}
+
+/// PATCH 1건이 쓰는 중인 세션 (drop되면 풀림)
+pub struct SessionLock {
+    writing: Arc<Mutex<HashSet<Uuid>>>,
+    id: Uuid,
+}
+
+impl Drop for SessionLock {
+    fn drop(&mut self) {
+        self.writing.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl UploadSessions {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            dir: Path::new(&config.upload_temp_dir).join("sessions"),
+            ttl_hours: config.upload_session_ttl_hours.max(1),
+            writing: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn ttl(&self) -> Duration {
+        Duration::hours(self.ttl_hours)
+    }
+
+    pub fn part_path(&self, id: Uuid) -> PathBuf {
+        self.dir.join(format!("{}.part", id.simple()))
+    }
+
+    /// 같은 세션에 동시에 들어온 PATCH는 하나만 (이미 쓰는 중이면 None)
+    pub fn try_lock(&self, id: Uuid) -> Option<SessionLock> {
+        self.writing.lock().unwrap().insert(id).then(|| SessionLock { writing: self.writing.clone(), id })
+    }
+
+    /// 받은 조각 삭제 (없으면 무시)
+    pub async fn discard(&self, id: Uuid) {
+        let path = self.part_path(id);
+        if let Err(e) = tokio::fs::remove_file(&path).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("⚠️ 업로드 조각 삭제 실패: {} - {}", path.display(), e);
+        }
+    }
+}
+
+/// PATCH 본문을 이어 쓴 결과: 끊기거나 길이를 넘어도 받은 만큼(written)은 남겨 다음 PATCH가 이어 보냄
+pub struct Appended {
+    pub written: u64,
+    pub interrupted: Option<SpoolError>,
+}
+
+/// 받은 조각 파일의 offset 위치부터 본문을 이어 씀 (offset 뒤에 남은 기록되지 않은 바이트는 버림)
+pub async fn append_body(path: &Path, offset: u64, remaining: u64, payload: &mut web::Payload) -> Result<Appended, SpoolError> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let mut file = tokio::fs::OpenOptions::new().create(true).write(true).truncate(false).open(path).await?;
+    file.set_len(offset).await?;
+    file.seek(std::io::SeekFrom::Start(offset)).await?;
+
+    let mut written = 0u64;
+    let mut interrupted = None;
+    while let Some(chunk) = payload.next().await {
+        let data = match chunk {
+            Ok(data) => data,
+            Err(e) => {
+                interrupted = Some(SpoolError::Payload(e.to_string()));
+                break;
+            }
+        };
+        let fits = (remaining - written).min(data.len() as u64) as usize;
+        file.write_all(&data[..fits]).await?;
+        written += fits as u64;
+        if fits < data.len() {
+            interrupted = Some(SpoolError::TooLarge { max_mb: (offset + remaining) as f64 / (1024.0 * 1024.0) });
+            break;
+        }
+    }
+    file.flush().await?;
+    file.sync_data().await?;
+    Ok(Appended { written, interrupted })
+}
+
+/// 받은 조각 파일 앞부분 (내용 확인용)
+pub async fn read_head(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    tokio::fs::File::open(path).await?.take(SNIFF_BYTES as u64).read_to_end(&mut head).await?;
+    Ok(head)
+}
+
+/// 다 받은 세션 처리: 원본을 저장소에 올리고 일반 업로드와 같은 처리 후 결과 응답을 세션에 남김
+/// 성공/실패 모두 받은 조각은 지우고, 같은 응답을 GET /api/uploads/{id}로 다시 볼 수 있음
+pub async fn complete_session(
+    session: &UploadSession,
+    sessions: &UploadSessions,
+    db: &Database,
+    config: &Config,
+    storage: &dyn Storage,
+    keys: &ObjectKeys,
+    hooks: &ImageHooks,
+) -> HttpResponse {
+    let path = sessions.part_path(session.id);
+    let output_format = match session.output_format.as_str() {
+        "avif" => OutputFormat::Avif,
+        _ => OutputFormat::Webp,
+    };
+    let processor = s3_routes::upload_processor(&session.image_type, config, hooks, output_format);
+    info!("📦 이어 받은 업로드 처리 시작: {} ({}, {:.2}MB)", session.id, session.filename, session.upload_length as f64 / (1024.0 * 1024.0));
+
+    let response = match read_head(&path).await {
+        Err(e) => SpoolError::Io(e).into_response(),
+        Ok(head) => match processor.check_image_content(&session.filename, &head) {
+            Err(e) => SpoolError::UnsupportedContent(e).into_response(),
+            Ok(_) => match store_original(session, &path, storage, keys).await {
+                Err(response) => response,
+                Ok((key, url)) => {
+                    let original = ReceivedOriginal {
+                        path: path.clone(),
+                        filename: session.filename.clone(),
+                        size: session.upload_length as usize,
+                        key,
+                        url,
+                    };
+                    let target = UploadTarget { image_type: &session.image_type, extract_exif: session.extract_exif };
+                    match s3_routes::process_received_original(original, target, &processor, db, config, storage, keys).await {
+                        Ok(response) => HttpResponse::Ok().json(response),
+                        Err(response) => response,
+                    }
+                }
+            },
+        },
+    };
+    sessions.discard(session.id).await;
+
+    // 응답 본문을 세션 결과로 남기고 같은 응답을 돌려줌
+    let (response, body) = response.into_parts();
+    let body = body::to_bytes(body).await.unwrap_or_default();
+    let status = if response.status() == StatusCode::OK { "completed" } else { "failed" };
+    if let Err(e) = db.finish_upload_session(session.id, status, &String::from_utf8_lossy(&body)).await {
+        warn!("⚠️ 업로드 세션 결과 저장 실패: {} - {}", session.id, e);
+    }
+    info!("📦 이어 받은 업로드 처리 {}: {}", status, session.id);
+    response.set_body(body).map_into_boxed_body()
+}
+
+/// 받은 조각 파일을 그대로 원본으로 저장 (originals/{종류}/), (키, URL) 반환
+async fn store_original(session: &UploadSession, path: &Path, storage: &dyn Storage, keys: &ObjectKeys) -> Result<(String, String), HttpResponse> {
+    let extension = Path::new(&session.filename).extension().and_then(|ext| ext.to_str()).unwrap_or("bin").to_lowercase();
+    let key = keys.new_key(&format!("originals/{}", session.image_type), "original", &extension);
+    let mut upload = storage.start_upload(&key, storage::content_type_for(&session.filename)).await
+        .map_err(|e| s3_routes::storage_failed(storage, &e))?;
+    let copied = async {
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut buffer = vec![0u8; COPY_CHUNK_BYTES];
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                return anyhow::Ok(());
+            }
+            upload.write(&buffer[..read]).await?;
+        }
+    }.await;
+    if let Err(e) = copied {
+        upload.abort().await;
+        return Err(s3_routes::storage_failed(storage, &e));
+    }
+    let url = upload.complete().await.map_err(|e| s3_routes::storage_failed(storage, &e))?;
+    Ok((key, url))
+}
+
+/// 만료된 업로드 세션과 받은 조각을 주기적으로 정리 (서버 시작 시 spawn)
+pub async fn run_upload_session_cleanup_worker(db: Database, sessions: UploadSessions, clock: Arc<dyn Clock>) {
+    info!("🔁 업로드 세션 정리 작업 시작 ({}시간 보관)", sessions.ttl().num_hours());
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(CLEANUP_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        match db.delete_expired_upload_sessions(clock.now()).await {
+            Ok(ids) => {
+                for id in &ids {
+                    sessions.discard(*id).await;
+                }
+                if !ids.is_empty() {
+                    info!("🧹 만료된 업로드 세션 {}개 정리", ids.len());
+                }
+            }
+            Err(e) => warn!("⚠️ 업로드 세션 정리 실패: {}", e),
+        }
+    }
+}
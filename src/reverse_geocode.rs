@@ -0,0 +1,194 @@
+// 마커 주소 역지오코딩 백필 (주소 컬럼이 생기기 전에 만들어진 마커를 천천히 채움)
+// 같은 H3 셀의 마커는 셀 중심 좌표로 한 번만 조회하고, 제공자 호출 속도를 제한하며 429를 받으면 쉬었다가 이어감
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use geo_types::Point;
+use h3ron::{H3Cell, Index, ToCoordinate};
+use log::{error, info, warn};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::database::{Database, MarkerAddress};
+
+/// 약 0.1km² 셀 (읍면동 단위 주소에는 충분히 작음)
+const GEOCODE_H3_RESOLUTION: u8 = 9;
+/// 조회할 마커가 없을 때 다시 확인하는 간격 (새로 생긴 마커도 이 작업이 채움)
+const IDLE_INTERVAL_SECS: u64 = 600;
+/// 제공자가 Retry-After 없이 429를 줄 때 쉬는 시간
+const DEFAULT_RATE_LIMIT_BACKOFF_SECS: u64 = 60;
+/// 셀별 결과 캐시 상한 (넘으면 비움)
+const MAX_CACHED_CELLS: usize = 10_000;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+pub enum GeocodeError {
+    /// 호출 한도 초과 (이 시간만큼 쉬고 다시 시도)
+    RateLimited(Duration),
+    Failed(anyhow::Error),
+}
+
+/// 좌표 → 주소 제공자 (주소가 없는 곳이면 None)
+pub trait ReverseGeocoder: Send + Sync {
+    fn reverse_geocode(&self, latitude: f64, longitude: f64) -> impl Future<Output = Result<Option<MarkerAddress>, GeocodeError>> + Send;
+}
+
+/// 카카오 로컬 API 좌표 → 행정구역 변환
+pub struct KakaoGeocoder {
+    client: reqwest::Client,
+    api_key: String,
+}
+
+#[derive(Deserialize)]
+struct KakaoRegionResponse {
+    documents: Vec<KakaoRegion>,
+}
+
+#[derive(Deserialize)]
+struct KakaoRegion {
+    region_type: String, // H: 행정동, B: 법정동
+    address_name: String,
+    region_1depth_name: String,
+    region_2depth_name: String,
+    region_3depth_name: String,
+}
+
+impl KakaoGeocoder {
+    pub fn new(api_key: &str) -> Self {
+        Self { client: reqwest::Client::new(), api_key: api_key.to_string() }
+    }
+}
+
+impl ReverseGeocoder for KakaoGeocoder {
+    async fn reverse_geocode(&self, latitude: f64, longitude: f64) -> Result<Option<MarkerAddress>, GeocodeError> {
+        let response = self.client
+            .get("https://dapi.kakao.com/v2/local/geo/coord2regioncode.json")
+            .query(&[("x", longitude), ("y", latitude)])
+            .header(reqwest::header::AUTHORIZATION, format!("KakaoAK {}", self.api_key))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .send()
+            .await
+            .map_err(|e| GeocodeError::Failed(e.into()))?;
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response.headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_RATE_LIMIT_BACKOFF_SECS);
+            return Err(GeocodeError::RateLimited(Duration::from_secs(retry_after)));
+        }
+        if !response.status().is_success() {
+            return Err(GeocodeError::Failed(anyhow!("카카오 역지오코딩 응답 {}", response.status())));
+        }
+
+        let body: KakaoRegionResponse = response.json().await.map_err(|e| GeocodeError::Failed(e.into()))?;
+        // 행정동 결과를 우선 사용
+        let region = body.documents.iter()
+            .find(|region| region.region_type == "H")
+            .or(body.documents.first());
+        Ok(region.filter(|region| !region.region_1depth_name.is_empty()).map(|region| MarkerAddress {
+            region: region.region_1depth_name.clone(),
+            locality: region.region_2depth_name.clone(),
+            neighborhood: region.region_3depth_name.clone(),
+            full_address: region.address_name.clone(),
+        }))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BackfillBatch {
+    pub markers: usize, // 주소를 저장한 마커 수
+    pub cells: usize,
+    pub lookups: usize, // 실제 제공자 호출 수 (캐시 적중 제외)
+    pub failed_cells: usize, // 일시 오류로 다음에 다시 시도할 셀 수
+    pub rate_limited: Option<Duration>, // 호출 한도에 걸려 중간에 멈춘 경우 쉴 시간
+}
+
+/// 주소 없는 마커 한 묶음 처리
+/// 셀마다 바로 저장하므로 중간에 한도에 걸려도 처리한 셀은 남고, 나머지는 다음 묶음에서 이어감
+pub async fn backfill_batch<G: ReverseGeocoder>(
+    db: &Database,
+    geocoder: &G,
+    cache: &mut HashMap<u64, Option<MarkerAddress>>,
+    batch_size: i64,
+    min_interval: Duration,
+) -> Result<BackfillBatch> {
+    let pending = db.get_markers_pending_geocode(batch_size).await?;
+    let mut cells: HashMap<u64, Vec<i64>> = HashMap::new();
+    for (marker_id, latitude, longitude) in pending {
+        let cell = H3Cell::from_point(Point::new(longitude, latitude), GEOCODE_H3_RESOLUTION)?;
+        cells.entry(cell.h3index()).or_default().push(marker_id);
+    }
+
+    let mut batch = BackfillBatch { cells: cells.len(), ..Default::default() };
+    if cache.len() > MAX_CACHED_CELLS {
+        cache.clear();
+    }
+    for (h3index, marker_ids) in cells {
+        let address = match cache.get(&h3index) {
+            Some(address) => address.clone(),
+            None => {
+                let center = H3Cell::new(h3index).to_coordinate()?;
+                tokio::time::sleep(min_interval).await;
+                batch.lookups += 1;
+                match geocoder.reverse_geocode(center.y, center.x).await {
+                    Ok(address) => {
+                        cache.insert(h3index, address.clone());
+                        address
+                    }
+                    Err(GeocodeError::RateLimited(wait)) => {
+                        batch.rate_limited = Some(wait);
+                        break;
+                    }
+                    Err(GeocodeError::Failed(e)) => {
+                        warn!("⚠️ 역지오코딩 실패 (셀 {:x}, 마커 {}개): {}", h3index, marker_ids.len(), e);
+                        batch.failed_cells += 1;
+                        continue;
+                    }
+                }
+            }
+        };
+        batch.markers += db.set_marker_addresses(&marker_ids, address.as_ref(), Utc::now()).await? as usize;
+    }
+    Ok(batch)
+}
+
+/// 주소가 빈 마커가 없어질 때까지 천천히 채우고, 이후에는 새 마커를 주기적으로 확인
+pub async fn run_geocode_backfill_worker<G: ReverseGeocoder>(db: Database, geocoder: G, config: Config) {
+    let min_interval = Duration::from_secs_f64(1.0 / config.geocode_requests_per_sec);
+    info!("🗺️ 마커 주소 백필 작업 시작 (초당 최대 {}건)", config.geocode_requests_per_sec);
+    let mut cache = HashMap::new();
+    loop {
+        let wait = match backfill_batch(&db, &geocoder, &mut cache, config.geocode_batch_size, min_interval).await {
+            Ok(batch) => {
+                if batch.markers > 0 {
+                    match db.get_geocode_backfill_progress().await {
+                        Ok(progress) => info!(
+                            "🗺️ 주소 백필: 마커 {}개 / 셀 {}개 (조회 {}건) - 진행 {}/{}, 남은 마커 {}개",
+                            batch.markers, batch.cells, batch.lookups, progress.geocoded, progress.total_markers, progress.pending
+                        ),
+                        Err(e) => warn!("⚠️ 주소 백필 진행 상황 조회 실패: {}", e),
+                    }
+                }
+                match batch.rate_limited {
+                    Some(wait) => {
+                        warn!("⏳ 역지오코딩 호출 한도 초과, {}초 후 재개", wait.as_secs());
+                        Some(wait)
+                    }
+                    // 저장한 마커가 없으면(남은 마커가 없거나 모두 일시 오류) 쉬었다가 확인, 아니면 바로 다음 묶음
+                    None if batch.markers == 0 => Some(Duration::from_secs(IDLE_INTERVAL_SECS)),
+                    None => None,
+                }
+            }
+            Err(e) => {
+                error!("❌ 주소 백필 실패: {}", e);
+                Some(Duration::from_secs(IDLE_INTERVAL_SECS))
+            }
+        };
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
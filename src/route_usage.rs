@@ -0,0 +1,146 @@
+// 라우트별 API 사용량 집계 (제품 지표, 레거시 엔드포인트 제거 판단용)
+// 요청마다 DB에 쓰지 않고 인스턴스 메모리에 (UTC 날짜, 메서드, 라우트 패턴, 앱 버전)별로 모았다가
+// 주기적으로 route_usage_daily에 더함 (인스턴스가 여럿이면 각자 더함)
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::StatusCode,
+    middleware::Next,
+    web, Error,
+};
+use chrono::NaiveDate;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::clock::Clock;
+use crate::database::Database;
+
+/// 앱이 보내는 버전 헤더 (예: 2.3.0, 없으면 unknown)
+pub const APP_VERSION_HEADER: &str = "x-app-version";
+pub const UNKNOWN_VERSION: &str = "unknown";
+/// 집계 키가 이만큼 쌓이면 새 버전 문자열은 other로 묶음 (임의 헤더로 메모리가 늘지 않도록)
+const MAX_PENDING_KEYS: usize = 10_000;
+const OTHER_VERSION: &str = "other";
+const MAX_VERSION_LEN: usize = 30;
+/// 라우트에 걸리지 않은 요청 (404 등, 경로를 그대로 쓰면 종류가 끝없이 늘어남)
+const UNMATCHED_ROUTE: &str = "(unmatched)";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UsageKey {
+    pub usage_date: NaiveDate, // UTC
+    pub method: String,
+    pub route: String, // 라우트 패턴 (예: /api/markers/{id})
+    pub client_version: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageCounts {
+    pub requests: i64,
+    pub client_errors: i64, // 4xx
+    pub server_errors: i64, // 5xx
+}
+
+impl UsageCounts {
+    fn add(&mut self, other: UsageCounts) {
+        self.requests += other.requests;
+        self.client_errors += other.client_errors;
+        self.server_errors += other.server_errors;
+    }
+}
+
+/// 아직 DB에 더하지 않은 사용량 (워커 전체가 공유)
+#[derive(Clone, Default)]
+pub struct RouteUsage {
+    pending: Arc<Mutex<HashMap<UsageKey, UsageCounts>>>,
+}
+
+impl RouteUsage {
+    /// 요청 1회 집계
+    pub fn record(&self, mut key: UsageKey, status: StatusCode) {
+        let counts = UsageCounts {
+            requests: 1,
+            client_errors: status.is_client_error() as i64,
+            server_errors: status.is_server_error() as i64,
+        };
+        let mut pending = self.pending.lock().unwrap();
+        if pending.len() >= MAX_PENDING_KEYS && !pending.contains_key(&key) {
+            key.client_version = OTHER_VERSION.to_string();
+        }
+        pending.entry(key).or_default().add(counts);
+    }
+
+    /// 쌓인 사용량을 꺼내고 비움
+    pub fn take(&self) -> Vec<(UsageKey, UsageCounts)> {
+        self.pending.lock().unwrap().drain().collect()
+    }
+
+    /// DB 반영에 실패한 사용량을 되돌려 다음 반영 때 다시 시도
+    fn restore(&self, rows: Vec<(UsageKey, UsageCounts)>) {
+        let mut pending = self.pending.lock().unwrap();
+        for (key, counts) in rows {
+            pending.entry(key).or_default().add(counts);
+        }
+    }
+
+    /// 쌓인 사용량을 route_usage_daily에 더하고 반영한 집계 키 수 반환
+    pub async fn flush(&self, db: &Database) -> anyhow::Result<usize> {
+        let rows = self.take();
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        match db.add_route_usage(&rows).await {
+            Ok(()) => Ok(rows.len()),
+            Err(e) => {
+                self.restore(rows);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// 버전 헤더 정리 (영숫자와 . - _ + 만, 너무 길거나 이상하면 unknown)
+pub fn normalize_client_version(value: Option<&str>) -> String {
+    value.map(str::trim)
+        .filter(|version| !version.is_empty() && version.len() <= MAX_VERSION_LEN)
+        .filter(|version| version.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+')))
+        .unwrap_or(UNKNOWN_VERSION)
+        .to_string()
+}
+
+/// 앱 전체에 거는 미들웨어: 응답 상태까지 보고 라우트 패턴별로 집계
+pub async fn record_route_usage(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let usage = req.app_data::<web::Data<RouteUsage>>().cloned();
+    let clock = req.app_data::<web::Data<dyn Clock>>().cloned();
+    let response = next.call(req).await?;
+
+    if let (Some(usage), Some(clock)) = (usage, clock) {
+        let request = response.request();
+        let key = UsageKey {
+            usage_date: clock.now().date_naive(),
+            method: request.method().to_string(),
+            route: request.match_pattern().unwrap_or_else(|| UNMATCHED_ROUTE.to_string()),
+            client_version: normalize_client_version(
+                request.headers().get(APP_VERSION_HEADER).and_then(|value| value.to_str().ok())
+            ),
+        };
+        usage.record(key, response.status());
+    }
+    Ok(response)
+}
+
+/// 주기적으로 쌓인 사용량을 DB에 반영 (서버 시작 시 spawn)
+pub async fn run_usage_flush_worker(db: Database, usage: RouteUsage, interval_secs: u64) {
+    info!("📈 라우트 사용량 집계 시작 ({}초마다 반영)", interval_secs);
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+    loop {
+        interval.tick().await;
+        if let Err(e) = usage.flush(&db).await {
+            warn!("⚠️ 라우트 사용량 반영 실패 (다음에 다시 시도): {}", e);
+        }
+    }
+}
@@ -1,57 +1,63 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{web, middleware::from_fn, HttpResponse, Result};
 use actix_multipart::Multipart;
 use futures_util::stream::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use uuid::Uuid;
-use chrono::Utc;
+use chrono::{Datelike, Utc};
 use std::fs;
 use sqlx::PgPool;
 use log::{info, warn, error};
-use jsonwebtoken::{encode, EncodingKey, Header, decode, DecodingKey, Validation};
-use base64::Engine;
+use jsonwebtoken::{encode, EncodingKey, Header};
 
+use crate::image_pipeline::ImageHooks;
 use crate::image_processor::ImageProcessor;
-use crate::database::{Database, Member, AuthProvider};
+use crate::database::{AdminMarkerExportFilter, BookmarkFolder, Database, Marker, MarkerReport, MarkerReportResolution, MemberListFilter, MemberModerationAction, MarkerCollection, MemberDataExport, MarkerStatus, Member, NewConnectedAccount, NewMarkerImage, NewMarkerPromotion, PromotionEvent, ContentTakedownAction, DistrictImport, MarkerImageQuota, MarkerImageQuotaExceeded, MarkerVisibility, MemberMarkerState, MemberProfileUpdate, NewUploadSession, TrendingMarkerFilter, UpdateMarkerInput, UploadSession, CreatedTimeFilter, LoginAttempt, RegistrationConflict, StatsInterval, ClientErrorFilter, NewClientError, Activity, ACTIVITY_MARKER_CREATED, ACTIVITY_MEMBER_FOLLOWED, marker_search_tsquery, parse_datetime_param};
 use crate::config::Config;
 use crate::s3_service::S3Service;
-use crate::s3_routes::{upload_image_s3, upload_circular_thumbnail_s3_internal};
+use crate::s3_routes::{upload_image_to_storage, upload_circular_thumbnail_to_storage, upload_processor, rehost_marker_image, UploadQuery, UploadTarget};
+use crate::storage::{self, LocalStorage, ObjectKeys, Storage};
 use crate::error_handler::ErrorHandler;
-use crate::emotions::get_all_emotions;
-
-// 구글 ID 토큰 페이로드 구조체
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GoogleIdTokenPayload {
-    pub iss: String,           // issuer (Google)
-    pub sub: String,           // subject (Google user ID)
-    pub aud: String,           // audience (client ID)
-    pub exp: i64,              // expiration time
-    pub iat: i64,              // issued at
-    pub email: String,         // user email
-    pub email_verified: bool,  // email verification status
-    pub name: Option<String>,  // user name
-    pub picture: Option<String>, // profile picture URL
-    pub given_name: Option<String>,
-    pub family_name: Option<String>,
-    pub locale: Option<String>,
-}
-
-// 구글 공개키 구조체
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GooglePublicKey {
-    pub kid: String,
-    pub e: String,
-    pub n: String,
-    pub alg: String,
-    pub kty: String,
-    #[serde(rename = "use")]
-    pub use_field: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GoogleKeysResponse {
-    pub keys: Vec<GooglePublicKey>,
-}
+use crate::upload_spool::receive_field;
+use crate::auth::{AuthenticatedMember, Claims};
+use crate::clock::{Clock, IdGenerator};
+use crate::schema_check::verify_schema;
+use crate::rate_limit::{limit_auth_requests, AuthRateLimiter};
+use crate::upload_guard::{shed_uploads, UploadLimiter};
+use crate::idempotency::idempotent_requests;
+use crate::api_keys::{generate_api_key, ApiKeyClient, API_KEY_HEADER, SCOPE_PUBLIC_READ};
+use crate::link_preview::{fetch_page_metadata, fetch_image};
+use crate::google_auth::verify_google_id_token;
+use crate::emotions::{get_all_emotions, get_emotion_by_id, unknown_emotion_ids};
+use crate::share_card::{self, CardFonts, MapInset, ShareCard};
+use crate::image_cleanup::{locate as locate_stored_image, StoredImage};
+use crate::dto::{ActivityDto, AdminMarkerClaimDto, AdminMemberDto, ApiKeyDto, ApiKeyUsageDto, AuthProviderDto, BookmarkFolderDto, ClientErrorDto, ConnectedAccountDto, ContentTakedownAuditDto, ContentTakedownDto, DeadLetterJobDto, GoogleProfileDto, IssuedApiKeyDto, MarkerClaimDto, MarkerCollectionDetailDto, MarkerCollectionDto, MarkerCrosspostDto, MarkerDto, MarkerExportDto, MarkerImageDto, MarkerPromotionDto, MarkerReportDto, MarkerShareDto, MemberDataExportDto, MemberDto, NotificationDto, NotificationPreferencesDto, PublicMarkerDto, SharedMarkerDto, UploadSessionDto};
+use crate::totp::{generate_secret as generate_totp_secret, provisioning_uri, verify_code as verify_totp_code};
+use crate::language::{detect_language, is_supported_language, language_for_country, parse_accept_language, parse_language_list};
+use crate::geoip::{parse_client_ip, GeoIp};
+use crate::marker_export;
+use crate::member_export;
+use crate::presence::MarkerPresence;
+use crate::marker_views::{self, MarkerViews};
+use crate::watermark::{Watermark, WatermarkQuery};
+use crate::marker_events::{self, Bounds, MarkerEventKind, MarkerEvents};
+use crate::location_suggest::{self, Places};
+use crate::promotions;
+use crate::orphan_gc;
+use crate::embed::{self, EmbedMarker};
+use crate::resumable_upload::{self, UploadSessions};
+use crate::public_id;
+use crate::geojson::{self, ResponseFormat};
+use crate::hashtags::{collect_marker_tags, normalize_tag};
+use crate::emotion_profile::{build_profile, EmotionProfile, EmotionProfileCache, ProfileRange};
+use crate::trust::{contains_link, MemberTrust, TrustLevel, TrustPolicy};
+use crate::request_id::{is_valid_request_id, request_id};
+use crate::route_usage::RouteUsage;
+use crate::push::PUSH_PLATFORMS;
+use crate::cluster_zoom::{self, ZoomResolutions};
+use crate::cluster_cache::{ClusterCache, ClusterFilters, ClusterViewport};
+use crate::search_index::{SearchIndex, SearchRequest};
+use crate::emotion_tiles::{self, TileCoord};
+use crate::crosspost::{build_caption, CrossPost, CrossPostProviders, TokenCipher};
 
 #[derive(Serialize)]
 pub struct ApiResponse<T> {
@@ -106,6 +112,12 @@ pub struct RegisterSocialMember {
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
+    pub totp_code: Option<String>, // 2단계 인증을 켠 회원만 필요
+}
+
+#[derive(Deserialize)]
+pub struct TotpCodeRequest {
+    pub code: String,
 }
 
 #[derive(Deserialize)]
@@ -132,15 +144,294 @@ pub struct CreateMarkerRequest {
     pub emotion_tag_input: Option<String>, // 사용자가 입력한 감성태그들 (예: "커피,맛집,데이트")
     pub emotion: Option<String>, // 자유로운 감정/경험 설명 텍스트
     pub description: String,
-    pub sharing_option: Option<String>, // public, friends, private
+    pub sharing_option: Option<String>, // 예전 앱 호환 (visibility가 없을 때만 사용, friends는 followers)
+    pub visibility: Option<String>, // public(기본), followers, private
     pub thumbnail_img: Option<String>,
     pub images: Option<Vec<CreateMarkerImageRequest>>,
+    pub tags: Option<Vec<String>>, // 해시태그 (설명 속 #태그와 합쳐 저장)
+    pub status: Option<String>, // draft, published(기본), scheduled
+    pub publish_at: Option<String>, // scheduled일 때 게시 시각 (RFC 3339)
+}
+
+#[derive(Deserialize)]
+pub struct TakedownContentRequest {
+    pub reason: String, // 게시 중단 사유 (감사 로그에 기록)
+    pub legal_reference: Option<String>, // 법원 명령/신고 번호 등
+}
+
+#[derive(Deserialize)]
+pub struct RestoreContentRequest {
+    pub reason: String,
+    pub release_legal_hold: Option<bool>, // true면 원본 파일 법적 보존도 해제
+}
+
+#[derive(Deserialize)]
+pub struct TakedownAuditQuery {
+    pub target_type: Option<String>, // marker, marker_image
+    pub target_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct MarkerExportQuery {
+    pub since: Option<String>, // RFC 3339 또는 YYYY-MM-DD(UTC 자정), 이후 수정된 마커만
+    pub format: Option<String>, // csv(기본), parquet
+}
+
+#[derive(Deserialize)]
+pub struct AdminMarkerStreamExportQuery {
+    pub format: Option<String>, // csv(기본), geojson
+    pub from: Option<String>, // 작성 시각, RFC 3339 또는 YYYY-MM-DD(UTC 자정), 포함
+    pub to: Option<String>, // 미포함
+    pub region: Option<String>, // 시도 (예: 서울특별시)
+    pub district_code: Option<String>, // 행정구역 코드 (경계 안의 마커만)
+}
+
+#[derive(Deserialize)]
+pub struct MemberDataExportQuery {
+    pub format: Option<String>, // zip(기본), json
+}
+
+#[derive(Deserialize)]
+pub struct MarkerClaimListQuery {
+    pub status: Option<String>, // pending(기본), approved, rejected, all
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct ReviewMarkerClaimRequest {
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ReportMarkerRequest {
+    pub reason: String, // MARKER_REPORT_REASONS 중 하나
+    pub details: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateCollectionRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub is_public: Option<bool>, // 기본 공개
+}
+
+#[derive(Deserialize)]
+pub struct UpdateCollectionRequest {
+    pub name: Option<String>,
+    pub description: Option<String>, // 빈 문자열이면 설명 삭제
+    pub is_public: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct AddCollectionMarkerRequest {
+    pub marker_id: i64,
+}
+
+#[derive(Deserialize)]
+pub struct ReorderCollectionRequest {
+    pub marker_ids: Vec<i64>, // 담긴 마커 전부, 보여 줄 순서대로
+}
+
+#[derive(Deserialize)]
+pub struct CreateBookmarkFolderRequest {
+    pub name: String,
+    pub color: Option<String>, // #RRGGBB
+}
+
+#[derive(Deserialize)]
+pub struct UpdateBookmarkFolderRequest {
+    pub name: Option<String>,
+    pub color: Option<String>, // 빈 문자열이면 색 삭제
+}
+
+#[derive(Deserialize)]
+pub struct ReorderBookmarkFoldersRequest {
+    pub folder_ids: Vec<i64>, // 내 폴더 전부, 보여 줄 순서대로
+}
+
+#[derive(Deserialize)]
+pub struct MoveBookmarksRequest {
+    pub marker_ids: Vec<i64>,
+    pub folder_id: Option<i64>, // null이면 폴더에서 뺌
+}
+
+#[derive(Deserialize)]
+pub struct MarkerReportListQuery {
+    pub status: Option<String>, // pending(기본), dismissed, hidden, all
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct DeadLetterListQuery {
+    pub status: Option<String>, // dead(기본), retried, discarded, all
+    pub job_type: Option<String>, // image_cleanup, marker_export
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct ClientErrorReport {
+    pub kind: String, // crash, api_error
+    pub platform: String, // ios, android, web
+    pub app_version: Option<String>,
+    pub device: Option<String>,
+    pub os_version: Option<String>,
+    pub route: Option<String>, // 화면 경로 또는 실패한 API 경로
+    pub request_id: Option<String>, // 실패한 응답의 X-Request-Id
+    pub http_status: Option<i32>,
+    pub message: String,
+    pub stack_trace: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ClientErrorListQuery {
+    pub kind: Option<String>,
+    pub platform: Option<String>,
+    pub app_version: Option<String>,
+    pub request_id: Option<String>, // 서버 로그의 요청 ID로 찾기
+    pub hours: Option<i64>, // 요약 집계 기간 (기본 24시간)
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct ResolveMarkerReportRequest {
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ConnectAccountRequest {
+    pub platform: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<i64>, // 액세스 토큰 유효 시간 (초)
+}
+
+#[derive(Deserialize)]
+pub struct CrosspostRequest {
+    pub platform: String,
+    pub caption: Option<String>, // 없으면 설명 + 감성 태그 해시태그
+}
+
+#[derive(Deserialize)]
+pub struct CreateMarkerPromotionRequest {
+    pub marker_id: i64,
+    pub sponsor_name: String,
+    pub district_codes: Option<Vec<String>>, // 비우면 전국
+    pub starts_at: chrono::DateTime<Utc>,
+    pub ends_at: chrono::DateTime<Utc>,
+    pub impression_cap: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateMarkerPromotionRequest {
+    pub is_active: Option<bool>,
+    pub ends_at: Option<chrono::DateTime<Utc>>,
+    pub impression_cap: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct PromotionReportQuery {
+    pub days: Option<i32>,
+}
+
+/// 행정구역 경계 GeoJSON (FeatureCollection)
+#[derive(Deserialize)]
+pub struct DistrictImportRequest {
+    pub features: Vec<DistrictFeature>,
+}
+
+#[derive(Deserialize)]
+pub struct DistrictFeature {
+    pub properties: serde_json::Map<String, serde_json::Value>,
+    pub geometry: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+pub struct MemoriesNotificationRequest {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize)]
+pub struct RegisterDeviceRequest {
+    pub token: String, // FCM 등록 토큰
+    pub platform: String, // ios, android, web
+}
+
+#[derive(Deserialize)]
+pub struct DeviceTokenQuery {
+    pub token: String,
+}
+
+#[derive(Deserialize)]
+pub struct NotificationPreferencesRequest {
+    pub likes: Option<bool>,
+    pub comments: Option<bool>,
+    pub memories: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct NotificationsQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct ActivityFeedQuery {
+    pub cursor: Option<String>, // 이전 응답의 nextCursor
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub daily_quota: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct ApiKeyMetricsQuery {
+    pub days: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct RouteUsageQuery {
+    pub days: Option<i32>, // 기본 30일
+    pub route: Option<String>, // 이 경로로 시작하는 라우트만 (예: /api/images)
+    pub client_version: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PublicMarkerSearchQuery {
+    pub lat_min: f64,
+    pub lat_max: f64,
+    pub lng_min: f64,
+    pub lng_max: f64,
+    pub emotion_tags: Option<String>, // 쉼표로 구분 (예: "happy,calm")
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateMarkerRequest {
+    pub description: Option<String>,
+    pub emotion_tag: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>, // 위치 변경 시 latitude와 함께 전달
+    pub thumbnail_img: Option<String>,
+    pub status: Option<String>, // 임시 저장/예약 마커만 변경 가능 (draft, published, scheduled)
+    pub publish_at: Option<String>, // scheduled일 때 게시 시각 (RFC 3339)
+    pub visibility: Option<String>, // public, followers, private
+    pub version: Option<i32>, // 마지막으로 본 버전 (If-Match 헤더 대신 보낼 수 있음)
+}
+
+#[derive(Deserialize)]
+pub struct CreateMarkerFromUrlRequest {
+    pub url: String, // 공유받은 외부 웹 페이지 URL
 }
 
 #[derive(Deserialize)]
 pub struct CreateMarkerImageRequest {
     pub image_url: String,
-    pub image_type: String, // thumbnail, detail, gallery
+    pub image_type: Option<String>, // thumbnail, detail, gallery (생략 시 detail + 자동 썸네일)
     pub image_order: Option<i32>,
     pub is_primary: Option<bool>,
 }
@@ -148,7 +439,7 @@ pub struct CreateMarkerImageRequest {
 #[derive(Deserialize)]
 pub struct AddMarkerImageRequest {
     pub image_url: String,
-    pub image_type: String, // thumbnail, detail, gallery
+    pub image_type: Option<String>, // thumbnail, detail, gallery (생략 시 detail + 자동 썸네일)
     pub image_order: Option<i32>,
     pub is_primary: Option<bool>,
 }
@@ -158,6 +449,11 @@ pub struct UpdateMarkerImageOrderRequest {
     pub image_order: i32,
 }
 
+#[derive(Deserialize)]
+pub struct ReorderMarkerImagesRequest {
+    pub image_ids: Vec<i32>, // 원하는 순서대로 나열한 마커의 전체 이미지 ID
+}
+
 #[derive(Serialize)]
 pub struct MarkerImageResponse {
     pub success: bool,
@@ -180,6 +476,7 @@ pub struct MarkerReactionResponse {
     pub dislikes: i32,
     pub is_liked: Option<bool>,
     pub is_disliked: Option<bool>,
+    pub is_bookmarked: Option<bool>,
 }
 
 #[derive(Serialize)]
@@ -204,26 +501,53 @@ pub struct GoogleIdTokenResponse {
     pub is_new_user: Option<bool>,
 }
 
+#[derive(Deserialize)]
+pub struct UpdateProfileRequest {
+    pub nickname: Option<String>,
+    pub region: Option<String>,
+    pub gender: Option<String>,
+    pub birth_year: Option<i32>,
+    pub personality_type: Option<String>,
+    pub profile_image_url: Option<String>,
+    pub interests: Option<Vec<String>>, // 보낸 목록으로 교체 (빈 배열이면 모두 해제)
+    pub hobbies: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateLanguagesRequest {
+    pub languages: Vec<String>, // 예: ["ko", "en"]
+}
+
 #[derive(Deserialize)]
 pub struct ListMembersQuery {
     pub limit: Option<i64>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct Claims {
-    pub sub: String, // subject (user id)
-    pub email: String,
-    pub exp: usize, // 만료시간 (timestamp)
+#[derive(Deserialize)]
+pub struct AdminListMembersQuery {
+    pub email: Option<String>, // 부분 일치
+    pub provider_type: Option<String>, // google, kakao, naver, meta, email
+    pub created_from: Option<String>, // RFC 3339 또는 YYYY-MM-DD (포함)
+    pub created_to: Option<String>, // RFC 3339 또는 YYYY-MM-DD (미포함)
+    pub is_active: Option<bool>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
 }
 
-fn create_jwt(user_id: i64, email: &str, config: &Config) -> Result<String, jsonwebtoken::errors::Error> {
-    use chrono::Duration;
-    let expiration = Utc::now() + Duration::hours(24);
-    let claims = Claims {
-        sub: user_id.to_string(),
-        email: email.to_string(),
-        exp: expiration.timestamp() as usize,
-    };
+#[derive(Deserialize)]
+pub struct MemberModerationRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TrustLevelRequest {
+    pub level: String, // new, basic, trusted, auto(자동 계산으로 되돌림)
+    pub reason: Option<String>,
+}
+
+/// 액세스 토큰 (JWT_ACCESS_TTL_SECS, 기본 24시간)
+fn create_jwt(member: &Member, config: &Config, now: chrono::DateTime<Utc>) -> Result<String, jsonwebtoken::errors::Error> {
+    let claims = Claims::for_member(member, config.jwt_access_ttl_secs, config, now);
     encode(
         &Header::default(),
         &claims,
@@ -235,16 +559,51 @@ pub fn setup_routes(config: &mut web::ServiceConfig) {
     config
         .service(
             web::scope("/api")
+                // 외부 공개 API (API 키 인증, 읽기 전용)
+                .service(
+                    web::scope("/public/v1")
+                        .route("/markers/search", web::get().to(public_search_markers))
+                        .route("/emotions/stats", web::get().to(public_emotion_stats))
+                )
+                .route("/public/emotion-tiles/{z}/{x}/{y}.json", web::get().to(public_emotion_tile))
                 .route("/health", web::get().to(health_check))
+                .route("/health/ready", web::get().to(readiness_check))
+                .route("/metrics", web::get().to(get_metrics))
+                .route("/app/bootstrap", web::get().to(app_bootstrap))
                 .route("/markers", web::get().to(get_markers))
-                .route("/markers", web::post().to(
-                    |db, payload, config, req| create_marker(db, payload, config, req)
-                ))
+                .service(
+                    web::resource("/markers")
+                        .wrap(from_fn(idempotent_requests))
+                        .route(web::post().to(
+                            |db, payload, config, profiles, clock, marker_events, cluster_cache, member| create_marker(db, payload, config, profiles, clock, marker_events, cluster_cache, member)
+                        ))
+                )
+                .route("/markers/from-url", web::post().to(create_marker_draft_from_url))
                 .route("/markers/feed", web::get().to(get_markers_feed))
+                .route("/markers/search", web::get().to(search_markers))
+                .route("/search", web::get().to(search_markers))
                 .route("/markers/cluster", web::get().to(get_markers_cluster))
                 .route("/markers/rank", web::get().to(get_markers_rank))
+                .route("/markers/recommended", web::get().to(get_recommended_markers))
+                .route("/markers/suggest-location", web::get().to(suggest_marker_location))
+                .route("/markers/nearby", web::get().to(get_nearby_markers))
+                .route("/markers/heatmap", web::get().to(get_emotion_heatmap))
+                .route("/markers/stats", web::get().to(get_marker_stats))
+                // 공유 링크/QR 코드 (숫자 ID 대신 공개 ID)
+                .route("/m/{public_id}", web::get().to(get_shared_marker))
+                .route("/m/{public_id}/card.png", web::get().to(get_marker_share_card))
+                .route("/markers/{id}/share", web::post().to(share_marker))
                 .route("/markers/{id}", web::get().to(get_marker_detail))
+                .route("/markers/{id}", web::patch().to(update_marker))
                 .route("/markers/{id}/detail", web::get().to(get_marker_detail_with_view))
+                .route("/markers/{id}/presence/stream", web::get().to(stream_marker_presence))
+                .route("/ws", web::get().to(marker_updates_socket))
+                .route("/markers/{id}/claims", web::post().to(submit_marker_claim))
+                .route("/markers/{id}/crosspost", web::post().to(crosspost_marker))
+                .route("/markers/{id}/report", web::post().to(report_marker))
+                .route("/client-errors", web::post().to(report_client_error))
+                .route("/promotions/{id}/impression", web::post().to(record_promotion_impression))
+                .route("/promotions/{id}/click", web::post().to(record_promotion_click))
                 .route("/markers/{id}/reaction", web::post().to(toggle_marker_reaction))
                 .route("/markers/{id}/bookmark", web::post().to(toggle_marker_bookmark))
                 .route("/markers/{id}/likes/new", web::post().to(toggle_like_new))
@@ -252,9 +611,15 @@ pub fn setup_routes(config: &mut web::ServiceConfig) {
                 .route("/markers/{id}/likes", web::get().to(get_marker_likes))
                 .route("/likes/stats", web::get().to(get_like_stats))
                 .route("/emotions", web::get().to(get_emotions))
+                .route("/tags/trending", web::get().to(get_trending_tags))
                 .route("/markers/{id}/view", web::post().to(add_marker_view))
                 .route("/markers/{id}/images", web::get().to(get_marker_images))
-                .route("/markers/{id}/images", web::post().to(add_marker_image))
+                .service(
+                    web::resource("/markers/{id}/images")
+                        .wrap(from_fn(idempotent_requests))
+                        .route(web::post().to(add_marker_image))
+                )
+                .route("/markers/{id}/images/order", web::put().to(reorder_marker_images))
                 .route("/markers/{id}/images/{image_id}", web::delete().to(delete_marker_image))
                 .route("/markers/{id}/images/{image_id}/primary", web::put().to(set_marker_primary_image))
                 .route("/markers/{id}/images/{image_id}/order", web::put().to(update_marker_image_order))
@@ -266,49 +631,174 @@ pub fn setup_routes(config: &mut web::ServiceConfig) {
                 .route("/members/{id}/markers/interactions/{interaction_type}", web::get().to(get_member_markers_by_interaction))
                 .route("/members/{id}/markers/with-details", web::get().to(get_member_markers_with_details))
                 .route("/members/{id}/markers/stats", web::get().to(get_member_marker_stats))
+                .route("/members/{id}/emotion-profile", web::get().to(get_member_emotion_profile))
+                .route("/members/{id}/emotions/summary", web::get().to(get_member_emotion_profile)) // 프로필 "나의 감정 지도" 위젯용 (emotion-profile과 같은 응답)
+                .route("/members/{id}/follow", web::post().to(follow_member))
+                .route("/members/{id}/follow", web::delete().to(unfollow_member))
+                .route("/members/{id}/collections", web::get().to(get_member_collections))
+                .route("/bookmark-folders", web::get().to(list_bookmark_folders))
+                .route("/bookmark-folders", web::post().to(create_bookmark_folder))
+                .route("/bookmark-folders/order", web::put().to(reorder_bookmark_folders))
+                .route("/bookmark-folders/{id}", web::patch().to(update_bookmark_folder))
+                .route("/bookmark-folders/{id}", web::delete().to(delete_bookmark_folder))
+                .route("/bookmarks/folder", web::put().to(move_bookmarks))
+                .route("/collections", web::post().to(create_collection))
+                .route("/collections/{id}", web::get().to(get_collection))
+                .route("/collections/{id}", web::patch().to(update_collection))
+                .route("/collections/{id}", web::delete().to(delete_collection))
+                .route("/collections/{id}/markers", web::post().to(add_collection_marker))
+                .route("/collections/{id}/markers/order", web::put().to(reorder_collection_markers))
+                .route("/collections/{id}/markers/{marker_id}", web::delete().to(remove_collection_marker))
                 .route("/members", web::post().to(register_member))
                 .route("/members", web::get().to(list_members))
                 .route("/members/me", web::get().to(
-                    |db, config, req| get_me(db, config, req)
+                    |db, member| get_me(db, member)
                 ))
+                .route("/members/me", web::patch().to(update_my_profile))
+                .route("/members/me", web::delete().to(delete_my_account))
+                .route("/members/me/languages", web::put().to(update_my_languages))
+                .route("/members/me/trust", web::get().to(get_my_trust))
+                .route("/members/me/memories", web::get().to(get_my_memories))
+                .route("/members/me/markers/unpublished", web::get().to(get_my_unpublished_markers))
+                .route("/members/me/memories/notification", web::put().to(update_memories_notification))
+                .route("/members/me/notifications", web::get().to(get_my_notifications))
+                .route("/members/me/notification-preferences", web::get().to(get_my_notification_preferences))
+                .route("/members/me/notification-preferences", web::put().to(update_my_notification_preferences))
+                .route("/members/me/devices", web::post().to(register_my_device))
+                .route("/members/me/devices", web::delete().to(unregister_my_device))
+                .route("/members/me/activity", web::get().to(get_my_activity))
+                .route("/members/me/notifications-feed", web::get().to(get_my_notifications_feed))
+                .route("/members/me/marker-claims", web::get().to(get_my_marker_claims))
+                .route("/members/me/connected-accounts", web::get().to(get_my_connected_accounts))
+                .route("/members/me/connected-accounts", web::post().to(connect_account))
+                .route("/members/me/connected-accounts/{platform}", web::delete().to(disconnect_account))
+                .route("/members/me/export", web::get().to(export_my_data))
+                .route("/members/me/exports/{id}", web::get().to(get_my_data_export))
+                .route("/members/me/exports/{id}/download", web::get().to(download_my_data_export))
+                .route("/admin/markers/{id}/takedown", web::post().to(takedown_marker))
+                .route("/admin/markers/{id}/restore", web::post().to(restore_marker))
+                .route("/admin/marker-images/{id}/takedown", web::post().to(takedown_marker_image))
+                .route("/admin/marker-images/{id}/restore", web::post().to(restore_marker_image))
+                .route("/admin/takedowns", web::get().to(get_takedown_audit))
+                .route("/admin/members", web::get().to(admin_list_members))
+                .route("/admin/members/{id}/suspend", web::post().to(suspend_member))
+                .route("/admin/members/{id}/reactivate", web::post().to(reactivate_member))
+                .route("/admin/members/{id}/force-logout", web::post().to(force_logout_member))
+                .route("/admin/members/{id}/trust-level", web::put().to(set_member_trust_level))
+                .route("/admin/export/markers", web::post().to(request_marker_export))
+                .route("/admin/markers/export", web::get().to(stream_admin_marker_export))
+                .route("/admin/export/jobs/{id}", web::get().to(get_marker_export_status))
+                .route("/admin/export/jobs/{id}/download", web::get().to(download_marker_export))
+                .route("/admin/geocode-backfill", web::get().to(get_geocode_backfill_progress))
+                .route("/admin/marker-claims", web::get().to(list_marker_claims))
+                .route("/admin/marker-claims/{id}/approve", web::post().to(approve_marker_claim))
+                .route("/admin/marker-claims/{id}/reject", web::post().to(reject_marker_claim))
+                .route("/admin/marker-claims/{id}/document", web::get().to(download_marker_claim_document))
+                .route("/admin/marker-reports", web::get().to(list_marker_reports))
+                .route("/admin/marker-reports/{id}/resolve", web::post().to(dismiss_marker_reports))
+                .route("/admin/marker-reports/{id}/hide", web::post().to(hide_reported_marker))
+                .route("/admin/dlq", web::get().to(list_dead_letter_jobs))
+                .route("/admin/dlq/{id}/retry", web::post().to(retry_dead_letter_job))
+                .route("/admin/dlq/{id}/discard", web::post().to(discard_dead_letter_job))
+                .route("/admin/storage/orphans", web::get().to(list_orphaned_files))
+                .route("/admin/client-errors", web::get().to(list_client_errors))
+                .route("/admin/promotions", web::post().to(create_marker_promotion))
+                .route("/admin/promotions", web::get().to(list_marker_promotions))
+                .route("/admin/promotions/{id}", web::patch().to(update_marker_promotion))
+                .route("/admin/promotions/{id}/report", web::get().to(get_marker_promotion_report))
+                .service(
+                    web::resource("/admin/districts/import")
+                        // 시군구 경계 GeoJSON은 수십 MB까지 커질 수 있음
+                        .app_data(web::JsonConfig::default().limit(DISTRICT_IMPORT_MAX_BYTES))
+                        .route(web::post().to(import_districts))
+                )
+                .route("/districts/{code}/stats", web::get().to(get_district_stats))
+                .route("/admin/api-keys", web::post().to(create_api_key))
+                .route("/admin/api-keys/{id}/metrics", web::get().to(get_api_key_metrics))
+                .route("/admin/usage", web::get().to(get_route_usage))
                 .route("/members/{id}", web::get().to(get_member_by_id))
                 .route("/members/{id}/with-markers", web::get().to(get_member_with_markers))
                 .route("/members/{id}/with-marker-details", web::get().to(get_member_with_marker_details))
                 .route("/members/{id}/with-stats", web::get().to(get_member_with_stats))
-                .route("/auth/register", web::post().to(
-                    |db, payload, config| register_social_member(db, payload, config)
-                ))
-                .route("/auth/login", web::post().to(
-                    |db, payload, config| login_member(db, payload, config)
-                ))
+                // 무차별 대입 방지: IP별 제한 (계정별 제한은 핸들러에서)
+                .service(
+                    web::resource("/auth/register")
+                        .wrap(from_fn(limit_auth_requests))
+                        .route(web::post().to(
+                            |db, payload, config, clock, limiter| register_social_member(db, payload, config, clock, limiter)
+                        ))
+                )
+                .service(
+                    web::resource("/auth/login")
+                        .wrap(from_fn(limit_auth_requests))
+                        .route(web::post().to(
+                            |req, db, payload, config, clock, limiter| login_member(req, db, payload, config, clock, limiter)
+                        ))
+                )
                 .route("/auth/social-login", web::post().to(
-                    |db, payload, config| social_login(db, payload, config)
-                ))
-                .route("/auth/google-id-token", web::post().to(
-                    |db, payload, config| google_id_token_login(db, payload, config)
+                    |db, payload, config, clock| social_login(db, payload, config, clock)
                 ))
+                .service(
+                    web::resource("/auth/google-id-token")
+                        .wrap(from_fn(limit_auth_requests))
+                        .route(web::post().to(
+                            |db, payload, config, clock| google_id_token_login(db, payload, config, clock)
+                        ))
+                )
+                .service(
+                    web::resource("/auth/2fa/setup")
+                        .wrap(from_fn(limit_auth_requests))
+                        .route(web::post().to(setup_two_factor))
+                )
+                .service(
+                    web::resource("/auth/2fa/verify")
+                        .wrap(from_fn(limit_auth_requests))
+                        .route(web::post().to(verify_two_factor))
+                )
                 .route("/auth/profile", web::get().to(
-                    |db, config, req| verify_profile(db, config, req)
+                    |db, member| verify_profile(db, member)
                 ))
                 .service(
                     web::scope("/images")
+                        .wrap(from_fn(idempotent_requests))
+                        .wrap(from_fn(shed_uploads))
                         .route("/upload/thumbnail", web::post().to(upload_thumbnail))
                         .route("/upload/map", web::post().to(upload_map_image))
                         .route("/generate/thumbnail", web::post().to(generate_thumbnail))
                         .route("/info/{filename:.*}", web::get().to(get_image_info))
-                        .route("/download/{filename:.*}", web::get().to(download_image))
+                        // original/은 아래 전체 경로 패턴보다 먼저 등록해야 잡힘
                         .route("/download/original/{filename:.*}", web::get().to(download_original_image))
+                        .route("/download/{filename:.*}", web::get().to(download_image))
                         .route("/list", web::get().to(list_images))
                         .route("/stats", web::get().to(get_image_stats))
                 )
+                .route("/storage/{key:.*}", web::get().to(download_presigned_file))
                 .service(
                     web::scope("/s3")
-                        .route("/upload/thumbnail", web::post().to(upload_thumbnail_s3))
-                        .route("/upload/normal", web::post().to(upload_thumbnail_s3))
-                        .route("/upload/map", web::post().to(upload_map_s3))
-                        .route("/upload/circular", web::post().to(upload_circular_thumbnail_s3))
+                        .wrap(from_fn(idempotent_requests))
+                        .wrap(from_fn(shed_uploads))
+                        .route("/upload/thumbnail", web::post().to(upload_thumbnail))
+                        .route("/upload/normal", web::post().to(upload_thumbnail))
+                        .route("/upload/map", web::post().to(upload_map_image))
+                        .route("/upload/circular", web::post().to(upload_circular_thumbnail))
+                )
+                // 이어 받는 업로드 (tus 1.0.0)
+                .service(
+                    web::scope("/uploads")
+                        .route("", web::post().to(create_upload_session))
+                        .route("", web::method(actix_web::http::Method::OPTIONS).to(upload_session_options))
+                        .route("/{id}", web::head().to(head_upload_session))
+                        .route("/{id}", web::get().to(get_upload_session))
+                        .route("/{id}", web::patch().to(patch_upload_session))
+                        .route("/{id}", web::delete().to(delete_upload_session))
                 )
         )
+        // 외부 페이지 임베드 (iframe/oEmbed, 로그인 없이 공개 마커만)
+        .route("/embed/markers/{public_id}", web::get().to(embed_marker))
+        .route("/embed/markers/{public_id}/map.png", web::get().to(embed_marker_map))
+        .route("/oembed", web::get().to(oembed_marker))
+        // 공유 토큰 링크 (POST /api/markers/{id}/share로 만든 짧은 링크)
+        .route("/s/{token}", web::get().to(open_shared_marker))
         .route("/", web::get().to(index));
 }
 
@@ -319,6 +809,84 @@ async fn index() -> Result<HttpResponse> {
     })))
 }
 
+/// 운영 지표 (업로드 부하 차단 카운터, 이미지 파이프라인 단계별 시간 등)
+async fn get_metrics(
+    upload_limiter: web::Data<UploadLimiter>,
+    auth_rate_limiter: web::Data<AuthRateLimiter>,
+    image_hooks: web::Data<ImageHooks>,
+    marker_events: web::Data<MarkerEvents>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": {
+            "uploads": upload_limiter.metrics_json(),
+            "authRateLimited": auth_rate_limiter.rejected_count(),
+            "imagePipeline": image_hooks.metrics_json(),
+            "markerSubscribers": marker_events.subscriber_count()
+        }
+    })))
+}
+
+/// 앱 시작 정보: 접속 IP 기준 첫 지도 위치/지역과 표시 언어 (로그인 불필요)
+async fn app_bootstrap(
+    req: actix_web::HttpRequest,
+    config: web::Data<Config>,
+    geoip: web::Data<GeoIp>,
+) -> Result<HttpResponse> {
+    let location = req.connection_info()
+        .realip_remote_addr()
+        .and_then(parse_client_ip)
+        .and_then(|ip| geoip.lookup(ip))
+        .filter(|location| location.latitude.is_some() && location.longitude.is_some());
+
+    // 언어는 브라우저 설정 우선, 없으면 국가 기본 언어
+    let locale = req.headers()
+        .get("Accept-Language")
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_accept_language)
+        .or_else(|| location.as_ref().and_then(|l| l.country_code.as_deref()).map(language_for_country))
+        .unwrap_or("ko");
+
+    let (map, region, source) = match &location {
+        Some(location) => (
+            serde_json::json!({
+                "latitude": location.latitude,
+                "longitude": location.longitude,
+                "zoom": location.suggested_zoom()
+            }),
+            serde_json::json!({
+                "countryCode": location.country_code,
+                "country": location.country,
+                "subdivision": location.subdivision,
+                "city": location.city
+            }),
+            "geoip",
+        ),
+        None => (
+            serde_json::json!({
+                "latitude": config.default_map_latitude,
+                "longitude": config.default_map_longitude,
+                "zoom": config.default_map_zoom
+            }),
+            serde_json::Value::Null,
+            "default",
+        ),
+    };
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "private, max-age=3600"))
+        .insert_header(("Vary", "Accept-Language"))
+        .json(serde_json::json!({
+            "success": true,
+            "data": {
+                "map": map,
+                "region": region,
+                "locale": locale,
+                "source": source
+            }
+        })))
+}
+
 async fn health_check() -> Result<HttpResponse> {
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "status": "healthy",
@@ -326,12 +894,38 @@ async fn health_check() -> Result<HttpResponse> {
     })))
 }
 
+/// 준비 상태: DB 연결 + 스키마 검증 (불일치 시 503과 누락 목록)
+async fn readiness_check(db: web::Data<Database>) -> Result<HttpResponse> {
+    match verify_schema(&db.pool).await {
+        Ok(report) if report.is_ok() => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "status": "ready",
+            "schema": report
+        }))),
+        Ok(report) => {
+            report.log();
+            Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "status": "not_ready",
+                "reason": "schema_drift",
+                "schema": report
+            })))
+        }
+        Err(e) => {
+            error!("❌ 준비 상태 확인 실패: {}", e);
+            Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "status": "not_ready",
+                "reason": "database_unavailable"
+            })))
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct MarkersQuery {
-    lat: f64,
-    lng: f64,
-    lat_delta: f64,
-    lng_delta: f64,
+    lat: Option<f64>,
+    lng: Option<f64>,
+    lat_delta: Option<f64>,
+    lng_delta: Option<f64>,
+    district_code: Option<String>, // 행정구역 코드 (시도 2자리, 시군구 5자리). 지정하면 영역 없이도 조회 가능
     zoom: Option<i32>,
     emotion_tags: Option<String>,
     min_likes: Option<i32>,
@@ -340,6 +934,22 @@ pub struct MarkersQuery {
     sort_order: Option<String>,
     limit: Option<i32>,
     my: Option<bool>, // 추가: 내 마커만 표시 (기본 false)
+    created_hour_range: Option<String>, // 생성 시간대 (한국 시간, 예: "22-5"는 밤 10시~새벽 5시)
+    season: Option<String>, // 생성 계절 (spring, summer, autumn, winter, 쉼표로 여러 개)
+    created_after: Option<String>, // RFC 3339 또는 YYYY-MM-DD, 이후(포함) 작성된 마커만
+    created_before: Option<String>, // 이 시각 전에 작성된 마커만
+    period: Option<String>, // today, week, month (한국 시간 기준 오늘/이번 주/이번 달)
+    tag: Option<String>, // 해시태그 (# 없이, 대소문자 무시)
+    format: Option<String>, // json(기본) 또는 geojson (FeatureCollection)
+}
+
+/// 마커 실시간 구독 첫 영역 (GET /api/markers와 같은 중심 + 폭)
+#[derive(Deserialize)]
+pub struct MarkerSocketQuery {
+    lat: Option<f64>,
+    lng: Option<f64>,
+    lat_delta: Option<f64>,
+    lng_delta: Option<f64>,
 }
 
 #[derive(Deserialize)]
@@ -350,19 +960,52 @@ pub struct MarkersFeedQuery {
     min_likes: Option<i32>,
     min_views: Option<i32>,
     user_id: Option<i64>, // 특정 사용자의 마커만 조회
+    lang: Option<String>, // 콘텐츠 언어 필터 (예: "ko,en", "all"이면 필터 해제). 없으면 회원 선호 언어 사용
+    created_hour_range: Option<String>, // 생성 시간대 (한국 시간, 예: "22-5")
+    season: Option<String>, // 생성 계절 (spring, summer, autumn, winter)
+    created_after: Option<String>, // RFC 3339 또는 YYYY-MM-DD (포함)
+    created_before: Option<String>, // 미포함
+    period: Option<String>, // today, week, month
+    lat: Option<f64>, // 현재 위치 (지역 대상 프로모션용, 없으면 IP 위치)
+    lng: Option<f64>,
+    tag: Option<String>, // 해시태그 (# 없이, 대소문자 무시)
+}
+
+#[derive(Deserialize)]
+pub struct MarkerSearchQuery {
+    pub q: Option<String>, // 검색어 (설명, 작성자, 감성 태그)
+    pub page: Option<i32>,
+    pub limit: Option<i32>,
+    pub lat_min: Option<f64>, // 지도 영역 (네 값 모두 있을 때만 적용)
+    pub lat_max: Option<f64>,
+    pub lng_min: Option<f64>,
+    pub lng_max: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct EmotionProfileQuery {
+    pub year: Option<i32>, // 연도 (1~12월), 없으면 최근 months개월
+    pub months: Option<u32>, // 기본 12, 최대 60
+}
+
+#[derive(Deserialize)]
+pub struct TrendingTagsQuery {
+    pub hours: Option<i64>, // 집계 구간 (기본 24시간, 최대 30일)
+    pub limit: Option<i64>,
 }
 
 async fn get_markers(
     query: web::Query<MarkersQuery>,
     pool: web::Data<PgPool>,
-    config: web::Data<Config>,
-    req: actix_web::HttpRequest,
+    clock: web::Data<dyn Clock>,
+    member: Option<AuthenticatedMember>,
 ) -> Result<HttpResponse> {
     info!("🔍 마커 조회 요청 받음:");
-    info!("   - lat: {}", query.lat);
-    info!("   - lng: {}", query.lng);
-    info!("   - lat_delta: {}", query.lat_delta);
-    info!("   - lng_delta: {}", query.lng_delta);
+    info!("   - lat: {:?}", query.lat);
+    info!("   - lng: {:?}", query.lng);
+    info!("   - lat_delta: {:?}", query.lat_delta);
+    info!("   - lng_delta: {:?}", query.lng_delta);
+    info!("   - district_code: {:?}", query.district_code);
     info!("   - zoom: {:?}", query.zoom);
     info!("   - emotion_tags: {:?}", query.emotion_tags);
     info!("   - min_likes: {:?}", query.min_likes);
@@ -372,8 +1015,55 @@ async fn get_markers(
     info!("   - limit: {:?}", query.limit);
     info!("   - my: {:?}", query.my);
     
+    let format = match ResponseFormat::parse(query.format.as_deref()) {
+        Ok(format) => format,
+        Err(e) => return Ok(ErrorHandler::bad_request("응답 형식이 올바르지 않습니다.", Some(&e), None)),
+    };
     let db = Database { pool: pool.get_ref().clone() };
     
+    // 조회 영역: 지도 영역(lat/lng/delta 모두) 또는 행정구역 코드 중 하나는 필요
+    let bounds = match (query.lat, query.lng, query.lat_delta, query.lng_delta) {
+        (Some(lat), Some(lng), Some(lat_delta), Some(lng_delta)) => Some((lat, lng, lat_delta, lng_delta)),
+        _ => None,
+    };
+    let district_code = query.district_code.as_deref().map(str::trim).filter(|code| !code.is_empty());
+    if let Some(code) = district_code {
+        if !is_valid_district_code(code) {
+            return Ok(ErrorHandler::bad_request(
+                "행정구역 코드 형식이 올바르지 않습니다.",
+                None,
+                Some(&format!("district_code: {}", code))
+            ));
+        }
+        match db.get_district(code).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return Ok(ErrorHandler::not_found("행정구역을 찾을 수 없습니다.")),
+            Err(e) => {
+                return Ok(ErrorHandler::internal_server_error(
+                    "행정구역 조회 실패",
+                    Some(&format!("데이터베이스 오류: {}", e))
+                ));
+            }
+        }
+    } else if bounds.is_none() {
+        return Ok(ErrorHandler::bad_request(
+            "lat, lng, lat_delta, lng_delta 또는 district_code가 필요합니다.",
+            None,
+            None
+        ));
+    }
+    
+    let time_filter = match CreatedTimeFilter::parse(query.created_hour_range.as_deref(), query.season.as_deref())
+        .and_then(|filter| filter.with_created_range(query.created_after.as_deref(), query.created_before.as_deref(), query.period.as_deref(), clock.now()))
+    {
+        Ok(filter) => filter,
+        Err(e) => return Ok(ErrorHandler::bad_request("시간대/계절/기간 필터가 올바르지 않습니다.", Some(&e), None)),
+    };
+    let hashtag = match parse_hashtag_filter(query.tag.as_deref()) {
+        Ok(tag) => tag,
+        Err(e) => return Ok(ErrorHandler::bad_request("해시태그 형식이 올바르지 않습니다.", Some(&e), None)),
+    };
+    
     // 감성 태그 파싱
     let emotion_tags = query.emotion_tags.as_ref().map(|tags| {
         let parsed_tags: Vec<String> = tags.split(',')
@@ -392,30 +1082,24 @@ async fn get_markers(
 
     // 내 마커만 조회 옵션 처리
     let mut user_id: Option<i64> = None;
-    let mut current_user_id: Option<i64> = None;
-    
     // 토큰에서 현재 사용자 ID 추출 (공유 옵션 필터링용)
-    if let Ok(uid) = extract_user_id_from_token(&req, &config) {
-        current_user_id = Some(uid);
-    }
+    let current_user_id = member.map(|m| m.member_id);
     
     if query.my.unwrap_or(false) {
         // 내 마커만 조회하는 경우
         if let Some(uid) = current_user_id {
             user_id = Some(uid);
         } else {
-            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                "success": false,
-                "message": "내 마커만 조회하려면 로그인(JWT)이 필요합니다."
-            })));
+            return Ok(ErrorHandler::unauthorized(
+                "내 마커만 조회하려면 로그인(JWT)이 필요합니다.",
+                None
+            ));
         }
     }
     
     match db.get_markers(
-        query.lat,
-        query.lng,
-        query.lat_delta,
-        query.lng_delta,
+        bounds,
+        district_code,
         emotion_tags,
         query.min_likes,
         query.min_views,
@@ -424,6 +1108,8 @@ async fn get_markers(
         query.limit,
         user_id, // 내 마커만 조회할 때 사용
         current_user_id, // 공유 옵션 필터링용
+        &time_filter,
+        hashtag.as_deref(),
     ).await {
         Ok(markers) => {
             info!("✅ 마커 조회 성공: {}개 마커 반환", markers.len());
@@ -440,27 +1126,23 @@ async fn get_markers(
                     }
                 };
                 
-                let formatted_images: Vec<serde_json::Value> = images.iter()
-                    .map(|image| serde_json::json!({
-                        "id": image.id,
-                        "markerId": image.marker_id,
-                        "imageType": image.image_type,
-                        "imageUrl": image.image_url,
-                        "imageOrder": image.image_order,
-                        "isPrimary": image.is_primary,
-                        "createdAt": image.created_at,
-                        "updatedAt": image.updated_at
-                    }))
+                let formatted_images: Vec<MarkerImageDto> = images.iter()
+                    .map(MarkerImageDto::from)
                     .collect();
                 
-                let mut marker_data = marker_to_camelcase_json(marker);
-                if let Some(marker_obj) = marker_data.as_object_mut() {
-                    marker_obj.insert("images".to_string(), serde_json::Value::Array(formatted_images));
-                }
+                let marker_data = MarkerDto::from(marker).with_images(formatted_images);
                 
                 formatted_markers.push(marker_data);
             }
             
+            if format == ResponseFormat::GeoJson {
+                let features = formatted_markers.iter()
+                    .map(|marker| geojson::marker_feature(serde_json::json!(marker)))
+                    .collect();
+                return Ok(HttpResponse::Ok()
+                    .content_type(geojson::GEOJSON_CONTENT_TYPE)
+                    .json(geojson::feature_collection(features, serde_json::json!({ "count": markers.len() }))));
+            }
             Ok(HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
                 "data": formatted_markers,
@@ -477,465 +1159,363 @@ async fn get_markers(
     }
 }
 
-// S3 업로드 함수들
-async fn upload_thumbnail_s3(
+// 이미지 업로드 (/api/images, /api/s3 모두 설정된 저장소 사용)
+async fn upload_thumbnail(
     payload: Multipart, 
+    query: web::Query<UploadQuery>,
     pool: web::Data<PgPool>, 
     config: web::Data<Config>,
-    s3_service: web::Data<S3Service>
+    storage: web::Data<dyn Storage>,
+    keys: web::Data<ObjectKeys>,
+    image_hooks: web::Data<ImageHooks>,
 ) -> Result<HttpResponse> {
-    let processor = ImageProcessor::new(
-        config.thumbnail_max_width,
-        config.thumbnail_max_height,
-        config.thumbnail_quality
-    );
-    upload_image_s3(payload, "thumbnail", processor, pool, config, s3_service).await
+    let processor = upload_processor("thumbnail", &config, &image_hooks, query.output_format);
+    let target = UploadTarget { image_type: "thumbnail", extract_exif: query.exif };
+    upload_image_to_storage(payload, target, processor, pool, config, storage, keys).await
 }
 
-async fn upload_map_s3(
+async fn upload_map_image(
     payload: Multipart, 
+    query: web::Query<UploadQuery>,
     pool: web::Data<PgPool>, 
     config: web::Data<Config>,
-    s3_service: web::Data<S3Service>
+    storage: web::Data<dyn Storage>,
+    keys: web::Data<ObjectKeys>,
+    image_hooks: web::Data<ImageHooks>,
 ) -> Result<HttpResponse> {
-    let processor = ImageProcessor::new(
-        config.map_max_width,
-        config.map_max_height,
-        config.map_quality
-    );
-    upload_image_s3(payload, "map", processor, pool, config, s3_service).await
+    let processor = upload_processor("map", &config, &image_hooks, query.output_format);
+    let target = UploadTarget { image_type: "map", extract_exif: query.exif };
+    upload_image_to_storage(payload, target, processor, pool, config, storage, keys).await
 }
 
-async fn upload_circular_thumbnail_s3(
+async fn upload_circular_thumbnail(
     payload: Multipart, 
-    pool: web::Data<PgPool>, 
     config: web::Data<Config>,
-    s3_service: web::Data<S3Service>
+    storage: web::Data<dyn Storage>,
+    keys: web::Data<ObjectKeys>,
+    image_hooks: web::Data<ImageHooks>,
 ) -> Result<HttpResponse> {
-    let processor = ImageProcessor::new(250, 250, 85);
-    upload_circular_thumbnail_s3_internal(payload, "circular_thumbnail", processor, pool, config, s3_service).await
+    let processor = ImageProcessor::new(250, 250, 85).with_hooks(&image_hooks);
+    upload_circular_thumbnail_to_storage(payload, "circular_thumbnail", processor, config, storage, keys).await
 }
 
-async fn upload_thumbnail(payload: Multipart, pool: web::Data<PgPool>, config: web::Data<Config>) -> Result<HttpResponse> {
-    let processor = ImageProcessor::new(
-        config.thumbnail_max_width,
-        config.thumbnail_max_height,
-        config.thumbnail_quality
+async fn generate_thumbnail(
+    payload: Multipart, 
+    config: web::Data<Config>,
+    storage: web::Data<dyn Storage>,
+    keys: web::Data<ObjectKeys>,
+    image_hooks: web::Data<ImageHooks>,
+) -> Result<HttpResponse> {
+    // 150x150 원형 썸네일
+    let processor = ImageProcessor::new(150, 150, 85).with_hooks(&image_hooks);
+    upload_circular_thumbnail_to_storage(payload, "generated_thumbnail", processor, config, storage, keys).await
+}
+
+// 이어 받는 업로드 (tus 1.0.0): POST로 세션 생성 → PATCH로 Upload-Offset부터 이어 보냄 → HEAD로 받은 위치 확인
+fn tus_header_error(req: &actix_web::HttpRequest) -> Option<HttpResponse> {
+    let version = req.headers().get("Tus-Resumable").and_then(|v| v.to_str().ok());
+    if version == Some(resumable_upload::TUS_RESUMABLE) {
+        return None;
+    }
+    let mut response = ErrorHandler::log_and_respond(
+        actix_web::http::StatusCode::PRECONDITION_FAILED,
+        "지원하지 않는 tus 버전입니다 (Tus-Resumable: 1.0.0 필요)",
+        version,
+        None
     );
-    upload_image(payload, "thumbnail", processor, pool, config).await
+    response.headers_mut().insert(
+        actix_web::http::header::HeaderName::from_static("tus-version"),
+        actix_web::http::header::HeaderValue::from_static(resumable_upload::TUS_RESUMABLE),
+    );
+    Some(response)
 }
 
-async fn upload_map_image(payload: Multipart, pool: web::Data<PgPool>, config: web::Data<Config>) -> Result<HttpResponse> {
-    let processor = ImageProcessor::new(
-        config.map_max_width,
-        config.map_max_height,
-        config.map_quality
+/// 응답에 Tus-Resumable과 (있으면) Upload-Offset 헤더 추가
+fn with_tus_headers(mut response: HttpResponse, offset: Option<i64>) -> HttpResponse {
+    let headers = response.headers_mut();
+    headers.insert(
+        actix_web::http::header::HeaderName::from_static("tus-resumable"),
+        actix_web::http::header::HeaderValue::from_static(resumable_upload::TUS_RESUMABLE),
     );
-    upload_image(payload, "map", processor, pool, config).await
+    if let Some(offset) = offset {
+        headers.insert(
+            actix_web::http::header::HeaderName::from_static("upload-offset"),
+            actix_web::http::header::HeaderValue::from(offset),
+        );
+    }
+    response
 }
 
-async fn generate_thumbnail(payload: Multipart, pool: web::Data<PgPool>, config: web::Data<Config>) -> Result<HttpResponse> {
-    // 250x250 원형 썸네일용 프로세서 생성
-    let processor = ImageProcessor::new(150, 150, 85);
-    upload_circular_thumbnail(payload, "generated_thumbnail", processor, pool, config).await
+/// 만료되지 않은 세션 조회 (잘못된 id, 없거나 만료된 세션은 404)
+async fn load_upload_session(db: &Database, id: &str, now: chrono::DateTime<Utc>) -> std::result::Result<UploadSession, HttpResponse> {
+    let not_found = || ErrorHandler::not_found("업로드 세션을 찾을 수 없습니다. (만료되었거나 삭제됨)");
+    let id = uuid::Uuid::parse_str(id).map_err(|_| not_found())?;
+    match db.get_upload_session(id, now).await {
+        Ok(Some(session)) => Ok(session),
+        Ok(None) => Err(not_found()),
+        Err(e) => Err(ErrorHandler::internal_server_error(
+            "업로드 세션 조회 실패",
+            Some(&format!("데이터베이스 오류: {}", e))
+        )),
+    }
 }
 
-async fn upload_circular_thumbnail(
-    mut payload: Multipart, 
-    image_type: &str, 
-    processor: ImageProcessor,
-    pool: web::Data<PgPool>,
-    config: web::Data<Config>
+async fn upload_session_options(config: web::Data<Config>) -> Result<HttpResponse> {
+    let max_bytes = (config.max_file_size_mb * 1024.0 * 1024.0) as u64;
+    Ok(HttpResponse::NoContent()
+        .insert_header(("Tus-Resumable", resumable_upload::TUS_RESUMABLE))
+        .insert_header(("Tus-Version", resumable_upload::TUS_RESUMABLE))
+        .insert_header(("Tus-Extension", resumable_upload::TUS_EXTENSIONS))
+        .insert_header(("Tus-Max-Size", max_bytes))
+        .finish())
+}
+
+async fn create_upload_session(
+    req: actix_web::HttpRequest,
+    db: web::Data<Database>,
+    config: web::Data<Config>,
+    sessions: web::Data<UploadSessions>,
+    clock: web::Data<dyn Clock>,
+    ids: web::Data<dyn IdGenerator>,
 ) -> Result<HttpResponse> {
-    let mut image_data = Vec::new();
-    let mut filename = String::new();
-    
-    // 멀티파트 데이터 처리
-    while let Some(Ok(mut field)) = payload.next().await {
-        let content_disposition = field.content_disposition();
-        
-        if let Some(name) = content_disposition.get_name() {
-            if name == "image" {
-                if let Some(original_filename) = content_disposition.get_filename() {
-                    filename = original_filename.to_string();
-                    
-                    // 파일 형식 검증
-                    if !processor.is_valid_image_format(&filename) {
-                        return Ok(ErrorHandler::bad_request(
-                            "지원되지 않는 이미지 형식입니다. (jpg, jpeg, png, gif, bmp, webp)",
-                            Some(&format!("파일명: {}", filename)),
-                            Some("원형 썸네일 업로드 - 파일 형식 검증 실패")
-                        ));
-                    }
-                }
-                
-                // 이미지 데이터 수집
-                while let Some(chunk) = field.next().await {
-                    let data = chunk.map_err(|e| {
-                        actix_web::error::ErrorInternalServerError(format!("파일 읽기 실패: {}", e))
-                    })?;
-                    image_data.extend_from_slice(&data);
-                }
-            }
-        }
-    }
-    
-    if image_data.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(ImageResponse {
-            success: false,
-            message: "이미지 파일이 필요합니다".to_string(),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+    if let Some(response) = tus_header_error(&req) {
+        return Ok(response);
     }
-    
-    // 파일 크기 검증
-    if processor.get_file_size_mb(&image_data) > config.max_file_size_mb {
-        return Ok(ErrorHandler::bad_request(
-            "파일 크기는 30MB를 초과할 수 없습니다",
-            Some(&format!("현재 크기: {:.2}MB", processor.get_file_size_mb(&image_data))),
-            Some("원형 썸네일 업로드 - 파일 크기 초과")
-        ));
+    let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+
+    let Some(upload_length) = header("Upload-Length").and_then(|v| v.trim().parse::<i64>().ok()).filter(|length| *length > 0) else {
+        return Ok(with_tus_headers(ErrorHandler::bad_request(
+            "Upload-Length 헤더가 필요합니다 (1바이트 이상, Upload-Defer-Length는 지원하지 않음)",
+            header("Upload-Length"),
+            None
+        ), None));
+    };
+    let max_bytes = (config.max_file_size_mb * 1024.0 * 1024.0) as i64;
+    if upload_length > max_bytes {
+        return Ok(with_tus_headers(ErrorHandler::log_and_respond(
+            actix_web::http::StatusCode::PAYLOAD_TOO_LARGE,
+            &format!("파일 크기는 {:.0}MB를 초과할 수 없습니다", config.max_file_size_mb),
+            Some(&format!("Upload-Length {}", upload_length)),
+            None
+        ), None));
     }
-    
-    // 원형 썸네일 처리 (크롭 + 원형 마스킹 + WebP 변환)
-    let processed_data = match processor.process_circular_thumbnail(&image_data) {
-        Ok(data) => data,
-        Err(e) => {
-            return Ok(HttpResponse::InternalServerError().json(ImageResponse {
-                success: false,
-                message: format!("이미지 처리 실패: {}", e),
-                filename: None,
-                size_mb: None,
-                width: None,
-                height: None,
-                format: None,
-                url: None,
-            }));
-        }
+
+    let metadata = match resumable_upload::parse_metadata(header("Upload-Metadata").unwrap_or("")) {
+        Ok(metadata) => metadata,
+        Err(e) => return Ok(with_tus_headers(ErrorHandler::bad_request("Upload-Metadata 형식이 잘못되었습니다", Some(&e), None), None)),
     };
-    
-    // 고유한 파일명 생성
-    let timestamp = Utc::now().timestamp();
-    let uuid = Uuid::new_v4().to_string()[..8].to_string();
-    let webp_filename = format!("{}_{}_{}.webp", image_type, uuid, timestamp);
-    
-    // 업로드 디렉토리 생성 (./ 제거)
-    let upload_dir = config.get_upload_path(image_type).trim_start_matches("./").to_string();
-    if let Err(e) = fs::create_dir_all(&upload_dir) {
-        return Ok(HttpResponse::InternalServerError().json(ImageResponse {
-            success: false,
-            message: format!("디렉토리 생성 실패: {}", e),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+    let filename = metadata.get("filename").map(|name| name.trim()).unwrap_or("");
+    if filename.is_empty() {
+        return Ok(with_tus_headers(ErrorHandler::bad_request("Upload-Metadata에 filename이 필요합니다", None, None), None));
     }
-    
-    // 파일 저장 (WebP)
-    let filepath = format!("{}/{}", upload_dir, webp_filename);
-    if let Err(e) = fs::write(&filepath, &processed_data) {
-        return Ok(HttpResponse::InternalServerError().json(ImageResponse {
-            success: false,
-            message: format!("파일 저장 실패: {}", e),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+    if !ImageProcessor::new(0, 0, 0).is_valid_image_format(filename) {
+        return Ok(with_tus_headers(ErrorHandler::bad_request(
+            "지원되지 않는 이미지 형식입니다. (jpg, jpeg, png, gif, bmp, webp)",
+            Some(filename),
+            None
+        ), None));
+    }
+    let image_type = metadata.get("type").map(String::as_str).unwrap_or("thumbnail");
+    if !resumable_upload::RESUMABLE_IMAGE_TYPES.contains(&image_type) {
+        return Ok(with_tus_headers(ErrorHandler::bad_request("type은 thumbnail 또는 map이어야 합니다", Some(image_type), None), None));
+    }
+    let output_format = metadata.get("output_format").map(String::as_str).unwrap_or("webp");
+    if !matches!(output_format, "webp" | "avif") {
+        return Ok(with_tus_headers(ErrorHandler::bad_request("output_format은 webp 또는 avif여야 합니다", Some(output_format), None), None));
     }
 
-    // 원본 파일 저장 (원본 확장자 유지)
-    let original_ext = Path::new(&filename)
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("jpg");
-    let original_uuid = Uuid::new_v4().to_string()[..8].to_string();
-    let original_filename = format!("{}_{}_{}.{}", image_type, original_uuid, timestamp, original_ext);
-    let original_upload_dir = config.get_original_upload_path(image_type).trim_start_matches("./").to_string();
-    if let Err(e) = fs::create_dir_all(&original_upload_dir) {
-        return Ok(HttpResponse::InternalServerError().json(ImageResponse {
-            success: false,
-            message: format!("원본 디렉토리 생성 실패: {}", e),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+    // 받기 시작하기 전에 전체 크기만큼 디스크 여유 확인
+    if let Err(e) = fs::create_dir_all(sessions.dir()) {
+        return Ok(with_tus_headers(crate::upload_spool::SpoolError::Io(e).into_response(), None));
     }
-    let original_filepath = format!("{}/{}", original_upload_dir, original_filename);
-    if let Err(e) = fs::write(&original_filepath, &image_data) {
-        return Ok(HttpResponse::InternalServerError().json(ImageResponse {
-            success: false,
-            message: format!("원본 파일 저장 실패: {}", e),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+    if let Err(e) = crate::upload_spool::ensure_disk_space(sessions.dir(), upload_length as u64, config.min_free_disk_mb) {
+        return Ok(with_tus_headers(e.into_response(), None));
     }
 
-    // DB에 원본 이미지 정보 저장
-    let db = Database { pool: pool.get_ref().clone() };
-    let orig_size = processor.get_file_size_mb(&image_data);
-    let (orig_width, orig_height, orig_format) = match processor.get_image_info(&image_data) {
-        Ok(info) => info,
-        Err(_) => (0, 0, original_ext.to_string()),
-    };
-    let original_id = db.save_original_image(
-        &original_filename,
-        &filename,
-        &original_filepath.trim_start_matches("./"),
-        orig_size,
-        Some(orig_width),
-        Some(orig_height),
-        &orig_format,
-    ).await.map_err(|e| actix_web::error::ErrorInternalServerError(format!("원본 DB 저장 실패: {}", e)))?;
-
-    // DB에 WebP 이미지 정보 저장
-    // WebP 이미지 정보 추출
-    let (webp_width, webp_height, _) = match processor.get_image_info(&processed_data) {
-        Ok(info) => info,
-        Err(_) => (0, 0, "webp".to_string()),
-    };
-    let webp_size = processor.get_file_size_mb(&processed_data);
-    db.save_webp_image(
-        original_id,
-        &webp_filename,
-        &filepath.trim_start_matches("./"),
-        webp_size,
-        Some(webp_width),
-        Some(webp_height),
-        image_type,
-    ).await.map_err(|e| actix_web::error::ErrorInternalServerError(format!("WebP DB 저장 실패: {}", e)))?;
+    let new_session = NewUploadSession {
+        id: ids.new_id(),
+        image_type: image_type.to_string(),
+        filename: filename.to_string(),
+        upload_length,
+        output_format: output_format.to_string(),
+        extract_exif: metadata.get("exif").is_some_and(|exif| exif == "true"),
+    };
+    let session = match db.create_upload_session(&new_session, clock.now() + sessions.ttl()).await {
+        Ok(session) => session,
+        Err(e) => {
+            return Ok(ErrorHandler::internal_server_error(
+                "업로드 세션 생성 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    info!("📦 이어 받는 업로드 세션 생성: {} ({}, {}바이트)", session.id, session.filename, session.upload_length);
 
-    Ok(HttpResponse::Ok().json(ImageResponse {
-        success: true,
-        message: "원형 썸네일 생성 성공".to_string(),
-        filename: Some(webp_filename.clone()),
-        size_mb: Some(webp_size),
-        width: Some(webp_width),
-        height: Some(webp_height),
-        format: Some("webp".to_string()),
-        url: Some(config.get_file_url(&webp_filename)),
-    }))
+    Ok(HttpResponse::Created()
+        .insert_header(("Location", format!("/api/uploads/{}", session.id)))
+        .insert_header(("Upload-Expires", session.expires_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()))
+        .insert_header(("Tus-Resumable", resumable_upload::TUS_RESUMABLE))
+        .json(serde_json::json!({
+            "success": true,
+            "data": UploadSessionDto::from(&session)
+        })))
 }
 
-async fn upload_image(
-    mut payload: Multipart, 
-    image_type: &str, 
-    processor: ImageProcessor,
-    pool: web::Data<PgPool>,
-    config: web::Data<Config>
+async fn head_upload_session(
+    path: web::Path<String>,
+    db: web::Data<Database>,
+    clock: web::Data<dyn Clock>,
 ) -> Result<HttpResponse> {
-    let mut image_data = Vec::new();
-    let mut filename = String::new();
-    
-    // 멀티파트 데이터 처리
-    while let Some(Ok(mut field)) = payload.next().await {
-        let content_disposition = field.content_disposition();
-        
-        if let Some(name) = content_disposition.get_name() {
-            if name == "image" {
-                if let Some(original_filename) = content_disposition.get_filename() {
-                    filename = original_filename.to_string();
-                    
-                    // 파일 형식 검증
-                    if !processor.is_valid_image_format(&filename) {
-                        return Ok(HttpResponse::BadRequest().json(ImageResponse {
-                            success: false,
-                            message: "지원되지 않는 이미지 형식입니다. (jpg, jpeg, png, gif, bmp, webp)".to_string(),
-                            filename: None,
-                            size_mb: None,
-                            width: None,
-                            height: None,
-                            format: None,
-                            url: None,
-                        }));
-                    }
-                }
-                
-                // 이미지 데이터 수집
-                while let Some(chunk) = field.next().await {
-                    let data = chunk.map_err(|e| {
-                        actix_web::error::ErrorInternalServerError(format!("파일 읽기 실패: {}", e))
-                    })?;
-                    image_data.extend_from_slice(&data);
-                }
-            }
-        }
+    let session = match load_upload_session(&db, &path, clock.now()).await {
+        Ok(session) => session,
+        Err(response) => return Ok(with_tus_headers(response, None)),
+    };
+    Ok(HttpResponse::Ok()
+        .insert_header(("Upload-Offset", session.upload_offset))
+        .insert_header(("Upload-Length", session.upload_length))
+        .insert_header(("Upload-Expires", session.expires_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()))
+        .insert_header(("Cache-Control", "no-store"))
+        .insert_header(("Tus-Resumable", resumable_upload::TUS_RESUMABLE))
+        .finish())
+}
+
+/// 세션 상태와 (다 받았으면) 처리 결과 조회
+async fn get_upload_session(
+    path: web::Path<String>,
+    db: web::Data<Database>,
+    clock: web::Data<dyn Clock>,
+) -> Result<HttpResponse> {
+    match load_upload_session(&db, &path, clock.now()).await {
+        Ok(session) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": UploadSessionDto::from(&session)
+        }))),
+        Err(response) => Ok(response),
     }
-    
-    if image_data.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(ImageResponse {
-            success: false,
-            message: "이미지 파일이 필요합니다".to_string(),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+}
+
+async fn patch_upload_session(
+    req: actix_web::HttpRequest,
+    path: web::Path<String>,
+    mut payload: web::Payload,
+    db: web::Data<Database>,
+    config: web::Data<Config>,
+    sessions: web::Data<UploadSessions>,
+    image_hooks: web::Data<ImageHooks>,
+) -> Result<HttpResponse> {
+    if let Some(response) = tus_header_error(&req) {
+        return Ok(response);
     }
-    
-    // 파일 크기 검증 (설정에서 가져온 제한)
-    if processor.get_file_size_mb(&image_data) > config.max_file_size_mb {
-        return Ok(HttpResponse::BadRequest().json(ImageResponse {
-            success: false,
-            message: "파일 크기는 30MB를 초과할 수 없습니다".to_string(),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+    let content_type = req.headers().get("Content-Type").and_then(|v| v.to_str().ok());
+    if content_type != Some(resumable_upload::OFFSET_CONTENT_TYPE) {
+        return Ok(with_tus_headers(ErrorHandler::log_and_respond(
+            actix_web::http::StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Content-Type은 application/offset+octet-stream이어야 합니다",
+            content_type,
+            None
+        ), None));
     }
-    
-    // 이미지 처리 (WebP 변환)
-    let processed_data = match processor.process_image(&image_data) {
-        Ok(data) => data,
-        Err(e) => {
-            return Ok(HttpResponse::InternalServerError().json(ImageResponse {
-                success: false,
-                message: format!("이미지 처리 실패: {}", e),
-                filename: None,
-                size_mb: None,
-                width: None,
-                height: None,
-                format: None,
-                url: None,
-            }));
-        }
+    let Some(offset) = req.headers().get("Upload-Offset")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|offset| *offset >= 0)
+    else {
+        return Ok(with_tus_headers(ErrorHandler::bad_request("Upload-Offset 헤더가 필요합니다", None, None), None));
     };
-    
-    // 고유한 파일명 생성
-    let timestamp = Utc::now().timestamp();
-    let uuid = Uuid::new_v4().to_string()[..8].to_string();
-    let webp_filename = format!("{}_{}_{}.webp", image_type, uuid, timestamp);
-    
-    // 업로드 디렉토리 생성 (./ 제거)
-    let upload_dir = config.get_upload_path(image_type).trim_start_matches("./").to_string();
-    if let Err(e) = fs::create_dir_all(&upload_dir) {
-        return Ok(HttpResponse::InternalServerError().json(ImageResponse {
-            success: false,
-            message: format!("디렉토리 생성 실패: {}", e),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+    let (Some(clock), Some(storage), Some(keys)) = (
+        req.app_data::<web::Data<dyn Clock>>(),
+        req.app_data::<web::Data<dyn Storage>>(),
+        req.app_data::<web::Data<ObjectKeys>>(),
+    ) else {
+        return Ok(ErrorHandler::internal_server_error("업로드 처리 설정이 없습니다", None));
+    };
+
+    let mut session = match load_upload_session(&db, &path, clock.now()).await {
+        Ok(session) => session,
+        Err(response) => return Ok(with_tus_headers(response, None)),
+    };
+    if session.status != "uploading" {
+        return Ok(with_tus_headers(
+            ErrorHandler::conflict_with_code("이미 다 받은 업로드입니다. GET으로 결과를 확인하세요.", "UPLOAD_ALREADY_COMPLETE"),
+            Some(session.upload_offset)
+        ));
     }
-    
-    // 파일 저장 (WebP)
-    let filepath = format!("{}/{}", upload_dir, webp_filename);
-    if let Err(e) = fs::write(&filepath, &processed_data) {
-        return Ok(HttpResponse::InternalServerError().json(ImageResponse {
-            success: false,
-            message: format!("파일 저장 실패: {}", e),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+    if offset != session.upload_offset {
+        return Ok(with_tus_headers(
+            ErrorHandler::conflict(
+                "Upload-Offset이 받은 위치와 다릅니다. HEAD로 위치를 확인한 뒤 다시 보내주세요.",
+                Some(&format!("받은 위치 {}, 요청 {}", session.upload_offset, offset))
+            ),
+            Some(session.upload_offset)
+        ));
     }
+    let Some(_lock) = sessions.try_lock(session.id) else {
+        return Ok(with_tus_headers(
+            ErrorHandler::conflict_with_code("다른 요청이 이 업로드를 받는 중입니다", "UPLOAD_IN_PROGRESS"),
+            Some(session.upload_offset)
+        ));
+    };
 
-    // 원본 파일 저장 (원본 확장자 유지)
-    let original_ext = Path::new(&filename)
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("jpg");
-    let original_uuid = Uuid::new_v4().to_string()[..8].to_string();
-    let original_filename = format!("{}_{}_{}.{}", image_type, original_uuid, timestamp, original_ext);
-    let original_upload_dir = config.get_original_upload_path(image_type).trim_start_matches("./").to_string();
-    if let Err(e) = fs::create_dir_all(&original_upload_dir) {
-        return Ok(HttpResponse::InternalServerError().json(ImageResponse {
-            success: false,
-            message: format!("원본 디렉토리 생성 실패: {}", e),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+    let remaining = (session.upload_length - offset) as u64;
+    let appended = match resumable_upload::append_body(&sessions.part_path(session.id), offset as u64, remaining, &mut payload).await {
+        Ok(appended) => appended,
+        Err(e) => return Ok(with_tus_headers(e.into_response(), Some(offset))),
+    };
+    let new_offset = offset + appended.written as i64;
+    if appended.written > 0 {
+        match db.advance_upload_session(session.id, offset, new_offset).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return Ok(with_tus_headers(ErrorHandler::conflict("업로드 세션 상태가 바뀌었습니다. HEAD로 위치를 확인해주세요.", None), None));
+            }
+            Err(e) => {
+                return Ok(with_tus_headers(ErrorHandler::internal_server_error(
+                    "업로드 위치 저장 실패",
+                    Some(&format!("데이터베이스 오류: {}", e))
+                ), Some(offset)));
+            }
+        }
     }
-    let original_filepath = format!("{}/{}", original_upload_dir, original_filename);
-    if let Err(e) = fs::write(&original_filepath, &image_data) {
-        return Ok(HttpResponse::InternalServerError().json(ImageResponse {
-            success: false,
-            message: format!("원본 파일 저장 실패: {}", e),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            url: None,
-        }));
+    if let Some(e) = appended.interrupted {
+        warn!("⚠️ 이어 받는 업로드 중단: {} ({}바이트까지 받음)", session.id, new_offset);
+        return Ok(with_tus_headers(e.into_response(), Some(new_offset)));
+    }
+    if new_offset < session.upload_length {
+        return Ok(with_tus_headers(HttpResponse::NoContent().finish(), Some(new_offset)));
     }
 
-    // DB에 원본 이미지 정보 저장
-    let db = Database { pool: pool.get_ref().clone() };
-    let orig_size = processor.get_file_size_mb(&image_data);
-    let (orig_width, orig_height, orig_format) = match processor.get_image_info(&image_data) {
-        Ok(info) => info,
-        Err(_) => (0, 0, original_ext.to_string()),
-    };
-    let original_id = db.save_original_image(
-        &original_filename,
-        &filename,
-        &original_filepath.trim_start_matches("./"),
-        orig_size,
-        Some(orig_width),
-        Some(orig_height),
-        &orig_format,
-    ).await.map_err(|e| actix_web::error::ErrorInternalServerError(format!("원본 DB 저장 실패: {}", e)))?;
-
-    // DB에 WebP 이미지 정보 저장
-    // WebP 이미지 정보 추출
-    let (webp_width, webp_height, _) = match processor.get_image_info(&processed_data) {
-        Ok(info) => info,
-        Err(_) => (0, 0, "webp".to_string()),
-    };
-    let webp_size = processor.get_file_size_mb(&processed_data);
-    db.save_webp_image(
-        original_id,
-        &webp_filename,
-        &filepath.trim_start_matches("./"),
-        webp_size,
-        Some(webp_width),
-        Some(webp_height),
-        image_type,
-    ).await.map_err(|e| actix_web::error::ErrorInternalServerError(format!("WebP DB 저장 실패: {}", e)))?;
+    session.upload_offset = new_offset;
+    let response = resumable_upload::complete_session(&session, &sessions, &db, &config, storage.get_ref(), keys, &image_hooks).await;
+    Ok(with_tus_headers(response, Some(new_offset)))
+}
 
-    Ok(HttpResponse::Ok().json(ImageResponse {
-        success: true,
-        message: "이미지 업로드 성공".to_string(),
-        filename: Some(webp_filename.clone()),
-        size_mb: Some(webp_size),
-        width: Some(webp_width),
-        height: Some(webp_height),
-        format: Some("webp".to_string()),
-        url: Some(config.get_file_url(&webp_filename)),
-    }))
+async fn delete_upload_session(
+    req: actix_web::HttpRequest,
+    path: web::Path<String>,
+    db: web::Data<Database>,
+    sessions: web::Data<UploadSessions>,
+) -> Result<HttpResponse> {
+    if let Some(response) = tus_header_error(&req) {
+        return Ok(response);
+    }
+    let Ok(id) = uuid::Uuid::parse_str(&path) else {
+        return Ok(with_tus_headers(ErrorHandler::not_found("업로드 세션을 찾을 수 없습니다. (만료되었거나 삭제됨)"), None));
+    };
+    let Some(_lock) = sessions.try_lock(id) else {
+        return Ok(with_tus_headers(ErrorHandler::conflict_with_code("다른 요청이 이 업로드를 받는 중입니다", "UPLOAD_IN_PROGRESS"), None));
+    };
+    match db.delete_upload_session(id).await {
+        Ok(true) => {
+            sessions.discard(id).await;
+            info!("🗑️ 이어 받는 업로드 취소: {}", id);
+            Ok(with_tus_headers(HttpResponse::NoContent().finish(), None))
+        }
+        Ok(false) => Ok(with_tus_headers(ErrorHandler::not_found("업로드 세션을 찾을 수 없습니다. (만료되었거나 삭제됨)"), None)),
+        Err(e) => Ok(ErrorHandler::internal_server_error(
+            "업로드 세션 삭제 실패",
+            Some(&format!("데이터베이스 오류: {}", e))
+        )),
+    }
 }
 
 async fn get_image_info(path: web::Path<String>, config: web::Data<Config>) -> Result<HttpResponse> {
@@ -993,7 +1573,12 @@ async fn get_image_info(path: web::Path<String>, config: web::Data<Config>) -> R
     }))
 }
 
-async fn download_image(path: web::Path<String>, config: web::Data<Config>) -> Result<HttpResponse> {
+async fn download_image(
+    path: web::Path<String>,
+    query: web::Query<WatermarkQuery>,
+    config: web::Data<Config>,
+    watermark: web::Data<Watermark>,
+) -> Result<HttpResponse> {
     let filename = path.into_inner();
     
     // 파일 경로 찾기
@@ -1028,19 +1613,74 @@ async fn download_image(path: web::Path<String>, config: web::Data<Config>) -> R
         }
     };
     
+    // 로컬 저장소 업로드 원본(originals/)은 원본 다운로드와 같이 워터마크
+    if filename.starts_with("originals/") && watermark.applies_to(&query) {
+        return Ok(watermarked_response(&watermark, &file_data));
+    }
+    
     Ok(HttpResponse::Ok()
-        .content_type("image/webp")
+        .content_type(storage::content_type_for(&filepath))
         .body(file_data))
 }
 
-async fn download_original_image(path: web::Path<String>, config: web::Data<Config>) -> Result<HttpResponse> {
-    let filename = path.into_inner();
-    
-    // 원본 파일 경로 찾기
-    let filepath = find_original_image_file(&filename, &config);
-    if filepath.is_empty() {
-        return Ok(HttpResponse::NotFound().json(ImageResponse {
-            success: false,
+/// 워터마크를 찍은 이미지 응답 (찍지 못하면 원본을 그대로 내보내지 않고 500)
+fn watermarked_response(watermark: &Watermark, data: &[u8]) -> HttpResponse {
+    match watermark.apply_to_bytes(data) {
+        Ok((data, content_type)) => HttpResponse::Ok().content_type(content_type).body(data),
+        Err(e) => {
+            error!("❌ 워터마크 적용 실패: {}", e);
+            ErrorHandler::internal_server_error("워터마크 적용 실패", Some(&e.to_string()))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PresignedDownloadQuery {
+    expires: i64,
+    signature: String,
+}
+
+/// 로컬 저장소 서명 URL 다운로드 (Storage::presign으로 만든 링크, 만료 전까지 로그인 없이)
+async fn download_presigned_file(
+    path: web::Path<String>,
+    query: web::Query<PresignedDownloadQuery>,
+    config: web::Data<Config>,
+    storage: web::Data<dyn Storage>,
+    clock: web::Data<dyn Clock>,
+) -> Result<HttpResponse> {
+    if storage.name() != "local" {
+        return Ok(ErrorHandler::not_found("파일을 찾을 수 없습니다"));
+    }
+    let key = path.into_inner();
+    let local = LocalStorage::from_config(&config).with_clock(clock.into_inner());
+    if !local.verify_presigned(&key, query.expires, &query.signature) {
+        return Ok(ErrorHandler::forbidden("만료되었거나 올바르지 않은 다운로드 링크입니다", None));
+    }
+    match storage.get(&key).await {
+        Ok(data) => Ok(HttpResponse::Ok()
+            .content_type(storage::content_type_for(&key))
+            .insert_header(("Cache-Control", "private, no-store"))
+            .body(data)),
+        Err(e) => {
+            warn!("⚠️ 서명 URL 파일 읽기 실패: {} - {}", key, e);
+            Ok(ErrorHandler::not_found("파일을 찾을 수 없습니다"))
+        }
+    }
+}
+
+async fn download_original_image(
+    path: web::Path<String>,
+    query: web::Query<WatermarkQuery>,
+    config: web::Data<Config>,
+    watermark: web::Data<Watermark>,
+) -> Result<HttpResponse> {
+    let filename = path.into_inner();
+    
+    // 원본 파일 경로 찾기
+    let filepath = find_original_image_file(&filename, &config);
+    if filepath.is_empty() {
+        return Ok(HttpResponse::NotFound().json(ImageResponse {
+            success: false,
             message: "원본 파일을 찾을 수 없습니다".to_string(),
             filename: None,
             size_mb: None,
@@ -1068,6 +1708,10 @@ async fn download_original_image(path: web::Path<String>, config: web::Data<Conf
         }
     };
     
+    if watermark.applies_to(&query) {
+        return Ok(watermarked_response(&watermark, &file_data));
+    }
+    
     // 파일 확장자에 따른 content-type 설정
     let content_type = match Path::new(&filename).extension().and_then(|e| e.to_str()) {
         Some("jpg") | Some("jpeg") => "image/jpeg",
@@ -1102,6 +1746,14 @@ fn find_image_file(filename: &str, config: &Config) -> String {
         return generated_thumbnail_path;
     }
     
+    // 로컬 저장소에 키 경로로 저장된 파일 (markers/..., originals/...)
+    if storage::is_public_key(filename) {
+        let stored_path = format!("{}/{}", config.upload_dir, filename);
+        if Path::new(&stored_path).exists() {
+            return stored_path;
+        }
+    }
+    
     String::new()
 }
 
@@ -1224,6 +1876,12 @@ async fn get_image_stats(pool: web::Data<PgPool>) -> Result<HttpResponse> {
     })))
 } 
 
+/// 가입/연결 중 이메일·소셜 계정 중복이면 409 + errorCode 응답
+fn registration_conflict_response(e: &anyhow::Error) -> Option<HttpResponse> {
+    e.downcast_ref::<RegistrationConflict>()
+        .map(|conflict| ErrorHandler::conflict_with_code(conflict.message(), conflict.code()))
+}
+
 async fn register_member(
     db: web::Data<Database>,
     payload: web::Json<RegisterMember>,
@@ -1248,16 +1906,21 @@ async fn register_member(
                 let _ = db.add_member_hobbies(member.id, hobbies).await;
             }
             Ok(HttpResponse::Ok().json(ApiResponse {
-                data: Some(member),
+                data: Some(MemberDto::from(&member)),
                 code: 0,
                 message: "회원 등록 성공".to_string(),
             }))
         },
-        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
-            data: None,
-            code: 500,
-            message: format!("회원 등록 실패: {}", e),
-        })),
+        Err(e) => {
+            if let Some(response) = registration_conflict_response(&e) {
+                return Ok(response);
+            }
+            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                data: None,
+                code: 500,
+                message: format!("회원 등록 실패: {}", e),
+            }))
+        }
     }
 }
 
@@ -1267,10 +1930,25 @@ async fn get_member_by_id(
 ) -> Result<HttpResponse> {
     let id = path.into_inner();
     match db.get_member_by_id(id.into()).await {
-        Ok(Some(member)) => Ok(HttpResponse::Ok().json(serde_json::json!({
-            "success": true,
-            "data": member
-        }))),
+        Ok(Some(member)) => {
+            let mut data = serde_json::json!(MemberDto::from(&member));
+            // 인증받은 사업장 (프로필의 인증 배지)
+            data["verifiedBusinesses"] = match db.get_member_verified_claims(member.id).await {
+                Ok(claims) => serde_json::json!(claims.iter().map(|claim| serde_json::json!({
+                    "markerId": claim.marker_id,
+                    "businessName": claim.business_name,
+                    "verifiedAt": claim.reviewed_at
+                })).collect::<Vec<_>>()),
+                Err(e) => {
+                    warn!("⚠️ 회원 인증 사업장 조회 실패: {}", e);
+                    serde_json::json!([])
+                }
+            };
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": data
+            })))
+        }
         Ok(None) => Ok(HttpResponse::NotFound().json(serde_json::json!({
             "success": false,
             "message": "회원이 존재하지 않습니다."
@@ -1290,7 +1968,7 @@ async fn list_members(
     match db.list_members(limit).await {
         Ok(members) => Ok(HttpResponse::Ok().json(serde_json::json!({
             "success": true,
-            "data": members
+            "data": members.iter().map(MemberDto::from).collect::<Vec<_>>()
         }))),
         Err(e) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
             "success": false,
@@ -1304,8 +1982,14 @@ async fn register_social_member(
     db: web::Data<Database>,
     payload: web::Json<RegisterSocialMember>,
     config: web::Data<Config>,
+    clock: web::Data<dyn Clock>,
+    limiter: web::Data<AuthRateLimiter>,
 ) -> Result<HttpResponse> {
     let input = payload.into_inner();
+    if let Err(retry_after) = limiter.check_account(&input.email, clock.now()) {
+        warn!("🚦 회원가입 요청 제한: {}", input.email);
+        return Ok(AuthRateLimiter::too_many_requests(retry_after));
+    }
     
     info!("🔐 소셜 회원가입 요청:");
     info!("   - 이메일: {}", input.email);
@@ -1315,6 +1999,9 @@ async fn register_social_member(
     
     // 1. 이미 존재하는 소셜 계정인지 확인
     if let Ok(Some((existing_member, existing_auth))) = db.find_member_by_social_provider(&input.provider_type, &input.provider_id).await {
+        if !existing_member.is_active {
+            return Ok(suspended_login_response(&existing_member));
+        }
         info!("✅ 기존 소셜 계정 발견, 로그인 처리");
         
         // 마지막 로그인 시간 업데이트
@@ -1323,11 +2010,11 @@ async fn register_social_member(
         }
         
         // JWT 생성
-        let token = create_jwt(existing_member.id, &existing_member.email, &config).unwrap_or_default();
+        let token = create_jwt(&existing_member, &config, clock.now()).unwrap_or_default();
         return Ok(HttpResponse::Ok().json(ApiResponse {
             data: Some(serde_json::json!({
-                "member": member_to_camelcase_json(&existing_member),
-                "authProvider": auth_provider_to_camelcase_json(&existing_auth),
+                "member": MemberDto::from(&existing_member),
+                "authProvider": AuthProviderDto::from(&existing_auth),
                 "isNewUser": false
             })),
             code: 0,
@@ -1337,6 +2024,9 @@ async fn register_social_member(
     
     // 2. 같은 이메일로 가입된 계정이 있는지 확인
     if let Ok(Some((existing_member, _existing_auth))) = db.find_member_by_email(&input.email).await {
+        if !existing_member.is_active {
+            return Ok(suspended_login_response(&existing_member));
+        }
         info!("📧 같은 이메일의 기존 계정 발견");
         
         // 기존 계정에 새로운 소셜 로그인 연결
@@ -1349,11 +2039,11 @@ async fn register_social_member(
             Ok(new_auth) => {
                 info!("✅ 기존 계정에 소셜 로그인 연결 성공");
                 // JWT 생성
-                let token = create_jwt(existing_member.id, &existing_member.email, &config).unwrap_or_default();
+                let token = create_jwt(&existing_member, &config, clock.now()).unwrap_or_default();
                 return Ok(HttpResponse::Ok().json(ApiResponse {
                     data: Some(serde_json::json!({
-                        "member": member_to_camelcase_json(&existing_member),
-                        "authProvider": auth_provider_to_camelcase_json(&new_auth),
+                        "member": MemberDto::from(&existing_member),
+                        "authProvider": AuthProviderDto::from(&new_auth),
                         "isNewUser": false
                     })),
                     code: 0,
@@ -1361,6 +2051,9 @@ async fn register_social_member(
                 }));
             }
             Err(e) => {
+                if let Some(response) = registration_conflict_response(&e) {
+                    return Ok(response);
+                }
                 error!("❌ 소셜 로그인 연결 실패: {}", e);
                 return Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
                     data: None,
@@ -1426,11 +2119,11 @@ async fn register_social_member(
             }
             info!("✅ 새로운 회원 생성 성공: ID {}", member.id);
             // JWT 생성
-            let token = create_jwt(member.id, &member.email, &config).unwrap_or_default();
+            let token = create_jwt(&member, &config, clock.now()).unwrap_or_default();
             Ok(HttpResponse::Ok().json(ApiResponse {
                 data: Some(serde_json::json!({
-                    "member": member_to_camelcase_json(&member),
-                    "authProvider": auth_provider_to_camelcase_json(&auth_provider),
+                    "member": MemberDto::from(&member),
+                    "authProvider": AuthProviderDto::from(&auth_provider),
                     "isNewUser": true
                 })),
                 code: 0,
@@ -1438,6 +2131,9 @@ async fn register_social_member(
             }))
         }
         Err(e) => {
+            if let Some(response) = registration_conflict_response(&e) {
+                return Ok(response);
+            }
             error!("❌ 회원가입 실패: {}", e);
             Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
                 data: None,
@@ -1450,44 +2146,123 @@ async fn register_social_member(
 
 /// 이메일/비밀번호 로그인
 async fn login_member(
+    req: actix_web::HttpRequest,
     db: web::Data<Database>,
     payload: web::Json<LoginRequest>,
     config: web::Data<Config>,
+    clock: web::Data<dyn Clock>,
+    limiter: web::Data<AuthRateLimiter>,
 ) -> Result<HttpResponse> {
     let input = payload.into_inner();
+    let now = clock.now();
+    if let Err(retry_after) = limiter.check_account(&input.email, now) {
+        warn!("🚦 로그인 요청 제한: {}", input.email);
+        return Ok(AuthRateLimiter::too_many_requests(retry_after));
+    }
     
     info!("🔐 이메일 로그인 요청: {}", input.email);
+
+    // 반복 실패로 잠긴 계정인지 확인 (조회 실패 시에는 로그인 진행)
+    let window = chrono::Duration::seconds(config.login_lockout_window_secs);
+    match db.get_login_locked_until(&input.email, config.login_lockout_max_failures, window, now).await {
+        Ok(Some(locked_until)) if locked_until > now => {
+            let retry_after = (locked_until - now).num_seconds().max(1);
+            return Ok(ErrorHandler::account_locked(
+                "로그인 실패가 반복되어 계정이 잠시 잠겼습니다. 잠시 후 다시 시도해주세요.",
+                locked_until,
+                retry_after,
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => warn!("⚠️ 로그인 잠금 상태 조회 실패: {}", e),
+    }
+
+    let mut attempt = LoginAttempt {
+        email: input.email.clone(),
+        member_id: None,
+        success: false,
+        failure_reason: None,
+        client_ip: req.connection_info().realip_remote_addr().map(|ip| ip.to_string()),
+        user_agent: req
+            .headers()
+            .get("User-Agent")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string()),
+    };
     
     // 이메일로 회원 찾기
     match db.find_member_by_email(&input.email).await {
         Ok(Some((member, auth_provider))) => {
+            attempt.member_id = Some(member.id);
             // 비밀번호 검증 (실제로는 해시 비교가 필요)
-            if auth_provider.provider_type == "email" {
+            let failure_reason = match &auth_provider.password_hash {
                 // 실제로는 bcrypt나 argon2로 비밀번호 검증
-                if let Some(stored_hash) = &auth_provider.password_hash {
+                Some(stored_hash) if auth_provider.provider_type == "email" => {
                     if stored_hash == &input.password { // 실제로는 해시 비교
-                        // 마지막 로그인 시간 업데이트
-                        if let Err(e) = db.update_last_login(member.id).await {
-                            warn!("⚠️ 마지막 로그인 시간 업데이트 실패: {}", e);
-                        }
-                        // JWT 생성
-                        let token = create_jwt(member.id, &member.email, &config).unwrap_or_default();
-                        let access_token = generate_access_token(member.id, &member.email, &config);
-                        let refresh_token = generate_refresh_token(member.id, &member.email, &config);
-                        info!("✅ 이메일 로그인 성공: {}", input.email);
-                        return Ok(HttpResponse::Ok().json(serde_json::json!({
-                            "success": true,
-                            "message": "로그인 성공",
-                            "token": token,
-                            "accessToken": access_token,
-                            "refreshToken": refresh_token,
-                            "data": {
-                                "member": member_to_camelcase_json(&member),
-                                "authProvider": auth_provider_to_camelcase_json(&auth_provider)
-                            }
+                        None
+                    } else {
+                        Some("invalid_password")
+                    }
+                }
+                _ => Some("no_password"),
+            };
+
+            // 2단계 인증을 켠 회원은 인증 코드까지 확인 (코드 없이 온 요청은 실패로 기록하지 않음)
+            let failure_reason = match failure_reason {
+                None => match login_totp_check(&db, member.id, input.totp_code.as_deref(), now).await {
+                    Ok(TotpCheck::NotEnabled) | Ok(TotpCheck::Passed) => None,
+                    Ok(TotpCheck::Missing) => {
+                        info!("🔑 2단계 인증 코드 필요: {}", input.email);
+                        return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
+                            "success": false,
+                            "twoFactorRequired": true,
+                            "message": "2단계 인증 코드가 필요합니다"
                         })));
                     }
+                    Ok(TotpCheck::Invalid) => Some("invalid_totp"),
+                    Err(e) => {
+                        error!("❌ 2단계 인증 확인 실패: {}", e);
+                        return Ok(ErrorHandler::internal_server_error(
+                            "로그인 처리 실패",
+                            Some(&format!("데이터베이스 오류: {}", e))
+                        ));
+                    }
+                },
+                other => other,
+            };
+
+            // 비밀번호가 맞아도 정지된 계정은 로그인 불가
+            let failure_reason = failure_reason.or((!member.is_active).then_some("suspended"));
+            attempt.success = failure_reason.is_none();
+            attempt.failure_reason = failure_reason.map(|r| r.to_string());
+            if let Err(e) = db.record_login_attempt(&attempt, now).await {
+                warn!("⚠️ 로그인 시도 기록 실패: {}", e);
+            }
+
+            if failure_reason.is_none() {
+                // 마지막 로그인 시간 업데이트
+                if let Err(e) = db.update_last_login(member.id).await {
+                    warn!("⚠️ 마지막 로그인 시간 업데이트 실패: {}", e);
                 }
+                // JWT 생성
+                let token = create_jwt(&member, &config, now).unwrap_or_default();
+                let access_token = generate_access_token(&member, &config, now);
+                let refresh_token = generate_refresh_token(&member, &config, now);
+                info!("✅ 이메일 로그인 성공: {}", input.email);
+                return Ok(HttpResponse::Ok().json(serde_json::json!({
+                    "success": true,
+                    "message": "로그인 성공",
+                    "token": token,
+                    "accessToken": access_token,
+                    "refreshToken": refresh_token,
+                    "data": {
+                        "member": MemberDto::from(&member),
+                        "authProvider": AuthProviderDto::from(&auth_provider)
+                    }
+                })));
+            }
+            if failure_reason == Some("suspended") {
+                return Ok(suspended_login_response(&member));
             }
             
             Ok(HttpResponse::Unauthorized().json(serde_json::json!({
@@ -1497,6 +2272,10 @@ async fn login_member(
         }
         Ok(None) => {
             info!("❌ 존재하지 않는 이메일: {}", input.email);
+            attempt.failure_reason = Some("unknown_email".to_string());
+            if let Err(e) = db.record_login_attempt(&attempt, now).await {
+                warn!("⚠️ 로그인 시도 기록 실패: {}", e);
+            }
             Ok(ErrorHandler::unauthorized(
                 "이메일 또는 비밀번호가 올바르지 않습니다",
                 Some(&format!("이메일: {}", input.email))
@@ -1512,11 +2291,135 @@ async fn login_member(
     }
 }
 
+/// 정지된 회원의 로그인 응답 (토큰은 발급해도 인증 미들웨어에서 거절되므로 미리 막음)
+fn suspended_login_response(member: &Member) -> HttpResponse {
+    warn!("🚫 정지된 회원 로그인 시도: {}", member.id);
+    ErrorHandler::forbidden(
+        "이용이 정지된 계정입니다.",
+        member.suspension_reason.as_deref()
+    )
+}
+
+enum TotpCheck {
+    NotEnabled,
+    Missing,
+    Invalid,
+    Passed,
+}
+
+/// 로그인 시 2단계 인증 코드 확인 (맞은 코드는 다시 쓸 수 없도록 사용 처리)
+async fn login_totp_check(db: &Database, member_id: i64, code: Option<&str>, now: chrono::DateTime<Utc>) -> anyhow::Result<TotpCheck> {
+    let Some(totp) = db.get_member_totp(member_id).await? else {
+        return Ok(TotpCheck::NotEnabled);
+    };
+    if totp.enabled_at.is_none() {
+        return Ok(TotpCheck::NotEnabled);
+    }
+    let Some(code) = code.filter(|c| !c.trim().is_empty()) else {
+        return Ok(TotpCheck::Missing);
+    };
+    match verify_totp_code(&totp.secret, code, now) {
+        Some(step) if db.consume_totp_step(member_id, step, now).await? => Ok(TotpCheck::Passed),
+        _ => Ok(TotpCheck::Invalid),
+    }
+}
+
+/// 2단계 인증 설정 시작: 새 비밀키와 인증 앱 등록용 URI 발급 (코드 확인 후 활성화)
+async fn setup_two_factor(
+    db: web::Data<Database>,
+    config: web::Data<Config>,
+    clock: web::Data<dyn Clock>,
+    ids: web::Data<dyn IdGenerator>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let secret = generate_totp_secret(ids.get_ref());
+    match db.save_pending_totp_secret(member.member_id, &secret, clock.now()).await {
+        Ok(true) => {
+            info!("🔑 2단계 인증 설정 시작: member_id={}", member.member_id);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": "인증 앱에 등록한 뒤 코드를 확인해주세요",
+                "data": {
+                    "secret": secret,
+                    "provisioningUri": provisioning_uri(&secret, &member.claims.email, &config.totp_issuer)
+                }
+            })))
+        }
+        Ok(false) => Ok(ErrorHandler::conflict(
+            "이미 2단계 인증이 활성화되어 있습니다.",
+            Some(&format!("member_id: {}", member.member_id))
+        )),
+        Err(e) => {
+            error!("❌ 2단계 인증 설정 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "2단계 인증 설정 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 2단계 인증 코드 확인 (설정 직후 첫 확인에 성공하면 활성화)
+async fn verify_two_factor(
+    db: web::Data<Database>,
+    clock: web::Data<dyn Clock>,
+    payload: web::Json<TotpCodeRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let now = clock.now();
+    let totp = match db.get_member_totp(member.member_id).await {
+        Ok(Some(totp)) => totp,
+        Ok(None) => return Ok(ErrorHandler::bad_request(
+            "2단계 인증 설정을 먼저 진행해주세요.",
+            None,
+            Some("2단계 인증 확인 - 설정 없음")
+        )),
+        Err(e) => {
+            error!("❌ 2단계 인증 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "2단계 인증 확인 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+
+    let consumed = match verify_totp_code(&totp.secret, &payload.code, now) {
+        Some(step) => db.consume_totp_step(member.member_id, step, now).await,
+        None => Ok(false),
+    };
+    match consumed {
+        Ok(true) => {
+            info!("✅ 2단계 인증 확인: member_id={}", member.member_id);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": "2단계 인증이 활성화되었습니다",
+                "data": {
+                    "enabled": true,
+                    "enabledAt": totp.enabled_at.unwrap_or(now)
+                }
+            })))
+        }
+        Ok(false) => Ok(ErrorHandler::bad_request(
+            "인증 코드가 올바르지 않습니다.",
+            None,
+            Some("2단계 인증 확인 - 코드 불일치")
+        )),
+        Err(e) => {
+            error!("❌ 2단계 인증 확인 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "2단계 인증 확인 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
 /// 소셜 로그인 (기존 계정 확인)
 async fn social_login(
     db: web::Data<Database>,
     payload: web::Json<SocialLoginRequest>,
     config: web::Data<Config>,
+    clock: web::Data<dyn Clock>,
 ) -> Result<HttpResponse> {
     let input = payload.into_inner();
     
@@ -1526,15 +2429,16 @@ async fn social_login(
     
     // 소셜 제공자로 기존 회원 찾기
     match db.find_member_by_social_provider(&input.provider_type, &input.provider_id).await {
+        Ok(Some((member, _))) if !member.is_active => Ok(suspended_login_response(&member)),
         Ok(Some((member, auth_provider))) => {
             // 마지막 로그인 시간 업데이트
             if let Err(e) = db.update_last_login(member.id).await {
                 warn!("⚠️ 마지막 로그인 시간 업데이트 실패: {}", e);
             }
             // JWT 생성
-            let token = create_jwt(member.id, &member.email, &config).unwrap_or_default();
-            let access_token = generate_access_token(member.id, &member.email, &config);
-            let refresh_token = generate_refresh_token(member.id, &member.email, &config);
+            let token = create_jwt(&member, &config, clock.now()).unwrap_or_default();
+            let access_token = generate_access_token(&member, &config, clock.now());
+            let refresh_token = generate_refresh_token(&member, &config, clock.now());
             info!("✅ 소셜 로그인 성공: {}", member.email);
             Ok(HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
@@ -1543,8 +2447,8 @@ async fn social_login(
                 "accessToken": access_token,
                 "refreshToken": refresh_token,
                 "data": {
-                    "member": member_to_camelcase_json(&member),
-                    "authProvider": auth_provider_to_camelcase_json(&auth_provider)
+                    "member": MemberDto::from(&member),
+                    "authProvider": AuthProviderDto::from(&auth_provider)
                 }
             })))
         }
@@ -1574,44 +2478,12 @@ async fn social_login(
 
 async fn get_me(
     db: web::Data<Database>,
-    config: web::Data<Config>,
-    req: actix_web::HttpRequest,
+    member: AuthenticatedMember,
 ) -> Result<HttpResponse> {
-    let auth_header = req.headers().get("Authorization").and_then(|h| h.to_str().ok());
-    if auth_header.is_none() || !auth_header.unwrap().starts_with("Bearer ") {
-        return Ok(ErrorHandler::unauthorized(
-            "No Bearer token",
-            Some("Authorization 헤더가 없거나 Bearer 형식이 아닙니다")
-        ));
-    }
-    let token = &auth_header.unwrap()[7..];
-    let validation = Validation::default();
-    let claims = match decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
-        &validation,
-    ) {
-        Ok(data) => data.claims,
-        Err(e) => {
-            return Ok(ErrorHandler::unauthorized(
-                "Invalid token",
-                Some(&format!("토큰 검증 실패: {}", e))
-            ));
-        }
-    };
-    let user_id: i64 = match claims.sub.parse() {
-        Ok(id) => id,
-        Err(_) => {
-            return Ok(ErrorHandler::unauthorized(
-                "Invalid user id in token",
-                Some(&format!("토큰의 사용자 ID 파싱 실패: {}", claims.sub))
-            ));
-        }
-    };
-    match db.get_member_by_id(user_id).await {
+    match db.get_member_by_id(member.member_id).await {
         Ok(Some(member)) => Ok(HttpResponse::Ok().json(serde_json::json!({
             "success": true,
-            "data": member_to_camelcase_json(&member)
+            "data": MemberDto::from(&member)
         }))),
         Ok(None) => Ok(HttpResponse::NotFound().json(serde_json::json!({
             "success": false,
@@ -1624,173 +2496,516 @@ async fn get_me(
     }
 }
 
-/// 프로필 검증 전용 함수
-async fn verify_profile(
+/// 지난 해들의 오늘 작성한 내 마커 ("지난 오늘")
+async fn get_my_memories(
     db: web::Data<Database>,
-    config: web::Data<Config>,
-    req: actix_web::HttpRequest,
+    member: AuthenticatedMember,
 ) -> Result<HttpResponse> {
-    info!("🔐 프로필 검증 요청");
-    
-    let auth_header = req.headers().get("Authorization").and_then(|h| h.to_str().ok());
-    if auth_header.is_none() || !auth_header.unwrap().starts_with("Bearer ") {
-        info!("❌ 인증 헤더 없음 또는 잘못된 형식");
-        return Ok(ErrorHandler::unauthorized(
-            "No Bearer token",
-            Some("Authorization 헤더가 없거나 Bearer 형식이 아닙니다")
-        ));
-    }
-    
-    let token = &auth_header.unwrap()[7..];
-    let validation = Validation::default();
-    
-    let claims = match decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
-        &validation,
-    ) {
-        Ok(data) => {
-            info!("✅ JWT 토큰 검증 성공");
-            data.claims
-        }
+    let markers = match db.get_member_memories(member.member_id).await {
+        Ok(markers) => markers,
         Err(e) => {
-            info!("❌ JWT 토큰 검증 실패: {}", e);
-            return Ok(ErrorHandler::unauthorized(
-                "Invalid token",
-                Some(&format!("토큰 검증 실패: {}", e))
+            error!("❌ 지난 오늘 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "지난 오늘 마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
             ));
         }
     };
+    let notification_enabled = db.is_memories_notification_enabled(member.member_id).await.unwrap_or(false);
     
-    let user_id: i64 = match claims.sub.parse() {
-        Ok(id) => {
-            info!("✅ 사용자 ID 파싱 성공: {}", id);
-            id
-        }
-        Err(_) => {
-            info!("❌ 사용자 ID 파싱 실패: {}", claims.sub);
-            return Ok(ErrorHandler::unauthorized(
-                "Invalid user id in token",
-                Some(&format!("토큰의 사용자 ID 파싱 실패: {}", claims.sub))
-            ));
-        }
-    };
+    // 연도 차이는 한국 시간 기준
+    let kst_year = |time: chrono::DateTime<Utc>| (time + chrono::Duration::hours(9)).year();
+    let current_year = kst_year(Utc::now());
+    let formatted: Vec<MarkerDto> = markers.iter()
+        .map(|marker| MarkerDto::from(marker).with_years_ago(current_year - kst_year(marker.created_at)))
+        .collect();
     
-    match db.get_member_by_id(user_id).await {
-        Ok(Some(member)) => {
-            info!("✅ 프로필 검증 성공: 사용자 {} ({})", member.nickname, member.email);
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": formatted,
+        "count": markers.len(),
+        "notificationEnabled": notification_enabled
+    })))
+}
+
+/// 내 임시 저장/예약 마커 (피드/지도에는 나오지 않으므로 작성자가 여기서 이어서 수정/게시)
+async fn get_my_unpublished_markers(
+    db: web::Data<Database>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match db.get_member_unpublished_markers(member.member_id).await {
+        Ok(markers) => {
+            let formatted: Vec<MarkerDto> = markers.iter().map(MarkerDto::from).collect();
             Ok(HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
-                "message": "프로필 검증 성공",
-                "data": {
-                    "user": member_to_camelcase_json(&member),
-                    "token": {
-                        "valid": true,
-                        "exp": claims.exp,
-                        "user_id": user_id,
-                        "email": claims.email
-                    }
-                }
-            })))
-        }
-        Ok(None) => {
-            info!("❌ 사용자를 찾을 수 없음: ID {}", user_id);
-            Ok(HttpResponse::NotFound().json(serde_json::json!({
-                "success": false,
-                "message": "회원이 존재하지 않습니다.",
-                "data": {
-                    "token": {
-                        "valid": false,
-                        "reason": "user_not_found"
-                    }
-                }
+                "data": formatted,
+                "count": markers.len()
             })))
         }
         Err(e) => {
-            error!("❌ 데이터베이스 조회 실패: {}", e);
-            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "success": false,
-                "message": format!("회원 조회 실패: {}", e),
-                "data": {
-                    "token": {
-                        "valid": false,
-                        "reason": "database_error"
-                    }
-                }
-            })))
+            error!("❌ 임시 저장/예약 마커 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "임시 저장/예약 마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
         }
     }
-} 
+}
 
-/// 구글 ID 토큰 검증 (간소화된 버전)
-async fn verify_google_id_token_simple(id_token: &str) -> Result<GoogleIdTokenPayload, Box<dyn std::error::Error>> {
-    // 1. ID 토큰을 헤더, 페이로드, 서명으로 분리
-    let parts: Vec<&str> = id_token.split('.').collect();
-    if parts.len() != 3 {
-        return Err("Invalid ID token format".into());
-    }
-    
-    // 2. 페이로드 디코딩 (서명 검증 없이)
-    let payload_json = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(parts[1])?;
-    let payload: GoogleIdTokenPayload = serde_json::from_slice(&payload_json)?;
-    
-    // 3. 기본 검증만 수행
-    let now = chrono::Utc::now().timestamp();
-    if payload.exp < now {
-        return Err("Token expired".into());
-    }
-    
-    if !payload.email_verified {
-        return Err("Email not verified".into());
+/// "지난 오늘" 아침 알림 수신 설정 (기본 꺼짐)
+async fn update_memories_notification(
+    db: web::Data<Database>,
+    payload: web::Json<MemoriesNotificationRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match db.set_memories_notification(member.member_id, payload.enabled).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": { "notificationEnabled": payload.enabled }
+        }))),
+        Ok(false) => Ok(ErrorHandler::not_found("회원이 존재하지 않습니다.")),
+        Err(e) => Ok(ErrorHandler::internal_server_error(
+            "알림 설정 변경 실패",
+            Some(&format!("데이터베이스 오류: {}", e))
+        )),
     }
-    
-    Ok(payload)
 }
 
-/// 액세스 토큰 생성
-fn generate_access_token(user_id: i64, email: &str, config: &Config) -> String {
-    use chrono::Duration;
-    let expiration = Utc::now() + Duration::hours(24);
-    let claims = Claims {
-        sub: user_id.to_string(),
-        email: email.to_string(),
-        exp: expiration.timestamp() as usize,
-    };
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
-    ).unwrap_or_default()
+/// 내 알림함
+async fn get_my_notifications(
+    db: web::Data<Database>,
+    query: web::Query<NotificationsQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    match db.get_member_notifications(member.member_id, limit).await {
+        Ok(notifications) => {
+            let formatted: Vec<NotificationDto> = notifications.iter().map(NotificationDto::from).collect();
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": formatted,
+                "count": notifications.len()
+            })))
+        }
+        Err(e) => {
+            error!("❌ 알림 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "알림 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
 }
 
-/// 리프레시 토큰 생성
-fn generate_refresh_token(user_id: i64, email: &str, config: &Config) -> String {
-    use chrono::Duration;
-    let expiration = Utc::now() + Duration::days(30); // 30일 유효
-    let claims = Claims {
-        sub: user_id.to_string(),
-        email: email.to_string(),
-        exp: expiration.timestamp() as usize,
-    };
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
-    ).unwrap_or_default()
+/// 활동 피드 커서 (이전 페이지 마지막 활동 ID)
+fn parse_activity_cursor(raw: Option<&str>) -> Result<Option<i64>, String> {
+    match raw.map(str::trim).filter(|cursor| !cursor.is_empty()) {
+        None => Ok(None),
+        Some(cursor) => cursor.parse::<i64>().map(Some).map_err(|_| format!("cursor: {}", cursor)),
+    }
 }
 
-/// 구글 ID 토큰으로 로그인/회원가입
+/// 활동 피드 한 페이지 (limit + 1개를 읽어 다음 페이지가 있을 때만 nextCursor)
+async fn activity_feed_page<F, Fut>(query: &ActivityFeedQuery, label: &str, fetch: F) -> Result<HttpResponse>
+where
+    F: FnOnce(Option<i64>, i64) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<Vec<Activity>>>,
+{
+    let before_id = match parse_activity_cursor(query.cursor.as_deref()) {
+        Ok(before_id) => before_id,
+        Err(details) => {
+            return Ok(ErrorHandler::bad_request(
+                "cursor 값이 올바르지 않습니다. 이전 응답의 nextCursor를 그대로 보내주세요.",
+                Some(&details),
+                None
+            ));
+        }
+    };
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    
+    match fetch(before_id, limit + 1).await {
+        Ok(mut activities) => {
+            let has_more = activities.len() as i64 > limit;
+            activities.truncate(limit as usize);
+            let next_cursor = activities.last().filter(|_| has_more).map(|activity| activity.id.to_string());
+            let formatted: Vec<ActivityDto> = activities.iter().map(ActivityDto::from).collect();
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": formatted,
+                "count": formatted.len(),
+                "nextCursor": next_cursor
+            })))
+        }
+        Err(e) => {
+            error!("❌ {} 조회 실패: {}", label, e);
+            Ok(ErrorHandler::internal_server_error(
+                &format!("{} 조회 실패", label),
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 내 활동 타임라인 (마커 작성, 좋아요 등, 최신순 커서 페이지네이션)
+async fn get_my_activity(
+    db: web::Data<Database>,
+    query: web::Query<ActivityFeedQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    activity_feed_page(&query, "활동 타임라인", |before_id, limit| {
+        db.get_member_activities(member.member_id, before_id, limit)
+    }).await
+}
+
+/// 내 마커에 다른 회원이 남긴 활동 (좋아요 등, 최신순 커서 페이지네이션)
+async fn get_my_notifications_feed(
+    db: web::Data<Database>,
+    query: web::Query<ActivityFeedQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    activity_feed_page(&query, "알림 피드", |before_id, limit| {
+        db.get_member_activity_notifications(member.member_id, before_id, limit)
+    }).await
+}
+
+const MAX_DEVICE_TOKEN_CHARS: usize = 512;
+
+/// 내 알림 수신 설정 (좋아요/댓글 푸시, "지난 오늘")
+async fn get_my_notification_preferences(
+    db: web::Data<Database>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match db.get_notification_preferences(member.member_id).await {
+        Ok(Some(preferences)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": NotificationPreferencesDto::from(&preferences)
+        }))),
+        Ok(None) => Ok(ErrorHandler::not_found("회원이 존재하지 않습니다.")),
+        Err(e) => Ok(ErrorHandler::internal_server_error(
+            "알림 설정 조회 실패",
+            Some(&format!("데이터베이스 오류: {}", e))
+        )),
+    }
+}
+
+/// 알림 수신 설정 변경 (보낸 항목만 바꿈)
+async fn update_my_notification_preferences(
+    db: web::Data<Database>,
+    payload: web::Json<NotificationPreferencesRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match db.update_notification_preferences(member.member_id, payload.likes, payload.comments, payload.memories).await {
+        Ok(Some(preferences)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": NotificationPreferencesDto::from(&preferences)
+        }))),
+        Ok(None) => Ok(ErrorHandler::not_found("회원이 존재하지 않습니다.")),
+        Err(e) => Ok(ErrorHandler::internal_server_error(
+            "알림 설정 변경 실패",
+            Some(&format!("데이터베이스 오류: {}", e))
+        )),
+    }
+}
+
+/// 푸시 기기 등록 (앱 시작/로그인 때마다 호출, 같은 토큰은 마지막으로 로그인한 회원에게 보냄)
+async fn register_my_device(
+    db: web::Data<Database>,
+    payload: web::Json<RegisterDeviceRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let token = payload.token.trim();
+    if token.is_empty() || token.chars().count() > MAX_DEVICE_TOKEN_CHARS {
+        return Ok(ErrorHandler::bad_request(
+            "기기 토큰이 올바르지 않습니다",
+            Some(&format!("token: 1~{}자", MAX_DEVICE_TOKEN_CHARS)),
+            None
+        ));
+    }
+    let platform = payload.platform.trim().to_lowercase();
+    if !PUSH_PLATFORMS.contains(&platform.as_str()) {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 플랫폼입니다",
+            Some(&format!("platform: {} ({})", payload.platform, PUSH_PLATFORMS.join(", "))),
+            None
+        ));
+    }
+    
+    match db.register_device_token(member.member_id, token, &platform).await {
+        Ok(device) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": {
+                "platform": device.platform,
+                "registeredAt": device.created_at,
+                "lastSeenAt": device.last_seen_at
+            }
+        }))),
+        Err(e) => {
+            error!("❌ 푸시 기기 등록 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "푸시 기기 등록 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 푸시 기기 해제 (로그아웃 시, `token` 쿼리)
+async fn unregister_my_device(
+    db: web::Data<Database>,
+    query: web::Query<DeviceTokenQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match db.unregister_device_token(member.member_id, query.token.trim()).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "푸시 알림을 더 이상 이 기기로 보내지 않습니다."
+        }))),
+        Ok(false) => Ok(ErrorHandler::not_found("등록된 기기가 없습니다.")),
+        Err(e) => Ok(ErrorHandler::internal_server_error(
+            "푸시 기기 해제 실패",
+            Some(&format!("데이터베이스 오류: {}", e))
+        )),
+    }
+}
+
+/// 회원 탈퇴 (개인정보 제거, 마커 작성자 익명화, 소셜 연결 해제, 이미지 정리 예약)
+async fn delete_my_account(
+    db: web::Data<Database>,
+    cluster_cache: web::Data<ClusterCache>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match db.delete_member_account(member.member_id).await {
+        Ok(Some(summary)) => {
+            if summary.deleted_markers > 0 || summary.anonymized_markers > 0 {
+                cluster_cache.clear();
+            }
+            info!("👋 회원 탈퇴: {} (익명화 마커 {}개, 삭제 마커 {}개, 정리 예약 이미지 {}개)",
+                  member.member_id, summary.anonymized_markers, summary.deleted_markers, summary.queued_images);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": "회원 탈퇴가 완료되었습니다.",
+                "data": {
+                    "anonymizedMarkers": summary.anonymized_markers,
+                    "deletedMarkers": summary.deleted_markers,
+                    "scheduledImageCleanups": summary.queued_images
+                }
+            })))
+        }
+        Ok(None) => Ok(ErrorHandler::not_found("회원이 존재하지 않거나 이미 탈퇴했습니다.")),
+        Err(e) => {
+            error!("❌ 회원 탈퇴 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "회원 탈퇴 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 내 선호 콘텐츠 언어 설정
+async fn update_my_languages(
+    db: web::Data<Database>,
+    payload: web::Json<UpdateLanguagesRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let user_id = member.member_id;
+    
+    let input = payload.into_inner();
+    if let Some(invalid) = input.languages.iter().find(|lang| !is_supported_language(&lang.trim().to_lowercase())) {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 언어 코드입니다. (ko, ja, zh, en)",
+            Some(&format!("언어 코드: {}", invalid)),
+            Some("선호 언어 설정 - 언어 코드 검증 실패")
+        ));
+    }
+    let languages = parse_language_list(&input.languages.join(","));
+    
+    info!("🌐 선호 언어 설정: 유저 {}, 언어 {:?}", user_id, languages);
+    
+    match db.update_member_preferred_languages(user_id, &languages).await {
+        Ok(Some(member)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "선호 언어 설정 성공",
+            "data": MemberDto::from(&member)
+        }))),
+        Ok(None) => Ok(ErrorHandler::not_found("회원이 존재하지 않습니다.")),
+        Err(e) => {
+            error!("❌ 선호 언어 설정 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "선호 언어 설정 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 공백 제거 후 중복/빈 값 제외 (순서 유지)
+fn normalize_name_list(names: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    names.into_iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && seen.insert(name.clone()))
+        .collect()
+}
+
+/// 내 프로필 수정 (보낸 항목만 변경)
+async fn update_my_profile(
+    db: web::Data<Database>,
+    payload: web::Json<UpdateProfileRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let user_id = member.member_id;
+    let input = payload.into_inner();
+    let trimmed = |value: Option<String>| value.map(|v| v.trim().to_string());
+    
+    let update = MemberProfileUpdate {
+        nickname: trimmed(input.nickname),
+        region: trimmed(input.region),
+        gender: trimmed(input.gender),
+        birth_year: input.birth_year,
+        personality_type: trimmed(input.personality_type),
+        profile_image_url: trimmed(input.profile_image_url),
+        interests: input.interests.map(normalize_name_list),
+        hobbies: input.hobbies.map(normalize_name_list),
+    };
+    
+    if let Some(nickname) = &update.nickname {
+        if nickname.is_empty() || nickname.chars().count() > 100 {
+            return Ok(ErrorHandler::bad_request(
+                "닉네임은 1~100자여야 합니다.",
+                Some(&format!("닉네임 길이: {}", nickname.chars().count())),
+                Some("프로필 수정 - 닉네임 검증 실패")
+            ));
+        }
+        match db.is_nickname_taken(nickname, user_id).await {
+            Ok(false) => {}
+            Ok(true) => return Ok(ErrorHandler::conflict(
+                "이미 사용 중인 닉네임입니다.",
+                Some(&format!("닉네임: {}", nickname))
+            )),
+            Err(e) => {
+                error!("❌ 닉네임 중복 확인 실패: {}", e);
+                return Ok(ErrorHandler::internal_server_error(
+                    "닉네임 중복 확인 실패",
+                    Some(&format!("데이터베이스 오류: {}", e))
+                ));
+            }
+        }
+    }
+    let current_year = Utc::now().year();
+    if let Some(birth_year) = update.birth_year
+        && !(1900..=current_year).contains(&birth_year)
+    {
+        return Ok(ErrorHandler::bad_request(
+            "출생 연도가 올바르지 않습니다.",
+            Some(&format!("출생 연도: {} (1900~{})", birth_year, current_year)),
+            Some("프로필 수정 - 출생 연도 검증 실패")
+        ));
+    }
+    
+    info!("👤 프로필 수정: 유저 {}", user_id);
+    
+    match db.update_member_profile(user_id, &update).await {
+        Ok(Some(member)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "프로필 수정 성공",
+            "data": MemberDto::from(&member)
+        }))),
+        Ok(None) => Ok(ErrorHandler::not_found("회원이 존재하지 않습니다.")),
+        Err(e) => {
+            error!("❌ 프로필 수정 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "프로필 수정 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 프로필 검증 전용 함수
+async fn verify_profile(
+    db: web::Data<Database>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    info!("🔐 프로필 검증 요청");
+    
+    let AuthenticatedMember { member_id: user_id, claims } = member;
+    info!("✅ JWT 토큰 검증 성공: 사용자 ID {}", user_id);
+    
+    match db.get_member_by_id(user_id).await {
+        Ok(Some(member)) => {
+            info!("✅ 프로필 검증 성공: 사용자 {} ({})", member.nickname, member.email);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": "프로필 검증 성공",
+                "data": {
+                    "user": MemberDto::from(&member),
+                    "token": {
+                        "valid": true,
+                        "exp": claims.exp,
+                        "user_id": user_id,
+                        "email": claims.email
+                    }
+                }
+            })))
+        }
+        Ok(None) => {
+            info!("❌ 사용자를 찾을 수 없음: ID {}", user_id);
+            Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "success": false,
+                "message": "회원이 존재하지 않습니다.",
+                "data": {
+                    "token": {
+                        "valid": false,
+                        "reason": "user_not_found"
+                    }
+                }
+            })))
+        }
+        Err(e) => {
+            error!("❌ 데이터베이스 조회 실패: {}", e);
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "message": format!("회원 조회 실패: {}", e),
+                "data": {
+                    "token": {
+                        "valid": false,
+                        "reason": "database_error"
+                    }
+                }
+            })))
+        }
+    }
+} 
+
+/// 액세스 토큰 생성
+fn generate_access_token(member: &Member, config: &Config, now: chrono::DateTime<Utc>) -> String {
+    create_jwt(member, config, now).unwrap_or_default()
+}
+
+/// 리프레시 토큰 생성 (JWT_REFRESH_TTL_SECS, 기본 30일)
+fn generate_refresh_token(member: &Member, config: &Config, now: chrono::DateTime<Utc>) -> String {
+    let claims = Claims::for_member(member, config.jwt_refresh_ttl_secs, config, now);
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+    ).unwrap_or_default()
+}
+
+/// 구글 ID 토큰으로 로그인/회원가입
 async fn google_id_token_login(
     db: web::Data<Database>,
     payload: web::Json<GoogleIdTokenRequest>,
     config: web::Data<Config>,
+    clock: web::Data<dyn Clock>,
 ) -> Result<HttpResponse> {
     let input = payload.into_inner();
     
     info!("🔐 구글 ID 토큰 로그인 요청");
     
     // ID 토큰 검증
-    let google_payload = match verify_google_id_token_simple(&input.id_token).await {
+    let google_payload = match verify_google_id_token(&input.id_token, &config).await {
         Ok(payload) => {
             info!("✅ 구글 ID 토큰 검증 성공: {}", payload.email);
             payload
@@ -1806,6 +3021,9 @@ async fn google_id_token_login(
     
     // 1. 이미 존재하는 구글 계정인지 확인
     if let Ok(Some((existing_member, existing_auth))) = db.find_member_by_social_provider("google", &google_payload.sub).await {
+        if !existing_member.is_active {
+            return Ok(suspended_login_response(&existing_member));
+        }
         info!("✅ 기존 구글 계정 발견, 로그인 처리");
         
         // 마지막 로그인 시간 업데이트
@@ -1814,16 +3032,16 @@ async fn google_id_token_login(
         }
         
         // JWT 생성
-        let token = create_jwt(existing_member.id, &existing_member.email, &config).unwrap_or_default();
-        let access_token = generate_access_token(existing_member.id, &existing_member.email, &config);
-        let refresh_token = generate_refresh_token(existing_member.id, &existing_member.email, &config);
+        let token = create_jwt(&existing_member, &config, clock.now()).unwrap_or_default();
+        let access_token = generate_access_token(&existing_member, &config, clock.now());
+        let refresh_token = generate_refresh_token(&existing_member, &config, clock.now());
         return Ok(HttpResponse::Ok().json(GoogleIdTokenResponse {
             success: true,
             message: "기존 계정으로 로그인 성공".to_string(),
             data: Some(serde_json::json!({
-                "member": member_to_camelcase_json(&existing_member),
-                "authProvider": auth_provider_to_camelcase_json(&existing_auth),
-                "googlePayload": google_payload_to_camelcase_json(&google_payload)
+                "member": MemberDto::from(&existing_member),
+                "authProvider": AuthProviderDto::from(&existing_auth),
+                "googlePayload": GoogleProfileDto::from(&google_payload)
             })),
             token: Some(token),
             access_token: Some(access_token),
@@ -1834,6 +3052,9 @@ async fn google_id_token_login(
     
     // 2. 같은 이메일로 가입된 계정이 있는지 확인
     if let Ok(Some((existing_member, _existing_auth))) = db.find_member_by_email(&google_payload.email).await {
+        if !existing_member.is_active {
+            return Ok(suspended_login_response(&existing_member));
+        }
         info!("📧 같은 이메일의 기존 계정 발견");
         
         // 기존 계정에 구글 로그인 연결
@@ -1846,16 +3067,16 @@ async fn google_id_token_login(
             Ok(new_auth) => {
                 info!("✅ 기존 계정에 구글 로그인 연결 성공");
                 // JWT 생성
-                let token = create_jwt(existing_member.id, &existing_member.email, &config).unwrap_or_default();
-                let access_token = generate_access_token(existing_member.id, &existing_member.email, &config);
-                let refresh_token = generate_refresh_token(existing_member.id, &existing_member.email, &config);
+                let token = create_jwt(&existing_member, &config, clock.now()).unwrap_or_default();
+                let access_token = generate_access_token(&existing_member, &config, clock.now());
+                let refresh_token = generate_refresh_token(&existing_member, &config, clock.now());
                 return Ok(HttpResponse::Ok().json(GoogleIdTokenResponse {
                     success: true,
                     message: "기존 계정에 구글 로그인 연결 성공".to_string(),
                     data: Some(serde_json::json!({
-                        "member": member_to_camelcase_json(&existing_member),
-                        "authProvider": auth_provider_to_camelcase_json(&new_auth),
-                        "googlePayload": google_payload_to_camelcase_json(&google_payload)
+                        "member": MemberDto::from(&existing_member),
+                        "authProvider": AuthProviderDto::from(&new_auth),
+                        "googlePayload": GoogleProfileDto::from(&google_payload)
                     })),
                     token: Some(token),
                     access_token: Some(access_token),
@@ -1864,6 +3085,9 @@ async fn google_id_token_login(
                 }));
             }
             Err(e) => {
+                if let Some(response) = registration_conflict_response(&e) {
+                    return Ok(response);
+                }
                 error!("❌ 구글 로그인 연결 실패: {}", e);
                 return Ok(HttpResponse::InternalServerError().json(GoogleIdTokenResponse {
                     success: false,
@@ -1906,16 +3130,16 @@ async fn google_id_token_login(
         Ok((member, auth_provider)) => {
             info!("✅ 새로운 구글 회원 생성 성공: ID {}", member.id);
             // JWT 생성
-            let token = create_jwt(member.id, &member.email, &config).unwrap_or_default();
-            let access_token = generate_access_token(member.id, &member.email, &config);
-            let refresh_token = generate_refresh_token(member.id, &member.email, &config);
+            let token = create_jwt(&member, &config, clock.now()).unwrap_or_default();
+            let access_token = generate_access_token(&member, &config, clock.now());
+            let refresh_token = generate_refresh_token(&member, &config, clock.now());
             Ok(HttpResponse::Ok().json(GoogleIdTokenResponse {
                 success: true,
                 message: "구글 회원가입 성공".to_string(),
                 data: Some(serde_json::json!({
-                    "member": member_to_camelcase_json(&member),
-                    "authProvider": auth_provider_to_camelcase_json(&auth_provider),
-                    "googlePayload": google_payload_to_camelcase_json(&google_payload)
+                    "member": MemberDto::from(&member),
+                    "authProvider": AuthProviderDto::from(&auth_provider),
+                    "googlePayload": GoogleProfileDto::from(&google_payload)
                 })),
                 token: Some(token),
                 access_token: Some(access_token),
@@ -1924,6 +3148,9 @@ async fn google_id_token_login(
             }))
         }
         Err(e) => {
+            if let Some(response) = registration_conflict_response(&e) {
+                return Ok(response);
+            }
             error!("❌ 구글 회원가입 실패: {}", e);
             Ok(HttpResponse::InternalServerError().json(GoogleIdTokenResponse {
                 success: false,
@@ -1942,25 +3169,23 @@ async fn google_id_token_login(
 async fn get_marker_images(
     db: web::Data<Database>,
     path: web::Path<i64>,
+    member: Option<AuthenticatedMember>,
 ) -> Result<HttpResponse> {
-    let marker_id = path.into_inner() as i32;
+    let marker_id = path.into_inner();
     
     info!("🖼️ 마커 이미지 조회 요청: 마커 ID {}", marker_id);
     
+    if let Ok(Some(marker)) = db.get_marker_detail(marker_id).await
+        && is_marker_hidden(&db, &marker, member.as_ref()).await
+    {
+        return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"));
+    }
+    
     match db.get_marker_images(marker_id).await {
         Ok(images) => {
             info!("✅ 마커 이미지 조회 성공: {}개 이미지", images.len());
-            let formatted_images: Vec<serde_json::Value> = images.iter()
-                .map(|image| serde_json::json!({
-                    "id": image.id,
-                    "markerId": image.marker_id,
-                    "imageType": image.image_type,
-                    "imageUrl": image.image_url,
-                    "imageOrder": image.image_order,
-                    "isPrimary": image.is_primary,
-                    "createdAt": image.created_at,
-                    "updatedAt": image.updated_at
-                }))
+            let formatted_images: Vec<MarkerImageDto> = images.iter()
+                .map(MarkerImageDto::from)
                 .collect();
             
             Ok(HttpResponse::Ok().json(serde_json::json!({
@@ -1984,32 +3209,71 @@ async fn add_marker_image(
     db: web::Data<Database>,
     path: web::Path<i64>,
     payload: web::Json<AddMarkerImageRequest>,
+    config: web::Data<Config>,
+    clock: web::Data<dyn Clock>,
 ) -> Result<HttpResponse> {
-    let marker_id = path.into_inner() as i32;
+    let marker_id = path.into_inner();
     let input = payload.into_inner();
     
-    info!("🖼️ 마커 이미지 추가 요청: 마커 ID {}, 이미지 타입 {}", marker_id, input.image_type);
+    info!("🖼️ 마커 이미지 추가 요청: 마커 ID {}, 이미지 타입 {:?}", marker_id, input.image_type);
+    
+    if let Some(image_type) = input.image_type.as_deref()
+        && !MARKER_IMAGE_TYPES.contains(&image_type)
+    {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 이미지 타입입니다. (thumbnail, detail, gallery)",
+            Some(&format!("요청 타입: {}", image_type)),
+            Some("마커 이미지 추가 - 요청 검증 실패")
+        ));
+    }
+    
+    // 마커당 이미지 수는 작성자 신뢰 등급 기준
+    let max_images = match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) => match marker.member_id {
+            Some(owner_id) => match load_member_trust(&db, &config, owner_id, clock.now()).await {
+                Ok(trust) => trust.limits.images_per_marker,
+                Err(response) => return Ok(response),
+            },
+            None => config.max_images_per_marker,
+        },
+        Ok(None) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            return Ok(ErrorHandler::internal_server_error("마커 조회 실패", Some(&format!("데이터베이스 오류: {}", e))));
+        }
+    };
+    let quota = marker_image_quota(&config, max_images);
     
     let image_order = input.image_order.unwrap_or(0);
     let is_primary = input.is_primary.unwrap_or(false);
     
-    match db.add_marker_image(marker_id, &input.image_type, &input.image_url, image_order, is_primary).await {
-        Ok(image_id) => {
-            info!("✅ 마커 이미지 추가 성공: 이미지 ID {}", image_id);
+    match db.add_marker_image_with_variants(marker_id, input.image_type.as_deref(), &input.image_url, image_order, is_primary, &quota).await {
+        Ok(mut images) => {
+            let image_id = images[0].id;
+            info!("✅ 마커 이미지 추가 성공: 이미지 ID {} (파생 {}개)", image_id, images.len() - 1);
+            match db.ensure_marker_cover_image(marker_id).await {
+                Ok(cover_id) => images[0].is_primary = cover_id == Some(image_id),
+                Err(e) => warn!("⚠️ 대표 이미지 자동 선정 실패: {}", e),
+            }
+            let image = &images[0];
+            let variants: Vec<MarkerImageDto> = images[1..].iter().map(MarkerImageDto::from).collect();
             Ok(HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
                 "message": "마커 이미지 추가 성공",
                 "data": {
-                    "imageId": image_id,
+                    "imageId": image.id,
                     "markerId": marker_id,
-                    "imageType": input.image_type,
-                    "imageUrl": input.image_url,
-                    "imageOrder": image_order,
-                    "isPrimary": is_primary
+                    "imageType": image.image_type,
+                    "imageUrl": image.image_url,
+                    "imageOrder": image.image_order,
+                    "isPrimary": image.is_primary,
+                    "variants": variants
                 }
             })))
         }
         Err(e) => {
+            if let Some(exceeded) = e.downcast_ref::<MarkerImageQuotaExceeded>() {
+                return Ok(marker_image_quota_response(&config, &quota, exceeded));
+            }
             error!("❌ 마커 이미지 추가 실패: {}", e);
             Ok(ErrorHandler::internal_server_error(
                 "마커 이미지 추가 실패",
@@ -2024,14 +3288,24 @@ async fn delete_marker_image(
     path: web::Path<(i64, i32)>,
 ) -> Result<HttpResponse> {
     let (marker_id, image_id) = path.into_inner();
-    let marker_id = marker_id as i32;
     
     info!("🗑️ 마커 이미지 삭제 요청: 마커 ID {}, 이미지 ID {}", marker_id, image_id);
     
+    if db.is_marker_image_on_legal_hold(image_id).await.unwrap_or(false) {
+        return Ok(ErrorHandler::conflict(
+            "법적 보존 중인 이미지는 삭제할 수 없습니다.",
+            Some(&format!("이미지 {} legal hold", image_id))
+        ));
+    }
+    
     match db.delete_marker_image(image_id).await {
         Ok(deleted) => {
             if deleted {
                 info!("✅ 마커 이미지 삭제 성공: 이미지 ID {}", image_id);
+                // 대표 이미지가 삭제된 경우 남은 이미지 중에서 다시 선정
+                if let Err(e) = db.ensure_marker_cover_image(marker_id).await {
+                    warn!("⚠️ 대표 이미지 자동 선정 실패: {}", e);
+                }
                 Ok(HttpResponse::Ok().json(serde_json::json!({
                     "success": true,
                     "message": "마커 이미지 삭제 성공",
@@ -2060,7 +3334,6 @@ async fn set_marker_primary_image(
     path: web::Path<(i64, i32)>,
 ) -> Result<HttpResponse> {
     let (marker_id, image_id) = path.into_inner();
-    let marker_id = marker_id as i32;
     
     info!("⭐ 마커 대표 이미지 설정 요청: 마커 ID {}, 이미지 ID {}", marker_id, image_id);
     
@@ -2092,7 +3365,6 @@ async fn update_marker_image_order(
     payload: web::Json<UpdateMarkerImageOrderRequest>,
 ) -> Result<HttpResponse> {
     let (marker_id, image_id) = path.into_inner();
-    let marker_id = marker_id as i32;
     let input = payload.into_inner();
     
     info!("📝 마커 이미지 순서 변경 요청: 마커 ID {}, 이미지 ID {}, 새 순서 {}", marker_id, image_id, input.image_order);
@@ -2119,139 +3391,389 @@ async fn update_marker_image_order(
     }
 }
 
-/// Member를 카멜케이스 JSON으로 변환
-fn member_to_camelcase_json(member: &Member) -> serde_json::Value {
-    serde_json::json!({
-        "id": member.id,
-        "email": member.email,
-        "nickname": member.nickname,
-        "profileImageUrl": member.profile_image_url,
-        "region": member.region,
-        "gender": member.gender,
-        "age": member.age,
-        "personalityType": member.personality_type,
-        "isActive": member.is_active,
-        "emailVerified": member.email_verified,
-        "createdAt": member.created_at,
-        "updatedAt": member.updated_at,
-        "lastLoginAt": member.last_login_at
-    })
-}
-
-/// AuthProvider를 카멜케이스 JSON으로 변환
-fn auth_provider_to_camelcase_json(auth_provider: &AuthProvider) -> serde_json::Value {
-    serde_json::json!({
-        "id": auth_provider.id,
-        "memberId": auth_provider.member_id,
-        "providerType": auth_provider.provider_type,
-        "providerId": auth_provider.provider_id,
-        "providerEmail": auth_provider.provider_email,
-        "passwordHash": auth_provider.password_hash,
-        "createdAt": auth_provider.created_at,
-        "updatedAt": auth_provider.updated_at
-    })
-}
-
-/// GooglePayload를 카멜케이스 JSON으로 변환
-fn google_payload_to_camelcase_json(payload: &GoogleIdTokenPayload) -> serde_json::Value {
-    serde_json::json!({
-        "email": payload.email,
-        "name": payload.name,
-        "picture": payload.picture,
-        "givenName": payload.given_name,
-        "familyName": payload.family_name
-    })
-}
-
-/// JWT 토큰에서 유저 ID 추출
-fn extract_user_id_from_token(req: &actix_web::HttpRequest, config: &Config) -> Result<i64, actix_web::Error> {
-    let auth_header = req.headers().get("Authorization").and_then(|h| h.to_str().ok());
-    if auth_header.is_none() || !auth_header.unwrap().starts_with("Bearer ") {
-        return Err(actix_web::error::ErrorUnauthorized("No Bearer token"));
-    }
-    let token = &auth_header.unwrap()[7..];
-    let validation = Validation::default();
-    let claims = match decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
-        &validation,
-    ) {
-        Ok(data) => data.claims,
-        Err(e) => {
-            return Err(actix_web::error::ErrorUnauthorized(format!("Invalid token: {}", e)));
-        }
-    };
-    let user_id: i64 = match claims.sub.parse() {
-        Ok(id) => id,
-        Err(_) => {
-            return Err(actix_web::error::ErrorUnauthorized("Invalid user id in token"));
-        }
-    };
-    Ok(user_id)
-}
-
-/// Marker를 카멜케이스 JSON으로 변환
-fn marker_to_camelcase_json(marker: &crate::database::Marker) -> serde_json::Value {
-    // PostGIS WKT 형식에서 좌표 추출 (POINT(lng lat))
-    let (latitude, longitude) = if let Some(location) = &marker.location {
-        if location.starts_with("POINT(") && location.ends_with(")") {
-            let coords = &location[6..location.len()-1]; // "POINT(" 제거하고 ")" 제거
-            let parts: Vec<&str> = coords.split_whitespace().collect();
-            if parts.len() == 2 {
-                if let (Ok(lng), Ok(lat)) = (parts[0].parse::<f64>(), parts[1].parse::<f64>()) {
-                    (lat, lng) // WKT는 (longitude latitude) 순서이므로 바꿔줌
-                } else {
-                    (0.0, 0.0)
-                }
-            } else {
-                (0.0, 0.0)
+/// 마커 이미지 순서 일괄 변경 (전체 이미지 ID 목록을 받아 한 트랜잭션으로 적용)
+async fn reorder_marker_images(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<ReorderMarkerImagesRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let marker_id = path.into_inner();
+    let input = payload.into_inner();
+    
+    let user_id = member.member_id;
+    
+    info!("📝 마커 이미지 순서 일괄 변경 요청: 마커 ID {}, 유저 {}, 순서 {:?}", marker_id, user_id, input.image_ids);
+    
+    // 마커 소유자 확인 (게시 중단된 마커는 수정 불가)
+    match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) if marker.taken_down_at.is_some() => {
+            return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"));
+        }
+        Ok(Some(marker)) => {
+            if marker.member_id != Some(user_id) {
+                return Ok(ErrorHandler::forbidden(
+                    "본인이 작성한 마커의 이미지만 변경할 수 있습니다.",
+                    Some(&format!("마커 {} 작성자 {:?}, 요청자 {}", marker_id, marker.member_id, user_id))
+                ));
             }
-        } else {
-            (0.0, 0.0)
         }
-    } else {
-        (0.0, 0.0)
+        Ok(None) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    
+    // 요청 목록 검증: 중복 없이 마커의 모든 이미지를 정확히 한 번씩 포함해야 함
+    let current_images = match db.get_marker_images(marker_id).await {
+        Ok(images) => images,
+        Err(e) => {
+            error!("❌ 마커 이미지 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "마커 이미지 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
     };
+    let current_ids: std::collections::HashSet<i32> = current_images.iter().map(|image| image.id).collect();
+    let mut seen = std::collections::HashSet::new();
+    if let Some(duplicate) = input.image_ids.iter().find(|id| !seen.insert(**id)) {
+        return Ok(ErrorHandler::bad_request(
+            "이미지 ID가 중복되었습니다.",
+            Some(&format!("중복 ID: {}", duplicate)),
+            Some("마커 이미지 순서 일괄 변경 - 요청 검증 실패")
+        ));
+    }
+    let unknown: Vec<i32> = input.image_ids.iter().copied().filter(|id| !current_ids.contains(id)).collect();
+    if !unknown.is_empty() {
+        return Ok(ErrorHandler::bad_request(
+            "마커에 속하지 않은 이미지 ID가 포함되어 있습니다.",
+            Some(&format!("잘못된 ID: {:?}", unknown)),
+            Some("마커 이미지 순서 일괄 변경 - 요청 검증 실패")
+        ));
+    }
+    let missing: Vec<i32> = current_images.iter().map(|image| image.id).filter(|id| !seen.contains(id)).collect();
+    if !missing.is_empty() {
+        return Ok(ErrorHandler::bad_request(
+            "마커의 모든 이미지 ID를 포함해야 합니다.",
+            Some(&format!("누락된 ID: {:?}", missing)),
+            Some("마커 이미지 순서 일괄 변경 - 요청 검증 실패")
+        ));
+    }
+    
+    match db.reorder_marker_images(marker_id, &input.image_ids).await {
+        Ok(Some(images)) => {
+            info!("✅ 마커 이미지 순서 일괄 변경 성공: 마커 ID {}, {}개 이미지", marker_id, images.len());
+            let formatted_images: Vec<MarkerImageDto> = images.iter()
+                .map(MarkerImageDto::from)
+                .collect();
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": "마커 이미지 순서 일괄 변경 성공",
+                "data": formatted_images,
+                "count": images.len()
+            })))
+        }
+        Ok(None) => Ok(ErrorHandler::conflict(
+            "이미지 목록이 변경되었습니다. 다시 조회 후 시도해주세요.",
+            Some(&format!("마커 {} 이미지 목록 동시 수정 감지", marker_id))
+        )),
+        Err(e) => {
+            error!("❌ 마커 이미지 순서 일괄 변경 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "마커 이미지 순서 일괄 변경 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자 권한 확인 (아니면 403 응답 반환)
+async fn require_admin(db: &Database, member: &AuthenticatedMember) -> std::result::Result<(), HttpResponse> {
+    match db.is_admin_member(member.member_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(ErrorHandler::forbidden(
+            "관리자만 사용할 수 있습니다.",
+            Some(&format!("요청자 {}", member.member_id))
+        )),
+        Err(e) => Err(ErrorHandler::internal_server_error(
+            "관리자 권한 확인 실패",
+            Some(&format!("데이터베이스 오류: {}", e))
+        )),
+    }
+}
+
+/// 게시 중단된 마커는 관리자가 아니면 존재하지 않는 것으로 처리
+/// 임시 저장/예약 마커와 비공개 마커는 작성자만, followers 마커는 작성자와 팔로워만 봄
+async fn is_marker_hidden(db: &Database, marker: &Marker, member: Option<&AuthenticatedMember>) -> bool {
+    let is_author = member.is_some_and(|member| marker.member_id == Some(member.member_id));
+    if !is_author {
+        if !marker.is_published() {
+            return true;
+        }
+        let visible = match MarkerVisibility::of(marker) {
+            MarkerVisibility::Public => true,
+            MarkerVisibility::Followers => match (member, marker.member_id) {
+                (Some(member), Some(author_id)) => db.is_following(member.member_id, author_id).await.unwrap_or(false),
+                _ => false,
+            },
+            MarkerVisibility::Private => false,
+        };
+        if !visible {
+            return true;
+        }
+    }
+    if marker.taken_down_at.is_none() {
+        return false;
+    }
+    match member {
+        Some(member) => !db.is_admin_member(member.member_id).await.unwrap_or(false),
+        None => true,
+    }
+}
+
+/// 시청자 스트림 이벤트 간격 (PRESENCE_TTL_SECS보다 짧아야 시청 중으로 유지됨)
+const PRESENCE_STREAM_INTERVAL_SECS: u64 = 15;
+
+/// 위치 추천에 보여줄 근처 마커/인기 지점 수, 인기 지점이 되는 최소 마커 수
+const NEARBY_SUGGESTION_LIMIT: i64 = 5;
+const POPULAR_SPOT_LIMIT: i64 = 5;
+const POPULAR_SPOT_MIN_MARKERS: i64 = 3;
+
+/// 주변 마커 조회 반경 (기본/최대, 미터)과 개수
+const DEFAULT_NEARBY_RADIUS_M: f64 = 1_000.0;
+const MAX_NEARBY_RADIUS_M: f64 = 20_000.0;
+const DEFAULT_NEARBY_LIMIT: i64 = 50;
+const MAX_NEARBY_LIMIT: i64 = 200;
 
+/// 히트맵 격자는 지도 영역 긴 변을 이 칸 수보다 잘게 나누지 않음 (큰 영역에 높은 줌을 보내도 응답 크기 제한)
+const MAX_HEATMAP_GRID_CELLS: f64 = 128.0;
+
+/// 마커 통계 기간 (from이 없으면 to에서 기본 기간 수만큼 앞으로, 한 번에 최대 기간 수)
+const DEFAULT_STATS_PERIODS: i32 = 30;
+const MAX_STATS_PERIODS: i64 = 366;
+/// 마커 통계 상위 지역 수 (기본/최대)
+const DEFAULT_STATS_REGION_LIMIT: i64 = 10;
+const MAX_STATS_REGION_LIMIT: i64 = 50;
+
+/// 클라이언트가 직접 지정할 수 있는 마커 이미지 타입
+const MARKER_IMAGE_TYPES: [&str; 3] = ["thumbnail", "detail", "gallery"];
+
+/// 마커 이미지 쿼터 JSON (한도, 사용량, 남은 양)
+fn marker_image_quota_json(config: &Config, max_images: i64, used_images: i64, used_bytes: i64) -> serde_json::Value {
+    let max_bytes = (config.max_marker_original_mb * 1024.0 * 1024.0) as i64;
     serde_json::json!({
-        "id": marker.id,
-        "memberId": marker.member_id,
-        "latitude": latitude,
-        "longitude": longitude,
-        "emotionTag": marker.emotion_tag,
-        "emotionTagInput": marker.emotion_tag_input,
-        "emotion": marker.emotion,
-        "description": marker.description,
-        "sharingOption": marker.sharing_option,
-        "likes": marker.likes,
-        "dislikes": marker.dislikes,
-        "views": marker.views,
-        "author": marker.author,
-        "thumbnailImg": marker.thumbnail_img,
-        "createdAt": marker.created_at,
-        "updatedAt": marker.updated_at
+        "maxImages": max_images,
+        "usedImages": used_images,
+        "remainingImages": (max_images - used_images).max(0),
+        "maxOriginalBytes": max_bytes,
+        "usedOriginalBytes": used_bytes,
+        "remainingOriginalBytes": (max_bytes - used_bytes).max(0)
     })
 }
 
+/// 마커 이미지 쿼터 (max_images는 작성자 신뢰 등급의 마커당 이미지 수)
+fn marker_image_quota(config: &Config, max_images: i64) -> MarkerImageQuota {
+    MarkerImageQuota {
+        max_images,
+        max_original_bytes: (config.max_marker_original_mb * 1024.0 * 1024.0) as i64,
+    }
+}
+
+/// 마커 이미지 쿼터 초과 응답 (이미지 수 한도를 먼저 안내)
+fn marker_image_quota_response(config: &Config, quota: &MarkerImageQuota, exceeded: &MarkerImageQuotaExceeded) -> HttpResponse {
+    let quota_json = marker_image_quota_json(config, quota.max_images, exceeded.used_images, exceeded.used_bytes);
+    if exceeded.used_images + exceeded.new_images > quota.max_images {
+        return ErrorHandler::quota_exceeded(
+            &format!("마커당 이미지는 최대 {}개까지 등록할 수 있습니다.", quota.max_images),
+            Some(&format!("현재 {}개, 추가 요청 {}개", exceeded.used_images, exceeded.new_images)),
+            quota_json
+        );
+    }
+    ErrorHandler::quota_exceeded(
+        &format!("마커당 원본 이미지 용량은 최대 {:.0}MB까지 등록할 수 있습니다.", config.max_marker_original_mb),
+        Some(&format!("현재 {} bytes, 추가 요청 {} bytes", exceeded.used_bytes, exceeded.new_bytes)),
+        quota_json
+    )
+}
+
+/// 마커 생성과 함께 추가할 이미지의 쿼터 확인 (초과 시 에러 응답 반환)
+/// 기존 마커에 추가할 때는 add_marker_image_with_variants가 마커 행을 잠그고 같은 트랜잭션에서 확인
+async fn check_new_marker_image_quota(
+    db: &Database,
+    config: &Config,
+    max_images: i64,
+    image_urls: &[&str],
+) -> std::result::Result<(), HttpResponse> {
+    let mut new_bytes = 0i64;
+    for url in image_urls {
+        new_bytes += db.get_image_original_size(url).await
+            .map_err(|e| ErrorHandler::internal_server_error(
+                "마커 이미지 쿼터 확인 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))?
+            .unwrap_or(0);
+    }
+    
+    let quota = marker_image_quota(config, max_images);
+    let exceeded = MarkerImageQuotaExceeded { used_images: 0, used_bytes: 0, new_images: image_urls.len() as i64, new_bytes };
+    if exceeded.exceeds(&quota) {
+        return Err(marker_image_quota_response(config, &quota, &exceeded));
+    }
+    Ok(())
+}
+
+/// 회원 신뢰 등급과 한도 (최근 24시간 마커 수 포함, 탈퇴했거나 없는 회원은 404)
+async fn load_member_trust(
+    db: &Database,
+    config: &Config,
+    member_id: i64,
+    now: chrono::DateTime<Utc>,
+) -> std::result::Result<MemberTrust, HttpResponse> {
+    match db.get_member_trust_stats(member_id, now - chrono::Duration::hours(24)).await {
+        Ok(Some(stats)) => Ok(MemberTrust::resolve(&TrustPolicy::from_config(config), &stats, now)),
+        Ok(None) => Err(ErrorHandler::not_found("회원을 찾을 수 없습니다.")),
+        Err(e) => Err(ErrorHandler::internal_server_error(
+            "회원 신뢰 등급 확인 실패",
+            Some(&format!("데이터베이스 오류: {}", e))
+        )),
+    }
+}
+
+/// 신뢰 등급상 링크를 쓸 수 없는데 글에 링크가 있으면 403 응답
+fn link_rejection(trust: &MemberTrust, texts: &[Option<&str>]) -> Option<HttpResponse> {
+    if trust.limits.links_allowed || !texts.iter().flatten().any(|text| contains_link(text)) {
+        return None;
+    }
+    Some(ErrorHandler::forbidden(
+        "신규 계정은 마커에 링크를 넣을 수 없습니다. 활동 이력이 쌓이면 자동으로 풀립니다.",
+        Some(&format!("신뢰 등급: {}", trust.level.as_str()))
+    ))
+}
+
+/// JWT 토큰에서 유저 ID 추출
+/// 피드/추천에 적용할 콘텐츠 언어 결정
+/// 쿼리 파라미터(lang)가 우선이며, 없으면 로그인한 회원의 선호 언어를 사용
+async fn resolve_content_languages(
+    lang_param: Option<&str>,
+    db: &Database,
+    member: Option<&AuthenticatedMember>,
+) -> Option<Vec<String>> {
+    if let Some(raw) = lang_param {
+        if raw.trim().eq_ignore_ascii_case("all") {
+            return None;
+        }
+        let languages = parse_language_list(raw);
+        return if languages.is_empty() { None } else { Some(languages) };
+    }
+
+    match db.get_member_by_id(member?.member_id).await {
+        Ok(Some(member)) => member.preferred_languages.filter(|langs| !langs.is_empty()),
+        Ok(None) => None,
+        Err(e) => {
+            warn!("⚠️ 선호 언어 조회 실패: {}", e);
+            None
+        }
+    }
+}
+
+fn invalid_visibility_response(raw: &str, context: &str) -> HttpResponse {
+    ErrorHandler::bad_request(
+        "visibility는 public, followers, private 중 하나여야 합니다.",
+        Some(&format!("요청 값: {}", raw)),
+        Some(&format!("{} - 공개 범위 검증 실패", context))
+    )
+}
+
+/// 감정 태그(쉼표 구분)가 비었거나 GET /api/emotions 목록에 없는 값이 있으면 422
+fn emotion_tag_rejection(raw: &str, context: &str) -> Option<HttpResponse> {
+    if raw.split(',').all(|id| id.trim().is_empty()) {
+        return Some(ErrorHandler::unprocessable_entity(
+            "감정 태그를 하나 이상 선택해야 합니다.",
+            Some(&format!("{} - 감정 태그 없음", context))
+        ));
+    }
+    let unknown = unknown_emotion_ids(raw);
+    if unknown.is_empty() {
+        return None;
+    }
+    Some(ErrorHandler::unprocessable_entity(
+        "알 수 없는 감정 태그입니다. (GET /api/emotions 목록의 id만 사용 가능)",
+        Some(&format!("{} - 알 수 없는 감정 태그: {}", context, unknown.join(", ")))
+    ))
+}
+
+/// 예약 게시는 이 기간 안으로만
+const MAX_SCHEDULE_AHEAD_DAYS: i64 = 365;
+
+/// 마커 게시 상태와 예약 시각 검증 (status가 없으면 바로 게시, publish_at은 scheduled에서만)
+fn parse_marker_publication(
+    status: Option<&str>,
+    publish_at: Option<&str>,
+    now: chrono::DateTime<Utc>,
+) -> std::result::Result<(MarkerStatus, Option<chrono::DateTime<Utc>>), String> {
+    let status = match status {
+        Some(raw) => MarkerStatus::parse(raw)
+            .ok_or_else(|| format!("status는 draft, published, scheduled 중 하나여야 합니다: {}", raw))?,
+        None => MarkerStatus::Published,
+    };
+    match (status, publish_at) {
+        (MarkerStatus::Scheduled, Some(raw)) => {
+            let publish_at = parse_datetime_param(raw)
+                .ok_or_else(|| format!("publish_at 형식이 올바르지 않습니다 (RFC 3339): {}", raw))?;
+            if publish_at <= now {
+                return Err("publish_at은 현재 시각 이후여야 합니다.".to_string());
+            }
+            if publish_at > now + chrono::Duration::days(MAX_SCHEDULE_AHEAD_DAYS) {
+                return Err(format!("예약 게시는 {}일 이내로만 할 수 있습니다.", MAX_SCHEDULE_AHEAD_DAYS));
+            }
+            Ok((status, Some(publish_at)))
+        }
+        (MarkerStatus::Scheduled, None) => Err("예약 게시(scheduled)에는 publish_at이 필요합니다.".to_string()),
+        (_, Some(_)) => Err("publish_at은 status가 scheduled일 때만 사용할 수 있습니다.".to_string()),
+        (_, None) => Ok((status, None)),
+    }
+}
+
 /// 마커 생성
 async fn create_marker(
     db: web::Data<Database>,
     payload: web::Json<CreateMarkerRequest>,
     config: web::Data<Config>,
-    req: actix_web::HttpRequest,
+    profiles: web::Data<EmotionProfileCache>,
+    clock: web::Data<dyn Clock>,
+    marker_events: web::Data<MarkerEvents>,
+    cluster_cache: web::Data<ClusterCache>,
+    member: AuthenticatedMember,
 ) -> Result<HttpResponse> {
     let input = payload.into_inner();
     
-    // JWT 토큰에서 사용자 ID 추출
-    let user_id = match extract_user_id_from_token(&req, &config) {
-        Ok(id) => id,
-        Err(_) => {
-            return Ok(ErrorHandler::unauthorized(
-                "로그인이 필요합니다. JWT 토큰을 확인해주세요.",
-                Some("마커 생성 - 토큰 추출 실패")
+    let user_id = member.member_id;
+    
+    let (status, publish_at) = match parse_marker_publication(input.status.as_deref(), input.publish_at.as_deref(), clock.now()) {
+        Ok(publication) => publication,
+        Err(message) => {
+            return Ok(ErrorHandler::bad_request(
+                &message,
+                None,
+                Some("마커 생성 - 게시 상태 검증 실패")
             ));
         }
     };
+    let visibility = match input.visibility.as_deref().or(input.sharing_option.as_deref()) {
+        Some(raw) => match MarkerVisibility::parse(raw) {
+            Some(visibility) => visibility,
+            None => return Ok(invalid_visibility_response(raw, "마커 생성")),
+        },
+        None => MarkerVisibility::Public,
+    };
+    if let Some(response) = emotion_tag_rejection(&input.emotion_tag, "마커 생성") {
+        return Ok(response);
+    }
     
     // 사용자 정보 조회
     let user = match db.get_member_by_id(user_id).await {
@@ -2275,75 +3797,135 @@ async fn create_marker(
     
     info!("📍 마커 생성 요청: 사용자 {} ({}), 위치 ({}, {})", user.nickname, user_id, input.latitude, input.longitude);
     
+    // 신뢰 등급별 한도 (하루 마커 수, 마커당 이미지 수, 링크 허용)
+    let trust = match load_member_trust(&db, &config, user_id, clock.now()).await {
+        Ok(trust) => trust,
+        Err(response) => return Ok(response),
+    };
+    if trust.markers_last_day >= trust.limits.markers_per_day {
+        return Ok(ErrorHandler::quota_exceeded(
+            &format!("하루에 마커는 최대 {}개까지 만들 수 있습니다.", trust.limits.markers_per_day),
+            Some(&format!("신뢰 등급 {}, 최근 24시간 {}개", trust.level.as_str(), trust.markers_last_day)),
+            serde_json::json!(trust)
+        ));
+    }
+    if let Some(response) = link_rejection(&trust, &[Some(input.description.as_str()), input.emotion.as_deref(), input.emotion_tag_input.as_deref()]) {
+        return Ok(response);
+    }
+    
     // 이미지 정보 로깅
     if let Some(ref images) = input.images {
         info!("   - 이미지 {}개 포함", images.len());
         for (i, img) in images.iter().enumerate() {
-            info!("     {}. {} (타입: {}, 순서: {}, 대표: {})", 
+            info!("     {}. {} (타입: {:?}, 순서: {}, 대표: {})", 
                 i + 1, img.image_url, img.image_type, 
                 img.image_order.unwrap_or(0), 
                 img.is_primary.unwrap_or(false));
         }
+        if let Some(invalid) = images.iter()
+            .filter_map(|img| img.image_type.as_deref())
+            .find(|image_type| !MARKER_IMAGE_TYPES.contains(image_type))
+        {
+            return Ok(ErrorHandler::bad_request(
+                "지원하지 않는 이미지 타입입니다. (thumbnail, detail, gallery)",
+                Some(&format!("요청 타입: {}", invalid)),
+                Some("마커 생성 - 이미지 검증 실패")
+            ));
+        }
+        let image_urls: Vec<&str> = images.iter().map(|img| img.image_url.as_str()).collect();
+        if let Err(response) = check_new_marker_image_quota(&db, &config, trust.limits.images_per_marker, &image_urls).await {
+            return Ok(response);
+        }
     }
     
-            match db.create_marker(
-            user_id,
-            input.latitude,
-            input.longitude,
-            &input.emotion_tag,
-            input.emotion_tag_input.as_deref(), // 사용자가 입력한 감성태그들
-            input.emotion.as_deref(), // 자유로운 감정/경험 설명 텍스트
-            &input.description,
-            &user.nickname, // 실제 사용자 닉네임 사용
-            input.thumbnail_img.as_deref(),
-            input.sharing_option.as_deref(), // 공유 옵션 추가
-        ).await {
-        Ok(marker) => {
-            info!("✅ 마커 생성 성공: ID {}, 작성자 {}", marker.id, user.nickname);
+    let tags = match collect_marker_tags(&input.description, input.tags.as_deref()) {
+        Ok(tags) => tags,
+        Err(invalid) => {
+            return Ok(ErrorHandler::bad_request(
+                &format!("해시태그는 {}자 이하의 글자, 숫자, 밑줄(_)만 사용할 수 있습니다.", crate::hashtags::MAX_TAG_CHARS),
+                Some(&format!("태그: {}", invalid)),
+                Some("마커 생성 - 해시태그 검증 실패")
+            ));
+        }
+    };
+    
+    let images: Vec<NewMarkerImage> = input.images.unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(index, image_req)| NewMarkerImage {
+            image_type: image_req.image_type,
+            image_url: image_req.image_url,
+            image_order: image_req.image_order.unwrap_or(index as i32),
+            is_primary: image_req.is_primary.unwrap_or(false), // 지정하지 않으면 아래에서 자동 선정
+        })
+        .collect();
+    
+    match db.create_marker(
+        user_id,
+        input.latitude,
+        input.longitude,
+        &input.emotion_tag,
+        input.emotion_tag_input.as_deref(), // 사용자가 입력한 감성태그들
+        input.emotion.as_deref(), // 자유로운 감정/경험 설명 텍스트
+        &input.description,
+        &user.nickname, // 실제 사용자 닉네임 사용
+        input.thumbnail_img.as_deref(),
+        Some(visibility.as_str()), // 공개 범위
+        detect_language(&input.description), // 설명으로 콘텐츠 언어 감지
+        status,
+        publish_at,
+        &images,
+    ).await {
+        Ok((marker, images)) => {
+            info!("✅ 마커 생성 성공: ID {}, 작성자 {}, 이미지 {}개, 상태 {}", marker.id, user.nickname, images.len(), status.as_str());
+            profiles.invalidate_member(user_id);
+            
+            // 태그 저장 실패해도 마커는 생성되었으므로 경고만 남김
+            if !tags.is_empty()
+                && let Err(e) = db.set_marker_tags(marker.id, &tags).await
+            {
+                warn!("⚠️ 마커 {} 해시태그 저장 실패: {}", marker.id, e);
+            }
+            // 임시 저장/예약 마커는 게시될 때 활동으로 기록
+            if status == MarkerStatus::Published
+                && let Err(e) = db.record_activity(user_id, None, ACTIVITY_MARKER_CREATED, Some(marker.id)).await
+            {
+                warn!("⚠️ 마커 {} 생성 활동 기록 실패: {}", marker.id, e);
+            }
             
-            // 이미지들 추가
-            let mut added_images = Vec::new();
-            if let Some(images) = input.images {
-                for (index, image_req) in images.into_iter().enumerate() {
-                    let image_order = image_req.image_order.unwrap_or(index as i32);
-                    let is_primary = image_req.is_primary.unwrap_or(index == 0); // 첫 번째 이미지를 기본 대표로 설정
-                    
-                    match db.add_marker_image(
-                        marker.id,
-                        &image_req.image_type,
-                        &image_req.image_url,
-                        image_order,
-                        is_primary,
-                    ).await {
-                        Ok(image_id) => {
-                            info!("✅ 이미지 추가 성공: ID {}, 타입 {}", image_id, image_req.image_type);
-                            added_images.push(serde_json::json!({
-                                "id": image_id,
-                                "markerId": marker.id,
-                                "imageType": image_req.image_type,
-                                "imageUrl": image_req.image_url,
-                                "imageOrder": image_order,
-                                "isPrimary": is_primary
-                            }));
+            let mut added_images: Vec<MarkerImageDto> = images.iter().map(MarkerImageDto::from).collect();
+            
+            // 대표 이미지가 지정되지 않았으면 해상도/선명도 기준으로 자동 선정
+            let mut marker = marker;
+            if !added_images.is_empty() {
+                match db.ensure_marker_cover_image(marker.id).await {
+                    Ok(Some(cover_id)) => {
+                        for image in added_images.iter_mut() {
+                            image.is_primary = image.id == cover_id;
                         }
-                        Err(e) => {
-                            error!("❌ 이미지 추가 실패: {}", e);
-                            // 이미지 추가 실패해도 마커는 생성되었으므로 경고만 남김
+                        if marker.thumbnail_img.as_deref().unwrap_or("").is_empty()
+                            && let Ok(Some(updated)) = db.get_marker_detail(marker.id).await
+                        {
+                            marker = updated;
                         }
                     }
+                    Ok(None) => {}
+                    Err(e) => warn!("⚠️ 대표 이미지 자동 선정 실패: {}", e),
                 }
             }
             
-            // 응답 데이터 구성
-            let mut marker_data = marker_to_camelcase_json(&marker);
-            if let Some(marker_obj) = marker_data.as_object_mut() {
-                marker_obj.insert("images".to_string(), serde_json::Value::Array(added_images));
+            if marker.is_published() {
+                marker_events.publish(MarkerEventKind::Created, &marker, None);
+                cluster_cache.invalidate_marker(&marker);
             }
             
+            // 응답 데이터 구성
+            let marker_data = MarkerDto::from(&marker).with_images(added_images).with_tags(tags);
+            
             Ok(HttpResponse::Ok().json(MarkerResponse {
                 success: true,
                 message: "마커 생성 성공".to_string(),
-                data: Some(marker_data),
+                data: Some(serde_json::json!(marker_data)),
             }))
         }
         Err(e) => {
@@ -2356,156 +3938,3844 @@ async fn create_marker(
     }
 }
 
-/// 마커 상세 정보 조회
-async fn get_marker_detail(
-    db: web::Data<Database>,
-    path: web::Path<i64>,
+/// 게시 중단/복구 대상과 요청 내용 (관리자/접속 정보는 handle_content_takedown에서 채움)
+struct ContentTakedownInput {
+    target_type: &'static str, // marker, marker_image
+    target_id: i64,
+    action: &'static str, // takedown, restore
+    reason: String,
+    legal_reference: Option<String>,
+    release_legal_hold: bool,
+}
+
+/// 관리자 게시 중단/복구 공통 처리 (권한 확인, 적용, 감사 로그)
+async fn handle_content_takedown(
+    db: &Database,
+    member: &AuthenticatedMember,
+    req: &actix_web::HttpRequest,
+    input: ContentTakedownInput,
 ) -> Result<HttpResponse> {
-    let marker_id = path.into_inner();
+    let ContentTakedownInput { target_type, target_id, action, reason, legal_reference, release_legal_hold } = input;
+    if let Err(response) = require_admin(db, member).await {
+        return Ok(response);
+    }
+    if reason.trim().is_empty() {
+        return Ok(ErrorHandler::bad_request(
+            "사유(reason)는 필수입니다.",
+            None,
+            Some(&format!("관리자 {} - {} {}", action, target_type, target_id))
+        ));
+    }
     
-    info!("🔍 마커 상세 조회: 마커 {}", marker_id);
+    let takedown = ContentTakedownAction {
+        admin_member_id: member.member_id,
+        target_type: target_type.to_string(),
+        target_id,
+        action: action.to_string(),
+        reason,
+        legal_reference,
+        release_legal_hold,
+        client_ip: req.connection_info().realip_remote_addr().map(|ip| ip.to_string()),
+        user_agent: req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|ua| ua.to_string()),
+    };
     
-    match db.get_marker_detail(marker_id).await {
-        Ok(Some(marker)) => {
-            // 마커 이미지 정보도 함께 조회
-            let images = match db.get_marker_images(marker_id as i32).await {
-                Ok(images) => images,
-                Err(e) => {
-                    warn!("⚠️ 마커 이미지 조회 실패: {}", e);
-                    vec![]
-                }
-            };
-            
-            let formatted_images: Vec<serde_json::Value> = images.iter()
-                .map(|image| serde_json::json!({
-                    "id": image.id,
-                    "markerId": image.marker_id,
-                    "imageType": image.image_type,
-                    "imageUrl": image.image_url,
-                    "imageOrder": image.image_order,
-                    "isPrimary": image.is_primary,
-                    "createdAt": image.created_at,
-                    "updatedAt": image.updated_at
-                }))
-                .collect();
-            
-            let marker_data = serde_json::json!({
-                "marker": marker_to_camelcase_json(&marker),
-                "images": formatted_images
-            });
-            
-            Ok(HttpResponse::Ok().json(MarkerResponse {
-                success: true,
-                message: "마커 상세 조회 성공".to_string(),
-                data: Some(marker_data),
-            }))
-        }
-        Ok(None) => {
-            Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"))
+    match db.apply_content_takedown(&takedown).await {
+        Ok(true) => {
+            // 대표 이미지가 게시 중단된 경우 남은 이미지 중에서 다시 선정
+            if target_type == "marker_image"
+                && action == "takedown"
+                && let Ok(Some(marker_id)) = db.get_marker_id_of_image(target_id as i32).await
+                && let Err(e) = db.ensure_marker_cover_image(marker_id).await
+            {
+                warn!("⚠️ 대표 이미지 자동 선정 실패: {}", e);
+            }
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": format!("콘텐츠 {} 완료", action),
+                "data": ContentTakedownDto::from(&takedown)
+            })))
         }
+        Ok(false) => Ok(ErrorHandler::not_found("대상 콘텐츠를 찾을 수 없습니다")),
         Err(e) => {
-            error!("❌ 마커 상세 조회 실패: {}", e);
+            error!("❌ 콘텐츠 {} 실패: {}", action, e);
             Ok(ErrorHandler::internal_server_error(
-                "마커 상세 조회 실패",
+                "콘텐츠 게시 중단 처리 실패",
                 Some(&format!("데이터베이스 오류: {}", e))
             ))
         }
     }
 }
 
-/// 마커 상세 조회 (조회수 증가 포함)
-async fn get_marker_detail_with_view(
+/// 관리자: 마커 게시 중단 (법적 보존)
+async fn takedown_marker(
     db: web::Data<Database>,
     path: web::Path<i64>,
-    config: web::Data<Config>,
+    payload: web::Json<TakedownContentRequest>,
+    member: AuthenticatedMember,
     req: actix_web::HttpRequest,
+    marker_events: web::Data<MarkerEvents>,
+    cluster_cache: web::Data<ClusterCache>,
 ) -> Result<HttpResponse> {
+    let input = payload.into_inner();
     let marker_id = path.into_inner();
-    
-    info!("📋 마커 상세 조회 (조회수 증가): 마커 {}", marker_id);
-    
-    // 먼저 마커 정보 조회
+    let response = handle_content_takedown(&db, &member, &req, ContentTakedownInput {
+        target_type: "marker",
+        target_id: marker_id,
+        action: "takedown",
+        reason: input.reason,
+        legal_reference: input.legal_reference,
+        release_legal_hold: false,
+    }).await?;
+    if response.status().is_success() {
+        publish_marker_visibility(&db, &marker_events, &cluster_cache, marker_id, MarkerEventKind::Deleted).await;
+    }
+    Ok(response)
+}
+
+/// 관리자: 마커 게시 복구
+async fn restore_marker(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<RestoreContentRequest>,
+    member: AuthenticatedMember,
+    req: actix_web::HttpRequest,
+    marker_events: web::Data<MarkerEvents>,
+    cluster_cache: web::Data<ClusterCache>,
+) -> Result<HttpResponse> {
+    let input = payload.into_inner();
+    let marker_id = path.into_inner();
+    let response = handle_content_takedown(&db, &member, &req, ContentTakedownInput {
+        target_type: "marker",
+        target_id: marker_id,
+        action: "restore",
+        reason: input.reason,
+        legal_reference: None,
+        release_legal_hold: input.release_legal_hold.unwrap_or(false),
+    }).await?;
+    if response.status().is_success() {
+        publish_marker_visibility(&db, &marker_events, &cluster_cache, marker_id, MarkerEventKind::Created).await;
+    }
+    Ok(response)
+}
+
+/// 게시 중단/복구된 마커를 실시간 구독자 지도와 클러스터 캐시에서 빼거나 다시 보이게 함
+async fn publish_marker_visibility(db: &Database, marker_events: &MarkerEvents, cluster_cache: &ClusterCache, marker_id: i64, kind: MarkerEventKind) {
     match db.get_marker_detail(marker_id).await {
         Ok(Some(marker)) => {
-            // 마커 이미지 정보도 함께 조회
-            let images = match db.get_marker_images(marker_id as i32).await {
-                Ok(images) => images,
-                Err(e) => {
-                    warn!("⚠️ 마커 이미지 조회 실패: {}", e);
-                    vec![]
-                }
-            };
-            
-            let formatted_images: Vec<serde_json::Value> = images.iter()
-                .map(|image| serde_json::json!({
-                    "id": image.id,
-                    "markerId": image.marker_id,
-                    "imageType": image.image_type,
-                    "imageUrl": image.image_url,
-                    "imageOrder": image.image_order,
-                    "isPrimary": image.is_primary,
-                    "createdAt": image.created_at,
-                    "updatedAt": image.updated_at
-                }))
-                .collect();
-            
-            let marker_data = serde_json::json!({
-                "marker": marker_to_camelcase_json(&marker),
-                "images": formatted_images
-            });
-            
-            // 조회수 증가 (로그인한 사용자인 경우에만)
-            if let Ok(user_id) = extract_user_id_from_token(&req, &config) {
-                // 비동기로 조회수 증가 (응답에 영향 주지 않도록)
-                let db_clone = db.clone();
-                tokio::spawn(async move {
-                    if let Err(e) = db_clone.add_marker_view(user_id, marker_id).await {
-                        error!("❌ 마커 조회수 증가 실패: {}", e);
-                    } else {
-                        info!("👁️ 마커 조회수 증가 완료: 마커 {}, 유저 {}", marker_id, user_id);
-                    }
-                });
-            }
-            
-            Ok(HttpResponse::Ok().json(MarkerResponse {
-                success: true,
-                message: "마커 상세 조회 성공 (조회수 증가됨)".to_string(),
-                data: Some(marker_data),
-            }))
-        }
-        Ok(None) => {
-            Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"))
-        }
-        Err(e) => {
-            error!("❌ 마커 상세 조회 실패: {}", e);
-            Ok(ErrorHandler::internal_server_error(
-                "마커 상세 조회 실패",
-                Some(&format!("데이터베이스 오류: {}", e))
-            ))
+            marker_events.publish(kind, &marker, None);
+            cluster_cache.invalidate_marker(&marker);
         }
+        Ok(None) => {}
+        Err(e) => warn!("⚠️ 마커 {} 실시간 이벤트용 조회 실패: {}", marker_id, e),
     }
 }
 
-#[derive(Deserialize)]
-pub struct ToggleReactionRequest {
-    pub like_type: String, // "like" 또는 "dislike"
+/// 관리자: 마커 이미지 게시 중단 (법적 보존)
+async fn takedown_marker_image(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<TakedownContentRequest>,
+    member: AuthenticatedMember,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse> {
+    let input = payload.into_inner();
+    handle_content_takedown(&db, &member, &req, ContentTakedownInput {
+        target_type: "marker_image",
+        target_id: path.into_inner(),
+        action: "takedown",
+        reason: input.reason,
+        legal_reference: input.legal_reference,
+        release_legal_hold: false,
+    }).await
 }
 
-/// 마커 좋아요/싫어요 통합 토글
-async fn toggle_marker_reaction(
+/// 관리자: 마커 이미지 게시 복구
+async fn restore_marker_image(
     db: web::Data<Database>,
     path: web::Path<i64>,
-    payload: web::Json<ToggleReactionRequest>,
-    config: web::Data<Config>,
+    payload: web::Json<RestoreContentRequest>,
+    member: AuthenticatedMember,
     req: actix_web::HttpRequest,
 ) -> Result<HttpResponse> {
-    let marker_id = path.into_inner();
-    let user_id = extract_user_id_from_token(&req, &config)?;
-    let like_type = &payload.like_type;
-    
-    info!("🚀 API 호출: POST /api/markers/{}/reaction - 유저: {}, 타입: {}", marker_id, user_id, like_type);
+    let input = payload.into_inner();
+    handle_content_takedown(&db, &member, &req, ContentTakedownInput {
+        target_type: "marker_image",
+        target_id: path.into_inner(),
+        action: "restore",
+        reason: input.reason,
+        legal_reference: None,
+        release_legal_hold: input.release_legal_hold.unwrap_or(false),
+    }).await
+}
+
+/// 관리자: 회원 목록 (이메일/로그인 수단/가입일/활성 여부 필터)
+async fn admin_list_members(
+    db: web::Data<Database>,
+    query: web::Query<AdminListMembersQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
     
-    // like_type을 member_markers 테이블의 interaction_type으로 매핑
+    let query = query.into_inner();
+    let mut created_range = [None, None];
+    for (slot, (name, raw)) in created_range.iter_mut().zip([("created_from", &query.created_from), ("created_to", &query.created_to)]) {
+        if let Some(raw) = raw.as_deref().filter(|raw| !raw.is_empty()) {
+            match parse_datetime_param(raw) {
+                Some(at) => *slot = Some(at),
+                None => {
+                    return Ok(ErrorHandler::bad_request(
+                        &format!("{}는 RFC 3339 또는 YYYY-MM-DD 형식이어야 합니다", name),
+                        Some(&format!("{}: {}", name, raw)),
+                        None
+                    ));
+                }
+            }
+        }
+    }
+    let filter = MemberListFilter {
+        email: query.email.map(|email| email.trim().to_string()).filter(|email| !email.is_empty()),
+        provider_type: query.provider_type.map(|provider| provider.trim().to_lowercase()).filter(|provider| !provider.is_empty()),
+        created_from: created_range[0],
+        created_to: created_range[1],
+        is_active: query.is_active,
+        limit: query.limit.unwrap_or(50).clamp(1, 200),
+        offset: query.offset.unwrap_or(0).max(0),
+    };
+    
+    match db.list_members_for_admin(&filter).await {
+        Ok(rows) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": rows.iter().map(AdminMemberDto::from).collect::<Vec<_>>(),
+            "count": rows.len(),
+            "limit": filter.limit,
+            "offset": filter.offset
+        }))),
+        Err(e) => {
+            error!("❌ 관리자 회원 목록 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "회원 목록 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자 회원 조치 공통 처리 (권한 확인 → 적용 + 감사 로그)
+async fn handle_member_moderation(
+    db: &Database,
+    member: &AuthenticatedMember,
+    req: &actix_web::HttpRequest,
+    now: chrono::DateTime<Utc>,
+    target_id: i64,
+    action: &str,
+    reason: Option<String>,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(db, member).await {
+        return Ok(response);
+    }
+    if target_id == member.member_id && action != "reactivate" {
+        return Ok(ErrorHandler::bad_request("본인 계정에는 사용할 수 없습니다.", None, None));
+    }
+    
+    let action = MemberModerationAction {
+        admin_member_id: member.member_id,
+        member_id: target_id,
+        action: action.to_string(),
+        reason: reason.map(|reason| reason.trim().to_string()).filter(|reason| !reason.is_empty()),
+        client_ip: req.connection_info().realip_remote_addr().map(|ip| ip.to_string()),
+        user_agent: req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|ua| ua.to_string()),
+    };
+    match db.apply_member_moderation(&action, now).await {
+        Ok(Some(target)) => {
+            info!("🛡️ 관리자 {} 회원 조치: {} → 회원 {}", member.member_id, action.action, target.id);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": match action.action.as_str() {
+                    "suspend" => "회원을 정지했습니다.",
+                    "reactivate" => "회원 정지를 해제했습니다.",
+                    "force_logout" => "회원의 모든 세션을 로그아웃했습니다.",
+                    _ => "회원 신뢰 등급을 변경했습니다.",
+                },
+                "data": {
+                    "memberId": target.id,
+                    "action": action.action,
+                    "isActive": target.is_active,
+                    "suspendedAt": target.suspended_at,
+                    "suspensionReason": target.suspension_reason,
+                    "trustLevelOverride": target.trust_level_override
+                }
+            })))
+        }
+        Ok(None) => Ok(ErrorHandler::not_found("회원이 존재하지 않습니다.")),
+        Err(e) => {
+            error!("❌ 회원 조치 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "회원 조치 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 회원 신뢰 등급 지정 (level=auto면 가입 기간/활동 이력으로 자동 계산)
+async fn set_member_trust_level(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<TrustLevelRequest>,
+    member: AuthenticatedMember,
+    clock: web::Data<dyn Clock>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse> {
+    let input = payload.into_inner();
+    let level = input.level.trim().to_lowercase();
+    if level != "auto" && TrustLevel::parse(&level).is_none() {
+        return Ok(ErrorHandler::bad_request(
+            "신뢰 등급은 new, basic, trusted, auto 중 하나여야 합니다.",
+            Some(&format!("level: {}", input.level)),
+            None
+        ));
+    }
+    let action = format!("trust_{}", level);
+    handle_member_moderation(&db, &member, &req, clock.now(), path.into_inner(), &action, input.reason).await
+}
+
+/// 내 신뢰 등급과 한도 (최근 24시간 마커 생성 수 포함)
+async fn get_my_trust(
+    db: web::Data<Database>,
+    config: web::Data<Config>,
+    clock: web::Data<dyn Clock>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match load_member_trust(&db, &config, member.member_id, clock.now()).await {
+        Ok(trust) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": trust
+        }))),
+        Err(response) => Ok(response),
+    }
+}
+
+/// 관리자: 회원 정지 (is_active = false, 발급된 토큰 모두 무효화)
+async fn suspend_member(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: Option<web::Json<MemberModerationRequest>>,
+    member: AuthenticatedMember,
+    clock: web::Data<dyn Clock>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse> {
+    let reason = payload.and_then(|payload| payload.into_inner().reason);
+    handle_member_moderation(&db, &member, &req, clock.now(), path.into_inner(), "suspend", reason).await
+}
+
+/// 관리자: 회원 정지 해제 (정지 전에 발급된 토큰은 계속 무효)
+async fn reactivate_member(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: Option<web::Json<MemberModerationRequest>>,
+    member: AuthenticatedMember,
+    clock: web::Data<dyn Clock>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse> {
+    let reason = payload.and_then(|payload| payload.into_inner().reason);
+    handle_member_moderation(&db, &member, &req, clock.now(), path.into_inner(), "reactivate", reason).await
+}
+
+/// 관리자: 강제 로그아웃 (지금까지 발급된 토큰 무효화)
+async fn force_logout_member(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: Option<web::Json<MemberModerationRequest>>,
+    member: AuthenticatedMember,
+    clock: web::Data<dyn Clock>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse> {
+    let reason = payload.and_then(|payload| payload.into_inner().reason);
+    handle_member_moderation(&db, &member, &req, clock.now(), path.into_inner(), "force_logout", reason).await
+}
+
+/// 분석용 마커 내보내기 요청 (관리자 전용, 백그라운드에서 생성 후 다운로드 링크 제공)
+async fn request_marker_export(
+    db: web::Data<Database>,
+    query: web::Query<MarkerExportQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let format = query.format.as_deref().unwrap_or("csv").to_ascii_lowercase();
+    if !marker_export::EXPORT_FORMATS.contains(&format.as_str()) {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 내보내기 형식입니다",
+            Some(&format!("format은 {} 중 하나여야 합니다", marker_export::EXPORT_FORMATS.join(", "))),
+            None
+        ));
+    }
+    let since = match query.since.as_deref().map(parse_datetime_param) {
+        None => None,
+        Some(Some(since)) => Some(since),
+        Some(None) => {
+            return Ok(ErrorHandler::bad_request(
+                "since 형식이 올바르지 않습니다",
+                Some("RFC 3339(2024-01-01T00:00:00Z) 또는 YYYY-MM-DD 형식을 사용하세요"),
+                None
+            ));
+        }
+    };
+    
+    match db.create_marker_export(member.member_id, &format, since).await {
+        Ok(job) => {
+            info!("📦 마커 내보내기 요청: 작업 {} (관리자 {}, {})", job.id, member.member_id, format);
+            Ok(HttpResponse::Accepted().json(serde_json::json!({
+                "success": true,
+                "data": MarkerExportDto::from(&job)
+            })))
+        }
+        Err(e) => {
+            error!("❌ 마커 내보내기 요청 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "마커 내보내기 요청 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 마커 CSV/GeoJSON 내보내기 (기간/지역 조건, DB 커서에서 읽는 대로 바로 응답)
+async fn stream_admin_marker_export(
+    db: web::Data<Database>,
+    query: web::Query<AdminMarkerStreamExportQuery>,
+    member: AuthenticatedMember,
+    clock: web::Data<dyn Clock>,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let format = query.format.as_deref().unwrap_or("csv").trim().to_ascii_lowercase();
+    if !marker_export::STREAM_EXPORT_FORMATS.contains(&format.as_str()) {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 내보내기 형식입니다",
+            Some(&format!("format은 {} 중 하나여야 합니다", marker_export::STREAM_EXPORT_FORMATS.join(", "))),
+            None
+        ));
+    }
+    let mut range = [None, None];
+    for (slot, (name, raw)) in range.iter_mut().zip([("from", &query.from), ("to", &query.to)]) {
+        match raw.as_deref().map(parse_datetime_param) {
+            None => {}
+            Some(Some(value)) => *slot = Some(value),
+            Some(None) => {
+                return Ok(ErrorHandler::bad_request(
+                    &format!("{} 형식이 올바르지 않습니다", name),
+                    Some("RFC 3339(2024-01-01T00:00:00Z) 또는 YYYY-MM-DD 형식을 사용하세요"),
+                    None
+                ));
+            }
+        }
+    }
+    if let [Some(from), Some(to)] = range && from >= to {
+        return Ok(ErrorHandler::bad_request("from은 to보다 앞서야 합니다.", None, None));
+    }
+    let district_code = query.district_code.as_deref().map(str::trim).filter(|code| !code.is_empty());
+    if let Some(code) = district_code && !is_valid_district_code(code) {
+        return Ok(ErrorHandler::bad_request(
+            "행정구역 코드 형식이 올바르지 않습니다.",
+            None,
+            Some(&format!("district_code: {}", code))
+        ));
+    }
+    let filter = AdminMarkerExportFilter {
+        from: range[0],
+        to: range[1],
+        region: query.region.as_deref().map(str::trim).filter(|region| !region.is_empty()).map(str::to_string),
+        district_code: district_code.map(str::to_string),
+    };
+    info!("📤 관리자 마커 내보내기: 관리자 {} ({}, {:?})", member.member_id, format, filter);
+    
+    let chunks = marker_export::stream_admin_export(db.get_ref().clone(), filter, &format);
+    let body = futures_util::stream::unfold(chunks, |mut chunks| async move {
+        let chunk = chunks.recv().await?;
+        Some((chunk.map(web::Bytes::from).map_err(actix_web::error::ErrorInternalServerError), chunks))
+    });
+    let file_name = format!("markers_{}.{}", clock.now().format("%Y%m%d%H%M%S"), format);
+    Ok(HttpResponse::Ok()
+        .content_type(marker_export::content_type(&format))
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", file_name)))
+        .insert_header(("Cache-Control", "no-store"))
+        .streaming(body))
+}
+
+/// 마커 내보내기 작업 상태 (완료되면 다운로드 링크 포함)
+async fn get_marker_export_status(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    match db.get_marker_export(path.into_inner()).await {
+        Ok(Some(job)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": MarkerExportDto::from(&job)
+        }))),
+        Ok(None) => Ok(ErrorHandler::not_found("내보내기 작업을 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 마커 내보내기 상태 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "마커 내보내기 상태 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 완료된 내보내기 파일 다운로드 (저장소를 공개하지 않도록 API를 거쳐 전달)
+async fn download_marker_export(
+    db: web::Data<Database>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let job = match db.get_marker_export(path.into_inner()).await {
+        Ok(Some(job)) => job,
+        Ok(None) => return Ok(ErrorHandler::not_found("내보내기 작업을 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 마커 내보내기 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "마커 내보내기 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    let Some(file_key) = job.file_key.as_deref().filter(|_| job.status == "completed") else {
+        return Ok(ErrorHandler::conflict(
+            "아직 완료되지 않은 내보내기 작업입니다",
+            Some(&format!("현재 상태: {}", job.status))
+        ));
+    };
+    
+    match storage.get(file_key).await {
+        Ok(data) => Ok(HttpResponse::Ok()
+            .content_type(marker_export::content_type(&job.format))
+            .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", marker_export::file_name(&job))))
+            .body(data)),
+        Err(e) => {
+            error!("❌ 내보내기 파일 다운로드 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error("내보내기 파일 다운로드 실패", Some(&e.to_string())))
+        }
+    }
+}
+
+/// 내 데이터 내보내기 (마커/이미지 URL/상호작용)
+/// 마커가 적으면 파일을 바로 내려주고, 많으면 백그라운드 작업을 등록해 202와 상태 조회 링크를 돌려줌
+async fn export_my_data(
+    db: web::Data<Database>,
+    query: web::Query<MemberDataExportQuery>,
+    member: AuthenticatedMember,
+    clock: web::Data<dyn Clock>,
+) -> Result<HttpResponse> {
+    let format = query.format.as_deref().unwrap_or("zip").trim().to_ascii_lowercase();
+    if !member_export::MEMBER_EXPORT_FORMATS.contains(&format.as_str()) {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 내보내기 형식입니다",
+            Some(&format!("format은 {} 중 하나여야 합니다", member_export::MEMBER_EXPORT_FORMATS.join(", "))),
+            None
+        ));
+    }
+    
+    let marker_count = match db.count_member_export_markers(member.member_id).await {
+        Ok(count) => count,
+        Err(e) => {
+            error!("❌ 내 데이터 내보내기 마커 수 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "내 데이터 내보내기 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    
+    if marker_count <= member_export::INLINE_EXPORT_MAX_MARKERS {
+        let now = clock.now();
+        return match member_export::export_member_data(&db, member.member_id, &format, now).await {
+            Ok((data, _)) => Ok(HttpResponse::Ok()
+                .content_type(member_export::content_type(&format))
+                .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", member_export::file_name(member.member_id, &format, now))))
+                .insert_header(("Cache-Control", "no-store"))
+                .body(data)),
+            Err(e) => {
+                error!("❌ 내 데이터 내보내기 실패: 회원 {} - {}", member.member_id, e);
+                Ok(ErrorHandler::internal_server_error("내 데이터 내보내기 실패", Some(&e.to_string())))
+            }
+        };
+    }
+    
+    match db.create_member_data_export(member.member_id, &format).await {
+        Ok((job, created)) => {
+            if created {
+                info!("📦 개인 데이터 내보내기 요청: 작업 {} (회원 {}, 마커 {}개, {})", job.id, member.member_id, marker_count, format);
+            }
+            Ok(HttpResponse::Accepted().json(serde_json::json!({
+                "success": true,
+                "message": if created { "내보낼 데이터가 많아 파일을 준비하고 있습니다" } else { "이미 준비 중인 내보내기가 있습니다" },
+                "data": MemberDataExportDto::from(&job)
+            })))
+        }
+        Err(e) => {
+            error!("❌ 개인 데이터 내보내기 요청 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "내 데이터 내보내기 요청 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 내 내보내기 작업 (다른 회원의 작업은 없는 것으로 처리)
+async fn load_own_data_export(db: &Database, member: &AuthenticatedMember, job_id: i64) -> std::result::Result<MemberDataExport, HttpResponse> {
+    match db.get_member_data_export(job_id).await {
+        Ok(Some(job)) if job.member_id == member.member_id => Ok(job),
+        Ok(_) => Err(ErrorHandler::not_found("내보내기 작업을 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 개인 데이터 내보내기 조회 실패: {}", e);
+            Err(ErrorHandler::internal_server_error(
+                "내보내기 작업 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 내 데이터 내보내기 작업 상태 (완료되면 다운로드 링크 포함)
+async fn get_my_data_export(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match load_own_data_export(&db, &member, path.into_inner()).await {
+        Ok(job) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": MemberDataExportDto::from(&job)
+        }))),
+        Err(response) => Ok(response),
+    }
+}
+
+/// 완료된 내 데이터 내보내기 파일 다운로드
+async fn download_my_data_export(
+    db: web::Data<Database>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let job = match load_own_data_export(&db, &member, path.into_inner()).await {
+        Ok(job) => job,
+        Err(response) => return Ok(response),
+    };
+    let Some(file_key) = job.file_key.as_deref().filter(|_| job.status == "completed") else {
+        return Ok(ErrorHandler::conflict(
+            "아직 완료되지 않은 내보내기 작업입니다",
+            Some(&format!("현재 상태: {}", job.status))
+        ));
+    };
+    
+    match storage.get(file_key).await {
+        Ok(data) => Ok(HttpResponse::Ok()
+            .content_type(member_export::content_type(&job.format))
+            .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", member_export::file_name(job.member_id, &job.format, job.created_at))))
+            .insert_header(("Cache-Control", "no-store"))
+            .body(data)),
+        Err(e) => {
+            error!("❌ 개인 데이터 내보내기 파일 다운로드 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error("내보내기 파일 다운로드 실패", Some(&e.to_string())))
+        }
+    }
+}
+
+const MARKER_CLAIM_STATUSES: [&str; 3] = ["pending", "approved", "rejected"];
+const MAX_BUSINESS_NAME_CHARS: usize = 200;
+const MAX_BUSINESS_NUMBER_CHARS: usize = 50;
+
+/// 인증 서류 형식 (파일 내용으로 판별, 확장자/Content-Type)
+fn claim_document_type(data: &[u8]) -> Option<(&'static str, &'static str)> {
+    if data.starts_with(b"%PDF-") {
+        Some(("pdf", "application/pdf"))
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(("jpg", "image/jpeg"))
+    } else if data.starts_with(&[0x89, b'P', b'N', b'G']) {
+        Some(("png", "image/png"))
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some(("webp", "image/webp"))
+    } else {
+        None
+    }
+}
+
+fn claim_document_content_type(document_key: &str) -> &'static str {
+    match document_key.rsplit('.').next() {
+        Some("pdf") => "application/pdf",
+        Some("jpg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 마커 상세에 붙는 인증 배지 (승인된 소유자가 없으면 null)
+async fn verified_owner_json(db: &Database, marker_id: i64) -> serde_json::Value {
+    match db.get_marker_verified_owner(marker_id).await {
+        Ok(Some(claim)) => serde_json::json!({
+            "memberId": claim.member_id,
+            "businessName": claim.business_name,
+            "verifiedAt": claim.reviewed_at
+        }),
+        Ok(None) => serde_json::Value::Null,
+        Err(e) => {
+            warn!("⚠️ 마커 인증 소유자 조회 실패: {}", e);
+            serde_json::Value::Null
+        }
+    }
+}
+
+/// 요청한 회원의 마커별 반응/북마크 상태 (비로그인이거나 조회 실패 시 빈 목록)
+async fn viewer_marker_states(db: &Database, member: Option<&AuthenticatedMember>, marker_ids: &[i64]) -> std::collections::HashMap<i64, MemberMarkerState> {
+    let Some(member) = member else {
+        return std::collections::HashMap::new();
+    };
+    match db.get_member_marker_states(member.member_id, marker_ids).await {
+        Ok(states) => states,
+        Err(e) => {
+            warn!("⚠️ 회원 {} 마커 반응 상태 조회 실패: {}", member.member_id, e);
+            std::collections::HashMap::new()
+        }
+    }
+}
+
+/// 마커 JSON에 요청한 회원의 isLiked/isDisliked/isBookmarked 추가
+fn apply_viewer_marker_state(marker_data: &mut serde_json::Value, state: Option<&MemberMarkerState>) {
+    let state = state.copied().unwrap_or_default();
+    marker_data["isLiked"] = serde_json::json!(state.is_liked);
+    marker_data["isDisliked"] = serde_json::json!(state.is_disliked);
+    marker_data["isBookmarked"] = serde_json::json!(state.is_bookmarked);
+}
+
+/// 마커 해시태그 (상세 응답용, 조회 실패 시 빈 배열)
+async fn marker_tags_json(db: &Database, marker_id: i64) -> serde_json::Value {
+    match db.get_marker_tags(marker_id).await {
+        Ok(tags) => serde_json::json!(tags),
+        Err(e) => {
+            warn!("⚠️ 마커 해시태그 조회 실패: {}", e);
+            serde_json::json!([])
+        }
+    }
+}
+
+/// 마커 목록/피드의 tag 쿼리 파라미터 (비어 있으면 필터 없음, 형식이 틀리면 받은 값을 Err로)
+fn parse_hashtag_filter(raw: Option<&str>) -> std::result::Result<Option<String>, String> {
+    match raw.map(str::trim).filter(|raw| !raw.is_empty()) {
+        None => Ok(None),
+        Some(raw) => normalize_tag(raw).map(Some).ok_or_else(|| format!("tag: {}", raw)),
+    }
+}
+
+/// 검색어 최대 길이
+const MAX_SEARCH_QUERY_CHARS: usize = 100;
+
+/// 검색 결과 (마커와 Postgres 검색 순위, 전체 개수, 검색 엔진 이름)
+type MarkerSearchResults<'a> = (Vec<(Marker, Option<f32>)>, i64, &'a str);
+
+/// 마커 전문 검색 (공개 마커, 검색 순위 → 최신 순, 지도 영역 제한 가능, 외부 검색 색인이 있으면 그쪽 순위)
+async fn search_markers(
+    db: web::Data<Database>,
+    query: web::Query<MarkerSearchQuery>,
+    search_index: web::Data<SearchIndex>,
+) -> Result<HttpResponse> {
+    let q = query.q.as_deref().map(str::trim).unwrap_or_default();
+    if q.chars().count() > MAX_SEARCH_QUERY_CHARS {
+        return Ok(ErrorHandler::bad_request(
+            &format!("검색어는 {}자 이하로 입력해주세요.", MAX_SEARCH_QUERY_CHARS),
+            None,
+            Some("마커 검색")
+        ));
+    }
+    let Some(tsquery) = marker_search_tsquery(q) else {
+        return Ok(ErrorHandler::bad_request("검색어(q)가 필요합니다.", Some("글자나 숫자가 한 개 이상 있어야 합니다."), Some("마커 검색")));
+    };
+    
+    let envelope = match (query.lat_min, query.lat_max, query.lng_min, query.lng_max) {
+        (None, None, None, None) => None,
+        (Some(lat_min), Some(lat_max), Some(lng_min), Some(lng_max))
+            if (-90.0..=90.0).contains(&lat_min)
+                && (-90.0..=90.0).contains(&lat_max)
+                && (-180.0..=180.0).contains(&lng_min)
+                && (-180.0..=180.0).contains(&lng_max)
+                && lat_min <= lat_max
+                && lng_min <= lng_max => Some((lng_min, lat_min, lng_max, lat_max)),
+        _ => {
+            return Ok(ErrorHandler::bad_request(
+                "검색 영역이 올바르지 않습니다.",
+                Some("lat_min, lat_max, lng_min, lng_max를 모두 전달해야 합니다."),
+                Some("마커 검색")
+            ));
+        }
+    };
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 50);
+    
+    info!("🔎 마커 검색: q={:?}, 영역={:?}, 페이지 {}", q, envelope, page);
+    
+    // 외부 검색 색인이 있으면 먼저 사용 (오타 허용, 해시태그/닉네임), 실패하면 Postgres 전문 검색
+    let mut results: Option<MarkerSearchResults> = None;
+    if let Some(backend) = search_index.backend() {
+        let request = SearchRequest { q: q.to_string(), envelope, offset: i64::from((page - 1) * limit), limit: i64::from(limit) };
+        match backend.search(&request).await {
+            Ok(hits) => match db.get_public_markers_by_ids(&hits.ids).await {
+                Ok(markers) => {
+                    results = Some((markers.into_iter().map(|marker| (marker, None)).collect(), hits.total, backend.name()));
+                }
+                Err(e) => warn!("⚠️ 외부 검색 결과 마커 조회 실패, Postgres 전문 검색으로 대신함: {}", e),
+            },
+            Err(e) => warn!("⚠️ 외부 검색 실패, Postgres 전문 검색으로 대신함: {}", e),
+        }
+    }
+    let (markers, total_count, engine) = match results {
+        Some(results) => results,
+        None => match db.search_markers(&tsquery, envelope, page, limit).await {
+            Ok((hits, total_count)) => (hits.into_iter().map(|hit| (hit.marker, Some(hit.rank))).collect(), total_count, "postgres"),
+            Err(e) => {
+                error!("❌ 마커 검색 실패: {}", e);
+                return Ok(ErrorHandler::internal_server_error(
+                    "마커 검색 실패",
+                    Some(&format!("데이터베이스 오류: {}", e))
+                ));
+            }
+        },
+    };
+    
+    let mut formatted_markers = Vec::with_capacity(markers.len());
+    for (marker, rank) in &markers {
+        let images = match db.get_marker_images(marker.id).await {
+            Ok(images) => images,
+            Err(e) => {
+                warn!("⚠️ 마커 {} 이미지 조회 실패: {}", marker.id, e);
+                vec![]
+            }
+        };
+        let formatted_images: Vec<MarkerImageDto> = images.iter().map(MarkerImageDto::from).collect();
+        let mut marker_data = serde_json::json!(MarkerDto::from(marker).with_images(formatted_images));
+        if let Some(rank) = rank {
+            marker_data["searchRank"] = serde_json::json!(rank);
+        }
+        formatted_markers.push(marker_data);
+    }
+    
+    let total_pages = (total_count as f64 / limit as f64).ceil() as i32;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": formatted_markers,
+        "pagination": {
+            "currentPage": page,
+            "totalPages": total_pages,
+            "totalCount": total_count,
+            "limit": limit,
+            "hasNext": page < total_pages,
+            "hasPrev": page > 1
+        },
+        "count": markers.len(),
+        "engine": engine
+    })))
+}
+
+/// 인기 해시태그 (최근 공개 마커에 많이 붙은 순, 직전 구간 대비 증감 포함)
+async fn get_trending_tags(
+    db: web::Data<Database>,
+    query: web::Query<TrendingTagsQuery>,
+    clock: web::Data<dyn Clock>,
+) -> Result<HttpResponse> {
+    let hours = query.hours.unwrap_or(24).clamp(1, 24 * 30);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let now = clock.now();
+    let since = now - chrono::Duration::hours(hours);
+    
+    match db.get_trending_tags(since, since - chrono::Duration::hours(hours), limit).await {
+        Ok(tags) => Ok(HttpResponse::Ok()
+            .insert_header(("Cache-Control", "public, max-age=300"))
+            .json(serde_json::json!({
+                "success": true,
+                "data": tags.iter().map(|tag| serde_json::json!({
+                    "tag": tag.tag,
+                    "markerCount": tag.marker_count,
+                    "previousCount": tag.previous_count,
+                    "change": tag.marker_count - tag.previous_count,
+                    "lastUsedAt": tag.last_used_at
+                })).collect::<Vec<_>>(),
+                "hours": hours,
+                "since": since
+            }))),
+        Err(e) => {
+            error!("❌ 인기 해시태그 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "인기 해시태그 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 사업장 소유 인증 신청 (multipart: business_name, business_registration_number, contact_phone, document)
+async fn submit_marker_claim(
+    db: web::Data<Database>,
+    s3_service: web::Data<S3Service>,
+    config: web::Data<Config>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+    mut payload: Multipart,
+) -> Result<HttpResponse> {
+    let marker_id = path.into_inner();
+    
+    match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) if !is_marker_hidden(&db, &marker, Some(&member)).await => {}
+        Ok(_) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 인증 신청용 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    
+    match db.get_marker_verified_owner(marker_id).await {
+        Ok(None) => {}
+        Ok(Some(_)) => return Ok(ErrorHandler::conflict("이미 인증된 소유자가 있는 마커입니다", None)),
+        Err(e) => {
+            error!("❌ 마커 인증 소유자 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "인증 신청 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    
+    let mut business_name = String::new();
+    let mut business_number = String::new();
+    let mut contact_phone = String::new();
+    let mut document = Vec::new();
+    while let Some(Ok(mut field)) = payload.next().await {
+        let name = field.content_disposition().get_name().unwrap_or_default().to_string();
+        let data = match receive_field(&mut field, &config).await {
+            Ok(data) => data,
+            Err(e) => return Ok(e.into_response()),
+        };
+        match name.as_str() {
+            "business_name" => business_name = String::from_utf8_lossy(&data).trim().to_string(),
+            "business_registration_number" => business_number = String::from_utf8_lossy(&data).trim().to_string(),
+            "contact_phone" => contact_phone = String::from_utf8_lossy(&data).trim().to_string(),
+            "document" => document = data,
+            _ => {}
+        }
+    }
+    
+    if business_name.is_empty() || business_name.chars().count() > MAX_BUSINESS_NAME_CHARS {
+        return Ok(ErrorHandler::bad_request(
+            "상호명이 올바르지 않습니다",
+            Some(&format!("1~{}자로 입력해주세요", MAX_BUSINESS_NAME_CHARS)),
+            None
+        ));
+    }
+    if business_number.is_empty()
+        || business_number.chars().count() > MAX_BUSINESS_NUMBER_CHARS
+        || !business_number.chars().all(|c| c.is_ascii_digit() || c == '-')
+    {
+        return Ok(ErrorHandler::bad_request(
+            "사업자등록번호가 올바르지 않습니다",
+            Some("숫자와 '-'만 입력해주세요"),
+            None
+        ));
+    }
+    if document.is_empty() {
+        return Ok(ErrorHandler::bad_request("증빙 서류(document) 파일이 필요합니다", None, None));
+    }
+    let size_mb = document.len() as f64 / (1024.0 * 1024.0);
+    if size_mb > config.max_file_size_mb {
+        return Ok(ErrorHandler::bad_request(
+            "증빙 서류가 너무 큽니다",
+            Some(&format!("최대 {:.0}MB (현재: {:.2}MB)", config.max_file_size_mb, size_mb)),
+            None
+        ));
+    }
+    let Some((extension, content_type)) = claim_document_type(&document) else {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 서류 형식입니다",
+            Some("PDF, JPG, PNG, WEBP 파일만 가능합니다"),
+            None
+        ));
+    };
+    
+    // 서류를 올리기 전에 중복 신청 확인 (고아 파일 방지)
+    match db.get_member_marker_claims(member.member_id).await {
+        Ok(claims) if claims.iter().any(|c| c.marker_id == marker_id && c.status == "pending") => {
+            return Ok(ErrorHandler::conflict("이미 검토 중인 신청이 있습니다", None));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("❌ 인증 신청 내역 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "인증 신청 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    
+    let document_key = match s3_service.upload_claim_document(document, marker_id, extension, content_type).await {
+        Ok(key) => key,
+        Err(e) => {
+            error!("❌ 인증 서류 업로드 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error("인증 서류 업로드 실패", Some(&e.to_string())));
+        }
+    };
+    
+    let contact_phone = Some(contact_phone.as_str()).filter(|phone| !phone.is_empty());
+    match db.create_marker_claim(marker_id, member.member_id, &business_name, &business_number, contact_phone, &document_key).await {
+        Ok(Some(claim)) => {
+            info!("🏪 마커 소유 인증 신청: 마커 {} / 회원 {} (신청 {})", marker_id, member.member_id, claim.id);
+            Ok(HttpResponse::Created().json(serde_json::json!({
+                "success": true,
+                "message": "인증 신청이 접수되었습니다. 관리자 검토 후 결과를 알려드립니다.",
+                "data": MarkerClaimDto::from(&claim)
+            })))
+        }
+        Ok(None) => Ok(ErrorHandler::conflict("이미 검토 중인 신청이 있습니다", None)),
+        Err(e) => {
+            error!("❌ 인증 신청 저장 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "인증 신청 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 내 사업장 인증 신청 내역
+async fn get_my_marker_claims(
+    db: web::Data<Database>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match db.get_member_marker_claims(member.member_id).await {
+        Ok(claims) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": claims.iter().map(MarkerClaimDto::from).collect::<Vec<_>>()
+        }))),
+        Err(e) => {
+            error!("❌ 인증 신청 내역 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "인증 신청 내역 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 내 SNS 연결 목록 (토큰은 내려주지 않음)
+async fn get_my_connected_accounts(
+    db: web::Data<Database>,
+    providers: web::Data<CrossPostProviders>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match db.list_connected_accounts(member.member_id).await {
+        Ok(accounts) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": accounts.iter().map(ConnectedAccountDto::from).collect::<Vec<_>>(),
+            "platforms": providers.platforms()
+        }))),
+        Err(e) => {
+            error!("❌ SNS 연결 목록 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "SNS 연결 목록 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// SNS 계정 연결 (앱에서 받은 OAuth 토큰을 확인 후 암호화해 저장)
+async fn connect_account(
+    db: web::Data<Database>,
+    providers: web::Data<CrossPostProviders>,
+    cipher: web::Data<TokenCipher>,
+    clock: web::Data<dyn Clock>,
+    payload: web::Json<ConnectAccountRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let input = payload.into_inner();
+    let Some(provider) = providers.get(&input.platform) else {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 플랫폼입니다",
+            Some(&format!("platform: {} ({})", input.platform, providers.platforms().join(", "))),
+            None
+        ));
+    };
+    let access_token = input.access_token.trim();
+    if access_token.is_empty() {
+        return Ok(ErrorHandler::bad_request("access_token이 필요합니다.", None, None));
+    }
+    
+    let remote = match provider.verify_account(access_token).await {
+        Ok(remote) => remote,
+        Err(e) => {
+            return Ok(ErrorHandler::unauthorized(
+                "SNS 계정 토큰을 확인할 수 없습니다.",
+                Some(&format!("{}: {}", input.platform, e))
+            ));
+        }
+    };
+    
+    let encrypted = cipher.encrypt(access_token).and_then(|access| {
+        let refresh = input.refresh_token.as_deref()
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(|token| cipher.encrypt(token))
+            .transpose()?;
+        Ok((access, refresh))
+    });
+    let (access_token_encrypted, refresh_token_encrypted) = match encrypted {
+        Ok(encrypted) => encrypted,
+        Err(e) => {
+            return Ok(ErrorHandler::internal_server_error("토큰 암호화 실패", Some(&e.to_string())));
+        }
+    };
+    let account = NewConnectedAccount {
+        platform: provider.platform().to_string(),
+        remote_user_id: remote.id,
+        remote_username: remote.username,
+        access_token_encrypted,
+        refresh_token_encrypted,
+        token_expires_at: input.expires_in.filter(|secs| *secs > 0).map(|secs| clock.now() + chrono::Duration::seconds(secs)),
+    };
+    match db.upsert_connected_account(member.member_id, &account).await {
+        Ok(connected) => {
+            info!("🔗 SNS 계정 연결: 회원 {} → {} ({})", member.member_id, connected.platform, connected.remote_user_id);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": ConnectedAccountDto::from(&connected)
+            })))
+        }
+        Err(e) => {
+            error!("❌ SNS 계정 연결 저장 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "SNS 계정 연결 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// SNS 연결 해제 (저장된 토큰 삭제)
+async fn disconnect_account(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let platform = path.into_inner();
+    match db.delete_connected_account(member.member_id, &platform).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "SNS 연결이 해제되었습니다."
+        }))),
+        Ok(false) => Ok(ErrorHandler::not_found("연결된 SNS 계정이 없습니다")),
+        Err(e) => {
+            error!("❌ SNS 연결 해제 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "SNS 연결 해제 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 크로스포스팅 핸들러가 쓰는 앱 상태 묶음 (등록된 web::Data에서 꺼냄)
+struct CrosspostServices {
+    config: web::Data<Config>,
+    s3_service: web::Data<S3Service>,
+    providers: web::Data<CrossPostProviders>,
+    cipher: web::Data<TokenCipher>,
+    clock: web::Data<dyn Clock>,
+}
+
+impl actix_web::FromRequest for CrosspostServices {
+    type Error = actix_web::Error;
+    type Future = std::future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &actix_web::HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let services = (|| Some(Self {
+            config: req.app_data::<web::Data<Config>>()?.clone(),
+            s3_service: req.app_data::<web::Data<S3Service>>()?.clone(),
+            providers: req.app_data::<web::Data<CrossPostProviders>>()?.clone(),
+            cipher: req.app_data::<web::Data<TokenCipher>>()?.clone(),
+            clock: req.app_data::<web::Data<dyn Clock>>()?.clone(),
+        }))();
+        std::future::ready(services.ok_or_else(|| {
+            actix_web::error::ErrorInternalServerError("크로스포스팅 서비스가 등록되지 않았습니다")
+        }))
+    }
+}
+
+/// 내 마커를 연결한 SNS에 올리기 (대표 사진 + 캡션, 플랫폼별 한 번)
+async fn crosspost_marker(
+    db: web::Data<Database>,
+    services: CrosspostServices,
+    path: web::Path<i64>,
+    payload: web::Json<CrosspostRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let CrosspostServices { config, s3_service, providers, cipher, clock } = services;
+    let marker_id = path.into_inner();
+    let input = payload.into_inner();
+    let Some(provider) = providers.get(&input.platform) else {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 플랫폼입니다",
+            Some(&format!("platform: {} ({})", input.platform, providers.platforms().join(", "))),
+            None
+        ));
+    };
+    
+    let marker = match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) if marker.taken_down_at.is_none() => marker,
+        Ok(_) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    if marker.member_id != Some(member.member_id) {
+        return Ok(ErrorHandler::forbidden(
+            "본인이 작성한 마커만 SNS에 올릴 수 있습니다.",
+            Some(&format!("마커 {} 작성자 {:?}, 요청자 {}", marker_id, marker.member_id, member.member_id))
+        ));
+    }
+    
+    match db.get_marker_crosspost(marker_id, provider.platform()).await {
+        Ok(Some(existing)) => {
+            return Ok(ErrorHandler::conflict(
+                "이미 이 플랫폼에 올린 마커입니다.",
+                Some(&format!("{} 게시물 {}", existing.platform, existing.remote_post_id))
+            ));
+        }
+        Ok(None) => {}
+        Err(e) => {
+            return Ok(ErrorHandler::internal_server_error(
+                "크로스포스팅 기록 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    
+    let account = match db.get_connected_account(member.member_id, provider.platform()).await {
+        Ok(Some(account)) => account,
+        Ok(None) => {
+            return Ok(ErrorHandler::bad_request(
+                "연결된 SNS 계정이 없습니다. 먼저 계정을 연결해 주세요.",
+                Some(&format!("platform: {}", provider.platform())),
+                None
+            ));
+        }
+        Err(e) => {
+            return Ok(ErrorHandler::internal_server_error(
+                "SNS 연결 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    if account.token_expires_at.is_some_and(|expires_at| expires_at <= clock.now()) {
+        return Ok(ErrorHandler::unauthorized("SNS 계정 토큰이 만료되었습니다. 다시 연결해 주세요.", None));
+    }
+    let access_token = match cipher.decrypt(&account.access_token_encrypted) {
+        Ok(token) => token,
+        Err(e) => {
+            return Ok(ErrorHandler::internal_server_error("SNS 토큰 복호화 실패", Some(&e.to_string())));
+        }
+    };
+    
+    // 대표 이미지 (없으면 첫 이미지, 그것도 없으면 썸네일)
+    let images = db.get_marker_images(marker_id).await.unwrap_or_default();
+    let image_url = images.iter().find(|image| image.is_primary)
+        .or(images.first())
+        .map(|image| image.image_url.clone())
+        .or(marker.thumbnail_img.clone().filter(|url| !url.is_empty()));
+    let Some(image_url) = image_url else {
+        return Ok(ErrorHandler::bad_request("사진이 있는 마커만 SNS에 올릴 수 있습니다.", None, None));
+    };
+    let image = match load_stored_image(&image_url, &config, &s3_service).await {
+        Ok(image) => image,
+        Err(e) => {
+            return Ok(ErrorHandler::internal_server_error("마커 사진 읽기 실패", Some(&e.to_string())));
+        }
+    };
+    let post = CrossPost {
+        image_url: public_image_url(&image_url, &config, &s3_service),
+        image,
+        caption: build_caption(input.caption.as_deref(), marker.description.as_deref(), marker.emotion_tag_input.as_deref()),
+    };
+    
+    let published = match provider.publish(&access_token, &account.remote_user_id, &post).await {
+        Ok(published) => published,
+        Err(e) => {
+            return Ok(ErrorHandler::bad_gateway(
+                "SNS 게시에 실패했습니다.",
+                Some(&format!("{}: {}", provider.platform(), e))
+            ));
+        }
+    };
+    
+    match db.create_marker_crosspost(marker_id, member.member_id, provider.platform(), &published.remote_post_id, published.remote_url.as_deref()).await {
+        Ok(crosspost) => {
+            info!("📤 마커 {} → {} 게시 완료: {}", marker_id, provider.platform(), published.remote_post_id);
+            Ok(HttpResponse::Created().json(serde_json::json!({
+                "success": true,
+                "data": MarkerCrosspostDto {
+                    marker_id,
+                    platform: provider.platform().to_string(),
+                    remote_post_id: published.remote_post_id,
+                    remote_url: published.remote_url,
+                    created_at: crosspost.map(|c| c.created_at),
+                }
+            })))
+        }
+        Err(e) => {
+            // 게시는 이미 됐으므로 원격 ID를 로그에 남겨 수동으로 맞출 수 있게 함
+            error!("❌ 크로스포스팅 기록 저장 실패 (마커 {}, {} 게시물 {}): {}", marker_id, provider.platform(), published.remote_post_id, e);
+            Ok(ErrorHandler::internal_server_error(
+                "크로스포스팅 기록 저장 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 사업장 인증 신청 목록
+async fn list_marker_claims(
+    db: web::Data<Database>,
+    query: web::Query<MarkerClaimListQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let status = match query.status.as_deref().unwrap_or("pending") {
+        "all" => None,
+        status if MARKER_CLAIM_STATUSES.contains(&status) => Some(status),
+        other => {
+            return Ok(ErrorHandler::bad_request(
+                "지원하지 않는 신청 상태입니다",
+                Some(&format!("status: {} (pending, approved, rejected, all)", other)),
+                None
+            ));
+        }
+    };
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    
+    match db.list_marker_claims(status, limit).await {
+        Ok(claims) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": claims.iter().map(AdminMarkerClaimDto::from).collect::<Vec<_>>()
+        }))),
+        Err(e) => {
+            error!("❌ 인증 신청 목록 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "인증 신청 목록 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 사업장 인증 승인 (마커에 인증 배지)
+async fn approve_marker_claim(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: Option<web::Json<ReviewMarkerClaimRequest>>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let note = payload.and_then(|p| p.into_inner().note);
+    review_marker_claim(&db, &member, path.into_inner(), true, note).await
+}
+
+/// 관리자: 사업장 인증 반려
+async fn reject_marker_claim(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: Option<web::Json<ReviewMarkerClaimRequest>>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let note = payload.and_then(|p| p.into_inner().note);
+    review_marker_claim(&db, &member, path.into_inner(), false, note).await
+}
+
+async fn review_marker_claim(
+    db: &Database,
+    member: &AuthenticatedMember,
+    claim_id: i64,
+    approve: bool,
+    note: Option<String>,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(db, member).await {
+        return Ok(response);
+    }
+    
+    let claim = match db.get_marker_claim(claim_id).await {
+        Ok(Some(claim)) => claim,
+        Ok(None) => return Ok(ErrorHandler::not_found("인증 신청을 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 인증 신청 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "인증 신청 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    if claim.status != "pending" {
+        return Ok(ErrorHandler::conflict(
+            "이미 검토된 신청입니다",
+            Some(&format!("현재 상태: {}", claim.status))
+        ));
+    }
+    if approve {
+        match db.get_marker_verified_owner(claim.marker_id).await {
+            Ok(None) => {}
+            Ok(Some(owner)) => {
+                return Ok(ErrorHandler::conflict(
+                    "이미 인증된 소유자가 있는 마커입니다",
+                    Some(&format!("승인된 신청: {}", owner.id))
+                ));
+            }
+            Err(e) => {
+                error!("❌ 마커 인증 소유자 조회 실패: {}", e);
+                return Ok(ErrorHandler::internal_server_error(
+                    "인증 신청 검토 실패",
+                    Some(&format!("데이터베이스 오류: {}", e))
+                ));
+            }
+        }
+    }
+    
+    let note = note.as_deref().map(str::trim).filter(|note| !note.is_empty());
+    match db.review_marker_claim(claim_id, member.member_id, approve, note).await {
+        Ok(Some(claim)) => {
+            info!("🏪 마커 소유 인증 {}: 신청 {} (마커 {}, 관리자 {})",
+                if approve { "승인" } else { "반려" }, claim.id, claim.marker_id, member.member_id);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": MarkerClaimDto::from(&claim)
+            })))
+        }
+        Ok(None) => Ok(ErrorHandler::conflict("이미 검토된 신청입니다", None)),
+        Err(e) => {
+            error!("❌ 인증 신청 검토 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "인증 신청 검토 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 인증 서류 열람
+async fn download_marker_claim_document(
+    db: web::Data<Database>,
+    s3_service: web::Data<S3Service>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let claim = match db.get_marker_claim(path.into_inner()).await {
+        Ok(Some(claim)) => claim,
+        Ok(None) => return Ok(ErrorHandler::not_found("인증 신청을 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 인증 신청 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "인증 신청 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    
+    match s3_service.get_file(&claim.document_key).await {
+        Ok(data) => Ok(HttpResponse::Ok()
+            .content_type(claim_document_content_type(&claim.document_key))
+            .insert_header(("Cache-Control", "private, no-store"))
+            .body(data)),
+        Err(e) => {
+            error!("❌ 인증 서류 다운로드 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error("인증 서류 다운로드 실패", Some(&e.to_string())))
+        }
+    }
+}
+
+/// 관리자: 기존 마커 주소 백필 진행 상황
+async fn get_geocode_backfill_progress(
+    db: web::Data<Database>,
+    config: web::Data<Config>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    match db.get_geocode_backfill_progress().await {
+        Ok(progress) => {
+            let percent = if progress.total_markers > 0 {
+                (progress.geocoded as f64 * 1000.0 / progress.total_markers as f64).round() / 10.0
+            } else {
+                100.0
+            };
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": {
+                    "enabled": !config.kakao_rest_api_key.is_empty(),
+                    "totalMarkers": progress.total_markers,
+                    "geocoded": progress.geocoded,
+                    "withAddress": progress.with_address,
+                    "pending": progress.pending,
+                    "percent": percent
+                }
+            })))
+        }
+        Err(e) => {
+            error!("❌ 주소 백필 진행 상황 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "주소 백필 진행 상황 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 노출할 프로모션 마커 JSON (조회 실패 시 프로모션 없이 응답)
+async fn load_promoted_markers(
+    db: &Database,
+    now: chrono::DateTime<Utc>,
+    point: Option<(f64, f64)>,
+    envelope: Option<(f64, f64, f64, f64)>,
+    limit: i64,
+) -> Vec<serde_json::Value> {
+    let promoted = match db.get_promoted_markers(now, point, envelope, limit).await {
+        Ok(promoted) => promoted,
+        Err(e) => {
+            warn!("⚠️ 프로모션 마커 조회 실패: {}", e);
+            return vec![];
+        }
+    };
+    let mut markers = Vec::with_capacity(promoted.len());
+    for (promotion, marker) in promoted {
+        let images = db.get_marker_images(marker.id).await.unwrap_or_default();
+        let marker_data = MarkerDto::from(&marker).with_images(images.iter().map(MarkerImageDto::from).collect());
+        markers.push(promotions::promoted_marker_json(&promotion, serde_json::json!(marker_data)));
+    }
+    markers
+}
+
+/// 관리자: 프로모션 마커 등록
+async fn create_marker_promotion(
+    db: web::Data<Database>,
+    payload: web::Json<CreateMarkerPromotionRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let input = payload.into_inner();
+    let sponsor_name = input.sponsor_name.trim().to_string();
+    if sponsor_name.is_empty() || sponsor_name.chars().count() > 100 {
+        return Ok(ErrorHandler::bad_request("광고주 이름은 1~100자여야 합니다.", None, None));
+    }
+    if input.ends_at <= input.starts_at {
+        return Ok(ErrorHandler::bad_request(
+            "종료 시각은 시작 시각 이후여야 합니다.",
+            None,
+            Some(&format!("starts_at: {}, ends_at: {}", input.starts_at, input.ends_at))
+        ));
+    }
+    if input.impression_cap.is_some_and(|cap| cap <= 0) {
+        return Ok(ErrorHandler::bad_request("노출 한도는 1 이상이어야 합니다.", None, None));
+    }
+    
+    match db.get_marker_detail(input.marker_id).await {
+        Ok(Some(marker)) if marker.taken_down_at.is_none() && marker.sharing_option.as_deref().unwrap_or("public") == "public" => {}
+        Ok(Some(_)) => {
+            return Ok(ErrorHandler::bad_request("공개 상태의 마커만 프로모션할 수 있습니다.", None, None));
+        }
+        Ok(None) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 프로모션 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    
+    // 대상 행정구역은 등록된 경계가 있는 코드만 허용 (빈 목록은 전국)
+    let district_codes = input.district_codes
+        .map(|codes| codes.into_iter().map(|code| code.trim().to_string()).filter(|code| !code.is_empty()).collect::<Vec<_>>())
+        .filter(|codes| !codes.is_empty());
+    for code in district_codes.iter().flatten() {
+        match db.get_district(code).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Ok(ErrorHandler::bad_request(
+                    "등록되지 않은 행정구역 코드입니다.",
+                    None,
+                    Some(&format!("district_code: {}", code))
+                ));
+            }
+            Err(e) => {
+                return Ok(ErrorHandler::internal_server_error(
+                    "행정구역 조회 실패",
+                    Some(&format!("데이터베이스 오류: {}", e))
+                ));
+            }
+        }
+    }
+    
+    let promotion = NewMarkerPromotion {
+        marker_id: input.marker_id,
+        sponsor_name,
+        district_codes,
+        starts_at: input.starts_at,
+        ends_at: input.ends_at,
+        impression_cap: input.impression_cap,
+    };
+    match db.create_marker_promotion(&promotion, member.member_id).await {
+        Ok(created) => {
+            info!("📣 프로모션 등록: {} (마커 {}, {} ~ {}) by {}", created.id, created.marker_id, created.starts_at, created.ends_at, member.member_id);
+            Ok(HttpResponse::Created().json(serde_json::json!({
+                "success": true,
+                "data": MarkerPromotionDto::from(&created)
+            })))
+        }
+        Err(e) => {
+            error!("❌ 프로모션 등록 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "프로모션 등록 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 프로모션 목록 (최근 등록순)
+async fn list_marker_promotions(
+    db: web::Data<Database>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    match db.list_marker_promotions(200).await {
+        Ok(promotions) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": promotions.iter().map(MarkerPromotionDto::from).collect::<Vec<_>>()
+        }))),
+        Err(e) => {
+            error!("❌ 프로모션 목록 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "프로모션 목록 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 프로모션 중단/재개, 종료 시각, 노출 한도 수정
+async fn update_marker_promotion(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<UpdateMarkerPromotionRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let input = payload.into_inner();
+    if input.impression_cap.is_some_and(|cap| cap <= 0) {
+        return Ok(ErrorHandler::bad_request("노출 한도는 1 이상이어야 합니다.", None, None));
+    }
+    let promotion_id = path.into_inner();
+    if let Some(ends_at) = input.ends_at {
+        match db.get_marker_promotion(promotion_id).await {
+            Ok(Some(promotion)) if ends_at <= promotion.starts_at => {
+                return Ok(ErrorHandler::bad_request(
+                    "종료 시각은 시작 시각 이후여야 합니다.",
+                    None,
+                    Some(&format!("starts_at: {}, ends_at: {}", promotion.starts_at, ends_at))
+                ));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return Ok(ErrorHandler::internal_server_error(
+                    "프로모션 조회 실패",
+                    Some(&format!("데이터베이스 오류: {}", e))
+                ));
+            }
+        }
+    }
+    
+    match db.update_marker_promotion(promotion_id, input.is_active, input.ends_at, input.impression_cap).await {
+        Ok(Some(promotion)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": MarkerPromotionDto::from(&promotion)
+        }))),
+        Ok(None) => Ok(ErrorHandler::not_found("프로모션을 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 프로모션 수정 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "프로모션 수정 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 프로모션 일자별 노출/클릭 리포트
+async fn get_marker_promotion_report(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    query: web::Query<PromotionReportQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let promotion_id = path.into_inner();
+    let promotion = match db.get_marker_promotion(promotion_id).await {
+        Ok(Some(promotion)) => promotion,
+        Ok(None) => return Ok(ErrorHandler::not_found("프로모션을 찾을 수 없습니다")),
+        Err(e) => {
+            return Ok(ErrorHandler::internal_server_error(
+                "프로모션 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    
+    let days = query.days.unwrap_or(30).clamp(1, 365);
+    match db.get_promotion_stats(promotion_id, days).await {
+        Ok(stats) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": {
+                "promotion": MarkerPromotionDto::from(&promotion),
+                "days": days,
+                "daily": stats.iter().map(|day| serde_json::json!({
+                    "date": day.stat_date,
+                    "impressions": day.impressions,
+                    "clicks": day.clicks
+                })).collect::<Vec<_>>()
+            }
+        }))),
+        Err(e) => {
+            error!("❌ 프로모션 리포트 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "프로모션 리포트 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 프로모션 노출 보고 (클라이언트가 화면에 표시했을 때)
+async fn record_promotion_impression(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    clock: web::Data<dyn Clock>,
+) -> Result<HttpResponse> {
+    record_promotion_event(&db, path.into_inner(), PromotionEvent::Impression, clock.now()).await
+}
+
+/// 프로모션 클릭 보고
+async fn record_promotion_click(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    clock: web::Data<dyn Clock>,
+) -> Result<HttpResponse> {
+    record_promotion_event(&db, path.into_inner(), PromotionEvent::Click, clock.now()).await
+}
+
+async fn record_promotion_event(db: &Database, promotion_id: i64, event: PromotionEvent, now: chrono::DateTime<Utc>) -> Result<HttpResponse> {
+    match db.record_promotion_event(promotion_id, event, now).await {
+        Ok(counted) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "counted": counted
+        }))),
+        Err(e) => {
+            error!("❌ 프로모션 {:?} 기록 실패: {}", event, e);
+            Ok(ErrorHandler::internal_server_error(
+                "프로모션 이벤트 기록 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+const MARKER_REPORT_REASONS: [&str; 8] = ["spam", "offensive", "harassment", "sexual", "violence", "misinformation", "copyright", "other"];
+const MARKER_REPORT_STATUSES: [&str; 3] = ["pending", "dismissed", "hidden"];
+const DEAD_LETTER_STATUSES: [&str; 3] = ["dead", "retried", "discarded"];
+const CLIENT_ERROR_KINDS: [&str; 2] = ["crash", "api_error"];
+const CLIENT_ERROR_PLATFORMS: [&str; 3] = ["ios", "android", "web"];
+const MAX_CLIENT_ERROR_MESSAGE_CHARS: usize = 2000;
+const MAX_CLIENT_ERROR_STACK_CHARS: usize = 16000;
+const MAX_REPORT_DETAILS_CHARS: usize = 1000;
+
+/// 마커 신고 (처리 전 같은 마커 중복 신고는 409)
+async fn report_marker(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<ReportMarkerRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let marker_id = path.into_inner();
+    let input = payload.into_inner();
+    let reason = input.reason.trim().to_lowercase();
+    if !MARKER_REPORT_REASONS.contains(&reason.as_str()) {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 신고 사유입니다",
+            Some(&format!("reason: {} ({})", input.reason, MARKER_REPORT_REASONS.join(", "))),
+            None
+        ));
+    }
+    let details = input.details.as_deref().map(str::trim).filter(|details| !details.is_empty());
+    if details.is_some_and(|details| details.chars().count() > MAX_REPORT_DETAILS_CHARS) {
+        return Ok(ErrorHandler::bad_request(
+            &format!("신고 내용은 {}자 이하로 입력해 주세요.", MAX_REPORT_DETAILS_CHARS),
+            None,
+            None
+        ));
+    }
+    
+    match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) if is_marker_hidden(&db, &marker, Some(&member)).await => {
+            return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"));
+        }
+        Ok(Some(marker)) if marker.member_id == Some(member.member_id) => {
+            return Ok(ErrorHandler::bad_request("본인이 작성한 마커는 신고할 수 없습니다.", None, None));
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    
+    match db.create_marker_report(marker_id, member.member_id, &reason, details).await {
+        Ok(Some(report)) => {
+            info!("🚩 마커 {} 신고 접수: {} (회원 {})", marker_id, reason, member.member_id);
+            Ok(HttpResponse::Created().json(serde_json::json!({
+                "success": true,
+                "message": "신고가 접수되었습니다.",
+                "data": MarkerReportDto::from(&report)
+            })))
+        }
+        Ok(None) => Ok(ErrorHandler::conflict("이미 신고한 마커입니다. 검토가 끝날 때까지 기다려 주세요.", None)),
+        Err(e) => {
+            error!("❌ 마커 신고 저장 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "마커 신고 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 신고 대기열 (마커별로 묶어 신고가 많은 마커부터)
+async fn list_marker_reports(
+    db: web::Data<Database>,
+    query: web::Query<MarkerReportListQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let status = match query.status.as_deref().unwrap_or("pending") {
+        "all" => None,
+        status if MARKER_REPORT_STATUSES.contains(&status) => Some(status),
+        other => {
+            return Ok(ErrorHandler::bad_request(
+                "지원하지 않는 신고 상태입니다",
+                Some(&format!("status: {} (pending, dismissed, hidden, all)", other)),
+                None
+            ));
+        }
+    };
+    let limit = query.limit.unwrap_or(200).clamp(1, 1000);
+    
+    let reports = match db.list_marker_reports(status, limit).await {
+        Ok(reports) => reports,
+        Err(e) => {
+            error!("❌ 신고 목록 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "신고 목록 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    
+    // 최근 신고순으로 받았으므로 마커별 첫 신고가 가장 최근 신고
+    let mut groups: Vec<(i64, Vec<&MarkerReport>)> = Vec::new();
+    for report in &reports {
+        match groups.iter_mut().find(|(marker_id, _)| *marker_id == report.marker_id) {
+            Some((_, group)) => group.push(report),
+            None => groups.push((report.marker_id, vec![report])),
+        }
+    }
+    groups.sort_by_key(|(_, group)| std::cmp::Reverse(group.len()));
+    
+    let data: Vec<serde_json::Value> = groups.iter().map(|(marker_id, group)| {
+        let mut reasons = serde_json::Map::new();
+        for report in group {
+            let count = reasons.get(&report.reason).and_then(|v| v.as_i64()).unwrap_or(0);
+            reasons.insert(report.reason.clone(), serde_json::json!(count + 1));
+        }
+        serde_json::json!({
+            "markerId": marker_id,
+            "reportCount": group.len(),
+            "reasons": reasons,
+            "lastReportedAt": group.first().map(|report| report.created_at),
+            "reports": group.iter().map(|report| MarkerReportDto::from(*report)).collect::<Vec<_>>()
+        })
+    }).collect();
+    
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": data,
+        "count": reports.len()
+    })))
+}
+
+async fn resolve_marker_reports(
+    db: &Database,
+    member: &AuthenticatedMember,
+    req: &actix_web::HttpRequest,
+    report_id: i64,
+    hide: bool,
+    note: Option<String>,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(db, member).await {
+        return Ok(response);
+    }
+    
+    let resolution = MarkerReportResolution {
+        admin_member_id: member.member_id,
+        hide,
+        note: note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty()),
+        client_ip: req.connection_info().realip_remote_addr().map(|ip| ip.to_string()),
+        user_agent: req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|ua| ua.to_string()),
+    };
+    match db.resolve_marker_reports(report_id, &resolution).await {
+        Ok(Some(resolved)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": if hide { "신고된 마커를 숨겼습니다." } else { "신고를 기각했습니다." },
+            "data": {
+                "markerId": resolved.first().map(|report| report.marker_id),
+                "status": if hide { "hidden" } else { "dismissed" },
+                "resolvedReports": resolved.len()
+            }
+        }))),
+        Ok(None) => Ok(ErrorHandler::not_found("처리 대기 중인 신고를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 신고 처리 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "신고 처리 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 신고 기각 (같은 마커의 대기 중인 신고를 모두 닫음)
+async fn dismiss_marker_reports(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: Option<web::Json<ResolveMarkerReportRequest>>,
+    member: AuthenticatedMember,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse> {
+    let note = payload.and_then(|payload| payload.into_inner().note);
+    resolve_marker_reports(&db, &member, &req, path.into_inner(), false, note).await
+}
+
+/// 관리자: 신고된 마커 숨김 (게시 중단, 복구는 /admin/markers/{id}/restore)
+async fn hide_reported_marker(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: Option<web::Json<ResolveMarkerReportRequest>>,
+    member: AuthenticatedMember,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse> {
+    let note = payload.and_then(|payload| payload.into_inner().note);
+    resolve_marker_reports(&db, &member, &req, path.into_inner(), true, note).await
+}
+
+/// 관리자: 데드레터 큐 (영구 실패한 백그라운드 작업, 작업 종류/상태별 건수 요약 포함)
+async fn list_dead_letter_jobs(
+    db: web::Data<Database>,
+    query: web::Query<DeadLetterListQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let status = match query.status.as_deref().unwrap_or("dead") {
+        "all" => None,
+        status if DEAD_LETTER_STATUSES.contains(&status) => Some(status),
+        other => {
+            return Ok(ErrorHandler::bad_request(
+                "지원하지 않는 작업 상태입니다",
+                Some(&format!("status: {} (dead, retried, discarded, all)", other)),
+                None
+            ));
+        }
+    };
+    let job_type = query.job_type.as_deref().map(str::trim).filter(|job_type| !job_type.is_empty());
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let offset = query.offset.unwrap_or(0).max(0);
+    
+    let result = async {
+        let jobs = db.list_dead_letter_jobs(status, job_type, limit, offset).await?;
+        let counts = db.count_dead_letter_jobs().await?;
+        Ok::<_, anyhow::Error>((jobs, counts))
+    }.await;
+    match result {
+        Ok((jobs, counts)) => {
+            let mut summary = serde_json::Map::new();
+            for (job_type, status, count) in counts {
+                let entry = summary.entry(job_type).or_insert_with(|| serde_json::json!({}));
+                entry[status] = serde_json::json!(count);
+            }
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": jobs.iter().map(DeadLetterJobDto::from).collect::<Vec<_>>(),
+                "summary": summary,
+                "count": jobs.len()
+            })))
+        }
+        Err(e) => {
+            error!("❌ 데드레터 목록 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "데드레터 목록 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+async fn resolve_dead_letter_job(db: &Database, member: &AuthenticatedMember, id: i64, retry: bool) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(db, member).await {
+        return Ok(response);
+    }
+    
+    match db.resolve_dead_letter_job(id, retry, member.member_id).await {
+        Ok(Some(job)) => {
+            info!("🪦 관리자 {} 데드레터 {}: 작업 {} ({})", member.member_id, job.status, job.id, job.job_type);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": if retry { "작업을 다시 대기열에 넣었습니다." } else { "작업을 버렸습니다." },
+                "data": DeadLetterJobDto::from(&job)
+            })))
+        }
+        Ok(None) => Ok(ErrorHandler::not_found("처리 대기 중인 데드레터 작업이 없습니다.")),
+        Err(e) => {
+            error!("❌ 데드레터 처리 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "데드레터 처리 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 데드레터 작업 재시도 (원래 작업을 대기 상태로 되돌려 처리기가 다시 가져가게 함)
+async fn retry_dead_letter_job(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    resolve_dead_letter_job(&db, &member, path.into_inner(), true).await
+}
+
+/// 관리자: 데드레터 작업 버림 (원래 작업은 그대로 멈춰 있음)
+async fn discard_dead_letter_job(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    resolve_dead_letter_job(&db, &member, path.into_inner(), false).await
+}
+
+/// 관리자: 고아 파일 점검 (dry-run, 주기 정리 작업이 지울 파일과 마커 이미지를 지우지 않고 보여줌)
+async fn list_orphaned_files(
+    db: web::Data<Database>,
+    config: web::Data<Config>,
+    storage: web::Data<dyn Storage>,
+    clock: web::Data<dyn Clock>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let min_age = chrono::Duration::hours(config.orphan_gc_min_age_hours);
+    match orphan_gc::scan(&db, storage.get_ref(), clock.now(), min_age).await {
+        Ok(report) => {
+            info!("🧹 관리자 {} 고아 파일 점검: 파일 {}개, 파일이 없는 마커 이미지 {}개", member.member_id, report.orphaned_file_count, report.missing_image_count);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": report,
+                "minAgeHours": config.orphan_gc_min_age_hours,
+                "maxRemovalsPerRun": orphan_gc::MAX_REMOVALS_PER_RUN
+            })))
+        }
+        Err(e) => {
+            error!("❌ 고아 파일 점검 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "고아 파일 점검 실패",
+                Some(&e.to_string())
+            ))
+        }
+    }
+}
+
+/// 보고 필드 정리 (앞뒤 공백 제거, 빈 값은 None, 너무 길면 자름)
+fn client_error_field(value: Option<&str>, max_chars: usize) -> Option<String> {
+    value.map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.chars().take(max_chars).collect())
+}
+
+/// 클라이언트 오류 보고 (로그인 선택, 크래시는 항상 저장하고 API 오류는 CLIENT_ERROR_SAMPLE_RATE 비율만 저장)
+async fn report_client_error(
+    req: actix_web::HttpRequest,
+    db: web::Data<Database>,
+    config: web::Data<Config>,
+    ids: web::Data<dyn IdGenerator>,
+    payload: web::Json<ClientErrorReport>,
+    member: Option<AuthenticatedMember>,
+) -> Result<HttpResponse> {
+    let input = payload.into_inner();
+    let kind = input.kind.trim().to_lowercase();
+    if !CLIENT_ERROR_KINDS.contains(&kind.as_str()) {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 오류 종류입니다",
+            Some(&format!("kind: {} ({})", input.kind, CLIENT_ERROR_KINDS.join(", "))),
+            None
+        ));
+    }
+    let platform = input.platform.trim().to_lowercase();
+    if !CLIENT_ERROR_PLATFORMS.contains(&platform.as_str()) {
+        return Ok(ErrorHandler::bad_request(
+            "지원하지 않는 플랫폼입니다",
+            Some(&format!("platform: {} ({})", input.platform, CLIENT_ERROR_PLATFORMS.join(", "))),
+            None
+        ));
+    }
+    let Some(message) = client_error_field(Some(&input.message), MAX_CLIENT_ERROR_MESSAGE_CHARS) else {
+        return Ok(ErrorHandler::bad_request("오류 메시지를 입력해 주세요.", None, None));
+    };
+    let failed_request_id = input.request_id.as_deref().map(str::trim).filter(|id| !id.is_empty());
+    if failed_request_id.is_some_and(|id| !is_valid_request_id(id)) {
+        return Ok(ErrorHandler::bad_request(
+            "request_id 값이 올바르지 않습니다. 응답의 X-Request-Id를 그대로 보내주세요.",
+            None,
+            None
+        ));
+    }
+    
+    // 크래시는 드물고 하나하나가 중요하므로 샘플링하지 않음
+    let sample_rate = if kind == "crash" { 1.0 } else { config.client_error_sample_rate };
+    let sampled = (ids.new_id().as_u128() % 10_000) as f64 / 10_000.0 < sample_rate;
+    if !sampled {
+        return Ok(HttpResponse::Accepted().json(serde_json::json!({
+            "success": true,
+            "stored": false
+        })));
+    }
+    
+    let report = NewClientError {
+        kind,
+        platform,
+        app_version: client_error_field(input.app_version.as_deref(), 50),
+        device: client_error_field(input.device.as_deref(), 100),
+        os_version: client_error_field(input.os_version.as_deref(), 50),
+        route: client_error_field(input.route.as_deref(), 300),
+        request_id: failed_request_id.map(str::to_string),
+        http_status: input.http_status.filter(|status| (100..600).contains(status)),
+        message,
+        stack_trace: client_error_field(input.stack_trace.as_deref(), MAX_CLIENT_ERROR_STACK_CHARS),
+        member_id: member.map(|member| member.member_id),
+        report_request_id: request_id(&req),
+        sample_rate: sample_rate as f32,
+    };
+    match db.create_client_error(&report).await {
+        Ok(saved) => {
+            info!("📱 클라이언트 오류 보고 {}: {} {} (요청 {:?})", saved.id, saved.kind, saved.platform, saved.request_id);
+            Ok(HttpResponse::Accepted().json(serde_json::json!({
+                "success": true,
+                "stored": true,
+                "data": { "id": saved.id }
+            })))
+        }
+        Err(e) => {
+            error!("❌ 클라이언트 오류 보고 저장 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "클라이언트 오류 보고 저장 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 클라이언트 오류 보고 목록 (request_id로 서버 로그의 요청과 맞춰 보기, 최근 기간 추정 건수 요약 포함)
+async fn list_client_errors(
+    db: web::Data<Database>,
+    query: web::Query<ClientErrorListQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let text = |value: &Option<String>| value.as_deref().map(str::trim).filter(|value| !value.is_empty()).map(str::to_string);
+    let filter = ClientErrorFilter {
+        kind: text(&query.kind).map(|kind| kind.to_lowercase()),
+        platform: text(&query.platform).map(|platform| platform.to_lowercase()),
+        app_version: text(&query.app_version),
+        request_id: text(&query.request_id),
+    };
+    let hours = query.hours.unwrap_or(24).clamp(1, 24 * 30);
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let offset = query.offset.unwrap_or(0).max(0);
+    
+    let result = async {
+        let reports = db.list_client_errors(&filter, limit, offset).await?;
+        let summary = db.summarize_client_errors(hours).await?;
+        Ok::<_, anyhow::Error>((reports, summary))
+    }.await;
+    match result {
+        Ok((reports, summary)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": reports.iter().map(ClientErrorDto::from).collect::<Vec<_>>(),
+            "summary": summary.iter().map(|row| serde_json::json!({
+                "kind": row.kind,
+                "platform": row.platform,
+                "appVersion": row.app_version,
+                "reported": row.reported,
+                "estimated": row.estimated
+            })).collect::<Vec<_>>(),
+            "summaryHours": hours,
+            "count": reports.len()
+        }))),
+        Err(e) => {
+            error!("❌ 클라이언트 오류 목록 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "클라이언트 오류 목록 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: 게시 중단 감사 로그 조회
+async fn get_takedown_audit(
+    db: web::Data<Database>,
+    query: web::Query<TakedownAuditQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    match db.get_takedown_audit(query.target_type.as_deref(), query.target_id, limit).await {
+        Ok(entries) => {
+            let formatted: Vec<ContentTakedownAuditDto> = entries.iter().map(ContentTakedownAuditDto::from).collect();
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": formatted,
+                "count": entries.len()
+            })))
+        }
+        Err(e) => {
+            error!("❌ 게시 중단 감사 로그 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "게시 중단 감사 로그 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+const DISTRICT_IMPORT_MAX_BYTES: usize = 100 * 1024 * 1024;
+
+/// 행정구역 코드는 숫자만 (시도 2자리, 시군구 5자리, 읍면동 이하 최대 10자리)
+fn is_valid_district_code(code: &str) -> bool {
+    (2..=10).contains(&code.len()) && code.chars().all(|c| c.is_ascii_digit())
+}
+
+/// GeoJSON properties에서 첫 번째로 있는 키 값을 문자열로 (숫자 코드도 허용)
+fn district_property(properties: &serde_json::Map<String, serde_json::Value>, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| match properties.get(*key)? {
+        serde_json::Value::String(value) => Some(value.trim().to_string()),
+        serde_json::Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    })
+    .filter(|value| !value.is_empty())
+}
+
+/// 관리자: 행정구역 경계 등록 (SHP에서 변환한 GeoJSON, WGS84 좌표)
+/// 시도(CTPRVN_CD/CTP_KOR_NM), 시군구(SIG_CD/SIG_KOR_NM) 속성 또는 code/name 속성을 인식
+async fn import_districts(
+    db: web::Data<Database>,
+    payload: web::Json<DistrictImportRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let mut districts = Vec::new();
+    for (index, feature) in payload.features.iter().enumerate() {
+        let code = district_property(&feature.properties, &["SIG_CD", "CTPRVN_CD", "code"]);
+        let name = district_property(&feature.properties, &["SIG_KOR_NM", "CTP_KOR_NM", "name"]);
+        let geometry_type = feature.geometry.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+        let (Some(code), Some(name)) = (code, name) else {
+            return Ok(ErrorHandler::bad_request(
+                "행정구역 코드/이름 속성이 없습니다.",
+                None,
+                Some(&format!("feature #{}", index))
+            ));
+        };
+        if !is_valid_district_code(&code) || !matches!(geometry_type, "Polygon" | "MultiPolygon") {
+            return Ok(ErrorHandler::bad_request(
+                "행정구역 코드 또는 경계 형식이 올바르지 않습니다.",
+                None,
+                Some(&format!("feature #{} (code: {}, geometry: {})", index, code, geometry_type))
+            ));
+        }
+        let (level, parent_code) = if code.len() <= 2 {
+            ("sido", None)
+        } else {
+            ("sigungu", Some(code[..2].to_string()))
+        };
+        districts.push(DistrictImport {
+            code,
+            name,
+            level: level.to_string(),
+            parent_code,
+            geometry: feature.geometry.to_string(),
+        });
+    }
+    if districts.is_empty() {
+        return Ok(ErrorHandler::bad_request("등록할 행정구역이 없습니다.", None, None));
+    }
+    
+    match db.import_districts(&districts).await {
+        Ok(count) => {
+            info!("🗺️ 행정구역 경계 {}개 등록 by {}", count, member.member_id);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": { "imported": count }
+            })))
+        }
+        Err(e) => {
+            error!("❌ 행정구역 경계 등록 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "행정구역 경계 등록 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 행정구역 내 공개 마커 통계
+async fn get_district_stats(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let code = path.into_inner();
+    if !is_valid_district_code(&code) {
+        return Ok(ErrorHandler::bad_request(
+            "행정구역 코드 형식이 올바르지 않습니다.",
+            None,
+            Some(&format!("code: {}", code))
+        ));
+    }
+    
+    let district = match db.get_district(&code).await {
+        Ok(Some(district)) => district,
+        Ok(None) => return Ok(ErrorHandler::not_found("행정구역을 찾을 수 없습니다.")),
+        Err(e) => {
+            return Ok(ErrorHandler::internal_server_error(
+                "행정구역 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    
+    match db.get_district_stats(&code).await {
+        Ok(stats) => {
+            let top_emotions: Vec<serde_json::Value> = stats.top_emotions.iter()
+                .map(|(tag, count)| serde_json::json!({ "emotionTag": tag, "markerCount": count }))
+                .collect();
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": {
+                    "district": {
+                        "code": district.code,
+                        "name": district.name,
+                        "level": district.level,
+                        "parentCode": district.parent_code,
+                        "updatedAt": district.updated_at
+                    },
+                    "markerCount": stats.marker_count,
+                    "totalLikes": stats.total_likes,
+                    "totalViews": stats.total_views,
+                    "lastMarkerAt": stats.last_marker_at,
+                    "topEmotions": top_emotions
+                }
+            })))
+        }
+        Err(e) => {
+            error!("❌ 행정구역 통계 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "행정구역 통계 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 공개 API 키 기본 일일 한도
+const DEFAULT_API_KEY_DAILY_QUOTA: i32 = 1000;
+
+/// 관리자: 공개 API 키 발급 (원문 키는 이 응답에서만 확인 가능)
+async fn create_api_key(
+    db: web::Data<Database>,
+    payload: web::Json<CreateApiKeyRequest>,
+    member: AuthenticatedMember,
+    ids: web::Data<dyn IdGenerator>,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let name = payload.name.trim();
+    if name.is_empty() || name.chars().count() > 100 {
+        return Ok(ErrorHandler::bad_request(
+            "API 키 이름은 1~100자여야 합니다.",
+            None,
+            Some(&format!("name: {}", payload.name))
+        ));
+    }
+    let daily_quota = payload.daily_quota.unwrap_or(DEFAULT_API_KEY_DAILY_QUOTA);
+    if daily_quota <= 0 {
+        return Ok(ErrorHandler::bad_request(
+            "일일 한도는 1 이상이어야 합니다.",
+            None,
+            Some(&format!("daily_quota: {}", daily_quota))
+        ));
+    }
+    
+    let raw_key = generate_api_key(ids.as_ref());
+    match db.create_api_key(&raw_key, name, SCOPE_PUBLIC_READ, daily_quota, member.member_id).await {
+        Ok(key) => {
+            info!("🔑 공개 API 키 발급: {} ({}, 일일 {}회) by {}", key.key_prefix, key.name, key.daily_quota, member.member_id);
+            Ok(HttpResponse::Created().json(serde_json::json!({
+                "success": true,
+                "data": IssuedApiKeyDto { key: ApiKeyDto::from(&key), api_key: raw_key },
+                "message": "API 키는 다시 조회할 수 없으니 안전하게 보관하세요."
+            })))
+        }
+        Err(e) => {
+            error!("❌ API 키 발급 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "API 키 발급 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 오류 비율 (요청이 없으면 0)
+fn usage_error_rate(errors: i64, requests: i64) -> f64 {
+    if requests > 0 { errors as f64 / requests as f64 } else { 0.0 }
+}
+
+/// 관리자: 라우트/앱 버전별 호출 수와 오류율 (레거시 엔드포인트 제거 판단용)
+/// 이 인스턴스가 메모리에 모은 사용량은 조회 전에 먼저 반영
+async fn get_route_usage(
+    db: web::Data<Database>,
+    usage: web::Data<RouteUsage>,
+    query: web::Query<RouteUsageQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    if let Err(e) = usage.flush(&db).await {
+        warn!("⚠️ 라우트 사용량 반영 실패 (이전 반영분까지만 조회): {}", e);
+    }
+    let days = query.days.unwrap_or(30).clamp(1, 365);
+    let route = query.route.as_deref().map(str::trim).filter(|route| !route.is_empty());
+    let client_version = query.client_version.as_deref().map(str::trim).filter(|version| !version.is_empty());
+    match db.get_route_usage(days, route, client_version).await {
+        Ok(rows) => {
+            // 버전을 합친 라우트별 합계 (요청 많은 순)
+            let mut routes: Vec<(String, String, i64, i64, i64, chrono::NaiveDate)> = Vec::new();
+            for row in &rows {
+                match routes.iter_mut().find(|entry| entry.0 == row.method && entry.1 == row.route) {
+                    Some(entry) => {
+                        entry.2 += row.request_count;
+                        entry.3 += row.client_error_count;
+                        entry.4 += row.server_error_count;
+                        entry.5 = entry.5.max(row.last_used_on);
+                    }
+                    None => routes.push((row.method.clone(), row.route.clone(), row.request_count, row.client_error_count, row.server_error_count, row.last_used_on)),
+                }
+            }
+            routes.sort_by_key(|entry| std::cmp::Reverse(entry.2));
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "days": days,
+                "data": rows.iter().map(|row| serde_json::json!({
+                    "method": row.method,
+                    "route": row.route,
+                    "clientVersion": row.client_version,
+                    "requestCount": row.request_count,
+                    "clientErrorCount": row.client_error_count,
+                    "serverErrorCount": row.server_error_count,
+                    "errorRate": usage_error_rate(row.client_error_count + row.server_error_count, row.request_count),
+                    "serverErrorRate": usage_error_rate(row.server_error_count, row.request_count),
+                    "firstUsedOn": row.first_used_on,
+                    "lastUsedOn": row.last_used_on
+                })).collect::<Vec<_>>(),
+                "routes": routes.iter().map(|(method, route, requests, client_errors, server_errors, last_used_on)| serde_json::json!({
+                    "method": method,
+                    "route": route,
+                    "requestCount": requests,
+                    "errorRate": usage_error_rate(client_errors + server_errors, *requests),
+                    "serverErrorRate": usage_error_rate(*server_errors, *requests),
+                    "lastUsedOn": last_used_on
+                })).collect::<Vec<_>>(),
+                "count": rows.len()
+            })))
+        }
+        Err(e) => {
+            error!("❌ 라우트 사용량 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "라우트 사용량 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 관리자: API 키별 일자/엔드포인트 사용량
+async fn get_api_key_metrics(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    query: web::Query<ApiKeyMetricsQuery>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    if let Err(response) = require_admin(&db, &member).await {
+        return Ok(response);
+    }
+    
+    let api_key_id = path.into_inner();
+    let key = match db.get_api_key(api_key_id).await {
+        Ok(Some(key)) => key,
+        Ok(None) => return Ok(ErrorHandler::not_found("API 키를 찾을 수 없습니다.")),
+        Err(e) => {
+            return Ok(ErrorHandler::internal_server_error(
+                "API 키 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    
+    let days = query.days.unwrap_or(7).clamp(1, 90);
+    match db.get_api_key_usage(api_key_id, days).await {
+        Ok(usage) => {
+            let total_requests: i64 = usage.iter().map(|u| u.request_count as i64).sum();
+            let total_rejected: i64 = usage.iter().map(|u| u.rejected_count as i64).sum();
+            let formatted: Vec<ApiKeyUsageDto> = usage.iter().map(ApiKeyUsageDto::from).collect();
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": {
+                    "key": ApiKeyDto::from(&key),
+                    "days": days,
+                    "totalRequests": total_requests,
+                    "totalRejected": total_rejected,
+                    "usage": formatted
+                }
+            })))
+        }
+        Err(e) => {
+            error!("❌ API 키 사용량 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "API 키 사용량 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 공개 API: 영역 내 공개 마커 검색
+async fn public_search_markers(
+    db: web::Data<Database>,
+    query: web::Query<PublicMarkerSearchQuery>,
+    client: ApiKeyClient,
+) -> Result<HttpResponse> {
+    let valid_range = (-90.0..=90.0).contains(&query.lat_min)
+        && (-90.0..=90.0).contains(&query.lat_max)
+        && (-180.0..=180.0).contains(&query.lng_min)
+        && (-180.0..=180.0).contains(&query.lng_max)
+        && query.lat_min <= query.lat_max
+        && query.lng_min <= query.lng_max;
+    if !valid_range {
+        return Ok(ErrorHandler::bad_request(
+            "검색 영역이 올바르지 않습니다.",
+            None,
+            Some(&format!("lat {}~{}, lng {}~{}", query.lat_min, query.lat_max, query.lng_min, query.lng_max))
+        ));
+    }
+    
+    let emotion_tags = query.emotion_tags.as_ref().map(|tags| {
+        tags.split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect::<Vec<String>>()
+    }).filter(|tags| !tags.is_empty());
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    
+    match db.search_public_markers(query.lat_min, query.lat_max, query.lng_min, query.lng_max, emotion_tags, limit).await {
+        Ok(markers) => {
+            let formatted: Vec<PublicMarkerDto> = markers.iter().map(PublicMarkerDto::from).collect();
+            let mut response = HttpResponse::Ok();
+            for header in client.rate_limit_headers() {
+                response.insert_header(header);
+            }
+            Ok(response.json(serde_json::json!({
+                "success": true,
+                "data": formatted,
+                "count": markers.len()
+            })))
+        }
+        Err(e) => {
+            error!("❌ 공개 API 마커 검색 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "마커 검색 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 공개 API: 감성 태그별 공개 마커 통계
+async fn public_emotion_stats(
+    db: web::Data<Database>,
+    client: ApiKeyClient,
+) -> Result<HttpResponse> {
+    match db.get_public_emotion_stats().await {
+        Ok(stats) => {
+            let formatted: Vec<serde_json::Value> = stats.iter()
+                .map(|(tag, marker_count, total_likes)| serde_json::json!({
+                    "emotionTag": tag,
+                    "markerCount": marker_count,
+                    "totalLikes": total_likes
+                }))
+                .collect();
+            let mut response = HttpResponse::Ok();
+            for header in client.rate_limit_headers() {
+                response.insert_header(header);
+            }
+            Ok(response.json(serde_json::json!({
+                "success": true,
+                "data": formatted,
+                "count": stats.len()
+            })))
+        }
+        Err(e) => {
+            error!("❌ 공개 API 감성 통계 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "감성 통계 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 외부 임베드용 감정 지도 타일 (API 키 쿼터 적용, 칸별 공개 마커 수와 감정 비율만)
+async fn public_emotion_tile(
+    db: web::Data<Database>,
+    path: web::Path<(u32, u32, u32)>,
+    client: ApiKeyClient,
+) -> Result<HttpResponse> {
+    let (z, x, y) = path.into_inner();
+    let tile = match TileCoord::new(z, x, y) {
+        Ok(tile) => tile,
+        Err(e) => return Ok(ErrorHandler::bad_request("타일 좌표가 올바르지 않습니다.", Some(&e), None)),
+    };
+    match db.get_emotion_tile(&tile, emotion_tiles::MIN_CELL_MARKERS).await {
+        Ok(rows) => {
+            let mut response = HttpResponse::Ok();
+            for header in client.rate_limit_headers() {
+                response.insert_header(header);
+            }
+            // 헤더로 키를 보낸 요청끼리 CDN 캐시가 섞이지 않도록
+            response.insert_header((actix_web::http::header::VARY, API_KEY_HEADER));
+            Ok(response.json(serde_json::json!({
+                "success": true,
+                "data": emotion_tiles::tile_json(&tile, &rows)
+            })))
+        }
+        Err(e) => {
+            error!("❌ 감정 지도 타일 조회 실패 ({}/{}/{}): {}", z, x, y, e);
+            Ok(ErrorHandler::internal_server_error(
+                "감정 지도 타일 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 마커 ETag (낙관적 잠금 버전)
+fn marker_etag(version: i32) -> String {
+    format!("\"v{}\"", version)
+}
+
+enum VersionPreconditionError {
+    Missing,
+    Invalid(String),
+}
+
+/// 마커 수정 전제 조건: If-Match("v3", W/"v3", 3) 또는 본문 version으로 받은 마지막으로 본 버전
+/// If-Match: *이면 None (버전 확인 없이 덮어씀)
+fn marker_version_precondition(if_match: Option<&str>, body_version: Option<i32>) -> std::result::Result<Option<i32>, VersionPreconditionError> {
+    let header_version = match if_match.map(str::trim).filter(|value| !value.is_empty()) {
+        None => None,
+        Some("*") => return Ok(body_version),
+        Some(value) => {
+            let tag = value.strip_prefix("W/").unwrap_or(value).trim_matches('"');
+            match tag.strip_prefix('v').unwrap_or(tag).parse::<i32>() {
+                Ok(version) => Some(version),
+                Err(_) => return Err(VersionPreconditionError::Invalid(format!("If-Match 형식이 올바르지 않습니다: {}", value))),
+            }
+        }
+    };
+    match (header_version, body_version) {
+        (Some(header), Some(body)) if header != body => {
+            Err(VersionPreconditionError::Invalid(format!("If-Match(v{})와 version({})이 다릅니다", header, body)))
+        }
+        (Some(version), _) | (None, Some(version)) => Ok(Some(version)),
+        (None, None) => Err(VersionPreconditionError::Missing),
+    }
+}
+
+fn marker_version_conflict(current: &Marker) -> HttpResponse {
+    ErrorHandler::version_conflict(
+        "다른 기기에서 먼저 수정된 마커입니다. 최신 내용을 확인한 뒤 다시 수정해주세요.",
+        &marker_etag(current.version.unwrap_or(1)),
+        serde_json::json!(MarkerDto::from(current)),
+    )
+}
+
+/// 마커 수정 핸들러가 쓰는 앱 상태 묶음 (등록된 web::Data에서 꺼냄)
+struct MarkerUpdateServices {
+    config: web::Data<Config>,
+    profiles: web::Data<EmotionProfileCache>,
+    clock: web::Data<dyn Clock>,
+    marker_events: web::Data<MarkerEvents>,
+    cluster_cache: web::Data<ClusterCache>,
+}
+
+impl actix_web::FromRequest for MarkerUpdateServices {
+    type Error = actix_web::Error;
+    type Future = std::future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &actix_web::HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let services = (|| Some(Self {
+            config: req.app_data::<web::Data<Config>>()?.clone(),
+            profiles: req.app_data::<web::Data<EmotionProfileCache>>()?.clone(),
+            clock: req.app_data::<web::Data<dyn Clock>>()?.clone(),
+            marker_events: req.app_data::<web::Data<MarkerEvents>>()?.clone(),
+            cluster_cache: req.app_data::<web::Data<ClusterCache>>()?.clone(),
+        }))();
+        std::future::ready(services.ok_or_else(|| {
+            actix_web::error::ErrorInternalServerError("마커 수정 서비스가 등록되지 않았습니다")
+        }))
+    }
+}
+
+/// 마커 수정 (작성자 본인만 설명/감성 태그/위치/썸네일 변경 가능)
+/// 다른 기기의 수정을 덮어쓰지 않도록 마지막으로 본 버전(If-Match 또는 version)이 필요하고, 다르면 409
+async fn update_marker(
+    req: actix_web::HttpRequest,
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<UpdateMarkerRequest>,
+    services: MarkerUpdateServices,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let MarkerUpdateServices { config, profiles, clock, marker_events, cluster_cache } = services;
+    let marker_id = path.into_inner();
+    let input = payload.into_inner();
+    let user_id = member.member_id;
+    
+    info!("✏️ 마커 수정 요청: 마커 {}, 유저 {}", marker_id, user_id);
+    
+    let location = match (input.latitude, input.longitude) {
+        (Some(lat), Some(lng)) => {
+            if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+                return Ok(ErrorHandler::bad_request(
+                    "위도/경도 범위가 올바르지 않습니다.",
+                    Some(&format!("latitude {}, longitude {}", lat, lng)),
+                    Some("마커 수정 - 요청 검증 실패")
+                ));
+            }
+            Some((lat, lng))
+        }
+        (None, None) => None,
+        _ => {
+            return Ok(ErrorHandler::bad_request(
+                "위치를 변경하려면 latitude와 longitude를 함께 전달해야 합니다.",
+                None,
+                Some("마커 수정 - 요청 검증 실패")
+            ));
+        }
+    };
+    let publication = if input.status.is_some() || input.publish_at.is_some() {
+        match parse_marker_publication(input.status.as_deref(), input.publish_at.as_deref(), clock.now()) {
+            Ok(publication) => Some(publication),
+            Err(message) => {
+                return Ok(ErrorHandler::bad_request(
+                    &message,
+                    None,
+                    Some("마커 수정 - 게시 상태 검증 실패")
+                ));
+            }
+        }
+    } else {
+        None
+    };
+    let visibility = match input.visibility.as_deref() {
+        Some(raw) => match MarkerVisibility::parse(raw) {
+            Some(visibility) => Some(visibility),
+            None => return Ok(invalid_visibility_response(raw, "마커 수정")),
+        },
+        None => None,
+    };
+    if input.description.is_none() && input.emotion_tag.is_none() && location.is_none() && input.thumbnail_img.is_none() && publication.is_none() && visibility.is_none() {
+        return Ok(ErrorHandler::bad_request(
+            "수정할 항목이 없습니다.",
+            Some("description, emotion_tag, latitude/longitude, thumbnail_img, status, visibility 중 하나 이상 필요"),
+            Some("마커 수정 - 요청 검증 실패")
+        ));
+    }
+    if input.description.as_deref().is_some_and(|d| d.trim().is_empty())
+        || input.emotion_tag.as_deref().is_some_and(|t| t.trim().is_empty())
+    {
+        return Ok(ErrorHandler::bad_request(
+            "설명과 감성 태그는 비워둘 수 없습니다.",
+            None,
+            Some("마커 수정 - 요청 검증 실패")
+        ));
+    }
+    if let Some(response) = input.emotion_tag.as_deref().and_then(|tags| emotion_tag_rejection(tags, "마커 수정")) {
+        return Ok(response);
+    }
+    let if_match = req.headers().get(actix_web::http::header::IF_MATCH).and_then(|value| value.to_str().ok());
+    let expected_version = match marker_version_precondition(if_match, input.version) {
+        Ok(expected_version) => expected_version,
+        Err(VersionPreconditionError::Missing) => {
+            return Ok(ErrorHandler::precondition_required(
+                "마커를 수정하려면 마지막으로 본 버전이 필요합니다.",
+                Some("마커 조회 응답의 ETag를 If-Match 헤더로, 또는 version을 본문으로 보내세요")
+            ));
+        }
+        Err(VersionPreconditionError::Invalid(message)) => {
+            return Ok(ErrorHandler::bad_request(&message, None, Some("마커 수정 - 버전 검증 실패")));
+        }
+    };
+    if let Some(description) = input.description.as_deref()
+        && contains_link(description)
+    {
+        match load_member_trust(&db, &config, user_id, clock.now()).await {
+            Ok(trust) => {
+                if let Some(response) = link_rejection(&trust, &[Some(description)]) {
+                    return Ok(response);
+                }
+            }
+            Err(response) => return Ok(response),
+        }
+    }
+    
+    // 마커 소유자 확인 (게시 중단된 마커는 수정 불가, 게시 상태는 아직 게시 전인 마커만 변경)
+    let previous = match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) if marker.taken_down_at.is_some() => {
+            return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"));
+        }
+        Ok(Some(marker)) => {
+            if marker.member_id != Some(user_id) {
+                return Ok(ErrorHandler::forbidden(
+                    "본인이 작성한 마커만 수정할 수 있습니다.",
+                    Some(&format!("마커 {} 작성자 {:?}, 요청자 {}", marker_id, marker.member_id, user_id))
+                ));
+            }
+            if expected_version.is_some_and(|expected| marker.version != Some(expected)) {
+                return Ok(marker_version_conflict(&marker));
+            }
+            if publication.is_some() && marker.is_published() {
+                return Ok(ErrorHandler::bad_request(
+                    "이미 게시된 마커는 게시 상태를 바꿀 수 없습니다.",
+                    Some(&format!("마커 {}", marker_id)),
+                    Some("마커 수정 - 게시 상태 검증 실패")
+                ));
+            }
+            marker
+        }
+        Ok(None) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    
+    let update = UpdateMarkerInput {
+        language: input.description.as_deref().and_then(detect_language).map(str::to_string), // 설명으로 콘텐츠 언어 재감지
+        description: input.description,
+        emotion_tag: input.emotion_tag,
+        location,
+        thumbnail_img: input.thumbnail_img,
+        status: publication.map(|(status, _)| status),
+        publish_at: publication.and_then(|(_, publish_at)| publish_at),
+        visibility,
+        expected_version,
+    };
+    match db.update_marker(marker_id, user_id, &update).await {
+        Ok(Some(marker)) => {
+            info!("✅ 마커 수정 성공: 마커 {}", marker_id);
+            profiles.invalidate_member(user_id);
+            let previous_location = previous.get_latitude().zip(previous.get_longitude());
+            if previous.is_published() {
+                // 공개 범위가 바뀌면 지도에서 빼거나 새로 넣도록
+                let was_public = MarkerVisibility::of(&previous) == MarkerVisibility::Public;
+                match (was_public, MarkerVisibility::of(&marker) == MarkerVisibility::Public) {
+                    (true, false) => marker_events.publish(MarkerEventKind::Deleted, &previous, None),
+                    (false, true) => marker_events.publish(MarkerEventKind::Created, &marker, None),
+                    _ => marker_events.publish(MarkerEventKind::Updated, &marker, previous_location),
+                }
+                if let Some((lat, lng)) = previous_location {
+                    cluster_cache.invalidate_point(lat, lng);
+                }
+                cluster_cache.invalidate_marker(&marker);
+            } else if marker.is_published() {
+                // 임시 저장 마커를 지금 게시
+                if let Err(e) = db.record_activity(user_id, None, ACTIVITY_MARKER_CREATED, Some(marker.id)).await {
+                    warn!("⚠️ 마커 {} 생성 활동 기록 실패: {}", marker.id, e);
+                }
+                marker_events.publish(MarkerEventKind::Created, &marker, None);
+                cluster_cache.invalidate_marker(&marker);
+            }
+            Ok(HttpResponse::Ok()
+                .insert_header(("ETag", marker_etag(marker.version.unwrap_or(1))))
+                .json(MarkerResponse {
+                    success: true,
+                    message: "마커 수정 성공".to_string(),
+                    data: Some(serde_json::json!(MarkerDto::from(&marker))),
+                }))
+        }
+        // 확인과 수정 사이에 다른 기기가 먼저 고친 경우
+        Ok(None) => match db.get_marker_detail(marker_id).await {
+            Ok(Some(current)) if expected_version.is_some() => Ok(marker_version_conflict(&current)),
+            _ => Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        },
+        Err(e) => {
+            error!("❌ 마커 수정 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "마커 수정 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 외부 웹 페이지 URL로 마커 초안 생성
+/// OG 이미지/제목/좌표를 추출하고 이미지는 저장소에 재호스팅. 마커는 저장하지 않고
+/// POST /api/markers 요청 형식의 초안을 돌려주면 사용자가 확인 후 생성
+async fn create_marker_draft_from_url(
+    db: web::Data<Database>,
+    payload: web::Json<CreateMarkerFromUrlRequest>,
+    config: web::Data<Config>,
+    storage: web::Data<dyn Storage>,
+    keys: web::Data<ObjectKeys>,
+    image_hooks: web::Data<ImageHooks>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let input = payload.into_inner();
+    info!("🔗 URL 마커 초안 요청: 유저 {}, URL {}", member.member_id, input.url);
+    
+    let metadata = match fetch_page_metadata(&input.url).await {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            return Ok(ErrorHandler::bad_request(
+                "페이지 정보를 가져올 수 없습니다.",
+                Some(&e.to_string()),
+                Some(&format!("URL 마커 초안 - {}", input.url))
+            ));
+        }
+    };
+    
+    // OG 이미지 재호스팅 (실패해도 초안은 이미지 없이 반환)
+    let processor = ImageProcessor::new(
+        config.thumbnail_max_width,
+        config.thumbnail_max_height,
+        config.thumbnail_quality
+    ).with_hooks(&image_hooks).with_lossless_png(config.png_lossless);
+    let mut rehosted: Option<(String, Option<String>)> = None;
+    if let Some(image_url) = metadata.image_url.as_deref() {
+        let max_bytes = (config.max_file_size_mb * 1024.0 * 1024.0) as usize;
+        match fetch_image(image_url, max_bytes).await {
+            Ok(image_data) => match rehost_marker_image(&image_data, &processor, &db, storage.get_ref(), &keys).await {
+                Ok(urls) => rehosted = Some(urls),
+                Err(e) => warn!("⚠️ 외부 이미지 재호스팅 실패: {}", e),
+            },
+            Err(e) => warn!("⚠️ 외부 이미지 다운로드 실패: {}", e),
+        }
+    }
+    
+    let description = match (&metadata.title, &metadata.description) {
+        (Some(title), Some(description)) if description != title => format!("{}\n{}", title, description),
+        (Some(title), _) => title.clone(),
+        (None, Some(description)) => description.clone(),
+        (None, None) => String::new(),
+    };
+    
+    let mut missing_fields = vec!["emotion_tag"];
+    if metadata.latitude.is_none() {
+        missing_fields.push("latitude");
+        missing_fields.push("longitude");
+    }
+    if description.is_empty() {
+        missing_fields.push("description");
+    }
+    
+    let rehosted_url = rehosted.as_ref().map(|(url, _)| url.clone());
+    let images: Vec<serde_json::Value> = rehosted_url.iter()
+        .map(|url| serde_json::json!({ "image_url": url }))
+        .collect();
+    
+    Ok(HttpResponse::Ok().json(MarkerResponse {
+        success: true,
+        message: "URL 마커 초안 생성 성공".to_string(),
+        data: Some(serde_json::json!({
+            "draft": {
+                "latitude": metadata.latitude,
+                "longitude": metadata.longitude,
+                "emotion_tag": null,
+                "description": description,
+                "thumbnail_img": rehosted_url,
+                "images": images
+            },
+            "source": {
+                "url": metadata.url,
+                "title": metadata.title,
+                "description": metadata.description,
+                "imageUrl": metadata.image_url,
+                "thumbnailUrl": rehosted.and_then(|(_, thumbnail)| thumbnail),
+                "language": detect_language(&description)
+            },
+            "missingFields": missing_fields
+        })),
+    }))
+}
+
+/// 마커 상세 정보 조회
+async fn get_marker_detail(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    config: web::Data<Config>,
+    presence: web::Data<MarkerPresence>,
+    clock: web::Data<dyn Clock>,
+    member: Option<AuthenticatedMember>,
+) -> Result<HttpResponse> {
+    let marker_id = path.into_inner();
+    
+    info!("🔍 마커 상세 조회: 마커 {}", marker_id);
+    
+    match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) if is_marker_hidden(&db, &marker, member.as_ref()).await => {
+            Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"))
+        }
+        Ok(Some(marker)) => {
+            // 마커 이미지 정보도 함께 조회
+            let images = match db.get_marker_images(marker_id).await {
+                Ok(images) => images,
+                Err(e) => {
+                    warn!("⚠️ 마커 이미지 조회 실패: {}", e);
+                    vec![]
+                }
+            };
+            
+            let formatted_images: Vec<MarkerImageDto> = images.iter()
+                .map(MarkerImageDto::from)
+                .collect();
+            
+            let mut marker_data = serde_json::json!({
+                "marker": MarkerDto::from(&marker),
+                "images": formatted_images
+            });
+            marker_data["viewersNow"] = serde_json::json!(presence.count(marker_id, clock.now()).await);
+            marker_data["verifiedOwner"] = verified_owner_json(&db, marker_id).await;
+            marker_data["tags"] = marker_tags_json(&db, marker_id).await;
+            if let Some(public_id) = marker.public_id.as_deref() {
+                marker_data["shareUrl"] = serde_json::json!(public_id::share_url(&config.web_app_url, public_id));
+            }
+            if member.is_some() {
+                let states = viewer_marker_states(&db, member.as_ref(), &[marker_id]).await;
+                apply_viewer_marker_state(&mut marker_data, states.get(&marker_id));
+            }
+            
+            // 작성자에게만 남은 이미지 쿼터 노출
+            if member.as_ref().is_some_and(|m| marker.member_id == Some(m.member_id)) {
+                match db.get_marker_image_usage(marker_id).await {
+                    Ok((used_images, used_bytes)) => {
+                        marker_data["imageQuota"] = marker_image_quota_json(&config, config.max_images_per_marker, used_images, used_bytes);
+                    }
+                    Err(e) => warn!("⚠️ 마커 이미지 쿼터 조회 실패: {}", e),
+                }
+            }
+            
+            let mut response = HttpResponse::Ok();
+            if let Some(version) = marker.version {
+                response.insert_header(("ETag", marker_etag(version)));
+            }
+            Ok(response.json(MarkerResponse {
+                success: true,
+                message: "마커 상세 조회 성공".to_string(),
+                data: Some(marker_data),
+            }))
+        }
+        Ok(None) => {
+            Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"))
+        }
+        Err(e) => {
+            error!("❌ 마커 상세 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "마커 상세 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 마커 상세 조회 (조회수 증가 포함)
+async fn get_marker_detail_with_view(
+    req: actix_web::HttpRequest,
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    config: web::Data<Config>,
+    presence: web::Data<MarkerPresence>,
+    views: web::Data<MarkerViews>,
+    clock: web::Data<dyn Clock>,
+    member: Option<AuthenticatedMember>,
+) -> Result<HttpResponse> {
+    let marker_id = path.into_inner();
+    
+    info!("📋 마커 상세 조회 (조회수 증가): 마커 {}", marker_id);
+    
+    // 먼저 마커 정보 조회
+    match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) if is_marker_hidden(&db, &marker, member.as_ref()).await => {
+            Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"))
+        }
+        Ok(Some(marker)) => {
+            // 마커 이미지 정보도 함께 조회
+            let images = match db.get_marker_images(marker_id).await {
+                Ok(images) => images,
+                Err(e) => {
+                    warn!("⚠️ 마커 이미지 조회 실패: {}", e);
+                    vec![]
+                }
+            };
+            
+            let formatted_images: Vec<MarkerImageDto> = images.iter()
+                .map(MarkerImageDto::from)
+                .collect();
+            
+            let mut marker_data = serde_json::json!({
+                "marker": MarkerDto::from(&marker),
+                "images": formatted_images
+            });
+            
+            // 상세 화면을 연 사람으로 기록 (비슷한 시간에 보고 있는 사람 수)
+            let viewer = presence_viewer_key(member.as_ref(), &req);
+            marker_data["viewersNow"] = serde_json::json!(presence.touch(marker_id, &viewer, clock.now()).await);
+            marker_data["verifiedOwner"] = verified_owner_json(&db, marker_id).await;
+            marker_data["tags"] = marker_tags_json(&db, marker_id).await;
+            if let Some(public_id) = marker.public_id.as_deref() {
+                marker_data["shareUrl"] = serde_json::json!(public_id::share_url(&config.web_app_url, public_id));
+            }
+            if member.is_some() {
+                let states = viewer_marker_states(&db, member.as_ref(), &[marker_id]).await;
+                apply_viewer_marker_state(&mut marker_data, states.get(&marker_id));
+            }
+            
+            // 작성자에게만 남은 이미지 쿼터 노출
+            if member.as_ref().is_some_and(|m| marker.member_id == Some(m.member_id)) {
+                match db.get_marker_image_usage(marker_id).await {
+                    Ok((used_images, used_bytes)) => {
+                        marker_data["imageQuota"] = marker_image_quota_json(&config, config.max_images_per_marker, used_images, used_bytes);
+                    }
+                    Err(e) => warn!("⚠️ 마커 이미지 쿼터 조회 실패: {}", e),
+                }
+            }
+            
+            // 조회수 증가 (비로그인 포함, 같은 조회자는 중복 제거 기간에 한 번, 주기적으로 한꺼번에 반영)
+            let member_id = member.as_ref().map(|m| m.member_id);
+            views.record(marker_id, marker_viewer_key(member_id, &req, &config), member_id, clock.now());
+            
+            Ok(HttpResponse::Ok().json(MarkerResponse {
+                success: true,
+                message: "마커 상세 조회 성공 (조회수 증가됨)".to_string(),
+                data: Some(marker_data),
+            }))
+        }
+        Ok(None) => {
+            Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"))
+        }
+        Err(e) => {
+            error!("❌ 마커 상세 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "마커 상세 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 시청자 키: 로그인 회원은 회원 ID, 비로그인은 IP와 User-Agent 해시
+fn presence_viewer_key(member: Option<&AuthenticatedMember>, req: &actix_web::HttpRequest) -> String {
+    use std::hash::{Hash, Hasher};
+    if let Some(member) = member {
+        return format!("m:{}", member.member_id);
+    }
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    req.connection_info().realip_remote_addr().unwrap_or_default().hash(&mut hasher);
+    req.headers().get("User-Agent").and_then(|ua| ua.to_str().ok()).unwrap_or_default().hash(&mut hasher);
+    format!("a:{:016x}", hasher.finish())
+}
+
+/// 마커 실시간 시청자 수 스트림 (SSE, 연결이 열려 있는 동안 시청 중으로 기록)
+async fn stream_marker_presence(
+    req: actix_web::HttpRequest,
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    presence: web::Data<MarkerPresence>,
+    clock: web::Data<dyn Clock>,
+    member: Option<AuthenticatedMember>,
+) -> Result<HttpResponse> {
+    let marker_id = path.into_inner();
+    
+    match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) if !is_marker_hidden(&db, &marker, member.as_ref()).await => {}
+        Ok(_) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 시청자 스트림용 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    
+    let viewer = presence_viewer_key(member.as_ref(), &req);
+    let presence = presence.into_inner();
+    let clock = clock.into_inner();
+    let interval = tokio::time::interval(std::time::Duration::from_secs(PRESENCE_STREAM_INTERVAL_SECS));
+    let events = futures_util::stream::unfold(interval, move |mut interval| {
+        let (presence, clock, viewer) = (presence.clone(), clock.clone(), viewer.clone());
+        async move {
+            interval.tick().await;
+            let viewers = presence.touch(marker_id, &viewer, clock.now()).await;
+            let event = format!(
+                "event: presence\ndata: {}\n\n",
+                serde_json::json!({ "markerId": marker_id, "viewersNow": viewers })
+            );
+            Some((Ok::<_, actix_web::Error>(web::Bytes::from(event)), interval))
+        }
+    });
+    
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(events))
+}
+
+/// 마커 실시간 변경 구독 (WebSocket, 로그인 불필요)
+/// 쿼리로 처음 구독할 영역을 줄 수 있고, 연결 후 {"type":"subscribe",...} 메시지로 영역을 바꿈
+async fn marker_updates_socket(
+    req: actix_web::HttpRequest,
+    body: web::Payload,
+    query: web::Query<MarkerSocketQuery>,
+    marker_events: web::Data<MarkerEvents>,
+) -> Result<HttpResponse> {
+    let bounds = match (query.lat, query.lng, query.lat_delta, query.lng_delta) {
+        (Some(lat), Some(lng), Some(lat_delta), Some(lng_delta)) => match Bounds::new(lat, lng, lat_delta, lng_delta) {
+            Ok(bounds) => Some(bounds),
+            Err(e) => return Ok(ErrorHandler::bad_request("구독 영역이 올바르지 않습니다.", Some(&e), Some("마커 실시간 구독"))),
+        },
+        (None, None, None, None) => None,
+        _ => {
+            return Ok(ErrorHandler::bad_request(
+                "구독 영역은 lat, lng, lat_delta, lng_delta를 모두 전달해야 합니다.",
+                None,
+                Some("마커 실시간 구독")
+            ));
+        }
+    };
+    
+    let (response, session, stream) = actix_ws::handle(&req, body)?;
+    info!("🛰️ 마커 실시간 구독 연결 (현재 {}개)", marker_events.subscriber_count() + 1);
+    actix_web::rt::spawn(marker_events::run_marker_socket(
+        session,
+        stream.max_frame_size(marker_events::MAX_CLIENT_FRAME_BYTES),
+        marker_events.subscribe(),
+        bounds,
+    ));
+    Ok(response)
+}
+
+/// 공유 링크로 연 마커 (공개 마커만, 공유 카드/임베드 주소 포함)
+async fn get_shared_marker(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    let public_id = path.into_inner();
+    let marker = match load_embeddable_marker(&db, &public_id).await {
+        Ok(marker) => marker,
+        Err(response) => return Ok(response),
+    };
+    
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": shared_marker_dto(&db, &marker, &public_id, &config).await
+    })))
+}
+
+/// 공유 링크 응답 내용 (마커, 이미지, 공유 카드/임베드 주소)
+async fn shared_marker_dto(db: &Database, marker: &Marker, public_id: &str, config: &Config) -> SharedMarkerDto {
+    let images = match db.get_marker_images(marker.id).await {
+        Ok(images) => images,
+        Err(e) => {
+            warn!("⚠️ 마커 이미지 조회 실패: {}", e);
+            vec![]
+        }
+    };
+    let api_base_url = config.file_server_url.trim_end_matches('/');
+    SharedMarkerDto {
+        marker: MarkerDto::from(marker),
+        images: images.iter().map(MarkerImageDto::from).collect(),
+        share_url: public_id::share_url(&config.web_app_url, public_id),
+        card_url: format!("{}/api/m/{}/card.png", api_base_url, public_id),
+        embed_url: format!("{}/embed/markers/{}", api_base_url, public_id),
+        token: None,
+        opens: None,
+    }
+}
+
+/// 마커 공유 링크 만들기 (로그인 선택, 공개 마커만; 회원은 다시 공유해도 같은 링크이고 공유 수도 한 번만)
+async fn share_marker(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    config: web::Data<Config>,
+    member: Option<AuthenticatedMember>,
+) -> Result<HttpResponse> {
+    let marker_id = path.into_inner();
+    let member_id = member.as_ref().map(|member| member.member_id);
+    
+    info!("🔗 마커 공유 링크 생성: 마커 {}, 유저 {:?}", marker_id, member_id);
+    
+    let marker = match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) if !is_marker_hidden(&db, &marker, member.as_ref()).await => marker,
+        Ok(_) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error("마커 조회 실패", Some(&e.to_string())));
+        }
+    };
+    if !is_embeddable(&marker) {
+        return Ok(ErrorHandler::unprocessable_entity("공개 마커만 공유할 수 있습니다", None));
+    }
+    
+    match db.create_marker_share(marker_id, member_id).await {
+        Ok((share, shares)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": MarkerShareDto {
+                share_url: public_id::share_token_url(&config.file_server_url, &share.token),
+                token: share.token,
+                shares,
+                created_at: share.created_at,
+            }
+        }))),
+        Err(e) => {
+            error!("❌ 마커 공유 링크 생성 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error("공유 링크 생성 실패", Some(&e.to_string())))
+        }
+    }
+}
+
+/// 공유 토큰 링크 열기 (열린 횟수 기록; 브라우저는 웹앱 공유 링크로 보내고, Accept: application/json이면 마커 정보)
+async fn open_shared_marker(
+    req: actix_web::HttpRequest,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    config: web::Data<Config>,
+    clock: web::Data<dyn Clock>,
+) -> Result<HttpResponse> {
+    let token = path.into_inner();
+    if !public_id::is_valid(&token) {
+        return Ok(ErrorHandler::not_found("공유 링크를 찾을 수 없습니다"));
+    }
+    let share = match db.open_marker_share(&token, clock.now()).await {
+        Ok(Some(share)) => share,
+        Ok(None) => return Ok(ErrorHandler::not_found("공유 링크를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 공유 링크 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error("공유 링크 조회 실패", Some(&e.to_string())));
+        }
+    };
+    // 공유한 뒤 비공개로 바뀌었거나 게시 중단된 마커는 열리지 않음
+    let (marker, public_id) = match db.get_marker_detail(share.marker_id).await {
+        Ok(Some(marker)) if is_embeddable(&marker) => match marker.public_id.clone() {
+            Some(public_id) => (marker, public_id),
+            None => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        },
+        Ok(_) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error("마커 조회 실패", Some(&e.to_string())));
+        }
+    };
+    
+    let wants_json = req.headers()
+        .get(actix_web::http::header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json"));
+    if !wants_json {
+        return Ok(HttpResponse::Found()
+            .insert_header(("Location", public_id::share_url(&config.web_app_url, &public_id)))
+            .finish());
+    }
+    
+    let mut data = shared_marker_dto(&db, &marker, &public_id, &config).await;
+    data.token = Some(share.token);
+    data.opens = Some(share.opens);
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": data
+    })))
+}
+
+/// 마커 공유 카드 PNG (SNS 공유/다이제스트 메일용, 로그인 불필요, 공개 ID로만 조회)
+/// 공개 마커만 제공하며, 한 번 만든 카드는 S3에 캐시해 두고 다음부터 S3 주소로 보냄
+async fn get_marker_share_card(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    query: web::Query<WatermarkQuery>,
+    config: web::Data<Config>,
+    s3_service: web::Data<S3Service>,
+    card_fonts: web::Data<CardFonts>,
+    watermark: web::Data<Watermark>,
+) -> Result<HttpResponse> {
+    let marker = match load_embeddable_marker(&db, &path.into_inner()).await {
+        Ok(marker) => marker,
+        Err(response) => return Ok(response),
+    };
+    let marker_id = marker.id;
+
+    let photo_url = match db.get_marker_primary_image(marker_id).await {
+        Ok(Some(image)) => Some(image.image_url),
+        Ok(None) => marker.thumbnail_img.clone().filter(|url| !url.is_empty()),
+        Err(e) => {
+            warn!("⚠️ 대표 이미지 조회 실패: {}", e);
+            marker.thumbnail_img.clone().filter(|url| !url.is_empty())
+        }
+    };
+
+    let watermark = watermark.applies_to(&query).then(|| watermark.get_ref().clone());
+    let cache_key = match &watermark {
+        Some(watermark) => watermark.cache_key(&share_card::cache_key(&marker, photo_url.as_deref())),
+        None => share_card::cache_key(&marker, photo_url.as_deref()),
+    };
+    match s3_service.file_exists(&cache_key).await {
+        Ok(true) => {
+            return Ok(HttpResponse::Found()
+                .insert_header(("Location", s3_service.get_file_url(&cache_key)))
+                .insert_header(("Cache-Control", "public, max-age=3600"))
+                .finish());
+        }
+        Ok(false) => {}
+        Err(e) => warn!("⚠️ 공유 카드 캐시 확인 실패: {}", e),
+    }
+
+    info!("🖼️ 공유 카드 생성: 마커 {}", marker_id);
+    let photo = match &photo_url {
+        Some(url) => load_share_card_photo(url, &config, &s3_service).await,
+        None => None,
+    };
+    let map = match (marker.get_latitude(), marker.get_longitude()) {
+        (Some(latitude), Some(longitude)) => Some(load_share_card_map(&config, latitude, longitude).await),
+        _ => None,
+    };
+    let emotion = marker.emotion_tag.as_deref()
+        .or(marker.emotion.as_deref())
+        .and_then(|tags| tags.split(',').map(str::trim).find_map(get_emotion_by_id));
+    let description = marker.description.clone();
+    let fonts = card_fonts.get_ref().clone();
+
+    let rendered = web::block(move || {
+        let card = ShareCard { photo, emotion, description: description.as_deref(), map };
+        let png = share_card::render(&card, &fonts)?;
+        match watermark {
+            Some(watermark) => watermark.apply_to_bytes(&png).map(|(png, _)| png),
+            None => Ok(png),
+        }
+    }).await;
+    let png = match rendered {
+        Ok(Ok(png)) => png,
+        Ok(Err(e)) => {
+            error!("❌ 공유 카드 렌더링 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error("공유 카드 생성 실패", Some(&e.to_string())));
+        }
+        Err(e) => {
+            error!("❌ 공유 카드 렌더링 작업 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error("공유 카드 생성 실패", Some(&e.to_string())));
+        }
+    };
+
+    // 캐시 저장이 실패해도 이번 응답은 그대로 보냄
+    if let Err(e) = s3_service.upload_file(png.clone(), &cache_key, "image/png").await {
+        warn!("⚠️ 공유 카드 캐시 저장 실패: {}", e);
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type("image/png")
+        .insert_header(("Cache-Control", "public, max-age=3600"))
+        .body(png))
+}
+
+/// 저장된 이미지 원본 읽기 (S3, 로컬 업로드, 외부 URL)
+async fn load_stored_image(image_url: &str, config: &Config, s3_service: &S3Service) -> anyhow::Result<Vec<u8>> {
+    match locate_stored_image(image_url) {
+        Some(StoredImage::S3(key)) => s3_service.get_file(&key).await,
+        Some(StoredImage::Local(filename)) if !filename.contains("..") => {
+            let filepath = find_image_file(&filename, config);
+            if filepath.is_empty() {
+                Err(anyhow::anyhow!("파일을 찾을 수 없습니다: {}", filename))
+            } else {
+                fs::read(&filepath).map_err(anyhow::Error::from)
+            }
+        }
+        None if image_url.starts_with("http") => {
+            fetch_image(image_url, (config.max_file_size_mb * 1024.0 * 1024.0) as usize).await
+        }
+        _ => Err(anyhow::anyhow!("알 수 없는 이미지 경로: {}", image_url)),
+    }
+}
+
+/// 외부 서비스가 직접 가져갈 수 있는 이미지 주소
+fn public_image_url(image_url: &str, config: &Config, s3_service: &S3Service) -> String {
+    match locate_stored_image(image_url) {
+        _ if image_url.starts_with("http") => image_url.to_string(),
+        Some(StoredImage::S3(key)) => s3_service.get_file_url(&key),
+        Some(StoredImage::Local(filename)) => config.get_file_url(&filename),
+        None => image_url.to_string(),
+    }
+}
+
+/// 공유 카드 대표 사진 읽기, 실패하면 사진 없이 그림
+async fn load_share_card_photo(image_url: &str, config: &Config, s3_service: &S3Service) -> Option<image::DynamicImage> {
+    let data = load_stored_image(image_url, config, s3_service).await;
+    match data.and_then(|data| image::load_from_memory(&data).map_err(anyhow::Error::from)) {
+        Ok(photo) => Some(photo),
+        Err(e) => {
+            warn!("⚠️ 공유 카드 사진 읽기 실패 ({}): {}", image_url, e);
+            None
+        }
+    }
+}
+
+/// 공유 카드 위치 지도 (타일 서버가 없거나 실패하면 격자 배경)
+async fn load_share_card_map(config: &Config, latitude: f64, longitude: f64) -> MapInset {
+    if config.share_card_map_tile_url.is_empty() {
+        return MapInset { tile: None, pin: (0.5, 0.5) };
+    }
+    let (url, pin) = share_card::map_tile_url(&config.share_card_map_tile_url, latitude, longitude);
+    let tile = async {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(3))
+            .user_agent("BigPicture/1.0 (share card)")
+            .build()?;
+        let bytes = client.get(&url).send().await?.error_for_status()?.bytes().await?;
+        Ok::<_, anyhow::Error>(image::load_from_memory(&bytes)?)
+    }.await;
+    match tile {
+        Ok(tile) => MapInset { tile: Some(tile), pin },
+        Err(e) => {
+            warn!("⚠️ 지도 타일 다운로드 실패 ({}): {}", url, e);
+            MapInset { tile: None, pin: (0.5, 0.5) }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct EmbedQuery {
+    pub format: Option<String>, // html(기본), json
+}
+
+#[derive(Deserialize)]
+pub struct OEmbedQuery {
+    pub url: String,
+    pub format: Option<String>, // json만 지원
+    pub maxwidth: Option<u32>,
+    pub maxheight: Option<u32>,
+}
+
+/// 임베드 응답 캐시 시간 (좋아요 수가 조금 늦게 반영되어도 됨)
+const EMBED_CACHE_CONTROL: &str = "public, max-age=300";
+
+/// 로그인 없이 보여 줄 수 있는 마커 (게시된 공개 마커, 게시 중단 제외)
+fn is_embeddable(marker: &Marker) -> bool {
+    marker.taken_down_at.is_none()
+        && marker.is_published()
+        && marker.sharing_option.as_deref().unwrap_or("public") == "public"
+}
+
+/// 공개 ID로 임베드/공유할 수 있는 마커 (게시 중단되지 않은 공개 마커만, 아니면 404 응답)
+async fn load_embeddable_marker(db: &Database, public_id: &str) -> std::result::Result<Marker, HttpResponse> {
+    if !public_id::is_valid(public_id) {
+        return Err(ErrorHandler::not_found("마커를 찾을 수 없습니다"));
+    }
+    match db.get_marker_by_public_id(public_id).await {
+        Ok(Some(marker)) if is_embeddable(&marker) => Ok(marker),
+        Ok(_) => Err(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 임베드용 마커 조회 실패: {}", e);
+            Err(ErrorHandler::internal_server_error("마커 조회 실패", Some(&e.to_string())))
+        }
+    }
+}
+
+/// 임베드 카드 내용 (대표 사진은 외부에서 바로 열 수 있는 주소로)
+async fn build_embed_marker(db: &Database, marker: &Marker, config: &Config, s3_service: &S3Service) -> EmbedMarker {
+    let image_url = match db.get_marker_primary_image(marker.id).await {
+        Ok(Some(image)) => Some(image.image_url),
+        Ok(None) => marker.thumbnail_img.clone().filter(|url| !url.is_empty()),
+        Err(e) => {
+            warn!("⚠️ 대표 이미지 조회 실패: {}", e);
+            marker.thumbnail_img.clone().filter(|url| !url.is_empty())
+        }
+    };
+    let emotion = marker.emotion_tag.as_deref()
+        .or(marker.emotion.as_deref())
+        .and_then(|tags| tags.split(',').map(str::trim).find_map(get_emotion_by_id));
+    EmbedMarker::new(
+        marker,
+        emotion,
+        image_url.map(|url| public_image_url(&url, config, s3_service)),
+        &config.file_server_url,
+        &config.web_app_url,
+    )
+}
+
+/// If-None-Match가 현재 ETag와 같으면 304
+fn etag_matches(req: &actix_web::HttpRequest, etag: &str) -> bool {
+    req.headers().get("If-None-Match")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|candidate| candidate.trim() == etag || candidate.trim() == "*"))
+}
+
+/// 마커 임베드 카드 (iframe용 HTML, `format=json`이면 카드 데이터)
+async fn embed_marker(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    query: web::Query<EmbedQuery>,
+    config: web::Data<Config>,
+    s3_service: web::Data<S3Service>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse> {
+    let marker = match load_embeddable_marker(&db, &path.into_inner()).await {
+        Ok(marker) => marker,
+        Err(response) => return Ok(response),
+    };
+    let card = build_embed_marker(&db, &marker, &config, &s3_service).await;
+    let as_json = query.format.as_deref() == Some("json");
+    let etag = embed::etag(&marker, card.image_url.as_deref(), if as_json { "json" } else { "html" });
+    if etag_matches(&req, &etag) {
+        return Ok(HttpResponse::NotModified()
+            .insert_header(("ETag", etag))
+            .insert_header(("Cache-Control", EMBED_CACHE_CONTROL))
+            .finish());
+    }
+    
+    let mut response = HttpResponse::Ok();
+    response
+        .insert_header(("ETag", etag))
+        .insert_header(("Cache-Control", EMBED_CACHE_CONTROL))
+        // oEmbed 소비자가 찾을 수 있도록 링크 헤더로도 알림
+        .insert_header(("Link", format!(
+            "<{}>; rel=\"alternate\"; type=\"application/json+oembed\"",
+            embed::oembed_url(&config.file_server_url, &card.embed_url)
+        )));
+    if as_json {
+        return Ok(response.json(serde_json::json!({
+            "success": true,
+            "data": card
+        })));
+    }
+    Ok(response
+        .content_type("text/html; charset=utf-8")
+        .body(embed::render_html(&card)))
+}
+
+/// 임베드 카드 위치 지도 썸네일 (정사각형 PNG, S3 캐시)
+async fn embed_marker_map(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    config: web::Data<Config>,
+    s3_service: web::Data<S3Service>,
+) -> Result<HttpResponse> {
+    let public_id = path.into_inner();
+    let marker = match load_embeddable_marker(&db, &public_id).await {
+        Ok(marker) => marker,
+        Err(response) => return Ok(response),
+    };
+    let (Some(latitude), Some(longitude)) = (marker.get_latitude(), marker.get_longitude()) else {
+        return Ok(ErrorHandler::not_found("마커 위치가 없습니다"));
+    };
+    
+    let cache_key = embed::map_cache_key(&public_id, latitude, longitude, &config.share_card_map_tile_url);
+    match s3_service.file_exists(&cache_key).await {
+        Ok(true) => {
+            return Ok(HttpResponse::Found()
+                .insert_header(("Location", s3_service.get_file_url(&cache_key)))
+                .insert_header(("Cache-Control", "public, max-age=86400"))
+                .finish());
+        }
+        Ok(false) => {}
+        Err(e) => warn!("⚠️ 지도 썸네일 캐시 확인 실패: {}", e),
+    }
+    
+    let map = load_share_card_map(&config, latitude, longitude).await;
+    let rendered = web::block(move || share_card::render_map_thumbnail(&map, embed::MAP_THUMBNAIL_SIZE)).await;
+    let png = match rendered {
+        Ok(Ok(png)) => png,
+        Ok(Err(e)) => {
+            error!("❌ 지도 썸네일 렌더링 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error("지도 썸네일 생성 실패", Some(&e.to_string())));
+        }
+        Err(e) => {
+            error!("❌ 지도 썸네일 렌더링 작업 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error("지도 썸네일 생성 실패", Some(&e.to_string())));
+        }
+    };
+    if let Err(e) = s3_service.upload_file(png.clone(), &cache_key, "image/png").await {
+        warn!("⚠️ 지도 썸네일 캐시 저장 실패: {}", e);
+    }
+    
+    Ok(HttpResponse::Ok()
+        .content_type("image/png")
+        .insert_header(("Cache-Control", "public, max-age=86400"))
+        .body(png))
+}
+
+/// oEmbed 엔드포인트 (마커 웹 페이지 또는 임베드 주소 → iframe HTML)
+async fn oembed_marker(
+    db: web::Data<Database>,
+    query: web::Query<OEmbedQuery>,
+    config: web::Data<Config>,
+    s3_service: web::Data<S3Service>,
+) -> Result<HttpResponse> {
+    // oEmbed 규격: 지원하지 않는 형식은 501
+    if query.format.as_deref().is_some_and(|format| format != "json") {
+        return Ok(HttpResponse::NotImplemented().json(serde_json::json!({
+            "success": false,
+            "message": "oEmbed는 json 형식만 지원합니다"
+        })));
+    }
+    let Some(public_id) = embed::public_id_from_url(&query.url) else {
+        return Ok(ErrorHandler::not_found("임베드할 수 없는 주소입니다"));
+    };
+    let marker = match load_embeddable_marker(&db, &public_id).await {
+        Ok(marker) => marker,
+        Err(response) => return Ok(response),
+    };
+    let card = build_embed_marker(&db, &marker, &config, &s3_service).await;
+    
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "public, max-age=3600"))
+        .json(embed::oembed(&card, &config.web_app_url, query.maxwidth, query.maxheight)))
+}
+
+#[derive(Deserialize)]
+pub struct ToggleReactionRequest {
+    pub like_type: String, // "like" 또는 "dislike"
+}
+
+/// 마커 좋아요/싫어요 통합 토글
+async fn toggle_marker_reaction(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<ToggleReactionRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let marker_id = path.into_inner();
+    let user_id = member.member_id;
+    let like_type = &payload.like_type;
+    
+    info!("🚀 API 호출: POST /api/markers/{}/reaction - 유저: {}, 타입: {}", marker_id, user_id, like_type);
+    
+    // like_type을 member_markers 테이블의 interaction_type으로 매핑
     let reaction_type = match like_type.as_str() {
         "like" => "liked",
         "dislike" => "disliked",
@@ -2517,106 +7787,761 @@ async fn toggle_marker_reaction(
                 dislikes: 0,
                 is_liked: None,
                 is_disliked: None,
+                is_bookmarked: None,
             }));
         }
     };
     
-    info!("🔄 마커 반응 토글: 마커 {}, 유저 {}, 타입 {}", marker_id, user_id, like_type);
-    info!("💾 데이터베이스 작업 시작: toggle_marker_reaction 호출");
+    info!("🔄 마커 반응 토글: 마커 {}, 유저 {}, 타입 {}", marker_id, user_id, like_type);
+    info!("💾 데이터베이스 작업 시작: toggle_marker_reaction 호출");
+    
+    match db.toggle_marker_reaction(user_id, marker_id, reaction_type).await {
+        Ok((likes, dislikes)) => {
+            info!("✅ 데이터베이스 작업 완료: toggle_marker_reaction 성공 - likes: {}, dislikes: {}", likes, dislikes);
+            let message = match like_type.as_str() {
+                "like" => "좋아요 처리 완료",
+                "dislike" => "싫어요 처리 완료",
+                _ => "반응 처리 완료",
+            };
+            
+            // 전체 카운트가 아니라 요청한 회원 본인의 반응/북마크 상태
+            let state = match db.get_member_marker_states(user_id, &[marker_id]).await {
+                Ok(states) => Some(states.get(&marker_id).copied().unwrap_or_default()),
+                Err(e) => {
+                    warn!("⚠️ 회원 {} 마커 {} 반응 상태 조회 실패: {}", user_id, marker_id, e);
+                    None
+                }
+            };
+            
+            Ok(HttpResponse::Ok().json(MarkerReactionResponse {
+                success: true,
+                message: message.to_string(),
+                likes,
+                dislikes,
+                is_liked: state.map(|state| state.is_liked),
+                is_disliked: state.map(|state| state.is_disliked),
+                is_bookmarked: state.map(|state| state.is_bookmarked),
+            }))
+        }
+        Err(e) => {
+            error!("❌ 데이터베이스 작업 실패: toggle_marker_reaction 실패 - {}", e);
+            error!("❌ 마커 반응 처리 실패: {}", e);
+            Ok(HttpResponse::InternalServerError().json(MarkerReactionResponse {
+                success: false,
+                message: format!("반응 처리 실패: {}", e),
+                likes: 0,
+                dislikes: 0,
+                is_liked: None,
+                is_disliked: None,
+                is_bookmarked: None,
+            }))
+        }
+    }
+}
+
+/// 마커 북마크 토글
+async fn toggle_marker_bookmark(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let marker_id = path.into_inner();
+    let user_id = member.member_id;
+    
+    info!("🔖 마커 북마크 토글: 마커 {}, 유저 {}", marker_id, user_id);
+    
+    match db.toggle_marker_bookmark(user_id, marker_id).await {
+        Ok(is_bookmarked) => {
+            Ok(HttpResponse::Ok().json(MarkerBookmarkResponse {
+                success: true,
+                message: if is_bookmarked { "북마크 추가 완료".to_string() } else { "북마크 제거 완료".to_string() },
+                is_bookmarked,
+            }))
+        }
+        Err(e) => {
+            error!("❌ 마커 북마크 처리 실패: {}", e);
+            Ok(HttpResponse::InternalServerError().json(MarkerBookmarkResponse {
+                success: false,
+                message: format!("북마크 처리 실패: {}", e),
+                is_bookmarked: false,
+            }))
+        }
+    }
+}
+
+/// 회원 팔로우 (그 회원의 followers 공개 마커를 볼 수 있게 됨, 처음 팔로우할 때만 활동 기록)
+async fn follow_member(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let followee_id = path.into_inner();
+    let user_id = member.member_id;
+    
+    if followee_id == user_id {
+        return Ok(ErrorHandler::bad_request("자기 자신은 팔로우할 수 없습니다.", None, Some("팔로우 - 요청 검증 실패")));
+    }
+    match db.get_member_by_id(followee_id).await {
+        Ok(Some(followee)) if followee.is_active => {}
+        Ok(_) => return Ok(ErrorHandler::not_found("회원을 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 회원 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "회원 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    
+    match db.follow_member(user_id, followee_id).await {
+        Ok(followed) => {
+            if followed {
+                info!("👥 팔로우: 유저 {} → 유저 {}", user_id, followee_id);
+                if let Err(e) = db.record_activity(user_id, Some(followee_id), ACTIVITY_MEMBER_FOLLOWED, None).await {
+                    warn!("⚠️ 팔로우 활동 기록 실패: {}", e);
+                }
+            }
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": { "memberId": followee_id, "following": true }
+            })))
+        }
+        Err(e) => {
+            error!("❌ 팔로우 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "팔로우 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 팔로우 취소
+async fn unfollow_member(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let followee_id = path.into_inner();
+    match db.unfollow_member(member.member_id, followee_id).await {
+        Ok(_) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": { "memberId": followee_id, "following": false }
+        }))),
+        Err(e) => {
+            error!("❌ 팔로우 취소 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "팔로우 취소 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+const MAX_COLLECTION_NAME_CHARS: usize = 50;
+const MAX_COLLECTION_DESCRIPTION_CHARS: usize = 500;
+const MAX_COLLECTIONS_PER_MEMBER: i64 = 100;
+const MAX_MARKERS_PER_COLLECTION: i64 = 500;
+
+/// 컬렉션 이름 (앞뒤 공백 제거, 1~50자)
+fn validate_collection_name(name: &str) -> std::result::Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_COLLECTION_NAME_CHARS {
+        return Err(format!("컬렉션 이름은 1~{}자로 입력해 주세요.", MAX_COLLECTION_NAME_CHARS));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_collection_description(description: Option<&str>) -> std::result::Result<(), String> {
+    if description.is_some_and(|description| description.chars().count() > MAX_COLLECTION_DESCRIPTION_CHARS) {
+        return Err(format!("컬렉션 설명은 {}자 이하로 입력해 주세요.", MAX_COLLECTION_DESCRIPTION_CHARS));
+    }
+    Ok(())
+}
+
+/// 수정할 컬렉션 조회 (없거나 남의 비공개 컬렉션이면 404, 남의 공개 컬렉션이면 403)
+async fn load_own_collection(db: &Database, collection_id: i64, member: &AuthenticatedMember) -> std::result::Result<MarkerCollection, HttpResponse> {
+    match db.get_marker_collection(collection_id).await {
+        Ok(Some(collection)) if collection.member_id == member.member_id => Ok(collection),
+        Ok(Some(collection)) if collection.is_public => Err(ErrorHandler::forbidden("본인 컬렉션만 수정할 수 있습니다.", None)),
+        Ok(_) => Err(ErrorHandler::not_found("컬렉션을 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 컬렉션 조회 실패: {}", e);
+            Err(ErrorHandler::internal_server_error(
+                "컬렉션 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 컬렉션 만들기 (회원당 100개까지, 기본 공개)
+async fn create_collection(
+    db: web::Data<Database>,
+    payload: web::Json<CreateCollectionRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let input = payload.into_inner();
+    let name = match validate_collection_name(&input.name) {
+        Ok(name) => name,
+        Err(e) => return Ok(ErrorHandler::bad_request(&e, None, None)),
+    };
+    let description = input.description.as_deref().map(str::trim).filter(|description| !description.is_empty());
+    if let Err(e) = validate_collection_description(description) {
+        return Ok(ErrorHandler::bad_request(&e, None, None));
+    }
+    
+    match db.count_member_collections(member.member_id).await {
+        Ok(count) if count >= MAX_COLLECTIONS_PER_MEMBER => {
+            return Ok(ErrorHandler::unprocessable_entity(
+                &format!("컬렉션은 {}개까지 만들 수 있습니다.", MAX_COLLECTIONS_PER_MEMBER),
+                None
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("❌ 컬렉션 수 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "컬렉션 생성 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    
+    match db.create_marker_collection(member.member_id, &name, description, input.is_public.unwrap_or(true)).await {
+        Ok(collection) => {
+            info!("📚 컬렉션 생성: {} (회원 {})", collection.id, member.member_id);
+            Ok(HttpResponse::Created().json(serde_json::json!({
+                "success": true,
+                "data": MarkerCollectionDto::from(&collection)
+            })))
+        }
+        Err(e) => {
+            error!("❌ 컬렉션 생성 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "컬렉션 생성 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 컬렉션 조회 (비공개 컬렉션은 본인만, 마커는 요청한 회원이 볼 수 있는 것만 순서대로)
+async fn get_collection(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    member: Option<AuthenticatedMember>,
+) -> Result<HttpResponse> {
+    let collection_id = path.into_inner();
+    let viewer_id = member.as_ref().map(|member| member.member_id);
+    let collection = match db.get_marker_collection(collection_id).await {
+        Ok(Some(collection)) if collection.is_public || viewer_id == Some(collection.member_id) => collection,
+        Ok(_) => return Ok(ErrorHandler::not_found("컬렉션을 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 컬렉션 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "컬렉션 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    
+    match db.get_collection_markers(collection_id, viewer_id).await {
+        Ok(markers) => {
+            let data = MarkerCollectionDetailDto {
+                collection: MarkerCollectionDto::from(&collection),
+                markers: markers.iter().map(MarkerDto::from).collect(),
+            };
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": data
+            })))
+        }
+        Err(e) => {
+            error!("❌ 컬렉션 마커 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "컬렉션 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 컬렉션 이름/설명/공개 여부 수정 (본인만)
+async fn update_collection(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<UpdateCollectionRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let collection_id = path.into_inner();
+    let input = payload.into_inner();
+    let name = match input.name.as_deref().map(validate_collection_name).transpose() {
+        Ok(name) => name,
+        Err(e) => return Ok(ErrorHandler::bad_request(&e, None, None)),
+    };
+    let description = input.description.as_deref().map(str::trim);
+    if let Err(e) = validate_collection_description(description) {
+        return Ok(ErrorHandler::bad_request(&e, None, None));
+    }
+    if let Err(response) = load_own_collection(&db, collection_id, &member).await {
+        return Ok(response);
+    }
+    
+    match db.update_marker_collection(collection_id, name.as_deref(), description, input.is_public).await {
+        Ok(Some(collection)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": MarkerCollectionDto::from(&collection)
+        }))),
+        Ok(None) => Ok(ErrorHandler::not_found("컬렉션을 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 컬렉션 수정 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "컬렉션 수정 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 컬렉션 삭제 (본인만, 담긴 마커는 그대로)
+async fn delete_collection(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let collection_id = path.into_inner();
+    if let Err(response) = load_own_collection(&db, collection_id, &member).await {
+        return Ok(response);
+    }
     
-    match db.toggle_marker_reaction(user_id, marker_id, reaction_type).await {
-        Ok((likes, dislikes)) => {
-            info!("✅ 데이터베이스 작업 완료: toggle_marker_reaction 성공 - likes: {}, dislikes: {}", likes, dislikes);
-            let message = match like_type.as_str() {
-                "like" => "좋아요 처리 완료",
-                "dislike" => "싫어요 처리 완료",
-                _ => "반응 처리 완료",
-            };
-            
-            Ok(HttpResponse::Ok().json(MarkerReactionResponse {
-                success: true,
-                message: message.to_string(),
-                likes,
-                dislikes,
-                is_liked: Some(likes > 0),
-                is_disliked: Some(dislikes > 0),
-            }))
+    match db.delete_marker_collection(collection_id).await {
+        Ok(_) => {
+            info!("🗑️ 컬렉션 삭제: {} (회원 {})", collection_id, member.member_id);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": "컬렉션 삭제 완료"
+            })))
         }
         Err(e) => {
-            error!("❌ 데이터베이스 작업 실패: toggle_marker_reaction 실패 - {}", e);
-            error!("❌ 마커 반응 처리 실패: {}", e);
-            Ok(HttpResponse::InternalServerError().json(MarkerReactionResponse {
-                success: false,
-                message: format!("반응 처리 실패: {}", e),
-                likes: 0,
-                dislikes: 0,
-                is_liked: None,
-                is_disliked: None,
-            }))
+            error!("❌ 컬렉션 삭제 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "컬렉션 삭제 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
         }
     }
 }
 
-/// 마커 북마크 토글
-async fn toggle_marker_bookmark(
+/// 컬렉션에 마커 담기 (본인 컬렉션에, 내가 볼 수 있는 게시된 마커만, 끝에 추가)
+async fn add_collection_marker(
     db: web::Data<Database>,
     path: web::Path<i64>,
-    config: web::Data<Config>,
-    req: actix_web::HttpRequest,
+    payload: web::Json<AddCollectionMarkerRequest>,
+    member: AuthenticatedMember,
 ) -> Result<HttpResponse> {
-    let marker_id = path.into_inner();
-    let user_id = extract_user_id_from_token(&req, &config)?;
+    let collection_id = path.into_inner();
+    let marker_id = payload.marker_id;
+    let collection = match load_own_collection(&db, collection_id, &member).await {
+        Ok(collection) => collection,
+        Err(response) => return Ok(response),
+    };
+    match db.get_marker_detail(marker_id).await {
+        Ok(Some(marker)) if is_marker_hidden(&db, &marker, Some(&member)).await => {
+            return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다"));
+        }
+        Ok(Some(marker)) if !marker.is_published() => {
+            return Ok(ErrorHandler::unprocessable_entity("게시된 마커만 컬렉션에 담을 수 있습니다.", None));
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => return Ok(ErrorHandler::not_found("마커를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 마커 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
+    if collection.marker_count >= MAX_MARKERS_PER_COLLECTION {
+        return Ok(ErrorHandler::unprocessable_entity(
+            &format!("컬렉션에는 마커를 {}개까지 담을 수 있습니다.", MAX_MARKERS_PER_COLLECTION),
+            None
+        ));
+    }
     
-    info!("🔖 마커 북마크 토글: 마커 {}, 유저 {}", marker_id, user_id);
+    match db.add_marker_to_collection(collection_id, marker_id).await {
+        Ok(added) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": { "collectionId": collection_id, "markerId": marker_id, "added": added }
+        }))),
+        Err(e) => {
+            error!("❌ 컬렉션 마커 추가 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "컬렉션 마커 추가 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 컬렉션에서 마커 빼기 (본인만)
+async fn remove_collection_marker(
+    db: web::Data<Database>,
+    path: web::Path<(i64, i64)>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let (collection_id, marker_id) = path.into_inner();
+    if let Err(response) = load_own_collection(&db, collection_id, &member).await {
+        return Ok(response);
+    }
     
-    match db.toggle_marker_bookmark(user_id, marker_id).await {
-        Ok(is_bookmarked) => {
-            Ok(HttpResponse::Ok().json(MarkerBookmarkResponse {
-                success: true,
-                message: if is_bookmarked { "북마크 추가 완료".to_string() } else { "북마크 제거 완료".to_string() },
-                is_bookmarked,
-            }))
+    match db.remove_marker_from_collection(collection_id, marker_id).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": { "collectionId": collection_id, "markerId": marker_id, "removed": true }
+        }))),
+        Ok(false) => Ok(ErrorHandler::not_found("컬렉션에 없는 마커입니다")),
+        Err(e) => {
+            error!("❌ 컬렉션 마커 제거 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "컬렉션 마커 제거 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
         }
+    }
+}
+
+/// 컬렉션 마커 순서 바꾸기 (본인만, marker_ids에 담긴 마커 전부를 원하는 순서로)
+async fn reorder_collection_markers(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<ReorderCollectionRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let collection_id = path.into_inner();
+    let marker_ids = &payload.marker_ids;
+    if let Err(response) = load_own_collection(&db, collection_id, &member).await {
+        return Ok(response);
+    }
+    
+    match db.reorder_collection_markers(collection_id, marker_ids).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": { "collectionId": collection_id, "markerIds": marker_ids }
+        }))),
+        Ok(false) => Ok(ErrorHandler::bad_request(
+            "marker_ids에는 컬렉션에 담긴 마커를 빠짐없이 한 번씩 넣어 주세요.",
+            None,
+            None
+        )),
         Err(e) => {
-            error!("❌ 마커 북마크 처리 실패: {}", e);
-            Ok(HttpResponse::InternalServerError().json(MarkerBookmarkResponse {
-                success: false,
-                message: format!("북마크 처리 실패: {}", e),
-                is_bookmarked: false,
-            }))
+            error!("❌ 컬렉션 순서 변경 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "컬렉션 순서 변경 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
         }
     }
 }
 
-/// 마커 조회 기록 추가
-async fn add_marker_view(
+/// 회원의 컬렉션 목록 (본인이면 비공개 포함)
+async fn get_member_collections(
     db: web::Data<Database>,
     path: web::Path<i64>,
-    config: web::Data<Config>,
-    req: actix_web::HttpRequest,
+    member: Option<AuthenticatedMember>,
 ) -> Result<HttpResponse> {
-    let marker_id = path.into_inner();
-    let user_id = extract_user_id_from_token(&req, &config)?;
+    let member_id = path.into_inner();
+    let is_self = member.as_ref().is_some_and(|member| member.member_id == member_id);
+    
+    match db.get_member_collections(member_id, is_self).await {
+        Ok(collections) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": collections.iter().map(MarkerCollectionDto::from).collect::<Vec<_>>(),
+            "count": collections.len()
+        }))),
+        Err(e) => {
+            error!("❌ 컬렉션 목록 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "컬렉션 목록 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+const MAX_BOOKMARK_FOLDER_NAME_CHARS: usize = 30;
+const MAX_BOOKMARK_FOLDERS_PER_MEMBER: usize = 50;
+const MAX_BOOKMARKS_PER_MOVE: usize = 500;
+
+/// 북마크 폴더 이름 (앞뒤 공백 제거, 1~30자)
+fn validate_bookmark_folder_name(name: &str) -> std::result::Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_BOOKMARK_FOLDER_NAME_CHARS {
+        return Err(format!("폴더 이름은 1~{}자로 입력해 주세요.", MAX_BOOKMARK_FOLDER_NAME_CHARS));
+    }
+    Ok(name.to_string())
+}
+
+/// 폴더 색 (#RRGGBB, 대문자로 저장; 빈 문자열은 색 지우기)
+fn normalize_bookmark_folder_color(color: &str) -> std::result::Result<String, String> {
+    let color = color.trim();
+    if color.is_empty() {
+        return Ok(String::new());
+    }
+    let valid = color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|ch| ch.is_ascii_hexdigit());
+    if !valid {
+        return Err(format!("폴더 색은 #RRGGBB 형식이어야 합니다: {}", color));
+    }
+    Ok(color.to_ascii_uppercase())
+}
+
+/// 본인 북마크 폴더 조회 (남의 폴더는 없는 것처럼 404)
+async fn load_own_bookmark_folder(db: &Database, folder_id: i64, member: &AuthenticatedMember) -> std::result::Result<BookmarkFolder, HttpResponse> {
+    match db.get_bookmark_folder(folder_id).await {
+        Ok(Some(folder)) if folder.member_id == member.member_id => Ok(folder),
+        Ok(_) => Err(ErrorHandler::not_found("북마크 폴더를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 북마크 폴더 조회 실패: {}", e);
+            Err(ErrorHandler::internal_server_error(
+                "북마크 폴더 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 내 북마크 폴더 목록 (정한 순서대로)
+async fn list_bookmark_folders(
+    db: web::Data<Database>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    match db.list_bookmark_folders(member.member_id).await {
+        Ok(folders) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": folders.iter().map(BookmarkFolderDto::from).collect::<Vec<_>>(),
+            "count": folders.len()
+        }))),
+        Err(e) => {
+            error!("❌ 북마크 폴더 목록 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "북마크 폴더 목록 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 북마크 폴더 만들기 (맨 뒤에, 회원당 50개)
+async fn create_bookmark_folder(
+    db: web::Data<Database>,
+    payload: web::Json<CreateBookmarkFolderRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let input = payload.into_inner();
+    let name = match validate_bookmark_folder_name(&input.name) {
+        Ok(name) => name,
+        Err(e) => return Ok(ErrorHandler::bad_request(&e, None, None)),
+    };
+    let color = match input.color.as_deref().map(normalize_bookmark_folder_color).transpose() {
+        Ok(color) => color.filter(|color| !color.is_empty()),
+        Err(e) => return Ok(ErrorHandler::bad_request(&e, None, None)),
+    };
     
-    info!("👁️ 마커 조회 기록: 마커 {}, 유저 {}", marker_id, user_id);
+    match db.list_bookmark_folders(member.member_id).await {
+        Ok(folders) if folders.len() >= MAX_BOOKMARK_FOLDERS_PER_MEMBER => {
+            return Ok(ErrorHandler::unprocessable_entity(
+                &format!("북마크 폴더는 {}개까지 만들 수 있습니다.", MAX_BOOKMARK_FOLDERS_PER_MEMBER),
+                None
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("❌ 북마크 폴더 목록 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "북마크 폴더 생성 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    }
     
-    match db.add_marker_view(user_id, marker_id).await {
-        Ok(_) => {
-            Ok(HttpResponse::Ok().json(serde_json::json!({
+    match db.create_bookmark_folder(member.member_id, &name, color.as_deref()).await {
+        Ok(folder) => {
+            info!("📁 북마크 폴더 생성: {} (회원 {})", folder.id, member.member_id);
+            Ok(HttpResponse::Created().json(serde_json::json!({
                 "success": true,
-                "message": "조회 기록 추가 완료"
+                "data": BookmarkFolderDto::from(&folder)
             })))
         }
         Err(e) => {
-            error!("❌ 마커 조회 기록 실패: {}", e);
-            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "success": false,
-                "message": format!("조회 기록 실패: {}", e)
-            })))
+            error!("❌ 북마크 폴더 생성 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "북마크 폴더 생성 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 북마크 폴더 이름/색 수정
+async fn update_bookmark_folder(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    payload: web::Json<UpdateBookmarkFolderRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let folder_id = path.into_inner();
+    let input = payload.into_inner();
+    let name = match input.name.as_deref().map(validate_bookmark_folder_name).transpose() {
+        Ok(name) => name,
+        Err(e) => return Ok(ErrorHandler::bad_request(&e, None, None)),
+    };
+    let color = match input.color.as_deref().map(normalize_bookmark_folder_color).transpose() {
+        Ok(color) => color,
+        Err(e) => return Ok(ErrorHandler::bad_request(&e, None, None)),
+    };
+    if let Err(response) = load_own_bookmark_folder(&db, folder_id, &member).await {
+        return Ok(response);
+    }
+    
+    match db.update_bookmark_folder(folder_id, name.as_deref(), color.as_deref()).await {
+        Ok(Some(folder)) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": BookmarkFolderDto::from(&folder)
+        }))),
+        Ok(None) => Ok(ErrorHandler::not_found("북마크 폴더를 찾을 수 없습니다")),
+        Err(e) => {
+            error!("❌ 북마크 폴더 수정 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "북마크 폴더 수정 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 북마크 폴더 삭제 (북마크는 지우지 않고 폴더 없음으로)
+async fn delete_bookmark_folder(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let folder_id = path.into_inner();
+    if let Err(response) = load_own_bookmark_folder(&db, folder_id, &member).await {
+        return Ok(response);
+    }
+    
+    match db.delete_bookmark_folder(folder_id).await {
+        Ok(_) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": "북마크 폴더 삭제 완료"
+        }))),
+        Err(e) => {
+            error!("❌ 북마크 폴더 삭제 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "북마크 폴더 삭제 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 북마크 폴더 순서 바꾸기 (folder_ids에 내 폴더 전부를 원하는 순서로)
+async fn reorder_bookmark_folders(
+    db: web::Data<Database>,
+    payload: web::Json<ReorderBookmarkFoldersRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let folder_ids = &payload.folder_ids;
+    match db.reorder_bookmark_folders(member.member_id, folder_ids).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": { "folderIds": folder_ids }
+        }))),
+        Ok(false) => Ok(ErrorHandler::bad_request(
+            "folder_ids에는 내 북마크 폴더를 빠짐없이 한 번씩 넣어 주세요.",
+            None,
+            None
+        )),
+        Err(e) => {
+            error!("❌ 북마크 폴더 순서 변경 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "북마크 폴더 순서 변경 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+/// 북마크를 다른 폴더로 옮기기 (folder_id가 null이면 폴더에서 뺌, 북마크하지 않은 마커는 건너뜀)
+async fn move_bookmarks(
+    db: web::Data<Database>,
+    payload: web::Json<MoveBookmarksRequest>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let input = payload.into_inner();
+    if input.marker_ids.is_empty() || input.marker_ids.len() > MAX_BOOKMARKS_PER_MOVE {
+        return Ok(ErrorHandler::bad_request(
+            &format!("marker_ids는 1~{}개여야 합니다.", MAX_BOOKMARKS_PER_MOVE),
+            None,
+            None
+        ));
+    }
+    if let Some(folder_id) = input.folder_id
+        && let Err(response) = load_own_bookmark_folder(&db, folder_id, &member).await
+    {
+        return Ok(response);
+    }
+    
+    match db.move_bookmarks_to_folder(member.member_id, &input.marker_ids, input.folder_id).await {
+        Ok(moved) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": { "folderId": input.folder_id, "moved": moved }
+        }))),
+        Err(e) => {
+            error!("❌ 북마크 이동 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "북마크 이동 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
         }
     }
 }
 
+/// 조회수용 조회자 키 (회원 ID, 비로그인은 기기 ID 또는 IP/User-Agent의 HMAC)
+fn marker_viewer_key(member_id: Option<i64>, req: &actix_web::HttpRequest, config: &Config) -> String {
+    let header = |name: &str| req.headers().get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+    let client_ip = req.connection_info().realip_remote_addr().unwrap_or_default().to_string();
+    marker_views::viewer_key(
+        member_id,
+        header(marker_views::DEVICE_ID_HEADER).as_deref(),
+        &client_ip,
+        header("User-Agent").as_deref().unwrap_or_default(),
+        &config.jwt_secret,
+    )
+}
+
+/// 마커 조회 기록 추가 (비로그인도 가능, 조회수는 주기적으로 반영)
+async fn add_marker_view(
+    req: actix_web::HttpRequest,
+    path: web::Path<i64>,
+    config: web::Data<Config>,
+    views: web::Data<MarkerViews>,
+    clock: web::Data<dyn Clock>,
+    member: Option<AuthenticatedMember>,
+) -> Result<HttpResponse> {
+    let marker_id = path.into_inner();
+    let member_id = member.map(|m| m.member_id);
+    
+    info!("👁️ 마커 조회 기록: 마커 {}, 유저 {:?}", marker_id, member_id);
+    views.record(marker_id, marker_viewer_key(member_id, &req, &config), member_id, clock.now());
+    
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "message": "조회 기록 추가 완료"
+    })))
+}
+
 /// 유저가 생성한 마커 목록 조회
 async fn get_member_created_markers(
     db: web::Data<Database>,
@@ -2630,8 +8555,8 @@ async fn get_member_created_markers(
     
     match db.get_member_created_markers(member_id, limit).await {
         Ok(markers) => {
-            let markers_json: Vec<serde_json::Value> = markers.iter()
-                .map(|marker| marker_to_camelcase_json(marker))
+            let markers_json: Vec<MarkerDto> = markers.iter()
+                .map(MarkerDto::from)
                 .collect();
             
             Ok(HttpResponse::Ok().json(serde_json::json!({
@@ -2664,8 +8589,8 @@ async fn get_member_liked_markers(
     
     match db.get_member_liked_markers(member_id, limit).await {
         Ok(markers) => {
-            let markers_json: Vec<serde_json::Value> = markers.iter()
-                .map(|marker| marker_to_camelcase_json(marker))
+            let markers_json: Vec<MarkerDto> = markers.iter()
+                .map(MarkerDto::from)
                 .collect();
             
             Ok(HttpResponse::Ok().json(serde_json::json!({
@@ -2693,13 +8618,22 @@ async fn get_member_bookmarked_markers(
 ) -> Result<HttpResponse> {
     let member_id = path.into_inner();
     let limit = query.get("limit").and_then(|l| l.parse::<i32>().ok());
+    let folder_id = query.get("folder_id").and_then(|f| f.parse::<i64>().ok());
     
-    info!("🔖 유저 북마크 마커 조회: 유저 {}, 제한 {:?}", member_id, limit);
+    info!("🔖 유저 북마크 마커 조회: 유저 {}, 제한 {:?}, 폴더 {:?}", member_id, limit, folder_id);
     
-    match db.get_member_bookmarked_markers(member_id, limit).await {
+    match db.get_member_bookmarked_markers(member_id, limit, folder_id).await {
         Ok(markers) => {
+            // 마커마다 들어 있는 북마크 폴더 (없으면 null)
             let markers_json: Vec<serde_json::Value> = markers.iter()
-                .map(|marker| marker_to_camelcase_json(marker))
+                .map(|bookmarked| {
+                    let mut marker = serde_json::json!(MarkerDto::from(&bookmarked.marker));
+                    marker["bookmarkFolder"] = match (bookmarked.folder_id, &bookmarked.folder_name) {
+                        (Some(id), Some(name)) => serde_json::json!({ "id": id, "name": name, "color": bookmarked.folder_color }),
+                        _ => serde_json::Value::Null,
+                    };
+                    marker
+                })
                 .collect();
             
             Ok(HttpResponse::Ok().json(serde_json::json!({
@@ -2895,6 +8829,70 @@ async fn get_member_marker_stats(
     }
 }
 
+/// 회원 감정 프로필 (월별 감정 분포, 본인은 비공개 마커 포함 / 다른 사람은 공개 마커만)
+async fn get_member_emotion_profile(
+    db: web::Data<Database>,
+    path: web::Path<i64>,
+    query: web::Query<EmotionProfileQuery>,
+    clock: web::Data<dyn Clock>,
+    profiles: web::Data<EmotionProfileCache>,
+    member: Option<AuthenticatedMember>,
+) -> Result<HttpResponse> {
+    let member_id = path.into_inner();
+    let now = clock.now();
+    let range = match query.year {
+        Some(year) if (2000..=now.year()).contains(&year) => ProfileRange::for_year(year),
+        Some(year) => {
+            return Ok(ErrorHandler::bad_request("연도가 올바르지 않습니다.", Some(&format!("year: {}", year)), None));
+        }
+        None => Some(ProfileRange::recent(now, query.months.unwrap_or(12))),
+    };
+    let Some(range) = range else {
+        return Ok(ErrorHandler::bad_request("연도가 올바르지 않습니다.", None, None));
+    };
+    let include_private = member.as_ref().is_some_and(|member| member.member_id == member_id);
+    
+    if let Some(profile) = profiles.get(member_id, &range, include_private, now) {
+        return Ok(emotion_profile_response(&profile, include_private));
+    }
+    
+    match db.get_member_by_id(member_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Ok(ErrorHandler::not_found("회원을 찾을 수 없습니다.")),
+        Err(e) => {
+            error!("❌ 회원 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error("회원 조회 실패", Some(&format!("데이터베이스 오류: {}", e))));
+        }
+    }
+    
+    let (start, end) = range.bounds();
+    match db.get_member_emotion_counts(member_id, start, end, include_private).await {
+        Ok(counts) => {
+            info!("📊 회원 {} 감정 프로필 집계: {} ~ {}", member_id, start, end);
+            let profile = profiles.insert(&range, include_private, build_profile(member_id, &range, &counts), now);
+            Ok(emotion_profile_response(&profile, include_private))
+        }
+        Err(e) => {
+            error!("❌ 감정 프로필 집계 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "감정 프로필 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+fn emotion_profile_response(profile: &EmotionProfile, include_private: bool) -> HttpResponse {
+    // 비공개 마커가 섞인 본인 프로필은 공유 캐시에 남지 않도록
+    let cache_control = if include_private { "private, max-age=60" } else { "public, max-age=300" };
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", cache_control))
+        .json(serde_json::json!({
+            "success": true,
+            "data": profile
+        }))
+}
+
 /// 유저 조회 (마커 정보 포함)
 async fn get_member_with_markers(
     db: web::Data<Database>,
@@ -2910,7 +8908,7 @@ async fn get_member_with_markers(
                 "success": true,
                 "message": "유저 조회 성공 (마커 정보 포함)",
                 "data": {
-                    "member": member_to_camelcase_json(&member),
+                    "member": MemberDto::from(&member),
                     "markers": markers,
                     "marker_count": markers.len()
                 }
@@ -2953,7 +8951,7 @@ async fn get_member_with_marker_details(
                         "created_at": member_marker.created_at,
                         "updated_at": member_marker.updated_at
                     },
-                    "marker": marker_to_camelcase_json(marker)
+                    "marker": MarkerDto::from(marker)
                 })
             }).collect();
             
@@ -2961,7 +8959,7 @@ async fn get_member_with_marker_details(
                 "success": true,
                 "message": "유저 조회 성공 (마커 상세 정보 포함)",
                 "data": {
-                    "member": member_to_camelcase_json(&member),
+                    "member": MemberDto::from(&member),
                     "marker_details": formatted_details,
                     "marker_count": marker_details.len()
                 }
@@ -2998,7 +8996,7 @@ async fn get_member_with_stats(
                 "success": true,
                 "message": "유저 조회 성공 (마커 통계 포함)",
                 "data": {
-                    "member": member_to_camelcase_json(&member),
+                    "member": MemberDto::from(&member),
                     "marker_stats": stats
                 }
             })))
@@ -3021,9 +9019,12 @@ async fn get_member_with_stats(
 
 /// 피드용 마커 조회 (시간순 내림차순)
 async fn get_markers_feed(
+    req: actix_web::HttpRequest,
     query: web::Query<MarkersFeedQuery>,
     pool: web::Data<PgPool>,
-    config: web::Data<Config>,
+    geoip: web::Data<GeoIp>,
+    clock: web::Data<dyn Clock>,
+    member: Option<AuthenticatedMember>,
 ) -> Result<HttpResponse> {
     let page = query.page.unwrap_or(1);
     let limit = query.limit.unwrap_or(20);
@@ -3035,6 +9036,7 @@ async fn get_markers_feed(
     info!("   - 최소 좋아요: {:?}", query.min_likes);
     info!("   - 최소 조회수: {:?}", query.min_views);
     info!("   - 사용자 ID: {:?}", query.user_id);
+    info!("   - 언어: {:?}", query.lang);
     
     let db = Database { pool: pool.get_ref().clone() };
     
@@ -3047,6 +9049,20 @@ async fn get_markers_feed(
         parsed_tags
     });
     
+    // 콘텐츠 언어 (쿼리 파라미터 > 회원 선호 언어)
+    let languages = resolve_content_languages(query.lang.as_deref(), &db, member.as_ref()).await;
+    
+    let time_filter = match CreatedTimeFilter::parse(query.created_hour_range.as_deref(), query.season.as_deref())
+        .and_then(|filter| filter.with_created_range(query.created_after.as_deref(), query.created_before.as_deref(), query.period.as_deref(), clock.now()))
+    {
+        Ok(filter) => filter,
+        Err(e) => return Ok(ErrorHandler::bad_request("시간대/계절/기간 필터가 올바르지 않습니다.", Some(&e), None)),
+    };
+    let hashtag = match parse_hashtag_filter(query.tag.as_deref()) {
+        Ok(tag) => tag,
+        Err(e) => return Ok(ErrorHandler::bad_request("해시태그 형식이 올바르지 않습니다.", Some(&e), None)),
+    };
+    
     match db.get_markers_feed(
         page,
         limit,
@@ -3054,10 +9070,18 @@ async fn get_markers_feed(
         query.min_likes,
         query.min_views,
         query.user_id,
+        member.as_ref().map(|member| member.member_id), // 공개 범위 필터링용
+        languages.clone(),
+        &time_filter,
+        hashtag.as_deref(),
     ).await {
         Ok((markers, total_count)) => {
             info!("✅ 피드 마커 조회 성공: {}개 마커 반환 (전체: {}개)", markers.len(), total_count);
             
+            // 로그인 회원이면 마커별 내 반응/북마크 상태
+            let marker_ids: Vec<i64> = markers.iter().map(|marker| marker.id).collect();
+            let viewer_states = viewer_marker_states(&db, member.as_ref(), &marker_ids).await;
+            
             // 각 마커에 이미지 정보 추가
             let mut formatted_markers = Vec::new();
             for marker in &markers {
@@ -3070,27 +9094,33 @@ async fn get_markers_feed(
                     }
                 };
                 
-                let formatted_images: Vec<serde_json::Value> = images.iter()
-                    .map(|image| serde_json::json!({
-                        "id": image.id,
-                        "markerId": image.marker_id,
-                        "imageType": image.image_type,
-                        "imageUrl": image.image_url,
-                        "imageOrder": image.image_order,
-                        "isPrimary": image.is_primary,
-                        "createdAt": image.created_at,
-                        "updatedAt": image.updated_at
-                    }))
+                let formatted_images: Vec<MarkerImageDto> = images.iter()
+                    .map(MarkerImageDto::from)
                     .collect();
                 
-                let mut marker_data = marker_to_camelcase_json(marker);
-                if let Some(marker_obj) = marker_data.as_object_mut() {
-                    marker_obj.insert("images".to_string(), serde_json::Value::Array(formatted_images));
+                let marker_data = MarkerDto::from(marker).with_images(formatted_images);
+                let mut marker_data = serde_json::json!(marker_data);
+                if member.is_some() {
+                    apply_viewer_marker_state(&mut marker_data, viewer_states.get(&marker.id));
                 }
                 
                 formatted_markers.push(marker_data);
             }
             
+            // 특정 사용자의 마커 목록이 아니면 프로모션 마커 삽입
+            if query.user_id.is_none() {
+                let point = match (query.lat, query.lng) {
+                    (Some(lat), Some(lng)) => Some((lat, lng)),
+                    _ => req.connection_info()
+                        .realip_remote_addr()
+                        .and_then(parse_client_ip)
+                        .and_then(|ip| geoip.lookup(ip))
+                        .and_then(|location| location.latitude.zip(location.longitude)),
+                };
+                let promoted = load_promoted_markers(&db, clock.now(), point, None, promotions::FEED_PROMOTIONS_PER_PAGE).await;
+                promotions::inject_into_feed(&mut formatted_markers, promoted);
+            }
+            
             // 페이지네이션 정보 계산
             let total_pages = (total_count as f64 / limit as f64).ceil() as i32;
             let has_next = page < total_pages;
@@ -3107,6 +9137,7 @@ async fn get_markers_feed(
                     "hasNext": has_next,
                     "hasPrev": has_prev
                 },
+                "languages": languages,
                 "count": markers.len()
             })))
         }
@@ -3125,8 +9156,14 @@ async fn get_markers_cluster(
     query: web::Query<MarkersQuery>,
     pool: web::Data<PgPool>,
     config: web::Data<Config>,
-    req: actix_web::HttpRequest,
+    clock: web::Data<dyn Clock>,
+    cluster_cache: web::Data<ClusterCache>,
+    member: Option<AuthenticatedMember>,
 ) -> Result<HttpResponse> {
+    let format = match ResponseFormat::parse(query.format.as_deref()) {
+        Ok(format) => format,
+        Err(e) => return Ok(ErrorHandler::bad_request("응답 형식이 올바르지 않습니다.", Some(&e), None)),
+    };
     let db = Database { pool: pool.get_ref().clone() };
     // 파라미터 파싱
     let emotion_tags = query.emotion_tags.as_ref().map(|tags| {
@@ -3134,23 +9171,57 @@ async fn get_markers_cluster(
     });
     let sort_by = query.sort_by.as_deref();
     let sort_order = query.sort_order.as_deref();
+    let viewer_id = member.map(|member| member.member_id);
     let mut user_id = None;
     if query.my.unwrap_or(false) {
-        if let Ok(uid) = extract_user_id_from_token(&req, &config) {
-            user_id = Some(uid);
-        } else {
-            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                "success": false,
-                "message": "내 마커만 표시하려면 로그인(JWT)이 필요합니다."
-            })));
+        match viewer_id {
+            Some(member_id) => user_id = Some(member_id),
+            None => {
+                return Ok(ErrorHandler::unauthorized("내 마커만 표시하려면 로그인(JWT)이 필요합니다.", None));
+            }
         }
     }
-    match db.get_markers_cluster(
-        query.lat, query.lng, query.lat_delta, query.lng_delta,
-        emotion_tags, query.min_likes, query.min_views,
-        sort_by, sort_order, query.limit, user_id, query.zoom // zoom 추가
-    ).await {
-        Ok(mut clusters) => {
+    // 클러스터는 지도 영역 기준으로만 계산
+    let (Some(lat), Some(lng), Some(lat_delta), Some(lng_delta)) = (query.lat, query.lng, query.lat_delta, query.lng_delta) else {
+        return Ok(ErrorHandler::bad_request("lat, lng, lat_delta, lng_delta가 필요합니다.", None, None));
+    };
+    if let Some(zoom) = query.zoom.filter(|zoom| !ZoomResolutions::is_valid_zoom(*zoom)) {
+        return Ok(ErrorHandler::bad_request(&format!("zoom은 0~22 사이여야 합니다: {}", zoom), None, None));
+    }
+    // 클라이언트가 보낸 줌을 우선 사용 (없으면 지도 영역 크기로 추정)
+    let resolution = ZoomResolutions::from_config(&config).resolve(query.zoom, lat_delta, lng_delta);
+    // 조금씩 다른 화면도 같은 캐시 키가 되도록 중심/영역 크기를 맞춘 뒤 그 영역으로 조회
+    let viewport = ClusterViewport::normalize(lat, lng, lat_delta, lng_delta, resolution);
+    let (lat, lng, lat_delta, lng_delta) = (viewport.lat, viewport.lng, viewport.lat_delta, viewport.lng_delta);
+    let now = clock.now();
+    let time_filter = match CreatedTimeFilter::parse(query.created_hour_range.as_deref(), query.season.as_deref())
+        .and_then(|filter| filter.with_created_range(query.created_after.as_deref(), query.created_before.as_deref(), query.period.as_deref(), now))
+    {
+        Ok(filter) => filter,
+        Err(e) => return Ok(ErrorHandler::bad_request("시간대/계절/기간 필터가 올바르지 않습니다.", Some(&e), None)),
+    };
+    let mut filter_tags = emotion_tags.clone().unwrap_or_default();
+    filter_tags.sort();
+    filter_tags.dedup();
+    let filters = ClusterFilters {
+        emotion_tags: filter_tags,
+        min_likes: query.min_likes,
+        min_views: query.min_views,
+        limit: query.limit,
+        user_id,
+        viewer_id,
+        time_filter,
+    };
+    let cached = cluster_cache.get(resolution, &viewport, &filters, now);
+    let is_cached = cached.is_some();
+    let result = match cached {
+        Some(clusters) => Ok(clusters),
+        None => db.get_markers_cluster(lat, lng, lat_delta, lng_delta, &filters, sort_by, sort_order, resolution).await.map(|clusters| cluster_cache.insert(resolution, &viewport, &filters, clusters, now)),
+    };
+    match result {
+        Ok(clusters) => {
+            // 캐시된 결과는 공유하므로 요청마다 복사해서 isMine/프로모션 표시
+            let mut clusters = clusters.as_ref().clone();
             // user_id가 있으면 각 마커에 isMine 추가
             if let Some(uid) = user_id {
                 for cluster in clusters.iter_mut() {
@@ -3179,10 +9250,33 @@ async fn get_markers_cluster(
                     }
                 }
             }
+            // 내 마커만 보는 경우가 아니면 지도 영역 안의 프로모션 마커를 따로 표시
+            if user_id.is_none() {
+                let envelope = (lng - lng_delta / 2.0, lat - lat_delta / 2.0, lng + lng_delta / 2.0, lat + lat_delta / 2.0);
+                let promoted = load_promoted_markers(&db, clock.now(), Some((lat, lng)), Some(envelope), promotions::CLUSTER_PROMOTIONS).await;
+                promotions::inject_into_clusters(&mut clusters, promoted);
+            }
+            if format == ResponseFormat::GeoJson {
+                let count = clusters.len();
+                let features = clusters.into_iter().map(geojson::cluster_feature).collect();
+                return Ok(HttpResponse::Ok()
+                    .content_type(geojson::GEOJSON_CONTENT_TYPE)
+                    .json(geojson::feature_collection(features, serde_json::json!({
+                        "count": count,
+                        "zoom": query.zoom,
+                        "resolution": resolution,
+                        "aggregated": resolution <= cluster_zoom::AGGREGATE_MAX_RESOLUTION,
+                        "cached": is_cached
+                    }))));
+            }
             Ok(HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
                 "data": clusters,
-                "count": clusters.len()
+                "count": clusters.len(),
+                "zoom": query.zoom,
+                "resolution": resolution,
+                "aggregated": resolution <= cluster_zoom::AGGREGATE_MAX_RESOLUTION,
+                "cached": is_cached
             })))
         },
         Err(e) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
@@ -3201,15 +9295,17 @@ pub struct RankMarkersQuery {
     pub min_likes: Option<i32>,
     pub min_views: Option<i32>,
     pub my: Option<bool>,
+    pub mode: Option<String>, // top(기본, sort_by 순) 또는 trending(시간 감쇠 반응 점수 순, sort_by 무시)
 }
 
 async fn get_markers_rank(
     query: web::Query<RankMarkersQuery>,
     pool: web::Data<PgPool>,
-    config: web::Data<Config>,
-    req: actix_web::HttpRequest,
+    clock: web::Data<dyn Clock>,
+    member: Option<AuthenticatedMember>,
 ) -> Result<HttpResponse> {
     info!("🏆 마커 순위 조회 요청:");
+    info!("   - 모드: {:?}", query.mode);
     info!("   - 제한: {:?}", query.limit);
     info!("   - 정렬 기준: {:?}", query.sort_by);
     info!("   - 정렬 순서: {:?}", query.sort_order);
@@ -3217,37 +9313,62 @@ async fn get_markers_rank(
     info!("   - 최소 좋아요: {:?}", query.min_likes);
     info!("   - 최소 조회수: {:?}", query.min_views);
     info!("   - 내 마커 포함: {:?}", query.my);
+    let trending = match query.mode.as_deref().map(str::trim) {
+        None | Some("") | Some("top") => false,
+        Some("trending") => true,
+        Some(mode) => {
+            return Ok(ErrorHandler::bad_request("mode는 top 또는 trending이어야 합니다.", Some(&format!("요청 값: {}", mode)), None));
+        }
+    };
     let db = Database { pool: pool.get_ref().clone() };
     let emotion_tags = query.emotion_tags.as_ref().map(|tags| {
         tags.split(',').map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect::<Vec<_>>()
     });
     let sort_by = query.sort_by.as_deref();
     let sort_order = query.sort_order.as_deref();
+    let viewer_id = member.map(|member| member.member_id);
     let mut user_id: Option<i64> = None;
     if query.my.unwrap_or(false) {
-        if let Ok(uid) = extract_user_id_from_token(&req, &config) {
-            user_id = Some(uid);
-        } else {
-            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                "success": false,
-                "message": "내 마커만 조회하려면 로그인(JWT)이 필요합니다."
-            })));
+        match viewer_id {
+            Some(member_id) => user_id = Some(member_id),
+            None => {
+                return Ok(ErrorHandler::unauthorized("내 마커만 조회하려면 로그인(JWT)이 필요합니다.", None));
+            }
         }
     }
-    match db.get_markers_rank(
-        0.0, 0.0, 0.0, 0.0, // 좌표는 랭킹에 필요없으므로 더미값
-        emotion_tags,
-        query.min_likes,
-        query.min_views,
-        sort_by,
-        sort_order,
-        query.limit,
-        user_id,
-    ).await {
+    // (마커, 급상승 모드일 때 점수)
+    let ranked = if trending {
+        let filter = TrendingMarkerFilter {
+            emotion_tags,
+            min_likes: query.min_likes,
+            min_views: query.min_views,
+            limit: query.limit,
+            user_id,
+            viewer_id,
+        };
+        db.get_trending_markers(clock.now(), &filter).await
+            .map(|markers| markers.into_iter()
+                .map(|trending| (trending.marker, Some(trending.trending_score)))
+                .collect::<Vec<_>>())
+    } else {
+        db.get_markers_rank(
+            0.0, 0.0, 0.0, 0.0, // 좌표는 랭킹에 필요없으므로 더미값
+            emotion_tags,
+            query.min_likes,
+            query.min_views,
+            sort_by,
+            sort_order,
+            query.limit,
+            user_id,
+            viewer_id,
+        ).await
+            .map(|markers| markers.into_iter().map(|marker| (marker, None)).collect())
+    };
+    match ranked {
         Ok(markers) => {
             info!("✅ 마커 순위 조회 성공: {}개 마커 반환", markers.len());
             let mut formatted_markers = Vec::new();
-            for marker in &markers {
+            for (marker, score) in &markers {
                 let images = match db.get_marker_images(marker.id).await {
                     Ok(images) => images,
                     Err(e) => {
@@ -3255,28 +9376,20 @@ async fn get_markers_rank(
                         vec![]
                     }
                 };
-                let formatted_images: Vec<serde_json::Value> = images.iter()
-                    .map(|image| serde_json::json!({
-                        "id": image.id,
-                        "markerId": image.marker_id,
-                        "imageType": image.image_type,
-                        "imageUrl": image.image_url,
-                        "imageOrder": image.image_order,
-                        "isPrimary": image.is_primary,
-                        "createdAt": image.created_at,
-                        "updatedAt": image.updated_at
-                    }))
+                let formatted_images: Vec<MarkerImageDto> = images.iter()
+                    .map(MarkerImageDto::from)
                     .collect();
-                let mut marker_data = marker_to_camelcase_json(marker);
-                if let Some(marker_obj) = marker_data.as_object_mut() {
-                    marker_obj.insert("images".to_string(), serde_json::Value::Array(formatted_images));
+                let mut marker_data = serde_json::json!(MarkerDto::from(marker).with_images(formatted_images));
+                if let Some(trending_score) = score {
+                    marker_data["trendingScore"] = serde_json::json!(trending_score);
                 }
                 formatted_markers.push(marker_data);
             }
             Ok(HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
                 "data": formatted_markers,
-                "count": markers.len()
+                "count": markers.len(),
+                "mode": if trending { "trending" } else { "top" }
             })))
         }
         Err(e) => {
@@ -3289,6 +9402,396 @@ async fn get_markers_rank(
     }
 }
 
+#[derive(Deserialize)]
+pub struct RecommendedMarkersQuery {
+    page: Option<i32>,
+    limit: Option<i32>, // 기본 20, 최대 50
+    lang: Option<String>, // 콘텐츠 언어 (예: ko,en / all), 없으면 회원 선호 언어
+}
+
+/// 로그인 회원 맞춤 추천 마커 (관심사/취미, 좋아요한 감정, 지역으로 점수를 매겨 최신순 피드와 다른 순서)
+async fn get_recommended_markers(
+    db: web::Data<Database>,
+    query: web::Query<RecommendedMarkersQuery>,
+    clock: web::Data<dyn Clock>,
+    member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 50);
+    // 콘텐츠 언어 (쿼리 파라미터 > 회원 선호 언어)
+    let languages = resolve_content_languages(query.lang.as_deref(), &db, Some(&member)).await;
+    info!("🎯 추천 마커 조회: 회원 {}, 페이지 {}, 제한 {}, 언어 {:?}", member.member_id, page, limit, languages);
+    
+    match db.get_recommended_markers(member.member_id, clock.now(), languages.as_deref(), page, limit).await {
+        Ok((markers, total_count)) => {
+            let mut formatted_markers = Vec::with_capacity(markers.len());
+            for recommended in &markers {
+                let images = match db.get_marker_images(recommended.marker.id).await {
+                    Ok(images) => images,
+                    Err(e) => {
+                        warn!("⚠️ 마커 {} 이미지 조회 실패: {}", recommended.marker.id, e);
+                        vec![]
+                    }
+                };
+                let formatted_images: Vec<MarkerImageDto> = images.iter().map(MarkerImageDto::from).collect();
+                let mut marker_data = serde_json::json!(MarkerDto::from(&recommended.marker).with_images(formatted_images));
+                let mut reasons = Vec::new();
+                if recommended.keyword_matches > 0 {
+                    reasons.push("interests");
+                }
+                if recommended.emotion_affinity > 0 {
+                    reasons.push("liked_emotions");
+                }
+                if recommended.region_match {
+                    reasons.push("region");
+                }
+                marker_data["recommendationScore"] = serde_json::json!(recommended.recommendation_score);
+                marker_data["reasons"] = serde_json::json!(reasons);
+                formatted_markers.push(marker_data);
+            }
+            
+            let total_pages = (total_count as f64 / limit as f64).ceil() as i32;
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": formatted_markers,
+                "pagination": {
+                    "currentPage": page,
+                    "totalPages": total_pages,
+                    "totalCount": total_count,
+                    "limit": limit,
+                    "hasNext": page < total_pages,
+                    "hasPrev": page > 1
+                },
+                "count": markers.len()
+            })))
+        }
+        Err(e) => {
+            error!("❌ 추천 마커 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error("추천 마커 조회 실패", Some(&format!("데이터베이스 오류: {}", e))))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SuggestLocationQuery {
+    lat: f64,
+    lng: f64,
+    accuracy: Option<f64>, // 단말이 알려준 GPS 정확도 (미터)
+}
+
+/// 마커 작성 중 위치 추천: 근처 공개 마커, 마커가 많이 모인 지점, 근처 장소, GPS 오차를 줄인 보정 좌표
+async fn suggest_marker_location(
+    db: web::Data<Database>,
+    query: web::Query<SuggestLocationQuery>,
+    config: web::Data<Config>,
+    places: web::Data<Places>,
+    _member: AuthenticatedMember,
+) -> Result<HttpResponse> {
+    let (lat, lng) = (query.lat, query.lng);
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+        return Ok(ErrorHandler::bad_request(
+            "위도/경도 범위가 올바르지 않습니다.",
+            Some(&format!("lat {}, lng {}", lat, lng)),
+            Some("위치 추천 - 요청 검증 실패")
+        ));
+    }
+    let radius_m = config.location_suggest_radius_m;
+    
+    let (nearby, spots, places) = futures_util::join!(
+        db.get_nearby_public_markers(lat, lng, radius_m, NEARBY_SUGGESTION_LIMIT),
+        db.get_popular_spots(lat, lng, radius_m, POPULAR_SPOT_MIN_MARKERS, POPULAR_SPOT_LIMIT),
+        places.nearby(lat, lng, radius_m),
+    );
+    let (nearby, spots) = match (nearby, spots) {
+        (Ok(nearby), Ok(spots)) => (nearby, spots),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("❌ 위치 추천 조회 실패: {}", e);
+            return Ok(ErrorHandler::internal_server_error(
+                "위치 추천 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ));
+        }
+    };
+    
+    let snap_radius_m = location_suggest::snap_radius_m(query.accuracy, config.location_snap_radius_m);
+    let snapped = location_suggest::snap_location(lat, lng, &places, &spots, snap_radius_m);
+    
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "data": {
+            "snapped": snapped,
+            "snapRadiusM": snap_radius_m,
+            "places": places,
+            "popularSpots": spots.iter().map(|spot| serde_json::json!({
+                "latitude": spot.latitude,
+                "longitude": spot.longitude,
+                "markerCount": spot.marker_count,
+                "totalLikes": spot.total_likes,
+                "neighborhood": spot.neighborhood,
+                "topEmotionTag": spot.top_emotion_tag,
+                "distanceM": spot.distance_m
+            })).collect::<Vec<_>>(),
+            "nearbyMarkers": nearby.iter().map(|marker| serde_json::json!({
+                "id": marker.id,
+                "description": marker.description,
+                "emotionTag": marker.emotion_tag,
+                "thumbnailImg": marker.thumbnail_img,
+                "likes": marker.likes,
+                "latitude": marker.latitude,
+                "longitude": marker.longitude,
+                "distanceM": marker.distance_m
+            })).collect::<Vec<_>>()
+        }
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct NearbyMarkersQuery {
+    lat: f64,
+    lng: f64,
+    radius_m: Option<f64>,
+    limit: Option<i64>,
+    emotion_tags: Option<String>,
+}
+
+/// 내 주변 마커: 좌표 반경 안의 마커를 가까운 순으로 (지도 영역 대신 거리 기준, 마커마다 distanceM)
+async fn get_nearby_markers(
+    db: web::Data<Database>,
+    query: web::Query<NearbyMarkersQuery>,
+    member: Option<AuthenticatedMember>,
+) -> Result<HttpResponse> {
+    let (lat, lng) = (query.lat, query.lng);
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+        return Ok(ErrorHandler::bad_request(
+            "위도/경도 범위가 올바르지 않습니다.",
+            Some(&format!("lat {}, lng {}", lat, lng)),
+            Some("주변 마커 - 요청 검증 실패")
+        ));
+    }
+    let radius_m = query.radius_m.unwrap_or(DEFAULT_NEARBY_RADIUS_M);
+    if !(radius_m > 0.0 && radius_m <= MAX_NEARBY_RADIUS_M) {
+        return Ok(ErrorHandler::bad_request(
+            &format!("radius_m은 0보다 크고 {}m 이하여야 합니다.", MAX_NEARBY_RADIUS_M),
+            Some(&format!("radius_m {}", radius_m)),
+            Some("주변 마커 - 요청 검증 실패")
+        ));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_NEARBY_LIMIT).clamp(1, MAX_NEARBY_LIMIT);
+    let emotion_tags = query.emotion_tags.as_ref().map(|tags| {
+        tags.split(',').map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect::<Vec<_>>()
+    });
+    let current_user_id = member.map(|member| member.member_id);
+    
+    info!("📍 주변 마커 조회: ({}, {}) 반경 {}m, 최대 {}개", lat, lng, radius_m, limit);
+    
+    match db.get_nearby_markers(lat, lng, radius_m, emotion_tags.as_deref(), current_user_id, limit).await {
+        Ok(hits) => {
+            let mut formatted_markers = Vec::with_capacity(hits.len());
+            for hit in &hits {
+                let images = match db.get_marker_images(hit.marker.id).await {
+                    Ok(images) => images,
+                    Err(e) => {
+                        warn!("⚠️ 마커 {} 이미지 조회 실패: {}", hit.marker.id, e);
+                        vec![]
+                    }
+                };
+                let formatted_images: Vec<MarkerImageDto> = images.iter().map(MarkerImageDto::from).collect();
+                let mut marker_data = serde_json::json!(MarkerDto::from(&hit.marker).with_images(formatted_images));
+                marker_data["distanceM"] = serde_json::json!(hit.distance_m);
+                marker_data["isMine"] = serde_json::json!(current_user_id.is_some() && hit.marker.member_id == current_user_id);
+                formatted_markers.push(marker_data);
+            }
+            
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": formatted_markers,
+                "count": hits.len(),
+                "radiusM": radius_m
+            })))
+        }
+        Err(e) => {
+            error!("❌ 주변 마커 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "주변 마커 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct HeatmapQuery {
+    lat: f64,
+    lng: f64,
+    lat_delta: f64,
+    lng_delta: f64,
+    zoom: Option<i32>,
+    emotion_tags: Option<String>,
+    from: Option<String>, // RFC 3339 또는 YYYY-MM-DD(UTC 자정), 이후 작성된 마커만
+    to: Option<String>, // 이 시각 전에 작성된 마커만
+}
+
+/// 감정 히트맵: 지도 영역의 공개 마커를 격자점별 개수와 세기(0~1)로 (클러스터와 같은 줌 → 해상도 표로 격자 크기 결정)
+async fn get_emotion_heatmap(
+    db: web::Data<Database>,
+    query: web::Query<HeatmapQuery>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    let (lat, lng, lat_delta, lng_delta) = (query.lat, query.lng, query.lat_delta, query.lng_delta);
+    if !((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng) && lat_delta > 0.0 && lng_delta > 0.0) {
+        return Ok(ErrorHandler::bad_request(
+            "지도 영역이 올바르지 않습니다.",
+            Some(&format!("lat {}, lng {}, lat_delta {}, lng_delta {}", lat, lng, lat_delta, lng_delta)),
+            Some("감정 히트맵 - 요청 검증 실패")
+        ));
+    }
+    if let Some(zoom) = query.zoom.filter(|zoom| !ZoomResolutions::is_valid_zoom(*zoom)) {
+        return Ok(ErrorHandler::bad_request(&format!("zoom은 0~22 사이여야 합니다: {}", zoom), None, None));
+    }
+    let mut range = [None, None];
+    for (slot, (name, raw)) in range.iter_mut().zip([("from", &query.from), ("to", &query.to)]) {
+        match raw.as_deref().map(parse_datetime_param) {
+            None => {}
+            Some(Some(value)) => *slot = Some(value),
+            Some(None) => {
+                return Ok(ErrorHandler::bad_request(
+                    &format!("{} 형식이 올바르지 않습니다", name),
+                    Some("RFC 3339(2024-01-01T00:00:00Z) 또는 YYYY-MM-DD 형식을 사용하세요"),
+                    None
+                ));
+            }
+        }
+    }
+    let [from, to] = range;
+    if let (Some(from), Some(to)) = (from, to)
+        && from >= to
+    {
+        return Ok(ErrorHandler::bad_request("from은 to보다 앞서야 합니다.", None, None));
+    }
+    let emotion_tags = query.emotion_tags.as_ref().map(|tags| {
+        tags.split(',').map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect::<Vec<_>>()
+    });
+    
+    let resolution = ZoomResolutions::from_config(&config).resolve(query.zoom, lat_delta, lng_delta);
+    let grid_degrees = cluster_zoom::aggregate_grid_degrees(resolution)
+        .max(lat_delta.max(lng_delta) / MAX_HEATMAP_GRID_CELLS);
+    let envelope = (lng - lng_delta / 2.0, lat - lat_delta / 2.0, lng + lng_delta / 2.0, lat + lat_delta / 2.0);
+    
+    match db.get_emotion_heatmap(envelope, grid_degrees, emotion_tags.as_deref(), from, to).await {
+        Ok(cells) => {
+            let max_count = cells.iter().map(|cell| cell.marker_count).max().unwrap_or(0);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": cells.iter().map(|cell| serde_json::json!({
+                    "latitude": cell.latitude,
+                    "longitude": cell.longitude,
+                    "count": cell.marker_count,
+                    "intensity": cell.marker_count as f64 / max_count.max(1) as f64
+                })).collect::<Vec<_>>(),
+                "count": cells.len(),
+                "maxCount": max_count,
+                "zoom": query.zoom,
+                "resolution": resolution,
+                "gridDegrees": grid_degrees
+            })))
+        }
+        Err(e) => {
+            error!("❌ 감정 히트맵 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "감정 히트맵 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MarkerStatsQuery {
+    from: Option<String>, // RFC 3339 또는 YYYY-MM-DD(UTC 자정), 포함
+    to: Option<String>, // 미포함, 기본 지금
+    interval: Option<String>, // day(기본) 또는 week
+    region_limit: Option<i64>,
+}
+
+/// 대시보드용 공개 마커 통계: 감정 태그별, 일/주별, 상위 지역(시도/시군구) 마커 수
+async fn get_marker_stats(
+    db: web::Data<Database>,
+    query: web::Query<MarkerStatsQuery>,
+    clock: web::Data<dyn Clock>,
+) -> Result<HttpResponse> {
+    let interval_raw = query.interval.as_deref().unwrap_or("day");
+    let Some(interval) = StatsInterval::parse(interval_raw) else {
+        return Ok(ErrorHandler::bad_request(
+            &format!("interval은 day 또는 week여야 합니다: {}", interval_raw),
+            None,
+            None
+        ));
+    };
+    let mut range = [None, None];
+    for (slot, (name, raw)) in range.iter_mut().zip([("from", &query.from), ("to", &query.to)]) {
+        match raw.as_deref().map(parse_datetime_param) {
+            None => {}
+            Some(Some(value)) => *slot = Some(value),
+            Some(None) => {
+                return Ok(ErrorHandler::bad_request(
+                    &format!("{} 형식이 올바르지 않습니다", name),
+                    Some("RFC 3339(2024-01-01T00:00:00Z) 또는 YYYY-MM-DD 형식을 사용하세요"),
+                    None
+                ));
+            }
+        }
+    }
+    let to = range[1].unwrap_or_else(|| clock.now());
+    let from = range[0].unwrap_or_else(|| to - interval.duration() * DEFAULT_STATS_PERIODS);
+    if from >= to {
+        return Ok(ErrorHandler::bad_request("from은 to보다 앞서야 합니다.", None, None));
+    }
+    if (to - from).num_seconds() > interval.duration().num_seconds() * MAX_STATS_PERIODS {
+        return Ok(ErrorHandler::bad_request(
+            &format!("조회 기간은 최대 {}{}입니다.", MAX_STATS_PERIODS, if interval == StatsInterval::Day { "일" } else { "주" }),
+            Some("기간을 줄이거나 interval=week를 사용하세요"),
+            None
+        ));
+    }
+    let region_limit = query.region_limit.unwrap_or(DEFAULT_STATS_REGION_LIMIT).clamp(1, MAX_STATS_REGION_LIMIT);
+    
+    match db.get_marker_stats(from, to, interval, region_limit).await {
+        Ok(stats) => {
+            let total = stats.total_markers;
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "data": {
+                    "totalMarkers": total,
+                    "byEmotion": stats.by_emotion.iter().map(|(tag, count)| serde_json::json!({
+                        "emotionTag": tag,
+                        "count": count,
+                        "ratio": *count as f64 / total.max(1) as f64
+                    })).collect::<Vec<_>>(),
+                    "timeline": stats.timeline.iter().map(|period| serde_json::json!({
+                        "period": period.period,
+                        "count": period.marker_count
+                    })).collect::<Vec<_>>(),
+                    "topRegions": stats.top_regions.iter().map(|region| serde_json::json!({
+                        "region": region.region,
+                        "locality": region.locality,
+                        "count": region.marker_count
+                    })).collect::<Vec<_>>()
+                },
+                "interval": interval.as_str(),
+                "from": from,
+                "to": to
+            })))
+        }
+        Err(e) => {
+            error!("❌ 마커 통계 조회 실패: {}", e);
+            Ok(ErrorHandler::internal_server_error(
+                "마커 통계 조회 실패",
+                Some(&format!("데이터베이스 오류: {}", e))
+            ))
+        }
+    }
+}
+
 // 새로운 좋아요 테이블을 사용하는 API 엔드포인트들
 
 #[derive(Deserialize)]
@@ -3301,22 +9804,12 @@ async fn toggle_like_new(
     db: web::Data<Database>,
     path: web::Path<i64>,
     payload: web::Json<ToggleLikeRequest>,
-    config: web::Data<Config>,
-    req: actix_web::HttpRequest,
+    member: AuthenticatedMember,
 ) -> Result<HttpResponse> {
     let marker_id = path.into_inner();
     let like_type = &payload.like_type;
     
-    // JWT 토큰에서 사용자 ID 추출
-    let user_id = match extract_user_id_from_token(&req, &config) {
-        Ok(uid) => uid,
-        Err(_) => {
-            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                "success": false,
-                "message": "로그인이 필요합니다."
-            })));
-        }
-    };
+    let user_id = member.member_id;
 
     info!("👍 새로운 좋아요 토글 요청: 마커 {}, 사용자 {}, 타입 {}", marker_id, user_id, like_type);
 
@@ -3347,21 +9840,11 @@ async fn toggle_like_new(
 async fn get_like_status(
     db: web::Data<Database>,
     path: web::Path<i64>,
-    config: web::Data<Config>,
-    req: actix_web::HttpRequest,
+    member: AuthenticatedMember,
 ) -> Result<HttpResponse> {
     let marker_id = path.into_inner();
     
-    // JWT 토큰에서 사용자 ID 추출
-    let user_id = match extract_user_id_from_token(&req, &config) {
-        Ok(uid) => uid,
-        Err(_) => {
-            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                "success": false,
-                "message": "로그인이 필요합니다."
-            })));
-        }
-    };
+    let user_id = member.member_id;
 
     info!("🔍 좋아요 상태 조회: 마커 {}, 사용자 {}", marker_id, user_id);
 
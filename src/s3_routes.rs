@@ -1,14 +1,24 @@
+// 이미지 업로드 (/api/images와 /api/s3 업로드가 같은 코드로 설정된 저장소(S3 또는 로컬)에 저장)
 use actix_web::{web, HttpResponse, Result};
 use actix_multipart::Multipart;
 use futures_util::stream::StreamExt;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use log::{info, error};
+use log::{info, warn, error};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-use crate::image_processor::ImageProcessor;
+use crate::image_cleanup;
+use crate::error_handler::ErrorHandler;
+use crate::image_pipeline::{ImageHooks, ImageSource, PhotoExif, SharedImageSource};
+use crate::image_workers::ImageProcessingTimeout;
+use crate::image_processor::{ImageProcessor, OutputFormat, RESPONSIVE_WIDTHS, create_thumbnail_processor};
 use crate::config::Config;
-use crate::s3_service::S3Service;
+use crate::database::Database;
+use crate::moderation::ModerationStatus;
+use crate::storage::{ObjectKeys, Storage};
+use crate::upload_spool::{receive_field, stream_field_to_storage, SpoolError};
 
 #[derive(Serialize, Deserialize)]
 pub struct S3ImageResponse {
@@ -20,21 +30,68 @@ pub struct S3ImageResponse {
     pub height: Option<u32>,
     pub format: Option<String>,
     pub s3_url: Option<String>,
+    pub thumbnail_url: Option<String>, // 서버에서 함께 생성한 썸네일 (마커 이미지 업로드 시)
+    pub original_url: Option<String>, // 변환 전 원본 (originals/)
+    pub url: Option<String>, // s3_url과 같은 값 (/api/images 업로드 응답과 같은 이름)
+    pub variants: Option<BTreeMap<u32, String>>, // 너비별 이미지 URL ({"256": url, "800": url, ...})
+    pub srcset: Option<String>, // <img srcset>에 그대로 쓸 수 있는 "url 256w, url 800w" 형식
+    pub exif: Option<PhotoExif>, // ?exif=true일 때 사진의 촬영 위치/시간 (마커 위치/시간 추천용)
+    pub blurhash: Option<String>, // 변환된 이미지의 BlurHash 자리표시자
+    pub moderation: Option<ModerationStatus>, // 이미지 검수 결과 (MODERATION_BACKEND가 none이면 없음, rejected면 마커에 붙여도 공개되지 않음)
 }
 
-// S3 업로드 내부 함수들
-pub async fn upload_image_s3(
+/// 업로드 쿼리 (?exif=true면 사진 EXIF의 GPS 좌표와 촬영 시간을 응답에 포함,
+/// ?output_format=avif면 변환 결과를 WebP 대신 AVIF로 저장)
+#[derive(Debug, Default, Deserialize)]
+pub struct UploadQuery {
+    #[serde(default)]
+    pub exif: bool,
+    #[serde(default)]
+    pub output_format: OutputFormat,
+}
+
+/// 업로드할 이미지 종류와 옵션
+pub struct UploadTarget<'a> {
+    pub image_type: &'a str,
+    pub extract_exif: bool,
+}
+
+/// 변환된 이미지를 저장할 (폴더, 파일 이름 접두사)
+fn processed_key_parts(image_type: &str) -> (&'static str, &'static str) {
+    match image_type {
+        "map" => ("maps", "map"),
+        _ => ("markers", "thumbnail"),
+    }
+}
+
+/// 썸네일/지도 업로드 프로세서 (크기와 품질은 종류별 설정, 이어 받는 업로드도 같은 설정)
+pub fn upload_processor(image_type: &str, config: &Config, hooks: &ImageHooks, output_format: OutputFormat) -> ImageProcessor {
+    let (max_width, max_height, quality) = match image_type {
+        "map" => (config.map_max_width, config.map_max_height, config.map_quality),
+        _ => (config.thumbnail_max_width, config.thumbnail_max_height, config.thumbnail_quality),
+    };
+    ImageProcessor::new(max_width, max_height, quality)
+        .with_hooks(hooks)
+        .with_output_format(output_format, config.avif_quality)
+        .with_lossless_png(config.png_lossless)
+}
+
+/// 원본은 받는 대로 저장소(originals/, S3는 멀티파트 업로드)와 임시 파일로 보내고, 이미지 처리는 임시 파일에서 바로 디코딩
+/// (30MB 업로드도 원본 전체를 메모리에 모으지 않음)
+pub async fn upload_image_to_storage(
     mut payload: Multipart, 
-    image_type: &str, 
+    target: UploadTarget<'_>, 
     processor: ImageProcessor,
     pool: web::Data<PgPool>,
     config: web::Data<Config>,
-    s3_service: web::Data<S3Service>
+    storage: web::Data<dyn Storage>,
+    keys: web::Data<ObjectKeys>,
 ) -> Result<HttpResponse> {
     let start_time = Instant::now();
-    info!("🚀 S3 업로드 시작...");
+    let image_type = target.image_type;
+    info!("🚀 이미지 업로드 시작 (저장소: {})...", storage.name());
     
-    let mut image_data = Vec::new();
+    let mut received = None;
     let mut filename = String::new();
     
     // 멀티파트 데이터 처리
@@ -59,41 +116,52 @@ pub async fn upload_image_s3(
                             height: None,
                             format: None,
                             s3_url: None,
+                            thumbnail_url: None,
+                            original_url: None,
+                            url: None,
+                            variants: None,
+                            srcset: None,
+                            exif: None,
+                            blurhash: None,
+                            moderation: None,
                         }));
                     }
                 }
                 
-                // 이미지 데이터 수집
-                let mut chunk_count = 0;
-                let mut last_log_time = Instant::now();
-                while let Some(chunk) = field.next().await {
-                    let data = chunk.map_err(|e| {
-                        actix_web::error::ErrorInternalServerError(format!("파일 읽기 실패: {}", e))
-                    })?;
-                    image_data.extend_from_slice(&data);
-                    chunk_count += 1;
-                    
-                    // 큰 파일(5MB 이상)인 경우에만 진행 상황 로그
-                    let current_size_mb = image_data.len() as f64 / (1024.0 * 1024.0);
-                    if current_size_mb > 5.0 {
-                        let now = Instant::now();
-                        if now.duration_since(last_log_time).as_secs() >= 1 {
-                            info!("📦 청크 수신: {}개 (현재 크기: {:.2}MB)", 
-                                  chunk_count, 
-                                  current_size_mb);
-                            last_log_time = now;
-                        }
+                // 원본 저장과 임시 파일 수신을 함께 진행
+                let extension = Path::new(&filename).extension().and_then(|ext| ext.to_str()).unwrap_or("bin").to_lowercase();
+                let content_type = field.content_type().map_or("application/octet-stream".to_string(), |mime| mime.to_string());
+                let original_key = keys.new_key(&format!("originals/{}", image_type), "original", &extension);
+                let mut upload = match storage.start_upload(&original_key, &content_type).await {
+                    Ok(upload) => upload,
+                    Err(e) => return Ok(storage_failed(storage.get_ref(), &e)),
+                };
+                let spool = match stream_field_to_storage(&mut field, &config, upload.as_mut(), |head| processor.check_image_content(&filename, head)).await {
+                    Ok(spool) => spool,
+                    Err(e) => {
+                        upload.abort().await;
+                        return Ok(e.into_response());
                     }
+                };
+                if spool.is_empty() {
+                    upload.abort().await;
+                    break;
                 }
-                let final_size_mb = image_data.len() as f64 / (1024.0 * 1024.0);
+                let original_url = match upload.complete().await {
+                    Ok(url) => url,
+                    Err(e) => return Ok(storage_failed(storage.get_ref(), &e)),
+                };
+                let final_size_mb = spool.len() as f64 / (1024.0 * 1024.0);
                 if final_size_mb > 1.0 {
                     info!("✅ 파일 데이터 수신 완료: {:.2}MB", final_size_mb);
                 }
+                received = Some((spool, original_key, original_url));
+                break;
             }
         }
     }
     
-    if image_data.is_empty() {
+    let Some((mut spool, original_key, original_url)) = received else {
         return Ok(HttpResponse::BadRequest().json(S3ImageResponse {
             success: false,
             message: "이미지 파일이 필요합니다".to_string(),
@@ -103,45 +171,104 @@ pub async fn upload_image_s3(
             height: None,
             format: None,
             s3_url: None,
+            thumbnail_url: None,
+            original_url: None,
+            url: None,
+            variants: None,
+            srcset: None,
+            exif: None,
+            blurhash: None,
+            moderation: None,
         }));
-    }
+    };
+    let file_size = spool.len();
+    let file_size_mb = file_size as f64 / (1024.0 * 1024.0);
+    info!("📊 파일 크기: {:.2}MB, 제한: {:.2}MB", file_size_mb, config.max_file_size_mb);
+    let path = match spool.flush().await {
+        Ok(Some(path)) => path.to_path_buf(),
+        Ok(None) => {
+            discard_original(storage.get_ref(), &original_key).await;
+            return Ok(SpoolError::Io(std::io::Error::other("임시 파일이 없습니다")).into_response());
+        }
+        Err(e) => {
+            discard_original(storage.get_ref(), &original_key).await;
+            return Ok(e.into_response());
+        }
+    };
     
-    // 파일 크기 검증
-    let file_size_mb = processor.get_file_size_mb(&image_data);
-    let max_size_mb = config.max_file_size_mb;
-    info!("📊 파일 크기: {:.2}MB, 제한: {:.2}MB", file_size_mb, max_size_mb);
+    let db = Database { pool: pool.get_ref().clone() };
+    let original = ReceivedOriginal { path, filename, size: file_size, key: original_key, url: original_url };
+    let response = match process_received_original(original, target, &processor, &db, &config, storage.get_ref(), &keys).await {
+        Ok(response) => response,
+        Err(response) => return Ok(response),
+    };
     
-    if file_size_mb > max_size_mb {
-        return Ok(HttpResponse::BadRequest().json(S3ImageResponse {
-            success: false,
-            message: format!("파일 크기는 {:.0}MB를 초과할 수 없습니다 (현재: {:.2}MB)", max_size_mb, file_size_mb),
-            filename: None,
-            size_mb: None,
-            width: None,
-            height: None,
-            format: None,
-            s3_url: None,
-        }));
-    }
+    let total_time = start_time.elapsed();
+    info!("🎉 전체 업로드 완료: {:.2}초", total_time.as_secs_f64());
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// 저장소에 올린 원본과 처리할 임시 파일
+pub struct ReceivedOriginal {
+    pub path: PathBuf,
+    pub filename: String,
+    pub size: usize,
+    pub key: String,
+    pub url: String,
+}
+
+/// 받은 원본을 변환해 저장하고 품질 정보, 썸네일, 너비별 이미지, 검수까지 처리 (일반 업로드와 이어 받는 업로드 공용)
+/// 변환/저장에 실패하면 원본을 지우고 보낼 오류 응답 반환
+pub async fn process_received_original(
+    original: ReceivedOriginal,
+    target: UploadTarget<'_>,
+    processor: &ImageProcessor,
+    db: &Database,
+    config: &Config,
+    storage: &dyn Storage,
+    keys: &ObjectKeys,
+) -> std::result::Result<S3ImageResponse, HttpResponse> {
+    let ReceivedOriginal { path, filename, size: file_size, key: original_key, url: original_url } = original;
+    let file_size_mb = file_size as f64 / (1024.0 * 1024.0);
+    let image_type = target.image_type;
     
-    // 이미지 처리 (리사이즈 + WebP 변환)
-    let file_size_mb = processor.get_file_size_mb(&image_data);
+    // 촬영 위치/시간은 변환(메타데이터 제거) 전 원본에서 읽음, 실패해도 업로드는 계속
+    let exif = if target.extract_exif {
+        match ImageSource::File(&path).read_exif() {
+            Ok(exif) => Some(exif),
+            Err(e) => {
+                warn!("⚠️ EXIF 읽기 실패: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    
+    // 이미지 처리 (리사이즈 + WebP/AVIF 변환)
     if file_size_mb > 1.0 {
-        info!("🖼️ 이미지 처리 시작 (리사이즈 + WebP 변환)...");
+        info!("🖼️ 이미지 처리 시작 (리사이즈 + {} 변환)...", processor.output_format().extension());
     }
     let process_start = Instant::now();
-    let processed_data = match processor.process_image(&image_data) {
-        Ok(data) => {
+    let source = SharedImageSource::File(path.clone());
+    let pipeline_source = source.clone();
+    let processed = processor.run_blocking(move |processor| processor.run_source_pipeline(pipeline_source.as_source())).await;
+    let (processed_data, quality, blurhash) = match processed {
+        Ok(output) => {
             let process_time = process_start.elapsed();
             if file_size_mb > 1.0 {
                 info!("✅ 이미지 처리 완료: {:.2}초 (처리된 크기: {:.2}MB)", 
                       process_time.as_secs_f64(), 
-                      data.len() as f64 / (1024.0 * 1024.0));
+                      output.data.len() as f64 / (1024.0 * 1024.0));
             }
-            data
+            (output.data, output.quality, output.blurhash)
         },
         Err(e) => {
-            return Ok(HttpResponse::InternalServerError().json(S3ImageResponse {
+            discard_original(storage, &original_key).await;
+            if e.is::<ImageProcessingTimeout>() {
+                return Err(processing_timed_out(config, &e));
+            }
+            return Err(HttpResponse::InternalServerError().json(S3ImageResponse {
                 success: false,
                 message: format!("이미지 처리 실패: {}", e),
                 filename: None,
@@ -150,62 +277,259 @@ pub async fn upload_image_s3(
                 height: None,
                 format: None,
                 s3_url: None,
+                thumbnail_url: None,
+                original_url: None,
+                url: None,
+                variants: None,
+                srcset: None,
+                exif: None,
+                blurhash: None,
+                moderation: None,
             }));
         }
     };
     
-    // S3 업로드
-    info!("☁️ S3 업로드 시작...");
+    // 변환된 이미지 저장
+    info!("☁️ 저장소 업로드 시작...");
     let upload_start = Instant::now();
-    let s3_url = match s3_service.upload_thumbnail(processed_data, &filename).await {
+    let (dir, prefix) = processed_key_parts(image_type);
+    let output_format = processor.output_format();
+    let s3_url = match storage.put(&keys.new_key(dir, prefix, output_format.extension()), processed_data, output_format.content_type()).await {
         Ok(url) => {
             let upload_time = upload_start.elapsed();
-            info!("✅ S3 업로드 완료: {:.2}초", upload_time.as_secs_f64());
+            info!("✅ 저장소 업로드 완료: {:.2}초", upload_time.as_secs_f64());
             url
         },
         Err(e) => {
-            return Ok(HttpResponse::InternalServerError().json(S3ImageResponse {
-                success: false,
-                message: format!("S3 업로드 실패: {}", e),
-                filename: None,
-                size_mb: None,
-                width: None,
-                height: None,
-                format: None,
-                s3_url: None,
-            }));
+            discard_original(storage, &original_key).await;
+            return Err(storage_failed(storage, &e));
         }
     };
     
+    // 대표 이미지 자동 선정을 위한 품질 정보와 자리표시자 저장 (실패해도 업로드는 성공 처리)
+    if let Err(e) = db.save_image_quality(&s3_url, quality.width, quality.height, quality.sharpness, file_size as i64, &blurhash).await {
+        warn!("⚠️ 이미지 품질 정보 저장 실패: {}", e);
+    }
+    // 이미지가 삭제되면 원본도 함께 정리되도록 연결
+    if let (Some(storage_key), Some(original_key)) = (image_cleanup::storage_key(&s3_url), image_cleanup::storage_key(&original_url))
+        && let Err(e) = db.save_image_original(&storage_key, &original_key).await
+    {
+        warn!("⚠️ 원본 연결 정보 저장 실패: {}", e);
+    }
+    
+    // 마커 이미지 업로드는 썸네일도 서버에서 생성해 원본과 연결 (실패해도 업로드는 성공 처리)
+    let thumbnail_url = if image_type == "thumbnail" {
+        create_marker_thumbnail_variant(db, storage, keys, processor, source.clone(), &s3_url).await
+    } else {
+        None
+    };
+    let variants = create_responsive_variants(db, storage, keys, processor, source.clone(), &s3_url, image_type).await;
+    
+    // 검수 (거절돼도 업로드는 성공 처리, 마커에 붙이면 내려간 상태로 저장됨)
+    let moderation = moderate_upload(db, processor, &s3_url, thumbnail_url.as_deref(), &variants, source).await;
+    
     // 이미지 정보 가져오기
-    let (width, height, format) = match processor.get_image_info(&image_data) {
+    let (width, height, format) = match processor.get_file_image_info(&path) {
         Ok(info) => (Some(info.0), Some(info.1), info.2),
         Err(_) => (None, None, "Unknown".to_string()),
     };
     
-    let file_size_mb = processor.get_file_size_mb(&image_data);
-    let total_time = start_time.elapsed();
-    info!("🎉 전체 업로드 완료: {:.2}초", total_time.as_secs_f64());
-    
-    Ok(HttpResponse::Ok().json(S3ImageResponse {
+    Ok(S3ImageResponse {
         success: true,
-        message: "S3 업로드 성공".to_string(),
+        message: "이미지 업로드 성공".to_string(),
         filename: Some(filename),
         size_mb: Some(file_size_mb),
         width,
         height,
         format: Some(format),
+        url: Some(s3_url.clone()),
         s3_url: Some(s3_url),
-    }))
+        thumbnail_url,
+        original_url: Some(original_url),
+        srcset: Some(srcset(&variants)),
+        variants: Some(variants),
+        exif,
+        blurhash: Some(blurhash),
+        moderation,
+    })
+}
+
+pub(crate) fn storage_failed(storage: &dyn Storage, e: &anyhow::Error) -> HttpResponse {
+    let action = match storage.name() {
+        "s3" => "S3 업로드",
+        _ => "파일 저장",
+    };
+    HttpResponse::InternalServerError().json(S3ImageResponse {
+        success: false,
+        message: format!("{} 실패: {}", action, e),
+        filename: None,
+        size_mb: None,
+        width: None,
+        height: None,
+        format: None,
+        s3_url: None,
+        thumbnail_url: None,
+        original_url: None,
+        url: None,
+        variants: None,
+        srcset: None,
+        exif: None,
+        blurhash: None,
+        moderation: None,
+    })
 }
 
-pub async fn upload_circular_thumbnail_s3_internal(
+/// 이미지 처리가 IMAGE_PROCESSING_TIMEOUT_SECS 안에 끝나지 않음 (대기 중인 처리가 많을 때)
+fn processing_timed_out(config: &Config, e: &anyhow::Error) -> HttpResponse {
+    ErrorHandler::service_unavailable(
+        "이미지 처리 요청이 많아 시간이 초과되었습니다. 잠시 후 다시 시도해주세요",
+        Some(&e.to_string()),
+        config.upload_retry_after_secs,
+    )
+}
+
+/// 처리에 실패한 업로드의 원본 삭제 (실패해도 로그만 남김)
+async fn discard_original(storage: &dyn Storage, original_key: &str) {
+    if let Err(e) = storage.delete(original_key).await {
+        warn!("⚠️ 원본 삭제 실패: {} - {}", original_key, e);
+    }
+}
+
+/// 마커 이미지용 소형 썸네일 생성 후 원본 URL과 연결 (실패 시 None)
+async fn create_marker_thumbnail_variant(
+    db: &Database,
+    storage: &dyn Storage,
+    keys: &ObjectKeys,
+    processor: &ImageProcessor,
+    source: SharedImageSource,
+    s3_url: &str,
+) -> Option<String> {
+    // 크기만 작고 품질/무손실 설정은 업로드 프로세서를 따름 (THUMBNAIL_QUALITY, PNG_LOSSLESS)
+    let thumbnail_processor = create_thumbnail_processor()
+        .with_quality(processor.quality)
+        .with_lossless_png(processor.lossless_png());
+    let thumbnail = processor.hooks().workers().run(move || thumbnail_processor.process_source(source.as_source())).await;
+    match thumbnail {
+        Ok(thumbnail_data) => match storage.put(&keys.new_key("markers/thumbs", "thumbnail", "webp"), thumbnail_data, "image/webp").await {
+            Ok(url) => {
+                if let Err(e) = db.save_image_variant(s3_url, &url).await {
+                    warn!("⚠️ 썸네일 연결 정보 저장 실패: {}", e);
+                }
+                Some(url)
+            }
+            Err(e) => {
+                warn!("⚠️ 썸네일 저장 실패: {}", e);
+                None
+            }
+        },
+        Err(e) => {
+            warn!("⚠️ 썸네일 생성 실패: {}", e);
+            None
+        }
+    }
+}
+
+/// 너비별(반응형) 이미지 생성 후 변환된 이미지와 연결, 저장에 성공한 것만 {너비: URL}로 반환
+/// (실패해도 업로드는 성공 처리, 원본보다 큰 너비는 만들지 않음)
+async fn create_responsive_variants(
+    db: &Database,
+    storage: &dyn Storage,
+    keys: &ObjectKeys,
+    processor: &ImageProcessor,
+    source: SharedImageSource,
+    s3_url: &str,
+    image_type: &str,
+) -> BTreeMap<u32, String> {
+    let mut urls = BTreeMap::new();
+    let variants = processor.run_blocking(move |processor| processor.process_responsive_variants(source.as_source(), RESPONSIVE_WIDTHS)).await;
+    let variants = match variants {
+        Ok(variants) => variants,
+        Err(e) => {
+            warn!("⚠️ 반응형 이미지 생성 실패: {}", e);
+            return urls;
+        }
+    };
+    let storage_key = image_cleanup::storage_key(s3_url);
+    let (dir, prefix) = processed_key_parts(image_type);
+    let output_format = processor.output_format();
+    for variant in variants {
+        let key = keys.new_key(&format!("{}/w{}", dir, variant.width), prefix, output_format.extension());
+        let url = match storage.put(&key, variant.data, output_format.content_type()).await {
+            Ok(url) => url,
+            Err(e) => {
+                warn!("⚠️ 반응형 이미지 저장 실패 ({}w): {}", variant.width, e);
+                continue;
+            }
+        };
+        if let (Some(storage_key), Some(variant_key)) = (&storage_key, image_cleanup::storage_key(&url))
+            && let Err(e) = db.save_image_size_variant(storage_key, variant.width, variant.height, &url, &variant_key).await
+        {
+            warn!("⚠️ 반응형 이미지 연결 정보 저장 실패: {}", e);
+        }
+        urls.insert(variant.width, url);
+    }
+    urls
+}
+
+/// {너비: URL} → "url 256w, url 800w"
+fn srcset(variants: &BTreeMap<u32, String>) -> String {
+    variants.iter()
+        .map(|(width, url)| format!("{} {}w", url, width))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 변환된 이미지 검수 (검수기가 없으면 None), 썸네일/너비별 이미지도 같은 결과를 따름
+async fn moderate_upload(
+    db: &Database,
+    processor: &ImageProcessor,
+    s3_url: &str,
+    thumbnail_url: Option<&str>,
+    variants: &BTreeMap<u32, String>,
+    source: SharedImageSource,
+) -> Option<ModerationStatus> {
+    let storage_key = image_cleanup::storage_key(s3_url)?;
+    let derived_keys: Vec<String> = thumbnail_url.into_iter()
+        .chain(variants.values().map(String::as_str))
+        .filter_map(image_cleanup::storage_key)
+        .collect();
+    processor.hooks().moderation().review(db, processor.hooks().workers(), &storage_key, &derived_keys, source).await
+}
+
+/// 외부에서 가져온 이미지를 마커 이미지 업로드와 같은 파이프라인으로 저장소에 재호스팅
+/// (리사이즈 + WebP/AVIF 변환, 품질 정보 저장, 썸네일/너비별 이미지 생성, 검수). (이미지 URL, 썸네일 URL) 반환
+pub async fn rehost_marker_image(
+    image_data: &[u8],
+    processor: &ImageProcessor,
+    db: &Database,
+    storage: &dyn Storage,
+    keys: &ObjectKeys,
+) -> anyhow::Result<(String, Option<String>)> {
+    let source = SharedImageSource::from(ImageSource::Memory(image_data));
+    let pipeline_source = source.clone();
+    let output = processor.run_blocking(move |processor| processor.run_source_pipeline(pipeline_source.as_source())).await?;
+    let output_format = processor.output_format();
+    let s3_url = storage.put(&keys.new_key("markers", "thumbnail", output_format.extension()), output.data, output_format.content_type()).await?;
+    info!("☁️ 외부 이미지 재호스팅 완료: {}", s3_url);
+    
+    let quality = output.quality;
+    if let Err(e) = db.save_image_quality(&s3_url, quality.width, quality.height, quality.sharpness, image_data.len() as i64, &output.blurhash).await {
+        warn!("⚠️ 이미지 품질 정보 저장 실패: {}", e);
+    }
+    let thumbnail_url = create_marker_thumbnail_variant(db, storage, keys, processor, source.clone(), &s3_url).await;
+    let variants = create_responsive_variants(db, storage, keys, processor, source.clone(), &s3_url, "thumbnail").await;
+    moderate_upload(db, processor, &s3_url, thumbnail_url.as_deref(), &variants, source).await;
+    Ok((s3_url, thumbnail_url))
+}
+
+/// 원형 썸네일 (크롭 + 원형 마스킹, thumbnails/{종류}_...webp)
+pub async fn upload_circular_thumbnail_to_storage(
     mut payload: Multipart, 
     image_type: &str, 
     processor: ImageProcessor,
-    pool: web::Data<PgPool>,
     config: web::Data<Config>,
-    s3_service: web::Data<S3Service>
+    storage: web::Data<dyn Storage>,
+    keys: web::Data<ObjectKeys>,
 ) -> Result<HttpResponse> {
     let mut image_data = Vec::new();
     let mut filename = String::new();
@@ -230,16 +554,27 @@ pub async fn upload_circular_thumbnail_s3_internal(
                             height: None,
                             format: None,
                             s3_url: None,
+                            thumbnail_url: None,
+                            original_url: None,
+                            url: None,
+                            variants: None,
+                            srcset: None,
+                            exif: None,
+                            blurhash: None,
+                            moderation: None,
                         }));
                     }
                 }
                 
-                // 이미지 데이터 수집
-                while let Some(chunk) = field.next().await {
-                    let data = chunk.map_err(|e| {
-                        actix_web::error::ErrorInternalServerError(format!("파일 읽기 실패: {}", e))
-                    })?;
-                    image_data.extend_from_slice(&data);
+                // 이미지 데이터 수집 (임계값을 넘으면 임시 파일로 받음)
+                image_data = match receive_field(&mut field, &config).await {
+                    Ok(data) => data,
+                    Err(e) => return Ok(e.into_response()),
+                };
+                if !image_data.is_empty()
+                    && let Err(e) = processor.check_image_content(&filename, &image_data)
+                {
+                    return Ok(SpoolError::UnsupportedContent(e).into_response());
                 }
             }
         }
@@ -255,6 +590,14 @@ pub async fn upload_circular_thumbnail_s3_internal(
             height: None,
             format: None,
             s3_url: None,
+            thumbnail_url: None,
+            original_url: None,
+            url: None,
+            variants: None,
+            srcset: None,
+            exif: None,
+            blurhash: None,
+            moderation: None,
         }));
     }
     
@@ -273,12 +616,25 @@ pub async fn upload_circular_thumbnail_s3_internal(
             height: None,
             format: None,
             s3_url: None,
+            thumbnail_url: None,
+            original_url: None,
+            url: None,
+            variants: None,
+            srcset: None,
+            exif: None,
+            blurhash: None,
+            moderation: None,
         }));
     }
     
-    // 원형 썸네일 처리 (크롭 + 원형 마스킹 + WebP 변환)
-    let processed_data = match processor.process_circular_thumbnail(&image_data) {
-        Ok(data) => data,
+    // 원형 썸네일 처리 (크롭 + 원형 마스킹 + WebP 변환), 원본 정보도 같은 블로킹 작업에서 읽음
+    let processed = processor.run_blocking(move |processor| {
+        let processed_data = processor.process_circular_thumbnail(&image_data)?;
+        Ok((processed_data, processor.get_image_info(&image_data)))
+    }).await;
+    let (processed_data, image_info) = match processed {
+        Ok(processed) => processed,
+        Err(e) if e.is::<ImageProcessingTimeout>() => return Ok(processing_timed_out(&config, &e)),
         Err(e) => {
             return Ok(HttpResponse::InternalServerError().json(S3ImageResponse {
                 success: false,
@@ -289,43 +645,46 @@ pub async fn upload_circular_thumbnail_s3_internal(
                 height: None,
                 format: None,
                 s3_url: None,
+                thumbnail_url: None,
+                original_url: None,
+                url: None,
+                variants: None,
+                srcset: None,
+                exif: None,
+                blurhash: None,
+                moderation: None,
             }));
         }
     };
     
-    // S3 업로드
-    let s3_url = match s3_service.upload_circular_thumbnail(processed_data, &filename).await {
+    // 저장소 업로드
+    let s3_url = match storage.put(&keys.new_key("thumbnails", image_type, "webp"), processed_data, "image/webp").await {
         Ok(url) => url,
-        Err(e) => {
-            return Ok(HttpResponse::InternalServerError().json(S3ImageResponse {
-                success: false,
-                message: format!("S3 업로드 실패: {}", e),
-                filename: None,
-                size_mb: None,
-                width: None,
-                height: None,
-                format: None,
-                s3_url: None,
-            }));
-        }
+        Err(e) => return Ok(storage_failed(storage.get_ref(), &e)),
     };
     
     // 이미지 정보 가져오기
-    let (width, height, format) = match processor.get_image_info(&image_data) {
+    let (width, height, format) = match image_info {
         Ok(info) => (Some(info.0), Some(info.1), info.2),
         Err(_) => (None, None, "Unknown".to_string()),
     };
     
-    let file_size_mb = processor.get_file_size_mb(&image_data);
-    
     Ok(HttpResponse::Ok().json(S3ImageResponse {
         success: true,
-        message: "S3 원형 썸네일 업로드 성공".to_string(),
+        message: "원형 썸네일 업로드 성공".to_string(),
         filename: Some(filename),
         size_mb: Some(file_size_mb),
         width,
         height,
         format: Some(format),
+        url: Some(s3_url.clone()),
         s3_url: Some(s3_url),
+        thumbnail_url: None,
+        original_url: None,
+        variants: None,
+        srcset: None,
+        exif: None,
+        blurhash: None,
+        moderation: None,
     }))
 } 
\ No newline at end of file
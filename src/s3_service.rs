@@ -1,71 +1,109 @@
-use rusoto_core::{Region, HttpClient};
-use rusoto_credential::{StaticProvider, ProvideAwsCredentials};
-use rusoto_s3::{S3Client, S3, PutObjectRequest};
+use aws_config::{retry::RetryConfig, BehaviorVersion, Region};
+use aws_sdk_s3::config::{Credentials, SharedCredentialsProvider};
+use aws_sdk_s3::error::DisplayErrorContext;
+use aws_sdk_s3::presigning::PresigningConfig;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
+use aws_sdk_s3::Client;
 use anyhow::Result;
-use log::{info, error};
-use std::path::Path;
-use uuid::Uuid;
-use chrono::Utc;
+use log::{info, warn, error};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::clock::{Clock, IdGenerator, RandomIdGenerator, SystemClock};
+
+/// 멀티파트 업로드 파트 크기 (S3 최소 크기 5MB, 마지막 파트만 더 작을 수 있음)
+const MULTIPART_PART_SIZE: usize = 5 * 1024 * 1024;
 
 #[derive(Clone)]
 pub struct S3Service {
-    client: S3Client,
+    client: Client,
     bucket_name: String,
     region: String,
+    credentials: Option<SharedCredentialsProvider>, // 다른 AWS 서비스(Rekognition 등) 요청 서명에도 같은 자격 증명 사용
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
 }
 
 impl S3Service {
+    /// 액세스 키가 비어 있으면 기본 자격 증명 체인 사용
+    /// (환경 변수, ~/.aws 프로필, 웹 ID 토큰, ECS 작업 역할, EC2 인스턴스 프로필 순서)
     pub async fn new(bucket_name: String, region: String, access_key: String, secret_key: String) -> Result<Self> {
-        let credentials = StaticProvider::new_minimal(access_key, secret_key);
-        let region_name = region.clone();
-        
-        // 리전별 엔드포인트 설정
-        let region = match region_name.as_str() {
-            "us-east-1" => Region::UsEast1,
-            "us-west-1" => Region::UsWest1,
-            "us-west-2" => Region::UsWest2,
-            "eu-west-1" => Region::EuWest1,
-            "eu-central-1" => Region::EuCentral1,
-            "ap-southeast-1" => Region::ApSoutheast1,
-            "ap-southeast-2" => Region::ApSoutheast2,
-            "ap-northeast-1" => Region::ApNortheast1,
-            "ap-northeast-2" => Region::ApNortheast2,
-            "sa-east-1" => Region::SaEast1,
-            _ => Region::Custom {
-                name: region_name.clone(),
-                endpoint: format!("https://s3.{}.amazonaws.com", region_name),
-            }
-        };
-        
-        // HTTP 클라이언트 설정 개선
-        let http_client = HttpClient::new()?;
-        
-        let client = S3Client::new_with(http_client, credentials, region);
+        let mut loader = aws_config::defaults(BehaviorVersion::latest()).region(Region::new(region.clone()));
+        if !access_key.is_empty() && !secret_key.is_empty() {
+            loader = loader.credentials_provider(Credentials::new(access_key, secret_key, None, None, "bigpicture-static"));
+            info!("🔑 S3 자격 증명: 정적 액세스 키");
+        } else {
+            info!("🔑 S3 자격 증명: 기본 자격 증명 체인 (IAM 역할 등)");
+        }
+        let sdk_config = loader.load().await;
+        let client = Client::new(&sdk_config);
         
-        info!("✅ S3 클라이언트 초기화 완료 - 버킷: {}, 리전: {}", bucket_name, region_name);
+        info!("✅ S3 클라이언트 초기화 완료 - 버킷: {}, 리전: {}", bucket_name, region);
         
         Ok(Self {
             client,
             bucket_name,
-            region: region_name,
+            region,
+            credentials: sdk_config.credentials_provider(),
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
         })
     }
 
+    /// S3 호환 엔드포인트(로컬 MinIO, 테스트용 가짜 서버 등)를 쓰는 클라이언트
+    /// 경로 방식 주소를 쓰고, 연결 실패를 바로 알 수 있게 재시도하지 않음
+    pub fn with_endpoint(bucket_name: String, endpoint: String) -> Result<Self> {
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("custom"))
+            .endpoint_url(endpoint)
+            .credentials_provider(Credentials::new("test", "test", None, None, "bigpicture-endpoint"))
+            .force_path_style(true)
+            .retry_config(RetryConfig::disabled())
+            .build();
+        
+        Ok(Self {
+            client: Client::from_conf(config),
+            bucket_name,
+            region: "custom".to_string(),
+            credentials: None,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+        })
+    }
+
+    /// S3 클라이언트가 쓰는 자격 증명 (엔드포인트 지정 클라이언트는 None)
+    pub fn credentials(&self) -> Option<SharedCredentialsProvider> {
+        self.credentials.clone()
+    }
+
+    /// 객체 키 생성에 쓸 시각/ID 제공자 교체 (앱 상태에서 주입)
+    pub fn with_generators(mut self, clock: Arc<dyn Clock>, ids: Arc<dyn IdGenerator>) -> Self {
+        self.clock = clock;
+        self.ids = ids;
+        self
+    }
+
+    /// 객체 키용 (짧은 ID, 타임스탬프)
+    fn key_parts(&self) -> (String, i64) {
+        (self.ids.new_id().to_string()[..8].to_string(), self.clock.now().timestamp())
+    }
+
     pub async fn upload_file(&self, data: Vec<u8>, key: &str, content_type: &str) -> Result<String> {
         info!("📤 S3 업로드 시작: {}", key);
         info!("📤 버킷: {}, 리전: {}", self.bucket_name, self.region);
         info!("📤 파일 크기: {:.2}MB", data.len() as f64 / (1024.0 * 1024.0));
         
-        let put_request = PutObjectRequest {
-            bucket: self.bucket_name.clone(),
-            key: key.to_string(),
-            body: Some(data.into()),
-            content_type: Some(content_type.to_string()),
-            ..Default::default()
-        };
+        // 일시적인 오류는 SDK 표준 재시도 (최대 3회)
+        let put_request = self.client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .body(ByteStream::from(data))
+            .content_type(content_type);
         
-        // 단일 시도 (재시도는 나중에 구현)
-        match self.client.put_object(put_request).await {
+        match put_request.send().await {
             Ok(result) => {
                 let url = format!("https://{}.s3.{}.amazonaws.com/{}", self.bucket_name, self.region, key);
                 info!("✅ S3 업로드 완료: {}", url);
@@ -74,53 +112,195 @@ impl S3Service {
                 Ok(format!("/{}", key))
             }
             Err(e) => {
-                error!("❌ S3 업로드 실패: {:?}", e);
-                Err(anyhow::anyhow!("S3 업로드 실패: {:?}", e))
+                error!("❌ S3 업로드 실패: {}", DisplayErrorContext(&e));
+                Err(anyhow::anyhow!("S3 업로드 실패: {}", DisplayErrorContext(&e)))
             }
         }
     }
 
-    pub async fn upload_thumbnail(&self, image_data: Vec<u8>, _original_filename: &str) -> Result<String> {
-        let timestamp = Utc::now().timestamp();
-        let uuid = Uuid::new_v4().to_string()[..8].to_string();
-        // markers 폴더에 webp로 저장
-        let key = format!("markers/{}_{}_{}.webp", "thumbnail", uuid, timestamp);
-        let content_type = "image/webp";
-        self.upload_file(image_data, &key, content_type).await
-    }
-
-    pub async fn upload_circular_thumbnail(&self, image_data: Vec<u8>, _original_filename: &str) -> Result<String> {
-        let timestamp = Utc::now().timestamp();
-        let uuid = Uuid::new_v4().to_string()[..8].to_string();
-        let key = format!("thumbnails/circular_{}_{}_{}.webp", "thumbnail", uuid, timestamp);
-        self.upload_file(image_data, &key, "image/webp").await
+    /// 사업장 인증 서류 (공개 URL 없이 관리자 API로만 내려받음), 저장한 키 반환
+    pub async fn upload_claim_document(&self, data: Vec<u8>, marker_id: i64, extension: &str, content_type: &str) -> Result<String> {
+        let (uuid, timestamp) = self.key_parts();
+        let key = format!("claims/marker_{}_{}_{}.{}", marker_id, uuid, timestamp, extension);
+        self.upload_file(data, &key, content_type).await?;
+        Ok(key)
     }
 
-    pub async fn upload_map_image(&self, image_data: Vec<u8>, _original_filename: &str) -> Result<String> {
-        let timestamp = Utc::now().timestamp();
-        let uuid = Uuid::new_v4().to_string()[..8].to_string();
-        let key = format!("maps/{}_{}_{}.webp", "map", uuid, timestamp);
-        let content_type = "image/webp";
-        self.upload_file(image_data, &key, content_type).await
+    /// 멀티파트 업로드 시작 (받는 대로 write로 넘기고 complete로 마무리, 중간에 실패하면 abort)
+    pub async fn start_multipart_upload(&self, key: &str, content_type: &str) -> Result<S3MultipartUpload> {
+        let output = self.client
+            .create_multipart_upload()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .content_type(content_type)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("S3 멀티파트 업로드 시작 실패: {}", DisplayErrorContext(&e)))?;
+        let upload_id = output.upload_id.ok_or_else(|| anyhow::anyhow!("S3 멀티파트 업로드 ID가 없음: {}", key))?;
+        info!("📤 S3 멀티파트 업로드 시작: {}", key);
+        
+        Ok(S3MultipartUpload {
+            client: self.client.clone(),
+            bucket_name: self.bucket_name.clone(),
+            key: key.to_string(),
+            upload_id,
+            part: Vec::new(),
+            completed: Vec::new(),
+            size: 0,
+        })
     }
 
     pub async fn delete_file(&self, key: &str) -> Result<()> {
         info!("🗑️ S3 파일 삭제: {}", key);
         
-        let delete_request = rusoto_s3::DeleteObjectRequest {
-            bucket: self.bucket_name.clone(),
-            key: key.to_string(),
-            ..Default::default()
-        };
-        
-        self.client.delete_object(delete_request).await?;
+        self.client
+            .delete_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("S3 파일 삭제 실패: {}", DisplayErrorContext(&e)))?;
         
         info!("✅ S3 파일 삭제 완료: {}", key);
         
         Ok(())
     }
 
+    /// 객체 내용 다운로드
+    pub async fn get_file(&self, key: &str) -> Result<Vec<u8>> {
+        let output = self.client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("S3 객체 다운로드 실패: {}", DisplayErrorContext(&e)))?;
+        Ok(output.body.collect().await?.into_bytes().to_vec())
+    }
+
+    /// 객체 존재 여부 (캐시 확인용)
+    pub async fn file_exists(&self, key: &str) -> Result<bool> {
+        match self.client.head_object().bucket(&self.bucket_name).key(key).send().await {
+            Ok(_) => Ok(true),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(false),
+            Err(e) if e.raw_response().is_some_and(|response| response.status().as_u16() == 404) => Ok(false),
+            Err(e) => Err(anyhow::anyhow!("S3 객체 확인 실패: {}", DisplayErrorContext(&e))),
+        }
+    }
+
+    /// 접두사 아래 객체 목록 (키, 크기, 마지막 수정 시각), 1000개씩 나눠 받음
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<(String, u64, Option<chrono::DateTime<chrono::Utc>>)>> {
+        let mut pages = self.client
+            .list_objects_v2()
+            .bucket(&self.bucket_name)
+            .prefix(prefix)
+            .into_paginator()
+            .send();
+        let mut objects = Vec::new();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| anyhow::anyhow!("S3 객체 목록 조회 실패: {}", DisplayErrorContext(&e)))?;
+            for object in page.contents() {
+                let Some(key) = object.key() else {
+                    continue;
+                };
+                let modified = object.last_modified()
+                    .and_then(|modified| chrono::DateTime::from_timestamp(modified.secs(), modified.subsec_nanos()));
+                objects.push((key.to_string(), object.size().unwrap_or(0).max(0) as u64, modified));
+            }
+        }
+        Ok(objects)
+    }
+
+    /// 로그인 없이 일정 시간 내려받을 수 있는 서명된 GET URL
+    pub async fn presign_get(&self, key: &str, expires_in: Duration) -> Result<String> {
+        let presigning = PresigningConfig::expires_in(expires_in)?;
+        let request = self.client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .presigned(presigning)
+            .await
+            .map_err(|e| anyhow::anyhow!("S3 서명 URL 생성 실패: {}", DisplayErrorContext(&e)))?;
+        Ok(request.uri().to_string())
+    }
+
     pub fn get_file_url(&self, key: &str) -> String {
         format!("https://{}.s3.{}.amazonaws.com/{}", self.bucket_name, self.region, key)
     }
-} 
\ No newline at end of file
+}
+
+/// 진행 중인 멀티파트 업로드 (요청당 최대 한 파트만 메모리에 둠)
+pub struct S3MultipartUpload {
+    client: Client,
+    bucket_name: String,
+    key: String,
+    upload_id: String,
+    part: Vec<u8>,
+    completed: Vec<CompletedPart>,
+    size: usize,
+}
+
+impl S3MultipartUpload {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// 받은 조각 추가, 파트 크기가 차면 바로 전송
+    pub async fn write(&mut self, chunk: &[u8]) -> Result<()> {
+        self.part.extend_from_slice(chunk);
+        self.size += chunk.len();
+        if self.part.len() >= MULTIPART_PART_SIZE {
+            self.upload_part().await?;
+        }
+        Ok(())
+    }
+
+    async fn upload_part(&mut self) -> Result<()> {
+        let part_number = self.completed.len() as i32 + 1;
+        let body = std::mem::take(&mut self.part);
+        let output = self.client
+            .upload_part()
+            .bucket(&self.bucket_name)
+            .key(&self.key)
+            .upload_id(&self.upload_id)
+            .part_number(part_number)
+            .content_length(body.len() as i64)
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("S3 파트 {} 업로드 실패: {}", part_number, DisplayErrorContext(&e)))?;
+        self.completed.push(CompletedPart::builder().set_e_tag(output.e_tag).part_number(part_number).build());
+        Ok(())
+    }
+
+    /// 남은 조각을 보내고 업로드 완료, 파일 경로 반환 (upload_file과 같은 `/키` 형식)
+    pub async fn complete(mut self) -> Result<String> {
+        if !self.part.is_empty() || self.completed.is_empty() {
+            self.upload_part().await?;
+        }
+        let parts = CompletedMultipartUpload::builder().set_parts(Some(std::mem::take(&mut self.completed))).build();
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket_name)
+            .key(&self.key)
+            .upload_id(&self.upload_id)
+            .multipart_upload(parts)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("S3 멀티파트 업로드 완료 실패: {}", DisplayErrorContext(&e)))?;
+        info!("✅ S3 멀티파트 업로드 완료: {} ({:.2}MB)", self.key, self.size as f64 / (1024.0 * 1024.0));
+        Ok(format!("/{}", self.key))
+    }
+
+    /// 업로드 취소 (이미 올라간 파트 삭제), 실패해도 로그만 남김
+    pub async fn abort(self) {
+        let abort_request = self.client
+            .abort_multipart_upload()
+            .bucket(&self.bucket_name)
+            .key(&self.key)
+            .upload_id(&self.upload_id);
+        if let Err(e) = abort_request.send().await {
+            warn!("⚠️ S3 멀티파트 업로드 취소 실패: {} - {}", self.key, DisplayErrorContext(&e));
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// 예약 게시 마커 (status = scheduled) 게시 작업
+// publish_at이 지난 마커를 published로 바꾸고, 바로 게시한 마커처럼 활동 기록/실시간 이벤트/클러스터 캐시 무효화를 함
+// 게시 여부는 DB UPDATE 한 번으로 정해지므로 여러 인스턴스가 함께 돌아도 마커마다 한 번만 처리됨
+use anyhow::Result;
+use log::{info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::clock::Clock;
+use crate::cluster_cache::ClusterCache;
+use crate::database::{Database, ACTIVITY_MARKER_CREATED};
+use crate::emotion_profile::EmotionProfileCache;
+use crate::marker_events::{MarkerEventKind, MarkerEvents};
+
+/// 게시할 때가 된 예약 마커를 게시 (게시한 마커 수 반환)
+pub async fn publish_due_markers(
+    db: &Database,
+    clock: &dyn Clock,
+    marker_events: &MarkerEvents,
+    cluster_cache: &ClusterCache,
+    profiles: &EmotionProfileCache,
+) -> Result<usize> {
+    let markers = db.publish_due_markers(clock.now()).await?;
+    for marker in &markers {
+        if let Some(member_id) = marker.member_id {
+            profiles.invalidate_member(member_id);
+            if let Err(e) = db.record_activity(member_id, None, ACTIVITY_MARKER_CREATED, Some(marker.id)).await {
+                warn!("⚠️ 마커 {} 생성 활동 기록 실패: {}", marker.id, e);
+            }
+        }
+        marker_events.publish(MarkerEventKind::Created, marker, None);
+        cluster_cache.invalidate_marker(marker);
+    }
+    Ok(markers.len())
+}
+
+/// 서버가 떠 있는 동안 interval_secs마다 예약 마커 게시
+pub async fn run_scheduled_marker_publisher(
+    db: Database,
+    clock: Arc<dyn Clock>,
+    marker_events: MarkerEvents,
+    cluster_cache: ClusterCache,
+    profiles: EmotionProfileCache,
+    interval_secs: u64,
+) {
+    info!("⏰ 예약 마커 게시 작업 시작 ({}초마다 확인)", interval_secs);
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+    loop {
+        interval.tick().await;
+        match publish_due_markers(&db, clock.as_ref(), &marker_events, &cluster_cache, &profiles).await {
+            Ok(0) => {}
+            Ok(count) => info!("✅ 예약 마커 {}개 게시", count),
+            Err(e) => warn!("⚠️ 예약 마커 게시 실패 (다음에 다시 시도): {}", e),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+// 시작 시 스키마 검증: 마이그레이션(migrations/*.sql)이 만드는 컬럼/인덱스와 실제 DB 비교
+// 쿼리 시점의 알아보기 힘든 sqlx 에러 대신, 어떤 테이블/컬럼/인덱스가 빠졌는지 바로 보여줌
+use log::{error, info};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::HashSet;
+
+const SCHEMA: &str = "bigpicture";
+
+/// 코드가 사용하는 테이블별 컬럼 (마이그레이션으로 컬럼을 추가하면 여기도 함께 추가)
+const EXPECTED_COLUMNS: &[(&str, &[&str])] = &[
+    ("original_images", &["id", "filename", "original_filename", "file_path", "file_size_mb", "width", "height", "format", "created_at", "updated_at"]),
+    ("webp_images", &["id", "original_id", "filename", "file_path", "file_size_mb", "width", "height", "image_type", "created_at", "updated_at"]),
+    ("members", &[
+        "id", "email", "nickname", "profile_image_url", "region", "gender", "age", "personality_type",
+        "is_active", "email_verified", "created_at", "updated_at", "last_login_at",
+        "preferred_languages", "is_admin", "memories_notification_enabled", "deleted_at",
+        "suspended_at", "suspension_reason", "tokens_revoked_at", "trust_level_override",
+        "push_likes_enabled", "push_comments_enabled",
+    ]),
+    ("markers", &[
+        "id", "member_id", "location", "emotion_tag", "emotion", "emotion_tag_input", "description",
+        "sharing_option", "likes", "dislikes", "views", "author", "thumbnail_img", "language",
+        "taken_down_at", "legal_hold", "address_region", "address_locality", "address_neighborhood",
+        "address_text", "address_geocoded_at", "search_vector", "public_id", "status", "publish_at", "shares", "version", "created_at", "updated_at",
+    ]),
+    ("marker_images", &[
+        "id", "marker_id", "image_type", "image_url", "image_order", "is_primary", "source_image_id",
+        "taken_down_at", "legal_hold", "storage_key", "blurhash", "created_at", "updated_at",
+    ]),
+    ("image_quality", &["image_url", "width", "height", "sharpness_score", "original_size_bytes", "blurhash", "created_at"]),
+    ("image_variants", &["image_url", "thumbnail_url", "created_at"]),
+    ("image_originals", &["storage_key", "original_key", "created_at"]),
+    ("image_size_variants", &["storage_key", "width", "height", "variant_url", "variant_key", "created_at"]),
+    ("image_moderation", &["storage_key", "source_key", "status", "moderator", "labels", "attempts", "last_error", "created_at", "checked_at"]),
+    ("upload_sessions", &["id", "image_type", "filename", "upload_length", "upload_offset", "output_format", "extract_exif", "status", "result", "created_at", "updated_at", "expires_at"]),
+    ("content_takedown_audit", &["id", "admin_member_id", "target_type", "target_id", "action", "reason", "legal_reference", "legal_hold", "client_ip", "user_agent", "created_at"]),
+    ("api_keys", &["id", "key_hash", "key_prefix", "name", "scope", "daily_quota", "created_by", "is_active", "created_at", "last_used_at"]),
+    ("api_key_usage", &["api_key_id", "usage_date", "endpoint", "request_count", "rejected_count"]),
+    ("districts", &["code", "name", "level", "parent_code", "geom", "created_at", "updated_at"]),
+    ("activities", &["id", "actor_id", "recipient_id", "activity_type", "marker_id", "created_at"]),
+    ("member_follows", &["follower_id", "followee_id", "created_at"]),
+    ("marker_view_log", &["marker_id", "viewer_key", "counted_at"]),
+    ("marker_shares", &["token", "marker_id", "member_id", "opens", "created_at", "last_opened_at"]),
+    ("marker_collections", &["id", "member_id", "name", "description", "is_public", "created_at", "updated_at"]),
+    ("marker_collection_items", &["collection_id", "marker_id", "position", "added_at"]),
+    ("bookmark_folders", &["id", "member_id", "name", "color", "position", "created_at", "updated_at"]),
+    ("idempotency_keys", &["scope", "idempotency_key", "request_hash", "status", "response_status", "response_content_type", "response_body", "created_at", "completed_at"]),
+    ("member_data_exports", &["id", "member_id", "format", "status", "marker_count", "file_key", "file_size", "error", "created_at", "started_at", "completed_at"]),
+    ("notifications", &["id", "member_id", "notification_type", "title", "body", "marker_ids", "dedupe_key", "read_at", "created_at", "push_status", "pushed_at"]),
+    ("device_tokens", &["id", "member_id", "token", "platform", "created_at", "last_seen_at"]),
+    ("member_moderation_audit", &["id", "admin_member_id", "member_id", "action", "reason", "client_ip", "user_agent", "created_at"]),
+    ("login_attempts", &["id", "email", "member_id", "success", "failure_reason", "client_ip", "user_agent", "created_at"]),
+    ("member_totp", &["member_id", "secret", "enabled_at", "last_used_step", "created_at", "updated_at"]),
+    ("image_cleanup_jobs", &["id", "image_url", "storage_key", "reason", "attempts", "last_error", "created_at", "completed_at"]),
+    ("marker_exports", &["id", "requested_by", "format", "since", "status", "row_count", "file_key", "file_size", "error", "created_at", "started_at", "completed_at"]),
+    ("dead_letter_jobs", &["id", "job_type", "source_id", "payload", "attempts", "last_error", "status", "failed_at", "resolved_by", "resolved_at"]),
+    ("client_errors", &["id", "kind", "platform", "app_version", "device", "os_version", "route", "request_id", "http_status", "message", "stack_trace", "member_id", "report_request_id", "sample_rate", "created_at"]),
+    ("route_usage_daily", &["usage_date", "method", "route", "client_version", "request_count", "client_error_count", "server_error_count"]),
+    ("marker_claims", &["id", "marker_id", "member_id", "business_name", "business_registration_number", "contact_phone", "document_key", "status", "review_note", "reviewed_by", "reviewed_at", "created_at", "updated_at"]),
+    ("marker_promotions", &["id", "marker_id", "sponsor_name", "district_codes", "starts_at", "ends_at", "impression_cap", "impressions", "clicks", "is_active", "created_by", "created_at", "updated_at"]),
+    ("marker_promotion_stats", &["promotion_id", "stat_date", "impressions", "clicks"]),
+    ("member_connected_accounts", &["id", "member_id", "platform", "remote_user_id", "remote_username", "access_token_encrypted", "refresh_token_encrypted", "token_expires_at", "created_at", "updated_at"]),
+    ("marker_tags", &["marker_id", "tag", "created_at"]),
+    ("marker_reports", &["id", "marker_id", "reporter_id", "reason", "details", "status", "resolution_note", "resolved_by", "resolved_at", "created_at"]),
+    ("marker_crossposts", &["id", "marker_id", "member_id", "platform", "remote_post_id", "remote_url", "created_at"]),
+    ("auth_providers", &["id", "member_id", "provider_type", "provider_id", "provider_email", "password_hash", "created_at", "updated_at"]),
+    ("member_markers", &["id", "member_id", "marker_id", "interaction_type", "folder_id", "created_at", "updated_at"]),
+    ("hobbies", &["id", "name", "category", "description", "is_active", "created_at"]),
+    ("interests", &["id", "name", "category", "description", "is_active", "created_at"]),
+    ("member_hobbies", &["id", "member_id", "hobby_id", "proficiency_level", "created_at"]),
+    ("member_interests", &["id", "member_id", "interest_id", "interest_level", "created_at"]),
+];
+
+/// 성능에 필요한 인덱스 (없어도 쿼리는 되지만 느려짐)
+const EXPECTED_INDEXES: &[&str] = &[
+    "idx_original_images_filename", "idx_original_images_created_at",
+    "idx_webp_images_filename", "idx_webp_images_original_id", "idx_webp_images_image_type", "idx_webp_images_created_at",
+    "markers_location_gist", "idx_markers_member_id", "idx_markers_language", "idx_markers_created_hour", "idx_markers_created_month", "idx_markers_address_pending", "idx_markers_search_vector", "idx_markers_member_created", "idx_markers_public_created", "idx_markers_public_id", "idx_markers_scheduled_publish_at", "idx_markers_member_unpublished",
+    "idx_marker_images_marker_id", "idx_marker_images_image_type", "idx_marker_images_is_primary", "idx_marker_images_order", "idx_marker_images_source_image_id", "idx_marker_images_storage_key", "idx_markers_thumbnail_storage_key", "idx_image_quality_storage_key", "idx_image_variants_storage_key", "idx_image_moderation_pending", "idx_image_moderation_source_key", "idx_upload_sessions_expires",
+    "idx_content_takedown_audit_target",
+    "idx_districts_geom", "idx_districts_parent_code",
+    "idx_notifications_member_created", "idx_notifications_push_pending",
+    "idx_device_tokens_member",
+    "idx_activities_actor", "idx_activities_recipient", "idx_activities_marker_liked", "idx_member_follows_followee", "idx_marker_view_log_counted", "idx_marker_shares_member", "idx_marker_collections_member", "idx_marker_collection_items_marker", "idx_bookmark_folders_member", "idx_member_markers_folder", "idx_member_data_exports_pending", "idx_member_data_exports_active", "idx_idempotency_keys_created",
+    "idx_image_cleanup_jobs_pending",
+    "idx_marker_exports_pending",
+    "idx_dead_letter_jobs_status", "idx_dead_letter_jobs_source",
+    "idx_client_errors_created", "idx_client_errors_request",
+    "idx_route_usage_daily_route",
+    "idx_marker_claims_approved", "idx_marker_claims_pending",
+    "idx_marker_promotions_active",
+    "idx_marker_reports_pending", "idx_marker_reports_status_created",
+    "idx_marker_tags_tag", "idx_marker_tags_created",
+    "idx_login_attempts_email_created", "idx_member_moderation_audit_member",
+    "idx_members_email", "idx_members_nickname", "idx_members_created_at",
+    "idx_auth_providers_member_id", "idx_auth_providers_provider_type_id",
+    "idx_member_markers_member_id", "idx_member_markers_marker_id", "idx_member_markers_interaction_type",
+    "idx_member_markers_member_marker", "idx_member_markers_created_at",
+];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaReport {
+    pub missing_tables: Vec<String>,
+    pub missing_columns: Vec<String>, // "table.column"
+    pub missing_indexes: Vec<String>,
+}
+
+impl SchemaReport {
+    pub fn is_ok(&self) -> bool {
+        self.missing_tables.is_empty() && self.missing_columns.is_empty() && self.missing_indexes.is_empty()
+    }
+
+    /// 누락 항목마다 확인할 일을 한 줄씩 로그로 남김
+    pub fn log(&self) {
+        if self.is_ok() {
+            info!("✅ 스키마 검증 통과 (테이블 {}개, 인덱스 {}개)", EXPECTED_COLUMNS.len(), EXPECTED_INDEXES.len());
+            return;
+        }
+        error!("❌ 스키마 불일치 발견 - 관련 API가 쿼리 시점에 실패할 수 있습니다");
+        for table in &self.missing_tables {
+            error!("   📋 테이블 없음: {}.{} → 마이그레이션 적용 여부 확인 (_sqlx_migrations)", SCHEMA, table);
+        }
+        for column in &self.missing_columns {
+            error!("   📋 컬럼 없음: {}.{} → 기존 테이블이 예전 구조라면 ALTER TABLE ... ADD COLUMN 필요", SCHEMA, column);
+        }
+        for index in &self.missing_indexes {
+            error!("   🔍 인덱스 없음: {} → 마이그레이션 적용 여부 확인 (조회 성능 저하)", index);
+        }
+    }
+}
+
+/// information_schema/pg_indexes를 조회해 기대 스키마와 비교
+pub async fn verify_schema(pool: &PgPool) -> Result<SchemaReport, sqlx::Error> {
+    let columns: Vec<(String, String)> = sqlx::query_as(
+        "SELECT table_name::text, column_name::text FROM information_schema.columns WHERE table_schema = $1"
+    )
+    .bind(SCHEMA)
+    .fetch_all(pool)
+    .await?;
+    let indexes: Vec<(String,)> = sqlx::query_as("SELECT indexname::text FROM pg_indexes WHERE schemaname = $1")
+        .bind(SCHEMA)
+        .fetch_all(pool)
+        .await?;
+
+    let tables: HashSet<&str> = columns.iter().map(|(table, _)| table.as_str()).collect();
+    let live_columns: HashSet<(&str, &str)> = columns.iter().map(|(table, column)| (table.as_str(), column.as_str())).collect();
+    let live_indexes: HashSet<&str> = indexes.iter().map(|(name,)| name.as_str()).collect();
+
+    let mut report = SchemaReport { missing_tables: Vec::new(), missing_columns: Vec::new(), missing_indexes: Vec::new() };
+    for (table, expected) in EXPECTED_COLUMNS {
+        if !tables.contains(table) {
+            report.missing_tables.push(table.to_string());
+            continue;
+        }
+        for column in expected.iter() {
+            if !live_columns.contains(&(*table, *column)) {
+                report.missing_columns.push(format!("{}.{}", table, column));
+            }
+        }
+    }
+    for index in EXPECTED_INDEXES {
+        if !live_indexes.contains(index) {
+            report.missing_indexes.push(index.to_string());
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,240 @@
+// 외부 검색 색인 (Meilisearch): 오타 허용, 해시태그/닉네임 검색
+// 마커 실시간 변경 이벤트(marker_events)를 받아 공개 마커 문서를 넣고 빼며,
+// SEARCH_BACKEND_URL이 있으면 /api/search가 색인을 먼저 쓰고 실패하면 Postgres 전문 검색으로 대신함
+// 이벤트를 놓치면(버퍼 초과, 재시작 중 변경) 전체 재색인으로 맞춤
+use anyhow::{bail, Result};
+use futures_util::future::BoxFuture;
+use log::{error, info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::config::Config;
+use crate::database::{Database, MarkerSearchDocument};
+use crate::marker_events::{MarkerEvent, MarkerEventKind};
+
+const REQUEST_TIMEOUT_SECS: u64 = 5;
+/// 전체 재색인 시 한 번에 보내는 문서 수
+const REINDEX_BATCH_SIZE: i64 = 500;
+/// 검색 대상 필드 (앞에 있을수록 가중치가 큼)
+const SEARCHABLE_ATTRIBUTES: [&str; 6] = ["description", "tags", "nickname", "author", "emotion_tag_input", "emotion_tag"];
+
+/// 검색 요청 (envelope: lng_min, lat_min, lng_max, lat_max)
+#[derive(Debug, Clone)]
+pub struct SearchRequest {
+    pub q: String,
+    pub envelope: Option<(f64, f64, f64, f64)>,
+    pub offset: i64,
+    pub limit: i64,
+}
+
+/// 검색 결과 마커 id (순위 순)와 전체 개수 추정치
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchHits {
+    pub ids: Vec<i64>,
+    pub total: i64,
+}
+
+/// 외부 검색 엔진 (테스트에서는 가짜 엔진으로 교체)
+pub trait SearchBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// 색인 설정 (색인 작업 시작 시 한 번)
+    fn prepare(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+    fn upsert<'a>(&'a self, documents: &'a [MarkerSearchDocument]) -> BoxFuture<'a, Result<()>>;
+    fn delete<'a>(&'a self, ids: &'a [i64]) -> BoxFuture<'a, Result<()>>;
+    fn search<'a>(&'a self, request: &'a SearchRequest) -> BoxFuture<'a, Result<SearchHits>>;
+}
+
+/// 색인 문서 (_geo는 Meilisearch 지도 영역 필터용)
+pub fn document_json(document: &MarkerSearchDocument) -> serde_json::Value {
+    serde_json::json!({
+        "id": document.id,
+        "description": document.description,
+        "tags": document.tags,
+        "nickname": document.nickname,
+        "author": document.author,
+        "emotion_tag_input": document.emotion_tag_input,
+        "emotion_tag": document.emotion_tag,
+        "language": document.language,
+        "created_at": document.created_at.timestamp(),
+        "_geo": document.latitude.zip(document.longitude).map(|(lat, lng)| serde_json::json!({ "lat": lat, "lng": lng }))
+    })
+}
+
+/// Meilisearch HTTP API
+pub struct MeilisearchBackend {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    index: String,
+}
+
+impl MeilisearchBackend {
+    pub fn new(base_url: &str, api_key: &str, index: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            index: index.to_string(),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, format!("{}/indexes/{}{}", self.base_url, self.index, path));
+        if self.api_key.is_empty() {
+            request
+        } else {
+            request.bearer_auth(&self.api_key)
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("Meilisearch 응답 {}: {}", status, body.chars().take(300).collect::<String>());
+        }
+        Ok(response)
+    }
+}
+
+impl SearchBackend for MeilisearchBackend {
+    fn name(&self) -> &'static str {
+        "meilisearch"
+    }
+
+    /// 검색 필드와 지도 영역 필터
+    fn prepare(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let settings = serde_json::json!({
+                "searchableAttributes": SEARCHABLE_ATTRIBUTES,
+                "filterableAttributes": ["_geo"],
+                "sortableAttributes": ["created_at"]
+            });
+            self.send(self.request(reqwest::Method::PATCH, "/settings").json(&settings)).await?;
+            Ok(())
+        })
+    }
+
+    fn upsert<'a>(&'a self, documents: &'a [MarkerSearchDocument]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let body: Vec<serde_json::Value> = documents.iter().map(document_json).collect();
+            self.send(self.request(reqwest::Method::POST, "/documents?primaryKey=id").json(&body)).await?;
+            Ok(())
+        })
+    }
+
+    fn delete<'a>(&'a self, ids: &'a [i64]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.send(self.request(reqwest::Method::POST, "/documents/delete-batch").json(ids)).await?;
+            Ok(())
+        })
+    }
+
+    fn search<'a>(&'a self, request: &'a SearchRequest) -> BoxFuture<'a, Result<SearchHits>> {
+        Box::pin(async move {
+            let mut body = serde_json::json!({
+                "q": request.q,
+                "offset": request.offset,
+                "limit": request.limit,
+                "attributesToRetrieve": ["id"]
+            });
+            if let Some((lng_min, lat_min, lng_max, lat_max)) = request.envelope {
+                body["filter"] = serde_json::json!(format!(
+                    "_geoBoundingBox([{}, {}], [{}, {}])", lat_max, lng_max, lat_min, lng_min
+                ));
+            }
+            let response: serde_json::Value = self.send(self.request(reqwest::Method::POST, "/search").json(&body)).await?
+                .json()
+                .await?;
+            let ids: Vec<i64> = response["hits"].as_array()
+                .map(|hits| hits.iter().filter_map(|hit| hit["id"].as_i64()).collect())
+                .unwrap_or_default();
+            let total = response["estimatedTotalHits"].as_i64().unwrap_or(ids.len() as i64);
+            Ok(SearchHits { ids, total })
+        })
+    }
+}
+
+/// 설정된 외부 검색 엔진 (없으면 Postgres 전문 검색만)
+#[derive(Clone, Default)]
+pub struct SearchIndex {
+    backend: Option<Arc<dyn SearchBackend>>,
+}
+
+impl SearchIndex {
+    pub fn new(backend: Arc<dyn SearchBackend>) -> Self {
+        Self { backend: Some(backend) }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        if config.search_backend_url.is_empty() {
+            return Self::default();
+        }
+        Self::new(Arc::new(MeilisearchBackend::new(&config.search_backend_url, &config.search_api_key, &config.search_index_name)))
+    }
+
+    pub fn backend(&self) -> Option<&Arc<dyn SearchBackend>> {
+        self.backend.as_ref()
+    }
+}
+
+/// 이벤트 하나를 색인에 반영 (생성/수정은 DB에서 최신 문서를 읽어 넣고, 공개 마커가 아니면 뺌)
+pub async fn apply_event(db: &Database, backend: &dyn SearchBackend, event: &MarkerEvent) -> Result<()> {
+    if event.kind != MarkerEventKind::Deleted {
+        let documents = db.get_search_documents(0, Some(event.marker_id), 1).await?;
+        if !documents.is_empty() {
+            return backend.upsert(&documents).await;
+        }
+    }
+    backend.delete(&[event.marker_id]).await
+}
+
+/// 공개 마커 전체를 다시 넣음 (넣은 문서 수 반환, 지워진 마커는 이벤트로만 빠짐)
+pub async fn reindex_all(db: &Database, backend: &dyn SearchBackend) -> Result<usize> {
+    let mut after_id = 0;
+    let mut indexed = 0;
+    loop {
+        let documents = db.get_search_documents(after_id, None, REINDEX_BATCH_SIZE).await?;
+        let Some(last) = documents.last() else {
+            return Ok(indexed);
+        };
+        after_id = last.id;
+        backend.upsert(&documents).await?;
+        indexed += documents.len();
+    }
+}
+
+/// 서버가 떠 있는 동안 마커 변경을 색인에 반영 (SEARCH_BACKEND_URL이 있을 때만 spawn)
+pub async fn run_search_indexer(db: Database, backend: Arc<dyn SearchBackend>, mut events: broadcast::Receiver<Arc<MarkerEvent>>) {
+    info!("🔎 검색 색인 작업 시작 ({})", backend.name());
+    if let Err(e) = backend.prepare().await {
+        error!("❌ 검색 색인 설정 실패: {}", e);
+    }
+    match reindex_all(&db, backend.as_ref()).await {
+        Ok(count) => info!("✅ 검색 색인 {}건 재색인", count),
+        Err(e) => error!("❌ 검색 전체 재색인 실패: {}", e),
+    }
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                if let Err(e) = apply_event(&db, backend.as_ref(), &event).await {
+                    warn!("⚠️ 마커 {} 검색 색인 반영 실패: {}", event.marker_id, e);
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("⚠️ 검색 색인 이벤트 {}건 놓침, 전체 재색인", skipped);
+                if let Err(e) = reindex_all(&db, backend.as_ref()).await {
+                    error!("❌ 검색 전체 재색인 실패: {}", e);
+                }
+            }
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
@@ -0,0 +1,334 @@
+// 마커 공유 카드 이미지 (SNS 공유 미리보기/다이제스트 메일용 1200x630 PNG)
+// 대표 사진 + 감정 이모지 배지 + 설명 일부 + 위치 지도를 한 장으로 합성
+use ab_glyph::{point, Font, FontArc, PxScale, ScaleFont};
+use anyhow::Result;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageOutputFormat, Rgba, RgbaImage};
+use log::{info, warn};
+use std::collections::hash_map::DefaultHasher;
+use std::f64::consts::PI;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+
+use crate::database::Marker;
+use crate::emotions::EmotionTag;
+
+pub const CARD_WIDTH: u32 = 1200;
+pub const CARD_HEIGHT: u32 = 630;
+/// 레이아웃을 바꾸면 올려서 S3에 캐시된 이전 카드를 무효화
+const CARD_LAYOUT_VERSION: u32 = 1;
+const PHOTO_WIDTH: u32 = 630;
+const PADDING: i32 = 48;
+const BADGE_RADIUS: i32 = 40;
+const INSET_SIZE: u32 = 200;
+const MAP_TILE_ZOOM: u8 = 15;
+const DESCRIPTION_SCALE: f32 = 34.0;
+const DESCRIPTION_LINE_HEIGHT: i32 = 48;
+const MAX_DESCRIPTION_LINES: usize = 4; // 아래 위치 지도와 겹치지 않는 줄 수
+/// 줄바꿈 계산 전에 자르는 길이 (어차피 네 줄을 넘김)
+const MAX_DESCRIPTION_CHARS: usize = 400;
+
+const BACKGROUND: Rgba<u8> = Rgba([250, 250, 247, 255]);
+const TEXT_COLOR: Rgba<u8> = Rgba([34, 34, 34, 255]);
+const MUTED_TEXT_COLOR: Rgba<u8> = Rgba([120, 120, 120, 255]);
+const MAP_BACKGROUND: Rgba<u8> = Rgba([226, 234, 238, 255]);
+const MAP_GRID: Rgba<u8> = Rgba([205, 216, 222, 255]);
+const PIN_COLOR: Rgba<u8> = Rgba([229, 57, 53, 255]);
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// 카드에 쓰는 글꼴 (파일이 없으면 글자 없이 색상 배지/지도만 그림)
+#[derive(Clone, Default)]
+pub struct CardFonts {
+    text: Option<FontArc>,
+    emoji: Option<FontArc>,
+}
+
+impl CardFonts {
+    pub fn load(text_path: &str, emoji_path: &str) -> Self {
+        Self {
+            text: load_font(text_path),
+            emoji: load_font(emoji_path),
+        }
+    }
+}
+
+pub(crate) fn load_font(path: &str) -> Option<FontArc> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) => {
+            warn!("⚠️ 공유 카드 글꼴 없음 ({}): {}", path, e);
+            return None;
+        }
+    };
+    match FontArc::try_from_vec(data) {
+        Ok(font) => {
+            info!("✅ 공유 카드 글꼴 로드: {}", path);
+            Some(font)
+        }
+        Err(e) => {
+            warn!("⚠️ 공유 카드 글꼴 읽기 실패 ({}): {}", path, e);
+            None
+        }
+    }
+}
+
+/// 위치 지도 (타일을 못 받으면 격자 배경에 핀만 표시)
+pub struct MapInset {
+    pub tile: Option<DynamicImage>,
+    pub pin: (f64, f64), // 타일 안 핀 위치 (0~1)
+}
+
+/// 카드 내용
+pub struct ShareCard<'a> {
+    pub photo: Option<DynamicImage>,
+    pub emotion: Option<&'static EmotionTag>,
+    pub description: Option<&'a str>,
+    pub map: Option<MapInset>,
+}
+
+/// 마커 내용이 바뀌면 달라지는 S3 캐시 키
+pub fn cache_key(marker: &Marker, photo_url: Option<&str>) -> String {
+    let mut hasher = DefaultHasher::new();
+    CARD_LAYOUT_VERSION.hash(&mut hasher);
+    marker.updated_at.timestamp_millis().hash(&mut hasher);
+    marker.emotion_tag.hash(&mut hasher);
+    marker.description.hash(&mut hasher);
+    marker.location.hash(&mut hasher);
+    photo_url.hash(&mut hasher);
+    format!("cards/marker_{}_{:016x}.png", marker.id, hasher.finish())
+}
+
+/// 좌표가 들어 있는 지도 타일 URL과 타일 안 위치 ({z}/{x}/{y} 템플릿, 웹 메르카토르)
+pub fn map_tile_url(template: &str, latitude: f64, longitude: f64) -> (String, (f64, f64)) {
+    let n = f64::from(1u32 << MAP_TILE_ZOOM);
+    let x = (longitude + 180.0) / 360.0 * n;
+    let lat_rad = latitude.clamp(-85.0511, 85.0511).to_radians();
+    let y = (1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / PI) / 2.0 * n;
+    let tile_x = x.floor().clamp(0.0, n - 1.0);
+    let tile_y = y.floor().clamp(0.0, n - 1.0);
+    let url = template
+        .replace("{z}", &MAP_TILE_ZOOM.to_string())
+        .replace("{x}", &(tile_x as u32).to_string())
+        .replace("{y}", &(tile_y as u32).to_string());
+    (url, ((x - tile_x).clamp(0.0, 1.0), (y - tile_y).clamp(0.0, 1.0)))
+}
+
+/// 감정별 배지/배경 색상
+pub fn emotion_color(emotion_id: Option<&str>) -> Rgba<u8> {
+    let [r, g, b] = match emotion_id.unwrap_or_default() {
+        "happy" | "celebration" => [255, 193, 7],
+        "sad" | "lonely" => [66, 133, 244],
+        "angry" => [229, 57, 53],
+        "fear" | "anxious" => [126, 87, 194],
+        "surprise" | "energy" => [255, 112, 67],
+        "peaceful" | "tired" => [77, 182, 172],
+        "love" | "beauty" => [236, 64, 122],
+        "achievement" | "hopeful" => [102, 187, 106],
+        "inspiration" | "music" => [92, 107, 192],
+        "delicious" => [255, 152, 0],
+        "memory" | "nostalgic" => [161, 136, 127],
+        "grateful" => [139, 195, 74],
+        _ => [144, 164, 174],
+    };
+    Rgba([r, g, b, 255])
+}
+
+/// 카드 PNG 렌더링
+pub fn render(card: &ShareCard, fonts: &CardFonts) -> Result<Vec<u8>> {
+    let mut canvas = RgbaImage::from_pixel(CARD_WIDTH, CARD_HEIGHT, BACKGROUND);
+    let accent = emotion_color(card.emotion.map(|emotion| emotion.id));
+
+    // 왼쪽: 대표 사진 (없으면 감정 색상 배경에 큰 이모지)
+    match &card.photo {
+        Some(photo) => {
+            let cropped = photo.resize_to_fill(PHOTO_WIDTH, CARD_HEIGHT, FilterType::Lanczos3).to_rgba8();
+            image::imageops::overlay(&mut canvas, &cropped, 0, 0);
+        }
+        None => {
+            fill_rect(&mut canvas, 0, 0, PHOTO_WIDTH, CARD_HEIGHT, accent);
+            if let (Some(font), Some(emotion)) = (&fonts.emoji, card.emotion) {
+                draw_centered(&mut canvas, font, 220.0, PHOTO_WIDTH as i32 / 2, CARD_HEIGHT as i32 / 2, WHITE, emotion.emoji);
+            }
+        }
+    }
+
+    // 오른쪽 위: 감정 배지 + 이름
+    let panel_x = PHOTO_WIDTH as i32 + PADDING;
+    let badge_center = (panel_x + BADGE_RADIUS, PADDING + BADGE_RADIUS);
+    fill_circle(&mut canvas, badge_center, BADGE_RADIUS, accent);
+    if let Some(emotion) = card.emotion {
+        if let Some(font) = &fonts.emoji {
+            draw_centered(&mut canvas, font, 48.0, badge_center.0, badge_center.1, WHITE, emotion.emoji);
+        }
+        if let Some(font) = &fonts.text {
+            let label = format!("{} · {}", emotion.name, emotion.name_en);
+            draw_text(&mut canvas, font, 36.0, panel_x + BADGE_RADIUS * 2 + 20, badge_center.1 - 22, TEXT_COLOR, &label);
+        }
+    }
+
+    // 설명 일부
+    let text_width = (CARD_WIDTH as i32 - panel_x - PADDING) as f32;
+    if let (Some(font), Some(description)) = (&fonts.text, card.description) {
+        let description: String = description.chars().take(MAX_DESCRIPTION_CHARS).collect();
+        let top = PADDING + BADGE_RADIUS * 2 + 36;
+        for (i, line) in wrap_text(font, DESCRIPTION_SCALE, &description, text_width, MAX_DESCRIPTION_LINES).iter().enumerate() {
+            draw_text(&mut canvas, font, DESCRIPTION_SCALE, panel_x, top + i as i32 * DESCRIPTION_LINE_HEIGHT, TEXT_COLOR, line);
+        }
+    }
+
+    // 오른쪽 아래: 위치 지도
+    if let Some(map) = &card.map {
+        let inset_x = CARD_WIDTH as i32 - PADDING - INSET_SIZE as i32;
+        let inset_y = CARD_HEIGHT as i32 - PADDING - INSET_SIZE as i32;
+        fill_rect(&mut canvas, inset_x - 4, inset_y - 4, INSET_SIZE + 8, INSET_SIZE + 8, WHITE);
+        draw_map_inset(&mut canvas, map, inset_x, inset_y, INSET_SIZE);
+    }
+
+    // 왼쪽 아래 서비스 이름
+    if let Some(font) = &fonts.text {
+        draw_text(&mut canvas, font, 28.0, panel_x, CARD_HEIGHT as i32 - PADDING - 28, MUTED_TEXT_COLOR, "BigPicture");
+    }
+
+    let mut png = Vec::new();
+    DynamicImage::ImageRgba8(canvas).write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)?;
+    Ok(png)
+}
+
+/// 위치 지도만 정사각형 PNG로 렌더링 (임베드 카드 썸네일)
+pub fn render_map_thumbnail(map: &MapInset, size: u32) -> Result<Vec<u8>> {
+    let mut canvas = RgbaImage::from_pixel(size, size, MAP_BACKGROUND);
+    draw_map_inset(&mut canvas, map, 0, 0, size);
+    let mut png = Vec::new();
+    DynamicImage::ImageRgba8(canvas).write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)?;
+    Ok(png)
+}
+
+fn draw_map_inset(canvas: &mut RgbaImage, map: &MapInset, x: i32, y: i32, size: u32) {
+    let pin = match &map.tile {
+        Some(tile) => {
+            let resized = tile.resize_exact(size, size, FilterType::Triangle).to_rgba8();
+            image::imageops::overlay(canvas, &resized, x as i64, y as i64);
+            map.pin
+        }
+        None => {
+            fill_rect(canvas, x, y, size, size, MAP_BACKGROUND);
+            let step = (size / 5) as i32;
+            for i in 1..5 {
+                fill_rect(canvas, x + i * step, y, 2, size, MAP_GRID);
+                fill_rect(canvas, x, y + i * step, size, 2, MAP_GRID);
+            }
+            (0.5, 0.5)
+        }
+    };
+    let pin_x = x + (pin.0 * f64::from(size)) as i32;
+    let pin_y = y + (pin.1 * f64::from(size)) as i32;
+    fill_circle(canvas, (pin_x, pin_y), 12, WHITE);
+    fill_circle(canvas, (pin_x, pin_y), 9, PIN_COLOR);
+}
+
+fn fill_rect(canvas: &mut RgbaImage, x: i32, y: i32, width: u32, height: u32, color: Rgba<u8>) {
+    for py in y.max(0)..(y + height as i32).min(canvas.height() as i32) {
+        for px in x.max(0)..(x + width as i32).min(canvas.width() as i32) {
+            canvas.put_pixel(px as u32, py as u32, color);
+        }
+    }
+}
+
+fn fill_circle(canvas: &mut RgbaImage, center: (i32, i32), radius: i32, color: Rgba<u8>) {
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            // 가장자리 1px은 거리 비율로 섞어 계단 현상 완화
+            let distance = f64::from(dx * dx + dy * dy).sqrt();
+            let coverage = (f64::from(radius) + 0.5 - distance).clamp(0.0, 1.0) as f32;
+            blend_pixel(canvas, center.0 + dx, center.1 + dy, color, coverage);
+        }
+    }
+}
+
+pub(crate) fn blend_pixel(canvas: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>, coverage: f32) {
+    if coverage <= 0.0 || x < 0 || y < 0 || x >= canvas.width() as i32 || y >= canvas.height() as i32 {
+        return;
+    }
+    let alpha = coverage.min(1.0) * f32::from(color[3]) / 255.0;
+    let pixel = canvas.get_pixel_mut(x as u32, y as u32);
+    for channel in 0..3 {
+        let blended = f32::from(pixel[channel]) * (1.0 - alpha) + f32::from(color[channel]) * alpha;
+        pixel[channel] = blended.round() as u8;
+    }
+    pixel[3] = 255;
+}
+
+/// 글꼴에 없는 글자(.notdef)는 건너뜀
+fn glyphs(font: &FontArc, text: &str) -> impl Iterator<Item = ab_glyph::GlyphId> {
+    text.chars().map(|ch| font.glyph_id(ch)).filter(|id| id.0 != 0)
+}
+
+pub(crate) fn text_width(font: &FontArc, scale: f32, text: &str) -> f32 {
+    let scaled = font.as_scaled(PxScale::from(scale));
+    glyphs(font, text).map(|id| scaled.h_advance(id)).sum()
+}
+
+/// (x, y)를 글자 상자 왼쪽 위로 해서 한 줄 그리기
+pub(crate) fn draw_text(canvas: &mut RgbaImage, font: &FontArc, scale: f32, x: i32, y: i32, color: Rgba<u8>, text: &str) {
+    let scaled = font.as_scaled(PxScale::from(scale));
+    let baseline = y as f32 + scaled.ascent();
+    let mut caret = x as f32;
+    for id in glyphs(font, text) {
+        let glyph = id.with_scale_and_position(scale, point(caret, baseline));
+        caret += scaled.h_advance(id);
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                blend_pixel(canvas, bounds.min.x as i32 + gx as i32, bounds.min.y as i32 + gy as i32, color, coverage);
+            });
+        }
+    }
+}
+
+fn draw_centered(canvas: &mut RgbaImage, font: &FontArc, scale: f32, center_x: i32, center_y: i32, color: Rgba<u8>, text: &str) {
+    let scaled = font.as_scaled(PxScale::from(scale));
+    let width = text_width(font, scale, text);
+    let height = scaled.ascent() - scaled.descent();
+    draw_text(canvas, font, scale, center_x - (width / 2.0) as i32, center_y - (height / 2.0) as i32, color, text);
+}
+
+/// 폭에 맞춰 줄바꿈 (공백이 있으면 단어 단위, 한글처럼 공백 없이 길면 글자 단위)
+/// 줄 수를 넘기면 마지막 줄 끝을 말줄임표로 자름
+fn wrap_text(font: &FontArc, scale: f32, text: &str, max_width: f32, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for paragraph in text.lines().map(str::trim).filter(|p| !p.is_empty()) {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if text_width(font, scale, &candidate) <= max_width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for ch in word.chars() {
+                line.push(ch);
+                if text_width(font, scale, &line) > max_width {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, ch.to_string()));
+                }
+            }
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        if lines.len() > max_lines {
+            break;
+        }
+    }
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        let last = lines.last_mut().expect("max_lines > 0");
+        while !last.is_empty() && text_width(font, scale, &format!("{}…", last)) > max_width {
+            last.pop();
+        }
+        last.push('…');
+    }
+    lines
+}
@@ -0,0 +1,407 @@
+// 파일 저장소 (STORAGE_BACKEND=s3 | local): 업로드/내보내기 코드는 저장소 종류와 무관하게 같은 경로로 저장
+// s3: S3 버킷, local: UPLOAD_DIR 아래 디스크 (AWS 없이 자체 호스팅)
+// put은 DB에 넣는 URL 형식을 돌려줌 (S3 "/키", 로컬 다운로드 API URL) → image_cleanup::storage_key로 저장 위치를 찾을 수 있음
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use log::{info, warn};
+use ring::hmac;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+use crate::clock::{Clock, IdGenerator, SystemClock};
+use crate::config::Config;
+use crate::s3_service::{S3MultipartUpload, S3Service};
+use crate::upload_spool::ensure_disk_space;
+
+pub const STORAGE_BACKENDS: [&str; 2] = ["s3", "local"];
+/// 로컬 저장소에서 다운로드 API(/api/images/download)로 공개하는 폴더 (내보내기 파일 등은 서명된 URL로만)
+/// 업로드 이미지가 저장되는 폴더이기도 함 (고아 파일 정리 대상)
+pub const PUBLIC_PREFIXES: [&str; 4] = ["markers/", "maps/", "thumbnails/", "originals/"];
+const PARTIAL_SUFFIX: &str = ".part";
+
+/// 파일 저장소 (운영에서는 설정으로 선택, 테스트에서는 임시 디렉토리의 로컬 저장소)
+pub trait Storage: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// 저장 후 DB에 넣을 URL 반환
+    fn put<'a>(&'a self, key: &'a str, data: Vec<u8>, content_type: &'a str) -> BoxFuture<'a, Result<String>>;
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Vec<u8>>>;
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<()>>;
+    /// 로그인 없이 expires_in 동안 내려받을 수 있는 URL
+    fn presign<'a>(&'a self, key: &'a str, expires_in: Duration) -> BoxFuture<'a, Result<String>>;
+    /// 받는 대로 나눠 쓰는 업로드 (큰 원본을 메모리에 모으지 않음)
+    fn start_upload<'a>(&'a self, key: &'a str, content_type: &'a str) -> BoxFuture<'a, Result<Box<dyn StorageUpload>>>;
+    /// 접두사 아래 저장된 객체 (쓰는 중인 임시 파일 제외)
+    fn list<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, Result<Vec<StoredObject>>>;
+}
+
+/// 저장소에 있는 객체 하나
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub key: String,
+    pub size: u64,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// 진행 중인 업로드: write로 조각을 넘기고 complete(저장한 URL 반환)나 abort로 마무리
+pub trait StorageUpload: Send {
+    fn key(&self) -> &str;
+    fn write<'a>(&'a mut self, chunk: &'a [u8]) -> BoxFuture<'a, Result<()>>;
+    fn complete(self: Box<Self>) -> BoxFuture<'static, Result<String>>;
+    /// 실패해도 로그만 남김
+    fn abort(self: Box<Self>) -> BoxFuture<'static, ()>;
+}
+
+/// STORAGE_BACKEND에 맞는 저장소 (알 수 없는 값이면 S3)
+pub fn from_config(config: &Config, s3_service: &S3Service) -> Arc<dyn Storage> {
+    match config.storage_backend.as_str() {
+        "local" => {
+            info!("💾 파일 저장소: 로컬 디스크 ({})", config.upload_dir);
+            Arc::new(LocalStorage::from_config(config))
+        }
+        backend => {
+            if backend != "s3" {
+                warn!("⚠️ 알 수 없는 STORAGE_BACKEND '{}', S3를 사용합니다 ({})", backend, STORAGE_BACKENDS.join(", "));
+            }
+            Arc::new(S3Storage::new(s3_service.clone()))
+        }
+    }
+}
+
+/// 새 객체 키 생성 (시각/ID 제공자는 앱 상태에서 주입)
+#[derive(Clone)]
+pub struct ObjectKeys {
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+impl ObjectKeys {
+    pub fn new(clock: Arc<dyn Clock>, ids: Arc<dyn IdGenerator>) -> Self {
+        Self { clock, ids }
+    }
+
+    /// `{폴더}/{접두사}_{짧은 ID}_{타임스탬프}.{확장자}`
+    pub fn new_key(&self, dir: &str, prefix: &str, extension: &str) -> String {
+        let uuid = self.ids.new_id().to_string()[..8].to_string();
+        format!("{}/{}_{}_{}.{}", dir, prefix, uuid, self.clock.now().timestamp(), extension)
+    }
+}
+
+/// 다운로드 API가 공개로 내려줄 수 있는 키인지
+pub fn is_public_key(key: &str) -> bool {
+    validate_key(key).is_ok() && PUBLIC_PREFIXES.iter().any(|prefix| key.starts_with(prefix))
+}
+
+/// 확장자로 정한 Content-Type
+pub fn content_type_for(key: &str) -> &'static str {
+    match Path::new(key).extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("bmp") => "image/bmp",
+        Some("csv") => "text/csv; charset=utf-8",
+        Some("json") | Some("geojson") => "application/json; charset=utf-8",
+        Some("zip") => "application/zip",
+        Some("parquet") => "application/vnd.apache.parquet",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 상대 경로만 허용 (절대 경로, `..`, 빈 키, 임시 파일 이름 거부)
+fn validate_key(key: &str) -> Result<()> {
+    let path = Path::new(key);
+    if key.is_empty() || key.contains('\\') || key.ends_with(PARTIAL_SUFFIX)
+        || !path.components().all(|component| matches!(component, Component::Normal(_)))
+    {
+        bail!("잘못된 저장소 키: {}", key);
+    }
+    Ok(())
+}
+
+/// S3 버킷
+pub struct S3Storage {
+    s3_service: S3Service,
+}
+
+impl S3Storage {
+    pub fn new(s3_service: S3Service) -> Self {
+        Self { s3_service }
+    }
+}
+
+impl Storage for S3Storage {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: Vec<u8>, content_type: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(self.s3_service.upload_file(data, key, content_type))
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(self.s3_service.get_file(key))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.s3_service.delete_file(key))
+    }
+
+    fn presign<'a>(&'a self, key: &'a str, expires_in: Duration) -> BoxFuture<'a, Result<String>> {
+        Box::pin(self.s3_service.presign_get(key, expires_in))
+    }
+
+    fn start_upload<'a>(&'a self, key: &'a str, content_type: &'a str) -> BoxFuture<'a, Result<Box<dyn StorageUpload>>> {
+        Box::pin(async move {
+            let upload = self.s3_service.start_multipart_upload(key, content_type).await?;
+            Ok(Box::new(upload) as Box<dyn StorageUpload>)
+        })
+    }
+
+    fn list<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, Result<Vec<StoredObject>>> {
+        Box::pin(async move {
+            let objects = self.s3_service.list_objects(prefix).await?;
+            Ok(objects.into_iter().map(|(key, size, modified)| StoredObject { key, size, modified }).collect())
+        })
+    }
+}
+
+impl StorageUpload for S3MultipartUpload {
+    fn key(&self) -> &str {
+        S3MultipartUpload::key(self)
+    }
+
+    fn write<'a>(&'a mut self, chunk: &'a [u8]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(S3MultipartUpload::write(self, chunk))
+    }
+
+    fn complete(self: Box<Self>) -> BoxFuture<'static, Result<String>> {
+        Box::pin(S3MultipartUpload::complete(*self))
+    }
+
+    fn abort(self: Box<Self>) -> BoxFuture<'static, ()> {
+        Box::pin(S3MultipartUpload::abort(*self))
+    }
+}
+
+/// UPLOAD_DIR 아래 디스크 (키가 곧 상대 경로)
+/// 공개 폴더는 /api/images/download/{키}, 나머지는 presign으로 만든 /api/storage/{키} 서명 URL로만 내려받음
+pub struct LocalStorage {
+    root: PathBuf,
+    file_server_url: String,
+    signing_key: hmac::Key,
+    min_free_disk_mb: u64,
+    clock: Arc<dyn Clock>,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>, file_server_url: &str, signing_secret: &str) -> Self {
+        Self {
+            root: root.into(),
+            file_server_url: file_server_url.trim_end_matches('/').to_string(),
+            signing_key: hmac::Key::new(hmac::HMAC_SHA256, signing_secret.as_bytes()),
+            min_free_disk_mb: 0,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// UPLOAD_DIR, FILE_SERVER_URL, 서명 키는 JWT_SECRET에서 유도
+    pub fn from_config(config: &Config) -> Self {
+        let mut storage = Self::new(&config.upload_dir, &config.file_server_url, &format!("storage-presign:{}", config.jwt_secret));
+        storage.min_free_disk_mb = config.min_free_disk_mb;
+        storage
+    }
+
+    /// 서명 URL 만료 계산에 쓸 시각 제공자 교체
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/api/images/download/{}", self.file_server_url, key)
+    }
+
+    fn signature(&self, key: &str, expires: i64) -> String {
+        hmac::sign(&self.signing_key, format!("{}\n{}", key, expires).as_bytes())
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// presign으로 만든 URL의 만료 시각(유닉스 초)과 서명 확인
+    pub fn verify_presigned(&self, key: &str, expires: i64, signature: &str) -> bool {
+        if expires < self.clock.now().timestamp() || validate_key(key).is_err() {
+            return false;
+        }
+        let Some(signature) = decode_hex(signature) else {
+            return false;
+        };
+        hmac::verify(&self.signing_key, format!("{}\n{}", key, expires).as_bytes(), &signature).is_ok()
+    }
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| value.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+        .collect()
+}
+
+impl Storage for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: Vec<u8>, _content_type: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let path = self.path(key)?;
+            if let Some(dir) = path.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+                ensure_disk_space(dir, data.len() as u64, self.min_free_disk_mb).map_err(|e| anyhow!("{}", e))?;
+            }
+            // 임시 이름으로 쓴 뒤 옮겨서 읽는 쪽이 쓰다 만 파일을 보지 않게 함
+            let partial = partial_path(&path);
+            tokio::fs::write(&partial, &data).await?;
+            tokio::fs::rename(&partial, &path).await?;
+            info!("💾 로컬 저장 완료: {} ({:.2}MB)", key, data.len() as f64 / (1024.0 * 1024.0));
+            Ok(self.url(key))
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(async move {
+            let path = self.path(key)?;
+            tokio::fs::read(&path).await.map_err(|e| anyhow!("로컬 파일 읽기 실패: {} - {}", key, e))
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let path = self.path(key)?;
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => info!("🗑️ 로컬 파일 삭제: {}", key),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+            Ok(())
+        })
+    }
+
+    fn presign<'a>(&'a self, key: &'a str, expires_in: Duration) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            validate_key(key)?;
+            let expires = self.clock.now().timestamp() + expires_in.as_secs() as i64;
+            Ok(format!(
+                "{}/api/storage/{}?expires={}&signature={}",
+                self.file_server_url, key, expires, self.signature(key, expires)
+            ))
+        })
+    }
+
+    fn start_upload<'a>(&'a self, key: &'a str, _content_type: &'a str) -> BoxFuture<'a, Result<Box<dyn StorageUpload>>> {
+        Box::pin(async move {
+            let path = self.path(key)?;
+            if let Some(dir) = path.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            let partial = partial_path(&path);
+            let file = tokio::fs::File::create(&partial).await?;
+            Ok(Box::new(LocalUpload { key: key.to_string(), url: self.url(key), path, partial, file }) as Box<dyn StorageUpload>)
+        })
+    }
+
+    fn list<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, Result<Vec<StoredObject>>> {
+        Box::pin(async move {
+            // 접두사는 폴더 단위 ("markers/"), 하위 폴더까지 내려가며 파일만 모음
+            let mut objects = Vec::new();
+            let mut dirs = vec![self.path(prefix.trim_end_matches('/'))?];
+            while let Some(dir) = dirs.pop() {
+                let mut entries = match tokio::fs::read_dir(&dir).await {
+                    Ok(entries) => entries,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e.into()),
+                };
+                while let Some(entry) = entries.next_entry().await? {
+                    let metadata = entry.metadata().await?;
+                    let path = entry.path();
+                    if metadata.is_dir() {
+                        dirs.push(path);
+                        continue;
+                    }
+                    let Some(key) = path.strip_prefix(&self.root).ok().and_then(|key| key.to_str()) else {
+                        continue;
+                    };
+                    if key.ends_with(PARTIAL_SUFFIX) {
+                        continue;
+                    }
+                    objects.push(StoredObject {
+                        key: key.replace(std::path::MAIN_SEPARATOR, "/"),
+                        size: metadata.len(),
+                        modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+                    });
+                }
+            }
+            objects.sort_by(|a, b| a.key.cmp(&b.key));
+            Ok(objects)
+        })
+    }
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(PARTIAL_SUFFIX);
+    PathBuf::from(partial)
+}
+
+/// 로컬 저장소 업로드 (`.part` 파일에 쓰고 완료하면 제 이름으로 옮김)
+struct LocalUpload {
+    key: String,
+    url: String,
+    path: PathBuf,
+    partial: PathBuf,
+    file: tokio::fs::File,
+}
+
+impl StorageUpload for LocalUpload {
+    fn key(&self) -> &str {
+        &self.key
+    }
+
+    fn write<'a>(&'a mut self, chunk: &'a [u8]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.file.write_all(chunk).await?;
+            Ok(())
+        })
+    }
+
+    fn complete(mut self: Box<Self>) -> BoxFuture<'static, Result<String>> {
+        Box::pin(async move {
+            self.file.flush().await?;
+            tokio::fs::rename(&self.partial, &self.path).await?;
+            info!("💾 로컬 업로드 완료: {}", self.key);
+            Ok(self.url)
+        })
+    }
+
+    fn abort(self: Box<Self>) -> BoxFuture<'static, ()> {
+        Box::pin(async move {
+            drop(self.file);
+            if let Err(e) = tokio::fs::remove_file(&self.partial).await {
+                warn!("⚠️ 로컬 업로드 취소 실패: {} - {}", self.key, e);
+            }
+        })
+    }
+}
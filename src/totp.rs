@@ -0,0 +1,92 @@
+// TOTP 2단계 인증 (RFC 6238: HMAC-SHA1, 6자리, 30초 주기)
+// 인증 앱(Google Authenticator 등)과 호환되는 기본값만 지원
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+use crate::clock::IdGenerator;
+
+const STEP_SECS: i64 = 30;
+const DIGITS: u32 = 6;
+/// 앱과 서버 시계 차이를 앞뒤 한 주기까지 허용
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+const SECRET_BYTES: usize = 20;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// 새 비밀키 (Base32, 160비트)
+pub fn generate_secret(ids: &dyn IdGenerator) -> String {
+    let mut bytes = Vec::with_capacity(32);
+    bytes.extend_from_slice(ids.new_id().as_bytes());
+    bytes.extend_from_slice(ids.new_id().as_bytes());
+    base32_encode(&bytes[..SECRET_BYTES])
+}
+
+/// 인증 앱 등록용 otpauth:// URI (QR 코드로 변환해 보여줌)
+pub fn provisioning_uri(secret: &str, account: &str, issuer: &str) -> String {
+    let label = format!("{}:{}", issuer, account);
+    let mut uri = reqwest::Url::parse("otpauth://totp/").expect("otpauth base uri");
+    uri.path_segments_mut().expect("otpauth path").push(&label);
+    uri.query_pairs_mut()
+        .append_pair("secret", secret)
+        .append_pair("issuer", issuer)
+        .append_pair("algorithm", "SHA1")
+        .append_pair("digits", &DIGITS.to_string())
+        .append_pair("period", &STEP_SECS.to_string());
+    uri.to_string()
+}
+
+/// 코드가 맞으면 일치한 시간 단계 (재사용 방지를 위해 마지막 사용 단계와 비교)
+pub fn verify_code(secret: &str, code: &str, now: DateTime<Utc>) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let key = base32_decode(secret)?;
+    let current = now.timestamp().div_euclid(STEP_SECS);
+    (current - ALLOWED_DRIFT_STEPS..=current + ALLOWED_DRIFT_STEPS)
+        .find(|step| format!("{:0width$}", code_at(&key, *step), width = DIGITS as usize) == code)
+}
+
+/// 해당 시간 단계의 코드 (RFC 4226 dynamic truncation)
+fn code_at(key: &[u8], step: i64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(&(step as u64).to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([digest[offset] & 0x7f, digest[offset + 1], digest[offset + 2], digest[offset + 3]]);
+    binary % 10u32.pow(DIGITS)
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(encoded.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in encoded.chars().filter(|c| *c != '=' && !c.is_whitespace()) {
+        let value = BASE32_ALPHABET.iter().position(|&a| a as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    Some(output)
+}
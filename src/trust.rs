@@ -0,0 +1,134 @@
+// 신규 계정 신뢰 등급 (new → basic → trusted)
+// 등급은 가입 기간과 활동 이력으로 요청마다 계산하므로 조건을 채우면 자동으로 올라가고,
+// 관리자가 지정한 등급(members.trust_level_override)이 있으면 그 값을 우선
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::database::MemberTrustStats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustLevel {
+    New,
+    Basic,
+    Trusted,
+}
+
+impl TrustLevel {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "new" => Some(Self::New),
+            "basic" => Some(Self::Basic),
+            "trusted" => Some(Self::Trusted),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::Basic => "basic",
+            Self::Trusted => "trusted",
+        }
+    }
+}
+
+/// 등급별 한도
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustLimits {
+    pub markers_per_day: i64, // 최근 24시간 마커 생성 수
+    pub images_per_marker: i64,
+    pub links_allowed: bool, // 마커 설명에 URL 허용 여부
+}
+
+/// 등급 조건과 등급별 한도 (환경변수 설정)
+#[derive(Debug, Clone)]
+pub struct TrustPolicy {
+    basic_min_days: i64,
+    basic_min_markers: i64,
+    trusted_min_days: i64,
+    trusted_min_markers: i64,
+    new_limits: TrustLimits,
+    basic_limits: TrustLimits,
+    trusted_limits: TrustLimits,
+}
+
+impl TrustPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            basic_min_days: config.trust_basic_min_days,
+            basic_min_markers: config.trust_basic_min_markers,
+            trusted_min_days: config.trust_trusted_min_days,
+            trusted_min_markers: config.trust_trusted_min_markers,
+            new_limits: TrustLimits {
+                markers_per_day: config.new_member_markers_per_day,
+                images_per_marker: config.new_member_images_per_marker.min(config.max_images_per_marker),
+                links_allowed: false,
+            },
+            basic_limits: TrustLimits {
+                markers_per_day: config.basic_member_markers_per_day,
+                images_per_marker: config.max_images_per_marker,
+                links_allowed: true,
+            },
+            trusted_limits: TrustLimits {
+                markers_per_day: config.trusted_member_markers_per_day,
+                images_per_marker: config.max_images_per_marker,
+                links_allowed: true,
+            },
+        }
+    }
+
+    /// 가입 기간과 이력으로 계산한 등급 (게시 중단된 마커가 있으면 trusted로 올라가지 않음)
+    pub fn derive(&self, stats: &MemberTrustStats, now: DateTime<Utc>) -> TrustLevel {
+        let age = now - stats.created_at;
+        if age >= Duration::days(self.trusted_min_days)
+            && stats.marker_count >= self.trusted_min_markers
+            && stats.taken_down_count == 0
+        {
+            TrustLevel::Trusted
+        } else if age >= Duration::days(self.basic_min_days) && stats.marker_count >= self.basic_min_markers {
+            TrustLevel::Basic
+        } else {
+            TrustLevel::New
+        }
+    }
+
+    pub fn limits(&self, level: TrustLevel) -> TrustLimits {
+        match level {
+            TrustLevel::New => self.new_limits,
+            TrustLevel::Basic => self.basic_limits,
+            TrustLevel::Trusted => self.trusted_limits,
+        }
+    }
+}
+
+/// 회원의 현재 등급과 한도
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberTrust {
+    pub level: TrustLevel,
+    pub is_override: bool, // 관리자 지정 등급이면 true
+    pub limits: TrustLimits,
+    pub markers_last_day: i64,
+}
+
+impl MemberTrust {
+    pub fn resolve(policy: &TrustPolicy, stats: &MemberTrustStats, now: DateTime<Utc>) -> Self {
+        let override_level = stats.trust_level_override.as_deref().and_then(TrustLevel::parse);
+        let level = override_level.unwrap_or_else(|| policy.derive(stats, now));
+        Self {
+            level,
+            is_override: override_level.is_some(),
+            limits: policy.limits(level),
+            markers_last_day: stats.markers_last_day,
+        }
+    }
+}
+
+/// 글에 링크(http/https 주소, www. 주소)가 있는지
+pub fn contains_link(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    lower.contains("http://") || lower.contains("https://") || lower.split_whitespace().any(|word| word.starts_with("www."))
+}
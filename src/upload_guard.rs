@@ -0,0 +1,130 @@
+// 이미지 업로드 부하 차단 (동시 업로드 수/가용 메모리 기준으로 새 업로드를 503으로 거절)
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::Method,
+    middleware::Next,
+    web, Error,
+};
+use log::warn;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use crate::config::Config;
+use crate::error_handler::ErrorHandler;
+
+#[derive(Default)]
+struct Counters {
+    in_flight: AtomicUsize,
+    accepted: AtomicU64,
+    shed_in_flight: AtomicU64,
+    shed_memory: AtomicU64,
+}
+
+/// 워커 전체가 공유하는 업로드 한도와 차단 카운터
+#[derive(Clone)]
+pub struct UploadLimiter {
+    counters: Arc<Counters>,
+    max_in_flight: usize,
+    min_available_memory_mb: u64,
+    retry_after_secs: i64,
+}
+
+/// 처리 중인 업로드 1건 (drop되면 동시 업로드 수 감소)
+pub struct UploadPermit {
+    counters: Arc<Counters>,
+}
+
+impl Drop for UploadPermit {
+    fn drop(&mut self) {
+        self.counters.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub enum ShedReason {
+    InFlight(usize),
+    Memory(u64),
+}
+
+impl UploadLimiter {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            counters: Arc::new(Counters::default()),
+            max_in_flight: config.max_inflight_uploads,
+            min_available_memory_mb: config.upload_min_available_memory_mb,
+            retry_after_secs: config.upload_retry_after_secs,
+        }
+    }
+
+    /// 한도 안이면 permit 발급, 아니면 차단 사유 반환
+    pub fn try_acquire(&self) -> Result<UploadPermit, ShedReason> {
+        if let Some(available_mb) = available_memory_mb()
+            && available_mb < self.min_available_memory_mb
+        {
+            self.counters.shed_memory.fetch_add(1, Ordering::Relaxed);
+            return Err(ShedReason::Memory(available_mb));
+        }
+        let previous = self.counters.in_flight.fetch_add(1, Ordering::SeqCst);
+        if previous >= self.max_in_flight {
+            self.counters.in_flight.fetch_sub(1, Ordering::SeqCst);
+            self.counters.shed_in_flight.fetch_add(1, Ordering::Relaxed);
+            return Err(ShedReason::InFlight(previous));
+        }
+        self.counters.accepted.fetch_add(1, Ordering::Relaxed);
+        Ok(UploadPermit { counters: self.counters.clone() })
+    }
+
+    /// 메트릭 응답용 현재 상태
+    pub fn metrics_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "inFlight": self.counters.in_flight.load(Ordering::SeqCst),
+            "maxInFlight": self.max_in_flight,
+            "accepted": self.counters.accepted.load(Ordering::Relaxed),
+            "shedInFlight": self.counters.shed_in_flight.load(Ordering::Relaxed),
+            "shedMemory": self.counters.shed_memory.load(Ordering::Relaxed),
+            "availableMemoryMb": available_memory_mb(),
+            "minAvailableMemoryMb": self.min_available_memory_mb
+        })
+    }
+}
+
+/// /proc/meminfo의 MemAvailable (리눅스가 아니면 None → 메모리 기준 차단 안 함)
+fn available_memory_mb() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    meminfo.lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))
+        .and_then(|rest| rest.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+        .map(|kb| kb / 1024)
+}
+
+/// 업로드 스코프에 거는 미들웨어: POST 요청만 한도 확인, 본문을 읽기 전에 거절
+pub async fn shed_uploads(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let limiter = req.app_data::<web::Data<UploadLimiter>>().cloned();
+    let Some(limiter) = limiter.filter(|_| req.method() == Method::POST) else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+
+    match limiter.try_acquire() {
+        Ok(permit) => {
+            let response = next.call(req).await;
+            drop(permit);
+            Ok(response?.map_into_left_body())
+        }
+        Err(reason) => {
+            let details = match reason {
+                ShedReason::InFlight(count) => format!("동시 업로드 {}건 (한도 {})", count, limiter.max_in_flight),
+                ShedReason::Memory(available) => format!("가용 메모리 {}MB (최소 {}MB)", available, limiter.min_available_memory_mb),
+            };
+            warn!("🚧 업로드 차단: {} {} - {}", req.method(), req.path(), details);
+            let response = ErrorHandler::service_unavailable(
+                "서버가 혼잡합니다. 잠시 후 다시 시도해주세요.",
+                Some(&details),
+                limiter.retry_after_secs
+            );
+            Ok(req.into_response(response).map_into_right_body())
+        }
+    }
+}
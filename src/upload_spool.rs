@@ -0,0 +1,284 @@
+// 멀티파트 업로드 본문 임시 파일 스풀링과 디스크 여유 공간 확인
+use actix_multipart::Field;
+use actix_web::HttpResponse;
+use futures_util::stream::StreamExt;
+use log::{info, warn};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error_handler::ErrorHandler;
+use crate::storage::StorageUpload;
+
+const MB: f64 = 1024.0 * 1024.0;
+const TEMP_FILE_PREFIX: &str = "upload_";
+/// 내용 확인(매직 바이트)에 필요한 앞부분 크기 (WebP는 12바이트)
+const SNIFF_BYTES: usize = 16;
+
+#[derive(Debug)]
+pub enum SpoolError {
+    TooLarge { max_mb: f64 },
+    DiskFull { available_mb: u64, required_mb: u64 },
+    Payload(String),
+    Io(std::io::Error),
+    Storage(String),
+    UnsupportedContent(String),
+}
+
+impl fmt::Display for SpoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpoolError::TooLarge { max_mb } => write!(f, "upload exceeds {:.0}MB", max_mb),
+            SpoolError::DiskFull { available_mb, required_mb } => write!(f, "disk space {}MB available, {}MB required", available_mb, required_mb),
+            SpoolError::Payload(e) => write!(f, "payload read failed: {}", e),
+            SpoolError::Io(e) => write!(f, "temp file error: {}", e),
+            SpoolError::Storage(e) => write!(f, "storage upload failed: {}", e),
+            SpoolError::UnsupportedContent(e) => write!(f, "unsupported content: {}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for SpoolError {
+    fn from(e: std::io::Error) -> Self {
+        SpoolError::Io(e)
+    }
+}
+
+impl SpoolError {
+    pub fn into_response(self) -> HttpResponse {
+        match self {
+            SpoolError::TooLarge { max_mb } => ErrorHandler::bad_request(
+                &format!("파일 크기는 {:.0}MB를 초과할 수 없습니다", max_mb),
+                None,
+                Some("업로드 수신 중 크기 초과")
+            ),
+            SpoolError::DiskFull { available_mb, required_mb } => ErrorHandler::insufficient_storage(
+                "서버 저장 공간이 부족합니다. 잠시 후 다시 시도해주세요.",
+                Some(&format!("가용 {}MB, 필요 {}MB", available_mb, required_mb))
+            ),
+            SpoolError::Payload(e) => ErrorHandler::bad_request(
+                "파일 읽기 실패",
+                Some(&e),
+                Some("업로드 본문 수신 중단")
+            ),
+            SpoolError::Io(e) => ErrorHandler::internal_server_error(
+                "업로드 임시 파일 처리 실패",
+                Some(&e.to_string())
+            ),
+            SpoolError::Storage(e) => ErrorHandler::internal_server_error(
+                "파일 저장 실패",
+                Some(&e)
+            ),
+            SpoolError::UnsupportedContent(e) => ErrorHandler::unprocessable_entity(
+                &format!("{} (jpg, jpeg, png, gif, bmp, webp만 가능)", e),
+                Some("파일 내용(매직 바이트) 확인")
+            ),
+        }
+    }
+}
+
+/// 업로드 본문 버퍼: 임계값까지는 메모리, 넘으면 임시 파일로 옮겨 씀
+/// drop 시 임시 파일을 지우므로 요청이 중간에 끊겨도 파일이 남지 않음
+pub struct UploadSpool {
+    threshold_bytes: usize,
+    max_bytes: usize,
+    temp_dir: PathBuf,
+    min_free_disk_mb: u64,
+    buffer: Vec<u8>,
+    file: Option<(PathBuf, tokio::fs::File)>,
+    len: usize,
+}
+
+impl UploadSpool {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            threshold_bytes: (config.upload_spool_threshold_mb * MB) as usize,
+            max_bytes: (config.max_file_size_mb * MB) as usize,
+            temp_dir: PathBuf::from(&config.upload_temp_dir),
+            min_free_disk_mb: config.min_free_disk_mb,
+            buffer: Vec::new(),
+            file: None,
+            len: 0,
+        }
+    }
+
+    /// 처음부터 임시 파일로 받는 스풀 (이미지를 파일에서 바로 디코딩할 때)
+    pub fn on_disk(config: &Config) -> Self {
+        let mut spool = Self::new(config);
+        spool.threshold_bytes = 0;
+        spool
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub async fn write(&mut self, chunk: &[u8]) -> Result<(), SpoolError> {
+        if self.len + chunk.len() > self.max_bytes {
+            return Err(SpoolError::TooLarge { max_mb: self.max_bytes as f64 / MB });
+        }
+        self.len += chunk.len();
+
+        if self.file.is_none() && self.len > self.threshold_bytes {
+            self.spill_to_disk().await?;
+        }
+        match self.file.as_mut() {
+            Some((_, file)) => file.write_all(chunk).await?,
+            None => self.buffer.extend_from_slice(chunk),
+        }
+        Ok(())
+    }
+
+    /// 지금까지 받은 메모리 버퍼를 임시 파일로 옮김 (최대 크기만큼 여유 공간 확인)
+    async fn spill_to_disk(&mut self) -> Result<(), SpoolError> {
+        tokio::fs::create_dir_all(&self.temp_dir).await?;
+        ensure_disk_space(&self.temp_dir, self.max_bytes as u64, self.min_free_disk_mb)?;
+
+        let path = self.temp_dir.join(format!("{}{}", TEMP_FILE_PREFIX, Uuid::new_v4().simple()));
+        let file = tokio::fs::File::create(&path).await?;
+        // 먼저 등록해 두어야 아래 쓰기가 실패해도 drop에서 지워짐
+        let buffered = std::mem::take(&mut self.buffer);
+        let (_, file) = self.file.insert((path.clone(), file));
+        file.write_all(&buffered).await?;
+        info!("💾 업로드 본문 임시 파일로 전환: {} ({:.2}MB)", path.display(), buffered.len() as f64 / MB);
+        Ok(())
+    }
+
+    /// 받은 내용을 디스크에 기록하고 임시 파일 경로 반환 (메모리에만 있으면 None)
+    pub async fn flush(&mut self) -> Result<Option<&Path>, SpoolError> {
+        match self.file.as_mut() {
+            Some((path, file)) => {
+                file.flush().await?;
+                Ok(Some(path.as_path()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 이미지 처리를 위해 전체 본문을 읽어옴 (임시 파일은 drop 시 삭제)
+    pub async fn into_bytes(mut self) -> Result<Vec<u8>, SpoolError> {
+        match self.file.as_mut() {
+            Some((path, file)) => {
+                file.flush().await?;
+                Ok(tokio::fs::read(&path).await?)
+            }
+            None => Ok(std::mem::take(&mut self.buffer)),
+        }
+    }
+}
+
+impl Drop for UploadSpool {
+    fn drop(&mut self) {
+        if let Some((path, _)) = self.file.take()
+            && let Err(e) = std::fs::remove_file(&path)
+        {
+            warn!("⚠️ 업로드 임시 파일 삭제 실패: {} - {}", path.display(), e);
+        }
+    }
+}
+
+/// 멀티파트 필드 하나를 끝까지 받아 스풀에 담음
+pub async fn spool_field(field: &mut Field, config: &Config) -> Result<UploadSpool, SpoolError> {
+    let mut spool = UploadSpool::new(config);
+    while let Some(chunk) = field.next().await {
+        let data = chunk.map_err(|e| SpoolError::Payload(e.to_string()))?;
+        spool.write(&data).await?;
+    }
+    Ok(spool)
+}
+
+/// 필드를 임시 파일로 받으면서 받은 조각을 그대로 저장소 업로드로 보냄
+/// (S3는 요청당 멀티파트 파트 하나만 메모리에 둠, 실패 시 업로드 취소는 호출한 쪽에서)
+/// 앞부분은 check_content로 내용을 확인한 뒤에 저장소로 보내므로 거부된 파일은 저장소에 쓰지 않음
+pub async fn stream_field_to_storage<T>(
+    field: &mut Field,
+    config: &Config,
+    upload: &mut dyn StorageUpload,
+    check_content: impl Fn(&[u8]) -> Result<T, String>,
+) -> Result<UploadSpool, SpoolError> {
+    let mut spool = UploadSpool::on_disk(config);
+    let mut head = Vec::new();
+    let mut checked = false;
+    while let Some(chunk) = field.next().await {
+        let data = chunk.map_err(|e| SpoolError::Payload(e.to_string()))?;
+        spool.write(&data).await?;
+        if checked {
+            upload.write(&data).await.map_err(|e| SpoolError::Storage(e.to_string()))?;
+            continue;
+        }
+        head.extend_from_slice(&data);
+        if head.len() >= SNIFF_BYTES {
+            check_content(&head).map_err(SpoolError::UnsupportedContent)?;
+            checked = true;
+            upload.write(&std::mem::take(&mut head)).await.map_err(|e| SpoolError::Storage(e.to_string()))?;
+        }
+    }
+    // SNIFF_BYTES보다 작은 파일은 끝까지 받은 뒤 확인
+    if !head.is_empty() {
+        check_content(&head).map_err(SpoolError::UnsupportedContent)?;
+        upload.write(&head).await.map_err(|e| SpoolError::Storage(e.to_string()))?;
+    }
+    Ok(spool)
+}
+
+/// 필드를 받은 뒤 이미지 처리용 바이트로 반환
+pub async fn receive_field(field: &mut Field, config: &Config) -> Result<Vec<u8>, SpoolError> {
+    spool_field(field, config).await?.into_bytes().await
+}
+
+/// 경로가 속한 파일시스템의 가용 공간 (MB), 확인할 수 없으면 None
+#[cfg(unix)]
+pub fn available_disk_mb(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path는 NUL로 끝나는 유효한 문자열이고 stat은 호출 동안 살아있음
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some((stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64) / (1024 * 1024))
+}
+
+#[cfg(not(unix))]
+pub fn available_disk_mb(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// 쓰기 후에도 최소 여유 공간이 남는지 확인
+pub fn ensure_disk_space(dir: &Path, bytes: u64, min_free_disk_mb: u64) -> Result<(), SpoolError> {
+    let Some(available_mb) = available_disk_mb(dir) else {
+        return Ok(());
+    };
+    let required_mb = bytes.div_ceil(1024 * 1024) + min_free_disk_mb;
+    if available_mb < required_mb {
+        warn!("🚧 디스크 공간 부족: {} 가용 {}MB, 필요 {}MB", dir.display(), available_mb, required_mb);
+        return Err(SpoolError::DiskFull { available_mb, required_mb });
+    }
+    Ok(())
+}
+
+/// 서버가 비정상 종료되어 남은 임시 파일 정리 (시작 시 1회)
+pub fn clean_stale_temp_files(config: &Config) {
+    let Ok(entries) = std::fs::read_dir(&config.upload_temp_dir) else {
+        return;
+    };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with(TEMP_FILE_PREFIX)
+            && std::fs::remove_file(entry.path()).is_ok()
+        {
+            removed += 1;
+        }
+    }
+    if removed > 0 {
+        info!("🧹 남은 업로드 임시 파일 {}개 정리", removed);
+    }
+}
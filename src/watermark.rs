@@ -0,0 +1,187 @@
+// 워터마크 (다운로드한 원본과 공유 카드에 문구 또는 PNG를 오른쪽 아래에 반투명하게 찍음)
+// 배포 단위로 WATERMARK_ENABLED로 켜고, 요청마다 ?watermark=false로 끌 수 있음
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
+use anyhow::{bail, Result};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageOutputFormat, Rgba, RgbaImage};
+use log::{info, warn};
+use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::share_card;
+
+/// 워터마크 너비 (이미지 너비 대비)
+const WATERMARK_WIDTH_RATIO: f32 = 0.2;
+/// 가장자리 여백 (이미지 짧은 변 대비)
+const WATERMARK_MARGIN_RATIO: f32 = 0.03;
+const WATERMARK_JPEG_QUALITY: u8 = 90;
+const WATERMARK_WEBP_QUALITY: f32 = 90.0;
+const WATERMARK_TEXT_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const WATERMARK_SHADOW_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// 워터마크 쿼리 (?watermark=false면 이번 요청만 워터마크 없이, 배포에서 끈 경우 true여도 찍지 않음)
+#[derive(Debug, Default, Deserialize)]
+pub struct WatermarkQuery {
+    pub watermark: Option<bool>,
+}
+
+#[derive(Clone)]
+enum Mark {
+    Text { text: String, font: FontArc },
+    Overlay(Arc<RgbaImage>),
+}
+
+/// 설정된 워터마크 (WATERMARK_ENABLED가 아니거나 문구/이미지가 없으면 아무것도 찍지 않음)
+#[derive(Clone, Default)]
+pub struct Watermark {
+    mark: Option<Mark>,
+    opacity: f32,
+    fingerprint: u64, // 워터마크 설정이 바뀌면 공유 카드 캐시도 새로 만듦
+}
+
+impl Watermark {
+    /// 문구 워터마크 (글꼴은 공유 카드 본문 글꼴)
+    pub fn text(text: &str, font: FontArc, opacity: f32) -> Self {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        opacity.to_bits().hash(&mut hasher);
+        Self {
+            mark: Some(Mark::Text { text: text.to_string(), font }),
+            opacity: opacity.clamp(0.0, 1.0),
+            fingerprint: hasher.finish(),
+        }
+    }
+
+    /// PNG 오버레이 워터마크 (알파 채널 그대로, 전체 불투명도만 곱함)
+    pub fn overlay(overlay: RgbaImage, opacity: f32) -> Self {
+        let mut hasher = DefaultHasher::new();
+        overlay.dimensions().hash(&mut hasher);
+        overlay.as_raw().hash(&mut hasher);
+        opacity.to_bits().hash(&mut hasher);
+        Self {
+            mark: Some(Mark::Overlay(Arc::new(overlay))),
+            opacity: opacity.clamp(0.0, 1.0),
+            fingerprint: hasher.finish(),
+        }
+    }
+
+    /// WATERMARK_IMAGE_PATH가 있으면 PNG, 없으면 WATERMARK_TEXT
+    pub fn from_config(config: &Config) -> Self {
+        if !config.watermark_enabled {
+            return Self::default();
+        }
+        if !config.watermark_image_path.is_empty() {
+            return match image::open(&config.watermark_image_path) {
+                Ok(overlay) => {
+                    info!("✅ 워터마크 이미지 로드: {}", config.watermark_image_path);
+                    Self::overlay(overlay.to_rgba8(), config.watermark_opacity)
+                }
+                Err(e) => {
+                    warn!("⚠️ 워터마크 이미지를 읽을 수 없어 워터마크를 찍지 않습니다 ({}): {}", config.watermark_image_path, e);
+                    Self::default()
+                }
+            };
+        }
+        if config.watermark_text.trim().is_empty() {
+            warn!("⚠️ WATERMARK_TEXT와 WATERMARK_IMAGE_PATH가 없어 워터마크를 찍지 않습니다");
+            return Self::default();
+        }
+        match share_card::load_font(&config.share_card_font_path) {
+            Some(font) => Self::text(config.watermark_text.trim(), font, config.watermark_opacity),
+            None => {
+                warn!("⚠️ 워터마크 글꼴이 없어 워터마크를 찍지 않습니다");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.mark.is_some()
+    }
+
+    /// 이번 요청에 워터마크를 찍을지 (배포에서 켜져 있고 ?watermark=false가 아닐 때)
+    pub fn applies_to(&self, query: &WatermarkQuery) -> bool {
+        self.is_enabled() && query.watermark.unwrap_or(true)
+    }
+
+    /// 워터마크를 찍은 결과의 캐시 키 (cards/marker_1_abcd.png → cards/marker_1_abcd_wm1234.png)
+    pub fn cache_key(&self, key: &str) -> String {
+        let suffix = format!("_wm{:016x}", self.fingerprint);
+        match key.rsplit_once('.') {
+            Some((stem, extension)) => format!("{}{}.{}", stem, suffix, extension),
+            None => format!("{}{}", key, suffix),
+        }
+    }
+
+    /// 오른쪽 아래에 워터마크 찍기 (이미지 너비의 20%)
+    pub fn apply(&self, image: &mut RgbaImage) {
+        let Some(mark) = &self.mark else {
+            return;
+        };
+        let (width, height) = image.dimensions();
+        let margin = (width.min(height) as f32 * WATERMARK_MARGIN_RATIO).round() as i32;
+        let target_width = (width as f32 * WATERMARK_WIDTH_RATIO).max(1.0);
+        match mark {
+            Mark::Overlay(overlay) => {
+                let scale = target_width / overlay.width() as f32;
+                let scaled_height = ((overlay.height() as f32 * scale).round() as u32).max(1);
+                let scaled = image::imageops::resize(overlay.as_ref(), target_width.round() as u32, scaled_height, FilterType::Triangle);
+                let x = width as i32 - scaled.width() as i32 - margin;
+                let y = height as i32 - scaled.height() as i32 - margin;
+                for (ox, oy, pixel) in scaled.enumerate_pixels() {
+                    let coverage = f32::from(pixel[3]) / 255.0 * self.opacity;
+                    share_card::blend_pixel(image, x + ox as i32, y + oy as i32, Rgba([pixel[0], pixel[1], pixel[2], 255]), coverage);
+                }
+            }
+            Mark::Text { text, font } => {
+                // 너비에 맞춘 글자 크기 (너무 작은 이미지에서도 읽을 수 있게 최소 12px)
+                let unit_width = share_card::text_width(font, 100.0, text).max(1.0);
+                let scale = (target_width / unit_width * 100.0).max(12.0);
+                let scaled = font.as_scaled(PxScale::from(scale));
+                let text_height = (scaled.ascent() - scaled.descent()).ceil() as i32;
+                let x = width as i32 - share_card::text_width(font, scale, text).ceil() as i32 - margin;
+                let y = height as i32 - text_height - margin;
+                let shadow = (scale / 24.0).ceil() as i32;
+                let alpha = (self.opacity * 255.0).round() as u8;
+                let shadow_color = Rgba([WATERMARK_SHADOW_COLOR[0], WATERMARK_SHADOW_COLOR[1], WATERMARK_SHADOW_COLOR[2], alpha / 2]);
+                let text_color = Rgba([WATERMARK_TEXT_COLOR[0], WATERMARK_TEXT_COLOR[1], WATERMARK_TEXT_COLOR[2], alpha]);
+                share_card::draw_text(image, font, scale, x + shadow, y + shadow, shadow_color, text);
+                share_card::draw_text(image, font, scale, x, y, text_color, text);
+            }
+        }
+    }
+
+    /// 이미지 파일에 워터마크를 찍어 같은 형식으로 다시 인코딩 (GIF/BMP는 PNG로), (바이트, Content-Type) 반환
+    pub fn apply_to_bytes(&self, data: &[u8]) -> Result<(Vec<u8>, &'static str)> {
+        let format = image::guess_format(data)?;
+        let mut image = match format {
+            ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::Gif | ImageFormat::Bmp | ImageFormat::WebP => {
+                image::load_from_memory_with_format(data, format)?.to_rgba8()
+            }
+            other => bail!("워터마크를 찍을 수 없는 형식: {:?}", other),
+        };
+        self.apply(&mut image);
+        let mut output = Vec::new();
+        let content_type = match format {
+            ImageFormat::Jpeg => {
+                let rgb = DynamicImage::ImageRgba8(image).to_rgb8();
+                JpegEncoder::new_with_quality(&mut output, WATERMARK_JPEG_QUALITY).encode_image(&rgb)?;
+                "image/jpeg"
+            }
+            ImageFormat::WebP => {
+                output = webp::Encoder::from_rgba(&image, image.width(), image.height()).encode(WATERMARK_WEBP_QUALITY).to_vec();
+                "image/webp"
+            }
+            _ => {
+                DynamicImage::ImageRgba8(image).write_to(&mut Cursor::new(&mut output), ImageOutputFormat::Png)?;
+                "image/png"
+            }
+        };
+        Ok((output, content_type))
+    }
+}
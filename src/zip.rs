@@ -0,0 +1,138 @@
+// 개인 데이터 내보내기에 필요한 만큼만 구현한 ZIP 작성기
+// 무압축(stored) 항목만, ZIP64 없음 (항목/전체 4GB 미만), 파일 이름은 UTF-8 플래그로 기록
+// (https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT)
+use anyhow::{bail, Result};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+const VERSION_NEEDED: u16 = 20;
+const FLAG_UTF8_NAMES: u16 = 0x0800;
+const METHOD_STORED: u16 = 0;
+
+/// CRC-32 (IEEE 802.3, 반사 다항식 0xEDB88320) 표
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| CRC32_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+/// MS-DOS 날짜/시간 (1980년 이전은 1980-01-01, 초는 2초 단위)
+fn dos_date_time(at: DateTime<Utc>) -> (u16, u16) {
+    if at.year() < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+    let time = ((at.hour() as u16) << 11) | ((at.minute() as u16) << 5) | (at.second() as u16 / 2);
+    let date = (((at.year() - 1980) as u16) << 9) | ((at.month() as u16) << 5) | at.day() as u16;
+    (time, date)
+}
+
+struct CentralEntry {
+    name: String,
+    crc: u32,
+    size: u32,
+    time: u16,
+    date: u16,
+    offset: u32,
+}
+
+/// 메모리에 ZIP 파일을 만드는 작성기
+#[derive(Default)]
+pub struct ZipWriter {
+    out: Vec<u8>,
+    entries: Vec<CentralEntry>,
+}
+
+impl ZipWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 파일 하나 추가 (name은 `/`로 구분한 상대 경로)
+    pub fn add_file(&mut self, name: &str, data: &[u8], modified: DateTime<Utc>) -> Result<()> {
+        if name.is_empty() || name.len() > u16::MAX as usize || name.starts_with('/') {
+            bail!("ZIP 항목 이름이 올바르지 않습니다: {}", name);
+        }
+        let (Ok(size), Ok(offset)) = (u32::try_from(data.len()), u32::try_from(self.out.len())) else {
+            bail!("ZIP64가 필요한 크기는 지원하지 않습니다: {}", name);
+        };
+        let (time, date) = dos_date_time(modified);
+        let entry = CentralEntry { name: name.to_string(), crc: crc32(data), size, time, date, offset };
+
+        let out = &mut self.out;
+        out.extend_from_slice(&LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        out.extend_from_slice(&VERSION_NEEDED.to_le_bytes());
+        out.extend_from_slice(&FLAG_UTF8_NAMES.to_le_bytes());
+        out.extend_from_slice(&METHOD_STORED.to_le_bytes());
+        out.extend_from_slice(&entry.time.to_le_bytes());
+        out.extend_from_slice(&entry.date.to_le_bytes());
+        out.extend_from_slice(&entry.crc.to_le_bytes());
+        out.extend_from_slice(&entry.size.to_le_bytes()); // 압축 크기 (무압축이라 같음)
+        out.extend_from_slice(&entry.size.to_le_bytes());
+        out.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // extra field 없음
+        out.extend_from_slice(entry.name.as_bytes());
+        out.extend_from_slice(data);
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// 중앙 디렉터리와 끝 레코드를 붙여 완성
+    pub fn finish(mut self) -> Result<Vec<u8>> {
+        let Ok(directory_offset) = u32::try_from(self.out.len()) else {
+            bail!("ZIP64가 필요한 크기는 지원하지 않습니다");
+        };
+        if self.entries.len() > u16::MAX as usize {
+            bail!("ZIP 항목이 너무 많습니다: {}", self.entries.len());
+        }
+        let out = &mut self.out;
+        for entry in &self.entries {
+            out.extend_from_slice(&CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+            out.extend_from_slice(&VERSION_NEEDED.to_le_bytes()); // version made by
+            out.extend_from_slice(&VERSION_NEEDED.to_le_bytes());
+            out.extend_from_slice(&FLAG_UTF8_NAMES.to_le_bytes());
+            out.extend_from_slice(&METHOD_STORED.to_le_bytes());
+            out.extend_from_slice(&entry.time.to_le_bytes());
+            out.extend_from_slice(&entry.date.to_le_bytes());
+            out.extend_from_slice(&entry.crc.to_le_bytes());
+            out.extend_from_slice(&entry.size.to_le_bytes());
+            out.extend_from_slice(&entry.size.to_le_bytes());
+            out.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes()); // extra field
+            out.extend_from_slice(&0u16.to_le_bytes()); // comment
+            out.extend_from_slice(&0u16.to_le_bytes()); // disk number
+            out.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+            out.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+            out.extend_from_slice(&entry.offset.to_le_bytes());
+            out.extend_from_slice(entry.name.as_bytes());
+        }
+        let Ok(directory_size) = u32::try_from(out.len() - directory_offset as usize) else {
+            bail!("ZIP64가 필요한 크기는 지원하지 않습니다");
+        };
+        let entry_count = self.entries.len() as u16;
+        out.extend_from_slice(&END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // 이 디스크 번호
+        out.extend_from_slice(&0u16.to_le_bytes()); // 중앙 디렉터리 시작 디스크
+        out.extend_from_slice(&entry_count.to_le_bytes());
+        out.extend_from_slice(&entry_count.to_le_bytes());
+        out.extend_from_slice(&directory_size.to_le_bytes());
+        out.extend_from_slice(&directory_offset.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // 주석 없음
+        Ok(self.out)
+    }
+}
@@ -0,0 +1,4350 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::{self, TestRequest}};
+use bigpictureback::auth::Claims;
+use bigpictureback::anonymize::{anonymize_database, AnonymizeOptions};
+use bigpictureback::blurhash;
+use bigpictureback::build_app;
+use bigpictureback::cache_policy::{CachePolicies, CachePolicy};
+use bigpictureback::clock::FixedClock;
+use bigpictureback::cluster_cache::{ClusterCache, ClusterFilters, ClusterViewport};
+use bigpictureback::cluster_zoom::{self, ZoomResolutions};
+use bigpictureback::crosspost::{CrossPost, CrossPostProvider, CrossPostProviders, PublishedPost, RemoteAccount};
+use bigpictureback::geoip::GeoIp;
+use bigpictureback::geojson::{self, ResponseFormat};
+use bigpictureback::hashtags;
+use bigpictureback::idempotency;
+use bigpictureback::image_cleanup;
+use bigpictureback::image_pipeline::{self, ImageFrame, ImageHooks, ImagePlugin, ImageSource, PipelineStage};
+use bigpictureback::image_processor::{ImageProcessor, OutputFormat};
+use bigpictureback::image_workers::{ImageProcessingTimeout, ImageWorkers};
+use bigpictureback::emotions::{get_emotion_by_id, unknown_emotion_ids, EMOTION_TAGS};
+use bigpictureback::embed;
+use bigpictureback::public_id;
+use bigpictureback::resumable_upload::{self, UploadSessions};
+use bigpictureback::emotion_tiles::TileCoord;
+use bigpictureback::emotion_profile::{build_profile, ProfileRange};
+use bigpictureback::location_suggest::{self, Place, PlaceSearch, Places};
+use bigpictureback::marker_events::{Bounds, MarkerEvent, MarkerEventKind};
+use bigpictureback::marker_export;
+use bigpictureback::marker_views;
+use bigpictureback::orphan_gc;
+use bigpictureback::moderation::{ImageModerator, Moderation, ModerationStatus, ModerationVerdict, OnnxModerator};
+use bigpictureback::zip::{self, ZipWriter};
+use bigpictureback::presence::MarkerPresence;
+use bigpictureback::promotions;
+use bigpictureback::push::{self, PushMessage, PushResult, PushSender};
+use bigpictureback::reverse_geocode::{self, GeocodeError, ReverseGeocoder};
+use bigpictureback::route_usage::{normalize_client_version, UNKNOWN_VERSION};
+use bigpictureback::scheduled_markers;
+use bigpictureback::search_index::{self, SearchBackend, SearchHits, SearchIndex, SearchRequest};
+use bigpictureback::share_card::{self, CardFonts, MapInset, ShareCard};
+use bigpictureback::storage::{self, LocalStorage, Storage};
+use bigpictureback::database::{
+    marker_search_tsquery, ApiKey, RegistrationConflict, ApiKeyUsage, AuthProvider, ContentTakedownAudit, CreatedTimeFilter, EmotionMonthCount, Database, NewDeadLetterJob, MemberTrustStats, District, Hobby, ImageCleanupJob, Interest, Marker,
+    MarkerAddress, MarkerImage, MarkerSearchDocument, Member, MemberHobby, MemberInterest, MemberMarker, NewMarkerPromotion, Notification, OriginalImage, PromotionEvent, WebpImage,
+};
+use bigpictureback::rate_limit::AuthRateLimiter;
+use bigpictureback::schema_check::verify_schema;
+use bigpictureback::totp::{provisioning_uri, verify_code};
+use bigpictureback::trust::{contains_link, TrustLevel, TrustPolicy};
+use bigpictureback::upload_guard::UploadLimiter;
+use bigpictureback::watermark::{Watermark, WatermarkQuery};
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use std::sync::Arc;
+
+use common::{as_member, bearer_token, deterministic, fake_state, get, post_json, read_json, test_config, TestDatabase, TEST_JWT_SECRET};
+
+#[actix_web::test]
+async fn health_check_is_ok() {
+    let app = test::init_service(build_app(fake_state())).await;
+    let (status, body) = read_json(test::call_service(&app, get("/api/health").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "healthy");
+}
+
+#[actix_web::test]
+async fn me_requires_token() {
+    let app = test::init_service(build_app(fake_state())).await;
+    let (status, body) = read_json(test::call_service(&app, get("/api/members/me").to_request()).await).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["success"], false);
+}
+
+#[actix_web::test]
+async fn public_api_requires_api_key() {
+    let app = test::init_service(build_app(fake_state())).await;
+    let response = test::call_service(&app, get("/api/public/v1/emotions/stats").to_request()).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn markers_require_area_or_district() {
+    let app = test::init_service(build_app(fake_state())).await;
+    let response = test::call_service(&app, get("/api/markers").to_request()).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn registered_member_can_read_me() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let app = test::init_service(build_app(test_db.state.clone())).await;
+
+    let register = post_json("/api/members", &json!({
+        "email": "tester@example.invalid",
+        "nickname": "tester"
+    }));
+    let (status, body) = read_json(test::call_service(&app, register.to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let member_id = body["data"]["id"].as_i64().expect("member id");
+
+    let me = as_member(get("/api/members/me"), member_id, &test_db.state);
+    let (status, body) = read_json(test::call_service(&app, me.to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["email"], "tester@example.invalid");
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn token_expires_by_injected_clock() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()));
+    let state = deterministic(fake_state(), clock.clone());
+    let app = test::init_service(build_app(state.clone())).await;
+    // 언어 코드 검증은 DB 전에 실패하므로 인증 통과 여부만 확인 가능
+    let request = || as_member(TestRequest::put().uri("/api/members/me/languages"), 1, &state)
+        .set_json(json!({ "languages": ["xx"] }));
+
+    let token_request = request();
+    clock.advance(Duration::minutes(59));
+    let response = test::call_service(&app, token_request.to_request()).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let token_request = request();
+    clock.advance(Duration::hours(2));
+    let response = test::call_service(&app, token_request.to_request()).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn token_audience_must_match_config() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let request = |audience: &str| {
+        let claims = Claims {
+            sub: "1".to_string(),
+            email: "member1@example.invalid".to_string(),
+            exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
+            iss: Some(state.config.jwt_issuer.clone()),
+            aud: Some(audience.to_string()),
+            role: Some("member".to_string()),
+            nickname: Some("member1".to_string()),
+            iat: None,
+        };
+        let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(TEST_JWT_SECRET.as_bytes())).expect("token");
+        TestRequest::put().uri("/api/members/me/languages")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "languages": ["xx"] }))
+    };
+
+    let response = test::call_service(&app, request(&state.config.jwt_audience).to_request()).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = test::call_service(&app, request("some-other-app").to_request()).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn account_deletion_requires_token() {
+    let app = test::init_service(build_app(fake_state())).await;
+    let response = test::call_service(&app, TestRequest::delete().uri("/api/members/me").to_request()).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn account_deletion_removes_personal_data() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let pool = &test_db.state.database.pool;
+    sqlx::Executor::execute(pool, r#"
+        INSERT INTO bigpicture.members (email, nickname, profile_image_url) VALUES ('leaving@example.invalid', 'leaving', '/profiles/leaving.webp');
+        INSERT INTO bigpicture.auth_providers (member_id, provider_type, provider_id)
+            SELECT id, 'google', 'google-leaving' FROM bigpicture.members;
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, author, sharing_option, legal_hold, thumbnail_img)
+            SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', v.description, nickname, v.sharing_option, v.legal_hold, v.thumbnail_img
+            FROM bigpicture.members, (VALUES
+                ('공개', 'public', false, NULL),
+                ('비공개', 'private', false, '/markers/private.webp'),
+                ('보존', 'private', true, NULL)
+            ) AS v(description, sharing_option, legal_hold, thumbnail_img);
+        INSERT INTO bigpicture.marker_images (marker_id, image_type, image_url, is_primary)
+            SELECT id, 'detail', '/markers/private-detail.webp', true FROM bigpicture.markers WHERE description = '비공개';
+    "#).await.expect("seed");
+    let member_id: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(pool).await.unwrap();
+
+    let summary = test_db.state.database.delete_member_account(member_id).await.expect("delete").expect("member exists");
+    assert_eq!((summary.deleted_markers, summary.anonymized_markers, summary.queued_images), (1, 2, 3));
+
+    let (email, nickname, is_active, deleted): (String, String, bool, bool) = sqlx::query_as(
+        "SELECT email, nickname, is_active, deleted_at IS NOT NULL FROM bigpicture.members WHERE id = $1"
+    ).bind(member_id).fetch_one(pool).await.unwrap();
+    assert_eq!(email, format!("deleted_{}@deleted.invalid", member_id));
+    assert_eq!(nickname, "탈퇴회원");
+    assert_eq!((is_active, deleted), (false, true));
+
+    // 비공개 마커는 삭제(법적 보존 제외), 남은 마커의 작성자는 탈퇴회원
+    let markers: Vec<(String, String)> = sqlx::query_as("SELECT description, author FROM bigpicture.markers ORDER BY id").fetch_all(pool).await.unwrap();
+    assert_eq!(markers, vec![("공개".to_string(), "탈퇴회원".to_string()), ("보존".to_string(), "탈퇴회원".to_string())]);
+
+    let providers: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.auth_providers WHERE member_id = $1").bind(member_id).fetch_one(pool).await.unwrap();
+    assert_eq!(providers, 0);
+
+    let mut queued: Vec<String> = sqlx::query_scalar("SELECT image_url FROM bigpicture.image_cleanup_jobs WHERE reason = 'account_deletion'").fetch_all(pool).await.unwrap();
+    queued.sort();
+    assert_eq!(queued, vec!["/markers/private-detail.webp", "/markers/private.webp", "/profiles/leaving.webp"]);
+
+    // 이미 탈퇴한 회원은 다시 처리하지 않음
+    assert!(test_db.state.database.delete_member_account(member_id).await.expect("delete again").is_none());
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn uploads_are_shed_when_saturated() {
+    let mut config = test_config();
+    config.max_inflight_uploads = 0;
+    config.upload_min_available_memory_mb = 0;
+    let mut state = fake_state();
+    state.upload_limiter = UploadLimiter::new(&config);
+    let app = test::init_service(build_app(state)).await;
+
+    let response = test::call_service(&app, TestRequest::post().uri("/api/images/upload/thumbnail").to_request()).await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().contains_key("Retry-After"));
+
+    let (status, body) = read_json(test::call_service(&app, get("/api/metrics").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["uploads"]["shedInFlight"], 1);
+    assert_eq!(body["data"]["uploads"]["inFlight"], 0);
+}
+
+#[actix_web::test]
+async fn readiness_reports_unreachable_database() {
+    let app = test::init_service(build_app(fake_state())).await;
+    let (status, body) = read_json(test::call_service(&app, get("/api/health/ready").to_request()).await).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["reason"], "database_unavailable");
+}
+
+#[actix_web::test]
+async fn migrated_schema_passes_verification() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let report = verify_schema(&test_db.state.database.pool).await.expect("schema query");
+    assert!(report.is_ok(), "{:?}", report);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn restart_migrations_keep_existing_data() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    sqlx::Executor::execute(&test_db.state.database.pool, r#"
+        CREATE TABLE bigpicture.images (id SERIAL PRIMARY KEY, filename TEXT);
+        INSERT INTO bigpicture.images (filename) VALUES ('legacy.webp');
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('keep@example.invalid', 'keep');
+    "#).await.expect("seed");
+
+    // 재시작: 이미 적용된 마이그레이션은 건너뛰고 데이터는 그대로
+    let restarted = Database::connect(&test_db.url()).await.expect("rerun migrations");
+    let members: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.members").fetch_one(&restarted.pool).await.unwrap();
+    let images: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.images").fetch_one(&restarted.pool).await.unwrap();
+    assert_eq!((members, images), (1, 1));
+    restarted.pool.close().await;
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn profile_update_rejects_taken_nickname() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let app = test::init_service(build_app(test_db.state.clone())).await;
+    let mut member_ids = Vec::new();
+    for (email, nickname) in [("first@example.invalid", "first"), ("second@example.invalid", "second")] {
+        let register = post_json("/api/members", &json!({ "email": email, "nickname": nickname }));
+        let (_, body) = read_json(test::call_service(&app, register.to_request()).await).await;
+        member_ids.push(body["data"]["id"].as_i64().expect("member id"));
+    }
+
+    let update = |body: serde_json::Value| as_member(TestRequest::patch().uri("/api/members/me").set_json(body), member_ids[1], &test_db.state);
+    let (status, _) = read_json(test::call_service(&app, update(json!({ "nickname": " FIRST " })).to_request()).await).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, body) = read_json(test::call_service(&app, update(json!({ "nickname": "renamed", "region": "서울" })).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["nickname"], "renamed");
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn login_is_rate_limited_per_account() {
+    let mut config = test_config();
+    config.auth_rate_limit_per_account = 1;
+    let mut state = fake_state();
+    state.auth_rate_limiter = AuthRateLimiter::new(&config);
+    let app = test::init_service(build_app(state)).await;
+
+    let login = || post_json("/api/auth/login", &json!({ "email": "Target@example.invalid", "password": "guess" }));
+    let first = test::call_service(&app, login().to_request()).await;
+    assert_ne!(first.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let second = test::call_service(&app, login().to_request()).await;
+    assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(second.headers().contains_key("Retry-After"));
+}
+
+#[actix_web::test]
+async fn auth_ip_limit_ignores_forwarded_headers_from_untrusted_peers() {
+    let mut config = test_config();
+    config.auth_rate_limit_per_ip = 1;
+    config.auth_rate_limit_per_account = 100;
+    config.trusted_proxies = vec!["10.0.0.1".parse().unwrap()];
+    let mut state = fake_state();
+    state.auth_rate_limiter = AuthRateLimiter::new(&config);
+    let app = test::init_service(build_app(state)).await;
+    let login = |peer: &str, forwarded_for: &str| post_json("/api/auth/login", &json!({ "email": "ip@example.invalid", "password": "guess" }))
+        .peer_addr(format!("{}:40000", peer).parse().unwrap())
+        .insert_header(("X-Forwarded-For", forwarded_for.to_string()));
+
+    // 직접 접속한 클라이언트가 헤더를 바꿔도 같은 IP로 집계
+    assert_ne!(test::call_service(&app, login("203.0.113.7", "198.51.100.1").to_request()).await.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(test::call_service(&app, login("203.0.113.7", "198.51.100.2").to_request()).await.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // 신뢰하는 프록시를 거친 요청은 전달된 클라이언트 IP별로 집계
+    assert_ne!(test::call_service(&app, login("10.0.0.1", "198.51.100.3").to_request()).await.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_ne!(test::call_service(&app, login("10.0.0.1", "198.51.100.4").to_request()).await.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(test::call_service(&app, login("10.0.0.1", "198.51.100.4").to_request()).await.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[actix_web::test]
+async fn repeated_login_failures_lock_account() {
+    let Some(mut test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    test_db.state.config.login_lockout_max_failures = 2;
+    let app = test::init_service(build_app(test_db.state.clone())).await;
+
+    let login = || post_json("/api/auth/login", &json!({ "email": "nobody@example.invalid", "password": "guess" }));
+    for _ in 0..2 {
+        let response = test::call_service(&app, login().to_request()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let locked = test::call_service(&app, login().to_request()).await;
+    assert_eq!(locked.status(), StatusCode::LOCKED);
+    assert!(locked.headers().contains_key("Retry-After"));
+
+    test_db.drop_database().await;
+}
+
+/// 행 하나를 모델로 디코딩 (컬럼 이름/타입/NULL 여부가 어긋나면 모델 이름과 함께 실패)
+async fn decode_one<T>(pool: &sqlx::PgPool, sql: &str) -> T
+where
+    T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> + Send + Unpin,
+{
+    sqlx::query_as::<_, T>(sql)
+        .fetch_one(pool)
+        .await
+        .unwrap_or_else(|e| panic!("{} ↔ 스키마 불일치: {}", std::any::type_name::<T>(), e))
+}
+
+#[actix_web::test]
+async fn from_row_models_match_migrated_schema() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let pool = &test_db.state.database.pool;
+    sqlx::Executor::execute(pool, r#"
+        INSERT INTO bigpicture.members (email, nickname, preferred_languages) VALUES ('model@example.invalid', 'model', ARRAY['ko']);
+        INSERT INTO bigpicture.auth_providers (member_id, provider_type, provider_id, provider_email, password_hash)
+            SELECT id, 'email', email, email, 'hash' FROM bigpicture.members;
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, author, language)
+            SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', '설명', nickname, 'ko' FROM bigpicture.members;
+        INSERT INTO bigpicture.marker_images (marker_id, image_type, image_url, is_primary)
+            SELECT id, 'thumbnail', 'https://example.invalid/model.webp', true FROM bigpicture.markers;
+        INSERT INTO bigpicture.member_markers (member_id, marker_id, interaction_type) SELECT member_id, id, 'created' FROM bigpicture.markers;
+        INSERT INTO bigpicture.hobbies (name) VALUES ('등산');
+        INSERT INTO bigpicture.interests (name) VALUES ('사진');
+        INSERT INTO bigpicture.member_hobbies (member_id, hobby_id, proficiency_level) SELECT m.id, h.id, 3 FROM bigpicture.members m, bigpicture.hobbies h;
+        INSERT INTO bigpicture.member_interests (member_id, interest_id, interest_level) SELECT m.id, i.id, 3 FROM bigpicture.members m, bigpicture.interests i;
+        INSERT INTO bigpicture.notifications (member_id, notification_type, title, marker_ids, dedupe_key)
+            SELECT member_id, 'memories', '추억', ARRAY[id], 'model' FROM bigpicture.markers;
+        INSERT INTO bigpicture.districts (code, name, level, geom)
+            VALUES ('11', '서울특별시', 'sido', ST_Multi(ST_GeomFromText('POLYGON((126 37, 127 37, 127 38, 126 38, 126 37))', 4326)));
+        INSERT INTO bigpicture.api_keys (key_hash, key_prefix, name, scope, daily_quota) VALUES (repeat('a', 64), 'bp_model', 'model', 'public_read', 100);
+        INSERT INTO bigpicture.api_key_usage (api_key_id, usage_date, endpoint, request_count) SELECT id, CURRENT_DATE, '/api/public/markers', 1 FROM bigpicture.api_keys;
+        INSERT INTO bigpicture.content_takedown_audit (admin_member_id, target_type, target_id, action, reason, legal_hold)
+            SELECT member_id, 'marker', id, 'takedown', '신고', false FROM bigpicture.markers;
+        INSERT INTO bigpicture.image_cleanup_jobs (image_url, reason) VALUES ('https://example.invalid/model.webp', 'account_deletion');
+        INSERT INTO bigpicture.original_images (filename, original_filename, file_path, file_size_mb, format) VALUES ('model.jpg', 'model.jpg', 'uploads/model.jpg', 0.5, 'jpeg');
+        INSERT INTO bigpicture.webp_images (original_id, filename, file_path, file_size_mb, image_type)
+            SELECT id, 'model.webp', 'uploads/model.webp', 0.1, 'thumbnail' FROM bigpicture.original_images;
+    "#).await.expect("seed rows");
+
+    // ImageInfo는 마이그레이션이 만들지 않는 예전 images 테이블용이라 제외
+    let _: Member = decode_one(pool, "SELECT * FROM bigpicture.members").await;
+    let _: AuthProvider = decode_one(pool, "SELECT * FROM bigpicture.auth_providers").await;
+    let _: Marker = decode_one(pool, r#"
+        SELECT id, member_id, ST_AsText(location) AS location, emotion_tag, emotion, emotion_tag_input, description,
+               sharing_option, likes, dislikes, views, author, thumbnail_img, language, taken_down_at, created_at, updated_at
+        FROM bigpicture.markers
+    "#).await;
+    let _: MarkerImage = decode_one(pool, "SELECT * FROM bigpicture.marker_images").await;
+    let _: MemberMarker = decode_one(pool, "SELECT * FROM bigpicture.member_markers").await;
+    let _: Hobby = decode_one(pool, "SELECT * FROM bigpicture.hobbies").await;
+    let _: Interest = decode_one(pool, "SELECT * FROM bigpicture.interests").await;
+    let _: MemberHobby = decode_one(pool, "SELECT * FROM bigpicture.member_hobbies").await;
+    let _: MemberInterest = decode_one(pool, "SELECT * FROM bigpicture.member_interests").await;
+    let _: Notification = decode_one(pool, "SELECT * FROM bigpicture.notifications").await;
+    let _: District = decode_one(pool, "SELECT code, name, level, parent_code, updated_at FROM bigpicture.districts").await;
+    let _: ApiKey = decode_one(pool, "SELECT * FROM bigpicture.api_keys").await;
+    let _: ApiKeyUsage = decode_one(pool, "SELECT * FROM bigpicture.api_key_usage").await;
+    let _: ContentTakedownAudit = decode_one(pool, "SELECT * FROM bigpicture.content_takedown_audit").await;
+    let _: ImageCleanupJob = decode_one(pool, "SELECT * FROM bigpicture.image_cleanup_jobs").await;
+    let _: OriginalImage = decode_one(pool, "SELECT * FROM bigpicture.original_images").await;
+    let _: WebpImage = decode_one(pool, "SELECT * FROM bigpicture.webp_images").await;
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn deleting_marker_images_queues_cleanup_of_files_no_longer_used() {
+    assert_eq!(image_cleanup::storage_key("/markers/a.webp").as_deref(), Some("s3:markers/a.webp"));
+    assert_eq!(image_cleanup::storage_key("https://bucket.s3.ap-northeast-2.amazonaws.com/markers/a.webp").as_deref(), Some("s3:markers/a.webp"));
+    assert_eq!(image_cleanup::storage_key("http://localhost:5500/api/images/download/thumbnail_1.webp").as_deref(), Some("local:thumbnail_1.webp"));
+    assert_eq!(image_cleanup::storage_key(""), None);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let upload_dir = std::env::temp_dir().join(format!("bigpicture-cleanup-{}", uuid::Uuid::new_v4().simple()));
+    std::fs::create_dir_all(upload_dir.join("thumbnail")).unwrap();
+    std::fs::create_dir_all(upload_dir.join("thumbnail_original")).unwrap();
+    std::fs::write(upload_dir.join("thumbnail/local.webp"), b"webp").unwrap();
+    let original_path = upload_dir.join("thumbnail_original/local.jpg");
+    std::fs::write(&original_path, b"jpeg").unwrap();
+    let mut state = test_db.state.clone();
+    state.config.upload_dir = upload_dir.to_string_lossy().to_string();
+    let pool = &state.database.pool;
+    sqlx::Executor::execute(pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('cleanup@example.invalid', 'cleanup');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, author, thumbnail_img)
+            SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', n::TEXT, nickname, '/markers/a.webp'
+            FROM bigpicture.members, generate_series(1, 2) n;
+    "#).await.expect("seed");
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(pool).await.unwrap();
+    let markers: Vec<i64> = sqlx::query_scalar("SELECT id FROM bigpicture.markers ORDER BY id").fetch_all(pool).await.unwrap();
+    sqlx::query("UPDATE bigpicture.markers SET thumbnail_img = NULL WHERE id = $1").bind(markers[1]).execute(pool).await.unwrap();
+    let add = |marker_id: i64, url: &'static str, source: Option<i32>| {
+        sqlx::query_scalar::<_, i32>(
+            "INSERT INTO bigpicture.marker_images (marker_id, image_type, image_url, is_primary, source_image_id) VALUES ($1, 'detail', $2, $3 IS NULL AND $2 = '/markers/a.webp', $3) RETURNING id"
+        ).bind(marker_id).bind(url).bind(source).fetch_one(pool)
+    };
+    let cover = add(markers[0], "/markers/a.webp", None).await.unwrap();
+    add(markers[0], "/markers/thumbs/a.webp", Some(cover)).await.unwrap();
+    let shared = add(markers[0], "https://bucket.s3.ap-northeast-2.amazonaws.com/markers/b.webp", None).await.unwrap();
+    add(markers[1], "/markers/b.webp", None).await.unwrap();
+    let local = add(markers[1], "http://localhost:5500/api/images/download/local.webp", None).await.unwrap();
+    sqlx::Executor::execute(pool, r#"
+        INSERT INTO bigpicture.original_images (filename, original_filename, file_path, file_size_mb, format)
+            VALUES ('local.jpg', 'photo.jpg', 'placeholder', 0.1, 'jpeg');
+        INSERT INTO bigpicture.webp_images (original_id, filename, file_path, file_size_mb, image_type)
+            SELECT id, 'local.webp', 'uploads/thumbnail/local.webp', 0.1, 'thumbnail' FROM bigpicture.original_images;
+    "#).await.expect("seed local");
+    sqlx::query("UPDATE bigpicture.original_images SET file_path = $1").bind(original_path.to_string_lossy().to_string()).execute(pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let delete = |marker_id: i64, image_id: i32| as_member(TestRequest::delete().uri(&format!("/api/markers/{}/images/{}", marker_id, image_id)), author, &state);
+
+    // 대표 이미지와 자동 생성 썸네일을 함께 지우고 파일 정리를 예약, 썸네일은 남은 이미지로 다시 선정
+    assert_eq!(test::call_service(&app, delete(markers[0], cover).to_request()).await.status(), StatusCode::OK);
+    let queued = || sqlx::query_scalar::<_, String>("SELECT storage_key FROM bigpicture.image_cleanup_jobs ORDER BY storage_key").fetch_all(pool);
+    assert_eq!(queued().await.unwrap(), ["s3:markers/a.webp", "s3:markers/thumbs/a.webp"]);
+    let thumbnail: Option<String> = sqlx::query_scalar("SELECT thumbnail_img FROM bigpicture.markers WHERE id = $1").bind(markers[0]).fetch_one(pool).await.unwrap();
+    assert_eq!(thumbnail.as_deref(), Some("https://bucket.s3.ap-northeast-2.amazonaws.com/markers/b.webp"));
+    // 다른 마커가 같은 객체를 쓰면 (URL 형식이 달라도) 예약하지 않음
+    assert_eq!(test::call_service(&app, delete(markers[0], shared).to_request()).await.status(), StatusCode::OK);
+    assert_eq!(queued().await.unwrap().len(), 2);
+
+    // 로컬 업로드는 WebP와 원본 파일, 원본 기록까지 삭제
+    assert_eq!(test::call_service(&app, delete(markers[1], local).to_request()).await.status(), StatusCode::OK);
+    sqlx::query("UPDATE bigpicture.image_cleanup_jobs SET completed_at = NOW() WHERE storage_key LIKE 's3:%'").execute(pool).await.unwrap();
+    assert_eq!(image_cleanup::process_pending_jobs(&state.database, &state.config, &state.s3_service).await.unwrap(), 1);
+    assert!(!upload_dir.join("thumbnail/local.webp").exists() && !original_path.exists());
+    let originals: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.original_images").fetch_one(pool).await.unwrap();
+    assert_eq!(originals, 0);
+
+    std::fs::remove_dir_all(&upload_dir).ok();
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn totp_matches_rfc6238_reference() {
+    // RFC 6238 부록 B의 SHA1 키 "12345678901234567890" (6자리로 자른 값)
+    let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+    let at = |secs| Utc.timestamp_opt(secs, 0).unwrap();
+    assert_eq!(verify_code(secret, "287082", at(59)), Some(1));
+    assert_eq!(verify_code(secret, "081804", at(1111111109)), Some(37037036));
+    assert_eq!(verify_code(secret, "287082", at(59 + 120)), None);
+    assert_eq!(verify_code(secret, "28708", at(59)), None);
+
+    let uri = provisioning_uri(secret, "member@example.invalid", "BigPicture");
+    assert!(uri.starts_with("otpauth://totp/BigPicture:member@example.invalid?secret=GEZDGNBV"), "{}", uri);
+}
+
+/// 노드 하나짜리 IPv4 MMDB: 0.0.0.0/1 → 서울, 128.0.0.0/1 → 없음
+fn tiny_mmdb() -> Vec<u8> {
+    fn string(out: &mut Vec<u8>, s: &str) {
+        out.push((2 << 5) | s.len() as u8);
+        out.extend_from_slice(s.as_bytes());
+    }
+    fn map(out: &mut Vec<u8>, entries: u8) {
+        out.push((7 << 5) | entries);
+    }
+    fn double(out: &mut Vec<u8>, v: f64) {
+        out.push((3 << 5) | 8);
+        out.extend_from_slice(&v.to_be_bytes());
+    }
+    fn uint16(out: &mut Vec<u8>, v: u16) {
+        out.push((5 << 5) | 2);
+        out.extend_from_slice(&v.to_be_bytes());
+    }
+
+    // 검색 트리: 왼쪽 레코드 = node_count(1) + 16 + 데이터 offset 0, 오른쪽 = node_count(없음)
+    let mut db = vec![0, 0, 17, 0, 0, 1];
+    db.extend_from_slice(&[0u8; 16]);
+    map(&mut db, 3);
+    string(&mut db, "country");
+    map(&mut db, 1);
+    string(&mut db, "iso_code");
+    string(&mut db, "KR");
+    string(&mut db, "city");
+    map(&mut db, 1);
+    string(&mut db, "names");
+    map(&mut db, 1);
+    string(&mut db, "en");
+    string(&mut db, "Seoul");
+    string(&mut db, "location");
+    map(&mut db, 2);
+    string(&mut db, "latitude");
+    double(&mut db, 37.56);
+    string(&mut db, "longitude");
+    double(&mut db, 126.97);
+
+    db.extend_from_slice(b"\xAB\xCD\xEFMaxMind.com");
+    map(&mut db, 3);
+    string(&mut db, "node_count");
+    uint16(&mut db, 1);
+    string(&mut db, "record_size");
+    uint16(&mut db, 24);
+    string(&mut db, "ip_version");
+    uint16(&mut db, 4);
+    db
+}
+
+#[actix_web::test]
+async fn bootstrap_uses_geoip_then_defaults() {
+    let path = std::env::temp_dir().join(format!("bigpicture_geoip_{}.mmdb", uuid::Uuid::new_v4().simple()));
+    std::fs::write(&path, tiny_mmdb()).expect("write mmdb");
+    let mut state = fake_state();
+    state.geoip = GeoIp::open(path.to_str().unwrap());
+    assert!(state.geoip.is_loaded());
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let request = get("/api/app/bootstrap").insert_header(("X-Forwarded-For", "1.2.3.4"));
+    let (status, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["source"], "geoip");
+    assert_eq!(body["data"]["region"]["city"], "Seoul");
+    assert_eq!(body["data"]["map"]["zoom"], 12);
+    assert_eq!(body["data"]["locale"], "ko");
+
+    let request = get("/api/app/bootstrap")
+        .insert_header(("X-Forwarded-For", "200.1.1.1"))
+        .insert_header(("Accept-Language", "ja-JP,ja;q=0.9,en;q=0.8"));
+    let (_, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!(body["data"]["source"], "default");
+    assert_eq!(body["data"]["map"]["latitude"], state.config.default_map_latitude);
+    assert_eq!(body["data"]["locale"], "ja");
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[actix_web::test]
+async fn share_card_composes_photo_badge_and_map() {
+    let photo = image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(800, 600, image::Rgba([10, 120, 30, 255])));
+    let card = ShareCard {
+        photo: Some(photo),
+        emotion: get_emotion_by_id("happy"),
+        description: Some("한강 산책"),
+        map: Some(MapInset { tile: None, pin: (0.5, 0.5) }),
+    };
+    let png = share_card::render(&card, &CardFonts::default()).expect("render");
+    let rendered = image::load_from_memory(&png).expect("png").to_rgba8();
+    assert_eq!(rendered.dimensions(), (share_card::CARD_WIDTH, share_card::CARD_HEIGHT));
+    assert_eq!(*rendered.get_pixel(20, 20), image::Rgba([10, 120, 30, 255]));
+    assert_eq!(*rendered.get_pixel(718, 88), share_card::emotion_color(Some("happy")));
+    assert_eq!(*rendered.get_pixel(1052, 482), image::Rgba([229, 57, 53, 255]));
+
+    let (url, pin) = share_card::map_tile_url("https://tile.example/{z}/{x}/{y}.png", 37.5665, 126.9780);
+    assert_eq!(url, "https://tile.example/15/27941/12689.png");
+    assert!((pin.0 - 0.8197).abs() < 1e-3 && (pin.1 - 0.4829).abs() < 1e-3);
+}
+
+#[actix_web::test]
+async fn marker_export_excludes_pii_and_hidden_markers() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let db = &test_db.state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('export@example.invalid', 'exporter');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, author, sharing_option)
+            SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy,love', '집 주소 적힌 설명', nickname, 'public' FROM bigpicture.members;
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, author, sharing_option)
+            SELECT id, ST_GeogFromText('POINT(127.1 37.6)'), 'sad', '비공개', nickname, 'private' FROM bigpicture.members;
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, author, sharing_option, taken_down_at)
+            SELECT id, ST_GeogFromText('POINT(127.2 37.7)'), 'angry', '중단', nickname, 'public', NOW() FROM bigpicture.members;
+        INSERT INTO bigpicture.member_markers (member_id, marker_id, interaction_type)
+            SELECT member_id, id, 'liked' FROM bigpicture.markers;
+    "#).await.expect("seed");
+
+    let (csv, rows) = marker_export::export_markers(db, "csv", None).await.expect("csv export");
+    let csv = String::from_utf8(csv).expect("utf8");
+    assert_eq!(rows, 1);
+    assert!(csv.starts_with("marker_id,latitude,longitude,emotion_tag,"));
+    assert!(csv.contains("\"happy,love\""));
+    assert!(!csv.contains("exporter") && !csv.contains("설명"));
+
+    let (parquet, rows) = marker_export::export_markers(db, "parquet", None).await.expect("parquet export");
+    assert_eq!(rows, 1);
+    assert!(parquet.starts_with(b"PAR1") && parquet.ends_with(b"PAR1"));
+
+    let (_, rows) = marker_export::export_markers(db, "csv", Some(Utc::now() + Duration::days(1))).await.expect("since");
+    assert_eq!(rows, 0);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn marker_queries_bind_user_input() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let db = &test_db.state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.markers (location, emotion_tag, sharing_option, likes)
+            VALUES (ST_GeogFromText('POINT(127.0 37.5)'), 'happy', 'public', 3),
+                   (ST_GeogFromText('POINT(127.0 37.5)'), 'sad', 'private', 9);
+    "#).await.expect("seed");
+
+    let injected = vec!["happy') OR ('1'='1".to_string()];
+    let markers = db.get_markers(
+        Some((37.5, 127.0, 1.0, 1.0)), Some("11' OR '1'='1"), Some(injected.clone()), None, None,
+        Some("likes; DROP TABLE bigpicture.markers"), Some("desc"), Some(10), None, None, &CreatedTimeFilter::default(), None,
+    ).await.expect("get_markers");
+    assert!(markers.is_empty());
+
+    let markers = db.get_markers(
+        Some((37.5, 127.0, 1.0, 1.0)), None, Some(vec!["happy".to_string()]), Some(1), None,
+        Some("likes; DROP TABLE bigpicture.markers"), None, Some(10), None, None, &CreatedTimeFilter::default(), None,
+    ).await.expect("get_markers");
+    assert_eq!(markers.len(), 1);
+
+    let filters = ClusterFilters {
+        emotion_tags: injected.clone(),
+        min_likes: None,
+        min_views: None,
+        limit: None,
+        user_id: None,
+        viewer_id: None,
+        time_filter: CreatedTimeFilter::default(),
+    };
+    let clusters = db.get_markers_cluster(37.5, 127.0, 0.005, 0.005, &filters, None, None, 9)
+        .await.expect("cluster");
+    assert!(clusters.is_empty());
+    let ranked = db.get_markers_rank(0.0, 0.0, 0.0, 0.0, Some(injected), None, None, Some("views desc, (SELECT 1)"), None, None, None, None)
+        .await.expect("rank");
+    assert!(ranked.is_empty());
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn marker_claim_approval_sets_single_verified_owner() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let db = &test_db.state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('owner@example.invalid', 'owner'), ('rival@example.invalid', 'rival');
+        INSERT INTO bigpicture.markers (location, emotion_tag, sharing_option)
+            VALUES (ST_GeogFromText('POINT(127.0 37.5)'), 'happy', 'public');
+    "#).await.expect("seed");
+    let marker_id: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.markers").fetch_one(&db.pool).await.unwrap();
+    let owner: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members WHERE nickname = 'owner'").fetch_one(&db.pool).await.unwrap();
+    let rival: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members WHERE nickname = 'rival'").fetch_one(&db.pool).await.unwrap();
+
+    let claim = db.create_marker_claim(marker_id, owner, "빅픽처 카페", "123-45-67890", None, "claims/a.pdf")
+        .await.expect("claim").expect("created");
+    assert!(db.create_marker_claim(marker_id, owner, "빅픽처 카페", "123-45-67890", None, "claims/b.pdf")
+        .await.expect("duplicate").is_none());
+    let other = db.create_marker_claim(marker_id, rival, "다른 가게", "999-99-99999", None, "claims/c.pdf")
+        .await.expect("claim").expect("created");
+
+    let approved = db.review_marker_claim(claim.id, owner, true, None).await.expect("approve").expect("pending");
+    assert_eq!(approved.status, "approved");
+    assert!(db.review_marker_claim(claim.id, owner, false, None).await.expect("re-review").is_none());
+    let other = db.get_marker_claim(other.id).await.unwrap().unwrap();
+    assert_eq!(other.status, "rejected");
+
+    let verified = db.get_marker_verified_owner(marker_id).await.unwrap().expect("verified owner");
+    assert_eq!((verified.member_id, verified.business_name.as_str()), (owner, "빅픽처 카페"));
+    assert_eq!(db.get_member_verified_claims(owner).await.unwrap().len(), 1);
+    assert!(db.get_member_verified_claims(rival).await.unwrap().is_empty());
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn promoted_marker_stops_at_impression_cap() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let db = &test_db.state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.markers (location, emotion_tag, sharing_option)
+            VALUES (ST_GeogFromText('POINT(127.0 37.5)'), 'happy', 'public'),
+                   (ST_GeogFromText('POINT(127.001 37.5)'), 'calm', 'public');
+    "#).await.expect("seed");
+    let marker_ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM bigpicture.markers ORDER BY id").fetch_all(&db.pool).await.unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 5, 1, 12, 0, 0).unwrap();
+    let promotion = db.create_marker_promotion(&NewMarkerPromotion {
+        marker_id: marker_ids[0],
+        sponsor_name: "빅픽처 카페".to_string(),
+        district_codes: None,
+        starts_at: now - Duration::days(1),
+        ends_at: now + Duration::days(1),
+        impression_cap: Some(1),
+    }, 1).await.expect("create");
+
+    let promoted = db.get_promoted_markers(now, Some((37.5, 127.0)), Some((126.9, 37.4, 127.1, 37.6)), 2).await.unwrap();
+    assert_eq!(promoted.iter().map(|(p, m)| (p.id, m.id)).collect::<Vec<_>>(), vec![(promotion.id, marker_ids[0])]);
+    assert!(db.get_promoted_markers(now + Duration::days(2), None, None, 2).await.unwrap().is_empty());
+
+    // 클러스터에서 프로모션 마커는 일반 클러스터에서 빠지고 단독 클러스터로 맨 앞에 옴
+    let mut clusters = vec![json!({
+        "h3_index": "abc", "lat": 37.5, "lng": 127.0, "count": 2, "marker_ids": marker_ids,
+        "markers": [{ "id": marker_ids[0] }, { "id": marker_ids[1] }]
+    })];
+    let promoted_json = promotions::promoted_marker_json(&promotion, json!({ "id": marker_ids[0], "latitude": 37.5, "longitude": 127.0 }));
+    promotions::inject_into_clusters(&mut clusters, vec![promoted_json]);
+    assert_eq!(clusters[0]["isPromoted"], true);
+    assert_eq!(clusters[0]["markers"][0]["promotion"]["sponsorName"], "빅픽처 카페");
+    assert_eq!((clusters[1]["count"].as_i64(), clusters[1]["markers"][0]["isPromoted"].as_bool()), (Some(1), Some(false)));
+
+    assert!(db.record_promotion_event(promotion.id, PromotionEvent::Impression, now).await.unwrap());
+    assert!(!db.record_promotion_event(promotion.id, PromotionEvent::Impression, now).await.unwrap());
+    assert!(db.record_promotion_event(promotion.id, PromotionEvent::Click, now).await.unwrap());
+    assert!(db.get_promoted_markers(now, None, None, 2).await.unwrap().is_empty());
+    let stored = db.get_marker_promotion(promotion.id).await.unwrap().unwrap();
+    assert_eq!((stored.impressions, stored.clicks), (1, 1));
+
+    test_db.drop_database().await;
+}
+
+/// 위도 36도 위쪽만 주소가 있는 가짜 제공자 (호출 수 기록, rate_limited면 항상 429)
+struct FakeGeocoder {
+    calls: std::sync::atomic::AtomicUsize,
+    rate_limited: bool,
+}
+
+impl ReverseGeocoder for FakeGeocoder {
+    async fn reverse_geocode(&self, latitude: f64, _longitude: f64) -> Result<Option<MarkerAddress>, GeocodeError> {
+        self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        if self.rate_limited {
+            return Err(GeocodeError::RateLimited(std::time::Duration::from_secs(30)));
+        }
+        Ok((latitude > 36.0).then(|| MarkerAddress {
+            region: "서울특별시".to_string(),
+            locality: "중구".to_string(),
+            neighborhood: "명동".to_string(),
+            full_address: "서울특별시 중구 명동".to_string(),
+        }))
+    }
+}
+
+#[actix_web::test]
+async fn geocode_backfill_reuses_cells_and_pauses_on_rate_limit() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let db = &test_db.state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.markers (location, emotion_tag) VALUES
+            (ST_GeogFromText('POINT(126.98500 37.56350)'), 'happy'),
+            (ST_GeogFromText('POINT(126.98502 37.56351)'), 'calm'),
+            (ST_GeogFromText('POINT(129.07500 35.18000)'), 'sad');
+    "#).await.expect("seed");
+    let mut cache = std::collections::HashMap::new();
+    let interval = std::time::Duration::ZERO;
+
+    let limited = FakeGeocoder { calls: Default::default(), rate_limited: true };
+    let batch = reverse_geocode::backfill_batch(db, &limited, &mut cache, 100, interval).await.expect("batch");
+    assert_eq!((batch.markers, batch.lookups, batch.rate_limited.map(|wait| wait.as_secs())), (0, 1, Some(30)));
+    assert_eq!(db.get_geocode_backfill_progress().await.unwrap().pending, 3);
+
+    let geocoder = FakeGeocoder { calls: Default::default(), rate_limited: false };
+    let batch = reverse_geocode::backfill_batch(db, &geocoder, &mut cache, 100, interval).await.expect("batch");
+    assert_eq!((batch.markers, batch.cells, batch.lookups), (3, 2, 2));
+    let progress = db.get_geocode_backfill_progress().await.unwrap();
+    assert_eq!((progress.geocoded, progress.with_address, progress.pending), (3, 2, 0));
+    let localities: Vec<Option<String>> = sqlx::query_scalar("SELECT address_locality FROM bigpicture.markers ORDER BY id")
+        .fetch_all(&db.pool).await.unwrap();
+    assert_eq!(localities, vec![Some("중구".to_string()), Some("중구".to_string()), None]);
+
+    let batch = reverse_geocode::backfill_batch(db, &geocoder, &mut cache, 100, interval).await.expect("batch");
+    assert_eq!(batch.markers, 0);
+    assert_eq!(geocoder.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+    test_db.drop_database().await;
+}
+
+/// 토큰 "good-token"만 받아들이고 올린 캡션을 기록하는 가짜 SNS
+#[derive(Default)]
+struct FakeSns {
+    captions: std::sync::Mutex<Vec<String>>,
+}
+
+impl CrossPostProvider for FakeSns {
+    fn platform(&self) -> &'static str {
+        "instagram"
+    }
+
+    fn verify_account<'a>(&'a self, access_token: &'a str) -> futures::future::BoxFuture<'a, anyhow::Result<RemoteAccount>> {
+        Box::pin(async move {
+            anyhow::ensure!(access_token == "good-token", "invalid token");
+            Ok(RemoteAccount { id: "ig-1".to_string(), username: Some("bigpicture".to_string()) })
+        })
+    }
+
+    fn publish<'a>(&'a self, access_token: &'a str, remote_user_id: &'a str, post: &'a CrossPost) -> futures::future::BoxFuture<'a, anyhow::Result<PublishedPost>> {
+        Box::pin(async move {
+            assert_eq!((access_token, remote_user_id, post.image.as_slice()), ("good-token", "ig-1", b"photo".as_slice()));
+            self.captions.lock().unwrap().push(post.caption.clone());
+            Ok(PublishedPost { remote_post_id: "post-1".to_string(), remote_url: None })
+        })
+    }
+}
+
+#[actix_web::test]
+async fn crosspost_uses_encrypted_token_once_per_platform() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let upload_dir = std::env::temp_dir().join(format!("bigpicture-crosspost-{}", uuid::Uuid::new_v4().simple()));
+    std::fs::create_dir_all(upload_dir.join("map")).unwrap();
+    std::fs::write(upload_dir.join("map/photo.webp"), b"photo").unwrap();
+    let sns = Arc::new(FakeSns::default());
+    let mut state = test_db.state.clone();
+    state.config.upload_dir = upload_dir.to_string_lossy().to_string();
+    state.crosspost_providers = CrossPostProviders::new(vec![sns.clone()]);
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('author@example.invalid', 'author'), ('other@example.invalid', 'other');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, emotion_tag_input, description, sharing_option)
+            SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', '커피, 맛집', '오늘의 카페', 'public'
+            FROM bigpicture.members WHERE nickname = 'author';
+        INSERT INTO bigpicture.marker_images (marker_id, image_type, image_url, image_order, is_primary)
+            SELECT id, 'map', 'http://localhost:5500/api/images/download/photo.webp', 0, true FROM bigpicture.markers;
+    "#).await.expect("seed");
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members WHERE nickname = 'author'").fetch_one(&db.pool).await.unwrap();
+    let other: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members WHERE nickname = 'other'").fetch_one(&db.pool).await.unwrap();
+    let marker_id: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.markers").fetch_one(&db.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let connect = |token: &str| as_member(post_json("/api/members/me/connected-accounts", &json!({
+        "platform": "instagram", "access_token": token
+    })), author, &state);
+    let (status, _) = read_json(test::call_service(&app, connect("bad-token").to_request()).await).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, body) = read_json(test::call_service(&app, connect("good-token").to_request()).await).await;
+    assert_eq!((status, body["data"]["remoteUserId"].as_str()), (StatusCode::OK, Some("ig-1")));
+    assert!(body["data"].get("accessToken").is_none());
+    let stored: String = sqlx::query_scalar("SELECT access_token_encrypted FROM bigpicture.member_connected_accounts")
+        .fetch_one(&db.pool).await.unwrap();
+    assert!(!stored.contains("good-token"));
+    assert_eq!(state.token_cipher.decrypt(&stored).unwrap(), "good-token");
+
+    let crosspost = |member_id: i64| as_member(post_json(&format!("/api/markers/{}/crosspost", marker_id), &json!({
+        "platform": "instagram"
+    })), member_id, &state);
+    let (status, _) = read_json(test::call_service(&app, crosspost(other).to_request()).await).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = read_json(test::call_service(&app, crosspost(author).to_request()).await).await;
+    assert_eq!((status, body["data"]["remotePostId"].as_str()), (StatusCode::CREATED, Some("post-1")));
+    assert_eq!(sns.captions.lock().unwrap().as_slice(), ["오늘의 카페\n\n#커피 #맛집".to_string()]);
+    let (status, _) = read_json(test::call_service(&app, crosspost(author).to_request()).await).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    std::fs::remove_dir_all(&upload_dir).ok();
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn marker_report_hide_resolves_pending_reports() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('author@example.invalid', 'author'), ('a@example.invalid', 'a'), ('b@example.invalid', 'b');
+        INSERT INTO bigpicture.members (email, nickname, is_admin) VALUES ('admin@example.invalid', 'admin', true);
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, sharing_option)
+            SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', 'public' FROM bigpicture.members WHERE nickname = 'author';
+    "#).await.expect("seed");
+    let member = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&db.pool);
+    let (author, a, b, admin) = (member("author").await.unwrap(), member("a").await.unwrap(), member("b").await.unwrap(), member("admin").await.unwrap());
+    let marker_id: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.markers").fetch_one(&db.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let report = |member_id: i64, reason: &str| as_member(post_json(&format!("/api/markers/{}/report", marker_id), &json!({
+        "reason": reason, "details": "광고 글입니다"
+    })), member_id, &state);
+    let (status, _) = read_json(test::call_service(&app, report(a, "boring").to_request()).await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = read_json(test::call_service(&app, report(author, "spam").to_request()).await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = read_json(test::call_service(&app, report(a, "spam").to_request()).await).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let first_report = body["data"]["id"].as_i64().unwrap();
+    let (status, _) = read_json(test::call_service(&app, report(a, "offensive").to_request()).await).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = read_json(test::call_service(&app, report(b, "offensive").to_request()).await).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _) = read_json(test::call_service(&app, as_member(get("/api/admin/marker-reports"), a, &state).to_request()).await).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/admin/marker-reports"), admin, &state).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!((body["data"][0]["markerId"].as_i64(), body["data"][0]["reportCount"].as_i64()), (Some(marker_id), Some(2)));
+    assert_eq!(body["data"][0]["reasons"], json!({ "spam": 1, "offensive": 1 }));
+
+    let hide = as_member(post_json(&format!("/api/admin/marker-reports/{}/hide", first_report), &json!({ "note": "스팸" })), admin, &state);
+    let (status, body) = read_json(test::call_service(&app, hide.to_request()).await).await;
+    assert_eq!((status, body["data"]["resolvedReports"].as_i64()), (StatusCode::OK, Some(2)));
+    let (taken_down, legal_hold): (bool, bool) = sqlx::query_as("SELECT taken_down_at IS NOT NULL, COALESCE(legal_hold, false) FROM bigpicture.markers WHERE id = $1")
+        .bind(marker_id).fetch_one(&db.pool).await.unwrap();
+    assert_eq!((taken_down, legal_hold), (true, false));
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/admin/marker-reports"), admin, &state).to_request()).await).await;
+    assert_eq!((status, body["count"].as_i64()), (StatusCode::OK, Some(0)));
+    let dismiss = as_member(post_json(&format!("/api/admin/marker-reports/{}/resolve", first_report), &json!({})), admin, &state);
+    let (status, _) = read_json(test::call_service(&app, dismiss.to_request()).await).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn suspended_or_logged_out_members_lose_existing_tokens() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 9, 0, 0).unwrap()));
+    let state = deterministic(test_db.state.clone(), clock.clone());
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname, is_admin) VALUES ('admin@example.invalid', 'admin', true);
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('user@example.invalid', 'user'), ('other@example.invalid', 'other');
+        INSERT INTO bigpicture.auth_providers (member_id, provider_type, provider_id, password_hash)
+            SELECT id, 'email', email, 'pw' FROM bigpicture.members WHERE nickname = 'user';
+        INSERT INTO bigpicture.auth_providers (member_id, provider_type, provider_id)
+            SELECT id, 'google', 'google-other' FROM bigpicture.members WHERE nickname = 'other';
+    "#).await.expect("seed");
+    let member = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&db.pool);
+    let (admin, user) = (member("admin").await.unwrap(), member("user").await.unwrap());
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/admin/members?provider_type=email"), admin, &state).to_request()).await).await;
+    assert_eq!((status, body["count"].as_i64()), (StatusCode::OK, Some(1)));
+    assert_eq!((body["data"][0]["id"].as_i64(), body["data"][0]["providerTypes"].clone()), (Some(user), json!(["email"])));
+    let (status, _) = read_json(test::call_service(&app, as_member(get("/api/admin/members"), user, &state).to_request()).await).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let me = |token: &str| get("/api/members/me").insert_header(("Authorization", format!("Bearer {}", token)));
+    let old_token = bearer_token(user, clock.as_ref());
+    clock.advance(Duration::seconds(1));
+    let moderate = |action: &str| as_member(post_json(&format!("/api/admin/members/{}/{}", user, action), &json!({ "reason": "스팸 계정" })), admin, &state);
+    let (status, _) = read_json(test::call_service(&app, moderate("force-logout").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(test::call_service(&app, me(&old_token).to_request()).await.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(test::call_service(&app, as_member(get("/api/members/me"), user, &state).to_request()).await.status(), StatusCode::OK);
+
+    let login = || post_json("/api/auth/login", &json!({ "email": "user@example.invalid", "password": "pw" }));
+    let (status, body) = read_json(test::call_service(&app, moderate("suspend").to_request()).await).await;
+    assert_eq!((status, body["data"]["isActive"].as_bool()), (StatusCode::OK, Some(false)));
+    assert_eq!(test::call_service(&app, as_member(get("/api/members/me"), user, &state).to_request()).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(test::call_service(&app, login().to_request()).await.status(), StatusCode::FORBIDDEN);
+
+    let (status, _) = read_json(test::call_service(&app, moderate("reactivate").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = read_json(test::call_service(&app, login().to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(test::call_service(&app, me(body["accessToken"].as_str().unwrap()).to_request()).await.status(), StatusCode::OK);
+    let actions: Vec<String> = sqlx::query_scalar("SELECT action FROM bigpicture.member_moderation_audit ORDER BY id").fetch_all(&db.pool).await.unwrap();
+    assert_eq!(actions, ["force_logout", "suspend", "reactivate"]);
+
+    let self_suspend = as_member(post_json(&format!("/api/admin/members/{}/suspend", admin), &json!({})), admin, &state);
+    assert_eq!(test::call_service(&app, self_suspend.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn embed_card_is_cacheable_and_discoverable_by_oembed() {
+    assert_eq!(embed::public_id_from_url("https://bigpicture.app/m/b7Kx2mQr9T?from=share").as_deref(), Some("b7Kx2mQr9T"));
+    assert_eq!(embed::public_id_from_url("http://localhost:5500/embed/markers/b7Kx2mQr9T/").as_deref(), Some("b7Kx2mQr9T"));
+    assert_eq!(embed::public_id_from_url("https://bigpicture.app/markers/42"), None);
+    assert_eq!(embed::public_id_from_url("https://bigpicture.app/members/b7Kx2mQr9T"), None);
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let db = &test_db.state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.markers (location, emotion_tag, description, sharing_option)
+            VALUES (ST_GeogFromText('POINT(127.0 37.5)'), 'happy', '<b>한강</b> 산책', 'public'),
+                   (ST_GeogFromText('POINT(127.0 37.5)'), 'sad', '비공개', 'private');
+    "#).await.expect("seed");
+    let markers: Vec<(i64, String)> = sqlx::query_as("SELECT id, public_id FROM bigpicture.markers ORDER BY id").fetch_all(&db.pool).await.unwrap();
+    let public_ids: Vec<&str> = markers.iter().map(|(_, public_id)| public_id.as_str()).collect();
+    let app = test::init_service(build_app(test_db.state.clone())).await;
+
+    let response = test::call_service(&app, get(&format!("/embed/markers/{}", public_ids[0])).to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers().get("ETag").unwrap().to_str().unwrap().to_string();
+    assert!(response.headers().get("Link").unwrap().to_str().unwrap().contains("/oembed?url="));
+    let html = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+    assert!(html.contains("&lt;b&gt;한강&lt;/b&gt; 산책") && !html.contains("<b>"));
+    assert!(html.contains(&format!("/m/{}", public_ids[0])));
+    let cached = get(&format!("/embed/markers/{}", public_ids[0])).insert_header(("If-None-Match", etag));
+    assert_eq!(test::call_service(&app, cached.to_request()).await.status(), StatusCode::NOT_MODIFIED);
+    let response = test::call_service(&app, get(&format!("/embed/markers/{}", public_ids[1])).to_request()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    // 숫자 ID로는 열 수 없음
+    let response = test::call_service(&app, get(&format!("/embed/markers/{}", markers[0].0)).to_request()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let oembed = |query: &str| get(&format!("/oembed?{}", query));
+    let (status, body) = read_json(test::call_service(&app, oembed(&format!("url=https%3A%2F%2Fbigpicture.app%2Fm%2F{}&maxwidth=300", public_ids[0])).to_request()).await).await;
+    assert_eq!((status, body["type"].as_str(), body["width"].as_u64(), body["height"].as_u64()), (StatusCode::OK, Some("rich"), Some(300), Some(225)));
+    assert!(body["html"].as_str().unwrap().contains(&format!("/embed/markers/{}", public_ids[0])));
+    assert!(body["thumbnail_url"].as_str().unwrap().ends_with("/map.png"));
+    let response = test::call_service(&app, oembed(&format!("url=https%3A%2F%2Fbigpicture.app%2Fm%2F{}&format=xml", public_ids[0])).to_request()).await;
+    assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn shared_links_use_short_public_ids_instead_of_numeric_ids() {
+    assert!(public_id::is_valid("b7Kx2mQr9T"));
+    for invalid in ["b7Kx2mQr9", "b7Kx2mQr9Ta", "a7Kx2mQr9T", "07Kx2mQr9T", "b7Kx2mQr9/"] {
+        assert!(!public_id::is_valid(invalid), "{}", invalid);
+    }
+    assert_eq!(public_id::share_url("https://bigpicture.app/", "b7Kx2mQr9T"), "https://bigpicture.app/m/b7Kx2mQr9T");
+    let app = test::init_service(build_app(fake_state())).await;
+    assert_eq!(test::call_service(&app, get("/api/m/12345").to_request()).await.status(), StatusCode::NOT_FOUND);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let pool = test_db.state.database.pool.clone();
+    sqlx::Executor::execute(&pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('share@example.invalid', 'share');
+        INSERT INTO bigpicture.markers (location, emotion_tag, description, sharing_option)
+        SELECT ST_GeogFromText('POINT(127.0 37.5)'), 'happy', '공유 ' || n, CASE WHEN n = 1 THEN 'private' ELSE 'public' END
+        FROM generate_series(1, 200) AS n;
+    "#).await.expect("seed");
+    let public_ids: Vec<String> = sqlx::query_scalar("SELECT public_id FROM bigpicture.markers ORDER BY id").fetch_all(&pool).await.unwrap();
+    assert!(public_ids.iter().all(|public_id| public_id::is_valid(public_id)));
+    assert_eq!(public_ids.iter().collect::<std::collections::HashSet<_>>().len(), public_ids.len());
+    let app = test::init_service(build_app(test_db.state.clone())).await;
+
+    let (status, body) = read_json(test::call_service(&app, get(&format!("/api/m/{}", public_ids[1])).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["marker"]["publicId"], public_ids[1].as_str());
+    assert!(body["data"]["shareUrl"].as_str().unwrap().ends_with(&format!("/m/{}", public_ids[1])));
+    assert!(body["data"]["cardUrl"].as_str().unwrap().ends_with(&format!("/api/m/{}/card.png", public_ids[1])));
+    assert_eq!(test::call_service(&app, get(&format!("/api/m/{}", public_ids[0])).to_request()).await.status(), StatusCode::NOT_FOUND);
+
+    // 작성 직후 응답에도 공개 ID가 있음
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&pool).await.unwrap();
+    let create = as_member(post_json("/api/markers", &json!({
+        "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": "새 마커"
+    })), author, &test_db.state);
+    let (status, body) = read_json(test::call_service(&app, create.to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(public_id::is_valid(body["data"]["publicId"].as_str().unwrap()));
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn presence_counts_recent_viewers_in_memory_or_redis() {
+    let mut config = test_config();
+    config.redis_url = String::new();
+    config.presence_ttl_secs = 30;
+    let presence = MarkerPresence::new(&config);
+    let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    assert_eq!(presence.touch(7, "m:1", now).await, 1);
+    assert_eq!(presence.touch(7, "a:browser", now + Duration::seconds(10)).await, 2);
+    assert_eq!(presence.touch(7, "m:1", now + Duration::seconds(20)).await, 2);
+    assert_eq!(presence.count(7, now + Duration::seconds(45)).await, 1);
+    assert_eq!(presence.count(7, now + Duration::seconds(60)).await, 0);
+
+    // 가짜 Redis: 파이프라인 4개 명령에 정해진 응답 (ZCARD = 5)
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    config.redis_url = format!("redis://{}/2", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let (mut socket, _) = listener.accept().await.expect("accept");
+        let mut received = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !String::from_utf8_lossy(&received).contains("PEXPIRE") || !received.ends_with(b"30000\r\n") {
+            let n = socket.read(&mut buf).await.expect("read");
+            received.extend_from_slice(&buf[..n]);
+            if String::from_utf8_lossy(&received).ends_with("$1\r\n2\r\n") {
+                socket.write_all(b"+OK\r\n").await.expect("select reply");
+            }
+        }
+        socket.write_all(b":1\r\n:0\r\n:5\r\n:1\r\n").await.expect("reply");
+        String::from_utf8(received).expect("utf8")
+    });
+    let presence = MarkerPresence::new(&config);
+    assert_eq!(presence.touch(7, "m:1", now).await, 5);
+    let received = server.await.expect("server");
+    assert!(received.starts_with("*2\r\n$6\r\nSELECT\r\n$1\r\n2\r\n"));
+    assert!(received.contains("$4\r\nZADD\r\n$28\r\nbigpicture:presence:marker:7\r\n"));
+}
+
+#[actix_web::test]
+async fn marker_hashtags_filter_feed_and_trend() {
+    assert_eq!(hashtags::extract_hashtags("#한강 산책 #Sunset! C#은 제외, a.com/#frag #2024"), ["한강", "sunset"]);
+    assert_eq!(hashtags::collect_marker_tags("#야경 #한강", Some(&["#한강".to_string(), "Cafe".to_string()])), Ok(vec!["한강".to_string(), "cafe".to_string(), "야경".to_string()]));
+    assert!(hashtags::collect_marker_tags("", Some(&["카페 투어".to_string()])).is_err());
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, "INSERT INTO bigpicture.members (email, nickname) VALUES ('author@example.invalid', 'author')").await.expect("seed");
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&db.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let create = |description: &str, tags: serde_json::Value| as_member(post_json("/api/markers", &json!({
+        "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": description, "sharing_option": "public", "tags": tags
+    })), author, &state);
+    let (status, _) = read_json(test::call_service(&app, create("산책", json!(["카페 투어"])).to_request()).await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = read_json(test::call_service(&app, create("#한강 #야경 산책", json!(["Cafe"])).to_request()).await).await;
+    assert_eq!((status, body["data"]["tags"].clone()), (StatusCode::OK, json!(["cafe", "한강", "야경"])));
+    let tagged = body["data"]["id"].as_i64().unwrap();
+    let (status, _) = read_json(test::call_service(&app, create("#한강 노을", serde_json::Value::Null).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = read_json(test::call_service(&app, create("태그 없음", serde_json::Value::Null).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = read_json(test::call_service(&app, get("/api/markers?lat=37.5&lng=127.0&lat_delta=1&lng_delta=1&tag=%23CAFE").to_request()).await).await;
+    assert_eq!((status, body["count"].as_i64(), body["data"][0]["id"].as_i64()), (StatusCode::OK, Some(1), Some(tagged)));
+    let (status, body) = read_json(test::call_service(&app, get("/api/markers/feed?tag=%ED%95%9C%EA%B0%95").to_request()).await).await;
+    assert_eq!((status, body["data"].as_array().map(Vec::len)), (StatusCode::OK, Some(2)));
+    let (status, _) = read_json(test::call_service(&app, get("/api/markers/feed?tag=a-b").to_request()).await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = read_json(test::call_service(&app, get(&format!("/api/markers/{}", tagged)).to_request()).await).await;
+    assert_eq!((status, body["data"]["tags"].clone()), (StatusCode::OK, json!(["cafe", "야경", "한강"])));
+
+    let (status, body) = read_json(test::call_service(&app, get("/api/tags/trending?hours=1").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!((body["data"][0]["tag"].as_str(), body["data"][0]["markerCount"].as_i64(), body["data"][0]["previousCount"].as_i64()), (Some("한강"), Some(2), Some(0)));
+    assert_eq!(body["data"].as_array().map(Vec::len), Some(3));
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn marker_search_ranks_public_matches_within_bounds() {
+    assert_eq!(marker_search_tsquery("한강 야경!").as_deref(), Some("한강:* & 야경:*"));
+    assert_eq!(marker_search_tsquery("Coffee's & (latte)").as_deref(), Some("coffee:* & s:* & latte:*"));
+    assert_eq!(marker_search_tsquery(" :*& "), None);
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let db = &test_db.state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.markers (location, emotion_tag, description, author, sharing_option, created_at) VALUES
+            (ST_GeogFromText('POINT(127.0 37.5)'), 'happy', '한강에서 야경 산책', 'walker', 'public', NOW() - INTERVAL '2 days'),
+            (ST_GeogFromText('POINT(127.0 37.5)'), 'calm', '카페 창가', '한강러버', 'public', NOW() - INTERVAL '1 day'),
+            (ST_GeogFromText('POINT(129.0 35.1)'), 'happy', '부산 한강 아님', 'busan', 'public', NOW()),
+            (ST_GeogFromText('POINT(127.0 37.5)'), 'happy', '비공개 한강', 'secret', 'private', NOW());
+    "#).await.expect("seed");
+    let app = test::init_service(build_app(test_db.state.clone())).await;
+
+    let (status, body) = read_json(test::call_service(&app, get("/api/markers/search?q=%ED%95%9C%EA%B0%95&lat_min=37&lat_max=38&lng_min=126&lng_max=128").to_request()).await).await;
+    assert_eq!((status, body["pagination"]["totalCount"].as_i64()), (StatusCode::OK, Some(2)));
+    assert_eq!(body["data"][0]["description"].as_str(), Some("한강에서 야경 산책"));
+    assert!(body["data"][0]["searchRank"].as_f64() > body["data"][1]["searchRank"].as_f64());
+    let (status, body) = read_json(test::call_service(&app, get("/api/markers/search?q=%ED%95%9C%EA%B0%95&limit=1&page=2").to_request()).await).await;
+    assert_eq!((status, body["pagination"]["totalCount"].as_i64(), body["count"].as_i64()), (StatusCode::OK, Some(3), Some(1)));
+    let (status, body) = read_json(test::call_service(&app, get("/api/markers/search?q=HAPPY%20%EC%82%B0").to_request()).await).await;
+    assert_eq!((status, body["count"].as_i64()), (StatusCode::OK, Some(1)));
+    let (status, _) = read_json(test::call_service(&app, get("/api/markers/search?q=%21%21").to_request()).await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = read_json(test::call_service(&app, get("/api/markers/search?q=a&lat_min=37").to_request()).await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn emotion_profile_buckets_months_and_caches_until_owner_writes() {
+    let range = ProfileRange::recent(Utc.with_ymd_and_hms(2025, 2, 28, 16, 0, 0).unwrap(), 3); // 한국 시간 3월 1일
+    assert_eq!(range.month_labels(), ["2025-01", "2025-02", "2025-03"]);
+    assert_eq!(range.bounds().0, Utc.with_ymd_and_hms(2024, 12, 31, 15, 0, 0).unwrap());
+    let count = |month: &str, emotion_tag: &str, marker_count: i64| EmotionMonthCount { month: month.to_string(), emotion_tag: emotion_tag.to_string(), marker_count };
+    let profile = build_profile(1, &range, &[count("2025-01", "happy", 3), count("2025-01", "sad", 1), count("2025-03", "sad", 4)]);
+    assert_eq!((profile.total, profile.dominant_emotion.as_ref().map(|share| share.emotion_tag.as_str())), (8, Some("sad")));
+    assert_eq!(profile.months.iter().map(|month| month.total).collect::<Vec<_>>(), [4, 0, 4]);
+    assert_eq!(profile.months[0].emotions[0].ratio, 0.75);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('owner@example.invalid', 'owner');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option, created_at)
+            SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), tags, '기록', sharing, at FROM bigpicture.members,
+                (VALUES ('happy,calm', 'public', TIMESTAMPTZ '2025-01-31 16:00:00+00'),
+                        ('happy', 'public', TIMESTAMPTZ '2025-03-10 09:00:00+00'),
+                        ('sad', 'private', TIMESTAMPTZ '2025-03-11 09:00:00+00')) AS seed(tags, sharing, at);
+    "#).await.expect("seed");
+    let owner: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&db.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let path = format!("/api/members/{}/emotion-profile?year=2025", owner);
+
+    let (status, body) = read_json(test::call_service(&app, get(&path).to_request()).await).await;
+    assert_eq!((status, body["data"]["total"].as_i64(), body["data"]["months"].as_array().map(Vec::len)), (StatusCode::OK, Some(3), Some(12)));
+    assert_eq!((body["data"]["months"][1]["month"].as_str(), body["data"]["months"][1]["total"].as_i64()), (Some("2025-02"), Some(2))); // 한국 시간 2월 1일
+    assert_eq!(body["data"]["dominantEmotion"]["emotionTag"], "happy");
+    let (status, body) = read_json(test::call_service(&app, as_member(get(&path), owner, &state).to_request()).await).await;
+    assert_eq!((status, body["data"]["months"][2]["total"].as_i64()), (StatusCode::OK, Some(2)));
+    let (status, summary) = read_json(test::call_service(&app, as_member(get(&format!("/api/members/{}/emotions/summary?year=2025", owner)), owner, &state).to_request()).await).await;
+    assert_eq!((status, &summary["data"]), (StatusCode::OK, &body["data"]));
+
+    sqlx::query("UPDATE bigpicture.markers SET sharing_option = 'public'").execute(&db.pool).await.unwrap();
+    let (_, body) = read_json(test::call_service(&app, get(&path).to_request()).await).await;
+    assert_eq!(body["data"]["total"].as_i64(), Some(3));
+    let create = as_member(post_json("/api/markers", &json!({
+        "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": "오늘"
+    })), owner, &state);
+    assert_eq!(test::call_service(&app, create.to_request()).await.status(), StatusCode::OK);
+    let (_, body) = read_json(test::call_service(&app, get(&path).to_request()).await).await;
+    assert_eq!(body["data"]["total"].as_i64(), Some(4));
+
+    let (status, _) = read_json(test::call_service(&app, get("/api/members/999999/emotion-profile").to_request()).await).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = read_json(test::call_service(&app, get(&format!("/api/members/{}/emotion-profile?year=1999", owner)).to_request()).await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn new_accounts_get_lower_limits_until_promoted_or_overridden() {
+    let now = Utc.with_ymd_and_hms(2025, 6, 1, 9, 0, 0).unwrap();
+    let policy = TrustPolicy::from_config(&test_config());
+    let stats = |days: i64, marker_count: i64, taken_down_count: i64| MemberTrustStats {
+        created_at: now - Duration::days(days), trust_level_override: None, marker_count, taken_down_count, markers_last_day: 0,
+    };
+    assert_eq!(policy.derive(&stats(1, 50, 0), now), TrustLevel::New);
+    assert_eq!(policy.derive(&stats(5, 3, 0), now), TrustLevel::Basic);
+    assert_eq!(policy.derive(&stats(60, 40, 0), now), TrustLevel::Trusted);
+    assert_eq!(policy.derive(&stats(60, 40, 1), now), TrustLevel::Basic);
+    assert!(contains_link("여기 https://example.com 참고") && contains_link("WWW.example.com") && !contains_link("www 없음"));
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let mut state = test_db.state.clone();
+    state.config.new_member_markers_per_day = 2;
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname, is_admin) VALUES ('admin@example.invalid', 'admin', true);
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('new@example.invalid', 'newbie');
+    "#).await.expect("seed");
+    let member = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&db.pool);
+    let (admin, newbie) = (member("admin").await.unwrap(), member("newbie").await.unwrap());
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let create = |description: &str| as_member(post_json("/api/markers", &json!({
+        "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": description
+    })), newbie, &state);
+    let (status, _) = read_json(test::call_service(&app, create("할인 https://spam.example").to_request()).await).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    for _ in 0..2 {
+        assert_eq!(test::call_service(&app, create("산책").to_request()).await.status(), StatusCode::OK);
+    }
+    let (status, body) = read_json(test::call_service(&app, create("산책").to_request()).await).await;
+    assert_eq!((status, body["error"]["quota"]["markersLastDay"].as_i64()), (StatusCode::UNPROCESSABLE_ENTITY, Some(2)));
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/trust"), newbie, &state).to_request()).await).await;
+    assert_eq!((status, body["data"]["level"].as_str(), body["data"]["limits"]["linksAllowed"].as_bool()), (StatusCode::OK, Some("new"), Some(false)));
+
+    let set_level = |level: &str, by: i64| as_member(TestRequest::put().uri(&format!("/api/admin/members/{}/trust-level", newbie)).set_json(json!({ "level": level, "reason": "검토 완료" })), by, &state);
+    assert_eq!(test::call_service(&app, set_level("trusted", newbie).to_request()).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(test::call_service(&app, set_level("vip", admin).to_request()).await.status(), StatusCode::BAD_REQUEST);
+    let (status, body) = read_json(test::call_service(&app, set_level("trusted", admin).to_request()).await).await;
+    assert_eq!((status, body["data"]["trustLevelOverride"].as_str()), (StatusCode::OK, Some("trusted")));
+    assert_eq!(test::call_service(&app, create("공식 안내 https://bigpicture.example").to_request()).await.status(), StatusCode::OK);
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/trust"), newbie, &state).to_request()).await).await;
+    assert_eq!((body["data"]["level"].as_str(), body["data"]["isOverride"].as_bool()), (Some("trusted"), Some(true)));
+
+    let (status, _) = read_json(test::call_service(&app, set_level("auto", admin).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/trust"), newbie, &state).to_request()).await).await;
+    assert_eq!((body["data"]["level"].as_str(), body["data"]["isOverride"].as_bool()), (Some("new"), Some(false)));
+    let actions: Vec<String> = sqlx::query_scalar("SELECT action FROM bigpicture.member_moderation_audit ORDER BY id").fetch_all(&db.pool).await.unwrap();
+    assert_eq!(actions, ["trust_trusted", "trust_auto"]);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn dead_letter_jobs_can_be_listed_retried_and_discarded_by_admins() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname, is_admin) VALUES ('admin@example.invalid', 'admin', true);
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('user@example.invalid', 'user');
+    "#).await.expect("seed");
+    let member = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&db.pool);
+    let (admin, user) = (member("admin").await.unwrap(), member("user").await.unwrap());
+
+    let export = db.create_marker_export(admin, "csv", None).await.unwrap();
+    db.fail_marker_export(export.id, "S3 업로드 실패").await.unwrap();
+    let cleanup_id: i64 = sqlx::query_scalar(
+        "INSERT INTO bigpicture.image_cleanup_jobs (image_url, reason, attempts, last_error) VALUES ('https://bucket.example/a.webp', 'account_deletion', 5, 'AccessDenied') RETURNING id"
+    ).fetch_one(&db.pool).await.unwrap();
+    for job in [
+        NewDeadLetterJob { job_type: "marker_export".into(), source_id: Some(export.id), payload: json!({ "format": "csv" }), attempts: 1, last_error: Some("S3 업로드 실패".into()) },
+        NewDeadLetterJob { job_type: "image_cleanup".into(), source_id: Some(cleanup_id), payload: json!({ "image_url": "https://bucket.example/a.webp" }), attempts: 5, last_error: Some("AccessDenied".into()) },
+    ] {
+        db.add_dead_letter_job(&job).await.unwrap();
+    }
+    let app = test::init_service(build_app(state.clone())).await;
+
+    assert_eq!(test::call_service(&app, as_member(get("/api/admin/dlq"), user, &state).to_request()).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(test::call_service(&app, as_member(get("/api/admin/dlq?status=lost"), admin, &state).to_request()).await.status(), StatusCode::BAD_REQUEST);
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/admin/dlq"), admin, &state).to_request()).await).await;
+    assert_eq!((status, body["count"].as_i64(), body["summary"]["marker_export"]["dead"].as_i64()), (StatusCode::OK, Some(2), Some(1)));
+    let dead_id = |job_type: &str| body["data"].as_array().unwrap().iter()
+        .find(|job| job["jobType"] == job_type).and_then(|job| job["id"].as_i64()).unwrap();
+    let (export_dlq, cleanup_dlq) = (dead_id("marker_export"), dead_id("image_cleanup"));
+    assert_eq!(body["data"].as_array().unwrap().iter().find(|job| job["id"] == cleanup_dlq).unwrap()["payload"]["image_url"], "https://bucket.example/a.webp");
+
+    let resolve = |id: i64, action: &str| as_member(post_json(&format!("/api/admin/dlq/{}/{}", id, action), &json!({})), admin, &state);
+    let (status, body) = read_json(test::call_service(&app, resolve(export_dlq, "retry").to_request()).await).await;
+    assert_eq!((status, body["data"]["status"].as_str()), (StatusCode::OK, Some("retried")));
+    assert_eq!(db.get_marker_export(export.id).await.unwrap().unwrap().status, "pending");
+    assert_eq!(test::call_service(&app, resolve(export_dlq, "retry").to_request()).await.status(), StatusCode::NOT_FOUND);
+
+    let (status, body) = read_json(test::call_service(&app, resolve(cleanup_dlq, "discard").to_request()).await).await;
+    assert_eq!((status, body["data"]["status"].as_str(), body["data"]["resolvedBy"].as_i64()), (StatusCode::OK, Some("discarded"), Some(admin)));
+    let attempts: i32 = sqlx::query_scalar("SELECT attempts FROM bigpicture.image_cleanup_jobs WHERE id = $1").bind(cleanup_id).fetch_one(&db.pool).await.unwrap();
+    assert_eq!(attempts, 5);
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/admin/dlq?status=all&job_type=image_cleanup"), admin, &state).to_request()).await).await;
+    assert_eq!((body["count"].as_i64(), body["data"][0]["status"].as_str()), (Some(1), Some("discarded")));
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn read_endpoints_get_configured_cache_policies() {
+    let policies = CachePolicies::from_config(&test_config());
+    assert_eq!(policies.for_route("/api/districts/{code}/stats").and_then(|policy| policy.header_value()).as_deref(), Some("public, max-age=300, stale-while-revalidate=3600"));
+    assert_eq!(policies.for_route("/api/markers/cluster").and_then(|policy| policy.header_value()).as_deref(), Some("private, max-age=30"));
+    assert_eq!(policies.for_route("/api/markers/{id}"), None);
+    assert_eq!(CachePolicy::Public { max_age: 0, stale_while_revalidate: 60 }.header_value(), None);
+
+    let mut state = fake_state();
+    state.config.catalog_cache_max_age_secs = 120;
+    let app = test::init_service(build_app(state)).await;
+    let response = test::call_service(&app, get("/api/emotions").to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("cache-control").unwrap(), "public, max-age=120, stale-while-revalidate=604800");
+
+    // DB에 닿지 못한 오류 응답은 캐시하지 않음
+    let response = test::call_service(&app, get("/api/likes/stats").to_request()).await;
+    assert!(response.status().is_server_error());
+    assert!(response.headers().get("cache-control").is_none());
+}
+
+#[actix_web::test]
+async fn activity_timeline_and_notifications_feed_page_by_cursor() {
+    let app = test::init_service(build_app(fake_state())).await;
+    let (status, _) = read_json(test::call_service(&app, as_member(get("/api/members/me/activity?cursor=abc"), 1, &fake_state()).to_request()).await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('writer@example.invalid', 'writer');
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('fan@example.invalid', 'fan');
+    "#).await.expect("seed");
+    let member = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&db.pool);
+    let (writer, fan) = (member("writer").await.unwrap(), member("fan").await.unwrap());
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let mut marker_ids = Vec::new();
+    for description in ["첫 산책", "두 번째 산책", "세 번째 산책"] {
+        let (status, body) = read_json(test::call_service(&app, as_member(post_json("/api/markers", &json!({
+            "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": description
+        })), writer, &state).to_request()).await).await;
+        assert_eq!(status, StatusCode::OK);
+        marker_ids.push(body["data"]["id"].as_i64().unwrap());
+    }
+    let like = |marker_id: i64| as_member(post_json(&format!("/api/markers/{}/reaction", marker_id), &json!({ "like_type": "like" })), fan, &state);
+    for marker_id in [marker_ids[0], marker_ids[1], marker_ids[1], marker_ids[1]] {
+        assert_eq!(test::call_service(&app, like(marker_id).to_request()).await.status(), StatusCode::OK);
+    }
+
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/activity?limit=2"), writer, &state).to_request()).await).await;
+    assert_eq!((status, body["count"].as_i64()), (StatusCode::OK, Some(2)));
+    assert_eq!(body["data"][0]["marker"]["description"], "세 번째 산책");
+    let cursor = body["nextCursor"].as_str().unwrap().to_string();
+    let (_, body) = read_json(test::call_service(&app, as_member(get(&format!("/api/members/me/activity?limit=2&cursor={}", cursor)), writer, &state).to_request()).await).await;
+    assert_eq!((body["count"].as_i64(), body["data"][0]["type"].as_str(), body["nextCursor"].is_null()), (Some(1), Some("marker_created"), true));
+
+    // 두 번째 마커는 좋아요 → 취소 → 다시 좋아요라 활동이 하나만 남음
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/notifications-feed"), writer, &state).to_request()).await).await;
+    let liked: Vec<(Option<&str>, Option<i64>, Option<&str>)> = body["data"].as_array().unwrap().iter()
+        .map(|activity| (activity["type"].as_str(), activity["marker"]["id"].as_i64(), activity["actor"]["nickname"].as_str()))
+        .collect();
+    assert_eq!(liked, [(Some("marker_liked"), Some(marker_ids[1]), Some("fan")), (Some("marker_liked"), Some(marker_ids[0]), Some("fan"))]);
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/notifications-feed"), fan, &state).to_request()).await).await;
+    assert_eq!(body["count"].as_i64(), Some(0));
+
+    sqlx::query("UPDATE bigpicture.members SET suspended_at = NOW() WHERE id = $1").bind(fan).execute(&db.pool).await.unwrap();
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/notifications-feed"), writer, &state).to_request()).await).await;
+    assert_eq!(body["count"].as_i64(), Some(0));
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn client_error_reports_are_sampled_and_found_by_request_id() {
+    let mut state = fake_state();
+    state.config.client_error_sample_rate = 0.0;
+    let app = test::init_service(build_app(state)).await;
+    let response = test::call_service(&app, get("/api/emotions").insert_header(("X-Request-Id", "gw-123")).to_request()).await;
+    assert_eq!(response.headers().get("x-request-id").unwrap(), "gw-123");
+    let response = test::call_service(&app, get("/api/emotions").insert_header(("X-Request-Id", "bad id!")).to_request()).await;
+    assert_ne!(response.headers().get("x-request-id").unwrap(), "bad id!");
+
+    let report = |kind: &str, request_id: &str| post_json("/api/client-errors", &json!({
+        "kind": kind, "platform": "android", "app_version": "2.3.0", "device": "Pixel 8",
+        "route": "/api/markers", "request_id": request_id, "http_status": 500, "message": "Internal error"
+    }));
+    assert_eq!(test::call_service(&app, report("anr", "gw-123").to_request()).await.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(test::call_service(&app, report("api_error", "bad id!").to_request()).await.status(), StatusCode::BAD_REQUEST);
+    // 샘플링에서 빠진 API 오류는 DB에 닿지 않음
+    let (status, body) = read_json(test::call_service(&app, report("api_error", "gw-123").to_request()).await).await;
+    assert_eq!((status, body["stored"].as_bool()), (StatusCode::ACCEPTED, Some(false)));
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let mut state = test_db.state.clone();
+    state.config.client_error_sample_rate = 0.0;
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname, is_admin) VALUES ('admin@example.invalid', 'admin', true);
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('user@example.invalid', 'user');
+    "#).await.expect("seed");
+    let member = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&db.pool);
+    let (admin, user) = (member("admin").await.unwrap(), member("user").await.unwrap());
+    let app = test::init_service(build_app(state.clone())).await;
+
+    // 크래시는 샘플링 비율과 관계없이 저장
+    let (status, body) = read_json(test::call_service(&app, as_member(report("crash", "gw-456"), user, &state).to_request()).await).await;
+    assert_eq!((status, body["stored"].as_bool()), (StatusCode::ACCEPTED, Some(true)));
+    assert_eq!(test::call_service(&app, report("api_error", "gw-789").to_request()).await.status(), StatusCode::ACCEPTED);
+
+    assert_eq!(test::call_service(&app, as_member(get("/api/admin/client-errors"), user, &state).to_request()).await.status(), StatusCode::FORBIDDEN);
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/admin/client-errors?request_id=gw-456"), admin, &state).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!((body["count"].as_i64(), body["data"][0]["kind"].as_str(), body["data"][0]["memberId"].as_i64()), (Some(1), Some("crash"), Some(user)));
+    assert!(body["data"][0]["reportRequestId"].is_string());
+    assert_eq!((body["summary"][0]["platform"].as_str(), body["summary"][0]["estimated"].as_i64()), (Some("android"), Some(1)));
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/admin/client-errors?request_id=gw-789"), admin, &state).to_request()).await).await;
+    assert_eq!(body["count"].as_i64(), Some(0));
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn route_usage_is_aggregated_by_pattern_and_app_version() {
+    assert_eq!(normalize_client_version(Some(" 2.3.0 ")), "2.3.0");
+    assert_eq!(normalize_client_version(Some("2.3.0; rm -rf")), UNKNOWN_VERSION);
+    assert_eq!(normalize_client_version(None), UNKNOWN_VERSION);
+
+    let state = deterministic(fake_state(), Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap())));
+    let app = test::init_service(build_app(state.clone())).await;
+    for path in ["/api/emotions", "/api/emotions", "/api/likes/stats"] {
+        test::call_service(&app, get(path).insert_header(("X-App-Version", "2.3.0")).to_request()).await;
+    }
+    test::call_service(&app, get("/api/no-such-route").to_request()).await;
+    let mut usage = state.route_usage.take();
+    usage.sort_by(|(a, _), (b, _)| a.route.cmp(&b.route));
+    let summary: Vec<(&str, &str, i64, i64, i64)> = usage.iter()
+        .map(|(key, counts)| (key.route.as_str(), key.client_version.as_str(), counts.requests, counts.client_errors, counts.server_errors))
+        .collect();
+    assert_eq!(summary, [
+        ("(unmatched)", "unknown", 1, 1, 0),
+        ("/api/emotions", "2.3.0", 2, 0, 0),
+        ("/api/likes/stats", "2.3.0", 1, 0, 1),
+    ]);
+    assert!(usage.iter().all(|(key, _)| key.usage_date == NaiveDate::from_ymd_opt(2026, 3, 1).unwrap() && key.method == "GET"));
+    assert!(state.route_usage.take().is_empty());
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, "INSERT INTO bigpicture.members (email, nickname, is_admin) VALUES ('admin@example.invalid', 'admin', true)").await.expect("seed");
+    let admin: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members WHERE nickname = 'admin'").fetch_one(&db.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    for version in ["1.0.0", "1.0.0", "2.0.0"] {
+        test::call_service(&app, get("/api/images/list").insert_header(("X-App-Version", version)).to_request()).await;
+    }
+    test::call_service(&app, get("/api/emotions").to_request()).await;
+
+    // 조회 전에 메모리 집계를 반영하므로 바로 보임
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/admin/usage?route=/api/images"), admin, &state).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let rows: Vec<(Option<&str>, Option<i64>)> = body["data"].as_array().unwrap().iter()
+        .map(|row| (row["clientVersion"].as_str(), row["requestCount"].as_i64()))
+        .collect();
+    assert_eq!(rows, [(Some("1.0.0"), Some(2)), (Some("2.0.0"), Some(1))]);
+    assert_eq!((body["routes"][0]["route"].as_str(), body["routes"][0]["requestCount"].as_i64()), (Some("/api/images/list"), Some(3)));
+
+    // 다시 반영해도 이미 더한 사용량은 두 번 세지 않음
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/admin/usage?route=/api/images&client_version=1.0.0"), admin, &state).to_request()).await).await;
+    assert_eq!((body["count"].as_i64(), body["data"][0]["requestCount"].as_i64()), (Some(1), Some(2)));
+
+    test_db.drop_database().await;
+}
+
+/// "stale-token"은 삭제된 앱으로 취급하고 받은 알림을 기록하는 가짜 FCM
+#[derive(Default)]
+struct FakePush {
+    sent: std::sync::Mutex<Vec<(String, String, Vec<(String, String)>)>>,
+}
+
+impl PushSender for FakePush {
+    fn send<'a>(&'a self, token: &'a str, message: &'a PushMessage) -> futures::future::BoxFuture<'a, anyhow::Result<PushResult>> {
+        Box::pin(async move {
+            if token == "stale-token" {
+                return Ok(PushResult::Unregistered);
+            }
+            self.sent.lock().unwrap().push((token.to_string(), message.title.clone(), message.data.clone()));
+            Ok(PushResult::Delivered)
+        })
+    }
+}
+
+#[actix_web::test]
+async fn marker_likes_are_pushed_to_author_devices_once() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let register = |token: &str, platform: &str| as_member(post_json("/api/members/me/devices", &json!({ "token": token, "platform": platform })), 1, &state);
+    assert_eq!(test::call_service(&app, register("token-1", "windows").to_request()).await.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(test::call_service(&app, register(" ", "ios").to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('writer@example.invalid', 'writer');
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('fan@example.invalid', 'fan');
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('quiet@example.invalid', 'quiet');
+    "#).await.expect("seed");
+    let member = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&db.pool);
+    let (writer, fan, quiet) = (member("writer").await.unwrap(), member("fan").await.unwrap(), member("quiet").await.unwrap());
+    let app = test::init_service(build_app(state.clone())).await;
+
+    for (token, platform) in [("writer-phone", "ios"), ("stale-token", "android")] {
+        let (status, body) = read_json(test::call_service(&app, as_member(post_json("/api/members/me/devices", &json!({ "token": token, "platform": platform })), writer, &state).to_request()).await).await;
+        assert_eq!((status, body["data"]["platform"].as_str()), (StatusCode::OK, Some(platform)));
+    }
+    let (status, body) = read_json(test::call_service(&app, as_member(TestRequest::put().uri("/api/members/me/notification-preferences").set_json(json!({ "likes": false })), quiet, &state).to_request()).await).await;
+    assert_eq!((status, body["data"]["likes"].as_bool(), body["data"]["comments"].as_bool()), (StatusCode::OK, Some(false), Some(true)));
+
+    let create = |author: i64| as_member(post_json("/api/markers", &json!({ "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": "산책" })), author, &state);
+    let (_, body) = read_json(test::call_service(&app, create(writer).to_request()).await).await;
+    let writer_marker = body["data"]["id"].as_i64().unwrap();
+    let (_, body) = read_json(test::call_service(&app, create(quiet).to_request()).await).await;
+    let quiet_marker = body["data"]["id"].as_i64().unwrap();
+    // 좋아요 → 취소 → 다시 좋아요여도 알림은 한 번, 본인 마커 좋아요는 알림 없음
+    let like = |marker_id: i64, by: i64| as_member(post_json(&format!("/api/markers/{}/reaction", marker_id), &json!({ "like_type": "like" })), by, &state);
+    for (marker_id, by) in [(writer_marker, fan), (writer_marker, fan), (writer_marker, fan), (writer_marker, writer), (quiet_marker, fan)] {
+        assert_eq!(test::call_service(&app, like(marker_id, by).to_request()).await.status(), StatusCode::OK);
+    }
+
+    let push = FakePush::default();
+    assert_eq!(push::process_pending_pushes(db, &push, 3600).await.unwrap(), 1);
+    let sent = push.sent.lock().unwrap().clone();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].0, "writer-phone");
+    assert!(sent[0].2.contains(&("markerId".to_string(), writer_marker.to_string())));
+    assert_eq!(db.get_member_device_tokens(writer).await.unwrap(), ["writer-phone"]);
+    assert_eq!(push::process_pending_pushes(db, &push, 3600).await.unwrap(), 0);
+
+    // 좋아요 푸시를 끈 회원은 알림함에만 남음
+    let statuses: Vec<(i64, Option<String>)> = sqlx::query_as("SELECT member_id, push_status FROM bigpicture.notifications WHERE notification_type = 'marker_liked' ORDER BY member_id")
+        .fetch_all(&db.pool).await.unwrap();
+    let mut expected = vec![(writer, Some("sent".to_string())), (quiet, None)];
+    expected.sort();
+    assert_eq!(statuses, expected);
+
+    let unregister = |token: &str| as_member(TestRequest::delete().uri(&format!("/api/members/me/devices?token={}", token)), writer, &state);
+    assert_eq!(test::call_service(&app, unregister("writer-phone").to_request()).await.status(), StatusCode::OK);
+    assert_eq!(test::call_service(&app, unregister("writer-phone").to_request()).await.status(), StatusCode::NOT_FOUND);
+
+    test_db.drop_database().await;
+}
+
+struct StampPlugin {
+    name: &'static str,
+    stage: PipelineStage,
+    fail: bool,
+}
+
+impl ImagePlugin for StampPlugin {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn stage(&self) -> PipelineStage {
+        self.stage
+    }
+
+    fn apply(&self, frame: &mut ImageFrame) -> anyhow::Result<()> {
+        if self.fail {
+            anyhow::bail!("검사 서버 없음");
+        }
+        let (width, height) = (frame.image.width(), frame.image.height());
+        frame.annotations.insert(self.name.to_string(), format!("{}x{} {}", width, height, frame.encoded.len()));
+        Ok(())
+    }
+}
+
+#[actix_web::test]
+async fn image_pipeline_orients_runs_plugins_in_stage_order_and_times_stages() {
+    // 400x200 JPEG + EXIF 방향 6 (시계 방향 90도 회전해서 보여야 하는 세로 사진)
+    let photo = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(400, 200, image::Rgb([200, 40, 40])));
+    let mut jpeg = std::io::Cursor::new(Vec::new());
+    photo.write_to(&mut jpeg, image::ImageOutputFormat::Jpeg(90)).expect("jpeg");
+    let jpeg = jpeg.into_inner();
+    let tiff: &[u8] = &[b'M', b'M', 0, 42, 0, 0, 0, 8, 0, 1, 0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0, 0, 0, 0, 0];
+    let mut exif = b"Exif\0\0".to_vec();
+    exif.extend_from_slice(tiff);
+    let mut oriented = jpeg[..2].to_vec();
+    oriented.extend_from_slice(&[0xFF, 0xE1]);
+    oriented.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+    oriented.extend_from_slice(&exif);
+    oriented.extend_from_slice(&jpeg[2..]);
+    assert_eq!(image_pipeline::read_exif_orientation(&oriented), Some(6));
+    assert_eq!(image_pipeline::read_exif_orientation(&jpeg), None);
+
+    let hooks = ImageHooks::default()
+        .with_plugin(Arc::new(StampPlugin { name: "encoded", stage: PipelineStage::Encode, fail: false }))
+        .with_plugin(Arc::new(StampPlugin { name: "nsfw", stage: PipelineStage::Filter, fail: true }))
+        .with_plugin(Arc::new(StampPlugin { name: "blurhash", stage: PipelineStage::Filter, fail: false }));
+    let processor = ImageProcessor::new(100, 100, 80).with_hooks(&hooks);
+    let output = processor.run_pipeline(&oriented).expect("pipeline");
+
+    // 품질은 방향 보정 후 원본 기준, 결과는 세로로 돌려서 100x100 안에 맞춤
+    assert_eq!((output.quality.width, output.quality.height), (200, 400));
+    let processed = image::load_from_memory(&output.data).expect("webp");
+    assert_eq!((processed.width(), processed.height()), (50, 100));
+    // 실패한 선택 플러그인은 건너뛰고, 필터 단계 플러그인은 인코딩 전에 실행됨
+    assert_eq!(output.annotations.get("blurhash").map(String::as_str), Some("50x100 0"));
+    assert!(output.annotations["encoded"].starts_with("50x100 ") && !output.annotations.contains_key("nsfw"));
+    let keys: Vec<String> = output.timings.iter().map(|timing| timing.key()).collect();
+    assert_eq!(keys, ["decode", "orient", "resize", "filter", "filter:nsfw", "filter:blurhash", "encode", "encode:encoded"]);
+
+    let metrics = hooks.metrics_json();
+    assert_eq!(metrics["plugins"], json!(["encoded", "nsfw", "blurhash"]));
+    let nsfw = metrics["stages"].as_array().unwrap().iter().find(|stage| stage["stage"] == "filter:nsfw").expect("nsfw stats");
+    assert_eq!((nsfw["count"].as_u64(), nsfw["failures"].as_u64()), (Some(1), Some(1)));
+
+    // 필수 플러그인이 실패하면 처리 전체가 실패
+    struct Required;
+    impl ImagePlugin for Required {
+        fn name(&self) -> &'static str { "watermark" }
+        fn stage(&self) -> PipelineStage { PipelineStage::Filter }
+        fn apply(&self, _frame: &mut ImageFrame) -> anyhow::Result<()> { anyhow::bail!("폰트 없음") }
+        fn required(&self) -> bool { true }
+    }
+    let strict = ImageProcessor::new(100, 100, 80).with_hooks(&ImageHooks::default().with_plugin(Arc::new(Required)));
+    assert!(strict.process_image(&jpeg).is_err());
+
+    // 업로드 임시 파일에서 바로 디코딩해도 EXIF 방향과 결과가 같음
+    let path = std::env::temp_dir().join(format!("bigpicture-pipeline-{}.jpg", uuid::Uuid::new_v4().simple()));
+    std::fs::write(&path, &oriented).unwrap();
+    let (data, quality) = processor.process_source_with_quality(ImageSource::File(&path)).expect("file pipeline");
+    assert_eq!((quality.width, quality.height, data.len()), (200, 400, output.data.len()));
+    assert_eq!(processor.get_file_image_info(&path).unwrap(), (400, 200, "JPEG".to_string()));
+    std::fs::remove_file(&path).unwrap();
+    assert!(processor.process_source(ImageSource::File(&path)).is_err());
+}
+
+#[test]
+fn webp_encoding_uses_the_processor_quality_and_lossless_png() {
+    let noisy = image::RgbImage::from_fn(300, 200, |x, y| {
+        let noise = (x.wrapping_mul(7919) ^ y.wrapping_mul(104_729)) % 97;
+        image::Rgb([(x + noise) as u8, (y * 2 + noise) as u8, (noise * 2) as u8])
+    });
+    let mut jpeg = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgb8(noisy.clone()).write_to(&mut jpeg, image::ImageOutputFormat::Jpeg(95)).expect("jpeg");
+    let low = ImageProcessor::new(800, 800, 20).process_image(jpeg.get_ref()).expect("low");
+    let high = ImageProcessor::new(800, 800, 95).process_image(jpeg.get_ref()).expect("high");
+    assert!(low.len() < high.len(), "{} >= {}", low.len(), high.len());
+
+    // PNG 원본만 무손실 (VP8L), JPEG 원본은 무손실 설정이어도 손실 압축
+    let mut png = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgb8(noisy.clone()).write_to(&mut png, image::ImageOutputFormat::Png).expect("png");
+    let lossless = ImageProcessor::new(800, 800, 20).with_lossless_png(true);
+    let webp = lossless.process_image(png.get_ref()).expect("lossless");
+    assert_eq!(&webp[12..16], b"VP8L");
+    assert_eq!(image::load_from_memory(&webp).expect("webp").to_rgb8(), noisy);
+    assert_ne!(&lossless.process_image(jpeg.get_ref()).expect("jpeg")[12..16], b"VP8L");
+    assert_ne!(&ImageProcessor::new(800, 800, 20).process_image(png.get_ref()).expect("lossy")[12..16], b"VP8L");
+}
+
+#[test]
+fn circular_thumbnails_are_resized_and_antialiased() {
+    let photo = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(1200, 800, image::Rgb([200, 40, 40])));
+    let mut jpeg = std::io::Cursor::new(Vec::new());
+    photo.write_to(&mut jpeg, image::ImageOutputFormat::Jpeg(90)).expect("jpeg");
+    let webp = ImageProcessor::new(250, 250, 85).process_circular_thumbnail(jpeg.get_ref()).expect("circular");
+    let avatar = image::load_from_memory(&webp).expect("webp").to_rgba8();
+    // 지름 250 + 테두리 4px 양쪽 + 그림자 3px
+    assert_eq!(avatar.dimensions(), (261, 261));
+    assert_eq!(avatar.get_pixel(0, 0)[3], 0);
+    let center = avatar.get_pixel(129, 129);
+    assert!(center[3] == 255 && center[0] > 150 && center[1] < 100, "{:?}", center);
+    let border = avatar.get_pixel(129, 1);
+    assert!(border[3] == 255 && border.0[..3].iter().all(|&channel| channel > 200), "{:?}", border);
+    // 가장자리는 0/255만이 아니라 중간 불투명도로 부드럽게 이어짐
+    let edge_alphas: std::collections::BTreeSet<u8> = avatar.pixels().map(|pixel| pixel[3]).filter(|alpha| *alpha != 0 && *alpha != 255).collect();
+    assert!(edge_alphas.len() > 10, "{:?}", edge_alphas);
+
+    // 지름보다 작은 이미지는 키우지 않음
+    let small = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(100, 60, image::Rgb([20, 120, 200])));
+    let mut png = std::io::Cursor::new(Vec::new());
+    small.write_to(&mut png, image::ImageOutputFormat::Png).expect("png");
+    let webp = ImageProcessor::new(250, 250, 85).process_circular_thumbnail(png.get_ref()).expect("circular");
+    assert_eq!(image::load_from_memory(&webp).expect("webp").to_rgba8().dimensions(), (71, 71));
+}
+
+#[actix_web::test]
+async fn s3_uploads_stream_the_original_before_processing() {
+    let app = test::init_service(build_app(fake_state())).await;
+    let upload = |filename: &str| {
+        let boundary = "bigpicture-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"{filename}\"\r\nContent-Type: image/jpeg\r\n\r\n"
+        ).into_bytes();
+        body.extend_from_slice(&[0xFF, 0xD8, 0xFF, 0xD9]);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        TestRequest::post()
+            .uri("/api/s3/upload/thumbnail")
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(body)
+    };
+    let (status, body) = read_json(test::call_service(&app, upload("notes.txt").to_request()).await).await;
+    assert_eq!((status, body["success"].as_bool()), (StatusCode::BAD_REQUEST, Some(false)));
+    // 원본 멀티파트 업로드를 시작할 수 없으면 본문을 받기 전에 실패
+    let (status, body) = read_json(test::call_service(&app, upload("photo.jpg").to_request()).await).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(body["message"].as_str().unwrap().starts_with("S3 업로드 실패"), "{}", body);
+    assert!(body["original_url"].is_null());
+}
+
+#[actix_web::test]
+async fn image_processing_is_bounded_and_times_out_with_503() {
+    let workers = ImageWorkers::new(1, std::time::Duration::from_millis(200));
+    assert_eq!(workers.run(|| Ok(7)).await.unwrap(), 7);
+    assert_eq!(workers.in_flight(), 0);
+
+    // 자리 하나를 오래 걸리는 처리가 차지하면 다음 처리는 기다리다 시간 초과
+    let (started_tx, started_rx) = std::sync::mpsc::channel();
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+    let busy = {
+        let workers = workers.clone();
+        tokio::spawn(async move {
+            workers.run(move || {
+                started_tx.send(()).unwrap();
+                release_rx.recv().ok();
+                Ok(())
+            }).await
+        })
+    };
+    tokio::task::spawn_blocking(move || started_rx.recv().unwrap()).await.unwrap();
+    assert_eq!(workers.in_flight(), 1);
+    let error = workers.run(|| Ok(())).await.unwrap_err();
+    assert!(error.is::<ImageProcessingTimeout>(), "{}", error);
+    assert_eq!(workers.in_flight(), 1);
+
+    // 원형 썸네일 업로드도 같은 자리를 기다리다 503
+    let mut state = fake_state();
+    state.image_hooks = ImageHooks::default().with_workers(workers.clone());
+    let app = test::init_service(build_app(state)).await;
+    let mut png = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(64, 64, image::Rgb([20, 120, 200])))
+        .write_to(&mut png, image::ImageOutputFormat::Png)
+        .expect("png");
+    let boundary = "bigpicture-boundary";
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"face.png\"\r\nContent-Type: image/png\r\n\r\n"
+    ).into_bytes();
+    body.extend_from_slice(png.get_ref());
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    let request = TestRequest::post()
+        .uri("/api/s3/upload/circular")
+        .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+        .set_payload(body)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.headers().contains_key("Retry-After"));
+    let (status, body) = read_json(response).await;
+    assert_eq!((status, body["success"].as_bool()), (StatusCode::SERVICE_UNAVAILABLE, Some(false)));
+
+    // 시간 초과로 포기한 요청도 이미 시작한 처리가 끝날 때까지 자리를 차지함
+    assert!(busy.await.unwrap().unwrap_err().is::<ImageProcessingTimeout>());
+    assert_eq!(workers.in_flight(), 1);
+    release_tx.send(()).unwrap();
+    for _ in 0..100 {
+        if workers.in_flight() == 0 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(workers.in_flight(), 0);
+}
+
+#[actix_web::test]
+async fn local_storage_writes_under_the_root_and_signs_expiring_links() {
+    let root = std::env::temp_dir().join(format!("bigpicture-storage-{}", uuid::Uuid::new_v4().simple()));
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()));
+    let local = LocalStorage::new(&root, "http://files.test/", "secret").with_clock(clock.clone());
+
+    let url = local.put("markers/a.webp", b"webp".to_vec(), "image/webp").await.unwrap();
+    assert_eq!(url, "http://files.test/api/images/download/markers/a.webp");
+    assert_eq!(image_cleanup::storage_key(&url).as_deref(), Some("local:markers/a.webp"));
+    assert_eq!(local.get("markers/a.webp").await.unwrap(), b"webp");
+    for key in ["../a.webp", "/etc/passwd", "markers/../../a.webp", "", "markers/a.webp.part"] {
+        assert!(local.put(key, b"x".to_vec(), "image/webp").await.is_err(), "{}", key);
+    }
+    assert!(storage::is_public_key("originals/map/original_1.jpg"));
+    assert!(!storage::is_public_key("member-exports/1/2.zip"));
+
+    // 나눠 쓰는 업로드는 완료 전까지 제 이름으로 보이지 않음
+    let mut upload = local.start_upload("originals/thumbnail/b.jpg", "image/jpeg").await.unwrap();
+    upload.write(b"jp").await.unwrap();
+    upload.write(b"eg").await.unwrap();
+    assert!(local.get("originals/thumbnail/b.jpg").await.is_err());
+    assert_eq!(upload.complete().await.unwrap(), "http://files.test/api/images/download/originals/thumbnail/b.jpg");
+    assert_eq!(local.get("originals/thumbnail/b.jpg").await.unwrap(), b"jpeg");
+    let upload = local.start_upload("originals/thumbnail/c.jpg", "image/jpeg").await.unwrap();
+    upload.abort().await;
+    assert_eq!(std::fs::read_dir(root.join("originals/thumbnail")).unwrap().count(), 1);
+
+    let link = local.presign("exports/a.csv", std::time::Duration::from_secs(600)).await.unwrap();
+    let query = reqwest::Url::parse(&link).unwrap();
+    assert_eq!(query.path(), "/api/storage/exports/a.csv");
+    let param = |name: &str| query.query_pairs().find(|(key, _)| key == name).unwrap().1.to_string();
+    let (expires, signature) = (param("expires").parse::<i64>().unwrap(), param("signature"));
+    assert_eq!(expires, Utc.with_ymd_and_hms(2025, 3, 1, 12, 10, 0).unwrap().timestamp());
+    assert!(local.verify_presigned("exports/a.csv", expires, &signature));
+    assert!(!local.verify_presigned("exports/b.csv", expires, &signature));
+    assert!(!local.verify_presigned("exports/a.csv", expires + 1, &signature));
+    assert!(!LocalStorage::new(&root, "http://files.test", "other").verify_presigned("exports/a.csv", expires, &signature));
+    clock.advance(Duration::seconds(601));
+    assert!(!local.verify_presigned("exports/a.csv", expires, &signature));
+
+    local.delete("markers/a.webp").await.unwrap();
+    local.delete("markers/a.webp").await.unwrap();
+    assert!(local.get("markers/a.webp").await.is_err());
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[actix_web::test]
+async fn uploads_go_through_the_configured_storage_backend() {
+    let root = std::env::temp_dir().join(format!("bigpicture-local-uploads-{}", uuid::Uuid::new_v4().simple()));
+    let mut state = fake_state();
+    state.config.storage_backend = "local".to_string();
+    state.config.upload_dir = root.to_string_lossy().to_string();
+    state.config.file_server_url = "http://files.test".to_string();
+    state.config.min_free_disk_mb = 0;
+    state.storage = storage::from_config(&state.config, &state.s3_service);
+    let local = state.storage.clone();
+    let app = test::init_service(build_app(state)).await;
+
+    let photo = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(400, 200, image::Rgb([20, 120, 200])));
+    let mut jpeg = std::io::Cursor::new(Vec::new());
+    photo.write_to(&mut jpeg, image::ImageOutputFormat::Jpeg(90)).expect("jpeg");
+    let upload = |uri: &str| {
+        let boundary = "bigpicture-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"photo.jpg\"\r\nContent-Type: image/jpeg\r\n\r\n"
+        ).into_bytes();
+        body.extend_from_slice(jpeg.get_ref());
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        TestRequest::post()
+            .uri(uri)
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(body)
+    };
+    let download = |url: &str| get(url.strip_prefix("http://files.test").unwrap()).to_request();
+
+    // /api/images와 /api/s3 업로드가 같은 저장소/키 형식을 사용 (DB 기록 실패는 경고만)
+    for uri in ["/api/images/upload/thumbnail", "/api/s3/upload/thumbnail"] {
+        let (status, body) = read_json(test::call_service(&app, upload(uri).to_request()).await).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let url = body["s3_url"].as_str().unwrap();
+        assert!(url.starts_with("http://files.test/api/images/download/markers/thumbnail_"), "{}", url);
+        assert_eq!(body["url"], body["s3_url"]);
+        assert!(body["original_url"].as_str().unwrap().contains("/api/images/download/originals/thumbnail/original_"));
+        assert!(body["thumbnail_url"].as_str().unwrap().contains("/api/images/download/markers/thumbs/"));
+        let response = test::call_service(&app, download(url)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get("content-type").unwrap(), "image/webp");
+        let response = test::call_service(&app, download(body["original_url"].as_str().unwrap())).await;
+        assert_eq!(response.headers().get("content-type").unwrap(), "image/jpeg");
+        assert_eq!(test::read_body(response).await.as_ref(), jpeg.get_ref().as_slice());
+    }
+    let (status, body) = read_json(test::call_service(&app, upload("/api/images/upload/map").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["s3_url"].as_str().unwrap().contains("/api/images/download/maps/map_"));
+    let (status, body) = read_json(test::call_service(&app, upload("/api/images/generate/thumbnail").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["s3_url"].as_str().unwrap().contains("/api/images/download/thumbnails/generated_thumbnail_"));
+
+    // 공개 폴더 밖 파일은 서명된 링크로만
+    local.put("member-exports/7/1.zip", b"zip".to_vec(), "application/zip").await.unwrap();
+    let response = test::call_service(&app, get("/api/images/download/member-exports/7/1.zip").to_request()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let link = local.presign("member-exports/7/1.zip", std::time::Duration::from_secs(60)).await.unwrap();
+    let response = test::call_service(&app, download(&link)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(test::read_body(response).await.as_ref(), b"zip");
+    let response = test::call_service(&app, download(&link.replace("signature=", "signature=00"))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // S3 저장소에서는 서명 링크 경로를 쓰지 않음 (S3가 직접 서명 URL 발급)
+    let app = test::init_service(build_app(fake_state())).await;
+    let response = test::call_service(&app, download(&link)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let s3_link = fake_state().storage.presign("member-exports/7/1.zip", std::time::Duration::from_secs(60)).await.unwrap();
+    assert!(s3_link.contains("X-Amz-Signature="), "{}", s3_link);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[actix_web::test]
+async fn uploads_return_responsive_variants_up_to_the_source_width() {
+    let root = std::env::temp_dir().join(format!("bigpicture-responsive-{}", uuid::Uuid::new_v4().simple()));
+    let mut state = fake_state();
+    state.config.storage_backend = "local".to_string();
+    state.config.upload_dir = root.to_string_lossy().to_string();
+    state.config.file_server_url = "http://files.test".to_string();
+    state.config.min_free_disk_mb = 0;
+    state.storage = storage::from_config(&state.config, &state.s3_service);
+    let app = test::init_service(build_app(state)).await;
+
+    let photo = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(1000, 500, image::Rgb([200, 80, 40])));
+    let mut png = std::io::Cursor::new(Vec::new());
+    photo.write_to(&mut png, image::ImageOutputFormat::Png).expect("png");
+    let boundary = "bigpicture-boundary";
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"wide.png\"\r\nContent-Type: image/png\r\n\r\n"
+    ).into_bytes();
+    body.extend_from_slice(png.get_ref());
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    let request = TestRequest::post()
+        .uri("/api/images/upload/thumbnail")
+        .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+        .set_payload(body)
+        .to_request();
+    let (status, body) = read_json(test::call_service(&app, request).await).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    // 1000px 원본: 256/800만 만들고 1600은 확대하지 않음
+    let variants = body["variants"].as_object().unwrap();
+    assert_eq!(variants.keys().collect::<Vec<_>>(), ["256", "800"]);
+    for (width, url) in variants {
+        let url = url.as_str().unwrap();
+        assert!(url.contains(&format!("/api/images/download/markers/w{}/thumbnail_", width)), "{}", url);
+        let response = test::call_service(&app, get(url.strip_prefix("http://files.test").unwrap()).to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let image = image::load_from_memory(&test::read_body(response).await).unwrap();
+        assert_eq!(image.width(), width.parse::<u32>().unwrap());
+        assert_eq!(image.height(), image.width() / 2);
+    }
+    assert_eq!(
+        body["srcset"],
+        format!("{} 256w, {} 800w", variants["256"].as_str().unwrap(), variants["800"].as_str().unwrap())
+    );
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[actix_web::test]
+async fn uploads_return_exif_location_and_capture_time_when_requested() {
+    // EXIF: IFD0(Exif/GPS 위치) → Exif IFD(DateTimeOriginal) → GPS IFD(37°33'59.4"N, 126°58'40.8"E)
+    let entry = |tag: u16, kind: u16, count: u32, value: u32| {
+        let mut bytes = tag.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&kind.to_be_bytes());
+        bytes.extend_from_slice(&count.to_be_bytes());
+        bytes.extend_from_slice(&value.to_be_bytes());
+        bytes
+    };
+    let mut tiff = vec![b'M', b'M', 0, 42, 0, 0, 0, 8];
+    tiff.extend_from_slice(&[0, 2]);
+    tiff.extend(entry(0x8769, 4, 1, 38));
+    tiff.extend(entry(0x8825, 4, 1, 56));
+    tiff.extend_from_slice(&[0, 0, 0, 0]);
+    tiff.extend_from_slice(&[0, 1]);
+    tiff.extend(entry(0x9003, 2, 20, 110));
+    tiff.extend_from_slice(&[0, 0, 0, 0]);
+    tiff.extend_from_slice(&[0, 4]);
+    tiff.extend(entry(0x0001, 2, 2, u32::from_be_bytes([b'N', 0, 0, 0])));
+    tiff.extend(entry(0x0002, 5, 3, 130));
+    tiff.extend(entry(0x0003, 2, 2, u32::from_be_bytes([b'E', 0, 0, 0])));
+    tiff.extend(entry(0x0004, 5, 3, 154));
+    tiff.extend_from_slice(&[0, 0, 0, 0]);
+    assert_eq!(tiff.len(), 110);
+    tiff.extend_from_slice(b"2025:04:05 14:30:15\0");
+    for (numerator, denominator) in [(37u32, 1u32), (33, 1), (594, 10), (126, 1), (58, 1), (408, 10)] {
+        tiff.extend_from_slice(&numerator.to_be_bytes());
+        tiff.extend_from_slice(&denominator.to_be_bytes());
+    }
+    let photo = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(300, 200, image::Rgb([30, 160, 90])));
+    let mut jpeg = std::io::Cursor::new(Vec::new());
+    photo.write_to(&mut jpeg, image::ImageOutputFormat::Jpeg(90)).expect("jpeg");
+    let jpeg = jpeg.into_inner();
+    let mut exif = b"Exif\0\0".to_vec();
+    exif.extend_from_slice(&tiff);
+    let mut tagged = jpeg[..2].to_vec();
+    tagged.extend_from_slice(&[0xFF, 0xE1]);
+    tagged.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+    tagged.extend_from_slice(&exif);
+    tagged.extend_from_slice(&jpeg[2..]);
+
+    let parsed = image_pipeline::read_photo_exif(&tagged);
+    assert!((parsed.latitude.unwrap() - 37.5665).abs() < 1e-6, "{:?}", parsed);
+    assert!((parsed.longitude.unwrap() - 126.978).abs() < 1e-6, "{:?}", parsed);
+    assert_eq!(parsed.taken_at.unwrap().to_string(), "2025-04-05 14:30:15");
+    assert_eq!(image_pipeline::read_photo_exif(&jpeg), image_pipeline::PhotoExif::default());
+
+    let root = std::env::temp_dir().join(format!("bigpicture-exif-{}", uuid::Uuid::new_v4().simple()));
+    let mut state = fake_state();
+    state.config.storage_backend = "local".to_string();
+    state.config.upload_dir = root.to_string_lossy().to_string();
+    state.config.min_free_disk_mb = 0;
+    state.storage = storage::from_config(&state.config, &state.s3_service);
+    let app = test::init_service(build_app(state)).await;
+    let upload = |uri: &str| {
+        let boundary = "bigpicture-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"trip.jpg\"\r\nContent-Type: image/jpeg\r\n\r\n"
+        ).into_bytes();
+        body.extend_from_slice(&tagged);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        TestRequest::post()
+            .uri(uri)
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(body)
+            .to_request()
+    };
+
+    for uri in ["/api/images/upload/thumbnail?exif=true", "/api/s3/upload/map?exif=true"] {
+        let (status, body) = read_json(test::call_service(&app, upload(uri)).await).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert!((body["exif"]["latitude"].as_f64().unwrap() - 37.5665).abs() < 1e-6, "{}", body);
+        assert!((body["exif"]["longitude"].as_f64().unwrap() - 126.978).abs() < 1e-6, "{}", body);
+        assert_eq!(body["exif"]["taken_at"], "2025-04-05T14:30:15");
+    }
+    // 요청하지 않으면 읽지 않음
+    let (status, body) = read_json(test::call_service(&app, upload("/api/images/upload/thumbnail")).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["exif"].is_null());
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[actix_web::test]
+async fn uploads_can_be_encoded_as_avif() {
+    let photo = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(300, 150, |x, y| image::Rgb([x as u8, y as u8, 120])));
+    let mut png = std::io::Cursor::new(Vec::new());
+    photo.write_to(&mut png, image::ImageOutputFormat::Png).expect("png");
+    let png = png.into_inner();
+    let avif = ImageProcessor::new(200, 200, 80)
+        .with_output_format(OutputFormat::Avif, 50)
+        .run_pipeline(&png)
+        .unwrap();
+    assert_eq!(&avif.data[4..12], b"ftypavif");
+    assert_eq!(avif.dimensions, (200, 100));
+
+    let root = std::env::temp_dir().join(format!("bigpicture-avif-{}", uuid::Uuid::new_v4().simple()));
+    let mut state = fake_state();
+    state.config.storage_backend = "local".to_string();
+    state.config.upload_dir = root.to_string_lossy().to_string();
+    state.config.file_server_url = "http://files.test".to_string();
+    state.config.min_free_disk_mb = 0;
+    state.storage = storage::from_config(&state.config, &state.s3_service);
+    let app = test::init_service(build_app(state)).await;
+    let upload = |uri: &str| {
+        let boundary = "bigpicture-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"feed.png\"\r\nContent-Type: image/png\r\n\r\n"
+        ).into_bytes();
+        body.extend_from_slice(&png);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        TestRequest::post()
+            .uri(uri)
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(body)
+            .to_request()
+    };
+
+    // 변환 이미지와 너비별 이미지는 AVIF, 마커 썸네일은 WebP 그대로
+    let (status, body) = read_json(test::call_service(&app, upload("/api/images/upload/thumbnail?output_format=avif")).await).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let url = body["url"].as_str().unwrap();
+    assert!(url.contains("/api/images/download/markers/thumbnail_") && url.ends_with(".avif"), "{}", url);
+    assert!(body["variants"]["256"].as_str().unwrap().ends_with(".avif"));
+    assert!(body["thumbnail_url"].as_str().unwrap().ends_with(".webp"));
+    let response = test::call_service(&app, get(url.strip_prefix("http://files.test").unwrap()).to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("content-type").unwrap(), "image/avif");
+    assert_eq!(&test::read_body(response).await[4..12], b"ftypavif");
+
+    let (status, body) = read_json(test::call_service(&app, upload("/api/s3/upload/map?output_format=webp")).await).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(body["url"].as_str().unwrap().ends_with(".webp"));
+    let response = test::call_service(&app, upload("/api/images/upload/thumbnail?output_format=heic")).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[actix_web::test]
+async fn uploads_reject_content_that_does_not_match_a_supported_image() {
+    let root = std::env::temp_dir().join(format!("bigpicture-sniff-{}", uuid::Uuid::new_v4().simple()));
+    let mut state = fake_state();
+    state.config.storage_backend = "local".to_string();
+    state.config.upload_dir = root.to_string_lossy().to_string();
+    state.config.min_free_disk_mb = 0;
+    state.storage = storage::from_config(&state.config, &state.s3_service);
+    let app = test::init_service(build_app(state)).await;
+    let upload = |uri: &str, filename: &str, content: &[u8]| {
+        let boundary = "bigpicture-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"{filename}\"\r\nContent-Type: image/jpeg\r\n\r\n"
+        ).into_bytes();
+        body.extend_from_slice(content);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        TestRequest::post()
+            .uri(uri)
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(body)
+            .to_request()
+    };
+    let mut exe = b"MZ\x90\x00\x03\x00\x00\x00\x04\x00\x00\x00\xff\xff\x00\x00".to_vec();
+    exe.resize(4096, 0);
+    let photo = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(64, 64, image::Rgb([10, 10, 10])));
+    let mut png = std::io::Cursor::new(Vec::new());
+    photo.write_to(&mut png, image::ImageOutputFormat::Png).expect("png");
+
+    for uri in ["/api/images/upload/thumbnail", "/api/s3/upload/map", "/api/s3/upload/circular"] {
+        // 확장자만 바꾼 실행 파일, 아주 작은 파일
+        for content in [exe.as_slice(), b"MZ"] {
+            let (status, body) = read_json(test::call_service(&app, upload(uri, "photo.jpg", content)).await).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{} {}", uri, body);
+        }
+        // 내용은 PNG인데 확장자는 .jpg
+        let (status, body) = read_json(test::call_service(&app, upload(uri, "photo.jpg", png.get_ref())).await).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{} {}", uri, body);
+        assert!(body.to_string().contains(".jpg"), "{}", body);
+        let response = test::call_service(&app, upload(uri, "photo.png", png.get_ref())).await;
+        assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+    }
+    // 거부된 파일은 원본도 저장하지 않음
+    let originals: Vec<_> = walk_files(&root.join("originals"));
+    assert_eq!(originals.len(), 2, "{:?}", originals);
+    assert!(originals.iter().all(|path| path.extension().unwrap() == "png"));
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+fn walk_files(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries.flatten().flat_map(|entry| {
+        let path = entry.path();
+        if path.is_dir() { walk_files(&path) } else { vec![path] }
+    }).collect()
+}
+
+#[actix_web::test]
+async fn processed_images_carry_a_blurhash_placeholder() {
+    // 크기 플래그(4x3 → L, 1x1 → 0), AC 최대값, DC(평균 색, 흰색 = TSUA), AC 성분 2자씩
+    let white = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(40, 30, image::Rgb([255, 255, 255])));
+    assert_eq!(blurhash::encode(&white, 1, 1), "00TSUA");
+    let white_hash = blurhash::encode(&white, 4, 3);
+    assert!(white_hash.starts_with('L') && &white_hash[2..6] == "TSUA", "{}", white_hash);
+    let gradient = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(64, 48, |x, y| image::Rgb([(x * 4) as u8, (y * 5) as u8, 90])));
+    let hash = blurhash::encode(&gradient, blurhash::X_COMPONENTS, blurhash::Y_COMPONENTS);
+    assert_eq!(hash.len(), 28);
+    assert_ne!(&hash[6..8], "fQ", "{}", hash);
+
+    let mut png = std::io::Cursor::new(Vec::new());
+    gradient.write_to(&mut png, image::ImageOutputFormat::Png).expect("png");
+    let output = ImageProcessor::new(800, 800, 80).run_pipeline(png.get_ref()).unwrap();
+    assert_eq!(output.blurhash, hash);
+
+    let root = std::env::temp_dir().join(format!("bigpicture-blurhash-{}", uuid::Uuid::new_v4().simple()));
+    let mut state = fake_state();
+    state.config.storage_backend = "local".to_string();
+    state.config.upload_dir = root.to_string_lossy().to_string();
+    state.config.min_free_disk_mb = 0;
+    state.storage = storage::from_config(&state.config, &state.s3_service);
+    let app = test::init_service(build_app(state)).await;
+    let boundary = "bigpicture-boundary";
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"gradient.png\"\r\nContent-Type: image/png\r\n\r\n"
+    ).into_bytes();
+    body.extend_from_slice(png.get_ref());
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    let request = TestRequest::post()
+        .uri("/api/images/upload/thumbnail")
+        .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+        .set_payload(body)
+        .to_request();
+    let (status, body) = read_json(test::call_service(&app, request).await).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["blurhash"], hash);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+struct FakeModerator(Vec<&'static str>);
+
+impl ImageModerator for FakeModerator {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn moderate<'a>(&'a self, jpeg: &'a [u8]) -> futures_util::future::BoxFuture<'a, anyhow::Result<ModerationVerdict>> {
+        Box::pin(async move {
+            assert_eq!(image::guess_format(jpeg).unwrap(), image::ImageFormat::Jpeg);
+            Ok(ModerationVerdict::from_labels(self.0.iter().map(|label| label.to_string()).collect()))
+        })
+    }
+}
+
+#[actix_web::test]
+async fn moderation_rejects_images_and_hides_them_from_markers() {
+    let verdict = ModerationVerdict::from_labels(vec!["Violence".to_string(), "Explicit Nudity".to_string(), "Violence".to_string()]);
+    assert_eq!(verdict.status, ModerationStatus::Rejected);
+    assert_eq!(verdict.labels, ["Explicit Nudity", "Violence"]);
+    assert_eq!(ModerationVerdict::from_labels(Vec::new()).status, ModerationStatus::Approved);
+
+    // 가짜 ONNX 분류기 서버: 안전 클래스와 기준 미만 점수는 거절 사유가 아님
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let onnx = OnnxModerator::new(&format!("http://{}/classify", listener.local_addr().unwrap()), 0.8);
+    let server = tokio::spawn(async move {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let (mut socket, _) = listener.accept().await.expect("accept");
+        let mut received = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut buf).await.expect("read");
+            received.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&received).to_lowercase();
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let length: usize = text.split("content-length: ").nth(1).and_then(|rest| rest.split("\r\n").next()).unwrap().parse().unwrap();
+                if received.len() >= header_end + 4 + length {
+                    break;
+                }
+            }
+        }
+        let reply = r#"{"labels":[{"name":"neutral","score":0.97},{"name":"porn","score":0.91},{"name":"sexy","score":0.4}]}"#;
+        socket.write_all(format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}", reply.len(), reply).as_bytes()).await.expect("reply");
+        String::from_utf8_lossy(&received).to_string()
+    });
+    let verdict = onnx.moderate(b"jpeg bytes").await.expect("classify");
+    assert_eq!(verdict, ModerationVerdict { status: ModerationStatus::Rejected, labels: vec!["porn".to_string()] });
+    let received = server.await.expect("server");
+    assert!(received.starts_with("POST /classify HTTP/1.1"), "{}", received);
+    assert!(received.to_lowercase().contains("content-type: image/jpeg") && received.ends_with("jpeg bytes"));
+
+    let gradient = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(64, 48, |x, y| image::Rgb([(x * 4) as u8, (y * 5) as u8, 90])));
+    let mut png = std::io::Cursor::new(Vec::new());
+    gradient.write_to(&mut png, image::ImageOutputFormat::Png).expect("png");
+    let upload = || {
+        let boundary = "bigpicture-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"gradient.png\"\r\nContent-Type: image/png\r\n\r\n"
+        ).into_bytes();
+        body.extend_from_slice(png.get_ref());
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        TestRequest::post()
+            .uri("/api/images/upload/thumbnail")
+            .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+            .set_payload(body)
+            .to_request()
+    };
+    let local_storage = |state: &mut bigpictureback::AppState, root: &std::path::Path| {
+        state.config.storage_backend = "local".to_string();
+        state.config.upload_dir = root.to_string_lossy().to_string();
+        state.config.min_free_disk_mb = 0;
+        state.storage = storage::from_config(&state.config, &state.s3_service);
+    };
+
+    // 검수기가 없으면 검수 결과 없음, 검수 기록을 남기지 못하면 pending
+    let root = std::env::temp_dir().join(format!("bigpicture-moderation-{}", uuid::Uuid::new_v4().simple()));
+    let mut state = fake_state();
+    local_storage(&mut state, &root);
+    let app = test::init_service(build_app(state.clone())).await;
+    let (status, body) = read_json(test::call_service(&app, upload()).await).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(body["moderation"].is_null());
+    state.image_hooks = ImageHooks::default().with_moderation(Moderation::new(Arc::new(FakeModerator(vec!["Explicit Nudity"]))));
+    let app = test::init_service(build_app(state.clone())).await;
+    let (status, body) = read_json(test::call_service(&app, upload()).await).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["moderation"], "pending");
+    std::fs::remove_dir_all(&root).unwrap();
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let root = std::env::temp_dir().join(format!("bigpicture-moderation-{}", uuid::Uuid::new_v4().simple()));
+    let mut state = test_db.state.clone();
+    local_storage(&mut state, &root);
+    state.image_hooks = ImageHooks::default().with_moderation(Moderation::new(Arc::new(FakeModerator(vec!["Explicit Nudity"]))));
+    let db = state.database.clone();
+    let app = test::init_service(build_app(state.clone())).await;
+    let (status, body) = read_json(test::call_service(&app, upload()).await).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["moderation"], "rejected");
+    let image_url = body["s3_url"].as_str().unwrap().to_string();
+    let thumbnail_url = body["thumbnail_url"].as_str().unwrap().to_string();
+    let image_key = image_cleanup::storage_key(&image_url).unwrap();
+    assert_eq!(db.get_image_moderation_status(&image_key).await.unwrap().as_deref(), Some("rejected"));
+    assert_eq!(db.get_image_moderation_status(&image_cleanup::storage_key(&thumbnail_url).unwrap()).await.unwrap().as_deref(), Some("rejected"));
+
+    // 거절된 이미지는 마커에 붙여도 내려간 상태, 마커 썸네일은 비움
+    sqlx::Executor::execute(&db.pool, "INSERT INTO bigpicture.members (email, nickname) VALUES ('author@example.invalid', 'author')").await.expect("seed");
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&db.pool).await.unwrap();
+    let marker_id: i64 = sqlx::query_scalar(
+        "INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, author, thumbnail_img) VALUES ($1, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', '검수', 'author', $2) RETURNING id"
+    ).bind(author).bind(&image_url).fetch_one(&db.pool).await.unwrap();
+    let thumbnail: Option<String> = sqlx::query_scalar("SELECT thumbnail_img FROM bigpicture.markers WHERE id = $1").bind(marker_id).fetch_one(&db.pool).await.unwrap();
+    assert_eq!(thumbnail, None);
+    for url in [&image_url, "/markers/pending.webp"] {
+        sqlx::query("INSERT INTO bigpicture.marker_images (marker_id, image_type, image_url, is_primary) VALUES ($1, 'detail', $2, false)")
+            .bind(marker_id).bind(url).execute(&db.pool).await.unwrap();
+    }
+    let visible = db.get_marker_images(marker_id).await.unwrap();
+    assert_eq!(visible.iter().map(|image| image.image_url.as_str()).collect::<Vec<_>>(), ["/markers/pending.webp"]);
+
+    // 재시도에서 나중에 거절되면 이미 붙은 이미지도 내림
+    db.save_image_moderation_pending("s3:markers/pending.webp", &[], "fake").await.unwrap();
+    db.record_image_moderation("s3:markers/pending.webp", "rejected", &["Violence".to_string()]).await.unwrap();
+    assert!(db.get_marker_images(marker_id).await.unwrap().is_empty());
+
+    std::fs::remove_dir_all(&root).unwrap();
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn downloaded_originals_are_watermarked_unless_disabled() {
+    // 빨간 10x10 오버레이를 이미지 너비의 20%로 키워 오른쪽 아래(여백 3%)에 찍음
+    let watermark = Watermark::overlay(image::RgbaImage::from_pixel(10, 10, image::Rgba([255, 0, 0, 255])), 1.0);
+    let mut canvas = image::RgbaImage::from_pixel(100, 100, image::Rgba([255, 255, 255, 255]));
+    watermark.apply(&mut canvas);
+    assert_eq!(canvas.get_pixel(87, 87), &image::Rgba([255, 0, 0, 255]));
+    assert_eq!(canvas.get_pixel(10, 10), &image::Rgba([255, 255, 255, 255]));
+    assert_eq!(canvas.get_pixel(98, 98), &image::Rgba([255, 255, 255, 255]));
+    let mut faded = image::RgbaImage::from_pixel(100, 100, image::Rgba([255, 255, 255, 255]));
+    Watermark::overlay(image::RgbaImage::from_pixel(10, 10, image::Rgba([255, 0, 0, 255])), 0.5).apply(&mut faded);
+    assert_eq!(faded.get_pixel(87, 87), &image::Rgba([255, 128, 128, 255]));
+
+    assert!(watermark.applies_to(&WatermarkQuery { watermark: None }));
+    assert!(!watermark.applies_to(&WatermarkQuery { watermark: Some(false) }));
+    assert!(!Watermark::default().applies_to(&WatermarkQuery { watermark: Some(true) }));
+    let key = watermark.cache_key("cards/marker_1_abcd.png");
+    assert!(key.starts_with("cards/marker_1_abcd_wm") && key.ends_with(".png"), "{}", key);
+    assert_ne!(key, Watermark::overlay(image::RgbaImage::new(10, 10), 1.0).cache_key("cards/marker_1_abcd.png"));
+
+    // 같은 형식으로 다시 인코딩
+    let white = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(100, 100, image::Rgb([255, 255, 255])));
+    let mut png = std::io::Cursor::new(Vec::new());
+    white.write_to(&mut png, image::ImageOutputFormat::Png).expect("png");
+    let mut jpeg = std::io::Cursor::new(Vec::new());
+    white.write_to(&mut jpeg, image::ImageOutputFormat::Jpeg(90)).expect("jpeg");
+    let (marked, content_type) = watermark.apply_to_bytes(jpeg.get_ref()).unwrap();
+    assert_eq!((image::guess_format(&marked).unwrap(), content_type), (image::ImageFormat::Jpeg, "image/jpeg"));
+    assert!(watermark.apply_to_bytes(b"not an image").is_err());
+
+    let root = std::env::temp_dir().join(format!("bigpicture-watermark-{}", uuid::Uuid::new_v4().simple()));
+    let mut state = fake_state();
+    state.config.storage_backend = "local".to_string();
+    state.config.upload_dir = root.to_string_lossy().to_string();
+    state.config.min_free_disk_mb = 0;
+    state.storage = storage::from_config(&state.config, &state.s3_service);
+    state.watermark = watermark;
+    std::fs::create_dir_all(root.join("thumbnail_original")).unwrap();
+    std::fs::write(root.join("thumbnail_original").join("legacy.png"), png.get_ref()).unwrap();
+    let app = test::init_service(build_app(state)).await;
+
+    let boundary = "bigpicture-boundary";
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"white.png\"\r\nContent-Type: image/png\r\n\r\n"
+    ).into_bytes();
+    body.extend_from_slice(png.get_ref());
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    let request = TestRequest::post()
+        .uri("/api/images/upload/thumbnail")
+        .insert_header(("content-type", format!("multipart/form-data; boundary={}", boundary)))
+        .set_payload(body)
+        .to_request();
+    let (status, body) = read_json(test::call_service(&app, request).await).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let original_path = format!("/api/{}", body["original_url"].as_str().unwrap().split_once("/api/").unwrap().1);
+    let processed_path = format!("/api/{}", body["s3_url"].as_str().unwrap().split_once("/api/").unwrap().1);
+
+    let download = |uri: String| {
+        let app = &app;
+        async move {
+            let response = test::call_service(app, get(&uri).to_request()).await;
+            assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+            let content_type = response.headers().get("content-type").unwrap().to_str().unwrap().to_string();
+            (content_type, test::read_body(response).await.to_vec())
+        }
+    };
+    for uri in [original_path.clone(), "/api/images/download/original/legacy.png".to_string()] {
+        let (content_type, data) = download(uri).await;
+        assert_eq!(content_type, "image/png");
+        let image = image::load_from_memory(&data).unwrap().to_rgba8();
+        assert_eq!(image.get_pixel(87, 87), &image::Rgba([255, 0, 0, 255]));
+    }
+    let (_, data) = download(format!("{}?watermark=false", original_path)).await;
+    assert_eq!(data, png.get_ref().as_slice());
+    // 변환된 이미지(마커 사진)는 원본이 아니라 그대로
+    let (content_type, _) = download(processed_path).await;
+    assert_eq!(content_type, "image/webp");
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[actix_web::test]
+async fn marker_socket_streams_public_changes_inside_subscribed_bounds() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let upgrade = |uri: &str| get(uri)
+        .insert_header(("Connection", "Upgrade"))
+        .insert_header(("Upgrade", "websocket"))
+        .insert_header(("Sec-WebSocket-Version", "13"))
+        .insert_header(("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="));
+    assert_eq!(test::call_service(&app, upgrade("/api/ws").to_request()).await.status(), StatusCode::SWITCHING_PROTOCOLS);
+    assert_eq!(test::call_service(&app, upgrade("/api/ws?lat=37.5&lng=127&lat_delta=0.1&lng_delta=0.1").to_request()).await.status(), StatusCode::SWITCHING_PROTOCOLS);
+    assert_eq!(test::call_service(&app, upgrade("/api/ws?lat=37.5&lng=127").to_request()).await.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(test::call_service(&app, upgrade("/api/ws?lat=95&lng=127&lat_delta=0.1&lng_delta=0.1").to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    // 날짜변경선을 넘는 영역, 다른 영역으로 옮겨간 마커는 이전 영역에도 전달
+    let pacific = Bounds::new(0.0, 179.0, 10.0, 10.0).unwrap();
+    assert!(pacific.contains(3.0, -177.0) && pacific.contains(-4.0, 175.0) && !pacific.contains(0.0, 170.0));
+    let moved = MarkerEvent {
+        kind: MarkerEventKind::Updated,
+        marker_id: 1,
+        latitude: 35.1,
+        longitude: 129.0,
+        previous: Some((37.5, 127.0)),
+        data: None,
+    };
+    let seoul = Bounds::new(37.5, 127.0, 0.2, 0.2).unwrap();
+    assert!(moved.is_visible_in(&seoul) && !MarkerEvent { previous: None, ..moved.clone() }.is_visible_in(&seoul));
+    assert_eq!(moved.to_json()["type"], "marker.updated");
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, "INSERT INTO bigpicture.members (email, nickname) VALUES ('live@example.invalid', 'live')")
+        .await.expect("seed");
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let mut events = state.marker_events.subscribe();
+
+    let create = |sharing: &str| as_member(post_json("/api/markers", &json!({
+        "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": "라이브", "sharing_option": sharing
+    })), author, &state);
+    let (_, body) = read_json(test::call_service(&app, create("private").to_request()).await).await;
+    assert!(body["data"]["id"].as_i64().is_some());
+    let (_, body) = read_json(test::call_service(&app, create("public").to_request()).await).await;
+    let marker_id = body["data"]["id"].as_i64().unwrap();
+    let request = as_member(TestRequest::patch().uri(&format!("/api/markers/{}", marker_id)).set_json(json!({ "latitude": 35.1, "longitude": 129.0, "version": 1 })), author, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+
+    // 비공개 마커는 보내지 않음
+    let created = events.try_recv().expect("created");
+    assert_eq!((created.kind, created.marker_id), (MarkerEventKind::Created, marker_id));
+    assert_eq!(created.data.as_ref().unwrap()["description"], "라이브");
+    let updated = events.try_recv().expect("updated");
+    assert_eq!((updated.kind, updated.previous), (MarkerEventKind::Updated, Some((37.5, 127.0))));
+    assert!(updated.is_visible_in(&seoul));
+    assert!(events.try_recv().is_err());
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn marker_updates_require_last_seen_version_and_reject_stale_edits() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let patch = |body: serde_json::Value| TestRequest::patch().uri("/api/markers/1").set_json(body);
+    let request = as_member(patch(json!({ "description": "버전 없음" })), 1, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::PRECONDITION_REQUIRED);
+    for if_match in ["\"v\"", "abc"] {
+        let request = as_member(patch(json!({ "description": "형식 오류" })).insert_header(("If-Match", if_match)), 1, &state);
+        assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST, "{}", if_match);
+    }
+    let request = as_member(patch(json!({ "description": "불일치", "version": 2 })).insert_header(("If-Match", "\"v1\"")), 1, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, "INSERT INTO bigpicture.members (email, nickname) VALUES ('version@example.invalid', 'version')")
+        .await.expect("seed");
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let request = as_member(post_json("/api/markers", &json!({
+        "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": "처음"
+    })), author, &state);
+    let (_, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    let marker_id = body["data"]["id"].as_i64().unwrap();
+    let uri = format!("/api/markers/{}", marker_id);
+
+    let response = test::call_service(&app, as_member(get(&uri), author, &state).to_request()).await;
+    assert_eq!(response.headers().get("ETag").unwrap(), "\"v1\"");
+
+    // 두 기기가 같은 버전을 보고 수정: 먼저 온 수정만 반영되고 나중 수정은 409와 최신 마커
+    let edit = |description: &str| as_member(TestRequest::patch().uri(&uri).set_json(json!({ "description": description })).insert_header(("If-Match", "W/\"v1\"")), author, &state);
+    let response = test::call_service(&app, edit("휴대폰에서 수정").to_request()).await;
+    assert_eq!(response.headers().get("ETag").unwrap(), "\"v2\"");
+    let (status, body) = read_json(response).await;
+    assert_eq!((status, body["data"]["version"].as_i64()), (StatusCode::OK, Some(2)));
+    let response = test::call_service(&app, edit("태블릿에서 수정").to_request()).await;
+    assert_eq!(response.headers().get("ETag").unwrap(), "\"v2\"");
+    let (status, body) = read_json(response).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["errorCode"], "VERSION_CONFLICT");
+    assert_eq!((body["error"]["current"]["version"].as_i64(), body["error"]["current"]["description"].as_str()), (Some(2), Some("휴대폰에서 수정")));
+
+    let request = as_member(TestRequest::patch().uri(&uri).set_json(json!({ "description": "다시 수정", "version": 2 })), author, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+    let marker = state.database.get_marker_detail(marker_id).await.unwrap().unwrap();
+    assert_eq!((marker.version, marker.description.as_deref()), (Some(3), Some("다시 수정")));
+
+    test_db.drop_database().await;
+}
+
+struct FakePlaces(Vec<Place>);
+
+impl PlaceSearch for FakePlaces {
+    fn nearby<'a>(&'a self, _latitude: f64, _longitude: f64, _radius_m: f64) -> futures_util::future::BoxFuture<'a, anyhow::Result<Vec<Place>>> {
+        Box::pin(async move { Ok(self.0.clone()) })
+    }
+}
+
+fn fake_place(id: &str, name: &str, latitude: f64, longitude: f64) -> Place {
+    Place {
+        id: id.to_string(),
+        name: name.to_string(),
+        category: "관광명소".to_string(),
+        address: None,
+        latitude,
+        longitude,
+        distance_m: location_suggest::distance_m(37.5000, 127.0000, latitude, longitude),
+    }
+}
+
+#[actix_web::test]
+async fn marker_location_suggestions_snap_to_places_and_popular_spots() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    assert_eq!(test::call_service(&app, get("/api/markers/suggest-location?lat=37.5&lng=127").to_request()).await.status(), StatusCode::UNAUTHORIZED);
+    let invalid = as_member(get("/api/markers/suggest-location?lat=91&lng=127"), 1, &state);
+    assert_eq!(test::call_service(&app, invalid.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    // 반경 밖/중복 장소는 빼고 가까운 순
+    let places = Places::new(Arc::new(FakePlaces(vec![
+        fake_place("far", "먼 카페", 37.5100, 127.0000),
+        fake_place("gate", "정문", 37.5001, 127.0001),
+        fake_place("tower", "전망대", 37.5005, 127.0000),
+        fake_place("gate", "정문", 37.5001, 127.0001),
+    ])));
+    let found = places.nearby(37.5, 127.0, 300.0).await;
+    assert_eq!(found.iter().map(|place| place.id.as_str()).collect::<Vec<_>>(), ["gate", "tower"]);
+    assert!(Places::default().nearby(37.5, 127.0, 300.0).await.is_empty());
+
+    assert_eq!(location_suggest::snap_radius_m(None, 30.0), 30.0);
+    assert_eq!(location_suggest::snap_radius_m(Some(500.0), 30.0), 100.0);
+    assert_eq!(location_suggest::snap_radius_m(Some(3.0), 30.0), 10.0);
+    let snapped = location_suggest::snap_location(37.5, 127.0, &found, &[], 30.0);
+    assert_eq!((snapped.source, snapped.name.as_deref()), ("place", Some("정문")));
+    let unsnapped = location_suggest::snap_location(37.5, 127.0, &found, &[], 10.0);
+    assert_eq!((unsnapped.source, unsnapped.latitude, unsnapped.distance_m), ("none", 37.5, 0.0));
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let mut state = test_db.state.clone();
+    state.places = Places::new(Arc::new(FakePlaces(vec![fake_place("tower", "전망대", 37.5005, 127.0000)])));
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('gps@example.invalid', 'gps');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option, likes, address_neighborhood)
+        SELECT m.id, ST_SetSRID(ST_MakePoint(127.0 + v.dx, 37.5 + v.dy), 4326)::geography, 'happy', v.description, v.sharing, v.likes, '역삼동'
+        FROM bigpicture.members m, (VALUES
+            (0.00010, 0.00010, '벤치 1', 'public', 3),
+            (0.00012, 0.00008, '벤치 2', 'public', 1),
+            (0.00009, 0.00011, '벤치 3', 'public', 0),
+            (0.00011, 0.00009, '비밀 벤치', 'private', 0),
+            (0.01000, 0.01000, '너무 먼 곳', 'public', 9)
+        ) AS v(dx, dy, description, sharing, likes);
+    "#).await.expect("seed");
+    let member_id: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    // 전망대는 55m 떨어져 기본 반경(30m) 밖 → 마커 3개가 모인 지점으로 보정
+    let request = as_member(get("/api/markers/suggest-location?lat=37.5&lng=127"), member_id, &state);
+    let (status, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let data = &body["data"];
+    assert_eq!(data["nearbyMarkers"].as_array().unwrap().len(), 3);
+    assert_eq!(data["popularSpots"][0]["markerCount"], 3);
+    assert_eq!(data["popularSpots"][0]["neighborhood"], "역삼동");
+    assert_eq!(data["snapped"]["source"], "popular_spot");
+    assert_eq!(data["places"][0]["name"], "전망대");
+
+    // GPS 정확도가 낮으면 더 멀리 있는 장소로도 보정
+    let request = as_member(get("/api/markers/suggest-location?lat=37.5&lng=127&accuracy=80"), member_id, &state);
+    let (_, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!((body["data"]["snapped"]["source"].as_str(), body["data"]["snapped"]["name"].as_str()), (Some("place"), Some("전망대")));
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn cluster_resolution_follows_client_zoom_through_configured_table() {
+    let table = ZoomResolutions::default();
+    assert_eq!([10, 13, 14, 15, 16, 20].map(|zoom| table.for_zoom(zoom)), [4, 4, 5, 8, 9, 9]);
+    // 줌이 있으면 영역 크기와 상관없이 표를 따르고, 없으면 영역 크기로 추정
+    assert_eq!(table.resolve(Some(14), 0.001, 0.001), 5);
+    assert_eq!(table.resolve(None, 3.0, 0.2), 3);
+    assert_eq!(table.resolve(None, 0.05, 0.02), 8);
+
+    let custom = ZoomResolutions::parse("12:6, 5:2").expect("table");
+    assert_eq!([0, 5, 11, 12, 18].map(|zoom| custom.for_zoom(zoom)), [2, 2, 2, 6, 6]);
+    for invalid in ["", "14", "14:16", "23:5", "14:5,14:6"] {
+        assert!(ZoomResolutions::parse(invalid).is_err(), "{}", invalid);
+    }
+
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let request = get("/api/markers/cluster?lat=37.5&lng=127&lat_delta=0.1&lng_delta=0.1&zoom=30");
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let mut state = test_db.state.clone();
+    state.config.cluster_zoom_resolutions = "0:3,15:7".to_string();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('zoom@example.invalid', 'zoom');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, sharing_option)
+        SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', 'public' FROM bigpicture.members;
+    "#).await.expect("seed");
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let (status, body) = read_json(test::call_service(&app, get("/api/markers/cluster?lat=37.5&lng=127&lat_delta=0.2&lng_delta=0.2&zoom=15").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!((body["zoom"].as_i64(), body["resolution"].as_u64()), (Some(15), Some(7)));
+    assert!(body["data"][0]["h3_index"].is_string());
+
+    let (_, body) = read_json(test::call_service(&app, get("/api/markers/cluster?lat=37.5&lng=127&lat_delta=0.2&lng_delta=0.2").to_request()).await).await;
+    assert_eq!((body["zoom"].is_null(), body["resolution"].as_u64()), (true, Some(5)));
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn nearby_markers_are_ordered_by_distance_within_radius() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    for invalid in ["lat=91&lng=127", "lat=37.5&lng=127&radius_m=0", "lat=37.5&lng=127&radius_m=50000"] {
+        let request = get(&format!("/api/markers/nearby?{}", invalid));
+        assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST, "{}", invalid);
+    }
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('near@example.invalid', 'near');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option)
+        SELECT m.id, ST_SetSRID(ST_MakePoint(127.0 + v.dx, 37.5), 4326)::geography, v.tag, v.description, v.sharing
+        FROM bigpicture.members m, (VALUES
+            (0.0050, 'happy', '450m', 'public'),
+            (0.0010, 'sad', '90m', 'public'),
+            (0.0030, 'happy', '260m', 'public'),
+            (0.0005, 'happy', '내 비밀', 'private'),
+            (0.0200, 'happy', '1.8km', 'public')
+        ) AS v(dx, tag, description, sharing);
+    "#).await.expect("seed");
+    let member_id: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let (status, body) = read_json(test::call_service(&app, get("/api/markers/nearby?lat=37.5&lng=127").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let descriptions: Vec<&str> = body["data"].as_array().unwrap().iter().map(|marker| marker["description"].as_str().unwrap()).collect();
+    assert_eq!(descriptions, ["90m", "260m", "450m"]);
+    let distance = body["data"][0]["distanceM"].as_f64().unwrap();
+    assert!((80.0..100.0).contains(&distance), "{}", distance);
+
+    let request = get("/api/markers/nearby?lat=37.5&lng=127&radius_m=300&emotion_tags=happy");
+    let (_, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!((body["count"].as_u64(), body["data"][0]["description"].as_str()), (Some(1), Some("260m")));
+
+    // 로그인하면 내 비공개 마커도 보임
+    let request = as_member(get("/api/markers/nearby?lat=37.5&lng=127&limit=2"), member_id, &state);
+    let (_, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!(body["data"][0]["description"], "내 비밀");
+    assert_eq!(body["data"][0]["isMine"], true);
+    assert_eq!(body["count"], 2);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn public_emotion_tiles_aggregate_cells_and_count_against_key_quota() {
+    assert!(TileCoord::new(15, 0, 0).is_err());
+    assert!(TileCoord::new(1, 2, 0).is_err());
+    let (lng_min, lat_min, lng_max, lat_max) = TileCoord::new(1, 1, 0).unwrap().envelope();
+    assert_eq!((lng_min, lng_max), (0.0, 180.0));
+    assert!(lat_min.abs() < 1e-9 && (lat_max - 85.0511).abs() < 1e-4);
+    let policies = CachePolicies::from_config(&test_config());
+    assert_eq!(
+        policies.for_route("/api/public/emotion-tiles/{z}/{x}/{y}.json").and_then(|policy| policy.header_value()).as_deref(),
+        Some("public, max-age=3600, stale-while-revalidate=86400")
+    );
+
+    let app = test::init_service(build_app(fake_state())).await;
+    let response = test::call_service(&app, get("/api/public/emotion-tiles/10/873/396.json").to_request()).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    // Retry-After는 주입한 시계 기준 (자정 1분 전)
+    let state = deterministic(test_db.state.clone(), Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2025, 5, 1, 23, 59, 0).unwrap())));
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('press@example.invalid', 'press');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option)
+        SELECT m.id, ST_SetSRID(ST_MakePoint(v.lng, 37.5), 4326)::geography, v.tag, v.description, v.sharing
+        FROM bigpicture.members m, (VALUES
+            (127.0000, 'happy', '비밀 일기 1', 'public'),
+            (127.0001, 'happy,sad', '비밀 일기 2', 'public'),
+            (127.0002, 'sad', '비밀 일기 3', 'public'),
+            (127.0003, 'angry', '비공개', 'private'),
+            (127.0500, 'angry', '혼자 있는 마커', 'public')
+        ) AS v(lng, tag, description, sharing);
+    "#).await.expect("seed");
+    let member_id: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&state.database.pool).await.unwrap();
+    state.database.create_api_key("bpk_tiles", "press", "public_read", 3, member_id).await.expect("api key");
+    let app = test::init_service(build_app(state.clone())).await;
+
+    // 임베드는 헤더 대신 쿼리 파라미터로 키 전달
+    let response = test::call_service(&app, get("/api/public/emotion-tiles/10/873/396.json?api_key=bpk_tiles").to_request()).await;
+    assert_eq!(response.headers().get("cache-control").unwrap(), "public, max-age=3600, stale-while-revalidate=86400");
+    assert_eq!(response.headers().get("x-ratelimit-remaining").unwrap(), "2");
+    let (status, body) = read_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let cells = body["data"]["cells"].as_array().unwrap();
+    // 마커 1개짜리 칸은 빠지고, 비공개 마커는 세지 않음
+    assert_eq!(cells.len(), 1);
+    assert_eq!((cells[0]["cellX"].as_i64(), cells[0]["cellY"].as_i64()), (Some(1), Some(6)));
+    assert_eq!(cells[0]["markerCount"], 3);
+    assert_eq!(cells[0]["emotions"][0]["count"], 2);
+    assert_eq!(cells[0]["emotions"].as_array().unwrap().len(), 2);
+    assert!(!body.to_string().contains("비밀 일기"));
+
+    let request = get("/api/public/emotion-tiles/15/0/0.json").insert_header(("X-API-Key", "bpk_tiles"));
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+    let request = || get("/api/public/emotion-tiles/10/873/396.json").insert_header(("X-API-Key", "bpk_tiles"));
+    assert_eq!(test::call_service(&app, request().to_request()).await.status(), StatusCode::OK);
+    let rejected = test::call_service(&app, request().to_request()).await;
+    assert_eq!(rejected.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(rejected.headers().get("Retry-After").unwrap(), "60");
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn duplicate_registrations_return_conflict_codes() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let register = || post_json("/api/members", &json!({ "email": "dup@example.invalid", "nickname": "dup" }));
+    assert_eq!(test::call_service(&app, register().to_request()).await.status(), StatusCode::OK);
+    let (status, body) = read_json(test::call_service(&app, register().to_request()).await).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["errorCode"], "EMAIL_TAKEN");
+
+    // 사전 확인을 지나친 동시 가입도 저장소 계층에서 구분
+    let error = state.database.create_social_member("dup@example.invalid", "dup2", "kakao", "k-1", None, None, None, None, None, None)
+        .await.expect_err("duplicate email");
+    assert_eq!(error.downcast_ref::<RegistrationConflict>(), Some(&RegistrationConflict::EmailTaken));
+
+    // 같은 이메일 계정에 같은 종류의 다른 소셜 계정을 연결하려고 하면 409
+    let social = |provider_id: &str| post_json("/api/auth/register", &json!({
+        "email": "dup@example.invalid",
+        "nickname": "dup",
+        "provider_type": "kakao",
+        "provider_id": provider_id
+    }));
+    let (status, body) = read_json(test::call_service(&app, social("k-1").to_request()).await).await;
+    assert_eq!((status, body["data"]["isNewUser"].as_bool()), (StatusCode::OK, Some(false)));
+    let (status, body) = read_json(test::call_service(&app, social("k-2").to_request()).await).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["errorCode"], "PROVIDER_ALREADY_LINKED");
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn low_zoom_clusters_are_aggregated_in_the_database() {
+    // 격자는 H3 셀 변보다 충분히 작게
+    assert!(cluster_zoom::aggregate_grid_degrees(4) < 22.61 / 111.32 / 2.0);
+    assert!(cluster_zoom::aggregate_grid_degrees(3) > cluster_zoom::aggregate_grid_degrees(5));
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    // 기본 LIMIT(1000)보다 많은 마커
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('grid@example.invalid', 'grid');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, sharing_option)
+        SELECT m.id, ST_SetSRID(ST_MakePoint(126.8 + (n % 40) * 0.01, 37.4 + (n / 40) * 0.01), 4326)::geography, 'happy', 'public'
+        FROM bigpicture.members m, generate_series(0, 1199) AS n;
+    "#).await.expect("seed");
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let request = get("/api/markers/cluster?lat=37.55&lng=127.0&lat_delta=1.0&lng_delta=1.0&zoom=10");
+    let (status, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!((body["resolution"].as_u64(), body["aggregated"].as_bool()), (Some(4), Some(true)));
+    let clusters = body["data"].as_array().unwrap();
+    assert_eq!(clusters.iter().map(|cluster| cluster["count"].as_i64().unwrap()).sum::<i64>(), 1200);
+    assert!(clusters.iter().all(|cluster| cluster["h3_index"].is_string() && cluster.get("markers").is_none()));
+
+    // 확대하면 기존처럼 마커 목록을 담아 Rust에서 묶음
+    let request = get("/api/markers/cluster?lat=37.45&lng=126.85&lat_delta=0.05&lng_delta=0.05&zoom=15");
+    let (_, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!(body["aggregated"], false);
+    assert!(body["data"][0]["markers"].is_array());
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn emotion_heatmap_counts_public_markers_per_grid_point() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let area = "lat=37.5&lng=127.0&lat_delta=0.1&lng_delta=0.1";
+    for invalid in ["lat=37.5&lng=127.0&lat_delta=0&lng_delta=0.1", "zoom=30", "from=yesterday", "from=2024-06-01&to=2024-05-01"] {
+        let request = get(&format!("/api/markers/heatmap?{}&{}", area, invalid));
+        assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST, "{}", invalid);
+    }
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('heat@example.invalid', 'heat');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, sharing_option, created_at)
+        SELECT m.id, ST_SetSRID(ST_MakePoint(v.lng, v.lat), 4326)::geography, v.tag, v.sharing, v.created_at::timestamptz
+        FROM bigpicture.members m, (VALUES
+            (127.0001, 37.5001, 'happy', 'public', '2024-05-10'),
+            (127.0002, 37.5002, 'happy,sad', 'public', '2024-06-10'),
+            (127.0003, 37.5001, 'sad', 'public', '2024-06-11'),
+            (127.0002, 37.5003, 'happy', 'private', '2024-06-12'),
+            (127.0300, 37.5300, 'happy', 'public', '2024-06-13')
+        ) AS v(lng, lat, tag, sharing, created_at);
+    "#).await.expect("seed");
+    let app = test::init_service(build_app(state.clone())).await;
+    let heatmap = |filters: &str| get(&format!("/api/markers/heatmap?{}&zoom=16{}", area, filters)).to_request();
+
+    let (status, body) = read_json(test::call_service(&app, heatmap("")).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let cells: Vec<(i64, f64)> = body["data"].as_array().unwrap().iter()
+        .map(|cell| (cell["count"].as_i64().unwrap(), cell["intensity"].as_f64().unwrap()))
+        .collect();
+    assert_eq!(cells, vec![(3, 1.0), (1, 1.0 / 3.0)]);
+    assert_eq!(body["maxCount"], 3);
+
+    let (_, body) = read_json(test::call_service(&app, heatmap("&emotion_tags=happy&from=2024-06-01&to=2024-06-13")).await).await;
+    assert_eq!(body["data"].as_array().unwrap().iter().map(|cell| cell["count"].as_i64().unwrap()).collect::<Vec<_>>(), vec![1]);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn marker_stats_aggregate_public_markers_by_emotion_period_and_region() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    for invalid in ["interval=month", "from=yesterday", "from=2024-06-01&to=2024-05-01", "from=2020-01-01&to=2024-01-01"] {
+        let request = get(&format!("/api/markers/stats?{}", invalid));
+        assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST, "{}", invalid);
+    }
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('stats@example.invalid', 'stats');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, sharing_option, address_region, address_locality, created_at)
+        SELECT m.id, ST_SetSRID(ST_MakePoint(127.0, 37.5), 4326)::geography, v.tag, v.sharing, v.region, v.locality, v.created_at::timestamptz
+        FROM bigpicture.members m, (VALUES
+            ('happy', 'public', '서울특별시', '종로구', '2024-06-03T01:00:00Z'),
+            ('happy,sad', 'public', '서울특별시', '종로구', '2024-06-03T20:00:00Z'),
+            ('sad', 'public', '부산광역시', '해운대구', '2024-06-05T03:00:00Z'),
+            ('happy', 'private', '부산광역시', '해운대구', '2024-06-05T04:00:00Z'),
+            ('calm', 'public', NULL, NULL, '2024-06-12T03:00:00Z')
+        ) AS v(tag, sharing, region, locality, created_at);
+    "#).await.expect("seed");
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let (status, body) = read_json(test::call_service(&app, get("/api/markers/stats?from=2024-06-02T15:00:00Z&to=2024-06-06T15:00:00Z").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["totalMarkers"], 3);
+    let emotions: Vec<(&str, i64)> = body["data"]["byEmotion"].as_array().unwrap().iter()
+        .map(|row| (row["emotionTag"].as_str().unwrap(), row["count"].as_i64().unwrap()))
+        .collect();
+    assert_eq!(emotions, vec![("happy", 2), ("sad", 2)]);
+    // 한국 날짜 기준, 마커가 없는 날도 0으로
+    let timeline: Vec<(&str, i64)> = body["data"]["timeline"].as_array().unwrap().iter()
+        .map(|row| (row["period"].as_str().unwrap(), row["count"].as_i64().unwrap()))
+        .collect();
+    assert_eq!(timeline, vec![("2024-06-03", 1), ("2024-06-04", 1), ("2024-06-05", 1), ("2024-06-06", 0)]);
+    let regions: Vec<(&str, i64)> = body["data"]["topRegions"].as_array().unwrap().iter()
+        .map(|row| (row["locality"].as_str().unwrap(), row["count"].as_i64().unwrap()))
+        .collect();
+    assert_eq!(regions, vec![("종로구", 2), ("해운대구", 1)]);
+
+    let (_, body) = read_json(test::call_service(&app, get("/api/markers/stats?from=2024-06-01&to=2024-06-15&interval=week&region_limit=1").to_request()).await).await;
+    let timeline: Vec<(&str, i64)> = body["data"]["timeline"].as_array().unwrap().iter()
+        .map(|row| (row["period"].as_str().unwrap(), row["count"].as_i64().unwrap()))
+        .collect();
+    assert_eq!(timeline, vec![("2024-05-27", 0), ("2024-06-03", 3), ("2024-06-10", 1)]);
+    assert_eq!(body["data"]["topRegions"].as_array().unwrap().len(), 1);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn markers_feed_and_clusters_filter_by_created_time_range() {
+    // 2024-06-05(수) 10:00 KST
+    let now = Utc.with_ymd_and_hms(2024, 6, 5, 1, 0, 0).unwrap();
+    let range = |after: Option<&str>, before: Option<&str>, period: Option<&str>| {
+        CreatedTimeFilter::default().with_created_range(after, before, period, now)
+    };
+    let kst_midnight = |day: u32| Utc.with_ymd_and_hms(2024, 6, day, 0, 0, 0).unwrap() - Duration::hours(9);
+    assert_eq!(range(None, None, Some("today")).unwrap().created_after, Some(kst_midnight(5)));
+    assert_eq!(range(None, None, Some("week")).unwrap().created_after, Some(kst_midnight(3)));
+    assert_eq!(range(None, None, Some("month")).unwrap().created_after, Some(kst_midnight(1)));
+    assert_eq!(range(Some("2024-06-04T12:00:00Z"), None, Some("week")).unwrap().created_after, Some(Utc.with_ymd_and_hms(2024, 6, 4, 12, 0, 0).unwrap()));
+    assert!(range(None, None, Some("year")).is_err());
+    assert!(range(Some("2024-06-05"), Some("2024-06-01"), None).is_err());
+
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let area = "lat=37.5&lng=127.0&lat_delta=0.1&lng_delta=0.1";
+    for path in ["/api/markers", "/api/markers/cluster"] {
+        for invalid in ["period=decade", "created_after=yesterday", "created_after=2024-06-05&created_before=2024-06-01"] {
+            let request = get(&format!("{}?{}&{}", path, area, invalid));
+            assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST, "{} {}", path, invalid);
+        }
+    }
+    let request = get("/api/markers/feed?period=decade");
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('range@example.invalid', 'range');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, sharing_option, created_at)
+        SELECT m.id, ST_SetSRID(ST_MakePoint(127.0 + v.n * 0.001, 37.5), 4326)::geography, 'happy', 'public', v.created_at::timestamptz
+        FROM bigpicture.members m, (VALUES
+            (1, '2024-05-20T00:00:00Z'),
+            (2, '2024-06-01T00:00:00Z'),
+            (3, '2024-06-03T00:00:00Z'),
+            (4, '2024-06-04T00:00:00Z')
+        ) AS v(n, created_at);
+    "#).await.expect("seed");
+    let app = test::init_service(build_app(state.clone())).await;
+    let range_query = "created_after=2024-06-01&created_before=2024-06-04";
+
+    let (status, body) = read_json(test::call_service(&app, get(&format!("/api/markers?{}&{}", area, range_query)).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().unwrap().len(), 2);
+    let (_, body) = read_json(test::call_service(&app, get(&format!("/api/markers/feed?{}", range_query)).to_request()).await).await;
+    assert_eq!(body["pagination"]["totalCount"], 2);
+    let (_, body) = read_json(test::call_service(&app, get(&format!("/api/markers/cluster?{}&zoom=16&{}", area, range_query)).to_request()).await).await;
+    let total: i64 = body["data"].as_array().unwrap().iter().map(|cluster| cluster["count"].as_i64().unwrap()).sum();
+    assert_eq!(total, 2);
+    // 다른 기간은 캐시를 함께 쓰지 않음
+    let (_, body) = read_json(test::call_service(&app, get(&format!("/api/markers/cluster?{}&zoom=16", area)).to_request()).await).await;
+    let total: i64 = body["data"].as_array().unwrap().iter().map(|cluster| cluster["count"].as_i64().unwrap()).sum();
+    assert_eq!(total, 4);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn draft_and_scheduled_markers_stay_out_of_feeds_until_published() {
+    let clock = Arc::new(FixedClock::new(Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()));
+    let state = deterministic(fake_state(), clock.clone());
+    let app = test::init_service(build_app(state.clone())).await;
+    let marker = json!({ "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": "예약" });
+    let with = |extra: serde_json::Value| {
+        let mut body = marker.clone();
+        body.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        body
+    };
+    for invalid in [
+        json!({ "status": "hidden" }),
+        json!({ "status": "scheduled" }),
+        json!({ "status": "scheduled", "publish_at": "2026-03-01T11:00:00Z" }),
+        json!({ "status": "scheduled", "publish_at": "2028-03-01T12:00:00Z" }),
+        json!({ "status": "draft", "publish_at": "2026-03-02T12:00:00Z" }),
+    ] {
+        let request = as_member(post_json("/api/markers", &with(invalid.clone())), 1, &state);
+        assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST, "{}", invalid);
+    }
+    let request = as_member(TestRequest::patch().uri("/api/markers/1").set_json(json!({ "publish_at": "2026-03-02T12:00:00Z" })), 1, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = deterministic(test_db.state.clone(), clock.clone());
+    let pool = state.database.pool.clone();
+    sqlx::Executor::execute(&pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('draft@example.invalid', 'draft');
+    "#).await.expect("seed");
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let create = |extra: serde_json::Value| as_member(post_json("/api/markers", &with(extra)), author, &state);
+
+    let (status, body) = read_json(test::call_service(&app, create(json!({ "status": "draft" })).to_request()).await).await;
+    assert_eq!((status, body["data"]["status"].as_str()), (StatusCode::OK, Some("draft")));
+    let draft_id = body["data"]["id"].as_i64().unwrap();
+    let (status, body) = read_json(test::call_service(&app, create(json!({ "status": "scheduled", "publish_at": "2026-03-01T13:00:00Z" })).to_request()).await).await;
+    assert_eq!((status, body["data"]["status"].as_str()), (StatusCode::OK, Some("scheduled")));
+    let scheduled_id = body["data"]["id"].as_i64().unwrap();
+
+    // 작성자만 보고, 피드/지도/공유 링크에는 나오지 않음
+    let (_, body) = read_json(test::call_service(&app, get("/api/markers/feed").to_request()).await).await;
+    assert_eq!(body["pagination"]["totalCount"], 0);
+    let (_, body) = read_json(test::call_service(&app, get("/api/markers?lat=37.5&lng=127.0&lat_delta=0.1&lng_delta=0.1").to_request()).await).await;
+    assert!(body["data"].as_array().unwrap().is_empty());
+    assert_eq!(test::call_service(&app, get(&format!("/api/markers/{}", draft_id)).to_request()).await.status(), StatusCode::NOT_FOUND);
+    let request = as_member(get(&format!("/api/markers/{}", draft_id)), author, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/markers/unpublished"), author, &state).to_request()).await).await;
+    assert_eq!(body["count"], 2);
+
+    // 예약 시각이 지나면 게시 작업이 게시하고, 임시 저장 마커는 작성자가 게시
+    assert_eq!(scheduled_markers::publish_due_markers(&state.database, clock.as_ref(), &state.marker_events, &state.cluster_cache, &state.emotion_profiles).await.unwrap(), 0);
+    clock.advance(Duration::hours(2));
+    assert_eq!(scheduled_markers::publish_due_markers(&state.database, clock.as_ref(), &state.marker_events, &state.cluster_cache, &state.emotion_profiles).await.unwrap(), 1);
+    let published = state.database.get_marker_detail(scheduled_id).await.unwrap().unwrap();
+    assert_eq!((published.status.as_deref(), published.created_at), (Some("published"), Utc.with_ymd_and_hms(2026, 3, 1, 13, 0, 0).unwrap()));
+    let request = as_member(TestRequest::patch().uri(&format!("/api/markers/{}", draft_id)).set_json(json!({ "status": "published", "version": 1 })), author, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+    let (_, body) = read_json(test::call_service(&app, get("/api/markers/feed").to_request()).await).await;
+    assert_eq!(body["pagination"]["totalCount"], 2);
+    let request = as_member(TestRequest::patch().uri(&format!("/api/markers/{}", draft_id)).set_json(json!({ "status": "draft", "version": 2 })), author, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn marker_visibility_limits_followers_and_private_markers_to_allowed_viewers() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let request = as_member(post_json("/api/markers", &json!({
+        "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": "범위", "visibility": "friends-only"
+    })), 1, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+    let request = as_member(TestRequest::patch().uri("/api/markers/1").set_json(json!({ "visibility": "secret" })), 1, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+    let request = as_member(TestRequest::post().uri("/api/members/1/follow"), 1, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let pool = state.database.pool.clone();
+    sqlx::Executor::execute(&pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES
+            ('author@example.invalid', 'author'), ('follower@example.invalid', 'follower'), ('stranger@example.invalid', 'stranger');
+    "#).await.expect("seed");
+    let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM bigpicture.members ORDER BY id").fetch_all(&pool).await.unwrap();
+    let (author, follower, stranger) = (ids[0], ids[1], ids[2]);
+    let app = test::init_service(build_app(state.clone())).await;
+    let request = as_member(TestRequest::post().uri(&format!("/api/members/{}/follow", author)), follower, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+
+    let mut marker_ids = Vec::new();
+    for visibility in ["public", "followers", "private"] {
+        let request = as_member(post_json("/api/markers", &json!({
+            "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": visibility, "visibility": visibility
+        })), author, &state);
+        let (status, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+        assert_eq!((status, body["data"]["visibility"].as_str()), (StatusCode::OK, Some(visibility)));
+        marker_ids.push(body["data"]["id"].as_i64().unwrap());
+    }
+    // 예전 앱의 sharing_option=friends는 followers
+    let request = as_member(post_json("/api/markers", &json!({
+        "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": "예전 앱", "sharing_option": "friends"
+    })), author, &state);
+    let (_, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!(body["data"]["visibility"], "followers");
+
+    let area = "lat=37.5&lng=127.0&lat_delta=0.1&lng_delta=0.1";
+    for (viewer, expected) in [(None, 1), (Some(stranger), 1), (Some(follower), 3), (Some(author), 4)] {
+        let request = |path: &str| match viewer {
+            Some(viewer) => as_member(get(path), viewer, &state),
+            None => get(path),
+        };
+        let (_, body) = read_json(test::call_service(&app, request("/api/markers/feed").to_request()).await).await;
+        assert_eq!(body["pagination"]["totalCount"], expected, "feed {:?}", viewer);
+        let (_, body) = read_json(test::call_service(&app, request(&format!("/api/markers?{}", area)).to_request()).await).await;
+        assert_eq!(body["data"].as_array().unwrap().len(), expected as usize, "markers {:?}", viewer);
+        let (_, body) = read_json(test::call_service(&app, request(&format!("/api/markers/cluster?{}&zoom=16", area)).to_request()).await).await;
+        let total: i64 = body["data"].as_array().unwrap().iter().map(|cluster| cluster["count"].as_i64().unwrap()).sum();
+        assert_eq!(total, expected, "cluster {:?}", viewer);
+    }
+    let detail = |marker_id: i64, viewer: i64| as_member(get(&format!("/api/markers/{}", marker_id)), viewer, &state);
+    assert_eq!(test::call_service(&app, detail(marker_ids[1], stranger).to_request()).await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(test::call_service(&app, detail(marker_ids[1], follower).to_request()).await.status(), StatusCode::OK);
+    assert_eq!(test::call_service(&app, detail(marker_ids[2], follower).to_request()).await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(test::call_service(&app, detail(marker_ids[2], author).to_request()).await.status(), StatusCode::OK);
+
+    // 공개 범위 변경과 팔로우 취소
+    let request = as_member(TestRequest::patch().uri(&format!("/api/markers/{}", marker_ids[2])).set_json(json!({ "visibility": "public", "version": 1 })), author, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+    let (_, body) = read_json(test::call_service(&app, get("/api/markers/feed").to_request()).await).await;
+    assert_eq!(body["pagination"]["totalCount"], 2);
+    let request = as_member(TestRequest::delete().uri(&format!("/api/members/{}/follow", author)), follower, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+    assert_eq!(test::call_service(&app, detail(marker_ids[1], follower).to_request()).await.status(), StatusCode::NOT_FOUND);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn markers_and_clusters_can_be_returned_as_geojson_feature_collections() {
+    assert_eq!(ResponseFormat::parse(None), Ok(ResponseFormat::Json));
+    assert_eq!(ResponseFormat::parse(Some("GeoJSON")), Ok(ResponseFormat::GeoJson));
+    assert!(ResponseFormat::parse(Some("kml")).is_err());
+    // 좌표는 [경도, 위도], 나머지 필드는 properties
+    let feature = geojson::marker_feature(serde_json::json!({ "id": 7, "latitude": 37.5, "longitude": 127.0, "emotionTag": "happy" }));
+    assert_eq!(feature["id"], 7);
+    assert_eq!(feature["geometry"]["coordinates"], serde_json::json!([127.0, 37.5]));
+    assert_eq!(feature["properties"], serde_json::json!({ "id": 7, "emotionTag": "happy" }));
+    let feature = geojson::cluster_feature(serde_json::json!({ "h3_index": "85283473fffffff", "lat": 37.5, "lng": 127.0, "count": 3 }));
+    assert_eq!(feature["geometry"]["type"], "Point");
+    assert!(feature.get("id").is_none());
+    assert!(geojson::marker_feature(serde_json::json!({ "id": 8 }))["geometry"].is_null());
+
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    for path in ["/api/markers", "/api/markers/cluster"] {
+        let request = get(&format!("{}?lat=37.5&lng=127.0&lat_delta=0.1&lng_delta=0.1&format=kml", path));
+        assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST, "{}", path);
+    }
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('geojson@example.invalid', 'geojson');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, sharing_option)
+        SELECT id, ST_GeogFromText('POINT(127.001 37.501)'), 'happy', 'public' FROM bigpicture.members;
+    "#).await.expect("seed");
+    let app = test::init_service(build_app(state.clone())).await;
+    let area = "lat=37.5&lng=127.0&lat_delta=0.1&lng_delta=0.1";
+
+    let response = test::call_service(&app, get(&format!("/api/markers?{}&format=geojson", area)).to_request()).await;
+    assert_eq!(response.headers().get("content-type").unwrap(), geojson::GEOJSON_CONTENT_TYPE);
+    let (status, body) = read_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["type"], "FeatureCollection");
+    assert_eq!(body["metadata"]["count"], 1);
+    let feature = &body["features"][0];
+    assert_eq!(feature["geometry"]["coordinates"], serde_json::json!([127.001, 37.501]));
+    assert_eq!(feature["properties"]["emotionTag"], "happy");
+    assert!(feature["properties"].get("latitude").is_none());
+
+    let (status, body) = read_json(test::call_service(&app, get(&format!("/api/markers/cluster?{}&zoom=16&format=geojson", area)).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["type"], "FeatureCollection");
+    assert_eq!(body["metadata"]["resolution"], 9);
+    let feature = &body["features"][0];
+    assert_eq!(feature["geometry"]["type"], "Point");
+    assert_eq!(feature["properties"]["count"], 1);
+    assert_eq!(feature["properties"]["markers"][0]["isMine"], false);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn emotion_catalog_is_listed_and_unknown_marker_emotion_tags_are_rejected() {
+    assert_eq!(unknown_emotion_ids("happy, sad,,"), Vec::<&str>::new());
+    assert_eq!(unknown_emotion_ids("happy,hungry, bored"), vec!["hungry", "bored"]);
+
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let (status, body) = read_json(test::call_service(&app, get("/api/emotions").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let emotions = body["data"].as_array().unwrap();
+    assert_eq!(emotions.len(), EMOTION_TAGS.len());
+    assert_eq!((emotions[0]["id"].as_str(), emotions[0]["emoji"].as_str(), emotions[0]["name"].as_str(), emotions[0]["name_en"].as_str()),
+        (Some("happy"), Some("😊"), Some("행복"), Some("Happy")));
+
+    for emotion_tag in ["hungry", "happy,hungry", " , "] {
+        let request = as_member(post_json("/api/markers", &json!({
+            "latitude": 37.5, "longitude": 127.0, "emotion_tag": emotion_tag, "description": "감정"
+        })), 1, &state);
+        assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::UNPROCESSABLE_ENTITY, "{}", emotion_tag);
+    }
+    let request = as_member(TestRequest::patch().uri("/api/markers/1").set_json(json!({ "emotion_tag": "sad,bored" })), 1, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[actix_web::test]
+async fn trending_rank_decays_reactions_by_age_and_hides_private_markers() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let request = get("/api/markers/rank?mode=hot");
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('trend@example.invalid', 'trend');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option, likes, views, created_at)
+        SELECT m.id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', v.description, v.sharing, v.likes, v.views, NOW() - v.age
+        FROM bigpicture.members m, (VALUES
+            ('오래된 인기', 'public', 60, 500, INTERVAL '5 days'),
+            ('방금 뜨는', 'public', 8, 40, INTERVAL '1 hour'),
+            ('비공개', 'private', 100, 900, INTERVAL '1 hour'),
+            ('한 달 넘음', 'public', 1000, 9000, INTERVAL '40 days')
+        ) AS v(description, sharing, likes, views, age);
+    "#).await.expect("seed");
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let (status, body) = read_json(test::call_service(&app, get("/api/markers/rank?mode=trending").to_request()).await).await;
+    assert_eq!((status, body["mode"].as_str()), (StatusCode::OK, Some("trending")));
+    let descriptions: Vec<&str> = body["data"].as_array().unwrap().iter().map(|marker| marker["description"].as_str().unwrap()).collect();
+    assert_eq!(descriptions, ["방금 뜨는", "오래된 인기"]);
+    assert!(body["data"][0]["trendingScore"].as_f64().unwrap() > body["data"][1]["trendingScore"].as_f64().unwrap());
+
+    // 기본(top)은 좋아요 순, 비공개 마커는 빠짐
+    let (_, body) = read_json(test::call_service(&app, get("/api/markers/rank").to_request()).await).await;
+    let descriptions: Vec<&str> = body["data"].as_array().unwrap().iter().map(|marker| marker["description"].as_str().unwrap()).collect();
+    assert_eq!(descriptions, ["한 달 넘음", "오래된 인기", "방금 뜨는"]);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn recommended_markers_rank_by_interests_liked_emotions_and_region() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    assert_eq!(test::call_service(&app, get("/api/markers/recommended").to_request()).await.status(), StatusCode::UNAUTHORIZED);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname, region) VALUES
+            ('viewer@example.invalid', 'viewer', '서울특별시 강남구'),
+            ('author@example.invalid', 'author', NULL);
+        INSERT INTO bigpicture.interests (name) VALUES ('커피');
+        INSERT INTO bigpicture.member_interests (member_id, interest_id)
+        SELECT m.id, i.id FROM bigpicture.members m, bigpicture.interests i WHERE m.nickname = 'viewer';
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option, address_locality, created_at)
+        SELECT m.id, ST_GeogFromText('POINT(127.0 37.5)'), v.emotion, v.description, 'public', v.locality, NOW() - INTERVAL '1 hour'
+        FROM bigpicture.members m, (VALUES
+            ('author', 'happy', '커피 맛집', NULL),
+            ('author', 'sad', '비 오는 날', NULL),
+            ('author', 'sad', '이미 좋아요함', NULL),
+            ('author', 'happy', '동네 산책', '강남구'),
+            ('author', 'peaceful', '그냥', NULL),
+            ('viewer', 'happy', '내 커피', '강남구')
+        ) AS v(nickname, emotion, description, locality)
+        WHERE m.nickname = v.nickname;
+        INSERT INTO bigpicture.member_markers (member_id, marker_id, interaction_type)
+        SELECT m.id, k.id, 'liked' FROM bigpicture.members m, bigpicture.markers k
+        WHERE m.nickname = 'viewer' AND k.description = '이미 좋아요함';
+    "#).await.expect("seed");
+    let viewer: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members WHERE nickname = 'viewer'").fetch_one(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/markers/recommended"), viewer, &state).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let descriptions: Vec<&str> = body["data"].as_array().unwrap().iter().map(|marker| marker["description"].as_str().unwrap()).collect();
+    assert_eq!(descriptions, ["커피 맛집", "동네 산책", "비 오는 날", "그냥"]);
+    assert_eq!(body["data"][0]["reasons"], json!(["interests"]));
+    assert_eq!(body["data"][1]["reasons"], json!(["region"]));
+    assert_eq!(body["data"][2]["reasons"], json!(["liked_emotions"]));
+    assert_eq!(body["pagination"]["totalCount"], 4);
+
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/markers/recommended?page=2&limit=3"), viewer, &state).to_request()).await).await;
+    assert_eq!((body["count"].as_u64(), body["pagination"]["hasPrev"].as_bool()), (Some(1), Some(true)));
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn recommended_markers_follow_content_languages() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname, preferred_languages) VALUES
+            ('viewer@example.invalid', 'viewer', ARRAY['ko']),
+            ('author@example.invalid', 'author', NULL);
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option, language, created_at)
+        SELECT m.id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', v.description, 'public', v.language, NOW() - v.age
+        FROM bigpicture.members m, (VALUES
+            ('한국어', 'ko', INTERVAL '1 hour'),
+            ('english', 'en', INTERVAL '2 hours'),
+            ('언어 미감지', NULL, INTERVAL '3 hours')
+        ) AS v(description, language, age)
+        WHERE m.nickname = 'author';
+    "#).await.expect("seed");
+    let viewer: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members WHERE nickname = 'viewer'").fetch_one(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let recommended = |uri: &'static str| as_member(get(uri), viewer, &state);
+
+    // 기본은 회원 선호 언어, lang 파라미터가 우선 (언어 미감지 마커는 항상 포함)
+    for (uri, expected) in [
+        ("/api/markers/recommended", vec!["한국어", "언어 미감지"]),
+        ("/api/markers/recommended?lang=en", vec!["english", "언어 미감지"]),
+        ("/api/markers/recommended?lang=all", vec!["한국어", "english", "언어 미감지"]),
+    ] {
+        let (status, body) = read_json(test::call_service(&app, recommended(uri).to_request()).await).await;
+        assert_eq!(status, StatusCode::OK);
+        let descriptions: Vec<&str> = body["data"].as_array().unwrap().iter().map(|marker| marker["description"].as_str().unwrap()).collect();
+        assert_eq!(descriptions, expected, "{}", uri);
+        assert_eq!(body["pagination"]["totalCount"], expected.len(), "{}", uri);
+    }
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn marker_views_count_anonymous_viewers_once_per_window_in_batches() {
+    let key = |member_id, device_id, ip| marker_views::viewer_key(member_id, device_id, ip, "ua", "secret");
+    assert_eq!(key(Some(7), Some("device"), "1.1.1.1"), "m:7");
+    assert!(key(None, Some("device"), "1.1.1.1").starts_with("d:"));
+    assert_eq!(key(None, Some("device"), "1.1.1.1"), key(None, Some("device"), "2.2.2.2"));
+    assert_ne!(key(None, None, "1.1.1.1"), key(None, None, "2.2.2.2"));
+    assert!(!key(None, None, "1.1.1.1").contains("1.1.1.1"));
+
+    // 로그인 없이 기록되고, 반영 전 같은 조회자의 재조회는 한 번만 쌓임
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    for device in ["phone-a", "phone-a", "phone-b"] {
+        let request = TestRequest::post().uri("/api/markers/1/view").insert_header((marker_views::DEVICE_ID_HEADER, device));
+        assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+    }
+    assert_eq!(state.marker_views.pending_len(), 2);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('viewer@example.invalid', 'viewer');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, sharing_option)
+        SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', 'public' FROM bigpicture.members;
+    "#).await.expect("seed");
+    let member: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&db.pool).await.unwrap();
+    let marker: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.markers").fetch_one(&db.pool).await.unwrap();
+    let views = &state.marker_views;
+    let now = Utc.with_ymd_and_hms(2025, 5, 1, 9, 0, 0).unwrap();
+    let record_all = |at| {
+        views.record(marker, format!("m:{}", member), Some(member), at);
+        views.record(marker, "a:anonymous".to_string(), None, at);
+        views.record(marker + 1000, "a:anonymous".to_string(), None, at); // 없는 마커
+    };
+    let marker_views_count = || async { sqlx::query_scalar::<_, i32>("SELECT views FROM bigpicture.markers").fetch_one(&db.pool).await.unwrap() };
+
+    record_all(now);
+    assert_eq!(views.flush(db, now).await.unwrap(), 2);
+    assert_eq!(marker_views_count().await, 2);
+    let viewed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.member_markers WHERE interaction_type = 'viewed'").fetch_one(&db.pool).await.unwrap();
+    assert_eq!(viewed, 1);
+
+    // 다른 인스턴스/재시작 후에도 24시간 안의 재조회는 세지 않음
+    record_all(now + Duration::hours(3));
+    assert_eq!(views.flush(db, now + Duration::hours(3)).await.unwrap(), 0);
+    record_all(now + Duration::hours(25));
+    assert_eq!(views.flush(db, now + Duration::hours(25)).await.unwrap(), 2);
+    assert_eq!(marker_views_count().await, 4);
+    assert_eq!(views.pending_len(), 0);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn marker_reaction_state_reflects_the_requesting_member() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('fan@example.invalid', 'fan'), ('other@example.invalid', 'other');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, sharing_option)
+        SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', 'public' FROM bigpicture.members WHERE nickname = 'other';
+    "#).await.expect("seed");
+    let fan: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members WHERE nickname = 'fan'").fetch_one(&state.database.pool).await.unwrap();
+    let other: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members WHERE nickname = 'other'").fetch_one(&state.database.pool).await.unwrap();
+    let marker: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.markers").fetch_one(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let react = |by: i64, like_type: &str| as_member(post_json(&format!("/api/markers/{}/reaction", marker), &json!({ "like_type": like_type })), by, &state);
+
+    let (_, body) = read_json(test::call_service(&app, react(fan, "like").to_request()).await).await;
+    assert_eq!((body["likes"].as_i64(), body["is_liked"].as_bool()), (Some(1), Some(true)));
+    test::call_service(&app, as_member(post_json(&format!("/api/markers/{}/bookmark", marker), &json!({})), fan, &state).to_request()).await;
+
+    // 다른 회원의 좋아요가 있어도 본인은 반응하지 않은 상태
+    let (_, body) = read_json(test::call_service(&app, react(other, "dislike").to_request()).await).await;
+    assert_eq!(body["likes"], 1);
+    assert_eq!((body["is_liked"].as_bool(), body["is_disliked"].as_bool(), body["is_bookmarked"].as_bool()), (Some(false), Some(true), Some(false)));
+
+    let (_, body) = read_json(test::call_service(&app, as_member(get(&format!("/api/markers/{}", marker)), fan, &state).to_request()).await).await;
+    assert_eq!((body["data"]["isLiked"].as_bool(), body["data"]["isDisliked"].as_bool(), body["data"]["isBookmarked"].as_bool()), (Some(true), Some(false), Some(true)));
+    let (_, body) = read_json(test::call_service(&app, as_member(get(&format!("/api/markers/feed?user_id={}", other)), other, &state).to_request()).await).await;
+    assert_eq!((body["data"][0]["isLiked"].as_bool(), body["data"][0]["isDisliked"].as_bool()), (Some(false), Some(true)));
+
+    // 비로그인 응답에는 상태 필드가 없음
+    let (_, body) = read_json(test::call_service(&app, get(&format!("/api/markers/{}", marker)).to_request()).await).await;
+    assert!(body["data"].get("isLiked").is_none());
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn share_links_count_shares_once_per_member_and_resolve_tokens() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    assert_eq!(test::call_service(&app, get("/s/not-a-token").to_request()).await.status(), StatusCode::NOT_FOUND);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('sharer@example.invalid', 'sharer');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option)
+        SELECT id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', v.description, v.sharing
+        FROM bigpicture.members, (VALUES ('공개', 'public'), ('비공개', 'private')) AS v(description, sharing);
+    "#).await.expect("seed");
+    let member: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&state.database.pool).await.unwrap();
+    let (public, private): (i64, i64) = sqlx::query_as("SELECT MIN(id), MAX(id) FROM bigpicture.markers").fetch_one(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let share = |marker: i64| post_json(&format!("/api/markers/{}/share", marker), &json!({}));
+
+    // 회원은 다시 공유해도 같은 링크, 비로그인 공유는 매번 새 링크
+    let (status, first) = read_json(test::call_service(&app, as_member(share(public), member, &state).to_request()).await).await;
+    assert_eq!((status, first["data"]["shares"].as_i64()), (StatusCode::OK, Some(1)));
+    let token = first["data"]["token"].as_str().unwrap().to_string();
+    assert!(first["data"]["shareUrl"].as_str().unwrap().ends_with(&format!("/s/{}", token)));
+    let (_, again) = read_json(test::call_service(&app, as_member(share(public), member, &state).to_request()).await).await;
+    assert_eq!((again["data"]["token"].as_str(), again["data"]["shares"].as_i64()), (Some(token.as_str()), Some(1)));
+    let (_, anonymous) = read_json(test::call_service(&app, share(public).to_request()).await).await;
+    assert_eq!(anonymous["data"]["shares"], 2);
+    assert_ne!(anonymous["data"]["token"].as_str(), Some(token.as_str()));
+    let response = test::call_service(&app, as_member(share(private), member, &state).to_request()).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    // 브라우저는 웹앱 공유 링크로, JSON 요청은 마커 정보 (열린 횟수 기록)
+    let response = test::call_service(&app, get(&format!("/s/{}", token)).to_request()).await;
+    assert_eq!(response.status(), StatusCode::FOUND);
+    assert!(response.headers().get("Location").unwrap().to_str().unwrap().contains("/m/"));
+    let request = get(&format!("/s/{}", token)).insert_header(("Accept", "application/json"));
+    let (status, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!((status, body["data"]["opens"].as_i64()), (StatusCode::OK, Some(2)));
+    assert_eq!((body["data"]["marker"]["id"].as_i64(), body["data"]["marker"]["shares"].as_i64()), (Some(public), Some(2)));
+
+    let (_, body) = read_json(test::call_service(&app, get(&format!("/api/markers/{}", public)).to_request()).await).await;
+    assert_eq!(body["data"]["marker"]["shares"], 2);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn collections_group_markers_in_order_and_hide_private_ones() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let request = as_member(post_json("/api/collections", &json!({ "name": "  " })), 1, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('owner@example.invalid', 'owner'), ('guest@example.invalid', 'guest');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option)
+        SELECT m.id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', v.description, v.sharing
+        FROM bigpicture.members m, (VALUES ('첫째', 'public'), ('둘째', 'public'), ('내 비밀', 'private')) AS v(description, sharing)
+        WHERE m.nickname = 'owner';
+    "#).await.expect("seed");
+    let member_id = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&state.database.pool);
+    let (owner, guest) = (member_id("owner").await.unwrap(), member_id("guest").await.unwrap());
+    let markers: Vec<i64> = sqlx::query_scalar("SELECT id FROM bigpicture.markers ORDER BY id").fetch_all(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let (status, body) = read_json(test::call_service(&app, as_member(post_json("/api/collections", &json!({ "name": " 산책길 " })), owner, &state).to_request()).await).await;
+    assert_eq!((status, body["data"]["name"].as_str(), body["data"]["isPublic"].as_bool()), (StatusCode::CREATED, Some("산책길"), Some(true)));
+    let collection = body["data"]["id"].as_i64().unwrap();
+    for marker in &markers {
+        let request = as_member(post_json(&format!("/api/collections/{}/markers", collection), &json!({ "marker_id": marker })), owner, &state);
+        assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+    }
+    let request = as_member(post_json(&format!("/api/collections/{}/markers", collection), &json!({ "marker_id": markers[0] })), guest, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::FORBIDDEN);
+
+    // 순서는 담긴 마커 전부를 줘야 바뀜
+    let reorder = |marker_ids: Vec<i64>| as_member(TestRequest::put().uri(&format!("/api/collections/{}/markers/order", collection)).set_json(json!({ "marker_ids": marker_ids })), owner, &state);
+    assert_eq!(test::call_service(&app, reorder(vec![markers[1], markers[0]]).to_request()).await.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(test::call_service(&app, reorder(vec![markers[2], markers[1], markers[0]]).to_request()).await.status(), StatusCode::OK);
+
+    // 다른 회원에게는 비공개 마커가 빠지고, 담긴 수는 그대로
+    let (_, body) = read_json(test::call_service(&app, as_member(get(&format!("/api/collections/{}", collection)), guest, &state).to_request()).await).await;
+    let descriptions: Vec<&str> = body["data"]["markers"].as_array().unwrap().iter().map(|marker| marker["description"].as_str().unwrap()).collect();
+    assert_eq!((descriptions, body["data"]["markerCount"].as_i64()), (vec!["둘째", "첫째"], Some(3)));
+    let (_, body) = read_json(test::call_service(&app, as_member(get(&format!("/api/collections/{}", collection)), owner, &state).to_request()).await).await;
+    assert_eq!(body["data"]["markers"][0]["description"], "내 비밀");
+
+    let request = as_member(TestRequest::delete().uri(&format!("/api/collections/{}/markers/{}", collection, markers[1])), owner, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+
+    // 비공개로 바꾸면 본인 목록에만 보임
+    let request = as_member(TestRequest::patch().uri(&format!("/api/collections/{}", collection)).set_json(json!({ "is_public": false })), owner, &state);
+    let (_, body) = read_json(test::call_service(&app, request.to_request()).await).await;
+    assert_eq!((body["data"]["isPublic"].as_bool(), body["data"]["markerCount"].as_i64()), (Some(false), Some(2)));
+    let (_, body) = read_json(test::call_service(&app, get(&format!("/api/members/{}/collections", owner)).to_request()).await).await;
+    assert_eq!(body["count"], 0);
+    let (_, body) = read_json(test::call_service(&app, as_member(get(&format!("/api/members/{}/collections", owner)), owner, &state).to_request()).await).await;
+    assert_eq!(body["count"], 1);
+    let response = test::call_service(&app, as_member(get(&format!("/api/collections/{}", collection)), guest, &state).to_request()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn bookmark_folders_are_ordered_and_shown_on_bookmarked_markers() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let request = as_member(post_json("/api/bookmark-folders", &json!({ "name": "여행", "color": "red" })), 1, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('saver@example.invalid', 'saver'), ('other@example.invalid', 'other');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option)
+        SELECT m.id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', v.description, 'public'
+        FROM bigpicture.members m, (VALUES ('카페'), ('바다')) AS v(description)
+        WHERE m.nickname = 'other';
+        INSERT INTO bigpicture.member_markers (member_id, marker_id, interaction_type)
+        SELECT m.id, k.id, 'bookmarked' FROM bigpicture.members m, bigpicture.markers k WHERE m.nickname = 'saver';
+    "#).await.expect("seed");
+    let member_id = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&state.database.pool);
+    let (saver, other) = (member_id("saver").await.unwrap(), member_id("other").await.unwrap());
+    let cafe: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.markers WHERE description = '카페'").fetch_one(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let create = |name: &str| as_member(post_json("/api/bookmark-folders", &json!({ "name": name, "color": "#ff8800" })), saver, &state);
+    let (status, body) = read_json(test::call_service(&app, create("여행").to_request()).await).await;
+    assert_eq!((status, body["data"]["color"].as_str(), body["data"]["position"].as_i64()), (StatusCode::CREATED, Some("#FF8800"), Some(1)));
+    let travel = body["data"]["id"].as_i64().unwrap();
+    let (_, body) = read_json(test::call_service(&app, create("맛집").to_request()).await).await;
+    let food = body["data"]["id"].as_i64().unwrap();
+
+    // 순서 바꾸기는 내 폴더 전부가 있어야 함
+    let reorder = |folder_ids: Vec<i64>| as_member(TestRequest::put().uri("/api/bookmark-folders/order").set_json(json!({ "folder_ids": folder_ids })), saver, &state);
+    assert_eq!(test::call_service(&app, reorder(vec![food]).to_request()).await.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(test::call_service(&app, reorder(vec![food, travel]).to_request()).await.status(), StatusCode::OK);
+
+    // 남의 폴더로는 옮길 수 없음
+    let move_to = |folder_id: Option<i64>, by: i64| as_member(TestRequest::put().uri("/api/bookmarks/folder").set_json(json!({ "marker_ids": [cafe], "folder_id": folder_id })), by, &state);
+    assert_eq!(test::call_service(&app, move_to(Some(food), other).to_request()).await.status(), StatusCode::NOT_FOUND);
+    let (_, body) = read_json(test::call_service(&app, move_to(Some(food), saver).to_request()).await).await;
+    assert_eq!(body["data"]["moved"], 1);
+
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/bookmark-folders"), saver, &state).to_request()).await).await;
+    let folders: Vec<(&str, i64)> = body["data"].as_array().unwrap().iter().map(|folder| (folder["name"].as_str().unwrap(), folder["bookmarkCount"].as_i64().unwrap())).collect();
+    assert_eq!(folders, vec![("맛집", 1), ("여행", 0)]);
+
+    let (_, body) = read_json(test::call_service(&app, get(&format!("/api/members/{}/markers/bookmarked", saver)).to_request()).await).await;
+    let folder_of = |description: &str| body["data"].as_array().unwrap().iter().find(|marker| marker["description"] == description).unwrap()["bookmarkFolder"].clone();
+    assert_eq!(folder_of("카페"), json!({ "id": food, "name": "맛집", "color": "#FF8800" }));
+    assert_eq!(folder_of("바다"), serde_json::Value::Null);
+    let (_, body) = read_json(test::call_service(&app, get(&format!("/api/members/{}/markers/bookmarked?folder_id={}", saver, food)).to_request()).await).await;
+    assert_eq!(body["count"], 1);
+
+    // 폴더를 지워도 북마크는 남음
+    let request = as_member(TestRequest::delete().uri(&format!("/api/bookmark-folders/{}", food)), saver, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+    let (_, body) = read_json(test::call_service(&app, get(&format!("/api/members/{}/markers/bookmarked", saver)).to_request()).await).await;
+    assert_eq!(body["count"], 2);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn idempotency_keys_replay_the_first_marker_creation_response() {
+    assert!(idempotency::is_valid_idempotency_key("8e0f1c2a-retry_1"));
+    assert!(!idempotency::is_valid_idempotency_key("has space"));
+    let hash = |body: &[u8]| idempotency::request_hash("POST", "/api/markers", "", "application/json", Some(body));
+    assert_eq!(hash(b"{}"), hash(b"{}"));
+    assert_ne!(hash(b"{}"), hash(b"[]"));
+
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    let request = as_member(post_json("/api/markers", &json!({})), 1, &state).insert_header(("Idempotency-Key", "키"));
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, "INSERT INTO bigpicture.members (email, nickname) VALUES ('retry@example.invalid', 'retry')").await.expect("seed");
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&db.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    let create = |key: &str, description: &str| as_member(post_json("/api/markers", &json!({
+        "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": description, "sharing_option": "public"
+    })), author, &state).insert_header(("Idempotency-Key", key.to_string()));
+
+    // 실패한 요청은 저장하지 않아 같은 키로 고쳐서 다시 보낼 수 있음
+    let invalid = as_member(post_json("/api/markers", &json!({ "latitude": 37.5, "longitude": 127.0, "emotion_tag": "hungry" })), author, &state)
+        .insert_header(("Idempotency-Key", "retry-1"));
+    assert_eq!(test::call_service(&app, invalid.to_request()).await.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (status, first) = read_json(test::call_service(&app, create("retry-1", "한강 산책").to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let response = test::call_service(&app, create("retry-1", "한강 산책").to_request()).await;
+    assert_eq!(response.headers().get(idempotency::IDEMPOTENT_REPLAYED_HEADER).unwrap(), "true");
+    let (status, replayed) = read_json(response).await;
+    assert_eq!((status, replayed["data"]["id"].clone()), (StatusCode::OK, first["data"]["id"].clone()));
+    let markers: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.markers").fetch_one(&db.pool).await.unwrap();
+    assert_eq!(markers, 1);
+
+    // 같은 키를 다른 요청에 쓰면 거절, 새 키는 새로 처리
+    assert_eq!(test::call_service(&app, create("retry-1", "다른 내용").to_request()).await.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(test::call_service(&app, create("retry-2", "다른 내용").to_request()).await.status(), StatusCode::OK);
+    let markers: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.markers").fetch_one(&db.pool).await.unwrap();
+    assert_eq!(markers, 2);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn admin_marker_export_streams_csv_and_geojson_filtered_by_date_and_region() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    assert_eq!(test::call_service(&app, get("/api/admin/markers/export").to_request()).await.status(), StatusCode::UNAUTHORIZED);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname, is_admin) VALUES ('admin@example.invalid', 'admin', true), ('user@example.invalid', 'user', false);
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option, address_region, created_at)
+        SELECT m.id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', v.description, 'public', v.region, v.created_at::TIMESTAMPTZ
+        FROM bigpicture.members m,
+             (VALUES ('서울, 한강', '서울특별시', '2024-03-01T00:00:00Z'), ('부산 바다', '부산광역시', '2024-03-02T00:00:00Z'), ('작년 서울', '서울특별시', '2023-03-01T00:00:00Z')) AS v(description, region, created_at)
+        WHERE m.nickname = 'user';
+    "#).await.expect("seed");
+    let member_id = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&state.database.pool);
+    let (admin, user) = (member_id("admin").await.unwrap(), member_id("user").await.unwrap());
+    let app = test::init_service(build_app(state.clone())).await;
+
+    assert_eq!(test::call_service(&app, as_member(get("/api/admin/markers/export"), user, &state).to_request()).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(test::call_service(&app, as_member(get("/api/admin/markers/export?format=xlsx"), admin, &state).to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let response = test::call_service(&app, as_member(get("/api/admin/markers/export?from=2024-01-01&region=%EC%84%9C%EC%9A%B8%ED%8A%B9%EB%B3%84%EC%8B%9C"), admin, &state).to_request()).await;
+    assert_eq!(response.headers().get("content-type").unwrap(), "text/csv; charset=utf-8");
+    let csv = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(lines[0].starts_with("marker_id,public_id,member_id,latitude,longitude"));
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].contains("\"서울, 한강\""));
+
+    let response = test::call_service(&app, as_member(get("/api/admin/markers/export?format=geojson&from=2024-01-01&to=2025-01-01"), admin, &state).to_request()).await;
+    let body: serde_json::Value = serde_json::from_slice(&test::read_body(response).await).unwrap();
+    let regions: Vec<&str> = body["features"].as_array().unwrap().iter().map(|feature| feature["properties"]["addressRegion"].as_str().unwrap()).collect();
+    assert_eq!(regions, vec!["서울특별시", "부산광역시"]);
+    assert_eq!(body["features"][0]["geometry"]["coordinates"], json!([127.0, 37.5]));
+
+    test_db.drop_database().await;
+}
+
+#[test]
+fn zip_writer_stores_entries_with_crc_and_central_directory() {
+    assert_eq!(zip::crc32(b"123456789"), 0xCBF4_3926);
+    let modified = Utc.with_ymd_and_hms(2024, 5, 17, 10, 30, 0).unwrap();
+    let mut writer = ZipWriter::new();
+    writer.add_file("markers.json", b"[]", modified).unwrap();
+    writer.add_file("지도/markers.geojson", b"{}", modified).unwrap();
+    assert!(writer.add_file("/absolute.json", b"", modified).is_err());
+    let data = writer.finish().unwrap();
+
+    assert_eq!(&data[..4], b"PK\x03\x04");
+    assert_eq!(u32::from_le_bytes(data[14..18].try_into().unwrap()), zip::crc32(b"[]"));
+    let eocd = &data[data.len() - 22..];
+    assert_eq!(&eocd[..4], b"PK\x05\x06");
+    assert_eq!(u16::from_le_bytes([eocd[10], eocd[11]]), 2);
+    let directory_offset = u32::from_le_bytes(eocd[16..20].try_into().unwrap()) as usize;
+    assert_eq!(&data[directory_offset..directory_offset + 4], b"PK\x01\x02");
+}
+
+#[actix_web::test]
+async fn member_data_export_is_inline_for_small_accounts_and_queued_for_large_ones() {
+    let state = fake_state();
+    let app = test::init_service(build_app(state.clone())).await;
+    assert_eq!(test::call_service(&app, get("/api/members/me/export").to_request()).await.status(), StatusCode::UNAUTHORIZED);
+    let request = as_member(get("/api/members/me/export?format=csv"), 1, &state);
+    assert_eq!(test::call_service(&app, request.to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('owner@example.invalid', 'owner'), ('busy@example.invalid', 'busy');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option, status)
+        SELECT m.id, ST_GeogFromText('POINT(127.0 37.5)'), 'happy', v.description, v.sharing_option, v.status
+        FROM bigpicture.members m, (VALUES ('공개', 'public', 'published'), ('비공개', 'private', 'published'), ('임시', 'public', 'draft')) AS v(description, sharing_option, status)
+        WHERE m.nickname = 'owner';
+        INSERT INTO bigpicture.marker_images (marker_id, image_type, image_url, is_primary)
+        SELECT id, 'detail', 'https://cdn.example.invalid/detail.webp', true FROM bigpicture.markers WHERE description = '공개';
+        INSERT INTO bigpicture.member_markers (member_id, marker_id, interaction_type)
+        SELECT m.id, k.id, 'liked' FROM bigpicture.members m, bigpicture.markers k WHERE m.nickname = 'owner' AND k.description = '공개';
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option)
+        SELECT m.id, ST_GeogFromText('POINT(127.0 37.5)'), 'calm', 'bulk ' || n, 'public'
+        FROM bigpicture.members m, generate_series(1, 201) AS n
+        WHERE m.nickname = 'busy';
+    "#).await.expect("seed");
+    let member_id = |nickname: &str| sqlx::query_scalar::<_, i64>("SELECT id FROM bigpicture.members WHERE nickname = $1").bind(nickname.to_string()).fetch_one(&state.database.pool);
+    let (owner, busy) = (member_id("owner").await.unwrap(), member_id("busy").await.unwrap());
+    let app = test::init_service(build_app(state.clone())).await;
+
+    // 비공개/임시 마커까지 모두, 이미지 URL과 상호작용 포함
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/export?format=json"), owner, &state).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["markers"].as_array().unwrap().len(), 3);
+    assert_eq!(body["geojson"]["features"].as_array().unwrap().len(), 3);
+    assert_eq!(body["images"][0]["imageUrl"], "https://cdn.example.invalid/detail.webp");
+    assert_eq!(body["interactions"].as_array().unwrap().iter().filter(|interaction| interaction["interactionType"] == "liked").count(), 1);
+
+    let response = test::call_service(&app, as_member(get("/api/members/me/export"), owner, &state).to_request()).await;
+    assert_eq!(response.headers().get("content-type").unwrap(), "application/zip");
+    let data = test::read_body(response).await;
+    assert_eq!(&data[..4], b"PK\x03\x04");
+    for name in ["member.json", "markers.json", "markers.geojson", "images.json", "interactions.json"] {
+        assert!(data.windows(name.len()).any(|window| window == name.as_bytes()), "{} 없음", name);
+    }
+
+    // 마커가 많으면 작업을 등록하고, 다시 요청해도 같은 작업
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/export"), busy, &state).to_request()).await).await;
+    assert_eq!((status, body["data"]["status"].as_str()), (StatusCode::ACCEPTED, Some("pending")));
+    let job_id = body["data"]["id"].as_i64().unwrap();
+    let (_, body) = read_json(test::call_service(&app, as_member(get("/api/members/me/export"), busy, &state).to_request()).await).await;
+    assert_eq!(body["data"]["id"].as_i64(), Some(job_id));
+
+    let status_url = format!("/api/members/me/exports/{}", job_id);
+    assert_eq!(test::call_service(&app, as_member(get(&status_url), owner, &state).to_request()).await.status(), StatusCode::NOT_FOUND);
+    let download = as_member(get(&format!("{}/download", status_url)), busy, &state);
+    assert_eq!(test::call_service(&app, download.to_request()).await.status(), StatusCode::CONFLICT);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn marker_creation_saves_images_and_created_interaction_atomically() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    let pool = state.database.pool.clone();
+    sqlx::Executor::execute(&pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('atomic@example.invalid', 'atomic');
+        INSERT INTO bigpicture.image_variants (image_url, thumbnail_url)
+            VALUES ('https://bucket.s3.ap-northeast-2.amazonaws.com/markers/a.webp', 'https://bucket.s3.ap-northeast-2.amazonaws.com/markers/a_thumb.webp');
+    "#).await.expect("seed");
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let create = |images: serde_json::Value| as_member(post_json("/api/markers", &json!({
+        "latitude": 37.5, "longitude": 127.0, "emotion_tag": "happy", "description": "사진", "images": images
+    })), author, &state);
+
+    let (status, body) = read_json(test::call_service(&app, create(json!([
+        {"image_url": "/markers/a.webp"},
+        {"image_url": "/markers/b.webp", "image_type": "gallery"}
+    ])).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let types: Vec<&str> = body["data"]["images"].as_array().unwrap().iter().map(|image| image["imageType"].as_str().unwrap()).collect();
+    assert_eq!(types, vec!["detail", "thumbnail", "gallery"]);
+    let marker_id = body["data"]["id"].as_i64().unwrap();
+    let created: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.member_markers WHERE member_id = $1 AND marker_id = $2 AND interaction_type = 'created'")
+        .bind(author).bind(marker_id).fetch_one(&pool).await.unwrap();
+    assert_eq!(created, 1);
+
+    // 이미지 하나라도 저장에 실패하면 마커도 남지 않음
+    let (status, _) = read_json(test::call_service(&app, create(json!([
+        {"image_url": "/markers/c.webp"},
+        {"image_url": format!("https://cdn.example/{}", "x".repeat(600))}
+    ])).to_request()).await).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    let markers: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.markers").fetch_one(&pool).await.unwrap();
+    let images: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.marker_images").fetch_one(&pool).await.unwrap();
+    assert_eq!((markers, images), (1, 3));
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn concurrent_marker_image_adds_respect_the_quota() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let mut state = test_db.state.clone();
+    state.config.max_images_per_marker = 2;
+    let pool = state.database.pool.clone();
+    sqlx::Executor::execute(&pool, r#"
+        INSERT INTO bigpicture.markers (location, emotion_tag, description)
+            VALUES (ST_GeogFromText('POINT(127.0 37.5)'), 'happy', '쿼터');
+        INSERT INTO bigpicture.marker_images (marker_id, image_type, image_url, is_primary)
+            SELECT id, 'detail', '/markers/existing.webp', true FROM bigpicture.markers;
+    "#).await.expect("seed");
+    let marker_id: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.markers").fetch_one(&pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let add = |n: usize| post_json(&format!("/api/markers/{}/images", marker_id), &json!({ "image_url": format!("/markers/concurrent-{}.webp", n), "image_type": "gallery" }));
+
+    // 남은 자리는 하나뿐이라 동시에 보내도 하나만 저장
+    let responses = futures::future::join_all((0..3).map(|n| test::call_service(&app, add(n).to_request()))).await;
+    let mut statuses: Vec<u16> = responses.iter().map(|response| response.status().as_u16()).collect();
+    statuses.sort();
+    assert_eq!(statuses, vec![200, 422, 422]);
+    let images: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bigpicture.marker_images WHERE marker_id = $1").bind(marker_id).fetch_one(&pool).await.unwrap();
+    assert_eq!(images, 2);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn cluster_results_are_cached_per_viewport_and_invalidated_on_marker_changes() {
+    // 중심은 H3 셀 중심으로, 영역 크기는 유효숫자 2자리로 올림
+    let viewport = ClusterViewport::normalize(37.55, 127.0, 0.0123, 0.02, 8);
+    assert_eq!((viewport.lat_delta, viewport.lng_delta), (0.013, 0.02));
+    let nearby = ClusterViewport::normalize(viewport.lat + 0.00001, viewport.lng + 0.00001, 0.013, 0.0195, 8);
+    assert_eq!(nearby, viewport);
+
+    let filters = ClusterFilters { emotion_tags: vec!["happy".to_string()], min_likes: None, min_views: None, limit: None, user_id: None, viewer_id: None, time_filter: CreatedTimeFilter::default() };
+    let cache = ClusterCache::new(&test_config());
+    let now = Utc::now();
+    cache.insert(8, &viewport, &filters, vec![json!({"count": 1})], now);
+    assert!(cache.get(8, &viewport, &filters, now).is_some());
+    assert!(cache.get(9, &viewport, &filters, now).is_none());
+    assert!(cache.get(8, &viewport, &ClusterFilters { user_id: Some(1), ..filters.clone() }, now).is_none());
+    assert!(cache.get(8, &viewport, &filters, now + Duration::minutes(5)).is_none());
+    cache.invalidate_point(36.0, 128.0); // 영역 밖
+    assert_eq!(cache.len(), 1);
+    cache.invalidate_point(viewport.lat + 0.005, viewport.lng);
+    assert!(cache.is_empty());
+
+    let mut config = test_config();
+    config.cluster_result_cache_secs = 0;
+    let disabled = ClusterCache::new(&config);
+    disabled.insert(8, &viewport, &filters, Vec::new(), now);
+    assert!(disabled.get(8, &viewport, &filters, now).is_none());
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let state = test_db.state.clone();
+    sqlx::Executor::execute(&state.database.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('cache@example.invalid', 'cache');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, sharing_option)
+        SELECT id, ST_SetSRID(ST_MakePoint(127.0, 37.55), 4326)::geography, 'happy', 'public' FROM bigpicture.members;
+    "#).await.expect("seed");
+    let author: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&state.database.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+    let count = |body: &serde_json::Value| body["data"].as_array().unwrap().iter()
+        .map(|cluster| cluster["count"].as_i64().unwrap_or(1))
+        .sum::<i64>();
+
+    let center = ClusterViewport::normalize(37.55, 127.0, 0.05, 0.05, 8);
+    let path = |lat: f64, lng: f64| format!("/api/markers/cluster?lat={}&lng={}&lat_delta=0.05&lng_delta=0.05&zoom=15", lat, lng);
+    let (status, body) = read_json(test::call_service(&app, get(&path(center.lat, center.lng)).to_request()).await).await;
+    assert_eq!((status, body["cached"].as_bool(), count(&body)), (StatusCode::OK, Some(false), 1));
+    // 중심이 조금 달라도 같은 캐시
+    let (_, body) = read_json(test::call_service(&app, get(&path(center.lat + 0.00001, center.lng + 0.00001)).to_request()).await).await;
+    assert_eq!((body["cached"].as_bool(), count(&body)), (Some(true), 1));
+
+    // 영역 안에 마커가 생기면 바로 반영
+    let create = as_member(post_json("/api/markers", &json!({
+        "latitude": 37.551, "longitude": 127.001, "emotion_tag": "happy", "description": "캐시", "sharing_option": "public"
+    })), author, &state);
+    assert_eq!(test::call_service(&app, create.to_request()).await.status(), StatusCode::OK);
+    let (_, body) = read_json(test::call_service(&app, get(&path(center.lat, center.lng)).to_request()).await).await;
+    assert_eq!((body["cached"].as_bool(), count(&body)), (Some(false), 2));
+
+    test_db.drop_database().await;
+}
+
+/// 받은 문서/삭제를 기록하고, 검색은 정해 둔 id를 돌려주는 가짜 검색 엔진 (ids가 None이면 실패)
+#[derive(Default)]
+struct FakeSearch {
+    ids: Option<Vec<i64>>,
+    upserted: std::sync::Mutex<Vec<MarkerSearchDocument>>,
+    deleted: std::sync::Mutex<Vec<i64>>,
+}
+
+impl SearchBackend for FakeSearch {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn upsert<'a>(&'a self, documents: &'a [MarkerSearchDocument]) -> futures::future::BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            self.upserted.lock().unwrap().extend(documents.iter().cloned());
+            Ok(())
+        })
+    }
+
+    fn delete<'a>(&'a self, ids: &'a [i64]) -> futures::future::BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            self.deleted.lock().unwrap().extend_from_slice(ids);
+            Ok(())
+        })
+    }
+
+    fn search<'a>(&'a self, _request: &'a SearchRequest) -> futures::future::BoxFuture<'a, anyhow::Result<SearchHits>> {
+        Box::pin(async move {
+            let ids = self.ids.clone().ok_or_else(|| anyhow::anyhow!("검색 엔진 응답 없음"))?;
+            Ok(SearchHits { total: ids.len() as i64, ids })
+        })
+    }
+}
+
+#[actix_web::test]
+async fn search_uses_external_index_when_configured_and_falls_back_to_postgres() {
+    let document = MarkerSearchDocument {
+        id: 7,
+        description: Some("한강 야경".to_string()),
+        emotion_tag: Some("happy".to_string()),
+        emotion_tag_input: None,
+        author: Some("산책러".to_string()),
+        nickname: Some("산책러".to_string()),
+        language: Some("ko".to_string()),
+        latitude: Some(37.5),
+        longitude: Some(127.0),
+        created_at: Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap(),
+        tags: vec!["야경".to_string()],
+    };
+    let json = search_index::document_json(&document);
+    assert_eq!((json["_geo"]["lat"].as_f64(), json["tags"][0].as_str()), (Some(37.5), Some("야경")));
+
+    let mut state = fake_state();
+    state.search_index = SearchIndex::new(Arc::new(FakeSearch::default()));
+    let app = test::init_service(build_app(state)).await;
+    assert_eq!(test::call_service(&app, get("/api/search?q=%20").to_request()).await.status(), StatusCode::BAD_REQUEST);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let pool = test_db.state.database.pool.clone();
+    sqlx::Executor::execute(&pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('search@example.invalid', '산책러');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option)
+        SELECT m.id, ST_SetSRID(ST_MakePoint(127.0, 37.5), 4326)::geography, 'happy', v.description, v.sharing
+        FROM bigpicture.members m, (VALUES ('한강 야경', 'public'), ('한강 산책', 'public'), ('한강 비밀', 'private')) AS v(description, sharing);
+        INSERT INTO bigpicture.marker_tags (marker_id, tag) SELECT id, '야경' FROM bigpicture.markers WHERE description = '한강 야경';
+    "#).await.expect("seed");
+    let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM bigpicture.markers ORDER BY id").fetch_all(&pool).await.unwrap();
+
+    // 색인 순서를 따르고, 그사이 비공개가 된 마커는 빠짐
+    let mut state = test_db.state.clone();
+    state.search_index = SearchIndex::new(Arc::new(FakeSearch { ids: Some(vec![ids[2], ids[1], ids[0]]), ..Default::default() }));
+    let app = test::init_service(build_app(state)).await;
+    let (status, body) = read_json(test::call_service(&app, get("/api/search?q=hangang").to_request()).await).await;
+    assert_eq!((status, body["engine"].as_str()), (StatusCode::OK, Some("fake")));
+    let found: Vec<i64> = body["data"].as_array().unwrap().iter().map(|marker| marker["id"].as_i64().unwrap()).collect();
+    assert_eq!(found, vec![ids[1], ids[0]]);
+
+    // 검색 엔진이 실패하면 Postgres 전문 검색
+    let mut state = test_db.state.clone();
+    state.search_index = SearchIndex::new(Arc::new(FakeSearch::default()));
+    let app = test::init_service(build_app(state)).await;
+    let (status, body) = read_json(test::call_service(&app, get("/api/search?q=야경").to_request()).await).await;
+    assert_eq!((status, body["engine"].as_str(), body["count"].as_i64()), (StatusCode::OK, Some("postgres"), Some(1)));
+
+    // 전체 재색인은 공개 마커만 해시태그/닉네임과 함께, 게시 중단 이벤트는 삭제로
+    let backend = FakeSearch::default();
+    assert_eq!(search_index::reindex_all(&test_db.state.database, &backend).await.unwrap(), 2);
+    let upserted = backend.upserted.lock().unwrap().clone();
+    assert_eq!((upserted[0].tags.clone(), upserted[0].nickname.as_deref()), (vec!["야경".to_string()], Some("산책러")));
+    let marker = test_db.state.database.get_marker_detail(ids[0]).await.unwrap().unwrap();
+    let event = MarkerEvent::from_marker(MarkerEventKind::Deleted, &marker, None).unwrap();
+    search_index::apply_event(&test_db.state.database, &backend, &event).await.unwrap();
+    assert_eq!(*backend.deleted.lock().unwrap(), vec![ids[0]]);
+
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn resumable_uploads_continue_from_the_received_offset() {
+    let metadata = resumable_upload::parse_metadata("filename cGhvdG8uanBn, type bWFw,exif").unwrap();
+    assert_eq!((metadata["filename"].as_str(), metadata["type"].as_str(), metadata["exif"].as_str()), ("photo.jpg", "map", ""));
+    assert!(resumable_upload::parse_metadata("filename !!!").is_err());
+    assert!(resumable_upload::parse_metadata("a YQ==,a Yg==").is_err());
+
+    let app = test::init_service(build_app(fake_state())).await;
+    let response = test::call_service(&app, TestRequest::default().method(actix_web::http::Method::OPTIONS).uri("/api/uploads").to_request()).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(response.headers().get("Tus-Version").unwrap(), "1.0.0");
+    assert_eq!(response.headers().get("Tus-Extension").unwrap(), "creation,termination,expiration");
+    let create = |length: &str, metadata: &str| TestRequest::post()
+        .uri("/api/uploads")
+        .insert_header(("Tus-Resumable", "1.0.0"))
+        .insert_header(("Upload-Length", length.to_string()))
+        .insert_header(("Upload-Metadata", metadata.to_string()));
+    let status = |request: TestRequest| {
+        let app = &app;
+        async move { test::call_service(app, request.to_request()).await.status() }
+    };
+    assert_eq!(status(TestRequest::post().uri("/api/uploads").insert_header(("Upload-Length", "10"))).await, StatusCode::PRECONDITION_FAILED);
+    assert_eq!(status(create("0", "filename cGhvdG8uanBn")).await, StatusCode::BAD_REQUEST);
+    assert_eq!(status(create("999999999999", "filename cGhvdG8uanBn")).await, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(status(create("10", "filename bm90ZXMudHh0")).await, StatusCode::BAD_REQUEST);
+    assert_eq!(status(create("10", "filename cGhvdG8uanBn,type Y2lyY3VsYXI=")).await, StatusCode::BAD_REQUEST);
+    let patch = |uri: &str, offset: i64, body: &[u8]| TestRequest::patch()
+        .uri(uri)
+        .insert_header(("Tus-Resumable", "1.0.0"))
+        .insert_header(("Content-Type", "application/offset+octet-stream"))
+        .insert_header(("Upload-Offset", offset.to_string()))
+        .set_payload(body.to_vec());
+    let id = uuid::Uuid::new_v4();
+    let wrong_type = patch(&format!("/api/uploads/{}", id), 0, b"x").insert_header(("Content-Type", "application/octet-stream"));
+    assert_eq!(status(wrong_type).await, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let root = std::env::temp_dir().join(format!("bigpicture-resumable-{}", uuid::Uuid::new_v4().simple()));
+    let mut state = test_db.state.clone();
+    state.config.storage_backend = "local".to_string();
+    state.config.upload_dir = root.join("files").to_string_lossy().to_string();
+    state.config.upload_temp_dir = root.join("tmp").to_string_lossy().to_string();
+    state.config.file_server_url = "http://files.test".to_string();
+    state.config.min_free_disk_mb = 0;
+    state.storage = storage::from_config(&state.config, &state.s3_service);
+    state.upload_sessions = UploadSessions::new(&state.config);
+    let sessions = state.upload_sessions.clone();
+    let app = test::init_service(build_app(state)).await;
+    let status = |request: TestRequest| {
+        let app = &app;
+        async move { test::call_service(app, request.to_request()).await.status() }
+    };
+
+    let mut jpeg = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(400, 200, image::Rgb([20, 120, 200])))
+        .write_to(&mut jpeg, image::ImageOutputFormat::Jpeg(90))
+        .expect("jpeg");
+    let jpeg = jpeg.into_inner();
+    let response = test::call_service(&app, create(&jpeg.len().to_string(), "filename cGhvdG8uanBn,type dGh1bWJuYWls").to_request()).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let location = response.headers().get("Location").unwrap().to_str().unwrap().to_string();
+    assert!(response.headers().contains_key("Upload-Expires"));
+
+    // 앞부분만 보내고 끊긴 뒤 HEAD로 받은 위치를 확인해 이어 보냄
+    let half = jpeg.len() / 2;
+    let response = test::call_service(&app, patch(&location, 0, &jpeg[..half]).to_request()).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(response.headers().get("Upload-Offset").unwrap().to_str().unwrap(), half.to_string());
+    let response = test::call_service(&app, TestRequest::default().method(actix_web::http::Method::HEAD).uri(&location).to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("Upload-Offset").unwrap().to_str().unwrap(), half.to_string());
+    assert_eq!(status(patch(&location, 0, &jpeg)).await, StatusCode::CONFLICT);
+
+    let (status_code, body) = read_json(test::call_service(&app, patch(&location, half as i64, &jpeg[half..]).to_request()).await).await;
+    assert_eq!(status_code, StatusCode::OK, "{}", body);
+    assert!(body["s3_url"].as_str().unwrap().starts_with("http://files.test/api/images/download/markers/thumbnail_"), "{}", body);
+    assert!(body["original_url"].as_str().unwrap().contains("/originals/thumbnail/"), "{}", body);
+    assert_eq!(std::fs::read_dir(sessions.dir()).unwrap().count(), 0);
+
+    let (status_code, body) = read_json(test::call_service(&app, get(&location).to_request()).await).await;
+    assert_eq!((status_code, body["data"]["status"].as_str(), body["data"]["progress"].as_f64()), (StatusCode::OK, Some("completed"), Some(100.0)));
+    assert!(body["data"]["result"]["s3_url"].is_string(), "{}", body);
+    assert_eq!(status(patch(&location, jpeg.len() as i64, b"")).await, StatusCode::CONFLICT);
+
+    // 취소하면 세션도 사라짐
+    let delete = || TestRequest::delete().uri(&location).insert_header(("Tus-Resumable", "1.0.0"));
+    assert_eq!(status(delete()).await, StatusCode::NO_CONTENT);
+    assert_eq!(status(delete()).await, StatusCode::NOT_FOUND);
+
+    std::fs::remove_dir_all(&root).ok();
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn orphaned_files_and_missing_marker_images_are_reported_then_collected() {
+    let root = std::env::temp_dir().join(format!("bigpicture-orphans-{}", uuid::Uuid::new_v4().simple()));
+    let local = LocalStorage::new(&root, "http://files.test", "secret");
+    for key in ["markers/kept.webp", "markers/w256/quality.webp", "originals/thumbnail/orphan.jpg", "exports/a.csv"] {
+        local.put(key, b"data".to_vec(), storage::content_type_for(key)).await.unwrap();
+    }
+    let upload = local.start_upload("markers/uploading.webp", "image/webp").await.unwrap();
+    let listed: Vec<String> = local.list("markers/").await.unwrap().into_iter().map(|object| object.key).collect();
+    assert_eq!(listed, vec!["markers/kept.webp", "markers/w256/quality.webp"]);
+    assert!(local.list("maps/").await.unwrap().is_empty());
+    upload.abort().await;
+
+    let app = test::init_service(build_app(fake_state())).await;
+    assert_eq!(test::call_service(&app, get("/api/admin/storage/orphans").to_request()).await.status(), StatusCode::UNAUTHORIZED);
+
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        std::fs::remove_dir_all(&root).ok();
+        return;
+    };
+    let mut state = deterministic(test_db.state.clone(), Arc::new(FixedClock::new(Utc::now() + Duration::days(2))));
+    state.config.orphan_gc_min_age_hours = 24;
+    state.storage = Arc::new(LocalStorage::new(&root, "http://files.test", "secret"));
+    let db = &state.database;
+    sqlx::Executor::execute(&db.pool, r#"
+        INSERT INTO bigpicture.members (email, nickname, is_admin) VALUES ('admin@example.invalid', 'admin', true);
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option)
+        SELECT id, ST_SetSRID(ST_MakePoint(127.0, 37.5), 4326)::geography, 'happy', '한강', 'public' FROM bigpicture.members;
+        INSERT INTO bigpicture.marker_images (marker_id, image_type, image_url, image_order, is_primary)
+        SELECT m.id, 'detail', v.url, v.image_order, v.image_order = 0
+        FROM bigpicture.markers m, (VALUES
+            ('http://files.test/api/images/download/markers/kept.webp', 0),
+            ('http://files.test/api/images/download/markers/missing.webp', 1),
+            ('/markers/on-s3.webp', 2)
+        ) AS v(url, image_order);
+    "#).await.expect("seed");
+    db.save_image_quality("http://files.test/api/images/download/markers/w256/quality.webp", 256, 128, 1.0, 4, "L00000fQfQfQfQfQfQfQ").await.unwrap();
+    let admin: i64 = sqlx::query_scalar("SELECT id FROM bigpicture.members").fetch_one(&db.pool).await.unwrap();
+    let app = test::init_service(build_app(state.clone())).await;
+
+    // 점검은 지우지 않고 보여주기만 함 (다른 저장소의 이미지, 내보내기 폴더는 대상 아님)
+    let (status, body) = read_json(test::call_service(&app, as_member(get("/api/admin/storage/orphans"), admin, &state).to_request()).await).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let report = &body["data"];
+    assert_eq!((report["dryRun"].as_bool(), report["scannedFiles"].as_i64()), (Some(true), Some(3)));
+    assert_eq!((report["orphanedFileCount"].as_i64(), report["orphanedFiles"][0]["key"].as_str()), (Some(1), Some("originals/thumbnail/orphan.jpg")));
+    assert_eq!((report["missingImageCount"].as_i64(), report["missingImages"][0]["imageUrl"].as_str()), (Some(1), Some("http://files.test/api/images/download/markers/missing.webp")));
+    assert!(root.join("originals/thumbnail/orphan.jpg").exists());
+
+    // 최근 파일은 업로드 중일 수 있어 제외
+    let recent = orphan_gc::scan(db, state.storage.as_ref(), Utc::now(), Duration::hours(24)).await.unwrap();
+    assert_eq!((recent.orphaned_file_count, recent.missing_image_count), (0, 0));
+
+    let report = orphan_gc::collect_garbage(db, state.storage.as_ref(), state.clock.now(), Duration::hours(24)).await.unwrap();
+    assert_eq!((report.deleted_files, report.removed_images, report.errors.len()), (1, 1, 0));
+    assert!(!root.join("originals/thumbnail/orphan.jpg").exists());
+    assert!(root.join("markers/kept.webp").exists() && root.join("exports/a.csv").exists());
+    let urls: Vec<String> = sqlx::query_scalar("SELECT image_url FROM bigpicture.marker_images ORDER BY image_order").fetch_all(&db.pool).await.unwrap();
+    assert_eq!(urls, vec!["http://files.test/api/images/download/markers/kept.webp", "/markers/on-s3.webp"]);
+
+    std::fs::remove_dir_all(&root).ok();
+    test_db.drop_database().await;
+}
+
+#[actix_web::test]
+async fn anonymizer_clears_reverse_geocoded_addresses_with_the_jittered_location() {
+    let Some(test_db) = TestDatabase::create().await else {
+        eprintln!("TEST_DATABASE_URL이 없어 건너뜀");
+        return;
+    };
+    let pool = test_db.state.database.pool.clone();
+    sqlx::Executor::execute(&pool, r#"
+        INSERT INTO bigpicture.members (email, nickname) VALUES ('anon@example.invalid', '산책러');
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option, address_region, address_locality, address_neighborhood, address_text, address_geocoded_at)
+        SELECT id, ST_SetSRID(ST_MakePoint(127.0, 37.5), 4326)::geography, 'happy', '집 앞', 'public', '서울특별시', '마포구', '합정동', '서울특별시 마포구 합정동 123-4', NOW()
+        FROM bigpicture.members;
+        INSERT INTO bigpicture.markers (member_id, location, emotion_tag, description, sharing_option)
+        SELECT id, ST_SetSRID(ST_MakePoint(127.1, 37.6), 4326)::geography, 'calm', '주소 없음', 'public' FROM bigpicture.members;
+    "#).await.expect("seed");
+
+    let wrong = AnonymizeOptions { confirm_database: "bigpicture".to_string(), jitter_meters: 300.0 };
+    assert!(anonymize_database(&pool, &wrong).await.is_err());
+    let options = AnonymizeOptions { confirm_database: test_db.name.clone(), jitter_meters: 300.0 };
+    let report = anonymize_database(&pool, &options).await.unwrap();
+    assert_eq!((report.markers, report.marker_addresses), (2, 1));
+    let remaining: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM bigpicture.markers WHERE address_text IS NOT NULL OR address_region IS NOT NULL OR address_locality IS NOT NULL OR address_neighborhood IS NOT NULL OR address_geocoded_at IS NOT NULL"
+    ).fetch_one(&pool).await.unwrap();
+    assert_eq!(remaining, 0);
+    let author: Option<String> = sqlx::query_scalar("SELECT author FROM bigpicture.markers LIMIT 1").fetch_one(&pool).await.unwrap();
+    assert!(author.unwrap().starts_with("user_"));
+
+    test_db.drop_database().await;
+}